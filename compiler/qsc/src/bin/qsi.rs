@@ -10,8 +10,9 @@ use num_complex::Complex64;
 use qsc::{
     hir::PackageId,
     interpret::{self, InterpretResult, Interpreter},
+    noisy,
     packages::BuildableProgram,
-    PackageStore,
+    stabilizer, NoiseConfig, PackageStore, PauliNoise, SparseSim,
 };
 use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
 use qsc_eval::{
@@ -34,6 +35,7 @@ use std::{
 #[derive(Debug, Parser)]
 #[command(name = "qsi", version = concat!(crate_version!(), " (", env!("QSHARP_GIT_HASH"), ")"))]
 #[command(author, about, next_line_help = true)]
+#[allow(clippy::struct_excessive_bools)]
 struct Cli {
     /// Use the given file on startup as initial session input.
     #[arg(long = "use")]
@@ -62,6 +64,39 @@ struct Cli {
     /// Compile the given files and interactive snippets in debug mode.
     #[arg(long)]
     debug: bool,
+
+    /// When used with `--exec`, run the program on the noisy density matrix simulator,
+    /// which supports up to the given number of simultaneously allocated qubits.
+    #[arg(
+        long,
+        value_name = "QUBITS",
+        requires = "exec",
+        conflicts_with_all = ["pauli_noise", "measurement_error"]
+    )]
+    noisy_sim: Option<usize>,
+
+    /// Depolarizing error probabilities for single-qubit gates, two-qubit gates and measurements
+    /// when running on the noisy simulator, separated by commas.
+    #[arg(long, value_delimiter = ',', requires = "noisy_sim")]
+    depolarizing: Option<Vec<f64>>,
+
+    /// When used with `--exec`, inject Pauli errors with the given X, Y and Z probabilities,
+    /// separated by commas, after every gate of the sparse simulation.
+    #[arg(long, value_delimiter = ',', requires = "exec")]
+    pauli_noise: Option<Vec<f64>>,
+
+    /// When used with `--exec`, the probability that a measurement reports the wrong result.
+    #[arg(long, requires = "exec")]
+    measurement_error: Option<f64>,
+
+    /// When used with `--exec`, run the program on the stabilizer simulator, which supports
+    /// programs with many qubits as long as they only use Clifford operations.
+    #[arg(
+        long,
+        requires = "exec",
+        conflicts_with_all = ["noisy_sim", "pauli_noise", "measurement_error"]
+    )]
+    stabilizer: bool,
 }
 
 struct TerminalReceiver;
@@ -154,9 +189,48 @@ fn main() -> miette::Result<ExitCode> {
                 return Ok(ExitCode::FAILURE);
             }
         };
-        return Ok(print_exec_result(
-            interpreter.eval_entry(&mut TerminalReceiver),
-        ));
+        let result = if cli.stabilizer {
+            interpreter
+                .eval_entry_with_sim(&mut stabilizer::StabilizerSim::new(), &mut TerminalReceiver)
+        } else if let Some(capacity) = cli.noisy_sim {
+            let (p1, p2, p_meas) = match cli.depolarizing.as_deref() {
+                Some(&[p1, p2, p_meas]) => (p1, p2, p_meas),
+                None => (0.0, 0.0, 0.0),
+                Some(_) => {
+                    eprintln!("error: --depolarizing expects three probabilities");
+                    return Ok(ExitCode::FAILURE);
+                }
+            };
+            let noise = match noisy::NoiseModel::depolarizing(p1, p2, p_meas) {
+                Ok(noise) => noise,
+                Err(err) => {
+                    eprintln!("error: invalid noise model: {err}");
+                    return Ok(ExitCode::FAILURE);
+                }
+            };
+            let mut sim = noisy::DensityMatrixSim::new(capacity, noise);
+            interpreter.eval_entry_with_sim(&mut sim, &mut TerminalReceiver)
+        } else if cli.pauli_noise.is_some() || cli.measurement_error.is_some() {
+            if cli.pauli_noise.as_ref().is_some_and(|p| p.len() != 3) {
+                eprintln!("error: --pauli-noise expects three probabilities");
+                return Ok(ExitCode::FAILURE);
+            }
+            let noise = match pauli_noise_config(
+                cli.pauli_noise.as_deref(),
+                cli.measurement_error.unwrap_or_default(),
+            ) {
+                Ok(noise) => noise,
+                Err(err) => {
+                    eprintln!("error: invalid noise configuration: {err}");
+                    return Ok(ExitCode::FAILURE);
+                }
+            };
+            let mut sim = SparseSim::new_with_noise(noise);
+            interpreter.eval_entry_with_sim(&mut sim, &mut TerminalReceiver)
+        } else {
+            interpreter.eval_entry(&mut TerminalReceiver)
+        };
+        return Ok(print_exec_result(result));
     }
 
     let mut interpreter = match (if cli.debug {
@@ -189,6 +263,17 @@ fn main() -> miette::Result<ExitCode> {
     Ok(ExitCode::SUCCESS)
 }
 
+fn pauli_noise_config(
+    probabilities: Option<&[f64]>,
+    measurement_error: f64,
+) -> Result<NoiseConfig, qsc_eval::noise::Error> {
+    let noise = match probabilities {
+        Some(&[p_x, p_y, p_z]) => PauliNoise::from_probabilities(p_x, p_y, p_z)?,
+        _ => PauliNoise::default(),
+    };
+    NoiseConfig::uniform(noise).with_measurement_error(measurement_error)
+}
+
 fn repl(interpreter: &mut Interpreter, receiver: &mut impl Receiver) -> io::Result<()> {
     print_prompt(false);
 
@@ -281,7 +366,7 @@ fn load_project(
     features: &mut LanguageFeatures,
 ) -> Result<(PackageStore, Vec<(PackageId, Option<Arc<str>>)>, SourceMap), ExitCode> {
     let fs = StdFs;
-    let project = match fs.load_project(dir.as_ref(), None) {
+    let project = match fs.load_project_and_update_lockfile(dir.as_ref(), None) {
         Ok(project) => project,
         Err(errs) => {
             for e in errs {
@@ -4,13 +4,25 @@
 #[cfg(test)]
 mod tests;
 
+use qsc_codegen::qir::fir_to_rir_program;
+use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
+use qsc_frontend::{
+    compile::{Dependencies, PackageStore, SourceMap},
+    error::WithSource,
+};
+use qsc_partial_eval::ProgramEntry;
+use qsc_passes::{PackageType, PassContext};
+use qsc_rir::rir;
+
+use crate::interpret::Error;
+
 pub mod qsharp {
     pub use qsc_codegen::qsharp::write_package_string;
     pub use qsc_codegen::qsharp::write_stmt_string;
 }
 
 pub mod qir {
-    use qsc_codegen::qir::fir_to_qir;
+    use qsc_codegen::qir::{fir_to_qir, rir_to_qir};
 
     use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
     use qsc_frontend::{
@@ -91,68 +103,110 @@ pub mod qir {
         sources: SourceMap,
         language_features: LanguageFeatures,
         capabilities: TargetCapabilityFlags,
-        mut package_store: PackageStore,
+        package_store: PackageStore,
         dependencies: &Dependencies,
     ) -> Result<String, Vec<Error>> {
-        if capabilities == TargetCapabilityFlags::all() {
-            return Err(vec![Error::UnsupportedRuntimeCapabilities]);
-        }
-
-        let (unit, errors) = crate::compile::compile(
-            &package_store,
-            dependencies,
+        let program = super::compile_to_rir(
             sources,
-            PackageType::Exe,
+            language_features,
             capabilities,
+            package_store,
+            dependencies,
+        )?;
+        Ok(rir_to_qir(&program))
+    }
+}
+
+pub mod qasm3 {
+    use qsc_codegen::qasm3::program_to_qasm3;
+
+    use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
+    use qsc_frontend::compile::{Dependencies, PackageStore, SourceMap};
+
+    use crate::interpret::Error;
+    pub fn get_qasm3(
+        sources: SourceMap,
+        language_features: LanguageFeatures,
+        capabilities: TargetCapabilityFlags,
+        package_store: PackageStore,
+        dependencies: &Dependencies,
+    ) -> Result<String, Vec<Error>> {
+        let program = super::compile_to_rir(
+            sources,
             language_features,
-        );
+            capabilities,
+            package_store,
+            dependencies,
+        )?;
+        program_to_qasm3(&program).map_err(|e| vec![Error::Qasm3Gen(e)])
+    }
+}
 
-        // Ensure it compiles before trying to add it to the store.
-        if !errors.is_empty() {
-            return Err(errors.iter().map(|e| Error::Compile(e.clone())).collect());
-        }
+/// Compiles the sources as an executable and partially evaluates its entry
+/// expression to the RIR that code generation starts from.
+fn compile_to_rir(
+    sources: SourceMap,
+    language_features: LanguageFeatures,
+    capabilities: TargetCapabilityFlags,
+    mut package_store: PackageStore,
+    dependencies: &Dependencies,
+) -> Result<rir::Program, Vec<Error>> {
+    if capabilities == TargetCapabilityFlags::all() {
+        return Err(vec![Error::UnsupportedRuntimeCapabilities]);
+    }
 
-        let package_id = package_store.insert(unit);
-        let (fir_store, fir_package_id) = qsc_passes::lower_hir_to_fir(&package_store, package_id);
-        let package = fir_store.get(fir_package_id);
-        let entry = ProgramEntry {
-            exec_graph: package.entry_exec_graph.clone(),
-            expr: (
-                fir_package_id,
-                package
-                    .entry
-                    .expect("package must have an entry expression"),
-            )
-                .into(),
-        };
+    let (unit, errors) = crate::compile::compile(
+        &package_store,
+        dependencies,
+        sources,
+        PackageType::Exe,
+        capabilities,
+        language_features,
+    );
 
-        let compute_properties = PassContext::run_fir_passes_on_fir(
-            &fir_store,
+    // Ensure it compiles before trying to add it to the store.
+    if !errors.is_empty() {
+        return Err(errors.iter().map(|e| Error::Compile(e.clone())).collect());
+    }
+
+    let package_id = package_store.insert(unit);
+    let (fir_store, fir_package_id) = qsc_passes::lower_hir_to_fir(&package_store, package_id);
+    let package = fir_store.get(fir_package_id);
+    let entry = ProgramEntry {
+        exec_graph: package.entry_exec_graph.clone(),
+        expr: (
             fir_package_id,
-            capabilities,
+            package
+                .entry
+                .expect("package must have an entry expression"),
         )
-        .map_err(|errors| {
-            let source_package = package_store
-                .get(package_id)
-                .expect("package should be in store");
-            errors
-                .iter()
-                .map(|e| Error::Pass(WithSource::from_map(&source_package.sources, e.clone())))
-                .collect::<Vec<_>>()
-        })?;
+            .into(),
+    };
 
-        fir_to_qir(&fir_store, capabilities, Some(compute_properties), &entry).map_err(|e| {
-            let source_package_id = match e.span() {
-                Some(span) => span.package,
-                None => package_id,
-            };
-            let source_package = package_store
-                .get(source_package_id)
-                .expect("package should be in store");
-            vec![Error::PartialEvaluation(WithSource::from_map(
-                &source_package.sources,
-                e,
-            ))]
-        })
-    }
+    let compute_properties =
+        PassContext::run_fir_passes_on_fir(&fir_store, fir_package_id, capabilities).map_err(
+            |errors| {
+                let source_package = package_store
+                    .get(package_id)
+                    .expect("package should be in store");
+                errors
+                    .iter()
+                    .map(|e| Error::Pass(WithSource::from_map(&source_package.sources, e.clone())))
+                    .collect::<Vec<_>>()
+            },
+        )?;
+
+    fir_to_rir_program(&fir_store, capabilities, Some(compute_properties), &entry).map_err(|e| {
+        let source_package_id = match e.span() {
+            Some(span) => span.package,
+            None => package_id,
+        };
+        let source_package = package_store
+            .get(source_package_id)
+            .expect("package should be in store");
+        vec![Error::PartialEvaluation(WithSource::from_map(
+            &source_package.sources,
+            e,
+        ))]
+    })
 }
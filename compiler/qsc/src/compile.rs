@@ -3,13 +3,17 @@
 
 use log::warn;
 use miette::{Diagnostic, Report};
-use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
+use qsc_data_structures::{
+    language_features::LanguageFeatures, span::Span, target::TargetCapabilityFlags,
+};
 pub use qsc_frontend::compile::Dependencies;
 use qsc_frontend::{
     compile::{CompileUnit, PackageStore, SourceMap},
     error::WithSource,
 };
+use qsc_hir::hir::{Attr, ItemKind, Package};
 use qsc_passes::{run_core_passes, run_default_passes, PackageType};
+use std::rc::Rc;
 use thiserror::Error;
 
 pub type Error = WithSource<ErrorKind>;
@@ -107,6 +111,41 @@ fn process_compile_unit(
     (unit, errors)
 }
 
+/// A callable marked with the `@Test()` attribute.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TestDescriptor {
+    /// The fully qualified name of the callable, which can be called with no arguments.
+    pub callable_name: Rc<str>,
+    /// The span of the callable declaration.
+    pub span: Span,
+}
+
+/// Lists the test callables declared in the package, in source order.
+#[must_use]
+pub fn discover_tests(package: &Package) -> Vec<TestDescriptor> {
+    let mut tests = package
+        .items
+        .values()
+        .filter_map(|item| {
+            let ItemKind::Callable(decl) = &item.kind else {
+                return None;
+            };
+            if !item.attrs.contains(&Attr::Test) {
+                return None;
+            }
+            let ItemKind::Namespace(namespace, _) = &package.items.get(item.parent?)?.kind else {
+                return None;
+            };
+            Some(TestDescriptor {
+                callable_name: format!("{}.{}", namespace.name(), decl.name.name).into(),
+                span: decl.span,
+            })
+        })
+        .collect::<Vec<_>>();
+    tests.sort_by_key(|test| test.span.lo);
+    tests
+}
+
 #[must_use]
 pub fn package_store_with_stdlib(
     capabilities: TargetCapabilityFlags,
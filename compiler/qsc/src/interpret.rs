@@ -8,10 +8,11 @@ mod debug;
 mod debugger_tests;
 #[cfg(test)]
 mod package_tests;
+mod shots;
 #[cfg(test)]
 mod tests;
 
-use std::rc::Rc;
+use std::{ops::ControlFlow, rc::Rc};
 
 pub use qsc_eval::{
     debug::Frame,
@@ -26,8 +27,11 @@ use qsc_linter::{HirLint, Lint, LintKind, LintLevel};
 use qsc_lowerer::{map_fir_package_to_hir, map_hir_package_to_fir};
 use qsc_partial_eval::ProgramEntry;
 use qsc_rca::PackageStoreComputeProperties;
+use qsc_rir::rir;
+pub use shots::{ShotOutput, ShotResult, ShotResults, ShotValue};
 
 use crate::{
+    compile::{discover_tests, TestDescriptor},
     error::{self, WithStack},
     incremental::Compiler,
     location::Location,
@@ -41,7 +45,10 @@ use qsc_circuit::{
     operations::entry_expr_for_qubit_operation, Builder as CircuitBuilder, Circuit,
     Config as CircuitConfig, QubitNames,
 };
-use qsc_codegen::qir::fir_to_qir;
+use qsc_codegen::{
+    qasm3::program_to_qasm3,
+    qir::{fir_to_rir_program, rir_to_qir},
+};
 use qsc_data_structures::{
     functors::FunctorApp,
     index_map::IndexMap,
@@ -51,11 +58,16 @@ use qsc_data_structures::{
     target::TargetCapabilityFlags,
 };
 use qsc_eval::{
-    backend::{Backend, Chain as BackendChain, SparseSim},
+    backend::{
+        journal::{Journal, Recorder},
+        noisy::{DensityMatrixSim, NoiseModel},
+        Backend, Chain as BackendChain, SparseSim,
+    },
+    noise::NoiseConfig,
     output::Receiver,
     val, Env, QubitSpans, State, VariableInfo,
 };
-use qsc_fir::fir::{self, ExecGraph, ExecGraphNode, Global, PackageStoreLookup};
+use qsc_fir::fir::{self, ExecGraph, Global, PackageStoreLookup};
 use qsc_fir::{
     fir::{Block, BlockId, Expr, ExprId, Package, PackageId, Pat, PatId, Stmt, StmtId},
     visit::{self, Visitor},
@@ -67,6 +79,7 @@ use qsc_frontend::{
 };
 use qsc_passes::{PackageType, PassContext};
 use rustc_hash::FxHashSet;
+use shots::{shot_seed, RawShotResult};
 use thiserror::Error;
 
 impl Error {
@@ -106,6 +119,15 @@ pub enum Error {
     #[error("partial evaluation error")]
     #[diagnostic(transparent)]
     PartialEvaluation(#[from] WithSource<qsc_partial_eval::Error>),
+    #[error("OpenQASM 3 generation error")]
+    #[diagnostic(transparent)]
+    Qasm3Gen(qsc_codegen::qasm3::Error),
+    #[error("operation matrix was not produced")]
+    #[diagnostic(code("Qsc.Interpret.NoOperationMatrix"))]
+    NoOperationMatrix,
+    #[error("could not restore the debugger to a previous position")]
+    #[diagnostic(code("Qsc.Interpret.ReplayDiverged"))]
+    ReplayDiverged,
 }
 
 /// A Q# interpreter.
@@ -145,6 +167,49 @@ pub struct Interpreter {
 
 pub type InterpretResult = std::result::Result<Value, Vec<Error>>;
 
+/// The outcome of running a single test callable.
+#[derive(Debug)]
+pub struct TestResult {
+    /// The fully qualified name of the test callable.
+    pub callable_name: Rc<str>,
+    /// The output the test produced, such as messages.
+    pub output: String,
+    /// Why the test failed, or `None` if it passed.
+    pub failure: Option<TestFailure>,
+}
+
+/// Describes why a test failed.
+#[derive(Debug)]
+pub struct TestFailure {
+    /// The message given to `fail` if the test failed explicitly,
+    /// otherwise the description of the first error.
+    pub message: String,
+    /// The call stack at the point of failure, for runtime errors.
+    pub stack_trace: Option<String>,
+    /// All the errors the test failed with.
+    pub errors: Vec<Error>,
+}
+
+impl TestFailure {
+    fn new(errors: Vec<Error>) -> Self {
+        let first = errors.first();
+        let message = match first {
+            Some(Error::Eval(error)) => match error.error().error() {
+                qsc_eval::Error::UserFail(message, _) => message.clone(),
+                error => error.to_string(),
+            },
+            Some(error) => error.to_string(),
+            None => String::new(),
+        };
+        let stack_trace = first.and_then(|error| error.stack_trace().clone());
+        Self {
+            message,
+            stack_trace,
+            errors,
+        }
+    }
+}
+
 impl Interpreter {
     /// Creates a new incremental compiler, compiling the passed in sources.
     /// # Errors
@@ -476,6 +541,102 @@ impl Interpreter {
         self.run_with_sim(&mut SparseSim::new(), receiver, expr)
     }
 
+    /// Runs the given entry expression on a new instance of the environment and of a simulator
+    /// that injects Pauli errors according to the given noise configuration.
+    pub fn run_with_noise(
+        &mut self,
+        receiver: &mut impl Receiver,
+        expr: Option<&str>,
+        noise: NoiseConfig,
+    ) -> InterpretResult {
+        self.run_with_sim(&mut SparseSim::new_with_noise(noise), receiver, expr)
+    }
+
+    /// Runs the given entry expression on a new instance of the environment and of the noisy
+    /// density matrix simulator, which supports up to `qubits` simultaneously allocated qubits
+    /// and applies the channels of the given noise model after each gate.
+    pub fn run_with_noise_model(
+        &mut self,
+        receiver: &mut impl Receiver,
+        expr: Option<&str>,
+        qubits: usize,
+        noise: NoiseModel,
+    ) -> InterpretResult {
+        let mut sim = DensityMatrixSim::new(qubits, noise);
+        self.run_with_sim(&mut sim, receiver, expr)
+    }
+
+    /// Runs the given entry expression, or the entry point of the sources if no expression is
+    /// given, for `shots` shots, each on a new instance of the environment and simulator,
+    /// spreading the shots across up to `parallelism` threads.
+    /// If a noise configuration is given, every shot's simulator injects Pauli errors according
+    /// to it.
+    ///
+    /// Each shot gets its own seed derived from `seed`, or from the quantum seed if `seed` is not
+    /// given, so seeded runs produce the same results regardless of `parallelism`.
+    /// # Errors
+    /// Returns a vector of errors if the entry expression fails to compile or there is no entry
+    /// point.
+    pub fn run_shots(
+        &mut self,
+        expr: Option<&str>,
+        shots: usize,
+        seed: Option<u64>,
+        parallelism: usize,
+        noise: Option<&NoiseConfig>,
+    ) -> std::result::Result<ShotResults, Vec<Error>> {
+        self.run_shots_with(expr, shots, seed, parallelism, noise, |_| {
+            ControlFlow::Continue(())
+        })
+    }
+
+    /// Runs shots like `run_shots`, passing each shot to `on_shot`, in shot order, as soon as it
+    /// and all the shots before it have finished. No more shots are started once `on_shot`
+    /// returns `ControlFlow::Break`, and the results only include the shots passed to it.
+    /// # Errors
+    /// Returns a vector of errors if the entry expression fails to compile or there is no entry
+    /// point.
+    pub fn run_shots_with(
+        &mut self,
+        expr: Option<&str>,
+        shots: usize,
+        seed: Option<u64>,
+        parallelism: usize,
+        noise: Option<&NoiseConfig>,
+        mut on_shot: impl FnMut(&ShotResult) -> ControlFlow<()>,
+    ) -> std::result::Result<ShotResults, Vec<Error>> {
+        let entry = self.shot_entry(expr)?;
+        self.expr_graph = Some(entry.1.clone());
+
+        let seed = seed.or(self.quantum_seed);
+        let classical_seed = self.classical_seed.or(seed);
+        let seeds = (0..shots)
+            .map(|shot| {
+                (
+                    seed.map(|seed| shot_seed(seed, shot)),
+                    classical_seed.map(|seed| shot_seed(seed, shot)),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let mut results = Vec::with_capacity(shots);
+        let report = |shot: RawShotResult| {
+            let shot = shot.into_shot_result(self.compiler.package_store(), &self.fir_store);
+            let flow = on_shot(&shot);
+            results.push(shot);
+            flow
+        };
+        shots::run(
+            &self.fir_store,
+            &entry,
+            noise,
+            &seeds,
+            parallelism.clamp(1, shots.max(1)),
+            report,
+        );
+        Ok(ShotResults::new(results))
+    }
+
     /// Gets the current quantum state of the simulator.
     pub fn get_quantum_state(&mut self) -> (Vec<(BigUint, Complex<f64>)>, usize) {
         self.sim.capture_quantum_state()
@@ -489,6 +650,23 @@ impl Interpreter {
     /// Performs QIR codegen using the given entry expression on a new instance of the environment
     /// and simulator but using the current compilation.
     pub fn qirgen(&mut self, expr: &str) -> std::result::Result<String, Vec<Error>> {
+        let program = self.partially_evaluate_entry_expr(expr)?;
+        Ok(rir_to_qir(&program))
+    }
+
+    /// Performs `OpenQASM` 3 codegen using the given entry expression on a new instance of the
+    /// environment and simulator but using the current compilation.
+    pub fn qasm3gen(&mut self, expr: &str) -> std::result::Result<String, Vec<Error>> {
+        let program = self.partially_evaluate_entry_expr(expr)?;
+        program_to_qasm3(&program).map_err(|e| vec![Error::Qasm3Gen(e)])
+    }
+
+    /// Partially evaluates the given entry expression to the RIR that codegen starts from,
+    /// on a new instance of the environment but using the current compilation.
+    fn partially_evaluate_entry_expr(
+        &mut self,
+        expr: &str,
+    ) -> std::result::Result<rir::Program, Vec<Error>> {
         if self.capabilities == TargetCapabilityFlags::all() {
             return Err(vec![Error::UnsupportedRuntimeCapabilities]);
         }
@@ -513,8 +691,7 @@ impl Interpreter {
             )
                 .into(),
         };
-        // Generate QIR
-        fir_to_qir(
+        fir_to_rir_program(
             &self.fir_store,
             self.capabilities,
             Some(compute_properties),
@@ -587,6 +764,57 @@ impl Interpreter {
         Ok(circuit)
     }
 
+    /// Computes the unitary matrix of the given operation on a new instance of the environment
+    /// and simulator but using the current compilation.
+    ///
+    /// `operation` can be an operation name or a lambda expression that takes an array of
+    /// `n_qubits` qubits, e.g. `qs => CNOT(qs[0], qs[1])`. The matrix is computed by applying
+    /// the operation to one half of a maximally entangled state and reading the resulting
+    /// amplitudes, so that row `i` and column `j` of the result hold the amplitude of the
+    /// basis state `i` when the operation is applied to the basis state `j`.
+    pub fn get_operation_matrix(
+        &mut self,
+        operation: &str,
+        n_qubits: usize,
+    ) -> std::result::Result<Vec<Vec<Complex<f64>>>, Vec<Error>> {
+        let mut receiver = MatrixReceiver::default();
+        let expr = operation_matrix_expr(operation, n_qubits);
+        self.run(&mut receiver, Some(&expr))?;
+        receiver
+            .matrix
+            .ok_or_else(|| vec![Error::NoOperationMatrix])
+    }
+
+    /// Lists the test callables declared in the interpreter's sources.
+    #[must_use]
+    pub fn discover_tests(&self) -> Vec<TestDescriptor> {
+        let unit = self
+            .compiler
+            .package_store()
+            .get(self.compiler.source_package_id())
+            .expect("source package should be in the package store");
+        discover_tests(&unit.package)
+    }
+
+    /// Runs each of the given tests on a new instance of the environment and simulator,
+    /// so that no state carries over from one test to another.
+    pub fn run_tests(&mut self, tests: &[TestDescriptor]) -> Vec<TestResult> {
+        tests.iter().map(|test| self.run_test(test)).collect()
+    }
+
+    /// Runs a single test on a new instance of the environment and simulator.
+    pub fn run_test(&mut self, test: &TestDescriptor) -> TestResult {
+        let mut output = Vec::new();
+        let mut receiver = GenericReceiver::new(&mut output);
+        let expr = format!("{}()", test.callable_name);
+        let result = self.run(&mut receiver, Some(&expr));
+        TestResult {
+            callable_name: test.callable_name.clone(),
+            output: String::from_utf8_lossy(&output).into_owned(),
+            failure: result.err().map(TestFailure::new),
+        }
+    }
+
     /// Sets the entry expression for the interpreter.
     pub fn set_entry_expr(&mut self, entry_expr: &str) -> std::result::Result<(), Vec<Error>> {
         let (graph, _) = self.compile_entry_expr(entry_expr)?;
@@ -859,6 +1087,56 @@ fn sim_circuit_backend() -> BackendChain<SparseSim, CircuitBuilder> {
     )
 }
 
+/// Builds an expression that applies `operation` on `n_qubits` qubits to one half of a
+/// maximally entangled state and dumps the state of both halves, from which the matrix of the
+/// operation is read. This mirrors `Std.Diagnostics.DumpOperation`, but does not require the
+/// operation to be adjointable, so that the matrix of any operation on a qubit array can be
+/// computed.
+fn operation_matrix_expr(operation: &str, n_qubits: usize) -> String {
+    format!(
+        "{{
+            use (targets, extra) = (Qubit[{n_qubits}], Qubit[{n_qubits}]);
+            for i in 0..{n_qubits} - 1 {{
+                Std.Intrinsic.H(targets[i]);
+                Std.Intrinsic.CNOT(targets[i], extra[i]);
+            }}
+            ({operation})(targets);
+            Std.Diagnostics.DumpRegister(targets + extra);
+            Std.Intrinsic.ResetAll(targets + extra);
+        }}"
+    )
+}
+
+/// A receiver that keeps the matrix read from the last state it is given, where the first half
+/// of the qubits was entangled with the second half before an operation was applied to it, and
+/// discards all other output.
+#[derive(Default)]
+struct MatrixReceiver {
+    matrix: Option<Vec<Vec<Complex<f64>>>>,
+}
+
+impl Receiver for MatrixReceiver {
+    fn state(
+        &mut self,
+        state: Vec<(BigUint, Complex<f64>)>,
+        qubit_count: usize,
+    ) -> std::result::Result<(), output::Error> {
+        self.matrix = Some(qsc_eval::state_to_matrix(state, qubit_count / 2));
+        Ok(())
+    }
+
+    fn matrix(
+        &mut self,
+        _matrix: Vec<Vec<Complex<f64>>>,
+    ) -> std::result::Result<(), output::Error> {
+        Ok(())
+    }
+
+    fn message(&mut self, _msg: &str) -> std::result::Result<(), output::Error> {
+        Ok(())
+    }
+}
+
 /// Describes the entry point for circuit generation.
 pub enum CircuitEntryPoint {
     /// An operation. This must be a callable name or a lambda
@@ -871,6 +1149,18 @@ pub enum CircuitEntryPoint {
     EntryPoint,
 }
 
+/// The number of statements evaluated between the checkpoints the debugger
+/// takes to support stepping backward.
+const CHECKPOINT_INTERVAL: u64 = 1000;
+
+/// The maximum number of checkpoints the debugger keeps. When there are more, every other
+/// checkpoint is dropped, doubling the distance between the remaining ones.
+const MAX_CHECKPOINTS: usize = 100;
+
+/// The maximum number of simulator operations the debugger records. No checkpoints are taken
+/// after the journal is full, so stepping backward replays from the last one taken before.
+const MAX_JOURNAL_LEN: usize = 1_000_000;
+
 /// A debugger that enables step-by-step evaluation of code
 /// and inspecting state in the interpreter.
 pub struct Debugger {
@@ -880,6 +1170,20 @@ pub struct Debugger {
     position_encoding: Encoding,
     /// The current state of the evaluator.
     state: State,
+    /// The operations applied to the simulator so far, which are replayed
+    /// to restore the simulator when stepping backward.
+    journal: Journal,
+    /// Snapshots of the execution, in order of position. The first is always
+    /// the start of the execution.
+    checkpoints: Vec<Checkpoint>,
+}
+
+/// A snapshot of the debugger's execution at the position of its evaluator state.
+struct Checkpoint {
+    state: State,
+    env: Env,
+    /// The number of journal operations that had been applied to the simulator.
+    journal_len: usize,
 }
 
 impl Debugger {
@@ -891,7 +1195,7 @@ impl Debugger {
         store: PackageStore,
         dependencies: &Dependencies,
     ) -> std::result::Result<Self, Vec<Error>> {
-        let interpreter = Interpreter::new_with_debug(
+        let mut interpreter = Interpreter::new_with_debug(
             sources,
             PackageType::Exe,
             capabilities,
@@ -902,10 +1206,23 @@ impl Debugger {
         let source_package_id = interpreter.source_package;
         let unit = interpreter.fir_store.get(source_package_id);
         let entry_exec_graph = unit.entry_exec_graph.clone();
+        let state = State::new(source_package_id, entry_exec_graph, None);
+        let journal = Journal::new(
+            &mut interpreter.sim,
+            interpreter.quantum_seed,
+            MAX_JOURNAL_LEN,
+        );
+        let start = Checkpoint {
+            state: state.clone(),
+            env: interpreter.env.clone(),
+            journal_len: journal.len(),
+        };
         Ok(Self {
             interpreter,
             position_encoding,
-            state: State::new(source_package_id, entry_exec_graph, None),
+            state,
+            journal,
+            checkpoints: vec![start],
         })
     }
 
@@ -918,14 +1235,75 @@ impl Debugger {
         breakpoints: &[StmtId],
         step: StepAction,
     ) -> std::result::Result<StepResult, Vec<Error>> {
+        let current_frame = self.state.call_depth();
+        loop {
+            let position = self.state.stmt_count();
+            let limit = (position / CHECKPOINT_INTERVAL + 1) * CHECKPOINT_INTERVAL;
+            if let Some(result) =
+                self.eval_until(receiver, breakpoints, step, current_frame, limit)?
+            {
+                return Ok(result);
+            }
+            self.take_checkpoint();
+        }
+    }
+
+    /// Moves execution back to the last statement before the current one in the current frame
+    /// or one of its callers, reversing a `StepAction::Next`. Execution stays where it is if
+    /// there is no such statement.
+    /// # Errors
+    /// Returns a vector of errors if replaying the execution fails.
+    pub fn step_back(&mut self) -> std::result::Result<StepResult, Vec<Error>> {
+        let depth = self.state.call_depth();
+        if let Some((position, _)) =
+            self.find_last_stop(&[], StepAction::In, |state| state.call_depth() <= depth)?
+        {
+            self.restore(position)?;
+        }
+        Ok(StepResult::Next)
+    }
+
+    /// Moves execution back to the last breakpoint hit before the current position, or to the
+    /// first statement of the program if no breakpoint was hit.
+    /// # Errors
+    /// Returns a vector of errors if replaying the execution fails.
+    pub fn reverse_continue(
+        &mut self,
+        breakpoints: &[StmtId],
+    ) -> std::result::Result<StepResult, Vec<Error>> {
+        if let Some((position, result)) =
+            self.find_last_stop(breakpoints, StepAction::Continue, |_| true)?
+        {
+            self.restore(position)?;
+            return Ok(result);
+        }
+        self.restore_checkpoint(0);
+        let mut sink = std::io::sink();
+        let mut receiver = GenericReceiver::new(&mut sink);
+        let current_frame = self.state.call_depth();
+        let result =
+            self.eval_until(&mut receiver, &[], StepAction::In, current_frame, u64::MAX)?;
+        Ok(result.expect("evaluation without a statement limit should not pause"))
+    }
+
+    fn eval_until(
+        &mut self,
+        receiver: &mut impl Receiver,
+        breakpoints: &[StmtId],
+        step: StepAction,
+        current_frame: usize,
+        limit: u64,
+    ) -> std::result::Result<Option<StepResult>, Vec<Error>> {
         self.state
-            .eval(
+            .eval_until(
                 &self.interpreter.fir_store,
                 &mut self.interpreter.env,
-                &mut self.interpreter.sim,
+                &mut Recorder::new(&mut self.interpreter.sim, &mut self.journal),
                 receiver,
                 breakpoints,
                 step,
+                current_frame,
+                limit,
             )
             .map_err(|(error, call_stack)| {
                 eval_error(
@@ -937,6 +1315,131 @@ impl Debugger {
             })
     }
 
+    /// Records a checkpoint at the current position, unless execution has already been
+    /// past this position and checkpointed before stepping backward, or the simulator state
+    /// can no longer be reconstructed from the journal.
+    fn take_checkpoint(&mut self) {
+        let position = self.state.stmt_count();
+        if self.journal.is_full()
+            || self
+                .checkpoints
+                .last()
+                .is_some_and(|checkpoint| checkpoint.state.stmt_count() >= position)
+        {
+            return;
+        }
+        self.checkpoints.push(Checkpoint {
+            state: self.state.clone(),
+            env: self.interpreter.env.clone(),
+            journal_len: self.journal.len(),
+        });
+        if self.checkpoints.len() > MAX_CHECKPOINTS {
+            // Keeping the even indices keeps the checkpoint at the start of execution.
+            let mut index = 0;
+            self.checkpoints.retain(|_| {
+                index += 1;
+                index % 2 == 1
+            });
+        }
+    }
+
+    /// Finds the last position before the current one at which evaluating with `step` would stop
+    /// and the state there satisfies `accept`. The execution is replayed from each checkpoint in
+    /// turn, starting from the most recent one, and left at the current position if no such
+    /// position is found, including after the program has finished.
+    fn find_last_stop(
+        &mut self,
+        breakpoints: &[StmtId],
+        step: StepAction,
+        accept: impl Fn(&State) -> bool,
+    ) -> std::result::Result<Option<(u64, StepResult)>, Vec<Error>> {
+        let current = self.state.stmt_count();
+        if current == 0 {
+            return Ok(None);
+        }
+        let finished = self.state.is_finished();
+        let mut sink = std::io::sink();
+        let mut receiver = GenericReceiver::new(&mut sink);
+        for index in (0..self.checkpoints.len()).rev() {
+            let start = self.checkpoints[index].state.stmt_count();
+            if start >= current {
+                continue;
+            }
+            // Positions after the next checkpoint were already searched.
+            let end = self
+                .checkpoints
+                .get(index + 1)
+                .map_or(current - 1, |next| next.state.stmt_count().min(current - 1));
+            self.restore_checkpoint(index);
+            let mut found = None;
+            while self.state.stmt_count() < end {
+                let current_frame = self.state.call_depth();
+                match self.eval_until(&mut receiver, breakpoints, step, current_frame, end)? {
+                    None | Some(StepResult::Return(_)) => break,
+                    Some(result) => {
+                        if accept(&self.state) {
+                            found = Some((self.state.stmt_count(), result));
+                        }
+                    }
+                }
+            }
+            if found.is_some() {
+                return Ok(found);
+            }
+        }
+        self.restore(current)?;
+        if finished {
+            // Replaying stops at the last statement, so evaluate the rest of the program again.
+            let current_frame = self.state.call_depth();
+            self.eval_until(
+                &mut receiver,
+                &[],
+                StepAction::Continue,
+                current_frame,
+                u64::MAX,
+            )?;
+        }
+        Ok(None)
+    }
+
+    /// Restores the execution to the given position, which must have been reached before,
+    /// by replaying it from the last checkpoint at or before that position.
+    fn restore(&mut self, position: u64) -> std::result::Result<(), Vec<Error>> {
+        let index = self
+            .checkpoints
+            .iter()
+            .rposition(|checkpoint| checkpoint.state.stmt_count() <= position)
+            .ok_or_else(|| vec![Error::ReplayDiverged])?;
+        self.restore_checkpoint(index);
+        if self.state.stmt_count() < position {
+            let mut sink = std::io::sink();
+            let mut receiver = GenericReceiver::new(&mut sink);
+            let current_frame = self.state.call_depth();
+            let result = self.eval_until(
+                &mut receiver,
+                &[],
+                StepAction::Continue,
+                current_frame,
+                position,
+            )?;
+            if result.is_some() {
+                return Err(vec![Error::ReplayDiverged]);
+            }
+        }
+        Ok(())
+    }
+
+    /// Restores the evaluator, environment and simulator to the given checkpoint.
+    fn restore_checkpoint(&mut self, index: usize) {
+        let checkpoint = &self.checkpoints[index];
+        self.state = checkpoint.state.clone();
+        self.interpreter.env = checkpoint.env.clone();
+        self.journal.truncate(checkpoint.journal_len);
+        let mut sim = sim_circuit_backend();
+        self.journal.replay(checkpoint.journal_len, &mut sim);
+        self.interpreter.sim = sim;
+    }
+
     #[must_use]
     pub fn get_stack_frames(&self) -> Vec<StackFrame> {
         let frames = self.state.get_stack_frames();
@@ -1050,7 +1553,7 @@ fn eval(
 fn eval_and_get_qubit_table(
     package: PackageId,
     classical_seed: Option<u64>,
-    exec_graph: Rc<[ExecGraphNode]>,
+    exec_graph: ExecGraph,
     package_store: &PackageStore,
     fir_store: &fir::PackageStore,
     env: &mut Env,
@@ -234,4 +234,260 @@ mod given_debugger {
             Ok(())
         }
     }
+
+    #[cfg(test)]
+    mod step_back {
+        use num_bigint::BigUint;
+        use num_complex::Complex;
+        use qsc_data_structures::target::TargetCapabilityFlags;
+
+        use super::*;
+
+        fn new_debugger(source: &str) -> Result<Debugger, Vec<crate::interpret::Error>> {
+            let sources = SourceMap::new([("test".into(), source.into())], None);
+            let (std_id, store) =
+                crate::compile::package_store_with_stdlib(TargetCapabilityFlags::all());
+            Debugger::new(
+                sources,
+                TargetCapabilityFlags::all(),
+                Encoding::Utf8,
+                LanguageFeatures::default(),
+                store,
+                &[(std_id, None)],
+            )
+        }
+
+        fn frames(debugger: &Debugger) -> Vec<(String, crate::location::Location)> {
+            debugger
+                .get_stack_frames()
+                .into_iter()
+                .map(|frame| (frame.name, frame.location))
+                .collect()
+        }
+
+        fn local(debugger: &Debugger, name: &str) -> String {
+            debugger
+                .get_locals()
+                .into_iter()
+                .find(|var| &*var.name == name)
+                .map(|var| var.value.to_string())
+                .expect("variable should be in scope")
+        }
+
+        fn assert_states_eq(
+            actual: &(Vec<(BigUint, Complex<f64>)>, usize),
+            expected: &(Vec<(BigUint, Complex<f64>)>, usize),
+        ) {
+            assert_eq!(actual.1, expected.1);
+            assert_eq!(actual.0.len(), expected.0.len());
+            for ((actual_id, actual), (expected_id, expected)) in actual.0.iter().zip(&expected.0) {
+                assert_eq!(actual_id, expected_id);
+                assert!((actual - expected).norm() < 1e-9);
+            }
+        }
+
+        #[test]
+        fn back_after_next_returns_to_previous_statement(
+        ) -> Result<(), Vec<crate::interpret::Error>> {
+            let mut debugger = new_debugger(STEPPING_SOURCE)?;
+            let ids = get_breakpoint_ids(&debugger, "test");
+            expect_bp(&mut debugger, &ids[..1], ids[0]);
+            let at_breakpoint = frames(&debugger);
+            expect_next(&mut debugger);
+            assert_ne!(frames(&debugger), at_breakpoint);
+            debugger.step_back()?;
+            assert_eq!(frames(&debugger), at_breakpoint);
+            expect_next(&mut debugger);
+            expect_next(&mut debugger);
+            expect_return(debugger, "42");
+            Ok(())
+        }
+
+        #[test]
+        fn back_from_callee_returns_to_call_site() -> Result<(), Vec<crate::interpret::Error>> {
+            let mut debugger = new_debugger(STEPPING_SOURCE)?;
+            let ids = get_breakpoint_ids(&debugger, "test");
+            expect_bp(&mut debugger, &ids[..1], ids[0]);
+            let at_call_site = frames(&debugger);
+            expect_in(&mut debugger);
+            assert_eq!(frames(&debugger).len(), at_call_site.len() + 1);
+            debugger.step_back()?;
+            assert_eq!(frames(&debugger), at_call_site);
+            Ok(())
+        }
+
+        #[test]
+        fn back_at_first_statement_stays() -> Result<(), Vec<crate::interpret::Error>> {
+            let mut debugger = new_debugger(STEPPING_SOURCE)?;
+            expect_in(&mut debugger);
+            let first = frames(&debugger);
+            debugger.step_back()?;
+            assert_eq!(frames(&debugger), first);
+            Ok(())
+        }
+
+        #[test]
+        fn back_after_return_stays_finished() -> Result<(), Vec<crate::interpret::Error>> {
+            let mut debugger = new_debugger(STEPPING_SOURCE)?;
+            match step(&mut debugger, &[], StepAction::Continue).0 {
+                Ok(StepResult::Return(value)) => assert_eq!(value.to_string(), "42"),
+                Ok(v) => panic!("Expected Return, got {v:?}"),
+                Err(e) => panic!("Expected Return, got {e:?}"),
+            }
+            debugger.step_back()?;
+            assert!(frames(&debugger).is_empty());
+            match step(&mut debugger, &[], StepAction::Next).0 {
+                Ok(StepResult::Return(_)) => (),
+                Ok(v) => panic!("Expected Return, got {v:?}"),
+                Err(e) => panic!("Expected Return, got {e:?}"),
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn reverse_continue_returns_to_last_breakpoint() -> Result<(), Vec<crate::interpret::Error>>
+        {
+            let mut debugger = new_debugger(STEPPING_SOURCE)?;
+            let ids = get_breakpoint_ids(&debugger, "test");
+            expect_bp(&mut debugger, &ids[..1], ids[0]);
+            let at_breakpoint = frames(&debugger);
+            expect_next(&mut debugger);
+            expect_next(&mut debugger);
+            match debugger.reverse_continue(&ids[..1]) {
+                Ok(StepResult::BreakpointHit(id)) => assert_eq!(id, ids[0]),
+                Ok(v) => panic!("Expected BP, got {v:?}"),
+                Err(e) => panic!("Expected BP, got {e:?}"),
+            }
+            assert_eq!(frames(&debugger), at_breakpoint);
+            Ok(())
+        }
+
+        #[test]
+        fn reverse_continue_without_breakpoints_returns_to_start(
+        ) -> Result<(), Vec<crate::interpret::Error>> {
+            let mut debugger = new_debugger(STEPPING_SOURCE)?;
+            expect_in(&mut debugger);
+            let first = frames(&debugger);
+            expect_next(&mut debugger);
+            expect_next(&mut debugger);
+            debugger.reverse_continue(&[])?;
+            assert_eq!(frames(&debugger), first);
+            Ok(())
+        }
+
+        #[test]
+        fn back_restores_quantum_state_and_measurements() -> Result<(), Vec<crate::interpret::Error>>
+        {
+            let source = r#"
+                namespace Test {
+                    @EntryPoint()
+                    operation Main() : Result[] {
+                        use qs = Qubit[4];
+                        ApplyToEach(H, qs);
+                        let rs = MeasureEachZ(qs);
+                        ResetAll(qs);
+                        rs
+                    }
+                }"#;
+            let mut debugger = new_debugger(source)?;
+            expect_in(&mut debugger);
+            expect_next(&mut debugger);
+            expect_next(&mut debugger);
+            let superposition = debugger.capture_quantum_state();
+            expect_next(&mut debugger);
+            let measured = debugger.capture_quantum_state();
+            let rs = local(&debugger, "rs");
+            expect_next(&mut debugger);
+            debugger.step_back()?;
+            assert_states_eq(&debugger.capture_quantum_state(), &measured);
+            debugger.step_back()?;
+            assert_states_eq(&debugger.capture_quantum_state(), &superposition);
+            expect_next(&mut debugger);
+            // Replaying the measurements after stepping back repeats their outcomes.
+            assert_eq!(local(&debugger, "rs"), rs);
+            assert_states_eq(&debugger.capture_quantum_state(), &measured);
+            match step(&mut debugger, &[], StepAction::Continue).0 {
+                Ok(StepResult::Return(value)) => assert_eq!(value.to_string(), rs),
+                Ok(v) => panic!("Expected Return, got {v:?}"),
+                Err(e) => panic!("Expected Return, got {e:?}"),
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn back_works_across_checkpoints() -> Result<(), Vec<crate::interpret::Error>> {
+            let source = r#"
+                namespace Test {
+                    @EntryPoint()
+                    operation Main() : Int {
+                        mutable sum = 0;
+                        mutable i = 0;
+                        while i < 2000 {
+                            set sum += i;
+                            set i += 1;
+                        }
+                        sum
+                    }
+                }"#;
+            let mut debugger = new_debugger(source)?;
+            // Breakpoint locations are ordered by position, so the last one is the final statement.
+            let last = debugger
+                .get_breakpoints("test")
+                .last()
+                .expect("there should be breakpoint locations")
+                .id
+                .into();
+            match step(&mut debugger, &[last], StepAction::Continue).0 {
+                Ok(StepResult::BreakpointHit(id)) => assert_eq!(id, last),
+                Ok(v) => panic!("Expected BP, got {v:?}"),
+                Err(e) => panic!("Expected BP, got {e:?}"),
+            }
+            debugger.step_back()?;
+            assert_eq!(local(&debugger, "i"), "1999");
+            assert_eq!(local(&debugger, "sum"), "1999000");
+            debugger.step_back()?;
+            assert_eq!(local(&debugger, "i"), "1999");
+            assert_eq!(local(&debugger, "sum"), "1997001");
+            expect_next(&mut debugger);
+            expect_next(&mut debugger);
+            assert_eq!(local(&debugger, "sum"), "1999000");
+            expect_return(debugger, "1999000");
+            Ok(())
+        }
+
+        #[test]
+        fn checkpoints_are_capped() -> Result<(), Vec<crate::interpret::Error>> {
+            let source = r#"
+                namespace Test {
+                    @EntryPoint()
+                    operation Main() : Int {
+                        mutable i = 0;
+                        while i < 60000 {
+                            set i += 1;
+                        }
+                        i
+                    }
+                }"#;
+            let mut debugger = new_debugger(source)?;
+            let last = debugger
+                .get_breakpoints("test")
+                .last()
+                .expect("there should be breakpoint locations")
+                .id
+                .into();
+            match step(&mut debugger, &[last], StepAction::Continue).0 {
+                Ok(StepResult::BreakpointHit(id)) => assert_eq!(id, last),
+                Ok(v) => panic!("Expected BP, got {v:?}"),
+                Err(e) => panic!("Expected BP, got {e:?}"),
+            }
+            assert!(debugger.checkpoints.len() <= crate::interpret::MAX_CHECKPOINTS);
+            assert_eq!(debugger.checkpoints[0].state.stmt_count(), 0);
+            debugger.step_back()?;
+            assert_eq!(local(&debugger, "i"), "59999");
+            debugger.reverse_continue(&[])?;
+            expect_next(&mut debugger);
+            assert_eq!(local(&debugger, "i"), "0");
+            Ok(())
+        }
+    }
 }
@@ -1,7 +1,10 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
-use crate::{interpret::Interpreter, packages::BuildableProgram};
+use crate::{
+    interpret::{Interpreter, ShotOutput},
+    packages::BuildableProgram,
+};
 use indoc::indoc;
 use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
 use qsc_eval::output::CursorReceiver;
@@ -27,6 +30,8 @@ fn import_and_call_reexport() {
             language_features: LanguageFeatures::default(),
             dependencies: [("Foo".into(), "PackageAKey".into())].into_iter().collect(),
             package_type: None,
+            version: None,
+            version_requirements: FxHashMap::default(),
         },
         packages: [(
             "PackageAKey".into(),
@@ -46,6 +51,8 @@ fn import_and_call_reexport() {
                 language_features: LanguageFeatures::default(),
                 dependencies: FxHashMap::default(),
                 package_type: None,
+                version: None,
+                version_requirements: FxHashMap::default(),
             },
         )]
         .into_iter()
@@ -106,6 +113,8 @@ fn directly_call_reexport() {
             language_features: LanguageFeatures::default(),
             dependencies: [("Foo".into(), "PackageAKey".into())].into_iter().collect(),
             package_type: None,
+            version: None,
+            version_requirements: FxHashMap::default(),
         },
         packages: [(
             "PackageAKey".into(),
@@ -125,6 +134,8 @@ fn directly_call_reexport() {
                 language_features: LanguageFeatures::default(),
                 dependencies: FxHashMap::default(),
                 package_type: None,
+                version: None,
+                version_requirements: FxHashMap::default(),
             },
         )]
         .into_iter()
@@ -168,3 +179,89 @@ fn directly_call_reexport() {
 
     assert_eq!(output, "hello from dependency A!\n");
 }
+
+#[test]
+fn run_shots_in_parallel_calls_dependency() {
+    let pkg_graph: PackageGraphSources = PackageGraphSources {
+        root: PackageInfo {
+            sources: vec![(
+                "PackageB.qs".into(),
+                indoc! {"
+                    operation Main() : Result {
+                        Foo.DependencyA.Flip()
+                    }"}
+                .into(),
+            )],
+            language_features: LanguageFeatures::default(),
+            dependencies: [("Foo".into(), "PackageAKey".into())].into_iter().collect(),
+            package_type: None,
+            version: None,
+            version_requirements: FxHashMap::default(),
+        },
+        packages: [(
+            "PackageAKey".into(),
+            PackageInfo {
+                sources: vec![(
+                    "Foo.qs".into(),
+                    r#"
+                    namespace DependencyA {
+                        operation Flip() : Result {
+                            Message("hello from dependency A!");
+                            use q = Qubit();
+                            X(q);
+                            MResetZ(q)
+                        }
+                        export Flip;
+                    }
+                    "#
+                    .into(),
+                )],
+                language_features: LanguageFeatures::default(),
+                dependencies: FxHashMap::default(),
+                package_type: None,
+                version: None,
+                version_requirements: FxHashMap::default(),
+            },
+        )]
+        .into_iter()
+        .collect(),
+    };
+
+    let buildable_program = BuildableProgram::new(TargetCapabilityFlags::all(), pkg_graph);
+
+    assert!(
+        buildable_program.dependency_errors.is_empty(),
+        "dependencies should be built without errors"
+    );
+
+    let BuildableProgram {
+        store,
+        user_code,
+        user_code_dependencies,
+        ..
+    } = buildable_program;
+
+    let user_code = SourceMap::new(user_code.sources, None);
+
+    let mut interpreter = Interpreter::new(
+        user_code,
+        PackageType::Exe,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+        store,
+        &user_code_dependencies,
+    )
+    .expect("interpreter creation should succeed");
+
+    let results = interpreter
+        .run_shots(None, 6, None, 3, None)
+        .expect("shots should run");
+
+    assert_eq!(results.histogram, vec![("One".to_string(), 6)]);
+    for shot in &results.shots {
+        assert_eq!(
+            shot.output,
+            vec![ShotOutput::Message("hello from dependency A!".to_string())]
+        );
+    }
+}
@@ -0,0 +1,324 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use std::{
+    fmt::{self, Display, Formatter},
+    ops::ControlFlow,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+};
+
+use num_bigint::{BigInt, BigUint};
+use num_complex::Complex64;
+use qsc_eval::{
+    backend::{Backend, SparseSim},
+    debug::Frame,
+    noise::NoiseConfig,
+    output::{self, Receiver},
+    val::{self, Value},
+    Env,
+};
+use qsc_fir::fir::{self, ExecGraph, PackageId};
+use qsc_frontend::compile::PackageStore;
+use rustc_hash::FxHashMap;
+
+use super::{eval_error, Error, Interpreter};
+
+/// A value returned by a shot. Unlike [`Value`], it can be sent between threads, so values that
+/// only make sense inside the program that produced them, such as callables and qubits, are only
+/// kept as their type name and rendering.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ShotValue {
+    Array(Vec<ShotValue>),
+    BigInt(BigInt),
+    Bool(bool),
+    Double(f64),
+    Int(i64),
+    Pauli(fir::Pauli),
+    Result(bool),
+    String(String),
+    Tuple(Vec<ShotValue>),
+    Other {
+        type_name: &'static str,
+        rendered: String,
+    },
+}
+
+impl From<&Value> for ShotValue {
+    fn from(value: &Value) -> Self {
+        match value {
+            Value::Array(values) => Self::Array(values.iter().map(Self::from).collect()),
+            Value::BigInt(v) => Self::BigInt(v.clone()),
+            Value::Bool(v) => Self::Bool(*v),
+            Value::Double(v) => Self::Double(*v),
+            Value::Int(v) => Self::Int(*v),
+            Value::Pauli(v) => Self::Pauli(*v),
+            Value::Result(val::Result::Val(v)) => Self::Result(*v),
+            Value::String(v) => Self::String(v.to_string()),
+            Value::Tuple(values) => Self::Tuple(values.iter().map(Self::from).collect()),
+            _ => Self::Other {
+                type_name: value.type_name(),
+                rendered: value.to_string(),
+            },
+        }
+    }
+}
+
+impl Display for ShotValue {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Array(values) => {
+                write!(f, "[")?;
+                join(f, values)?;
+                write!(f, "]")
+            }
+            Self::BigInt(v) => write!(f, "{v}"),
+            Self::Bool(v) => write!(f, "{v}"),
+            Self::Double(v) => write!(f, "{}", Value::Double(*v)),
+            Self::Int(v) => write!(f, "{v}"),
+            Self::Pauli(v) => write!(f, "{}", Value::Pauli(*v)),
+            Self::Result(v) => write!(f, "{}", Value::Result(val::Result::Val(*v))),
+            Self::String(v) => write!(f, "{v}"),
+            Self::Tuple(values) => {
+                write!(f, "(")?;
+                join(f, values)?;
+                if values.len() == 1 {
+                    write!(f, ",")?;
+                }
+                write!(f, ")")
+            }
+            Self::Other { rendered, .. } => write!(f, "{rendered}"),
+        }
+    }
+}
+
+fn join(f: &mut Formatter, values: &[ShotValue]) -> fmt::Result {
+    for (i, value) in values.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{value}")?;
+    }
+    Ok(())
+}
+
+/// An output produced during a shot, such as a message or a state dump.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ShotOutput {
+    State(Vec<(BigUint, Complex64)>, usize),
+    Matrix(Vec<Vec<Complex64>>),
+    Message(String),
+}
+
+impl ShotOutput {
+    /// Sends the output to the given receiver, as if the shot had run with it.
+    /// # Errors
+    /// Returns an error if the receiver fails to handle the output.
+    pub fn send_to(&self, receiver: &mut impl Receiver) -> Result<(), output::Error> {
+        match self {
+            Self::State(state, qubit_count) => receiver.state(state.clone(), *qubit_count),
+            Self::Matrix(matrix) => receiver.matrix(matrix.clone()),
+            Self::Message(msg) => receiver.message(msg),
+        }
+    }
+}
+
+/// Collects the outputs produced during a shot.
+#[derive(Default)]
+struct OutputRecorder(Vec<ShotOutput>);
+
+impl Receiver for OutputRecorder {
+    fn state(
+        &mut self,
+        state: Vec<(BigUint, Complex64)>,
+        qubit_count: usize,
+    ) -> Result<(), output::Error> {
+        self.0.push(ShotOutput::State(state, qubit_count));
+        Ok(())
+    }
+
+    fn matrix(&mut self, matrix: Vec<Vec<Complex64>>) -> Result<(), output::Error> {
+        self.0.push(ShotOutput::Matrix(matrix));
+        Ok(())
+    }
+
+    fn message(&mut self, msg: &str) -> Result<(), output::Error> {
+        self.0.push(ShotOutput::Message(msg.to_string()));
+        Ok(())
+    }
+}
+
+/// The outcome of a single shot run by `Interpreter::run_shots`.
+#[derive(Debug)]
+pub struct ShotResult {
+    /// The seed used for the simulator in this shot, if any.
+    pub seed: Option<u64>,
+    /// The output, such as messages and state dumps, produced during the shot.
+    pub output: Vec<ShotOutput>,
+    /// The value returned by the shot, or the errors that ended it.
+    pub result: Result<ShotValue, Vec<Error>>,
+}
+
+/// The results of running an entry expression for multiple shots.
+#[derive(Debug)]
+pub struct ShotResults {
+    /// The number of shots that returned each distinct value, rendered as a string, in order of
+    /// first appearance. Shots that failed with a runtime error are not counted.
+    pub histogram: Vec<(String, usize)>,
+    /// The outcome of each shot, in shot order.
+    pub shots: Vec<ShotResult>,
+}
+
+impl ShotResults {
+    pub(super) fn new(shots: Vec<ShotResult>) -> Self {
+        let mut histogram: Vec<(String, usize)> = Vec::new();
+        for value in shots.iter().filter_map(|shot| shot.result.as_ref().ok()) {
+            let value = value.to_string();
+            match histogram.iter_mut().find(|(v, _)| *v == value) {
+                Some((_, count)) => *count += 1,
+                None => histogram.push((value, 1)),
+            }
+        }
+        Self { histogram, shots }
+    }
+}
+
+impl Interpreter {
+    /// Gets the package and execution graph to run for the given entry expression, or for the
+    /// entry point of the sources if no expression is given.
+    pub(super) fn shot_entry(
+        &mut self,
+        expr: Option<&str>,
+    ) -> Result<(PackageId, ExecGraph), Vec<Error>> {
+        match expr {
+            Some(expr) => Ok((self.package, self.compile_entry_expr(expr)?.0)),
+            None => Ok((self.source_package, self.get_entry_exec_graph()?)),
+        }
+    }
+}
+
+/// The outcome of a shot as produced on the thread that ran it. Runtime errors are only turned
+/// into [`Error`]s once the result is back on the interpreter's thread, since that needs the
+/// compiled sources, which can't be shared across threads.
+pub(super) struct RawShotResult {
+    seed: Option<u64>,
+    output: Vec<ShotOutput>,
+    result: Result<ShotValue, (qsc_eval::Error, Vec<Frame>)>,
+}
+
+impl RawShotResult {
+    pub(super) fn into_shot_result(
+        self,
+        package_store: &PackageStore,
+        fir_store: &fir::PackageStore,
+    ) -> ShotResult {
+        ShotResult {
+            seed: self.seed,
+            output: self.output,
+            result: self.result.map_err(|(error, call_stack)| {
+                eval_error(package_store, fir_store, call_stack, error)
+            }),
+        }
+    }
+}
+
+fn run_shot(
+    fir_store: &fir::PackageStore,
+    (package, graph): &(PackageId, ExecGraph),
+    (seed, classical_seed): (Option<u64>, Option<u64>),
+    noise: Option<&NoiseConfig>,
+) -> RawShotResult {
+    let mut sim = match noise {
+        Some(noise) => SparseSim::new_with_noise(noise.clone()),
+        None => SparseSim::new(),
+    };
+    if seed.is_some() {
+        sim.set_seed(seed);
+    }
+    let mut output = OutputRecorder::default();
+    let result = qsc_eval::eval(
+        *package,
+        classical_seed,
+        graph.clone(),
+        fir_store,
+        &mut Env::default(),
+        &mut sim,
+        &mut output,
+    );
+    RawShotResult {
+        seed,
+        output: output.0,
+        result: result.map(|value| ShotValue::from(&value)),
+    }
+}
+
+/// Runs one shot of `entry` for each pair of quantum and classical seeds on up to `workers`
+/// threads, which share the compiled program, and passes the results to `report` in seed order.
+/// No more shots are started once `report` returns `ControlFlow::Break`.
+pub(super) fn run(
+    fir_store: &fir::PackageStore,
+    entry: &(PackageId, ExecGraph),
+    noise: Option<&NoiseConfig>,
+    seeds: &[(Option<u64>, Option<u64>)],
+    workers: usize,
+    mut report: impl FnMut(RawShotResult) -> ControlFlow<()>,
+) {
+    if workers <= 1 {
+        for &seeds in seeds {
+            if report(run_shot(fir_store, entry, seeds, noise)).is_break() {
+                return;
+            }
+        }
+        return;
+    }
+
+    let next = AtomicUsize::new(0);
+    let stop = AtomicBool::new(false);
+    thread::scope(|scope| {
+        let (results, receiver) = mpsc::channel();
+        for _ in 0..workers {
+            let results = results.clone();
+            let (next, stop) = (&next, &stop);
+            scope.spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    let shot = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(&seeds) = seeds.get(shot) else {
+                        break;
+                    };
+                    let result = run_shot(fir_store, entry, seeds, noise);
+                    if results.send((shot, result)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(results);
+
+        // Shots finish out of order, so each one is held back until all the shots before it
+        // have been reported.
+        let mut finished = FxHashMap::default();
+        let mut reported = 0;
+        for (shot, result) in receiver {
+            finished.insert(shot, result);
+            while let Some(result) = finished.remove(&reported) {
+                reported += 1;
+                if report(result).is_break() {
+                    stop.store(true, Ordering::Relaxed);
+                    return;
+                }
+            }
+        }
+    });
+}
+
+/// Derives the seed for a shot from the seed of the run, using the `SplitMix64` mixing function
+/// so that neighboring shots get uncorrelated seeds.
+pub(super) fn shot_seed(seed: u64, shot: usize) -> u64 {
+    let mut z = seed.wrapping_add((shot as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
@@ -2,14 +2,23 @@
 // Licensed under the MIT License.
 
 mod given_interpreter {
-    use crate::interpret::{InterpretResult, Interpreter};
+    use crate::interpret::{InterpretResult, Interpreter, ShotOutput, ShotResults, ShotValue};
     use expect_test::Expect;
     use miette::Diagnostic;
     use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
-    use qsc_eval::{output::CursorReceiver, val::Value};
+    use qsc_eval::{
+        backend::{
+            noisy::{depolarizing_channel, readout_error_measurement, NoiseModel},
+            stabilizer::StabilizerSim,
+        },
+        noise::{NoiseConfig, PauliNoise},
+        output::CursorReceiver,
+        val::Value,
+    };
+    use qsc_fir::fir;
     use qsc_frontend::compile::SourceMap;
     use qsc_passes::PackageType;
-    use std::{fmt::Write, io::Cursor, iter, str::from_utf8};
+    use std::{fmt::Write, io::Cursor, iter, ops::ControlFlow, str::from_utf8};
 
     fn line(interpreter: &mut Interpreter, line: &str) -> (InterpretResult, String) {
         let mut cursor = Cursor::new(Vec::<u8>::new());
@@ -26,6 +35,16 @@ mod given_interpreter {
         (interpreter.run(&mut receiver, Some(expr)), receiver.dump())
     }
 
+    fn run_with_stabilizer_sim(
+        interpreter: &mut Interpreter,
+        expr: &str,
+    ) -> (InterpretResult, String) {
+        let mut cursor = Cursor::new(Vec::<u8>::new());
+        let mut receiver = CursorReceiver::new(&mut cursor);
+        let result = interpreter.run_with_sim(&mut StabilizerSim::new(), &mut receiver, Some(expr));
+        (result, receiver.dump())
+    }
+
     fn entry(interpreter: &mut Interpreter) -> (InterpretResult, String) {
         let mut cursor = Cursor::new(Vec::<u8>::new());
         let mut receiver = CursorReceiver::new(&mut cursor);
@@ -1222,6 +1241,273 @@ mod given_interpreter {
             }
         }
 
+        #[test]
+        fn run_with_pauli_noise() {
+            let mut interpreter = get_interpreter();
+            let mut noise = NoiseConfig::default();
+            noise.x = PauliNoise::bit_flip(1.0).expect("noise should be valid");
+            let mut cursor = Cursor::new(Vec::<u8>::new());
+            let mut receiver = CursorReceiver::new(&mut cursor);
+            let result = interpreter.run_with_noise(
+                &mut receiver,
+                Some("{ use q = Qubit(); X(q); MResetZ(q) }"),
+                noise,
+            );
+            let output = receiver.dump();
+            is_only_value(&result, &output, &Value::RESULT_ZERO);
+        }
+
+        #[test]
+        fn run_with_noise_model() {
+            let mut interpreter = get_interpreter();
+            let mut noise = NoiseModel::default();
+            noise
+                .set_gate_noise(
+                    "x",
+                    depolarizing_channel(0.0).expect("noise should be valid"),
+                )
+                .expect("noise should be set");
+            noise.measurement =
+                Some(readout_error_measurement(1.0).expect("measurement should be valid"));
+            let mut cursor = Cursor::new(Vec::<u8>::new());
+            let mut receiver = CursorReceiver::new(&mut cursor);
+            let result = interpreter.run_with_noise_model(
+                &mut receiver,
+                Some("{ use q = Qubit(); X(q); MResetZ(q) }"),
+                1,
+                noise,
+            );
+            let output = receiver.dump();
+            is_only_value(&result, &output, &Value::RESULT_ZERO);
+        }
+
+        #[test]
+        fn run_with_noise_model_fails_when_allocating_too_many_qubits() {
+            let mut interpreter = get_interpreter();
+            let mut cursor = Cursor::new(Vec::<u8>::new());
+            let mut receiver = CursorReceiver::new(&mut cursor);
+            let result = interpreter.run_with_noise_model(
+                &mut receiver,
+                Some("{ use qs = Qubit[3]; }"),
+                2,
+                NoiseModel::default(),
+            );
+            let errors = result.expect_err("run should fail");
+            assert!(format!("{errors:?}")
+                .contains("noisy simulation supports at most 2 simultaneously allocated qubits"));
+        }
+
+        #[test]
+        fn run_clifford_program_with_stabilizer_sim() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = run_with_stabilizer_sim(
+                &mut interpreter,
+                "{ use qs = Qubit[100]; H(qs[0]); ApplyCNOTChain(qs); Rx(Std.Math.PI(), qs[0]); let rs = Std.Measurement.MResetEachZ(qs); rs[0] == rs[99] }",
+            );
+            is_only_value(&result, &output, &Value::Bool(false));
+        }
+
+        #[test]
+        fn run_non_clifford_program_with_stabilizer_sim_fails_at_call_site() {
+            let mut interpreter = get_interpreter();
+            let (result, output) =
+                run_with_stabilizer_sim(&mut interpreter, "{ use q = Qubit(); H(q); T(q); }");
+            is_only_error(
+                &result,
+                &output,
+                &expect![[r#"
+                    runtime error: unsupported operation: the stabilizer simulator only supports Clifford operations, and T is not a Clifford operation
+                      this operation is not supported by the simulator [<entry>] [T(q)]
+                "#]],
+            );
+        }
+
+        #[test]
+        fn run_shots_aggregates_results() {
+            let mut interpreter = get_interpreter();
+            let results = interpreter
+                .run_shots(
+                    Some("{ use q = Qubit(); X(q); MResetZ(q) }"),
+                    10,
+                    None,
+                    4,
+                    None,
+                )
+                .expect("shots should run");
+            assert_eq!(results.shots.len(), 10);
+            assert_eq!(results.histogram, vec![("One".to_string(), 10)]);
+            assert!(results
+                .shots
+                .iter()
+                .all(|shot| shot.result.as_ref().ok() == Some(&ShotValue::Result(true))));
+        }
+
+        #[test]
+        fn run_shots_captures_output_per_shot() {
+            let mut interpreter = get_interpreter();
+            let results = interpreter
+                .run_shots(Some("Message(\"hello!\")"), 3, None, 2, None)
+                .expect("shots should run");
+            for shot in &results.shots {
+                assert_eq!(shot.output, vec![ShotOutput::Message("hello!".to_string())]);
+                assert_eq!(
+                    shot.result.as_ref().ok(),
+                    Some(&ShotValue::Tuple(Vec::new()))
+                );
+            }
+        }
+
+        #[test]
+        fn run_shots_keeps_structure_of_returned_values() {
+            let mut interpreter = get_interpreter();
+            let results = interpreter
+                .run_shots(Some("([1, 2], PauliX, 1.0, \"a\", 1..3)"), 1, None, 1, None)
+                .expect("shots should run");
+            let value = results.shots[0]
+                .result
+                .as_ref()
+                .expect("shot should succeed");
+            let ShotValue::Tuple(items) = value else {
+                panic!("value should be a tuple, got {value:?}");
+            };
+            assert_eq!(
+                items[..4],
+                [
+                    ShotValue::Array(vec![ShotValue::Int(1), ShotValue::Int(2)]),
+                    ShotValue::Pauli(fir::Pauli::X),
+                    ShotValue::Double(1.0),
+                    ShotValue::String("a".to_string()),
+                ]
+            );
+            assert!(matches!(
+                items[4],
+                ShotValue::Other {
+                    type_name: "Range",
+                    ..
+                }
+            ));
+            assert_eq!(
+                results.histogram,
+                vec![("([1, 2], PauliX, 1.0, a, 1..3)".to_string(), 1)]
+            );
+        }
+
+        #[test]
+        fn run_shots_with_seed_is_independent_of_parallelism() {
+            let mut interpreter = get_interpreter();
+            let expr = "{ use q = Qubit(); H(q); MResetZ(q) }";
+            let sequential = interpreter
+                .run_shots(Some(expr), 20, Some(42), 1, None)
+                .expect("shots should run");
+            let parallel = interpreter
+                .run_shots(Some(expr), 20, Some(42), 3, None)
+                .expect("shots should run");
+            let values = |results: &ShotResults| {
+                results
+                    .shots
+                    .iter()
+                    .map(|shot| shot.result.as_ref().ok().cloned())
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(values(&sequential), values(&parallel));
+            assert_eq!(sequential.histogram.len(), 2);
+        }
+
+        #[test]
+        fn run_shots_uses_quantum_seed() {
+            let mut interpreter = get_interpreter();
+            interpreter.set_quantum_seed(Some(7));
+            let expr = "{ use q = Qubit(); H(q); MResetZ(q) }";
+            let first = interpreter
+                .run_shots(Some(expr), 8, None, 2, None)
+                .expect("shots should run");
+            let second = interpreter
+                .run_shots(Some(expr), 8, Some(7), 2, None)
+                .expect("shots should run");
+            assert_eq!(first.histogram, second.histogram);
+            assert!(first.shots.iter().all(|shot| shot.seed.is_some()));
+        }
+
+        #[test]
+        fn run_shots_applies_noise_on_workers() {
+            let mut interpreter = get_interpreter();
+            let mut noise = NoiseConfig::default();
+            noise.x = PauliNoise::bit_flip(1.0).expect("noise should be valid");
+            let expr = "{ use q = Qubit(); X(q); MResetZ(q) }";
+            for parallelism in [1, 3] {
+                let results = interpreter
+                    .run_shots(Some(expr), 6, None, parallelism, Some(&noise))
+                    .expect("shots should run");
+                assert_eq!(results.histogram, vec![("Zero".to_string(), 6)]);
+            }
+        }
+
+        #[test]
+        fn run_shots_sees_fragments_on_workers() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(
+                &mut interpreter,
+                "operation Flip() : Result { use q = Qubit(); X(q); MResetZ(q) }",
+            );
+            is_only_value(&result, &output, &Value::unit());
+            let results = interpreter
+                .run_shots(Some("Flip()"), 4, None, 2, None)
+                .expect("shots should run");
+            assert_eq!(results.histogram, vec![("One".to_string(), 4)]);
+            let (result, output) = line(&mut interpreter, "function Two() : Int { 2 }");
+            is_only_value(&result, &output, &Value::unit());
+            let results = interpreter
+                .run_shots(Some("(Flip(), Two())"), 4, None, 2, None)
+                .expect("shots should run");
+            assert_eq!(results.histogram, vec![("(One, 2)".to_string(), 4)]);
+        }
+
+        #[test]
+        fn run_shots_with_reports_shots_in_order_and_stops_on_break() {
+            let mut interpreter = get_interpreter();
+            let expr = "{ use q = Qubit(); H(q); MResetZ(q) }";
+            let expected = interpreter
+                .run_shots(Some(expr), 10, Some(3), 1, None)
+                .expect("shots should run");
+            let mut reported = Vec::new();
+            let results = interpreter
+                .run_shots_with(Some(expr), 10, Some(3), 3, None, |shot| {
+                    reported.push(shot.result.as_ref().ok().cloned());
+                    if reported.len() == 4 {
+                        ControlFlow::Break(())
+                    } else {
+                        ControlFlow::Continue(())
+                    }
+                })
+                .expect("shots should run");
+            assert_eq!(results.shots.len(), 4);
+            assert_eq!(
+                reported,
+                expected.shots[..4]
+                    .iter()
+                    .map(|shot| shot.result.as_ref().ok().cloned())
+                    .collect::<Vec<_>>()
+            );
+        }
+
+        #[test]
+        fn run_shots_reports_runtime_errors_per_shot() {
+            let mut interpreter = get_interpreter();
+            let results = interpreter
+                .run_shots(Some("{ fail \"boom\"; }"), 2, None, 2, None)
+                .expect("shots should run");
+            assert!(results.histogram.is_empty());
+            assert!(results.shots.iter().all(|shot| shot.result.is_err()));
+        }
+
+        #[test]
+        fn run_shots_reports_compile_errors() {
+            let mut interpreter = get_interpreter();
+            assert!(interpreter
+                .run_shots(Some("Undefined()"), 2, None, 2, None)
+                .is_err());
+        }
+
         #[test]
         fn base_prof_non_result_return() {
             let mut interpreter = get_interpreter_with_capabilities(TargetCapabilityFlags::empty());
@@ -1884,5 +2170,185 @@ mod given_interpreter {
                 entry: None,
             }
         }
+
+        #[test]
+        fn tests_are_discovered_and_run() {
+            let source = indoc! { r#"
+            namespace Test {
+                @Test()
+                operation Passes() : Unit {
+                    Message("passing");
+                }
+
+                operation NotATest() : Unit {}
+
+                @Test()
+                function Fails() : Unit {
+                    fail "expected failure";
+                }
+            }"#};
+
+            let sources = SourceMap::new([("test".into(), source.into())], None);
+            let (std_id, store) =
+                crate::compile::package_store_with_stdlib(TargetCapabilityFlags::all());
+            let mut interpreter = Interpreter::new(
+                sources,
+                PackageType::Lib,
+                TargetCapabilityFlags::all(),
+                LanguageFeatures::default(),
+                store,
+                &[(std_id, None)],
+            )
+            .expect("interpreter should be created");
+
+            let tests = interpreter.discover_tests();
+            let results = interpreter
+                .run_tests(&tests)
+                .into_iter()
+                .map(|result| {
+                    (
+                        result.callable_name,
+                        result.output,
+                        result
+                            .failure
+                            .map(|failure| (failure.message, failure.stack_trace.is_some())),
+                    )
+                })
+                .collect::<Vec<_>>();
+            expect![[r#"
+                [
+                    (
+                        "Test.Passes",
+                        "passing\n",
+                        None,
+                    ),
+                    (
+                        "Test.Fails",
+                        "",
+                        Some(
+                            (
+                                "expected failure",
+                                true,
+                            ),
+                        ),
+                    ),
+                ]
+            "#]]
+            .assert_debug_eq(&results);
+        }
+
+        #[test]
+        fn operation_matrix_is_computed() {
+            let source = indoc! { r#"
+            namespace Test {
+                operation ApplyCNOT(qs : Qubit[]) : Unit is Adj {
+                    CNOT(qs[0], qs[1]);
+                }
+            }"#};
+
+            let sources = SourceMap::new([("test".into(), source.into())], None);
+            let (std_id, store) =
+                crate::compile::package_store_with_stdlib(TargetCapabilityFlags::all());
+            let mut interpreter = Interpreter::new(
+                sources,
+                PackageType::Lib,
+                TargetCapabilityFlags::all(),
+                LanguageFeatures::default(),
+                store,
+                &[(std_id, None)],
+            )
+            .expect("interpreter should be created");
+
+            let matrix = interpreter
+                .get_operation_matrix("Test.ApplyCNOT", 2)
+                .expect("matrix should be computed");
+            let rows = matrix
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|elem| format!("{:.1}", elem.re))
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                })
+                .collect::<Vec<_>>();
+            expect![[r#"
+                [
+                    "1.0 0.0 0.0 0.0",
+                    "0.0 1.0 0.0 0.0",
+                    "0.0 0.0 0.0 1.0",
+                    "0.0 0.0 1.0 0.0",
+                ]
+            "#]]
+            .assert_debug_eq(&rows);
+        }
+
+        #[test]
+        fn operation_matrix_of_non_adjointable_operation_is_computed() {
+            let source = indoc! { r#"
+            namespace Test {
+                operation ApplyXWithMeasuredAux(qs : Qubit[]) : Unit {
+                    use aux = Qubit();
+                    let _ = MResetZ(aux);
+                    X(qs[0]);
+                }
+            }"#};
+
+            let sources = SourceMap::new([("test".into(), source.into())], None);
+            let (std_id, store) =
+                crate::compile::package_store_with_stdlib(TargetCapabilityFlags::all());
+            let mut interpreter = Interpreter::new(
+                sources,
+                PackageType::Lib,
+                TargetCapabilityFlags::all(),
+                LanguageFeatures::default(),
+                store,
+                &[(std_id, None)],
+            )
+            .expect("interpreter should be created");
+
+            let matrix = interpreter
+                .get_operation_matrix("Test.ApplyXWithMeasuredAux", 1)
+                .expect("matrix should be computed");
+            let rows = matrix
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|elem| format!("{:.1}", elem.re))
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                })
+                .collect::<Vec<_>>();
+            expect![[r#"
+                [
+                    "0.0 1.0",
+                    "1.0 0.0",
+                ]
+            "#]]
+            .assert_debug_eq(&rows);
+        }
+
+        #[test]
+        fn operation_matrix_of_lambda_is_computed() {
+            let mut interpreter = get_interpreter();
+            let matrix = interpreter
+                .get_operation_matrix("qs => S(qs[0])", 1)
+                .expect("matrix should be computed");
+            let rows = matrix
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|elem| format!("({:.1}, {:.1})", elem.re.abs(), elem.im.abs()))
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                })
+                .collect::<Vec<_>>();
+            expect![[r#"
+                [
+                    "(1.0, 0.0) (0.0, 0.0)",
+                    "(0.0, 0.0) (0.0, 1.0)",
+                ]
+            "#]]
+            .assert_debug_eq(&rows);
+        }
     }
 }
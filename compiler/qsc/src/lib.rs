@@ -49,7 +49,8 @@ pub mod line_column {
 }
 
 pub use qsc_eval::{
-    backend::{Backend, SparseSim},
+    backend::{noisy, stabilizer, Backend, SparseSim},
+    noise::{ChannelNoise, NoiseConfig, PauliNoise},
     state::{
         fmt_basis_state_label, fmt_complex, format_state_id, get_matrix_latex, get_phase,
         get_state_latex,
@@ -1,6 +1,6 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
-use crate::{compile, LanguageFeatures, TargetCapabilityFlags};
+use crate::{compile, formatter::FormatterConfig, LanguageFeatures, TargetCapabilityFlags};
 use expect_test::expect;
 use qsc_frontend::compile::{CompileUnit, SourceMap};
 use qsc_passes::PackageType;
@@ -23,6 +23,8 @@ fn mock_program() -> Project {
                     Arc::from("SomeLibraryKey"),
                 )]),
                 package_type: Some(qsc_project::PackageType::Exe),
+                version: None,
+                version_requirements: FxHashMap::default(),
             },
             packages: FxHashMap::from_iter(vec![(
                 Arc::from("SomeLibraryKey"),
@@ -34,10 +36,13 @@ fn mock_program() -> Project {
                     language_features: LanguageFeatures::default(),
                     dependencies: FxHashMap::default(),
                     package_type: Some(qsc_project::PackageType::Lib),
+                    version: None,
+                    version_requirements: FxHashMap::default(),
                 },
             )]),
         },
         lints: vec![],
+        formatter: FormatterConfig::default(),
         errors: vec![],
         path: "project/qsharp.json".into(),
         name: "project".into(),
@@ -0,0 +1,876 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+allocator::assign_global!();
+
+use clap::{crate_version, ArgGroup, Args, Parser, Subcommand, ValueEnum};
+use log::info;
+use miette::{Context, IntoDiagnostic, Report};
+use qsc::hir::PackageId;
+use qsc::interpret::{CircuitEntryPoint, Interpreter};
+use qsc::packages::BuildableProgram;
+use qsc::{
+    compile::{compile, TestDescriptor},
+    PassContext,
+};
+use qsc_codegen::{
+    qasm3::fir_to_qasm3,
+    qir::{fir_to_qir, fir_to_rir},
+    qsharp::write_package_string,
+};
+use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
+use qsc_frontend::{
+    compile::{PackageStore, SourceContents, SourceMap, SourceName},
+    error::WithSource,
+};
+use qsc_hir::hir::Package;
+use qsc_partial_eval::ProgramEntry;
+use qsc_passes::PackageType;
+use qsc_project::{
+    FileSystem, PackageCache, Project, StdFs, MANIFEST_FILE_NAME, WORKSPACE_MANIFEST_FILE_NAME,
+};
+use qsc_rca::PackageStoreComputeProperties;
+use std::{cell::RefCell, sync::Arc};
+use std::{
+    concat, fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    process::ExitCode,
+    string::String,
+};
+
+#[cfg(test)]
+mod tests;
+
+#[derive(clap::ValueEnum, Clone, Debug, Default, PartialEq)]
+pub enum Profile {
+    /// This is the default profile, which allows all operations.
+    #[default]
+    Unrestricted,
+    /// This profile restricts the set of operations to those that are supported by the Base profile.
+    Base,
+    /// This profile restricts the set of operations to those that are supported by the `AdaptiveRI` profile.
+    AdaptiveRI,
+    /// This profile restricts the set of operations to those that are supported by the `AdaptiveRIF` profile.
+    AdaptiveRIF,
+}
+
+// convert Profile into qsc::target::Profile
+impl From<Profile> for qsc::target::Profile {
+    fn from(profile: Profile) -> Self {
+        match profile {
+            Profile::Unrestricted => qsc::target::Profile::Unrestricted,
+            Profile::Base => qsc::target::Profile::Base,
+            Profile::AdaptiveRI => qsc::target::Profile::AdaptiveRI,
+            Profile::AdaptiveRIF => qsc::target::Profile::AdaptiveRIF,
+        }
+    }
+}
+
+#[derive(Debug, Parser)]
+#[command(version = concat!(crate_version!(), " (", env!("QSHARP_GIT_HASH"), ")"), arg_required_else_help(false), args_conflicts_with_subcommands(true))]
+#[clap(group(ArgGroup::new("input").args(["entry", "sources"]).required(false).multiple(true)))]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Disable automatic inclusion of the standard library.
+    #[arg(long)]
+    nostdlib: bool,
+
+    #[command(flatten)]
+    options: CompileOptions,
+
+    /// Enable verbose output.
+    #[arg(short, long)]
+    verbose: bool,
+
+    /// Entry expression to execute as the main operation.
+    #[arg(short, long)]
+    entry: Option<String>,
+
+    /// Q# source files to compile, or `-` to read from stdin.
+    #[arg()]
+    sources: Vec<PathBuf>,
+
+    /// Path to a Q# manifest for a project
+    #[arg(short, long)]
+    qsharp_json: Option<PathBuf>,
+
+    /// Path to a Q# workspace manifest. All member projects are built, in dependency order.
+    #[arg(short, long, conflicts_with_all = ["qsharp_json", "sources", "entry"])]
+    workspace: Option<PathBuf>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Build a Q# project, or all the projects of a workspace, along with their dependencies.
+    Build(BuildArgs),
+    /// Run the callables of a Q# project that are marked with the `@Test()` attribute.
+    Test(TestArgs),
+}
+
+#[derive(Debug, Args)]
+struct BuildArgs {
+    /// Path to a project directory, a `qsharp.json` manifest or a `qsharp.workspace.json` manifest.
+    /// Defaults to the current directory.
+    #[arg()]
+    path: Option<PathBuf>,
+
+    #[command(flatten)]
+    options: CompileOptions,
+}
+
+#[derive(Debug, Args)]
+struct TestArgs {
+    /// Path to a project directory or a `qsharp.json` manifest. Defaults to the current directory.
+    #[arg()]
+    path: Option<PathBuf>,
+
+    /// Only run the tests whose fully qualified name contains this string.
+    #[arg(long)]
+    filter: Option<String>,
+
+    /// Language features to compile with
+    #[arg(short, long)]
+    features: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+struct CompileOptions {
+    /// Emit the compilation unit in the specified format.
+    #[arg(long, value_enum)]
+    emit: Vec<Emit>,
+
+    /// Write output to compiler-chosen filename in <dir>.
+    #[arg(long = "outdir", value_name = "DIR")]
+    out_dir: Option<PathBuf>,
+
+    /// Target QIR profile for code generation
+    #[arg(short, long)]
+    profile: Option<Profile>,
+
+    /// Language features to compile with
+    #[arg(short, long)]
+    features: Vec<String>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum Emit {
+    Ast,
+    Hir,
+    Fir,
+    Rir,
+    Qir,
+    Qasm3,
+    Qsharp,
+    CircuitJson,
+    Estimates,
+}
+
+impl Emit {
+    /// Whether the output requires an entry point, and so an executable package.
+    fn needs_entry(self) -> bool {
+        matches!(
+            self,
+            Emit::Rir | Emit::Qir | Emit::Qasm3 | Emit::CircuitJson | Emit::Estimates
+        )
+    }
+}
+
+#[allow(clippy::too_many_lines)]
+fn main() -> miette::Result<ExitCode> {
+    env_logger::init();
+    let cli = Cli::parse();
+    match cli.command {
+        Some(Command::Build(args)) => return build(args),
+        Some(Command::Test(args)) => return run_tests(args),
+        None => {}
+    }
+
+    let options = cli.options;
+    let profile: qsc::target::Profile = options.profile.unwrap_or_default().into();
+    let capabilities = profile.into();
+    let package_type = default_package_type(&options.emit);
+    let mut features = LanguageFeatures::from_iter(options.features);
+    let out_dir = options
+        .out_dir
+        .as_ref()
+        .map_or(".".as_ref(), PathBuf::as_path);
+
+    if let Some(workspace) = &cli.workspace {
+        return build_workspace(
+            workspace,
+            &options.emit,
+            out_dir,
+            package_type,
+            capabilities,
+            features,
+        );
+    }
+
+    let (store, dependencies, source_map) = if let Some(qsharp_json) = cli.qsharp_json {
+        if let Some(dir) = qsharp_json.parent() {
+            match load_project(dir, &mut features) {
+                Ok(items) => items,
+                Err(exit_code) => return Ok(exit_code),
+            }
+        } else {
+            eprintln!("{} must have a parent directory", qsharp_json.display());
+            return Ok(ExitCode::FAILURE);
+        }
+    } else {
+        let sources = cli
+            .sources
+            .iter()
+            .map(read_source)
+            .collect::<miette::Result<Vec<_>>>()?;
+
+        let mut store = PackageStore::new(qsc::compile::core());
+        let dependencies = if cli.nostdlib {
+            vec![]
+        } else {
+            let std_id = store.insert(qsc::compile::std(&store, TargetCapabilityFlags::all()));
+            vec![(std_id, None)]
+        };
+        (
+            store,
+            dependencies,
+            SourceMap::new(sources, cli.entry.clone().map(std::convert::Into::into)),
+        )
+    };
+
+    let success = compile_and_emit(
+        store,
+        &dependencies,
+        source_map,
+        &options.emit,
+        out_dir,
+        package_type,
+        capabilities,
+        features,
+    )?;
+    Ok(if success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+/// Outputs that need an entry point are compiled as executables, everything else as a library.
+fn default_package_type(emit: &[Emit]) -> PackageType {
+    if emit.iter().any(|emit| emit.needs_entry()) {
+        PackageType::Exe
+    } else {
+        PackageType::Lib
+    }
+}
+
+/// Builds the project or workspace at the given path, see [`BuildArgs`].
+fn build(args: BuildArgs) -> miette::Result<ExitCode> {
+    let options = args.options;
+    let path = args.path.unwrap_or_else(|| PathBuf::from("."));
+    let profile: qsc::target::Profile = options.profile.unwrap_or_default().into();
+    let capabilities = profile.into();
+    let package_type = default_package_type(&options.emit);
+    let mut features = LanguageFeatures::from_iter(options.features);
+    let out_dir = options
+        .out_dir
+        .as_ref()
+        .map_or(".".as_ref(), PathBuf::as_path);
+
+    let workspace_json = if path.is_dir() {
+        let workspace_json = path.join(WORKSPACE_MANIFEST_FILE_NAME);
+        (!path.join(MANIFEST_FILE_NAME).is_file() && workspace_json.is_file())
+            .then_some(workspace_json)
+    } else {
+        (path.file_name() == Some(WORKSPACE_MANIFEST_FILE_NAME.as_ref())).then(|| path.clone())
+    };
+    if let Some(workspace_json) = workspace_json {
+        return build_workspace(
+            &workspace_json,
+            &options.emit,
+            out_dir,
+            package_type,
+            capabilities,
+            features,
+        );
+    }
+
+    let project = match read_project(project_dir(&path)) {
+        Ok(project) => project,
+        Err(exit_code) => return Ok(exit_code),
+    };
+    let package_type = manifest_package_type(&project, package_type);
+    let (store, dependencies, source_map) = match prepare_project(project, &mut features) {
+        Ok(items) => items,
+        Err(exit_code) => return Ok(exit_code),
+    };
+    let success = compile_and_emit(
+        store,
+        &dependencies,
+        source_map,
+        &options.emit,
+        out_dir,
+        package_type,
+        capabilities,
+        features,
+    )?;
+    Ok(if success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+/// Returns the project directory for a path that is either the directory itself
+/// or its `qsharp.json` manifest.
+fn project_dir(path: &Path) -> &Path {
+    if path.is_file() {
+        path.parent().unwrap_or(".".as_ref())
+    } else {
+        path
+    }
+}
+
+/// Returns the package type set in the project manifest, if any.
+fn manifest_package_type(project: &Project, default: PackageType) -> PackageType {
+    match project.package_graph_sources.root.package_type {
+        Some(qsc_project::PackageType::Exe) => PackageType::Exe,
+        Some(qsc_project::PackageType::Lib) => PackageType::Lib,
+        None => default,
+    }
+}
+
+/// Compiles the project at the given path and runs each of its tests, see [`TestArgs`].
+/// Each test runs with a fresh simulator and environment. Output from tests is only
+/// shown for the tests that fail.
+fn run_tests(args: TestArgs) -> miette::Result<ExitCode> {
+    let path = args.path.unwrap_or_else(|| PathBuf::from("."));
+    let features = LanguageFeatures::from_iter(args.features);
+    let (mut interpreter, tests) =
+        match discover_tests(project_dir(&path), args.filter.as_deref(), features) {
+            Ok(items) => items,
+            Err(exit_code) => return Ok(exit_code),
+        };
+
+    println!("running {} tests", tests.len());
+    let mut failures = Vec::new();
+    for test in &tests {
+        let result = interpreter.run_test(test);
+        if result.failure.is_some() {
+            println!("test {} ... FAILED", result.callable_name);
+            failures.push(result);
+        } else {
+            println!("test {} ... ok", result.callable_name);
+        }
+    }
+
+    for result in &failures {
+        let failure = result.failure.as_ref().expect("test should have failed");
+        println!();
+        println!("---- {} ----", result.callable_name);
+        print!("{}", result.output);
+        if let Some(stack_trace) = &failure.stack_trace {
+            eprintln!("{stack_trace}");
+        }
+        for error in &failure.errors {
+            eprintln!("{:?}", Report::new(error.clone()));
+        }
+    }
+
+    println!();
+    println!(
+        "test result: {}. {} passed; {} failed",
+        if failures.is_empty() { "ok" } else { "FAILED" },
+        tests.len() - failures.len(),
+        failures.len()
+    );
+    Ok(if failures.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+/// Compiles the project in the given directory for testing, and lists its tests whose
+/// fully qualified name contains `filter`, if given.
+fn discover_tests(
+    dir: &Path,
+    filter: Option<&str>,
+    mut features: LanguageFeatures,
+) -> Result<(Interpreter, Vec<TestDescriptor>), ExitCode> {
+    let (store, dependencies, source_map) = load_project(dir, &mut features)?;
+    let interpreter = Interpreter::new(
+        source_map,
+        PackageType::Lib,
+        TargetCapabilityFlags::all(),
+        features,
+        store,
+        &dependencies,
+    )
+    .map_err(|errors| {
+        for error in errors {
+            eprintln!("{:?}", Report::new(error));
+        }
+        ExitCode::FAILURE
+    })?;
+
+    let tests = interpreter
+        .discover_tests()
+        .into_iter()
+        .filter(|test| filter.map_or(true, |filter| test.callable_name.contains(filter)))
+        .collect();
+    Ok((interpreter, tests))
+}
+
+/// Compiles the user code against the given dependencies, and writes the requested outputs.
+/// Returns whether compilation succeeded. Errors are reported to stderr.
+#[allow(clippy::too_many_arguments, clippy::too_many_lines)]
+fn compile_and_emit(
+    mut store: PackageStore,
+    dependencies: &[(PackageId, Option<Arc<str>>)],
+    source_map: SourceMap,
+    emit: &[Emit],
+    out_dir: &Path,
+    package_type: PackageType,
+    capabilities: TargetCapabilityFlags,
+    features: LanguageFeatures,
+) -> miette::Result<bool> {
+    let (unit, errors) = compile(
+        &store,
+        dependencies,
+        source_map,
+        package_type,
+        capabilities,
+        features,
+    );
+    let package_id = store.insert(unit);
+    let unit = store.get(package_id).expect("package should be in store");
+
+    for emit in emit {
+        match emit {
+            Emit::Ast => write_output(out_dir, "ast.txt", "AST", &unit.ast.package.to_string())?,
+            Emit::Hir => emit_hir(&unit.package, out_dir)?,
+            Emit::Fir => {
+                if errors.is_empty() {
+                    let (fir_store, fir_package_id) =
+                        qsc_passes::lower_hir_to_fir(&store, package_id);
+                    let package = fir_store.get(fir_package_id);
+                    write_output(out_dir, "fir.txt", "FIR", &package.to_string())?;
+                }
+            }
+            Emit::Qsharp => write_output(
+                out_dir,
+                "qsharp.qs",
+                "Q#",
+                &write_package_string(&unit.ast.package),
+            )?,
+            Emit::Rir => {
+                if package_type != PackageType::Exe {
+                    eprintln!("RIR generation is only supported for executable packages");
+                    return Ok(false);
+                }
+                if capabilities == TargetCapabilityFlags::all() {
+                    eprintln!("RIR generation is not supported for unrestricted profile");
+                    return Ok(false);
+                }
+                if errors.is_empty()
+                    && !report_output_errors(emit_rir(out_dir, &store, package_id, capabilities))
+                {
+                    return Ok(false);
+                }
+            }
+            Emit::Qir => {
+                if package_type != PackageType::Exe {
+                    eprintln!("QIR generation is only supported for executable packages");
+                    return Ok(false);
+                }
+                if capabilities == TargetCapabilityFlags::all() {
+                    eprintln!("QIR generation is not supported for unrestricted profile");
+                    return Ok(false);
+                }
+                if errors.is_empty()
+                    && !report_output_errors(emit_qir(out_dir, &store, package_id, capabilities))
+                {
+                    return Ok(false);
+                }
+            }
+            Emit::Qasm3 => {
+                if capabilities == TargetCapabilityFlags::all() {
+                    eprintln!("OpenQASM 3 generation is not supported for unrestricted profile");
+                    return Ok(false);
+                }
+                if errors.is_empty()
+                    && !report_output_errors(emit_qasm3(out_dir, &store, package_id, capabilities))
+                {
+                    return Ok(false);
+                }
+            }
+            Emit::CircuitJson => {
+                if package_type != PackageType::Exe {
+                    eprintln!("circuit generation is only supported for executable packages");
+                    return Ok(false);
+                }
+            }
+            Emit::Estimates => {
+                if package_type != PackageType::Exe {
+                    eprintln!("resource estimation is only supported for executable packages");
+                    return Ok(false);
+                }
+            }
+        }
+    }
+
+    // The interpreter takes ownership of the package store, so the outputs
+    // that need it are generated last.
+    if (emit.contains(&Emit::CircuitJson) || emit.contains(&Emit::Estimates))
+        && errors.is_empty()
+        && !report_output_errors(emit_interpreter_outputs(
+            out_dir,
+            emit,
+            store,
+            package_id,
+            capabilities,
+            features,
+            dependencies,
+        ))
+    {
+        return Ok(false);
+    }
+
+    let success = errors.is_empty();
+    for error in errors {
+        eprintln!("{:?}", Report::new(error));
+    }
+    Ok(success)
+}
+
+/// Reports the errors from generating an output to stderr. Returns whether the
+/// output was generated.
+fn report_output_errors(result: Result<(), Vec<Report>>) -> bool {
+    match result {
+        Ok(()) => true,
+        Err(reports) => {
+            for report in reports {
+                eprintln!("{report:?}");
+            }
+            false
+        }
+    }
+}
+
+fn read_source(path: impl AsRef<Path>) -> miette::Result<(SourceName, SourceContents)> {
+    let path = path.as_ref();
+    if path.as_os_str() == "-" {
+        let mut input = String::new();
+        io::stdin()
+            .read_to_string(&mut input)
+            .into_diagnostic()
+            .context("could not read standard input")?;
+
+        Ok(("<stdin>".into(), input.into()))
+    } else {
+        let contents = fs::read_to_string(path)
+            .into_diagnostic()
+            .with_context(|| format!("could not read source file `{}`", path.display()))?;
+
+        Ok((path.to_string_lossy().into(), contents.into()))
+    }
+}
+
+/// Writes the named output file into the output directory.
+fn write_output(
+    out_dir: &Path,
+    file_name: &str,
+    format: &str,
+    contents: &str,
+) -> miette::Result<()> {
+    let path = out_dir.join(file_name);
+    info!(
+        "Writing {format} output file to: {}",
+        path.to_str().unwrap_or_default()
+    );
+    fs::write(&path, contents)
+        .into_diagnostic()
+        .with_context(|| format!("could not emit {format} file `{}`", path.display()))
+}
+
+fn emit_hir(package: &Package, dir: impl AsRef<Path>) -> miette::Result<()> {
+    let path = dir.as_ref().join("hir.txt");
+    info!(
+        "Writing HIR output file to: {}",
+        path.to_str().unwrap_or_default()
+    );
+    fs::write(&path, package.to_string())
+        .into_diagnostic()
+        .with_context(|| format!("could not emit HIR file `{}`", path.display()))
+}
+
+/// Lowers the package to FIR and runs the FIR passes, returning everything needed
+/// to partially evaluate the package's entry expression.
+fn fir_program(
+    store: &PackageStore,
+    package_id: PackageId,
+    capabilities: TargetCapabilityFlags,
+) -> Result<
+    (
+        qsc_fir::fir::PackageStore,
+        ProgramEntry,
+        PackageStoreComputeProperties,
+    ),
+    Vec<Report>,
+> {
+    let (fir_store, fir_package_id) = qsc_passes::lower_hir_to_fir(store, package_id);
+    let package = fir_store.get(fir_package_id);
+    let entry = ProgramEntry {
+        exec_graph: package.entry_exec_graph.clone(),
+        expr: (
+            fir_package_id,
+            package
+                .entry
+                .expect("package must have an entry expression"),
+        )
+            .into(),
+    };
+
+    let compute_properties =
+        PassContext::run_fir_passes_on_fir(&fir_store, fir_package_id, capabilities)
+            .map_err(|errors| errors.into_iter().map(Report::new).collect::<Vec<_>>())?;
+    Ok((fir_store, entry, compute_properties))
+}
+
+/// Reports a partial evaluation error against the sources of the package it occurred in.
+fn partial_eval_report(
+    store: &PackageStore,
+    package_id: PackageId,
+    error: qsc_partial_eval::Error,
+) -> Report {
+    let source_package = match error.span() {
+        Some(span) => span.package,
+        None => package_id,
+    };
+    let unit = store
+        .get(source_package)
+        .expect("package should be in store");
+    Report::new(WithSource::from_map(&unit.sources, error))
+}
+
+fn emit_rir(
+    out_dir: &Path,
+    store: &PackageStore,
+    package_id: PackageId,
+    capabilities: TargetCapabilityFlags,
+) -> Result<(), Vec<Report>> {
+    let (fir_store, entry, compute_properties) = fir_program(store, package_id, capabilities)?;
+    match fir_to_rir(&fir_store, capabilities, Some(compute_properties), &entry) {
+        Ok(rir) => write_output(out_dir, "rir.txt", "RIR", &rir).map_err(|err| vec![err]),
+        Err(error) => Err(vec![partial_eval_report(store, package_id, error)]),
+    }
+}
+
+fn emit_qir(
+    out_dir: &Path,
+    store: &PackageStore,
+    package_id: PackageId,
+    capabilities: TargetCapabilityFlags,
+) -> Result<(), Vec<Report>> {
+    let (fir_store, entry, compute_properties) = fir_program(store, package_id, capabilities)?;
+    match fir_to_qir(&fir_store, capabilities, Some(compute_properties), &entry) {
+        Ok(qir) => write_output(out_dir, "qir.ll", "QIR", &qir).map_err(|err| vec![err]),
+        Err(error) => Err(vec![partial_eval_report(store, package_id, error)]),
+    }
+}
+
+fn emit_qasm3(
+    out_dir: &Path,
+    store: &PackageStore,
+    package_id: PackageId,
+    capabilities: TargetCapabilityFlags,
+) -> Result<(), Vec<Report>> {
+    let (fir_store, entry, compute_properties) = fir_program(store, package_id, capabilities)?;
+    match fir_to_qasm3(&fir_store, capabilities, Some(compute_properties), &entry) {
+        Ok(qasm) => {
+            write_output(out_dir, "qasm3.qasm", "OpenQASM 3", &qasm).map_err(|err| vec![err])
+        }
+        Err(qsc_codegen::qasm3::Error::PartialEvaluation(error)) => {
+            Err(vec![partial_eval_report(store, package_id, error)])
+        }
+        Err(error) => Err(vec![Report::new(error)]),
+    }
+}
+
+/// Writes the requested outputs that are produced by the interpreter: the circuit for the
+/// package's entry point, generated without simulating it, and the resource estimates for
+/// the entry point with the default estimator parameters. Both are written as JSON.
+fn emit_interpreter_outputs(
+    out_dir: &Path,
+    emit: &[Emit],
+    store: PackageStore,
+    package_id: PackageId,
+    capabilities: TargetCapabilityFlags,
+    features: LanguageFeatures,
+    dependencies: &[(PackageId, Option<Arc<str>>)],
+) -> Result<(), Vec<Report>> {
+    let mut interpreter =
+        Interpreter::from(store, package_id, capabilities, features, dependencies)
+            .map_err(|errors| errors.into_iter().map(Report::new).collect::<Vec<_>>())?;
+    if emit.contains(&Emit::CircuitJson) {
+        let circuit = interpreter
+            .circuit(CircuitEntryPoint::EntryPoint, false)
+            .map_err(|errors| errors.into_iter().map(Report::new).collect::<Vec<_>>())?;
+        let json = serde_json::to_string_pretty(&circuit)
+            .into_diagnostic()
+            .map_err(|err| vec![err])?;
+        write_output(out_dir, "circuit.json", "circuit", &json).map_err(|err| vec![err])?;
+    }
+    if emit.contains(&Emit::Estimates) {
+        let estimates = resource_estimator::estimate_entry(&mut interpreter, "[{}]")
+            .map_err(|errors| errors.into_iter().map(Report::new).collect::<Vec<_>>())?;
+        write_output(out_dir, "estimates.json", "resource estimates", &estimates)
+            .map_err(|err| vec![err])?;
+    }
+    Ok(())
+}
+
+/// Builds every member of the workspace at the given manifest path, in dependency order.
+/// The outputs of each member are written to a subdirectory of `out_dir` named after the member.
+/// Outputs that need an entry point are only emitted for executable members, so members
+/// that set the `lib` package type skip them.
+fn build_workspace(
+    workspace_json: &Path,
+    emit: &[Emit],
+    out_dir: &Path,
+    package_type: PackageType,
+    capabilities: TargetCapabilityFlags,
+    features: LanguageFeatures,
+) -> miette::Result<ExitCode> {
+    let Some(dir) = workspace_json.parent() else {
+        eprintln!("{} must have a parent directory", workspace_json.display());
+        return Ok(ExitCode::FAILURE);
+    };
+
+    let fs = StdFs;
+    let cache = RefCell::new(PackageCache::default());
+    let workspace = match fs.load_workspace_and_update_lockfile(dir, Some(&cache)) {
+        Ok(workspace) => workspace,
+        Err(errs) => {
+            for e in errs {
+                eprintln!("{e:?}");
+            }
+            return Ok(ExitCode::FAILURE);
+        }
+    };
+
+    if !workspace.errors.is_empty() {
+        for e in workspace.errors {
+            eprintln!("{e:?}");
+        }
+        return Ok(ExitCode::FAILURE);
+    }
+
+    for project in workspace.members {
+        info!("Building workspace member {}", project.name);
+        let member_out_dir = out_dir.join(&*project.name);
+        let member_package_type = manifest_package_type(&project, package_type);
+        // Only request outputs that make sense for the member's package type.
+        let member_emit = emit
+            .iter()
+            .copied()
+            .filter(|emit| !emit.needs_entry() || member_package_type == PackageType::Exe)
+            .collect::<Vec<_>>();
+        if !member_emit.is_empty() {
+            fs::create_dir_all(&member_out_dir)
+                .into_diagnostic()
+                .with_context(|| {
+                    format!("could not create directory `{}`", member_out_dir.display())
+                })?;
+        }
+
+        let mut member_features = features;
+        let (store, dependencies, source_map) = match prepare_project(project, &mut member_features)
+        {
+            Ok(items) => items,
+            Err(exit_code) => return Ok(exit_code),
+        };
+        let success = compile_and_emit(
+            store,
+            &dependencies,
+            source_map,
+            &member_emit,
+            &member_out_dir,
+            member_package_type,
+            capabilities,
+            member_features,
+        )?;
+        if !success {
+            return Ok(ExitCode::FAILURE);
+        }
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Loads a project from the given directory and returns the package store, the list of
+/// dependencies, and the source map.
+/// Pre-populates the package store with all of the compiled dependencies.
+#[allow(clippy::type_complexity)]
+fn load_project(
+    dir: impl AsRef<Path>,
+    features: &mut LanguageFeatures,
+) -> Result<(PackageStore, Vec<(PackageId, Option<Arc<str>>)>, SourceMap), ExitCode> {
+    let project = read_project(dir)?;
+    prepare_project(project, features)
+}
+
+/// Reads the project manifest and sources from the given directory.
+fn read_project(dir: impl AsRef<Path>) -> Result<Project, ExitCode> {
+    let fs = StdFs;
+    match fs.load_project_and_update_lockfile(dir.as_ref(), None) {
+        Ok(project) => Ok(project),
+        Err(errs) => {
+            for e in errs {
+                eprintln!("{e:?}");
+            }
+            Err(ExitCode::FAILURE)
+        }
+    }
+}
+
+/// Builds the dependencies of a loaded project, see [`load_project`].
+#[allow(clippy::type_complexity)]
+fn prepare_project(
+    project: Project,
+    features: &mut LanguageFeatures,
+) -> Result<(PackageStore, Vec<(PackageId, Option<Arc<str>>)>, SourceMap), ExitCode> {
+    if !project.errors.is_empty() {
+        for e in project.errors {
+            eprintln!("{e:?}");
+        }
+        return Err(ExitCode::FAILURE);
+    }
+
+    // This builds all the dependencies
+    let buildable_program =
+        BuildableProgram::new(TargetCapabilityFlags::all(), project.package_graph_sources);
+
+    if !buildable_program.dependency_errors.is_empty() {
+        for e in buildable_program.dependency_errors {
+            eprintln!("{e:?}");
+        }
+        return Err(ExitCode::FAILURE);
+    }
+
+    let BuildableProgram {
+        store,
+        user_code,
+        user_code_dependencies,
+        ..
+    } = buildable_program;
+
+    let source_map = qsc::SourceMap::new(user_code.sources, None);
+
+    features.merge(LanguageFeatures::from_iter(user_code.language_features));
+
+    Ok((store, user_code_dependencies, source_map))
+}
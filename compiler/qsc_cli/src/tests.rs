@@ -0,0 +1,296 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{compile_and_emit, discover_tests, Emit};
+use expect_test::{expect, Expect};
+use indoc::indoc;
+use qsc::{target::Profile, PackageType};
+use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
+use qsc_frontend::compile::{PackageStore, SourceMap};
+use std::{fs, path::PathBuf};
+
+const ENTRY_POINT: &str = indoc! {"
+    namespace Test {
+        @EntryPoint()
+        operation Main() : Result {
+            use q = Qubit();
+            H(q);
+            MResetZ(q)
+        }
+    }
+"};
+
+/// Compiles the source against the standard library, writing the requested outputs to a
+/// fresh directory. Returns whether compilation succeeded, and the names of the files written
+/// along with their contents.
+fn emit(
+    name: &str,
+    source: &str,
+    emit: &[Emit],
+    package_type: PackageType,
+    profile: Profile,
+) -> (bool, Vec<(String, String)>) {
+    let out_dir = std::env::temp_dir().join(format!("qsc_cli_{name}_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&out_dir);
+    fs::create_dir_all(&out_dir).expect("output directory should be created");
+
+    let mut store = PackageStore::new(qsc::compile::core());
+    let std_id = store.insert(qsc::compile::std(&store, TargetCapabilityFlags::all()));
+    let source_map = SourceMap::new([("test.qs".into(), source.into())], None);
+    let success = compile_and_emit(
+        store,
+        &[(std_id, None)],
+        source_map,
+        emit,
+        &out_dir,
+        package_type,
+        profile.into(),
+        LanguageFeatures::default(),
+    )
+    .expect("outputs should be written");
+
+    let mut outputs = fs::read_dir(&out_dir)
+        .expect("output directory should be readable")
+        .map(|entry| {
+            let path = entry.expect("entry should be readable").path();
+            let contents = fs::read_to_string(&path).expect("output should be readable");
+            let name = path
+                .file_name()
+                .expect("output should have a file name")
+                .to_string_lossy()
+                .to_string();
+            (name, contents)
+        })
+        .collect::<Vec<_>>();
+    outputs.sort();
+    fs::remove_dir_all(&out_dir).expect("output directory should be removed");
+    (success, outputs)
+}
+
+fn output<'a>(outputs: &'a [(String, String)], name: &str) -> &'a str {
+    &outputs
+        .iter()
+        .find(|(file_name, _)| file_name == name)
+        .unwrap_or_else(|| panic!("{name} should be written"))
+        .1
+}
+
+fn file_names(outputs: &[(String, String)]) -> Vec<&str> {
+    outputs.iter().map(|(name, _)| name.as_str()).collect()
+}
+
+#[test]
+fn library_outputs_are_written() {
+    let source = indoc! {"
+        namespace Test {
+            function Add(a : Int, b : Int) : Int {
+                a + b
+            }
+        }
+    "};
+    let (success, outputs) = emit(
+        "library_outputs",
+        source,
+        &[Emit::Ast, Emit::Hir, Emit::Fir, Emit::Qsharp],
+        PackageType::Lib,
+        Profile::Unrestricted,
+    );
+    assert!(success);
+    assert_eq!(
+        file_names(&outputs),
+        ["ast.txt", "fir.txt", "hir.txt", "qsharp.qs"]
+    );
+    for name in ["ast.txt", "fir.txt", "hir.txt"] {
+        assert!(
+            output(&outputs, name).contains("Add"),
+            "{name} should contain the callable"
+        );
+    }
+    expect![[r#"
+        namespace Test {
+            function Add(a : Int, b : Int) : Int {
+                a + b
+            }
+        }"#]]
+    .assert_eq(output(&outputs, "qsharp.qs"));
+}
+
+#[test]
+fn rir_and_qir_are_written_for_base_profile() {
+    let (success, outputs) = emit(
+        "rir_and_qir",
+        ENTRY_POINT,
+        &[Emit::Rir, Emit::Qir],
+        PackageType::Exe,
+        Profile::Base,
+    );
+    assert!(success);
+    assert_eq!(file_names(&outputs), ["qir.ll", "rir.txt"]);
+    assert!(output(&outputs, "rir.txt").contains("Program:"));
+    assert!(output(&outputs, "qir.ll").contains("define void @ENTRYPOINT__main()"));
+}
+
+#[test]
+fn qasm3_is_written_for_base_profile() {
+    let (success, outputs) = emit(
+        "qasm3",
+        ENTRY_POINT,
+        &[Emit::Qasm3],
+        PackageType::Exe,
+        Profile::Base,
+    );
+    assert!(success);
+    expect![[r#"
+        OPENQASM 3.0;
+        include "stdgates.inc";
+        qubit[1] q;
+        bit[1] c;
+        output bit[1] results;
+        h q[0];
+        c[0] = measure q[0];
+        results[0] = c[0];
+    "#]]
+    .assert_eq(output(&outputs, "qasm3.qasm"));
+}
+
+#[test]
+fn circuit_json_is_written() {
+    let (success, outputs) = emit(
+        "circuit_json",
+        ENTRY_POINT,
+        &[Emit::CircuitJson],
+        PackageType::Exe,
+        Profile::Unrestricted,
+    );
+    assert!(success);
+    let circuit: serde_json::Value =
+        serde_json::from_str(output(&outputs, "circuit.json")).expect("circuit should be JSON");
+    assert_eq!(circuit["qubits"].as_array().map(Vec::len), Some(1));
+}
+
+#[test]
+fn estimates_are_written() {
+    let (success, outputs) = emit(
+        "estimates",
+        ENTRY_POINT,
+        &[Emit::Estimates],
+        PackageType::Exe,
+        Profile::Unrestricted,
+    );
+    assert!(success);
+    let estimates: serde_json::Value =
+        serde_json::from_str(output(&outputs, "estimates.json")).expect("estimates should be JSON");
+    assert!(estimates[0]["physicalCounts"]["physicalQubits"].is_u64());
+}
+
+#[test]
+fn circuit_json_and_estimates_are_written_together() {
+    let (success, outputs) = emit(
+        "circuit_json_and_estimates",
+        ENTRY_POINT,
+        &[Emit::CircuitJson, Emit::Estimates],
+        PackageType::Exe,
+        Profile::Unrestricted,
+    );
+    assert!(success);
+    assert_eq!(file_names(&outputs), ["circuit.json", "estimates.json"]);
+}
+
+#[test]
+fn entry_point_outputs_are_rejected_for_libraries() {
+    for kind in [Emit::Rir, Emit::Qir, Emit::CircuitJson, Emit::Estimates] {
+        let (success, outputs) = emit(
+            "library_entry_outputs",
+            ENTRY_POINT,
+            &[kind],
+            PackageType::Lib,
+            Profile::Base,
+        );
+        assert!(!success, "{kind:?} should fail for a library");
+        assert!(outputs.is_empty(), "{kind:?} should not write any output");
+    }
+}
+
+#[test]
+fn codegen_outputs_are_rejected_for_unrestricted_profile() {
+    for kind in [Emit::Rir, Emit::Qir, Emit::Qasm3] {
+        let (success, outputs) = emit(
+            "unrestricted_codegen",
+            ENTRY_POINT,
+            &[kind],
+            PackageType::Exe,
+            Profile::Unrestricted,
+        );
+        assert!(
+            !success,
+            "{kind:?} should fail for the unrestricted profile"
+        );
+        assert!(outputs.is_empty(), "{kind:?} should not write any output");
+    }
+}
+
+fn test_project() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join("tests")
+        .join("projects")
+        .join("with_tests")
+}
+
+fn check_tests(filter: Option<&str>, expect: &Expect) {
+    let (mut interpreter, tests) =
+        discover_tests(&test_project(), filter, LanguageFeatures::default())
+            .expect("project should compile");
+    let results = tests
+        .iter()
+        .map(|test| {
+            let result = interpreter.run_test(test);
+            format!(
+                "{} ... {}",
+                result.callable_name,
+                if result.failure.is_some() {
+                    "FAILED"
+                } else {
+                    "ok"
+                }
+            )
+        })
+        .collect::<Vec<_>>();
+    expect.assert_debug_eq(&results);
+}
+
+#[test]
+fn tests_are_discovered_and_run() {
+    check_tests(
+        None,
+        &expect![[r#"
+        [
+            "Tests.AdditionWorks ... ok",
+            "Tests.AllocatedQubitIsZero ... ok",
+            "Tests.SubtractionFails ... FAILED",
+        ]
+    "#]],
+    );
+}
+
+#[test]
+fn tests_are_filtered_by_name() {
+    check_tests(
+        Some("Subtraction"),
+        &expect![[r#"
+        [
+            "Tests.SubtractionFails ... FAILED",
+        ]
+    "#]],
+    );
+}
+
+#[test]
+fn filter_that_matches_nothing_runs_no_tests() {
+    check_tests(
+        Some("Multiplication"),
+        &expect![[r#"
+        []
+    "#]],
+    );
+}
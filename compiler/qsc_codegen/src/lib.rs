@@ -1,5 +1,6 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
+pub mod qasm3;
 pub mod qir;
 pub mod qsharp;
@@ -0,0 +1,735 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use std::{collections::BTreeMap, fmt::Write};
+
+use miette::Diagnostic;
+use qsc_data_structures::target::TargetCapabilityFlags;
+use qsc_partial_eval::ProgramEntry;
+use qsc_rca::PackageStoreComputeProperties;
+use qsc_rir::{
+    rir::{self, BlockId, ConditionCode, FcmpConditionCode, Instruction, Operand, VariableId},
+    utils::{build_predecessors_map, get_block_successors},
+};
+use rustc_hash::{FxHashMap, FxHashSet};
+use thiserror::Error;
+
+use crate::qir::fir_to_rir_program;
+
+/// An `OpenQASM` 3 generation error.
+#[derive(Clone, Debug, Diagnostic, Error)]
+pub enum Error {
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    PartialEvaluation(#[from] qsc_partial_eval::Error),
+
+    #[error("cannot generate OpenQASM 3 for calls to `{0}`")]
+    #[diagnostic(code("Qsc.Qasm3Gen.UnsupportedCallable"))]
+    #[diagnostic(help(
+        "only the standard Q# intrinsic gates, measurements, and resets can be exported"
+    ))]
+    UnsupportedCallable(String),
+
+    #[error(
+        "cannot generate OpenQASM 3 for loops that are not entered and exited through their header"
+    )]
+    #[diagnostic(code("Qsc.Qasm3Gen.UnsupportedLoop"))]
+    #[diagnostic(help(
+        "only loops that check their condition at the start of each iteration can be exported"
+    ))]
+    UnsupportedLoop,
+
+    #[error("cannot generate OpenQASM 3 for the floating-point value `{0}`")]
+    #[diagnostic(code("Qsc.Qasm3Gen.NonFiniteDouble"))]
+    #[diagnostic(help("OpenQASM 3 has no literals for infinite or NaN values"))]
+    NonFiniteDouble(String),
+}
+
+pub fn fir_to_qasm3(
+    fir_store: &qsc_fir::fir::PackageStore,
+    capabilities: TargetCapabilityFlags,
+    compute_properties: Option<PackageStoreComputeProperties>,
+    entry: &ProgramEntry,
+) -> Result<String, Error> {
+    let program = fir_to_rir_program(fir_store, capabilities, compute_properties, entry)?;
+    program_to_qasm3(&program)
+}
+
+/// Converts a partially evaluated program to `OpenQASM` 3.
+///
+/// Qubits and results are declared as the registers `q` and `c`. Recorded
+/// results are copied into the `output bit[..] results` register in the order
+/// they are recorded, and other recorded values into `output` variables named
+/// `value_<n>`. Branches are emitted as nested `if` statements and loops as
+/// `while` statements.
+pub fn program_to_qasm3(program: &rir::Program) -> Result<String, Error> {
+    let entry = program.get_callable(program.entry);
+    let entry_block = entry.body.expect("entry callable should have a body");
+
+    let mut emitter = Emitter::new(program, entry_block)?;
+    emitter.emit_region(entry_block, None, 0)?;
+
+    let mut qasm = String::new();
+    qasm.push_str("OPENQASM 3.0;\ninclude \"stdgates.inc\";\n");
+    for definition in emitter.gate_definitions.values() {
+        qasm.push_str(definition);
+        qasm.push('\n');
+    }
+    if program.num_qubits > 0 {
+        let _ = writeln!(qasm, "qubit[{}] q;", program.num_qubits);
+    }
+    if program.num_results > 0 {
+        let _ = writeln!(qasm, "bit[{}] c;", program.num_results);
+    }
+    if emitter.num_recorded_results > 0 {
+        let _ = writeln!(
+            qasm,
+            "output bit[{}] results;",
+            emitter.num_recorded_results
+        );
+    }
+    for (index, ty) in emitter.recorded_values.iter().enumerate() {
+        let _ = writeln!(qasm, "output {} value_{index};", type_to_qasm3(*ty));
+    }
+    for (variable_id, ty) in &emitter.variables {
+        let _ = writeln!(
+            qasm,
+            "{} {};",
+            type_to_qasm3(*ty),
+            variable_name(*variable_id)
+        );
+    }
+    qasm.push_str(&emitter.body);
+    Ok(qasm)
+}
+
+/// Where a recorded value is copied to in the program's outputs.
+#[derive(Clone, Copy)]
+enum RecordedOutput {
+    /// An index into the `results` output register.
+    Result(usize),
+    /// The index of a `value_<n>` output variable.
+    Value(usize),
+}
+
+struct Emitter<'a> {
+    program: &'a rir::Program,
+    /// The copies that replace the phi nodes of each block, keyed by the
+    /// predecessor block that has to perform them before branching.
+    phi_copies: FxHashMap<BlockId, Vec<(VariableId, Operand)>>,
+    /// The immediate post-dominator of each reachable block, if any. Back
+    /// edges are ignored and a loop header is post-dominated by its exit.
+    post_dominators: FxHashMap<BlockId, Option<BlockId>>,
+    /// The exit block of each loop, keyed by the loop header.
+    loop_exits: FxHashMap<BlockId, BlockId>,
+    /// The output each output recording call is copied to, keyed by the
+    /// block and index of the call.
+    recorded_outputs: FxHashMap<(BlockId, usize), RecordedOutput>,
+    num_recorded_results: usize,
+    /// The types of the recorded values that are not results.
+    recorded_values: Vec<rir::Ty>,
+    /// Variables are declared up front as `OpenQASM` 3 doesn't have SSA values.
+    variables: BTreeMap<VariableId, rir::Ty>,
+    /// Definitions for gates that are not part of `stdgates.inc`.
+    gate_definitions: BTreeMap<&'static str, &'static str>,
+    body: String,
+}
+
+impl<'a> Emitter<'a> {
+    fn new(program: &'a rir::Program, entry_block: BlockId) -> Result<Self, Error> {
+        let (order, back_edges) = topological_order(program, entry_block);
+        let loop_exits = find_loop_exits(program, &back_edges)?;
+
+        let mut phi_copies: FxHashMap<BlockId, Vec<(VariableId, Operand)>> = FxHashMap::default();
+        let mut variables = BTreeMap::new();
+        let mut recorded_outputs = FxHashMap::default();
+        let mut num_recorded_results = 0;
+        let mut recorded_values = Vec::new();
+        for block_id in &order {
+            for (index, instr) in program.get_block(*block_id).0.iter().enumerate() {
+                if let Instruction::Phi(args, variable) = instr {
+                    for (operand, pred) in args {
+                        phi_copies
+                            .entry(*pred)
+                            .or_default()
+                            .push((variable.variable_id, *operand));
+                    }
+                }
+                if let Some(variable) = assigned_variable(instr) {
+                    variables.insert(variable.variable_id, variable.ty);
+                }
+                if let Instruction::Call(callable_id, args, _) = instr {
+                    let output = match program.get_callable(*callable_id).name.as_str() {
+                        "__quantum__rt__result_record_output" => {
+                            num_recorded_results += 1;
+                            RecordedOutput::Result(num_recorded_results - 1)
+                        }
+                        "__quantum__rt__bool_record_output"
+                        | "__quantum__rt__int_record_output"
+                        | "__quantum__rt__double_record_output" => {
+                            recorded_values.push(args[0].get_type());
+                            RecordedOutput::Value(recorded_values.len() - 1)
+                        }
+                        _ => continue,
+                    };
+                    recorded_outputs.insert((*block_id, index), output);
+                }
+            }
+        }
+
+        // Visiting the blocks in reverse topological order means every
+        // successor has its post-dominator computed before its predecessors.
+        let mut post_dominators = FxHashMap::default();
+        for block_id in order.iter().rev() {
+            let post_dominator = if let Some(exit) = loop_exits.get(block_id) {
+                Some(*exit)
+            } else {
+                let successors = get_block_successors(program.get_block(*block_id))
+                    .into_iter()
+                    .filter(|successor| !back_edges.contains(&(*block_id, *successor)))
+                    .collect::<Vec<_>>();
+                common_post_dominator(&successors, &post_dominators)
+            };
+            post_dominators.insert(*block_id, post_dominator);
+        }
+
+        Ok(Self {
+            program,
+            phi_copies,
+            post_dominators,
+            loop_exits,
+            recorded_outputs,
+            num_recorded_results,
+            recorded_values,
+            variables,
+            gate_definitions: BTreeMap::new(),
+            body: String::new(),
+        })
+    }
+
+    /// Emits the blocks starting at `block_id` until `stop` is reached or the
+    /// program returns.
+    fn emit_region(
+        &mut self,
+        mut block_id: BlockId,
+        stop: Option<BlockId>,
+        depth: usize,
+    ) -> Result<(), Error> {
+        loop {
+            if Some(block_id) == stop {
+                return Ok(());
+            }
+            if let Some(exit) = self.loop_exits.get(&block_id).copied() {
+                self.emit_loop(block_id, exit, depth)?;
+                block_id = exit;
+                continue;
+            }
+            match self.emit_block(block_id, depth)? {
+                Instruction::Jump(target) => block_id = *target,
+                Instruction::Branch(cond, true_id, false_id) => {
+                    // Branches that never join again still have to stop where the
+                    // enclosing region does, e.g. at the header of a loop.
+                    let join = self.post_dominators[&block_id].or(stop);
+                    let cond = variable_name(cond.variable_id);
+                    if Some(*true_id) == join {
+                        self.write_line(depth, &format!("if (!{cond}) {{"));
+                        self.emit_region(*false_id, join, depth + 1)?;
+                    } else {
+                        self.write_line(depth, &format!("if ({cond}) {{"));
+                        self.emit_region(*true_id, join, depth + 1)?;
+                        if Some(*false_id) != join {
+                            self.write_line(depth, "} else {");
+                            self.emit_region(*false_id, join, depth + 1)?;
+                        }
+                    }
+                    self.write_line(depth, "}");
+                    match join {
+                        Some(join) => block_id = join,
+                        None => return Ok(()),
+                    }
+                }
+                Instruction::Return => return Ok(()),
+                _ => panic!("block should end with a terminator"),
+            }
+        }
+    }
+
+    /// Emits the loop with the given header as a `while` statement. The
+    /// header computes the loop condition, so its instructions are emitted
+    /// both before the loop and at the end of each iteration.
+    fn emit_loop(&mut self, header: BlockId, exit: BlockId, depth: usize) -> Result<(), Error> {
+        let Instruction::Branch(cond, true_id, false_id) = self.emit_block(header, depth)? else {
+            panic!("loop header should end with a branch");
+        };
+        let cond = variable_name(cond.variable_id);
+        let (body, cond) = if *false_id == exit {
+            (*true_id, cond)
+        } else {
+            (*false_id, format!("!{cond}"))
+        };
+        self.write_line(depth, &format!("while ({cond}) {{"));
+        self.emit_region(body, Some(header), depth + 1)?;
+        self.emit_block(header, depth + 1)?;
+        self.write_line(depth, "}");
+        Ok(())
+    }
+
+    /// Emits the instructions of a block along with the phi copies it has to
+    /// perform, and returns its terminator.
+    fn emit_block(&mut self, block_id: BlockId, depth: usize) -> Result<&'a Instruction, Error> {
+        let program = self.program;
+        let (terminator, instrs) = program
+            .get_block(block_id)
+            .0
+            .split_last()
+            .expect("block should have at least one instruction");
+        for (index, instr) in instrs.iter().enumerate() {
+            self.emit_instruction((block_id, index), instr, depth)?;
+        }
+        let copies = self
+            .phi_copies
+            .get(&block_id)
+            .into_iter()
+            .flatten()
+            .map(|(variable_id, operand)| {
+                Ok(format!(
+                    "{} = {};",
+                    variable_name(*variable_id),
+                    operand_to_qasm3(operand)?
+                ))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        for copy in copies {
+            self.write_line(depth, &copy);
+        }
+        Ok(terminator)
+    }
+
+    fn emit_instruction(
+        &mut self,
+        position: (BlockId, usize),
+        instr: &Instruction,
+        depth: usize,
+    ) -> Result<(), Error> {
+        let line = match instr {
+            Instruction::Call(callable_id, args, output) => {
+                match self.call_to_qasm3(position, *callable_id, args, *output)? {
+                    Some(line) => line,
+                    None => return Ok(()),
+                }
+            }
+            Instruction::Add(lhs, rhs, variable) | Instruction::Fadd(lhs, rhs, variable) => {
+                binop_to_qasm3("+", lhs, rhs, *variable)?
+            }
+            Instruction::Sub(lhs, rhs, variable) | Instruction::Fsub(lhs, rhs, variable) => {
+                binop_to_qasm3("-", lhs, rhs, *variable)?
+            }
+            Instruction::Mul(lhs, rhs, variable) | Instruction::Fmul(lhs, rhs, variable) => {
+                binop_to_qasm3("*", lhs, rhs, *variable)?
+            }
+            Instruction::Sdiv(lhs, rhs, variable) | Instruction::Fdiv(lhs, rhs, variable) => {
+                binop_to_qasm3("/", lhs, rhs, *variable)?
+            }
+            Instruction::Srem(lhs, rhs, variable) => binop_to_qasm3("%", lhs, rhs, *variable)?,
+            Instruction::Shl(lhs, rhs, variable) => binop_to_qasm3("<<", lhs, rhs, *variable)?,
+            Instruction::Ashr(lhs, rhs, variable) => binop_to_qasm3(">>", lhs, rhs, *variable)?,
+            Instruction::Icmp(op, lhs, rhs, variable) => {
+                binop_to_qasm3(icmp_op(*op), lhs, rhs, *variable)?
+            }
+            Instruction::Fcmp(op, lhs, rhs, variable) => {
+                binop_to_qasm3(fcmp_op(*op), lhs, rhs, *variable)?
+            }
+            Instruction::LogicalAnd(lhs, rhs, variable) => {
+                binop_to_qasm3("&&", lhs, rhs, *variable)?
+            }
+            Instruction::LogicalOr(lhs, rhs, variable) => {
+                binop_to_qasm3("||", lhs, rhs, *variable)?
+            }
+            Instruction::BitwiseAnd(lhs, rhs, variable) => {
+                binop_to_qasm3("&", lhs, rhs, *variable)?
+            }
+            Instruction::BitwiseOr(lhs, rhs, variable) => binop_to_qasm3("|", lhs, rhs, *variable)?,
+            Instruction::BitwiseXor(lhs, rhs, variable) => {
+                binop_to_qasm3("^", lhs, rhs, *variable)?
+            }
+            Instruction::LogicalNot(value, variable) => {
+                format!(
+                    "{} = !{};",
+                    variable_name(variable.variable_id),
+                    operand_to_qasm3(value)?
+                )
+            }
+            Instruction::BitwiseNot(value, variable) => {
+                format!(
+                    "{} = ~{};",
+                    variable_name(variable.variable_id),
+                    operand_to_qasm3(value)?
+                )
+            }
+            Instruction::Sitofp(value, variable) => format!(
+                "{} = float({});",
+                variable_name(variable.variable_id),
+                operand_to_qasm3(value)?
+            ),
+            Instruction::Fptosi(value, variable) => format!(
+                "{} = int({});",
+                variable_name(variable.variable_id),
+                operand_to_qasm3(value)?
+            ),
+            // phi nodes are replaced by copies at the end of their predecessors
+            Instruction::Phi(..) => return Ok(()),
+            Instruction::Store(_, _) => unimplemented!("store should be removed by pass"),
+            Instruction::Jump(..) | Instruction::Branch(..) | Instruction::Return => {
+                panic!("terminators should only appear at the end of a block")
+            }
+        };
+        self.write_line(depth, &line);
+        Ok(())
+    }
+
+    fn call_to_qasm3(
+        &mut self,
+        position: (BlockId, usize),
+        callable_id: rir::CallableId,
+        args: &[Operand],
+        output: Option<rir::Variable>,
+    ) -> Result<Option<String>, Error> {
+        let callable = self.program.get_callable(callable_id);
+        if callable.call_type == rir::CallableType::OutputRecording {
+            // Array and tuple recording calls only describe the shape of the
+            // output, so they have nothing to copy.
+            let line = match self.recorded_outputs.get(&position) {
+                Some(RecordedOutput::Result(index)) => {
+                    format!("results[{index}] = {};", operand_to_qasm3(&args[0])?)
+                }
+                Some(RecordedOutput::Value(index)) => {
+                    format!("value_{index} = {};", operand_to_qasm3(&args[0])?)
+                }
+                None => return Ok(None),
+            };
+            return Ok(Some(line));
+        }
+        let line = match (callable.name.as_str(), args) {
+            ("__quantum__qis__m__body", [qubit, result]) => {
+                format!(
+                    "{} = measure {};",
+                    operand_to_qasm3(result)?,
+                    operand_to_qasm3(qubit)?
+                )
+            }
+            ("__quantum__qis__mresetz__body", [qubit, result]) => {
+                let qubit = operand_to_qasm3(qubit)?;
+                format!(
+                    "{} = measure {qubit};\nreset {qubit};",
+                    operand_to_qasm3(result)?
+                )
+            }
+            ("__quantum__qis__reset__body", [qubit]) => {
+                format!("reset {};", operand_to_qasm3(qubit)?)
+            }
+            ("__quantum__qis__read_result__body", [result]) => {
+                let output = output.expect("read result should have an output variable");
+                format!(
+                    "{} = bool({});",
+                    variable_name(output.variable_id),
+                    operand_to_qasm3(result)?
+                )
+            }
+            (name, args) => {
+                let Some((gate, definition)) = gate_for_callable(name) else {
+                    return Err(Error::UnsupportedCallable(name.to_string()));
+                };
+                if let Some(definition) = definition {
+                    self.gate_definitions.insert(gate, definition);
+                }
+                let (qubits, params): (Vec<_>, Vec<_>) = args
+                    .iter()
+                    .partition(|arg| arg.get_type() == rir::Ty::Qubit);
+                let qubits = qubits
+                    .into_iter()
+                    .map(operand_to_qasm3)
+                    .collect::<Result<Vec<_>, _>>()?
+                    .join(", ");
+                if params.is_empty() {
+                    format!("{gate} {qubits};")
+                } else {
+                    let params = params
+                        .into_iter()
+                        .map(operand_to_qasm3)
+                        .collect::<Result<Vec<_>, _>>()?
+                        .join(", ");
+                    format!("{gate}({params}) {qubits};")
+                }
+            }
+        };
+        Ok(Some(line))
+    }
+
+    fn write_line(&mut self, depth: usize, line: &str) {
+        for line in line.lines() {
+            for _ in 0..depth {
+                self.body.push_str("    ");
+            }
+            self.body.push_str(line);
+            self.body.push('\n');
+        }
+    }
+}
+
+/// Returns the blocks reachable from `entry` in topological order, ignoring
+/// back edges, along with the back edges of the control flow graph.
+fn topological_order(
+    program: &rir::Program,
+    entry: BlockId,
+) -> (Vec<BlockId>, FxHashSet<(BlockId, BlockId)>) {
+    let mut post_order = Vec::new();
+    let mut back_edges = FxHashSet::default();
+    let mut visited = FxHashSet::default();
+    let mut on_stack = FxHashSet::default();
+    // Each stack entry is a block and the index of the next successor to visit.
+    let mut stack = vec![(entry, 0)];
+    visited.insert(entry);
+    on_stack.insert(entry);
+    while let Some((block_id, next)) = stack.pop() {
+        let successors = get_block_successors(program.get_block(block_id));
+        if let Some(successor) = successors.get(next) {
+            stack.push((block_id, next + 1));
+            if on_stack.contains(successor) {
+                back_edges.insert((block_id, *successor));
+            } else if visited.insert(*successor) {
+                on_stack.insert(*successor);
+                stack.push((*successor, 0));
+            }
+        } else {
+            on_stack.remove(&block_id);
+            post_order.push(block_id);
+        }
+    }
+    post_order.reverse();
+    (post_order, back_edges)
+}
+
+/// Finds the exit block of each loop, keyed by the loop header. Every loop
+/// must be entered through its header, and the header's branch must be the
+/// only way out of the loop, so that it maps onto a `while` statement.
+fn find_loop_exits(
+    program: &rir::Program,
+    back_edges: &FxHashSet<(BlockId, BlockId)>,
+) -> Result<FxHashMap<BlockId, BlockId>, Error> {
+    let preds = build_predecessors_map(program);
+    let mut latches: FxHashMap<BlockId, Vec<BlockId>> = FxHashMap::default();
+    for (latch, header) in back_edges {
+        latches.entry(*header).or_default().push(*latch);
+    }
+
+    let mut loop_exits = FxHashMap::default();
+    for (header, latches) in latches {
+        // The loop body is every block that reaches a latch without going
+        // through the header.
+        let mut body = FxHashSet::default();
+        body.insert(header);
+        let mut worklist = latches;
+        while let Some(block_id) = worklist.pop() {
+            if body.insert(block_id) {
+                worklist.extend(preds.get(block_id).into_iter().flatten().copied());
+            }
+        }
+
+        let Instruction::Branch(_, true_id, false_id) = program
+            .get_block(header)
+            .0
+            .last()
+            .expect("block should have at least one instruction")
+        else {
+            return Err(Error::UnsupportedLoop);
+        };
+        let exit = match (body.contains(true_id), body.contains(false_id)) {
+            (true, false) => *false_id,
+            (false, true) => *true_id,
+            _ => return Err(Error::UnsupportedLoop),
+        };
+        for block_id in body.iter().filter(|block_id| **block_id != header) {
+            let has_outside_pred = preds
+                .get(*block_id)
+                .into_iter()
+                .flatten()
+                .any(|pred| !body.contains(pred));
+            let has_outside_succ = get_block_successors(program.get_block(*block_id))
+                .iter()
+                .any(|succ| !body.contains(succ));
+            if has_outside_pred || has_outside_succ {
+                return Err(Error::UnsupportedLoop);
+            }
+        }
+        loop_exits.insert(header, exit);
+    }
+    Ok(loop_exits)
+}
+
+/// Finds the first block that every path from the given successors passes
+/// through, using the already computed post-dominators of the successors.
+fn common_post_dominator(
+    successors: &[BlockId],
+    post_dominators: &FxHashMap<BlockId, Option<BlockId>>,
+) -> Option<BlockId> {
+    let chain = |block_id: BlockId| {
+        let mut chain = vec![block_id];
+        let mut current = block_id;
+        while let Some(next) = post_dominators[&current] {
+            chain.push(next);
+            current = next;
+        }
+        chain
+    };
+    let (first, rest) = successors.split_first()?;
+    let rest = rest.iter().map(|s| chain(*s)).collect::<Vec<_>>();
+    chain(*first)
+        .into_iter()
+        .find(|block_id| rest.iter().all(|chain| chain.contains(block_id)))
+}
+
+const RXX_DEFINITION: &str =
+    "gate rxx(theta) a, b { h a; h b; cx a, b; rz(theta) b; cx a, b; h a; h b; }";
+const RYY_DEFINITION: &str = concat!(
+    "gate ryy(theta) a, b { rx(pi / 2) a; rx(pi / 2) b; cx a, b; rz(theta) b; cx a, b; ",
+    "rx(-pi / 2) a; rx(-pi / 2) b; }"
+);
+const RZZ_DEFINITION: &str = "gate rzz(theta) a, b { cx a, b; rz(theta) b; cx a, b; }";
+
+/// Maps a QIR intrinsic to its `OpenQASM` 3 gate, along with a definition for
+/// gates that are not part of `stdgates.inc`.
+fn gate_for_callable(name: &str) -> Option<(&'static str, Option<&'static str>)> {
+    let gate = match name {
+        "__quantum__qis__ccx__body" => ("ccx", None),
+        "__quantum__qis__cx__body" => ("cx", None),
+        "__quantum__qis__cy__body" => ("cy", None),
+        "__quantum__qis__cz__body" => ("cz", None),
+        "__quantum__qis__h__body" => ("h", None),
+        "__quantum__qis__rx__body" => ("rx", None),
+        "__quantum__qis__rxx__body" => ("rxx", Some(RXX_DEFINITION)),
+        "__quantum__qis__ry__body" => ("ry", None),
+        "__quantum__qis__ryy__body" => ("ryy", Some(RYY_DEFINITION)),
+        "__quantum__qis__rz__body" => ("rz", None),
+        "__quantum__qis__rzz__body" => ("rzz", Some(RZZ_DEFINITION)),
+        "__quantum__qis__s__body" => ("s", None),
+        "__quantum__qis__s__adj" => ("sdg", None),
+        "__quantum__qis__swap__body" => ("swap", None),
+        "__quantum__qis__t__body" => ("t", None),
+        "__quantum__qis__t__adj" => ("tdg", None),
+        "__quantum__qis__x__body" => ("x", None),
+        "__quantum__qis__y__body" => ("y", None),
+        "__quantum__qis__z__body" => ("z", None),
+        _ => return None,
+    };
+    Some(gate)
+}
+
+fn assigned_variable(instr: &Instruction) -> Option<rir::Variable> {
+    match instr {
+        Instruction::Call(_, _, variable) => *variable,
+        Instruction::Store(_, variable)
+        | Instruction::Add(_, _, variable)
+        | Instruction::Sub(_, _, variable)
+        | Instruction::Mul(_, _, variable)
+        | Instruction::Sdiv(_, _, variable)
+        | Instruction::Srem(_, _, variable)
+        | Instruction::Shl(_, _, variable)
+        | Instruction::Ashr(_, _, variable)
+        | Instruction::Icmp(_, _, _, variable)
+        | Instruction::Fadd(_, _, variable)
+        | Instruction::Fsub(_, _, variable)
+        | Instruction::Fmul(_, _, variable)
+        | Instruction::Fdiv(_, _, variable)
+        | Instruction::Fcmp(_, _, _, variable)
+        | Instruction::Sitofp(_, variable)
+        | Instruction::Fptosi(_, variable)
+        | Instruction::LogicalNot(_, variable)
+        | Instruction::LogicalAnd(_, _, variable)
+        | Instruction::LogicalOr(_, _, variable)
+        | Instruction::BitwiseNot(_, variable)
+        | Instruction::BitwiseAnd(_, _, variable)
+        | Instruction::BitwiseOr(_, _, variable)
+        | Instruction::BitwiseXor(_, _, variable)
+        | Instruction::Phi(_, variable) => Some(*variable),
+        Instruction::Jump(_) | Instruction::Branch(..) | Instruction::Return => None,
+    }
+}
+
+fn binop_to_qasm3(
+    op: &str,
+    lhs: &Operand,
+    rhs: &Operand,
+    variable: rir::Variable,
+) -> Result<String, Error> {
+    Ok(format!(
+        "{} = {} {op} {};",
+        variable_name(variable.variable_id),
+        operand_to_qasm3(lhs)?,
+        operand_to_qasm3(rhs)?
+    ))
+}
+
+fn icmp_op(op: ConditionCode) -> &'static str {
+    match op {
+        ConditionCode::Eq => "==",
+        ConditionCode::Ne => "!=",
+        ConditionCode::Sgt => ">",
+        ConditionCode::Sge => ">=",
+        ConditionCode::Slt => "<",
+        ConditionCode::Sle => "<=",
+    }
+}
+
+fn fcmp_op(op: FcmpConditionCode) -> &'static str {
+    match op {
+        FcmpConditionCode::Oeq => "==",
+        FcmpConditionCode::One => "!=",
+        FcmpConditionCode::Ogt => ">",
+        FcmpConditionCode::Oge => ">=",
+        FcmpConditionCode::Olt => "<",
+        FcmpConditionCode::Ole => "<=",
+    }
+}
+
+fn variable_name(variable_id: VariableId) -> String {
+    format!("var_{}", variable_id.0)
+}
+
+fn type_to_qasm3(ty: rir::Ty) -> &'static str {
+    match ty {
+        rir::Ty::Boolean => "bool",
+        rir::Ty::Double => "float",
+        rir::Ty::Integer => "int",
+        rir::Ty::Result => "bit",
+        rir::Ty::Qubit | rir::Ty::Pointer => panic!("unsupported variable type {ty}"),
+    }
+}
+
+fn operand_to_qasm3(operand: &Operand) -> Result<String, Error> {
+    let operand = match operand {
+        Operand::Literal(lit) => match lit {
+            rir::Literal::Bool(b) => format!("{b}"),
+            rir::Literal::Double(d) if !d.is_finite() => {
+                return Err(Error::NonFiniteDouble(d.to_string()));
+            }
+            rir::Literal::Double(d) => {
+                if (d.floor() - d.ceil()).abs() < f64::EPSILON {
+                    // The value is a whole number, which requires at least one decimal point
+                    // to differentiate it from an integer value.
+                    format!("{d:.1}")
+                } else {
+                    format!("{d}")
+                }
+            }
+            rir::Literal::Integer(i) => format!("{i}"),
+            rir::Literal::Qubit(q) => format!("q[{q}]"),
+            rir::Literal::Result(r) => format!("c[{r}]"),
+            rir::Literal::Pointer => panic!("pointers should only be used for output recording"),
+        },
+        Operand::Variable(var) => variable_name(var.variable_id),
+    };
+    Ok(operand)
+}
@@ -0,0 +1,371 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::program_to_qasm3;
+use expect_test::expect;
+use qsc_rir::builder;
+use qsc_rir::rir::{
+    Block, BlockId, CallableId, ConditionCode, Instruction, Literal, Operand, Program, Ty,
+    Variable, VariableId,
+};
+
+fn measure_into_var_0(program: &mut Program) -> Vec<Instruction> {
+    program.callables.insert(CallableId(1), builder::m_decl());
+    program
+        .callables
+        .insert(CallableId(2), builder::read_result_decl());
+    program.num_qubits = 1;
+    program.num_results = 1;
+    vec![
+        Instruction::Call(
+            CallableId(1),
+            vec![
+                Operand::Literal(Literal::Qubit(0)),
+                Operand::Literal(Literal::Result(0)),
+            ],
+            None,
+        ),
+        Instruction::Call(
+            CallableId(2),
+            vec![Operand::Literal(Literal::Result(0))],
+            Some(Variable {
+                variable_id: VariableId(0),
+                ty: Ty::Boolean,
+            }),
+        ),
+    ]
+}
+
+#[test]
+fn bell_program() {
+    let program = builder::bell_program();
+    expect![[r#"
+        OPENQASM 3.0;
+        include "stdgates.inc";
+        qubit[2] q;
+        bit[2] c;
+        output bit[2] results;
+        h q[0];
+        cx q[0], q[1];
+        c[0] = measure q[0];
+        c[1] = measure q[1];
+        results[0] = c[0];
+        results[1] = c[1];
+    "#]]
+    .assert_eq(&program_to_qasm3(&program).expect("program should be converted"));
+}
+
+#[test]
+fn teleport_program() {
+    let program = builder::teleport_program();
+    expect![[r#"
+        OPENQASM 3.0;
+        include "stdgates.inc";
+        qubit[3] q;
+        bit[3] c;
+        output bit[1] results;
+        bool var_0;
+        bool var_1;
+        x q[0];
+        h q[2];
+        cx q[2], q[1];
+        cx q[0], q[2];
+        h q[0];
+        c[0] = measure q[0];
+        reset q[0];
+        var_0 = bool(c[0]);
+        if (var_0) {
+            z q[1];
+        }
+        c[1] = measure q[2];
+        reset q[2];
+        var_1 = bool(c[1]);
+        if (var_1) {
+            x q[1];
+        }
+        c[2] = measure q[1];
+        reset q[1];
+        results[0] = c[2];
+    "#]]
+    .assert_eq(&program_to_qasm3(&program).expect("program should be converted"));
+}
+
+#[test]
+fn rotation_angle_is_emitted_as_gate_parameter() {
+    let mut program = builder::new_program();
+    program.callables.insert(CallableId(1), builder::rx_decl());
+    program.num_qubits = 1;
+    program.blocks.insert(
+        BlockId(0),
+        Block(vec![
+            Instruction::Call(
+                CallableId(1),
+                vec![
+                    Operand::Literal(Literal::Double(0.5)),
+                    Operand::Literal(Literal::Qubit(0)),
+                ],
+                None,
+            ),
+            Instruction::Call(
+                CallableId(1),
+                vec![
+                    Operand::Literal(Literal::Double(2.0)),
+                    Operand::Literal(Literal::Qubit(0)),
+                ],
+                None,
+            ),
+            Instruction::Return,
+        ]),
+    );
+    expect![[r#"
+        OPENQASM 3.0;
+        include "stdgates.inc";
+        qubit[1] q;
+        rx(0.5) q[0];
+        rx(2.0) q[0];
+    "#]]
+    .assert_eq(&program_to_qasm3(&program).expect("program should be converted"));
+}
+
+#[test]
+fn phi_nodes_become_assignments_in_each_branch() {
+    let mut program = builder::new_program();
+    program
+        .callables
+        .insert(CallableId(3), builder::int_record_decl());
+    let mut instrs = measure_into_var_0(&mut program);
+    instrs.push(Instruction::Branch(
+        Variable {
+            variable_id: VariableId(0),
+            ty: Ty::Boolean,
+        },
+        BlockId(1),
+        BlockId(2),
+    ));
+    program.blocks.insert(BlockId(0), Block(instrs));
+    program
+        .blocks
+        .insert(BlockId(1), Block(vec![Instruction::Jump(BlockId(3))]));
+    program
+        .blocks
+        .insert(BlockId(2), Block(vec![Instruction::Jump(BlockId(3))]));
+    let var_1 = Variable {
+        variable_id: VariableId(1),
+        ty: Ty::Integer,
+    };
+    program.blocks.insert(
+        BlockId(3),
+        Block(vec![
+            Instruction::Phi(
+                vec![
+                    (Operand::Literal(Literal::Integer(1)), BlockId(1)),
+                    (Operand::Literal(Literal::Integer(2)), BlockId(2)),
+                ],
+                var_1,
+            ),
+            Instruction::Call(
+                CallableId(3),
+                vec![Operand::Variable(var_1), Operand::Literal(Literal::Pointer)],
+                None,
+            ),
+            Instruction::Return,
+        ]),
+    );
+    expect![[r#"
+        OPENQASM 3.0;
+        include "stdgates.inc";
+        qubit[1] q;
+        bit[1] c;
+        output int value_0;
+        bool var_0;
+        int var_1;
+        c[0] = measure q[0];
+        var_0 = bool(c[0]);
+        if (var_0) {
+            var_1 = 1;
+        } else {
+            var_1 = 2;
+        }
+        value_0 = var_1;
+    "#]]
+    .assert_eq(&program_to_qasm3(&program).expect("program should be converted"));
+}
+
+#[test]
+fn static_loop_becomes_while_loop() {
+    let mut program = builder::new_program();
+    program.callables.insert(CallableId(1), builder::x_decl());
+    program.num_qubits = 1;
+    let counter = Variable {
+        variable_id: VariableId(0),
+        ty: Ty::Integer,
+    };
+    let cond = Variable {
+        variable_id: VariableId(1),
+        ty: Ty::Boolean,
+    };
+    let next = Variable {
+        variable_id: VariableId(2),
+        ty: Ty::Integer,
+    };
+    program
+        .blocks
+        .insert(BlockId(0), Block(vec![Instruction::Jump(BlockId(1))]));
+    program.blocks.insert(
+        BlockId(1),
+        Block(vec![
+            Instruction::Phi(
+                vec![
+                    (Operand::Literal(Literal::Integer(0)), BlockId(0)),
+                    (Operand::Variable(next), BlockId(2)),
+                ],
+                counter,
+            ),
+            Instruction::Icmp(
+                ConditionCode::Slt,
+                Operand::Variable(counter),
+                Operand::Literal(Literal::Integer(3)),
+                cond,
+            ),
+            Instruction::Branch(cond, BlockId(2), BlockId(3)),
+        ]),
+    );
+    program.blocks.insert(
+        BlockId(2),
+        Block(vec![
+            Instruction::Call(
+                CallableId(1),
+                vec![Operand::Literal(Literal::Qubit(0))],
+                None,
+            ),
+            Instruction::Add(
+                Operand::Variable(counter),
+                Operand::Literal(Literal::Integer(1)),
+                next,
+            ),
+            Instruction::Jump(BlockId(1)),
+        ]),
+    );
+    program
+        .blocks
+        .insert(BlockId(3), Block(vec![Instruction::Return]));
+    expect![[r#"
+        OPENQASM 3.0;
+        include "stdgates.inc";
+        qubit[1] q;
+        int var_0;
+        bool var_1;
+        int var_2;
+        var_0 = 0;
+        var_1 = var_0 < 3;
+        while (var_1) {
+            x q[0];
+            var_2 = var_0 + 1;
+            var_0 = var_2;
+            var_1 = var_0 < 3;
+        }
+    "#]]
+    .assert_eq(&program_to_qasm3(&program).expect("program should be converted"));
+}
+
+#[test]
+fn loop_on_measurement_result_becomes_while_loop() {
+    let mut program = builder::new_program();
+    let mut instrs = measure_into_var_0(&mut program);
+    instrs.push(Instruction::Branch(
+        Variable {
+            variable_id: VariableId(0),
+            ty: Ty::Boolean,
+        },
+        BlockId(0),
+        BlockId(1),
+    ));
+    program.blocks.insert(BlockId(0), Block(instrs));
+    program
+        .blocks
+        .insert(BlockId(1), Block(vec![Instruction::Return]));
+    expect![[r#"
+        OPENQASM 3.0;
+        include "stdgates.inc";
+        qubit[1] q;
+        bit[1] c;
+        bool var_0;
+        c[0] = measure q[0];
+        var_0 = bool(c[0]);
+        while (var_0) {
+            c[0] = measure q[0];
+            var_0 = bool(c[0]);
+        }
+    "#]]
+    .assert_eq(&program_to_qasm3(&program).expect("program should be converted"));
+}
+
+#[test]
+fn loops_exited_from_their_body_are_not_supported() {
+    let mut program = builder::new_program();
+    let var_0 = Variable {
+        variable_id: VariableId(0),
+        ty: Ty::Boolean,
+    };
+    let mut instrs = measure_into_var_0(&mut program);
+    instrs.push(Instruction::Branch(var_0, BlockId(2), BlockId(3)));
+    program
+        .blocks
+        .insert(BlockId(0), Block(vec![Instruction::Jump(BlockId(1))]));
+    program.blocks.insert(BlockId(1), Block(instrs));
+    program.blocks.insert(
+        BlockId(2),
+        Block(vec![Instruction::Branch(var_0, BlockId(1), BlockId(3))]),
+    );
+    program
+        .blocks
+        .insert(BlockId(3), Block(vec![Instruction::Return]));
+    let err = program_to_qasm3(&program).expect_err("program should fail");
+    expect!["UnsupportedLoop"].assert_eq(&format!("{err:?}"));
+}
+
+#[test]
+fn unknown_callables_are_not_supported() {
+    let mut program = builder::new_program();
+    let mut decl = builder::x_decl();
+    decl.name = "__quantum__qis__custom__body".to_string();
+    program.callables.insert(CallableId(1), decl);
+    program.num_qubits = 1;
+    program.blocks.insert(
+        BlockId(0),
+        Block(vec![
+            Instruction::Call(
+                CallableId(1),
+                vec![Operand::Literal(Literal::Qubit(0))],
+                None,
+            ),
+            Instruction::Return,
+        ]),
+    );
+    let err = program_to_qasm3(&program).expect_err("program should fail");
+    expect![[r#"UnsupportedCallable("__quantum__qis__custom__body")"#]]
+        .assert_eq(&format!("{err:?}"));
+}
+
+#[test]
+fn non_finite_doubles_are_not_supported() {
+    let mut program = builder::new_program();
+    program.callables.insert(CallableId(1), builder::rx_decl());
+    program.num_qubits = 1;
+    program.blocks.insert(
+        BlockId(0),
+        Block(vec![
+            Instruction::Call(
+                CallableId(1),
+                vec![
+                    Operand::Literal(Literal::Double(f64::NAN)),
+                    Operand::Literal(Literal::Qubit(0)),
+                ],
+                None,
+            ),
+            Instruction::Return,
+        ]),
+    );
+    let err = program_to_qasm3(&program).expect_err("program should fail");
+    expect![[r#"NonFiniteDouble("NaN")"#]].assert_eq(&format!("{err:?}"));
+}
@@ -13,7 +13,7 @@ use qsc_partial_eval::{partially_evaluate, ProgramEntry};
 use qsc_rca::PackageStoreComputeProperties;
 use qsc_rir::{
     passes::check_and_transform,
-    rir::{self, ConditionCode},
+    rir::{self, ConditionCode, FcmpConditionCode},
     utils::get_all_block_successors,
 };
 
@@ -43,9 +43,39 @@ pub fn fir_to_qir(
     compute_properties: Option<PackageStoreComputeProperties>,
     entry: &ProgramEntry,
 ) -> Result<String, qsc_partial_eval::Error> {
+    let program = fir_to_rir_program(fir_store, capabilities, compute_properties, entry)?;
+    Ok(rir_to_qir(&program))
+}
+
+/// converts the given FIR to RIR, as it is after the checks and transformations
+/// that are applied before QIR generation.
+pub fn fir_to_rir(
+    fir_store: &qsc_fir::fir::PackageStore,
+    capabilities: TargetCapabilityFlags,
+    compute_properties: Option<PackageStoreComputeProperties>,
+    entry: &ProgramEntry,
+) -> Result<String, qsc_partial_eval::Error> {
+    let program = fir_to_rir_program(fir_store, capabilities, compute_properties, entry)?;
+    Ok(program.to_string())
+}
+
+/// Partially evaluates the entry expression to RIR, and applies the checks and
+/// transformations that code generation expects.
+pub fn fir_to_rir_program(
+    fir_store: &qsc_fir::fir::PackageStore,
+    capabilities: TargetCapabilityFlags,
+    compute_properties: Option<PackageStoreComputeProperties>,
+    entry: &ProgramEntry,
+) -> Result<rir::Program, qsc_partial_eval::Error> {
     let mut program = get_rir_from_compilation(fir_store, compute_properties, entry, capabilities)?;
     check_and_transform(&mut program);
-    Ok(ToQir::<String>::to_qir(&program, &program))
+    Ok(program)
+}
+
+/// Converts a program returned by [`fir_to_rir_program`] to QIR.
+#[must_use]
+pub fn rir_to_qir(program: &rir::Program) -> String {
+    ToQir::<String>::to_qir(program, program)
 }
 
 fn get_rir_from_compilation(
@@ -149,6 +179,19 @@ impl ToQir<String> for rir::ConditionCode {
     }
 }
 
+impl ToQir<String> for rir::FcmpConditionCode {
+    fn to_qir(&self, _program: &rir::Program) -> String {
+        match self {
+            rir::FcmpConditionCode::Oeq => "oeq".to_string(),
+            rir::FcmpConditionCode::One => "one".to_string(),
+            rir::FcmpConditionCode::Ogt => "ogt".to_string(),
+            rir::FcmpConditionCode::Oge => "oge".to_string(),
+            rir::FcmpConditionCode::Olt => "olt".to_string(),
+            rir::FcmpConditionCode::Ole => "ole".to_string(),
+        }
+    }
+}
+
 impl ToQir<String> for rir::Instruction {
     fn to_qir(&self, program: &rir::Program) -> String {
         match self {
@@ -181,6 +224,24 @@ impl ToQir<String> for rir::Instruction {
             rir::Instruction::Call(call_id, args, output) => {
                 call_to_qir(args, *call_id, *output, program)
             }
+            rir::Instruction::Fadd(lhs, rhs, variable) => {
+                fbinop_to_qir("fadd", lhs, rhs, *variable, program)
+            }
+            rir::Instruction::Fcmp(op, lhs, rhs, variable) => {
+                fcmp_to_qir(*op, lhs, rhs, *variable, program)
+            }
+            rir::Instruction::Fdiv(lhs, rhs, variable) => {
+                fbinop_to_qir("fdiv", lhs, rhs, *variable, program)
+            }
+            rir::Instruction::Fmul(lhs, rhs, variable) => {
+                fbinop_to_qir("fmul", lhs, rhs, *variable, program)
+            }
+            rir::Instruction::Fptosi(value, variable) => {
+                cast_to_qir("fptosi", "double", "i64", value, *variable, program)
+            }
+            rir::Instruction::Fsub(lhs, rhs, variable) => {
+                fbinop_to_qir("fsub", lhs, rhs, *variable, program)
+            }
             rir::Instruction::LogicalAnd(lhs, rhs, variable) => {
                 logical_binop_to_qir("and", lhs, rhs, *variable, program)
             }
@@ -207,6 +268,9 @@ impl ToQir<String> for rir::Instruction {
             rir::Instruction::Shl(lhs, rhs, variable) => {
                 binop_to_qir("shl", lhs, rhs, *variable, program)
             }
+            rir::Instruction::Sitofp(value, variable) => {
+                cast_to_qir("sitofp", "i64", "double", value, *variable, program)
+            }
             rir::Instruction::Srem(lhs, rhs, variable) => {
                 binop_to_qir("srem", lhs, rhs, *variable, program)
             }
@@ -339,6 +403,82 @@ fn icmp_to_qir(
     )
 }
 
+fn fcmp_to_qir(
+    op: FcmpConditionCode,
+    lhs: &rir::Operand,
+    rhs: &rir::Operand,
+    variable: rir::Variable,
+    program: &rir::Program,
+) -> String {
+    let lhs_ty = get_value_ty(lhs);
+    let rhs_ty = get_value_ty(rhs);
+    let var_ty = get_variable_ty(variable);
+    assert_eq!(
+        lhs_ty, rhs_ty,
+        "mismatched input types ({lhs_ty}, {rhs_ty}) for fcmp {op}"
+    );
+    assert_eq!(lhs_ty, "double", "unsupported input type {lhs_ty} for fcmp");
+    assert_eq!(var_ty, "i1", "unsupported output type {var_ty} for fcmp");
+    format!(
+        "  {} = fcmp {} {lhs_ty} {}, {}",
+        ToQir::<String>::to_qir(&variable.variable_id, program),
+        ToQir::<String>::to_qir(&op, program),
+        get_value_as_str(lhs, program),
+        get_value_as_str(rhs, program)
+    )
+}
+
+fn fbinop_to_qir(
+    op: &str,
+    lhs: &rir::Operand,
+    rhs: &rir::Operand,
+    variable: rir::Variable,
+    program: &rir::Program,
+) -> String {
+    let lhs_ty = get_value_ty(lhs);
+    let rhs_ty = get_value_ty(rhs);
+    let var_ty = get_variable_ty(variable);
+    assert_eq!(
+        lhs_ty, rhs_ty,
+        "mismatched input types ({lhs_ty}, {rhs_ty}) for {op}"
+    );
+    assert_eq!(
+        lhs_ty, var_ty,
+        "mismatched input/output types ({lhs_ty}, {var_ty}) for {op}"
+    );
+    assert_eq!(var_ty, "double", "unsupported type {var_ty} for {op}");
+
+    format!(
+        "  {} = {op} {var_ty} {}, {}",
+        ToQir::<String>::to_qir(&variable.variable_id, program),
+        get_value_as_str(lhs, program),
+        get_value_as_str(rhs, program)
+    )
+}
+
+fn cast_to_qir(
+    op: &str,
+    from_ty: &str,
+    to_ty: &str,
+    value: &rir::Operand,
+    variable: rir::Variable,
+    program: &rir::Program,
+) -> String {
+    let value_ty = get_value_ty(value);
+    let var_ty = get_variable_ty(variable);
+    assert_eq!(
+        value_ty, from_ty,
+        "unsupported input type {value_ty} for {op}"
+    );
+    assert_eq!(var_ty, to_ty, "unsupported output type {var_ty} for {op}");
+
+    format!(
+        "  {} = {op} {value_ty} {} to {var_ty}",
+        ToQir::<String>::to_qir(&variable.variable_id, program),
+        get_value_as_str(value, program)
+    )
+}
+
 fn binop_to_qir(
     op: &str,
     lhs: &rir::Operand,
@@ -455,7 +595,7 @@ fn get_value_ty(lhs: &rir::Operand) -> &str {
         rir::Operand::Literal(lit) => match lit {
             rir::Literal::Integer(_) => "i64",
             rir::Literal::Bool(_) => "i1",
-            rir::Literal::Double(_) => "f64",
+            rir::Literal::Double(_) => "double",
             rir::Literal::Qubit(_) => "%Qubit*",
             rir::Literal::Result(_) => "%Result*",
             rir::Literal::Pointer => "i8*",
@@ -468,7 +608,7 @@ fn get_variable_ty(variable: rir::Variable) -> &'static str {
     match variable.ty {
         rir::Ty::Integer => "i64",
         rir::Ty::Boolean => "i1",
-        rir::Ty::Double => "f64",
+        rir::Ty::Double => "double",
         rir::Ty::Qubit => "%Qubit*",
         rir::Ty::Result => "%Result*",
         rir::Ty::Pointer => "i8*",
@@ -2,10 +2,11 @@
 // Licensed under the MIT License.
 
 use crate::qir::ToQir;
+use expect_test::expect;
 use qsc_rir::rir;
 
 #[test]
-#[should_panic(expected = "unsupported type f64 for add")]
+#[should_panic(expected = "unsupported type double for add")]
 fn add_double_literals() {
     let inst = rir::Instruction::Add(
         rir::Operand::Literal(rir::Literal::Double(core::f64::consts::PI)),
@@ -19,7 +20,7 @@ fn add_double_literals() {
 }
 
 #[test]
-#[should_panic(expected = "unsupported type f64 for ashr")]
+#[should_panic(expected = "unsupported type double for ashr")]
 fn ashr_double_literals() {
     let inst = rir::Instruction::Ashr(
         rir::Operand::Literal(rir::Literal::Double(core::f64::consts::PI)),
@@ -33,7 +34,7 @@ fn ashr_double_literals() {
 }
 
 #[test]
-#[should_panic(expected = "unsupported type f64 for and")]
+#[should_panic(expected = "unsupported type double for and")]
 fn bitwise_and_double_literals() {
     let inst = rir::Instruction::BitwiseAnd(
         rir::Operand::Literal(rir::Literal::Double(core::f64::consts::PI)),
@@ -47,7 +48,7 @@ fn bitwise_and_double_literals() {
 }
 
 #[test]
-#[should_panic(expected = "unsupported type f64 for not")]
+#[should_panic(expected = "unsupported type double for not")]
 fn bitwise_not_double_literals() {
     let inst = rir::Instruction::BitwiseNot(
         rir::Operand::Literal(rir::Literal::Double(core::f64::consts::PI)),
@@ -60,7 +61,7 @@ fn bitwise_not_double_literals() {
 }
 
 #[test]
-#[should_panic(expected = "unsupported type f64 for or")]
+#[should_panic(expected = "unsupported type double for or")]
 fn bitwise_or_double_literals() {
     let inst = rir::Instruction::BitwiseOr(
         rir::Operand::Literal(rir::Literal::Double(core::f64::consts::PI)),
@@ -74,7 +75,7 @@ fn bitwise_or_double_literals() {
 }
 
 #[test]
-#[should_panic(expected = "unsupported type f64 for xor")]
+#[should_panic(expected = "unsupported type double for xor")]
 fn bitwise_xor_double_literals() {
     let inst = rir::Instruction::BitwiseXor(
         rir::Operand::Literal(rir::Literal::Double(core::f64::consts::PI)),
@@ -86,3 +87,400 @@ fn bitwise_xor_double_literals() {
     );
     let _ = &inst.to_qir(&rir::Program::default());
 }
+
+#[test]
+fn fadd_double_literals() {
+    let inst = rir::Instruction::Fadd(
+        rir::Operand::Literal(rir::Literal::Double(core::f64::consts::PI)),
+        rir::Operand::Literal(rir::Literal::Double(2.0)),
+        rir::Variable {
+            variable_id: rir::VariableId(0),
+            ty: rir::Ty::Double,
+        },
+    );
+    expect!["  %var_0 = fadd double 3.141592653589793, 2.0"]
+        .assert_eq(&inst.to_qir(&rir::Program::default()));
+}
+
+#[test]
+fn fadd_double_variables() {
+    let inst = rir::Instruction::Fadd(
+        rir::Operand::Variable(rir::Variable {
+            variable_id: rir::VariableId(1),
+            ty: rir::Ty::Double,
+        }),
+        rir::Operand::Variable(rir::Variable {
+            variable_id: rir::VariableId(2),
+            ty: rir::Ty::Double,
+        }),
+        rir::Variable {
+            variable_id: rir::VariableId(0),
+            ty: rir::Ty::Double,
+        },
+    );
+    expect!["  %var_0 = fadd double %var_1, %var_2"]
+        .assert_eq(&inst.to_qir(&rir::Program::default()));
+}
+
+#[test]
+fn fdiv_double_literals() {
+    let inst = rir::Instruction::Fdiv(
+        rir::Operand::Literal(rir::Literal::Double(core::f64::consts::PI)),
+        rir::Operand::Literal(rir::Literal::Double(2.0)),
+        rir::Variable {
+            variable_id: rir::VariableId(0),
+            ty: rir::Ty::Double,
+        },
+    );
+    expect!["  %var_0 = fdiv double 3.141592653589793, 2.0"]
+        .assert_eq(&inst.to_qir(&rir::Program::default()));
+}
+
+#[test]
+fn fdiv_double_variables() {
+    let inst = rir::Instruction::Fdiv(
+        rir::Operand::Variable(rir::Variable {
+            variable_id: rir::VariableId(1),
+            ty: rir::Ty::Double,
+        }),
+        rir::Operand::Variable(rir::Variable {
+            variable_id: rir::VariableId(2),
+            ty: rir::Ty::Double,
+        }),
+        rir::Variable {
+            variable_id: rir::VariableId(0),
+            ty: rir::Ty::Double,
+        },
+    );
+    expect!["  %var_0 = fdiv double %var_1, %var_2"]
+        .assert_eq(&inst.to_qir(&rir::Program::default()));
+}
+
+#[test]
+fn fmul_double_literals() {
+    let inst = rir::Instruction::Fmul(
+        rir::Operand::Literal(rir::Literal::Double(core::f64::consts::PI)),
+        rir::Operand::Literal(rir::Literal::Double(2.0)),
+        rir::Variable {
+            variable_id: rir::VariableId(0),
+            ty: rir::Ty::Double,
+        },
+    );
+    expect!["  %var_0 = fmul double 3.141592653589793, 2.0"]
+        .assert_eq(&inst.to_qir(&rir::Program::default()));
+}
+
+#[test]
+fn fmul_double_variables() {
+    let inst = rir::Instruction::Fmul(
+        rir::Operand::Variable(rir::Variable {
+            variable_id: rir::VariableId(1),
+            ty: rir::Ty::Double,
+        }),
+        rir::Operand::Variable(rir::Variable {
+            variable_id: rir::VariableId(2),
+            ty: rir::Ty::Double,
+        }),
+        rir::Variable {
+            variable_id: rir::VariableId(0),
+            ty: rir::Ty::Double,
+        },
+    );
+    expect!["  %var_0 = fmul double %var_1, %var_2"]
+        .assert_eq(&inst.to_qir(&rir::Program::default()));
+}
+
+#[test]
+fn fsub_double_literals() {
+    let inst = rir::Instruction::Fsub(
+        rir::Operand::Literal(rir::Literal::Double(core::f64::consts::PI)),
+        rir::Operand::Literal(rir::Literal::Double(2.0)),
+        rir::Variable {
+            variable_id: rir::VariableId(0),
+            ty: rir::Ty::Double,
+        },
+    );
+    expect!["  %var_0 = fsub double 3.141592653589793, 2.0"]
+        .assert_eq(&inst.to_qir(&rir::Program::default()));
+}
+
+#[test]
+fn fsub_double_variables() {
+    let inst = rir::Instruction::Fsub(
+        rir::Operand::Variable(rir::Variable {
+            variable_id: rir::VariableId(1),
+            ty: rir::Ty::Double,
+        }),
+        rir::Operand::Variable(rir::Variable {
+            variable_id: rir::VariableId(2),
+            ty: rir::Ty::Double,
+        }),
+        rir::Variable {
+            variable_id: rir::VariableId(0),
+            ty: rir::Ty::Double,
+        },
+    );
+    expect!["  %var_0 = fsub double %var_1, %var_2"]
+        .assert_eq(&inst.to_qir(&rir::Program::default()));
+}
+
+#[test]
+fn fcmp_oeq_double_literals() {
+    let inst = rir::Instruction::Fcmp(
+        rir::FcmpConditionCode::Oeq,
+        rir::Operand::Literal(rir::Literal::Double(core::f64::consts::PI)),
+        rir::Operand::Literal(rir::Literal::Double(2.0)),
+        rir::Variable {
+            variable_id: rir::VariableId(0),
+            ty: rir::Ty::Boolean,
+        },
+    );
+    expect!["  %var_0 = fcmp oeq double 3.141592653589793, 2.0"]
+        .assert_eq(&inst.to_qir(&rir::Program::default()));
+}
+
+#[test]
+fn fcmp_oeq_double_variables() {
+    let inst = rir::Instruction::Fcmp(
+        rir::FcmpConditionCode::Oeq,
+        rir::Operand::Variable(rir::Variable {
+            variable_id: rir::VariableId(1),
+            ty: rir::Ty::Double,
+        }),
+        rir::Operand::Variable(rir::Variable {
+            variable_id: rir::VariableId(2),
+            ty: rir::Ty::Double,
+        }),
+        rir::Variable {
+            variable_id: rir::VariableId(0),
+            ty: rir::Ty::Boolean,
+        },
+    );
+    expect!["  %var_0 = fcmp oeq double %var_1, %var_2"]
+        .assert_eq(&inst.to_qir(&rir::Program::default()));
+}
+
+#[test]
+fn fcmp_one_double_literals() {
+    let inst = rir::Instruction::Fcmp(
+        rir::FcmpConditionCode::One,
+        rir::Operand::Literal(rir::Literal::Double(core::f64::consts::PI)),
+        rir::Operand::Literal(rir::Literal::Double(2.0)),
+        rir::Variable {
+            variable_id: rir::VariableId(0),
+            ty: rir::Ty::Boolean,
+        },
+    );
+    expect!["  %var_0 = fcmp one double 3.141592653589793, 2.0"]
+        .assert_eq(&inst.to_qir(&rir::Program::default()));
+}
+
+#[test]
+fn fcmp_one_double_variables() {
+    let inst = rir::Instruction::Fcmp(
+        rir::FcmpConditionCode::One,
+        rir::Operand::Variable(rir::Variable {
+            variable_id: rir::VariableId(1),
+            ty: rir::Ty::Double,
+        }),
+        rir::Operand::Variable(rir::Variable {
+            variable_id: rir::VariableId(2),
+            ty: rir::Ty::Double,
+        }),
+        rir::Variable {
+            variable_id: rir::VariableId(0),
+            ty: rir::Ty::Boolean,
+        },
+    );
+    expect!["  %var_0 = fcmp one double %var_1, %var_2"]
+        .assert_eq(&inst.to_qir(&rir::Program::default()));
+}
+
+#[test]
+fn fcmp_olt_double_literals() {
+    let inst = rir::Instruction::Fcmp(
+        rir::FcmpConditionCode::Olt,
+        rir::Operand::Literal(rir::Literal::Double(core::f64::consts::PI)),
+        rir::Operand::Literal(rir::Literal::Double(2.0)),
+        rir::Variable {
+            variable_id: rir::VariableId(0),
+            ty: rir::Ty::Boolean,
+        },
+    );
+    expect!["  %var_0 = fcmp olt double 3.141592653589793, 2.0"]
+        .assert_eq(&inst.to_qir(&rir::Program::default()));
+}
+
+#[test]
+fn fcmp_olt_double_variables() {
+    let inst = rir::Instruction::Fcmp(
+        rir::FcmpConditionCode::Olt,
+        rir::Operand::Variable(rir::Variable {
+            variable_id: rir::VariableId(1),
+            ty: rir::Ty::Double,
+        }),
+        rir::Operand::Variable(rir::Variable {
+            variable_id: rir::VariableId(2),
+            ty: rir::Ty::Double,
+        }),
+        rir::Variable {
+            variable_id: rir::VariableId(0),
+            ty: rir::Ty::Boolean,
+        },
+    );
+    expect!["  %var_0 = fcmp olt double %var_1, %var_2"]
+        .assert_eq(&inst.to_qir(&rir::Program::default()));
+}
+
+#[test]
+fn fcmp_ole_double_literals() {
+    let inst = rir::Instruction::Fcmp(
+        rir::FcmpConditionCode::Ole,
+        rir::Operand::Literal(rir::Literal::Double(core::f64::consts::PI)),
+        rir::Operand::Literal(rir::Literal::Double(2.0)),
+        rir::Variable {
+            variable_id: rir::VariableId(0),
+            ty: rir::Ty::Boolean,
+        },
+    );
+    expect!["  %var_0 = fcmp ole double 3.141592653589793, 2.0"]
+        .assert_eq(&inst.to_qir(&rir::Program::default()));
+}
+
+#[test]
+fn fcmp_ole_double_variables() {
+    let inst = rir::Instruction::Fcmp(
+        rir::FcmpConditionCode::Ole,
+        rir::Operand::Variable(rir::Variable {
+            variable_id: rir::VariableId(1),
+            ty: rir::Ty::Double,
+        }),
+        rir::Operand::Variable(rir::Variable {
+            variable_id: rir::VariableId(2),
+            ty: rir::Ty::Double,
+        }),
+        rir::Variable {
+            variable_id: rir::VariableId(0),
+            ty: rir::Ty::Boolean,
+        },
+    );
+    expect!["  %var_0 = fcmp ole double %var_1, %var_2"]
+        .assert_eq(&inst.to_qir(&rir::Program::default()));
+}
+
+#[test]
+fn fcmp_ogt_double_literals() {
+    let inst = rir::Instruction::Fcmp(
+        rir::FcmpConditionCode::Ogt,
+        rir::Operand::Literal(rir::Literal::Double(core::f64::consts::PI)),
+        rir::Operand::Literal(rir::Literal::Double(2.0)),
+        rir::Variable {
+            variable_id: rir::VariableId(0),
+            ty: rir::Ty::Boolean,
+        },
+    );
+    expect!["  %var_0 = fcmp ogt double 3.141592653589793, 2.0"]
+        .assert_eq(&inst.to_qir(&rir::Program::default()));
+}
+
+#[test]
+fn fcmp_ogt_double_variables() {
+    let inst = rir::Instruction::Fcmp(
+        rir::FcmpConditionCode::Ogt,
+        rir::Operand::Variable(rir::Variable {
+            variable_id: rir::VariableId(1),
+            ty: rir::Ty::Double,
+        }),
+        rir::Operand::Variable(rir::Variable {
+            variable_id: rir::VariableId(2),
+            ty: rir::Ty::Double,
+        }),
+        rir::Variable {
+            variable_id: rir::VariableId(0),
+            ty: rir::Ty::Boolean,
+        },
+    );
+    expect!["  %var_0 = fcmp ogt double %var_1, %var_2"]
+        .assert_eq(&inst.to_qir(&rir::Program::default()));
+}
+
+#[test]
+fn fcmp_oge_double_literals() {
+    let inst = rir::Instruction::Fcmp(
+        rir::FcmpConditionCode::Oge,
+        rir::Operand::Literal(rir::Literal::Double(core::f64::consts::PI)),
+        rir::Operand::Literal(rir::Literal::Double(2.0)),
+        rir::Variable {
+            variable_id: rir::VariableId(0),
+            ty: rir::Ty::Boolean,
+        },
+    );
+    expect!["  %var_0 = fcmp oge double 3.141592653589793, 2.0"]
+        .assert_eq(&inst.to_qir(&rir::Program::default()));
+}
+
+#[test]
+fn fcmp_oge_double_variables() {
+    let inst = rir::Instruction::Fcmp(
+        rir::FcmpConditionCode::Oge,
+        rir::Operand::Variable(rir::Variable {
+            variable_id: rir::VariableId(1),
+            ty: rir::Ty::Double,
+        }),
+        rir::Operand::Variable(rir::Variable {
+            variable_id: rir::VariableId(2),
+            ty: rir::Ty::Double,
+        }),
+        rir::Variable {
+            variable_id: rir::VariableId(0),
+            ty: rir::Ty::Boolean,
+        },
+    );
+    expect!["  %var_0 = fcmp oge double %var_1, %var_2"]
+        .assert_eq(&inst.to_qir(&rir::Program::default()));
+}
+
+#[test]
+fn fptosi_double_variable() {
+    let inst = rir::Instruction::Fptosi(
+        rir::Operand::Variable(rir::Variable {
+            variable_id: rir::VariableId(1),
+            ty: rir::Ty::Double,
+        }),
+        rir::Variable {
+            variable_id: rir::VariableId(0),
+            ty: rir::Ty::Integer,
+        },
+    );
+    expect!["  %var_0 = fptosi double %var_1 to i64"]
+        .assert_eq(&inst.to_qir(&rir::Program::default()));
+}
+
+#[test]
+fn sitofp_integer_variable() {
+    let inst = rir::Instruction::Sitofp(
+        rir::Operand::Variable(rir::Variable {
+            variable_id: rir::VariableId(1),
+            ty: rir::Ty::Integer,
+        }),
+        rir::Variable {
+            variable_id: rir::VariableId(0),
+            ty: rir::Ty::Double,
+        },
+    );
+    expect!["  %var_0 = sitofp i64 %var_1 to double"]
+        .assert_eq(&inst.to_qir(&rir::Program::default()));
+}
+
+#[test]
+#[should_panic(expected = "unsupported input type i64 for fptosi")]
+fn fptosi_integer_literal() {
+    let inst = rir::Instruction::Fptosi(
+        rir::Operand::Literal(rir::Literal::Integer(2)),
+        rir::Variable {
+            variable_id: rir::VariableId(0),
+            ty: rir::Ty::Integer,
+        },
+    );
+    let _ = &inst.to_qir(&rir::Program::default());
+}
@@ -557,3 +557,17 @@ fn sub_integer_variables() {
     );
     expect!["  %var_0 = sub i64 %var_1, %var_2"].assert_eq(&inst.to_qir(&rir::Program::default()));
 }
+
+#[test]
+#[should_panic(expected = "unsupported type i64 for fadd")]
+fn fadd_integer_literals() {
+    let inst = rir::Instruction::Fadd(
+        rir::Operand::Literal(rir::Literal::Integer(2)),
+        rir::Operand::Literal(rir::Literal::Integer(5)),
+        rir::Variable {
+            variable_id: rir::VariableId(0),
+            ty: rir::Ty::Integer,
+        },
+    );
+    let _ = &inst.to_qir(&rir::Program::default());
+}
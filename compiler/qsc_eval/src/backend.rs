@@ -1,12 +1,21 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
+pub mod journal;
+pub mod noisy;
+pub mod stabilizer;
+
 use num_bigint::BigUint;
 use num_complex::Complex;
 use quantum_sparse_sim::QuantumSim;
-use rand::RngCore;
+use rand::{rngs::StdRng, Rng, RngCore, SeedableRng};
+use rustc_hash::FxHashMap;
 
-use crate::{intrinsic::utils, val::Value};
+use crate::{
+    intrinsic::utils,
+    noise::{Fault, NoiseConfig, PauliNoise},
+    val::Value,
+};
 
 /// The trait that must be implemented by a quantum backend, whose functions will be invoked when
 /// quantum intrinsics are called.
@@ -104,11 +113,25 @@ pub trait Backend {
         None
     }
     fn set_seed(&mut self, _seed: Option<u64>) {}
+    /// Returns the reason the backend could not carry out the last operation, if any.
+    /// Backends that only support a subset of operations use this to fail the program at the
+    /// call site instead of panicking.
+    fn take_error(&mut self) -> Option<String> {
+        None
+    }
 }
 
 /// Default backend used when targeting sparse simulation.
 pub struct SparseSim {
     pub sim: QuantumSim,
+    /// The Pauli noise applied after each operation.
+    pub noise: NoiseConfig,
+    /// Random number generator used to sample noise.
+    rng: StdRng,
+    /// The number of operations applied so far, used to measure how long qubits stay idle.
+    time: u64,
+    /// The time at which each allocated qubit was last operated on.
+    last_used: FxHashMap<usize, u64>,
 }
 
 impl Default for SparseSim {
@@ -120,135 +143,247 @@ impl Default for SparseSim {
 impl SparseSim {
     #[must_use]
     pub fn new() -> Self {
+        Self::new_with_noise(NoiseConfig::default())
+    }
+
+    #[must_use]
+    pub fn new_with_noise(noise: NoiseConfig) -> Self {
         Self {
             sim: QuantumSim::new(None),
+            noise,
+            rng: StdRng::from_entropy(),
+            time: 0,
+            last_used: FxHashMap::default(),
+        }
+    }
+
+    /// Samples an error from the given channel and applies it to the given qubits,
+    /// independently for each qubit.
+    fn apply_noise(&mut self, noise: PauliNoise, qubits: &[usize]) {
+        if noise.is_noiseless() {
+            return;
+        }
+        for &q in qubits {
+            match noise.sample(&mut self.rng) {
+                Fault::None => {}
+                Fault::X => self.sim.x(q),
+                Fault::Y => self.sim.y(q),
+                Fault::Z => self.sim.z(q),
+            }
+        }
+    }
+
+    /// Applies the idle noise accumulated by the given qubits since they were last operated on,
+    /// then marks them as used by the operation about to be applied.
+    fn apply_idle_noise(&mut self, qubits: &[usize]) {
+        if self.noise.idle.is_noiseless() {
+            return;
+        }
+        for &q in qubits {
+            let idle = self.time - self.last_used.get(&q).copied().unwrap_or(self.time);
+            if idle > 0 {
+                self.apply_noise(self.noise.idle.repeated(idle), &[q]);
+            }
+            self.last_used.insert(q, self.time + 1);
+        }
+        self.time += 1;
+    }
+
+    /// Flips the measurement result with the configured measurement error probability.
+    fn apply_measurement_noise(&mut self, res: bool) -> bool {
+        if self.noise.measurement() > 0.0 && self.rng.gen_bool(self.noise.measurement()) {
+            !res
+        } else {
+            res
         }
     }
+
+    fn rzz_noiseless(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.sim.mcx(&[q1], q0);
+        self.sim.rz(theta, q0);
+        self.sim.mcx(&[q1], q0);
+    }
 }
 
 impl Backend for SparseSim {
     type ResultType = bool;
 
     fn ccx(&mut self, ctl0: usize, ctl1: usize, q: usize) {
+        self.apply_idle_noise(&[ctl0, ctl1, q]);
         self.sim.mcx(&[ctl0, ctl1], q);
+        self.apply_noise(self.noise.ccx, &[ctl0, ctl1, q]);
     }
 
     fn cx(&mut self, ctl: usize, q: usize) {
+        self.apply_idle_noise(&[ctl, q]);
         self.sim.mcx(&[ctl], q);
+        self.apply_noise(self.noise.cx, &[ctl, q]);
     }
 
     fn cy(&mut self, ctl: usize, q: usize) {
+        self.apply_idle_noise(&[ctl, q]);
         self.sim.mcy(&[ctl], q);
+        self.apply_noise(self.noise.cy, &[ctl, q]);
     }
 
     fn cz(&mut self, ctl: usize, q: usize) {
+        self.apply_idle_noise(&[ctl, q]);
         self.sim.mcz(&[ctl], q);
+        self.apply_noise(self.noise.cz, &[ctl, q]);
     }
 
     fn h(&mut self, q: usize) {
+        self.apply_idle_noise(&[q]);
         self.sim.h(q);
+        self.apply_noise(self.noise.h, &[q]);
     }
 
     fn m(&mut self, q: usize) -> Self::ResultType {
-        self.sim.measure(q)
+        self.apply_idle_noise(&[q]);
+        let res = self.sim.measure(q);
+        self.apply_measurement_noise(res)
     }
 
     fn mresetz(&mut self, q: usize) -> Self::ResultType {
+        self.apply_idle_noise(&[q]);
         let res = self.sim.measure(q);
         if res {
             self.sim.x(q);
         }
-        res
+        self.apply_noise(self.noise.reset, &[q]);
+        self.apply_measurement_noise(res)
     }
 
     fn reset(&mut self, q: usize) {
-        self.mresetz(q);
+        self.apply_idle_noise(&[q]);
+        if self.sim.measure(q) {
+            self.sim.x(q);
+        }
+        self.apply_noise(self.noise.reset, &[q]);
     }
 
     fn rx(&mut self, theta: f64, q: usize) {
+        self.apply_idle_noise(&[q]);
         self.sim.rx(theta, q);
+        self.apply_noise(self.noise.rx, &[q]);
     }
 
     fn rxx(&mut self, theta: f64, q0: usize, q1: usize) {
-        self.h(q0);
-        self.h(q1);
-        self.rzz(theta, q0, q1);
-        self.h(q1);
-        self.h(q0);
+        self.apply_idle_noise(&[q0, q1]);
+        self.sim.h(q0);
+        self.sim.h(q1);
+        self.rzz_noiseless(theta, q0, q1);
+        self.sim.h(q1);
+        self.sim.h(q0);
+        self.apply_noise(self.noise.rxx, &[q0, q1]);
     }
 
     fn ry(&mut self, theta: f64, q: usize) {
+        self.apply_idle_noise(&[q]);
         self.sim.ry(theta, q);
+        self.apply_noise(self.noise.ry, &[q]);
     }
 
     fn ryy(&mut self, theta: f64, q0: usize, q1: usize) {
-        self.h(q0);
-        self.s(q0);
-        self.h(q0);
-        self.h(q1);
-        self.s(q1);
-        self.h(q1);
-        self.rzz(theta, q0, q1);
-        self.h(q1);
-        self.sadj(q1);
-        self.h(q1);
-        self.h(q0);
-        self.sadj(q0);
-        self.h(q0);
+        self.apply_idle_noise(&[q0, q1]);
+        self.sim.h(q0);
+        self.sim.s(q0);
+        self.sim.h(q0);
+        self.sim.h(q1);
+        self.sim.s(q1);
+        self.sim.h(q1);
+        self.rzz_noiseless(theta, q0, q1);
+        self.sim.h(q1);
+        self.sim.sadj(q1);
+        self.sim.h(q1);
+        self.sim.h(q0);
+        self.sim.sadj(q0);
+        self.sim.h(q0);
+        self.apply_noise(self.noise.ryy, &[q0, q1]);
     }
 
     fn rz(&mut self, theta: f64, q: usize) {
+        self.apply_idle_noise(&[q]);
         self.sim.rz(theta, q);
+        self.apply_noise(self.noise.rz, &[q]);
     }
 
     fn rzz(&mut self, theta: f64, q0: usize, q1: usize) {
-        self.cx(q1, q0);
-        self.rz(theta, q0);
-        self.cx(q1, q0);
+        self.apply_idle_noise(&[q0, q1]);
+        self.rzz_noiseless(theta, q0, q1);
+        self.apply_noise(self.noise.rzz, &[q0, q1]);
     }
 
     fn sadj(&mut self, q: usize) {
+        self.apply_idle_noise(&[q]);
         self.sim.sadj(q);
+        self.apply_noise(self.noise.sadj, &[q]);
     }
 
     fn s(&mut self, q: usize) {
+        self.apply_idle_noise(&[q]);
         self.sim.s(q);
+        self.apply_noise(self.noise.s, &[q]);
     }
 
     fn swap(&mut self, q0: usize, q1: usize) {
+        self.apply_idle_noise(&[q0, q1]);
         self.sim.swap_qubit_ids(q0, q1);
+        self.apply_noise(self.noise.swap, &[q0, q1]);
     }
 
     fn tadj(&mut self, q: usize) {
+        self.apply_idle_noise(&[q]);
         self.sim.tadj(q);
+        self.apply_noise(self.noise.tadj, &[q]);
     }
 
     fn t(&mut self, q: usize) {
+        self.apply_idle_noise(&[q]);
         self.sim.t(q);
+        self.apply_noise(self.noise.t, &[q]);
     }
 
     fn x(&mut self, q: usize) {
+        self.apply_idle_noise(&[q]);
         self.sim.x(q);
+        self.apply_noise(self.noise.x, &[q]);
     }
 
     fn y(&mut self, q: usize) {
+        self.apply_idle_noise(&[q]);
         self.sim.y(q);
+        self.apply_noise(self.noise.y, &[q]);
     }
 
     fn z(&mut self, q: usize) {
+        self.apply_idle_noise(&[q]);
         self.sim.z(q);
+        self.apply_noise(self.noise.z, &[q]);
     }
 
     fn qubit_allocate(&mut self) -> usize {
-        self.sim.allocate()
+        let q = self.sim.allocate();
+        self.last_used.insert(q, self.time);
+        self.apply_noise(self.noise.reset, &[q]);
+        q
     }
 
     fn qubit_release(&mut self, q: usize) {
+        self.last_used.remove(&q);
         self.sim.release(q);
     }
 
     fn qubit_swap_id(&mut self, q0: usize, q1: usize) {
         self.sim.swap_qubit_ids(q0, q1);
+        let t0 = self.last_used.remove(&q0);
+        let t1 = self.last_used.remove(&q1);
+        if let Some(t) = t0 {
+            self.last_used.insert(q1, t);
+        }
+        if let Some(t) = t1 {
+            self.last_used.insert(q0, t);
+        }
     }
 
     fn capture_quantum_state(&mut self) -> (Vec<(BigUint, Complex<f64>)>, usize) {
@@ -322,8 +457,14 @@ impl Backend for SparseSim {
 
     fn set_seed(&mut self, seed: Option<u64>) {
         match seed {
-            Some(seed) => self.sim.set_rng_seed(seed),
-            None => self.sim.set_rng_seed(rand::thread_rng().next_u64()),
+            Some(seed) => {
+                self.sim.set_rng_seed(seed);
+                self.rng = StdRng::seed_from_u64(seed);
+            }
+            None => {
+                self.sim.set_rng_seed(rand::thread_rng().next_u64());
+                self.rng = StdRng::from_entropy();
+            }
         }
     }
 }
@@ -521,6 +662,11 @@ where
         self.chained.set_seed(seed);
         self.main.set_seed(seed);
     }
+
+    fn take_error(&mut self) -> Option<String> {
+        let chained = self.chained.take_error();
+        self.main.take_error().or(chained)
+    }
 }
 
 pub trait Annotate {
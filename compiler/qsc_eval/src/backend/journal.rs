@@ -0,0 +1,337 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! A journal of the operations applied to a backend. Since a seeded backend is deterministic,
+//! replaying a prefix of the journal on a new instance of the backend reconstructs the state the
+//! original backend was in at that point, which serves as a snapshot of the simulator.
+//! A journal holds a limited number of operations; once it is full, later operations are
+//! still applied to the backend but are no longer recorded, so only the states up to that
+//! point can be reconstructed.
+
+#[cfg(test)]
+mod tests;
+
+use super::Backend;
+use crate::val::Value;
+use num_bigint::BigUint;
+use num_complex::Complex;
+
+/// An operation that changes the state of a backend.
+#[derive(Clone, Debug)]
+enum Op {
+    Ccx(usize, usize, usize),
+    Cx(usize, usize),
+    Cy(usize, usize),
+    Cz(usize, usize),
+    H(usize),
+    M(usize),
+    Mresetz(usize),
+    Reset(usize),
+    Rx(f64, usize),
+    Rxx(f64, usize, usize),
+    Ry(f64, usize),
+    Ryy(f64, usize, usize),
+    Rz(f64, usize),
+    Rzz(f64, usize, usize),
+    Sadj(usize),
+    S(usize),
+    Swap(usize, usize),
+    Tadj(usize),
+    T(usize),
+    X(usize),
+    Y(usize),
+    Z(usize),
+    QubitAllocate,
+    QubitRelease(usize),
+    QubitSwapId(usize, usize),
+    CaptureQuantumState,
+    CaptureQuantumStateForQubits(Vec<usize>),
+    CustomIntrinsic(String, Value),
+    SetSeed(Option<u64>),
+}
+
+/// The operations applied to a backend, in order.
+#[derive(Clone, Debug)]
+pub struct Journal {
+    ops: Vec<Op>,
+    /// The maximum number of operations recorded.
+    limit: usize,
+    /// Whether operations were dropped because the journal reached its limit.
+    full: bool,
+}
+
+impl Journal {
+    /// Creates a journal that records up to `limit` operations for `backend`, first seeding it
+    /// with `seed` so that replaying the journal reproduces the same measurement outcomes.
+    /// If `seed` is `None`, a random seed is used.
+    pub fn new(backend: &mut impl Backend, seed: Option<u64>, limit: usize) -> Self {
+        let mut journal = Self {
+            ops: Vec::new(),
+            limit,
+            full: false,
+        };
+        let seed = seed.unwrap_or_else(rand::random);
+        Recorder::new(backend, &mut journal).set_seed(Some(seed));
+        journal
+    }
+
+    /// The number of operations recorded so far.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.ops.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    /// Whether operations were applied after the journal reached its limit, in which case
+    /// the current state of the backend can't be reconstructed.
+    #[must_use]
+    pub fn is_full(&self) -> bool {
+        self.full
+    }
+
+    /// Drops every operation after the first `len`, which must not be more than the number
+    /// of operations recorded. Recording resumes from there.
+    pub fn truncate(&mut self, len: usize) {
+        self.ops.truncate(len);
+        self.full = false;
+    }
+
+    fn push(&mut self, op: Op) {
+        if self.ops.len() < self.limit {
+            self.ops.push(op);
+        } else {
+            self.full = true;
+        }
+    }
+
+    /// Applies the first `len` recorded operations to `backend`, which should be a new instance
+    /// of the backend the operations were recorded from.
+    pub fn replay(&self, len: usize, backend: &mut impl Backend) {
+        for op in &self.ops[..len] {
+            match op {
+                Op::Ccx(ctl0, ctl1, q) => backend.ccx(*ctl0, *ctl1, *q),
+                Op::Cx(ctl, q) => backend.cx(*ctl, *q),
+                Op::Cy(ctl, q) => backend.cy(*ctl, *q),
+                Op::Cz(ctl, q) => backend.cz(*ctl, *q),
+                Op::H(q) => backend.h(*q),
+                Op::M(q) => {
+                    backend.m(*q);
+                }
+                Op::Mresetz(q) => {
+                    backend.mresetz(*q);
+                }
+                Op::Reset(q) => backend.reset(*q),
+                Op::Rx(theta, q) => backend.rx(*theta, *q),
+                Op::Rxx(theta, q0, q1) => backend.rxx(*theta, *q0, *q1),
+                Op::Ry(theta, q) => backend.ry(*theta, *q),
+                Op::Ryy(theta, q0, q1) => backend.ryy(*theta, *q0, *q1),
+                Op::Rz(theta, q) => backend.rz(*theta, *q),
+                Op::Rzz(theta, q0, q1) => backend.rzz(*theta, *q0, *q1),
+                Op::Sadj(q) => backend.sadj(*q),
+                Op::S(q) => backend.s(*q),
+                Op::Swap(q0, q1) => backend.swap(*q0, *q1),
+                Op::Tadj(q) => backend.tadj(*q),
+                Op::T(q) => backend.t(*q),
+                Op::X(q) => backend.x(*q),
+                Op::Y(q) => backend.y(*q),
+                Op::Z(q) => backend.z(*q),
+                Op::QubitAllocate => {
+                    backend.qubit_allocate();
+                }
+                Op::QubitRelease(q) => backend.qubit_release(*q),
+                Op::QubitSwapId(q0, q1) => backend.qubit_swap_id(*q0, *q1),
+                Op::CaptureQuantumState => {
+                    backend.capture_quantum_state();
+                }
+                Op::CaptureQuantumStateForQubits(qs) => {
+                    backend.capture_quantum_state_for_qubits(qs);
+                }
+                Op::CustomIntrinsic(name, arg) => {
+                    backend.custom_intrinsic(name, arg.clone());
+                }
+                Op::SetSeed(seed) => backend.set_seed(*seed),
+            }
+            // Errors were already reported when the operations were first applied.
+            let _ = backend.take_error();
+        }
+    }
+}
+
+/// A backend that forwards every operation to another backend and records it in a journal.
+pub struct Recorder<'a, B> {
+    backend: &'a mut B,
+    journal: &'a mut Journal,
+}
+
+impl<'a, B: Backend> Recorder<'a, B> {
+    pub fn new(backend: &'a mut B, journal: &'a mut Journal) -> Self {
+        Self { backend, journal }
+    }
+}
+
+impl<'a, B: Backend> Backend for Recorder<'a, B> {
+    type ResultType = B::ResultType;
+
+    fn ccx(&mut self, ctl0: usize, ctl1: usize, q: usize) {
+        self.journal.push(Op::Ccx(ctl0, ctl1, q));
+        self.backend.ccx(ctl0, ctl1, q);
+    }
+
+    fn cx(&mut self, ctl: usize, q: usize) {
+        self.journal.push(Op::Cx(ctl, q));
+        self.backend.cx(ctl, q);
+    }
+
+    fn cy(&mut self, ctl: usize, q: usize) {
+        self.journal.push(Op::Cy(ctl, q));
+        self.backend.cy(ctl, q);
+    }
+
+    fn cz(&mut self, ctl: usize, q: usize) {
+        self.journal.push(Op::Cz(ctl, q));
+        self.backend.cz(ctl, q);
+    }
+
+    fn h(&mut self, q: usize) {
+        self.journal.push(Op::H(q));
+        self.backend.h(q);
+    }
+
+    fn m(&mut self, q: usize) -> Self::ResultType {
+        self.journal.push(Op::M(q));
+        self.backend.m(q)
+    }
+
+    fn mresetz(&mut self, q: usize) -> Self::ResultType {
+        self.journal.push(Op::Mresetz(q));
+        self.backend.mresetz(q)
+    }
+
+    fn reset(&mut self, q: usize) {
+        self.journal.push(Op::Reset(q));
+        self.backend.reset(q);
+    }
+
+    fn rx(&mut self, theta: f64, q: usize) {
+        self.journal.push(Op::Rx(theta, q));
+        self.backend.rx(theta, q);
+    }
+
+    fn rxx(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.journal.push(Op::Rxx(theta, q0, q1));
+        self.backend.rxx(theta, q0, q1);
+    }
+
+    fn ry(&mut self, theta: f64, q: usize) {
+        self.journal.push(Op::Ry(theta, q));
+        self.backend.ry(theta, q);
+    }
+
+    fn ryy(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.journal.push(Op::Ryy(theta, q0, q1));
+        self.backend.ryy(theta, q0, q1);
+    }
+
+    fn rz(&mut self, theta: f64, q: usize) {
+        self.journal.push(Op::Rz(theta, q));
+        self.backend.rz(theta, q);
+    }
+
+    fn rzz(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.journal.push(Op::Rzz(theta, q0, q1));
+        self.backend.rzz(theta, q0, q1);
+    }
+
+    fn sadj(&mut self, q: usize) {
+        self.journal.push(Op::Sadj(q));
+        self.backend.sadj(q);
+    }
+
+    fn s(&mut self, q: usize) {
+        self.journal.push(Op::S(q));
+        self.backend.s(q);
+    }
+
+    fn swap(&mut self, q0: usize, q1: usize) {
+        self.journal.push(Op::Swap(q0, q1));
+        self.backend.swap(q0, q1);
+    }
+
+    fn tadj(&mut self, q: usize) {
+        self.journal.push(Op::Tadj(q));
+        self.backend.tadj(q);
+    }
+
+    fn t(&mut self, q: usize) {
+        self.journal.push(Op::T(q));
+        self.backend.t(q);
+    }
+
+    fn x(&mut self, q: usize) {
+        self.journal.push(Op::X(q));
+        self.backend.x(q);
+    }
+
+    fn y(&mut self, q: usize) {
+        self.journal.push(Op::Y(q));
+        self.backend.y(q);
+    }
+
+    fn z(&mut self, q: usize) {
+        self.journal.push(Op::Z(q));
+        self.backend.z(q);
+    }
+
+    fn qubit_allocate(&mut self) -> usize {
+        self.journal.push(Op::QubitAllocate);
+        self.backend.qubit_allocate()
+    }
+
+    fn qubit_release(&mut self, q: usize) {
+        self.journal.push(Op::QubitRelease(q));
+        self.backend.qubit_release(q);
+    }
+
+    fn qubit_swap_id(&mut self, q0: usize, q1: usize) {
+        self.journal.push(Op::QubitSwapId(q0, q1));
+        self.backend.qubit_swap_id(q0, q1);
+    }
+
+    fn capture_quantum_state(&mut self) -> (Vec<(BigUint, Complex<f64>)>, usize) {
+        // Capturing the state can annotate a chained backend, so it is recorded as well.
+        self.journal.push(Op::CaptureQuantumState);
+        self.backend.capture_quantum_state()
+    }
+
+    fn capture_quantum_state_for_qubits(
+        &mut self,
+        qs: &[usize],
+    ) -> (Vec<(BigUint, Complex<f64>)>, usize) {
+        self.journal
+            .push(Op::CaptureQuantumStateForQubits(qs.to_vec()));
+        self.backend.capture_quantum_state_for_qubits(qs)
+    }
+
+    fn qubit_is_zero(&mut self, q: usize) -> bool {
+        self.backend.qubit_is_zero(q)
+    }
+
+    fn custom_intrinsic(&mut self, name: &str, arg: Value) -> Option<Result<Value, String>> {
+        self.journal
+            .push(Op::CustomIntrinsic(name.to_string(), arg.clone()));
+        self.backend.custom_intrinsic(name, arg)
+    }
+
+    fn set_seed(&mut self, seed: Option<u64>) {
+        self.journal.push(Op::SetSeed(seed));
+        self.backend.set_seed(seed);
+    }
+
+    fn take_error(&mut self) -> Option<String> {
+        self.backend.take_error()
+    }
+}
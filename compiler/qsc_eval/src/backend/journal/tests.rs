@@ -0,0 +1,83 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{Journal, Recorder};
+use crate::backend::{Backend, SparseSim};
+
+/// Applies a sequence of operations with random outcomes, returning the measurement results.
+fn apply_random_ops(sim: &mut impl Backend<ResultType = bool>) -> Vec<bool> {
+    let qs = (0..3).map(|_| sim.qubit_allocate()).collect::<Vec<_>>();
+    let mut results = Vec::new();
+    for _ in 0..16 {
+        sim.h(qs[0]);
+        sim.cx(qs[0], qs[1]);
+        sim.ry(0.3, qs[2]);
+        results.push(sim.m(qs[0]));
+        results.push(sim.mresetz(qs[2]));
+    }
+    results
+}
+
+#[test]
+fn replay_reproduces_state_and_measurements() {
+    let mut sim = SparseSim::new();
+    let mut journal = Journal::new(&mut sim, None, usize::MAX);
+    let results = apply_random_ops(&mut Recorder::new(&mut sim, &mut journal));
+
+    let mut replayed = SparseSim::new();
+    journal.replay(journal.len(), &mut replayed);
+    assert_eq!(
+        sim.capture_quantum_state(),
+        replayed.capture_quantum_state()
+    );
+
+    // Since the measurements of a replayed journal repeat the original outcomes, continuing
+    // from a replayed prefix matches the original run.
+    let mut prefix = SparseSim::new();
+    journal.replay(1, &mut prefix);
+    assert_eq!(results, apply_random_ops(&mut prefix));
+}
+
+#[test]
+fn replay_of_prefix_restores_earlier_state() {
+    let mut sim = SparseSim::new();
+    let mut journal = Journal::new(&mut sim, Some(42), usize::MAX);
+    let mut recorder = Recorder::new(&mut sim, &mut journal);
+    let q = recorder.qubit_allocate();
+    recorder.x(q);
+    let (expected, _) = recorder.capture_quantum_state();
+    let len = journal.len();
+
+    let mut recorder = Recorder::new(&mut sim, &mut journal);
+    recorder.h(q);
+    recorder.t(q);
+
+    let mut replayed = SparseSim::new();
+    journal.replay(len, &mut replayed);
+    let (state, count) = replayed.capture_quantum_state();
+    assert_eq!(count, 1);
+    assert_eq!(state, expected);
+}
+
+#[test]
+fn full_journal_stops_recording_until_truncated() {
+    let mut sim = SparseSim::new();
+    let mut journal = Journal::new(&mut sim, Some(42), 3);
+    let mut recorder = Recorder::new(&mut sim, &mut journal);
+    let q = recorder.qubit_allocate();
+    recorder.x(q);
+    assert_eq!(journal.len(), 3);
+    assert!(!journal.is_full());
+
+    // Operations past the limit are still applied to the backend.
+    Recorder::new(&mut sim, &mut journal).h(q);
+    assert_eq!(journal.len(), 3);
+    assert!(journal.is_full());
+    let (state, _) = sim.capture_quantum_state();
+    assert_eq!(state.len(), 2);
+
+    journal.truncate(2);
+    assert!(!journal.is_full());
+    Recorder::new(&mut sim, &mut journal).z(q);
+    assert_eq!(journal.len(), 3);
+}
@@ -0,0 +1,855 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! A backend that runs Q# programs on the simulators from the `noisy_simulator` crate.
+//!
+//! Every intrinsic gate is mapped to an ideal `Operation` followed by the noise channel
+//! configured for that gate in the `NoiseModel`. Measurements are performed by sampling an
+//! `Instrument`, which can itself model readout errors.
+
+#[cfg(test)]
+mod tests;
+
+use noisy_simulator::{
+    DensityMatrix, DensityMatrixSimulator, NoisySimulator, SquareMatrix, StateVector,
+    StateVectorSimulator,
+};
+pub use noisy_simulator::{Error, Instrument, Operation};
+use num_bigint::BigUint;
+use num_complex::Complex;
+use rand::RngCore;
+
+use super::Backend;
+use crate::{intrinsic::utils, val::Value};
+
+/// Probabilities below this threshold are treated as zero when inspecting the simulator state.
+const TOLERANCE: f64 = 1e-10;
+
+/// States whose purity is within this distance of one are treated as pure.
+const PURITY_TOLERANCE: f64 = 1e-6;
+
+/// Noisy backend using a density matrix as its internal state.
+pub type DensityMatrixSim = NoisySim<DensityMatrixSimulator>;
+
+/// Noisy backend using a state vector (trajectory simulation) as its internal state.
+pub type StateVectorSim = NoisySim<StateVectorSimulator>;
+
+/// The per-gate noise configuration for a `NoisySim`.
+///
+/// Each channel is applied to the target qubits of the corresponding gate right after the
+/// ideal gate. Gates without a channel are applied noiselessly. Channels for two-qubit gates
+/// act on the qubits in the order they are passed to the gate, with the first qubit being the
+/// most significant one in the Kraus matrices.
+#[derive(Clone, Default)]
+pub struct NoiseModel {
+    pub x: Option<Operation>,
+    pub y: Option<Operation>,
+    pub z: Option<Operation>,
+    pub h: Option<Operation>,
+    pub s: Option<Operation>,
+    pub sadj: Option<Operation>,
+    pub t: Option<Operation>,
+    pub tadj: Option<Operation>,
+    pub rx: Option<Operation>,
+    pub ry: Option<Operation>,
+    pub rz: Option<Operation>,
+    pub cx: Option<Operation>,
+    pub cy: Option<Operation>,
+    pub cz: Option<Operation>,
+    pub ccx: Option<Operation>,
+    pub rxx: Option<Operation>,
+    pub ryy: Option<Operation>,
+    pub rzz: Option<Operation>,
+    pub swap: Option<Operation>,
+    pub reset: Option<Operation>,
+    /// The instrument used by `M` and `MResetZ`. Outcome 0 is reported as `Zero` and outcome 1
+    /// as `One`. If not set, an ideal Z-basis measurement is used.
+    pub measurement: Option<Instrument>,
+}
+
+impl NoiseModel {
+    /// Creates a noise model where every single-qubit gate is followed by a depolarizing channel
+    /// with probability `p1`, every two-qubit gate is followed by independent depolarizing channels
+    /// with probability `p2` on each of its qubits, and measurements report the wrong outcome with
+    /// probability `p_meas`.
+    pub fn depolarizing(p1: f64, p2: f64, p_meas: f64) -> Result<Self, Error> {
+        let one = depolarizing_channel(p1)?;
+        let two = two_qubit_depolarizing_channel(p2)?;
+        Ok(Self {
+            x: Some(one.clone()),
+            y: Some(one.clone()),
+            z: Some(one.clone()),
+            h: Some(one.clone()),
+            s: Some(one.clone()),
+            sadj: Some(one.clone()),
+            t: Some(one.clone()),
+            tadj: Some(one.clone()),
+            rx: Some(one.clone()),
+            ry: Some(one.clone()),
+            rz: Some(one),
+            cx: Some(two.clone()),
+            cy: Some(two.clone()),
+            cz: Some(two.clone()),
+            ccx: None,
+            rxx: Some(two.clone()),
+            ryy: Some(two.clone()),
+            rzz: Some(two.clone()),
+            swap: Some(two),
+            reset: None,
+            measurement: Some(readout_error_measurement(p_meas)?),
+        })
+    }
+
+    /// Sets the channel applied after the gate with the given name, which is the name of its
+    /// field in the model, such as `h` or `cx`.
+    /// # Errors
+    /// Returns an error if there is no gate with the given name, or if the channel doesn't act
+    /// on as many qubits as the gate.
+    pub fn set_gate_noise(&mut self, gate: &str, noise: Operation) -> Result<(), String> {
+        let (channel, qubits) = match gate {
+            "x" => (&mut self.x, 1),
+            "y" => (&mut self.y, 1),
+            "z" => (&mut self.z, 1),
+            "h" => (&mut self.h, 1),
+            "s" => (&mut self.s, 1),
+            "sadj" => (&mut self.sadj, 1),
+            "t" => (&mut self.t, 1),
+            "tadj" => (&mut self.tadj, 1),
+            "rx" => (&mut self.rx, 1),
+            "ry" => (&mut self.ry, 1),
+            "rz" => (&mut self.rz, 1),
+            "reset" => (&mut self.reset, 1),
+            "cx" => (&mut self.cx, 2),
+            "cy" => (&mut self.cy, 2),
+            "cz" => (&mut self.cz, 2),
+            "rxx" => (&mut self.rxx, 2),
+            "ryy" => (&mut self.ryy, 2),
+            "rzz" => (&mut self.rzz, 2),
+            "swap" => (&mut self.swap, 2),
+            "ccx" => (&mut self.ccx, 3),
+            _ => return Err(format!("there is no gate named `{gate}`")),
+        };
+        if noise.number_of_qubits() != qubits {
+            return Err(format!(
+                "the noise for `{gate}` should act on {qubits} qubit(s), but it acts on {}",
+                noise.number_of_qubits()
+            ));
+        }
+        *channel = Some(noise);
+        Ok(())
+    }
+}
+
+/// Returns a single-qubit depolarizing channel, which applies a uniformly random Pauli error
+/// with total probability `p`.
+pub fn depolarizing_channel(p: f64) -> Result<Operation, Error> {
+    check_probability(p)?;
+    let (identity, x, y, z) = paulis();
+    Operation::new(vec![
+        scaled(&identity, (1.0 - p).sqrt()),
+        scaled(&x, (p / 3.0).sqrt()),
+        scaled(&y, (p / 3.0).sqrt()),
+        scaled(&z, (p / 3.0).sqrt()),
+    ])
+}
+
+/// Returns the two-qubit channel obtained by applying independent single-qubit depolarizing
+/// channels with probability `p` to both qubits.
+pub fn two_qubit_depolarizing_channel(p: f64) -> Result<Operation, Error> {
+    let single = depolarizing_channel(p)?;
+    let kraus_operators = single.kraus_operators();
+    // The operation stores its Kraus operators transposed, so transpose them back
+    // before building the product channel.
+    let mut product = Vec::with_capacity(kraus_operators.len() * kraus_operators.len());
+    for a in kraus_operators {
+        for b in kraus_operators {
+            product.push(a.transpose().kronecker(&b.transpose()));
+        }
+    }
+    Operation::new(product)
+}
+
+/// Builds an operation from its Kraus operators, each given as the rows of a square matrix.
+pub fn operation_from_rows(kraus_operators: &[Vec<Vec<Complex<f64>>>]) -> Result<Operation, Error> {
+    let matrices = kraus_operators
+        .iter()
+        .map(|rows| {
+            let dim = rows.len();
+            if rows.iter().any(|row| row.len() != dim) {
+                return Err(Error::FailedToConstructOperation(
+                    "Kraus operators should be square matrices".to_string(),
+                ));
+            }
+            let entries = rows.iter().flatten().copied().collect::<Vec<_>>();
+            Ok(matrix(dim, &entries))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Operation::new(matrices)
+}
+
+/// Returns a Z-basis measurement instrument that reports the wrong outcome with probability `p`.
+/// The post-measurement state is always projected onto the actual outcome.
+pub fn readout_error_measurement(p: f64) -> Result<Instrument, Error> {
+    check_probability(p)?;
+    let (p0, p1) = projectors();
+    let zero = Operation::new(vec![scaled(&p0, (1.0 - p).sqrt()), scaled(&p1, p.sqrt())])?;
+    let one = Operation::new(vec![scaled(&p0, p.sqrt()), scaled(&p1, (1.0 - p).sqrt())])?;
+    Instrument::new(vec![zero, one])
+}
+
+/// Extracts the amplitudes and basis state probabilities from the state of a noisy simulator.
+/// Both are indexed so that bit `k` of the index corresponds to the simulator qubit `k`.
+pub trait StateAmplitudes {
+    /// Returns the amplitudes of the state, or `None` if the state is mixed and so has no
+    /// amplitudes.
+    fn amplitudes(&self) -> Option<Vec<Complex<f64>>>;
+    fn probabilities(&self) -> Vec<f64>;
+}
+
+impl StateAmplitudes for StateVector {
+    fn amplitudes(&self) -> Option<Vec<Complex<f64>>> {
+        Some(self.data().iter().copied().collect())
+    }
+
+    fn probabilities(&self) -> Vec<f64> {
+        self.data().iter().map(Complex::norm_sqr).collect()
+    }
+}
+
+impl StateAmplitudes for DensityMatrix {
+    /// A density matrix only has well defined amplitudes if it represents a pure state, which is
+    /// the case when its purity `Tr(ρ²)` is one. The amplitudes of a pure state are read from the
+    /// column of the matrix with the largest diagonal entry, which recovers the state up to a
+    /// global phase.
+    fn amplitudes(&self) -> Option<Vec<Complex<f64>>> {
+        let dim = self.dimension();
+        let data = self.data();
+        // Since the matrix is Hermitian, `Tr(ρ²)` is the sum of the squared norms of its entries.
+        let purity: f64 = data.iter().map(Complex::norm_sqr).sum();
+        if (purity - 1.0).abs() > PURITY_TOLERANCE {
+            return None;
+        }
+        let (col, norm) = (0..dim)
+            .map(|i| (i, data[i * dim + i].re))
+            .fold((0, 0.0), |max, cur| if cur.1 > max.1 { cur } else { max });
+        let norm = norm.sqrt();
+        Some((0..dim).map(|row| data[row * dim + col] / norm).collect())
+    }
+
+    fn probabilities(&self) -> Vec<f64> {
+        let dim = self.dimension();
+        let data = self.data();
+        (0..dim).map(|i| data[i * dim + i].re).collect()
+    }
+}
+
+/// The ideal gates used by `NoisySim`, built once when the backend is created.
+struct Gates {
+    x: Operation,
+    y: Operation,
+    z: Operation,
+    h: Operation,
+    s: Operation,
+    sadj: Operation,
+    t: Operation,
+    tadj: Operation,
+    cx: Operation,
+    cy: Operation,
+    cz: Operation,
+    ccx: Operation,
+    swap: Operation,
+    reset: Operation,
+    mz: Instrument,
+}
+
+impl Gates {
+    fn new() -> Self {
+        let (_, x, y, z) = paulis();
+        let (p0, p1) = projectors();
+        let amp = std::f64::consts::FRAC_1_SQRT_2;
+        let t_phase = Complex::from_polar(1.0, std::f64::consts::FRAC_PI_4);
+        let (zero, one) = (re(0.0), re(1.0));
+        #[rustfmt::skip]
+        let swap = matrix(4, &[
+            one, zero, zero, zero,
+            zero, zero, one, zero,
+            zero, one, zero, zero,
+            zero, zero, zero, one,
+        ]);
+        Self {
+            x: unitary(x.clone()),
+            y: unitary(y.clone()),
+            z: unitary(z.clone()),
+            h: unitary(matrix(2, &[re(amp), re(amp), re(amp), re(-amp)])),
+            s: unitary(diagonal(&[re(1.0), im(1.0)])),
+            sadj: unitary(diagonal(&[re(1.0), im(-1.0)])),
+            t: unitary(diagonal(&[re(1.0), t_phase])),
+            tadj: unitary(diagonal(&[re(1.0), t_phase.conj()])),
+            cx: unitary(controlled(1, &x)),
+            cy: unitary(controlled(1, &y)),
+            cz: unitary(controlled(1, &z)),
+            ccx: unitary(controlled(2, &x)),
+            swap: unitary(swap),
+            reset: Operation::new(vec![
+                p0.clone(),
+                matrix(2, &[re(0.0), re(1.0), re(0.0), re(0.0)]),
+            ])
+            .expect("reset should be a valid operation"),
+            mz: Instrument::new(vec![
+                Operation::new(vec![p0]).expect("projector should be a valid operation"),
+                Operation::new(vec![p1]).expect("projector should be a valid operation"),
+            ])
+            .expect("measurement should be a valid instrument"),
+        }
+    }
+}
+
+/// A backend that simulates Q# programs under noise using the `noisy_simulator` crate.
+///
+/// The underlying simulators have a fixed size, so the backend is created with the maximum number
+/// of qubits the program may have allocated at the same time. Released qubits are reset and their
+/// ids are reused by later allocations. Allocating more qubits than that, and any failure of the
+/// underlying simulator, fails the program with an error reported through
+/// [`Backend::take_error`].
+pub struct NoisySim<S: NoisySimulator> {
+    sim: S,
+    capacity: usize,
+    noise: NoiseModel,
+    gates: Gates,
+    /// Ids of released qubits that are available for reuse.
+    free: Vec<usize>,
+    /// One past the highest qubit id handed out so far.
+    next_id: usize,
+    error: Option<String>,
+}
+
+impl<S: NoisySimulator> NoisySim<S> {
+    /// Creates a new noisy backend that supports up to `capacity` simultaneously allocated qubits.
+    #[must_use]
+    pub fn new(capacity: usize, noise: NoiseModel) -> Self {
+        Self {
+            sim: S::new(capacity),
+            capacity,
+            noise,
+            gates: Gates::new(),
+            free: Vec::new(),
+            next_id: 0,
+            error: None,
+        }
+    }
+
+    #[must_use]
+    pub fn noise_model(&self) -> &NoiseModel {
+        &self.noise
+    }
+
+    #[must_use]
+    pub fn simulator(&self) -> &S {
+        &self.sim
+    }
+}
+
+/// Applies `op` to the given qubits, where the first qubit is the most significant one in the
+/// operation's Kraus matrices. If the simulator fails, the failure is stored in `error` and
+/// later operations are skipped until it has been taken.
+fn apply<S: NoisySimulator>(
+    sim: &mut S,
+    error: &mut Option<String>,
+    op: &Operation,
+    qubits: &[usize],
+) {
+    if error.is_some() {
+        return;
+    }
+    // The simulator treats the first qubit as the least significant bit of the matrix index.
+    let qubits = qubits.iter().rev().copied().collect::<Vec<_>>();
+    if let Err(e) = sim.apply_operation(op, &qubits) {
+        *error = Some(simulation_failed(&e));
+    }
+}
+
+/// Applies an ideal gate followed by its noise channel, if any.
+fn apply_gate<S: NoisySimulator>(
+    sim: &mut S,
+    error: &mut Option<String>,
+    gate: &Operation,
+    noise: Option<&Operation>,
+    qubits: &[usize],
+) {
+    apply(sim, error, gate, qubits);
+    if let Some(noise) = noise {
+        apply(sim, error, noise, qubits);
+    }
+}
+
+fn simulation_failed(e: &Error) -> String {
+    format!("noisy simulation failed: {e}")
+}
+
+impl<S> Backend for NoisySim<S>
+where
+    S: NoisySimulator,
+    S::State: StateAmplitudes,
+{
+    type ResultType = bool;
+
+    fn ccx(&mut self, ctl0: usize, ctl1: usize, q: usize) {
+        let qubits = [ctl0, ctl1, q];
+        apply_gate(
+            &mut self.sim,
+            &mut self.error,
+            &self.gates.ccx,
+            self.noise.ccx.as_ref(),
+            &qubits,
+        );
+    }
+
+    fn cx(&mut self, ctl: usize, q: usize) {
+        apply_gate(
+            &mut self.sim,
+            &mut self.error,
+            &self.gates.cx,
+            self.noise.cx.as_ref(),
+            &[ctl, q],
+        );
+    }
+
+    fn cy(&mut self, ctl: usize, q: usize) {
+        apply_gate(
+            &mut self.sim,
+            &mut self.error,
+            &self.gates.cy,
+            self.noise.cy.as_ref(),
+            &[ctl, q],
+        );
+    }
+
+    fn cz(&mut self, ctl: usize, q: usize) {
+        apply_gate(
+            &mut self.sim,
+            &mut self.error,
+            &self.gates.cz,
+            self.noise.cz.as_ref(),
+            &[ctl, q],
+        );
+    }
+
+    fn h(&mut self, q: usize) {
+        apply_gate(
+            &mut self.sim,
+            &mut self.error,
+            &self.gates.h,
+            self.noise.h.as_ref(),
+            &[q],
+        );
+    }
+
+    fn m(&mut self, q: usize) -> Self::ResultType {
+        if self.error.is_some() {
+            return false;
+        }
+        let mz = self.noise.measurement.as_ref().unwrap_or(&self.gates.mz);
+        match self.sim.sample_instrument(mz, &[q]) {
+            Ok(outcome) => outcome == 1,
+            Err(e) => {
+                self.error = Some(simulation_failed(&e));
+                false
+            }
+        }
+    }
+
+    fn mresetz(&mut self, q: usize) -> Self::ResultType {
+        // The measured outcome may differ from the post-measurement state when the measurement
+        // models readout errors, so the qubit is reset non-selectively instead of conditionally
+        // flipped based on the reported result.
+        let res = self.m(q);
+        apply(&mut self.sim, &mut self.error, &self.gates.reset, &[q]);
+        res
+    }
+
+    fn reset(&mut self, q: usize) {
+        apply_gate(
+            &mut self.sim,
+            &mut self.error,
+            &self.gates.reset,
+            self.noise.reset.as_ref(),
+            &[q],
+        );
+    }
+
+    fn rx(&mut self, theta: f64, q: usize) {
+        let (c, s) = half_angle(theta);
+        let gate = unitary(matrix(2, &[re(c), im(-s), im(-s), re(c)]));
+        apply_gate(
+            &mut self.sim,
+            &mut self.error,
+            &gate,
+            self.noise.rx.as_ref(),
+            &[q],
+        );
+    }
+
+    fn rxx(&mut self, theta: f64, q0: usize, q1: usize) {
+        let (cos, sin) = half_angle(theta);
+        let (o, c, s) = (re(0.0), re(cos), im(-sin));
+        #[rustfmt::skip]
+        let gate = unitary(matrix(4, &[
+            c, o, o, s,
+            o, c, s, o,
+            o, s, c, o,
+            s, o, o, c,
+        ]));
+        apply_gate(
+            &mut self.sim,
+            &mut self.error,
+            &gate,
+            self.noise.rxx.as_ref(),
+            &[q0, q1],
+        );
+    }
+
+    fn ry(&mut self, theta: f64, q: usize) {
+        let (c, s) = half_angle(theta);
+        let gate = unitary(matrix(2, &[re(c), re(-s), re(s), re(c)]));
+        apply_gate(
+            &mut self.sim,
+            &mut self.error,
+            &gate,
+            self.noise.ry.as_ref(),
+            &[q],
+        );
+    }
+
+    fn ryy(&mut self, theta: f64, q0: usize, q1: usize) {
+        let (cos, sin) = half_angle(theta);
+        let (o, c, s) = (re(0.0), re(cos), im(sin));
+        #[rustfmt::skip]
+        let gate = unitary(matrix(4, &[
+            c,  o,  o, s,
+            o,  c, -s, o,
+            o, -s,  c, o,
+            s,  o,  o, c,
+        ]));
+        apply_gate(
+            &mut self.sim,
+            &mut self.error,
+            &gate,
+            self.noise.ryy.as_ref(),
+            &[q0, q1],
+        );
+    }
+
+    fn rz(&mut self, theta: f64, q: usize) {
+        let phase = Complex::from_polar(1.0, theta / 2.0);
+        let gate = unitary(diagonal(&[phase.conj(), phase]));
+        apply_gate(
+            &mut self.sim,
+            &mut self.error,
+            &gate,
+            self.noise.rz.as_ref(),
+            &[q],
+        );
+    }
+
+    fn rzz(&mut self, theta: f64, q0: usize, q1: usize) {
+        let phase = Complex::from_polar(1.0, theta / 2.0);
+        let gate = unitary(diagonal(&[phase.conj(), phase, phase, phase.conj()]));
+        apply_gate(
+            &mut self.sim,
+            &mut self.error,
+            &gate,
+            self.noise.rzz.as_ref(),
+            &[q0, q1],
+        );
+    }
+
+    fn sadj(&mut self, q: usize) {
+        apply_gate(
+            &mut self.sim,
+            &mut self.error,
+            &self.gates.sadj,
+            self.noise.sadj.as_ref(),
+            &[q],
+        );
+    }
+
+    fn s(&mut self, q: usize) {
+        apply_gate(
+            &mut self.sim,
+            &mut self.error,
+            &self.gates.s,
+            self.noise.s.as_ref(),
+            &[q],
+        );
+    }
+
+    fn swap(&mut self, q0: usize, q1: usize) {
+        apply_gate(
+            &mut self.sim,
+            &mut self.error,
+            &self.gates.swap,
+            self.noise.swap.as_ref(),
+            &[q0, q1],
+        );
+    }
+
+    fn tadj(&mut self, q: usize) {
+        apply_gate(
+            &mut self.sim,
+            &mut self.error,
+            &self.gates.tadj,
+            self.noise.tadj.as_ref(),
+            &[q],
+        );
+    }
+
+    fn t(&mut self, q: usize) {
+        apply_gate(
+            &mut self.sim,
+            &mut self.error,
+            &self.gates.t,
+            self.noise.t.as_ref(),
+            &[q],
+        );
+    }
+
+    fn x(&mut self, q: usize) {
+        apply_gate(
+            &mut self.sim,
+            &mut self.error,
+            &self.gates.x,
+            self.noise.x.as_ref(),
+            &[q],
+        );
+    }
+
+    fn y(&mut self, q: usize) {
+        apply_gate(
+            &mut self.sim,
+            &mut self.error,
+            &self.gates.y,
+            self.noise.y.as_ref(),
+            &[q],
+        );
+    }
+
+    fn z(&mut self, q: usize) {
+        apply_gate(
+            &mut self.sim,
+            &mut self.error,
+            &self.gates.z,
+            self.noise.z.as_ref(),
+            &[q],
+        );
+    }
+
+    fn qubit_allocate(&mut self) -> usize {
+        if let Some(q) = self.free.pop() {
+            return q;
+        }
+        if self.next_id == self.capacity {
+            self.error.get_or_insert(format!(
+                "noisy simulation supports at most {} simultaneously allocated qubits",
+                self.capacity
+            ));
+            // The id is never used, since the program fails with the error.
+            return self.capacity;
+        }
+        self.next_id += 1;
+        self.next_id - 1
+    }
+
+    fn qubit_release(&mut self, q: usize) {
+        // Released qubits are reset so that they can be handed out again in the |0⟩ state.
+        apply(&mut self.sim, &mut self.error, &self.gates.reset, &[q]);
+        self.free.push(q);
+    }
+
+    fn qubit_swap_id(&mut self, q0: usize, q1: usize) {
+        // Swapping the states of the qubits is indistinguishable from relabeling them.
+        apply(&mut self.sim, &mut self.error, &self.gates.swap, &[q0, q1]);
+    }
+
+    fn capture_quantum_state(&mut self) -> (Vec<(BigUint, Complex<f64>)>, usize) {
+        let count = self.next_id;
+        let amplitudes = match self.sim.state() {
+            Ok(state) => state.amplitudes(),
+            Err(e) => {
+                self.error.get_or_insert(simulation_failed(e));
+                return (Vec::new(), 0);
+            }
+        };
+        let Some(amplitudes) = amplitudes else {
+            self.error.get_or_insert(
+                "the state is mixed and can't be shown as a state vector".to_string(),
+            );
+            return (Vec::new(), 0);
+        };
+        // The simulator stores qubit `k` in bit `k` of the index, while the expected
+        // order has qubit 0 as the most significant bit, so the bit order is reversed.
+        let mut state = amplitudes
+            .into_iter()
+            .enumerate()
+            .filter(|(_, val)| val.norm_sqr() > TOLERANCE)
+            .map(|(idx, val)| {
+                let mut new_idx = BigUint::default();
+                for i in 0..count {
+                    if idx & (1 << i) != 0 {
+                        new_idx.set_bit((count - 1 - i) as u64, true);
+                    }
+                }
+                (new_idx, val)
+            })
+            .collect::<Vec<_>>();
+        state.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        (state, count)
+    }
+
+    fn capture_quantum_state_for_qubits(
+        &mut self,
+        qubits: &[usize],
+    ) -> (Vec<(BigUint, Complex<f64>)>, usize) {
+        let (state, qubit_count) = self.capture_quantum_state();
+        match utils::split_state(qubits, &state, qubit_count) {
+            Ok(state) => (state, qubits.len()),
+            Err(()) => (Vec::default(), 0),
+        }
+    }
+
+    fn qubit_is_zero(&mut self, q: usize) -> bool {
+        let probabilities = match self.sim.state() {
+            Ok(state) => state.probabilities(),
+            Err(e) => {
+                self.error.get_or_insert(simulation_failed(e));
+                return true;
+            }
+        };
+        let one_probability: f64 = probabilities
+            .into_iter()
+            .enumerate()
+            .filter(|(idx, _)| idx & (1 << q) != 0)
+            .map(|(_, p)| p)
+            .sum();
+        one_probability < TOLERANCE
+    }
+
+    fn custom_intrinsic(&mut self, name: &str, arg: Value) -> Option<Result<Value, String>> {
+        match name {
+            "GlobalPhase" => {
+                // A global phase is only observable when it is controlled, in which case it
+                // becomes a phase on the all-ones state of the controls.
+                let [ctls_val, theta] = &*arg.unwrap_tuple() else {
+                    panic!("tuple arity for GlobalPhase intrinsic should be 2");
+                };
+                let ctls = ctls_val
+                    .clone()
+                    .unwrap_array()
+                    .iter()
+                    .map(|q| q.clone().unwrap_qubit().0)
+                    .collect::<Vec<_>>();
+                if !ctls.is_empty() {
+                    let dim = 1 << ctls.len();
+                    let mut entries = vec![re(1.0); dim];
+                    entries[dim - 1] = Complex::from_polar(1.0, theta.clone().unwrap_double());
+                    apply(
+                        &mut self.sim,
+                        &mut self.error,
+                        &unitary(diagonal(&entries)),
+                        &ctls,
+                    );
+                }
+                Some(Ok(Value::unit()))
+            }
+            "BeginEstimateCaching" => Some(Ok(Value::Bool(true))),
+            "EndEstimateCaching"
+            | "AccountForEstimatesInternal"
+            | "BeginRepeatEstimatesInternal"
+            | "EndRepeatEstimatesInternal" => Some(Ok(Value::unit())),
+            _ => None,
+        }
+    }
+
+    /// Seeding recreates the underlying simulator, so it must happen before any qubits
+    /// are allocated.
+    fn set_seed(&mut self, seed: Option<u64>) {
+        let seed = seed.unwrap_or_else(|| rand::thread_rng().next_u64());
+        self.sim = S::new_with_seed(self.capacity, seed);
+        self.free.clear();
+        self.next_id = 0;
+        self.error = None;
+    }
+
+    fn take_error(&mut self) -> Option<String> {
+        self.error.take()
+    }
+}
+
+fn check_probability(p: f64) -> Result<(), Error> {
+    if (0.0..=1.0).contains(&p) {
+        Ok(())
+    } else {
+        Err(Error::FailedToConstructOperation(format!(
+            "probability should be between 0 and 1, but it is {p}"
+        )))
+    }
+}
+
+fn re(value: f64) -> Complex<f64> {
+    Complex::new(value, 0.0)
+}
+
+fn im(value: f64) -> Complex<f64> {
+    Complex::new(0.0, value)
+}
+
+fn half_angle(theta: f64) -> (f64, f64) {
+    let (s, c) = (theta / 2.0).sin_cos();
+    (c, s)
+}
+
+/// Builds a `dim` x `dim` matrix from its entries in row-major order.
+fn matrix(dim: usize, entries: &[Complex<f64>]) -> SquareMatrix {
+    SquareMatrix::from_row_slice(dim, dim, entries)
+}
+
+fn diagonal(entries: &[Complex<f64>]) -> SquareMatrix {
+    let dim = entries.len();
+    let mut m = SquareMatrix::zeros(dim, dim);
+    for (i, entry) in entries.iter().enumerate() {
+        m[(i, i)] = *entry;
+    }
+    m
+}
+
+fn scaled(m: &SquareMatrix, factor: f64) -> SquareMatrix {
+    m.map(|entry| entry * factor)
+}
+
+/// Returns the matrix of the single-qubit gate `u` controlled on `controls` qubits, with the
+/// controls being the most significant qubits.
+fn controlled(controls: usize, u: &SquareMatrix) -> SquareMatrix {
+    let dim = 2 << controls;
+    let mut m = SquareMatrix::identity(dim, dim);
+    m.view_mut((dim - 2, dim - 2), (2, 2)).copy_from(u);
+    m
+}
+
+fn unitary(m: SquareMatrix) -> Operation {
+    Operation::new(vec![m]).expect("gate should be a valid operation")
+}
+
+fn paulis() -> (SquareMatrix, SquareMatrix, SquareMatrix, SquareMatrix) {
+    (
+        matrix(2, &[re(1.0), re(0.0), re(0.0), re(1.0)]),
+        matrix(2, &[re(0.0), re(1.0), re(1.0), re(0.0)]),
+        matrix(2, &[re(0.0), im(-1.0), im(1.0), re(0.0)]),
+        matrix(2, &[re(1.0), re(0.0), re(0.0), re(-1.0)]),
+    )
+}
+
+fn projectors() -> (SquareMatrix, SquareMatrix) {
+    (
+        matrix(2, &[re(1.0), re(0.0), re(0.0), re(0.0)]),
+        matrix(2, &[re(0.0), re(0.0), re(0.0), re(1.0)]),
+    )
+}
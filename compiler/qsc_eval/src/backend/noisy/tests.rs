@@ -0,0 +1,274 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{
+    depolarizing_channel, readout_error_measurement, DensityMatrixSim, NoiseModel, StateVectorSim,
+};
+use crate::backend::Backend;
+use expect_test::{expect, Expect};
+use noisy_simulator::{NoisySimulator, Operation};
+use std::fmt::Write;
+
+fn check_state(sim: &mut impl Backend, expect: &Expect) {
+    let (state, count) = sim.capture_quantum_state();
+    let mut out = format!("qubits: {count}\n");
+    // Rounding and adding zero avoids printing `-0.0000` for tiny negative values.
+    let round = |x: f64| (x * 1e4).round() / 1e4 + 0.0;
+    for (idx, val) in state {
+        writeln!(
+            out,
+            "{idx:0>count$b}: {:.4} {:+.4}i",
+            round(val.re),
+            round(val.im)
+        )
+        .expect("write should succeed");
+    }
+    expect.assert_eq(&out);
+}
+
+fn bell_pair(sim: &mut impl Backend) -> (usize, usize) {
+    let q0 = sim.qubit_allocate();
+    let q1 = sim.qubit_allocate();
+    sim.h(q0);
+    sim.cx(q0, q1);
+    (q0, q1)
+}
+
+#[test]
+fn state_vector_bell_pair_state() {
+    let mut sim = StateVectorSim::new(3, NoiseModel::default());
+    bell_pair(&mut sim);
+    check_state(
+        &mut sim,
+        &expect![[r#"
+            qubits: 2
+            00: 0.7071 +0.0000i
+            11: 0.7071 +0.0000i
+        "#]],
+    );
+}
+
+#[test]
+fn density_matrix_bell_pair_state() {
+    let mut sim = DensityMatrixSim::new(3, NoiseModel::default());
+    bell_pair(&mut sim);
+    check_state(
+        &mut sim,
+        &expect![[r#"
+            qubits: 2
+            00: 0.7071 +0.0000i
+            11: 0.7071 +0.0000i
+        "#]],
+    );
+}
+
+#[test]
+fn qubit_order_matches_sparse_sim() {
+    let mut sim = StateVectorSim::new(2, NoiseModel::default());
+    let _ = sim.qubit_allocate();
+    let q1 = sim.qubit_allocate();
+    sim.x(q1);
+    check_state(
+        &mut sim,
+        &expect![[r#"
+            qubits: 2
+            01: 1.0000 +0.0000i
+        "#]],
+    );
+}
+
+#[test]
+fn rotations_match_expected_amplitudes() {
+    let mut sim = StateVectorSim::new(2, NoiseModel::default());
+    let q0 = sim.qubit_allocate();
+    let q1 = sim.qubit_allocate();
+    sim.rx(std::f64::consts::FRAC_PI_2, q0);
+    sim.rzz(std::f64::consts::PI, q0, q1);
+    check_state(
+        &mut sim,
+        &expect![[r#"
+            qubits: 2
+            00: 0.0000 -0.7071i
+            10: 0.7071 +0.0000i
+        "#]],
+    );
+}
+
+#[test]
+fn controlled_gates_only_act_when_control_is_set() {
+    let mut sim = DensityMatrixSim::new(3, NoiseModel::default());
+    let c0 = sim.qubit_allocate();
+    let c1 = sim.qubit_allocate();
+    let t = sim.qubit_allocate();
+    sim.x(c0);
+    sim.ccx(c0, c1, t);
+    assert!(sim.qubit_is_zero(t));
+    sim.x(c1);
+    sim.ccx(c0, c1, t);
+    assert!(!sim.qubit_is_zero(t));
+    sim.cz(c0, t);
+    sim.swap(c1, t);
+    assert!(sim.m(t));
+    assert!(sim.m(c1));
+}
+
+#[test]
+fn mresetz_leaves_qubit_in_zero() {
+    let mut sim = StateVectorSim::new(1, NoiseModel::default());
+    let q = sim.qubit_allocate();
+    sim.x(q);
+    assert!(sim.mresetz(q));
+    assert!(sim.qubit_is_zero(q));
+    assert!(!sim.mresetz(q));
+}
+
+#[test]
+fn released_qubits_are_reset_and_reused() {
+    let mut sim = StateVectorSim::new(1, NoiseModel::default());
+    let q = sim.qubit_allocate();
+    sim.h(q);
+    sim.qubit_release(q);
+    let q = sim.qubit_allocate();
+    assert!(sim.qubit_is_zero(q));
+}
+
+#[test]
+fn allocating_beyond_capacity_reports_error() {
+    let mut sim = StateVectorSim::new(1, NoiseModel::default());
+    let _ = sim.qubit_allocate();
+    assert_eq!(sim.take_error(), None);
+    let _ = sim.qubit_allocate();
+    expect!["noisy simulation supports at most 1 simultaneously allocated qubits"]
+        .assert_eq(&sim.take_error().expect("error should be reported"));
+    assert_eq!(sim.take_error(), None);
+}
+
+#[test]
+fn bit_flip_noise_on_x_is_applied_after_gate() {
+    let bit_flip = Operation::new(vec![noisy_simulator::SquareMatrix::from_row_slice(
+        2,
+        2,
+        &[0.0.into(), 1.0.into(), 1.0.into(), 0.0.into()],
+    )])
+    .expect("operation should be valid");
+    let noise = NoiseModel {
+        x: Some(bit_flip),
+        ..Default::default()
+    };
+    let mut sim = DensityMatrixSim::new(1, noise);
+    let q = sim.qubit_allocate();
+    sim.x(q);
+    assert!(!sim.m(q));
+}
+
+#[test]
+fn readout_error_flips_reported_result_but_not_state() {
+    let noise = NoiseModel {
+        measurement: Some(readout_error_measurement(1.0).expect("instrument should be valid")),
+        ..Default::default()
+    };
+    let mut sim = StateVectorSim::new(1, noise);
+    let q = sim.qubit_allocate();
+    sim.x(q);
+    assert!(!sim.m(q));
+    assert!(!sim.qubit_is_zero(q));
+}
+
+#[test]
+fn full_depolarizing_noise_produces_mixed_state() {
+    let noise = NoiseModel {
+        h: Some(depolarizing_channel(0.75).expect("operation should be valid")),
+        ..Default::default()
+    };
+    let mut sim = DensityMatrixSim::new(1, noise);
+    let q = sim.qubit_allocate();
+    sim.h(q);
+    let state = sim.simulator().state().expect("state should be valid");
+    let data = state.data();
+    // A depolarizing channel with p = 3/4 maps every state to the maximally mixed state.
+    assert!((data[0].re - 0.5).abs() < 1e-9);
+    assert!((data[3].re - 0.5).abs() < 1e-9);
+    assert!(data[1].norm() < 1e-9);
+    assert!(data[2].norm() < 1e-9);
+}
+
+#[test]
+fn mixed_state_is_not_captured_as_amplitudes() {
+    let noise = NoiseModel {
+        h: Some(depolarizing_channel(0.5).expect("operation should be valid")),
+        ..Default::default()
+    };
+    let mut sim = DensityMatrixSim::new(1, noise);
+    let q = sim.qubit_allocate();
+    sim.h(q);
+    assert_eq!(sim.capture_quantum_state(), (Vec::new(), 0));
+    expect!["the state is mixed and can't be shown as a state vector"]
+        .assert_eq(&sim.take_error().expect("error should be reported"));
+}
+
+#[test]
+fn gate_noise_is_set_by_name() {
+    let mut noise = NoiseModel::default();
+    let bit_flip = Operation::new(vec![noisy_simulator::SquareMatrix::from_row_slice(
+        2,
+        2,
+        &[0.0.into(), 1.0.into(), 1.0.into(), 0.0.into()],
+    )])
+    .expect("operation should be valid");
+    noise
+        .set_gate_noise("h", bit_flip.clone())
+        .expect("noise should be set");
+    assert!(noise.h.is_some());
+    expect!["there is no gate named `foo`"].assert_eq(
+        &noise
+            .set_gate_noise("foo", bit_flip.clone())
+            .expect_err("gate should be unknown"),
+    );
+    expect!["the noise for `cx` should act on 2 qubit(s), but it acts on 1"].assert_eq(
+        &noise
+            .set_gate_noise("cx", bit_flip)
+            .expect_err("noise should have the wrong size"),
+    );
+}
+
+#[test]
+fn operation_from_rows_matches_gate() {
+    let (o, l) = (0.0.into(), 1.0.into());
+    let bit_flip = super::operation_from_rows(&[vec![vec![o, l], vec![l, o]]])
+        .expect("operation should be valid");
+    let noise = NoiseModel {
+        h: Some(bit_flip),
+        ..Default::default()
+    };
+    let mut sim = StateVectorSim::new(1, noise);
+    let q = sim.qubit_allocate();
+    sim.h(q);
+    sim.h(q);
+    assert!(!sim.qubit_is_zero(q));
+    assert!(super::operation_from_rows(&[vec![vec![o, l]]]).is_err());
+}
+
+#[test]
+fn invalid_probability_is_rejected() {
+    assert!(depolarizing_channel(1.5).is_err());
+    assert!(NoiseModel::depolarizing(0.01, -0.1, 0.0).is_err());
+}
+
+#[test]
+fn seeded_runs_are_reproducible() {
+    let sample = |seed| {
+        let mut sim = StateVectorSim::new(
+            1,
+            NoiseModel::depolarizing(0.2, 0.2, 0.1).expect("valid model"),
+        );
+        sim.set_seed(Some(seed));
+        let q = sim.qubit_allocate();
+        (0..32)
+            .map(|_| {
+                sim.h(q);
+                sim.mresetz(q)
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(sample(42), sample(42));
+}
@@ -0,0 +1,591 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! A stabilizer simulator backend, which tracks the state of the qubits as a stabilizer tableau
+//! (Aaronson and Gottesman, <https://arxiv.org/abs/quant-ph/0406196>). It simulates Clifford
+//! circuits on thousands of qubits, and reports an error for any non-Clifford operation.
+
+#[cfg(test)]
+mod tests;
+
+use super::Backend;
+use crate::{intrinsic::utils, val::Value};
+use num_bigint::BigUint;
+use num_complex::Complex;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::f64::consts::FRAC_PI_2;
+
+/// The largest number of qubits whose state can be dumped, since doing so requires expanding
+/// the tableau into a full state vector.
+pub const MAX_DUMP_QUBITS: usize = 20;
+
+const TOLERANCE: f64 = 1e-9;
+
+/// A Pauli operator on every qubit with a sign, stored as packed bits. A qubit with both its
+/// `x` and `z` bits set has a `Y` on it.
+#[derive(Clone, Debug)]
+struct PauliRow {
+    x: Vec<u64>,
+    z: Vec<u64>,
+    negative: bool,
+}
+
+impl PauliRow {
+    fn identity(words: usize) -> Self {
+        Self {
+            x: vec![0; words],
+            z: vec![0; words],
+            negative: false,
+        }
+    }
+
+    fn get(&self, q: usize) -> (bool, bool) {
+        let (word, bit) = (q / 64, 1 << (q % 64));
+        (self.x[word] & bit != 0, self.z[word] & bit != 0)
+    }
+
+    fn set(&mut self, q: usize, x: bool, z: bool) {
+        let (word, bit) = (q / 64, 1 << (q % 64));
+        self.x[word] = if x {
+            self.x[word] | bit
+        } else {
+            self.x[word] & !bit
+        };
+        self.z[word] = if z {
+            self.z[word] | bit
+        } else {
+            self.z[word] & !bit
+        };
+    }
+
+    fn has_x(&self, q: usize) -> bool {
+        self.get(q).0
+    }
+
+    /// Replaces this operator with its product with `other`, tracking the resulting sign.
+    fn multiply_by(&mut self, other: &Self) {
+        // Each qubit contributes a power of `i` to the product, depending on which Paulis are
+        // multiplied; the bit masks below count the qubits contributing `i` and `-i`.
+        let mut phase = 2 * (i64::from(self.negative) + i64::from(other.negative));
+        for word in 0..self.x.len() {
+            let (x1, z1, x2, z2) = (other.x[word], other.z[word], self.x[word], self.z[word]);
+            let plus = (x1 & z1 & z2 & !x2) | (x1 & !z1 & z2 & x2) | (!x1 & z1 & x2 & !z2);
+            let minus = (x1 & z1 & x2 & !z2) | (x1 & !z1 & z2 & !x2) | (!x1 & z1 & x2 & z2);
+            phase += i64::from(plus.count_ones()) - i64::from(minus.count_ones());
+            self.x[word] ^= x1;
+            self.z[word] ^= z1;
+        }
+        self.negative = phase.rem_euclid(4) == 2;
+    }
+
+    /// Applies this operator to the basis state `index`, where bit `q` of the index is the state
+    /// of qubit `q`, returning the resulting basis state and its phase.
+    fn apply_to_basis_state(&self, index: usize) -> (usize, Complex<f64>) {
+        // Only the first word is used, since states are only expanded for a few qubits.
+        #[allow(clippy::cast_possible_truncation)]
+        let (x, z) = (self.x[0] as usize, self.z[0] as usize);
+        // Y = iXZ, so each Y contributes a factor of i, and each Z (or Y) a sign for a set bit.
+        let mut phase = Complex::<f64>::i().powu((x & z).count_ones());
+        if (z & index).count_ones() % 2 == 1 {
+            phase = -phase;
+        }
+        if self.negative {
+            phase = -phase;
+        }
+        (index ^ x, phase)
+    }
+}
+
+/// The stabilizer tableau of the state, made up of the stabilizer generators of the state and
+/// their destabilizers. Row `i` of each describes qubit `i`.
+#[derive(Clone, Debug, Default)]
+struct Tableau {
+    destabilizers: Vec<PauliRow>,
+    stabilizers: Vec<PauliRow>,
+}
+
+impl Tableau {
+    fn num_qubits(&self) -> usize {
+        self.stabilizers.len()
+    }
+
+    /// Adds a qubit in the |0⟩ state.
+    fn add_qubit(&mut self) -> usize {
+        let q = self.num_qubits();
+        let words = (q + 1).div_ceil(64);
+        for row in self.destabilizers.iter_mut().chain(&mut self.stabilizers) {
+            row.x.resize(words, 0);
+            row.z.resize(words, 0);
+        }
+        let mut destabilizer = PauliRow::identity(words);
+        destabilizer.set(q, true, false);
+        self.destabilizers.push(destabilizer);
+        let mut stabilizer = PauliRow::identity(words);
+        stabilizer.set(q, false, true);
+        self.stabilizers.push(stabilizer);
+        q
+    }
+
+    fn rows(&mut self) -> impl Iterator<Item = &mut PauliRow> {
+        self.destabilizers.iter_mut().chain(&mut self.stabilizers)
+    }
+
+    fn h(&mut self, q: usize) {
+        for row in self.rows() {
+            let (x, z) = row.get(q);
+            row.negative ^= x && z;
+            row.set(q, z, x);
+        }
+    }
+
+    fn s(&mut self, q: usize) {
+        for row in self.rows() {
+            let (x, z) = row.get(q);
+            row.negative ^= x && z;
+            row.set(q, x, z ^ x);
+        }
+    }
+
+    fn sadj(&mut self, q: usize) {
+        for row in self.rows() {
+            let (x, z) = row.get(q);
+            row.negative ^= x && !z;
+            row.set(q, x, z ^ x);
+        }
+    }
+
+    fn x(&mut self, q: usize) {
+        for row in self.rows() {
+            row.negative ^= row.get(q).1;
+        }
+    }
+
+    fn y(&mut self, q: usize) {
+        for row in self.rows() {
+            let (x, z) = row.get(q);
+            row.negative ^= x ^ z;
+        }
+    }
+
+    fn z(&mut self, q: usize) {
+        for row in self.rows() {
+            row.negative ^= row.get(q).0;
+        }
+    }
+
+    fn cx(&mut self, ctl: usize, q: usize) {
+        for row in self.rows() {
+            let (x_ctl, z_ctl) = row.get(ctl);
+            let (x_q, z_q) = row.get(q);
+            row.negative ^= x_ctl && z_q && !(x_q ^ z_ctl);
+            row.set(q, x_q ^ x_ctl, z_q);
+            row.set(ctl, x_ctl, z_ctl ^ z_q);
+        }
+    }
+
+    fn swap(&mut self, q0: usize, q1: usize) {
+        for row in self.rows() {
+            let (x0, z0) = row.get(q0);
+            let (x1, z1) = row.get(q1);
+            row.set(q0, x1, z1);
+            row.set(q1, x0, z0);
+        }
+    }
+
+    /// Measures qubit `q` in the Z basis. If the outcome is not determined by the state,
+    /// `random_outcome` is called to choose it.
+    fn measure(&mut self, q: usize, random_outcome: impl FnOnce() -> bool) -> bool {
+        let Some(pivot) = (0..self.num_qubits()).find(|&i| self.stabilizers[i].has_x(q)) else {
+            return self.deterministic_outcome(q);
+        };
+
+        // Every other row that anticommutes with Z on the qubit is multiplied by the pivot so
+        // that it commutes with the measurement.
+        let pivot_row = self.stabilizers[pivot].clone();
+        for (i, row) in self.stabilizers.iter_mut().enumerate() {
+            if i != pivot && row.has_x(q) {
+                row.multiply_by(&pivot_row);
+            }
+        }
+        for row in &mut self.destabilizers {
+            if row.has_x(q) {
+                row.multiply_by(&pivot_row);
+            }
+        }
+
+        let outcome = random_outcome();
+        let mut measured = PauliRow::identity(pivot_row.x.len());
+        measured.set(q, false, true);
+        measured.negative = outcome;
+        self.destabilizers[pivot] = pivot_row;
+        self.stabilizers[pivot] = measured;
+        outcome
+    }
+
+    /// Returns whether measuring qubit `q` is certain to give a `One`.
+    /// The outcome must be determined by the state.
+    fn deterministic_outcome(&self, q: usize) -> bool {
+        let mut product = PauliRow::identity(self.stabilizers.first().map_or(0, |row| row.x.len()));
+        for (destabilizer, stabilizer) in self.destabilizers.iter().zip(&self.stabilizers) {
+            if destabilizer.has_x(q) {
+                product.multiply_by(stabilizer);
+            }
+        }
+        product.negative
+    }
+
+    fn is_deterministic(&self, q: usize) -> bool {
+        !self.stabilizers.iter().any(|row| row.has_x(q))
+    }
+
+    /// Expands the tableau into a state vector, where bit `q` of each index is the state of
+    /// qubit `q`. The global phase is chosen so that the first nonzero amplitude is positive.
+    fn state_vector(&self) -> Vec<Complex<f64>> {
+        // Collapsing a copy of the state gives a basis state in the support of the state. The
+        // state is then recovered by projecting that basis state onto the stabilized subspace.
+        let mut collapsed = self.clone();
+        let support = (0..self.num_qubits())
+            .filter(|&q| collapsed.measure(q, || false))
+            .fold(0, |index, q| index | (1 << q));
+
+        let mut state = vec![Complex::new(0.0, 0.0); 1 << self.num_qubits()];
+        state[support] = Complex::new(1.0, 0.0);
+        for stabilizer in &self.stabilizers {
+            let mut projected = state.clone();
+            for (index, amplitude) in state.iter().enumerate() {
+                if amplitude.norm_sqr() > 0.0 {
+                    let (target, phase) = stabilizer.apply_to_basis_state(index);
+                    projected[target] += phase * amplitude;
+                }
+            }
+            state = projected;
+        }
+
+        let norm = state.iter().map(Complex::norm_sqr).sum::<f64>().sqrt();
+        state
+            .into_iter()
+            .map(|amplitude| amplitude / norm)
+            .collect()
+    }
+}
+
+/// Returns the number of quarter turns, between 0 and 3, that a rotation by `theta` makes, or
+/// `None` if `theta` is not a multiple of π/2 and so the rotation is not a Clifford operation.
+fn quarter_turns(theta: f64) -> Option<u8> {
+    let turns = theta / FRAC_PI_2;
+    let rounded = turns.round();
+    if (turns - rounded).abs() < TOLERANCE {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // Always between 0 and 3
+        let quarter_turns = rounded.rem_euclid(4.0) as u8;
+        Some(quarter_turns)
+    } else {
+        None
+    }
+}
+
+/// A backend that simulates Clifford circuits using a stabilizer tableau.
+///
+/// Simulation time and memory grow polynomially with the number of qubits, so programs using
+/// many qubits can be simulated as long as they only use Clifford operations: `H`, `S`, the
+/// Pauli gates, `CNOT`, `CY`, `CZ`, `SWAP`, measurements, resets and rotations by multiples
+/// of π/2. Any other operation fails the program with an error reported through
+/// [`Backend::take_error`].
+pub struct StabilizerSim {
+    tableau: Tableau,
+    free: Vec<usize>,
+    rng: StdRng,
+    error: Option<String>,
+}
+
+impl Default for StabilizerSim {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StabilizerSim {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            tableau: Tableau::default(),
+            free: Vec::new(),
+            rng: StdRng::from_entropy(),
+            error: None,
+        }
+    }
+
+    fn unsupported(&mut self, message: String) {
+        // Only the first error is kept, since it is the one that ends the program.
+        self.error.get_or_insert(message);
+    }
+
+    fn non_clifford(&mut self, gate: &str) {
+        self.unsupported(format!(
+            "the stabilizer simulator only supports Clifford operations, and {gate} is not a Clifford operation"
+        ));
+    }
+
+    fn non_clifford_rotation(&mut self, gate: &str, theta: f64) {
+        self.unsupported(format!(
+            "the stabilizer simulator only supports rotations by multiples of π/2, but {gate} was called with an angle of {theta}"
+        ));
+    }
+
+    fn measure(&mut self, q: usize) -> bool {
+        let rng = &mut self.rng;
+        self.tableau.measure(q, || rng.gen_bool(0.5))
+    }
+
+    /// Applies a phase of `e^(iθ)` to the |1⟩ state of `q`, if it is a Clifford operation.
+    fn phase(&mut self, gate: &str, theta: f64, q: usize) {
+        match quarter_turns(theta) {
+            Some(0) => {}
+            Some(1) => self.tableau.s(q),
+            Some(2) => self.tableau.z(q),
+            Some(3) => self.tableau.sadj(q),
+            _ => self.non_clifford_rotation(gate, theta),
+        }
+    }
+}
+
+impl Backend for StabilizerSim {
+    type ResultType = bool;
+
+    fn ccx(&mut self, _ctl0: usize, _ctl1: usize, _q: usize) {
+        self.non_clifford("CCNOT");
+    }
+
+    fn cx(&mut self, ctl: usize, q: usize) {
+        self.tableau.cx(ctl, q);
+    }
+
+    fn cy(&mut self, ctl: usize, q: usize) {
+        self.tableau.sadj(q);
+        self.tableau.cx(ctl, q);
+        self.tableau.s(q);
+    }
+
+    fn cz(&mut self, ctl: usize, q: usize) {
+        self.tableau.h(q);
+        self.tableau.cx(ctl, q);
+        self.tableau.h(q);
+    }
+
+    fn h(&mut self, q: usize) {
+        self.tableau.h(q);
+    }
+
+    fn m(&mut self, q: usize) -> Self::ResultType {
+        self.measure(q)
+    }
+
+    fn mresetz(&mut self, q: usize) -> Self::ResultType {
+        let result = self.measure(q);
+        if result {
+            self.tableau.x(q);
+        }
+        result
+    }
+
+    fn reset(&mut self, q: usize) {
+        self.mresetz(q);
+    }
+
+    fn rx(&mut self, theta: f64, q: usize) {
+        if quarter_turns(theta).is_none() {
+            self.non_clifford_rotation("Rx", theta);
+            return;
+        }
+        self.tableau.h(q);
+        self.phase("Rx", theta, q);
+        self.tableau.h(q);
+    }
+
+    fn rxx(&mut self, theta: f64, q0: usize, q1: usize) {
+        if quarter_turns(theta).is_none() {
+            self.non_clifford_rotation("Rxx", theta);
+            return;
+        }
+        self.tableau.h(q0);
+        self.tableau.h(q1);
+        self.rzz(theta, q0, q1);
+        self.tableau.h(q1);
+        self.tableau.h(q0);
+    }
+
+    fn ry(&mut self, theta: f64, q: usize) {
+        if quarter_turns(theta).is_none() {
+            self.non_clifford_rotation("Ry", theta);
+            return;
+        }
+        // Ry(θ) = S Rx(θ) S†
+        self.tableau.sadj(q);
+        self.rx(theta, q);
+        self.tableau.s(q);
+    }
+
+    fn ryy(&mut self, theta: f64, q0: usize, q1: usize) {
+        if quarter_turns(theta).is_none() {
+            self.non_clifford_rotation("Ryy", theta);
+            return;
+        }
+        self.tableau.sadj(q0);
+        self.tableau.sadj(q1);
+        self.rxx(theta, q0, q1);
+        self.tableau.s(q1);
+        self.tableau.s(q0);
+    }
+
+    fn rz(&mut self, theta: f64, q: usize) {
+        // Up to a global phase, Rz(θ) applies a phase of e^(iθ) to |1⟩.
+        self.phase("Rz", theta, q);
+    }
+
+    fn rzz(&mut self, theta: f64, q0: usize, q1: usize) {
+        if quarter_turns(theta).is_none() {
+            self.non_clifford_rotation("Rzz", theta);
+            return;
+        }
+        self.tableau.cx(q0, q1);
+        self.phase("Rzz", theta, q1);
+        self.tableau.cx(q0, q1);
+    }
+
+    fn sadj(&mut self, q: usize) {
+        self.tableau.sadj(q);
+    }
+
+    fn s(&mut self, q: usize) {
+        self.tableau.s(q);
+    }
+
+    fn swap(&mut self, q0: usize, q1: usize) {
+        self.tableau.swap(q0, q1);
+    }
+
+    fn tadj(&mut self, _q: usize) {
+        self.non_clifford("Adjoint T");
+    }
+
+    fn t(&mut self, _q: usize) {
+        self.non_clifford("T");
+    }
+
+    fn x(&mut self, q: usize) {
+        self.tableau.x(q);
+    }
+
+    fn y(&mut self, q: usize) {
+        self.tableau.y(q);
+    }
+
+    fn z(&mut self, q: usize) {
+        self.tableau.z(q);
+    }
+
+    fn qubit_allocate(&mut self) -> usize {
+        match self.free.pop() {
+            Some(q) => q,
+            None => self.tableau.add_qubit(),
+        }
+    }
+
+    fn qubit_release(&mut self, q: usize) {
+        // Released qubits are reset so that they can be handed out again in the |0⟩ state.
+        self.reset(q);
+        self.free.push(q);
+    }
+
+    fn qubit_swap_id(&mut self, q0: usize, q1: usize) {
+        // Swapping the states of the qubits is indistinguishable from relabeling them.
+        self.tableau.swap(q0, q1);
+    }
+
+    fn capture_quantum_state(&mut self) -> (Vec<(BigUint, Complex<f64>)>, usize) {
+        let count = self.tableau.num_qubits();
+        if count > MAX_DUMP_QUBITS {
+            self.unsupported(format!(
+                "the stabilizer simulator can only dump the state of up to {MAX_DUMP_QUBITS} qubits, but {count} qubits are in use"
+            ));
+            return (Vec::new(), 0);
+        }
+        // The tableau stores qubit `k` in bit `k` of the index, while the expected
+        // order has qubit 0 as the most significant bit, so the bit order is reversed.
+        let mut state = self
+            .tableau
+            .state_vector()
+            .into_iter()
+            .enumerate()
+            .filter(|(_, val)| val.norm_sqr() > TOLERANCE)
+            .map(|(idx, val)| {
+                let mut new_idx = BigUint::default();
+                for i in 0..count {
+                    if idx & (1 << i) != 0 {
+                        new_idx.set_bit((count - 1 - i) as u64, true);
+                    }
+                }
+                (new_idx, val)
+            })
+            .collect::<Vec<_>>();
+        state.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        (state, count)
+    }
+
+    fn capture_quantum_state_for_qubits(
+        &mut self,
+        qubits: &[usize],
+    ) -> (Vec<(BigUint, Complex<f64>)>, usize) {
+        let (state, qubit_count) = self.capture_quantum_state();
+        match utils::split_state(qubits, &state, qubit_count) {
+            Ok(state) => (state, qubits.len()),
+            Err(()) => (Vec::default(), 0),
+        }
+    }
+
+    fn qubit_is_zero(&mut self, q: usize) -> bool {
+        self.tableau.is_deterministic(q) && !self.tableau.deterministic_outcome(q)
+    }
+
+    fn custom_intrinsic(&mut self, name: &str, arg: Value) -> Option<Result<Value, String>> {
+        match name {
+            "GlobalPhase" => {
+                // A global phase is only observable when it is controlled, in which case it
+                // becomes a phase on the all-ones state of the controls.
+                let [ctls_val, theta] = &*arg.unwrap_tuple() else {
+                    panic!("tuple arity for GlobalPhase intrinsic should be 2");
+                };
+                let ctls = ctls_val
+                    .clone()
+                    .unwrap_array()
+                    .iter()
+                    .map(|q| q.clone().unwrap_qubit().0)
+                    .collect::<Vec<_>>();
+                let theta = theta.clone().unwrap_double();
+                match (ctls.as_slice(), quarter_turns(theta)) {
+                    ([], _) | (_, Some(0)) => {}
+                    ([q], _) => self.phase("a controlled global phase", theta, *q),
+                    ([ctl, q], Some(2)) => self.cz(*ctl, *q),
+                    _ => self.non_clifford_rotation("a controlled global phase", theta),
+                }
+                Some(Ok(Value::unit()))
+            }
+            "BeginEstimateCaching" => Some(Ok(Value::Bool(true))),
+            "EndEstimateCaching"
+            | "AccountForEstimatesInternal"
+            | "BeginRepeatEstimatesInternal"
+            | "EndRepeatEstimatesInternal" => Some(Ok(Value::unit())),
+            _ => None,
+        }
+    }
+
+    fn set_seed(&mut self, seed: Option<u64>) {
+        self.rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+    }
+
+    fn take_error(&mut self) -> Option<String> {
+        self.error.take()
+    }
+}
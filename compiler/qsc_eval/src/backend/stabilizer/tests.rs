@@ -0,0 +1,221 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{StabilizerSim, MAX_DUMP_QUBITS};
+use crate::backend::{Backend, SparseSim};
+use expect_test::{expect, Expect};
+use num_bigint::BigUint;
+use num_complex::Complex;
+use std::f64::consts::{FRAC_PI_2, PI};
+use std::fmt::Write;
+
+fn check_state(sim: &mut impl Backend, expect: &Expect) {
+    let (state, count) = sim.capture_quantum_state();
+    let mut out = format!("qubits: {count}\n");
+    // Rounding and adding zero avoids printing `-0.0000` for tiny negative values.
+    let round = |x: f64| (x * 1e4).round() / 1e4 + 0.0;
+    for (idx, val) in state {
+        writeln!(
+            out,
+            "{idx:0>count$b}: {:.4} {:+.4}i",
+            round(val.re),
+            round(val.im)
+        )
+        .expect("write should succeed");
+    }
+    expect.assert_eq(&out);
+}
+
+#[test]
+fn bell_pair_state() {
+    let mut sim = StabilizerSim::new();
+    let q0 = sim.qubit_allocate();
+    let q1 = sim.qubit_allocate();
+    sim.h(q0);
+    sim.cx(q0, q1);
+    check_state(
+        &mut sim,
+        &expect![[r#"
+            qubits: 2
+            00: 0.7071 +0.0000i
+            11: 0.7071 +0.0000i
+        "#]],
+    );
+}
+
+#[test]
+fn phases_are_tracked() {
+    let mut sim = StabilizerSim::new();
+    let q0 = sim.qubit_allocate();
+    let q1 = sim.qubit_allocate();
+    sim.h(q0);
+    sim.s(q0);
+    sim.h(q1);
+    sim.z(q1);
+    check_state(
+        &mut sim,
+        &expect![[r#"
+            qubits: 2
+            00: 0.5000 +0.0000i
+            01: -0.5000 +0.0000i
+            10: 0.0000 +0.5000i
+            11: 0.0000 -0.5000i
+        "#]],
+    );
+}
+
+#[test]
+fn qubit_order_matches_sparse_sim() {
+    let mut sim = StabilizerSim::new();
+    let _ = sim.qubit_allocate();
+    let q1 = sim.qubit_allocate();
+    sim.x(q1);
+    check_state(
+        &mut sim,
+        &expect![[r#"
+            qubits: 2
+            01: 1.0000 +0.0000i
+        "#]],
+    );
+}
+
+#[test]
+fn clifford_circuit_matches_sparse_sim() {
+    fn circuit(sim: &mut impl Backend) {
+        let qs = (0..4).map(|_| sim.qubit_allocate()).collect::<Vec<_>>();
+        sim.h(qs[0]);
+        sim.cy(qs[0], qs[1]);
+        sim.sadj(qs[1]);
+        sim.h(qs[2]);
+        sim.cz(qs[2], qs[1]);
+        sim.y(qs[2]);
+        sim.swap(qs[1], qs[3]);
+        sim.rx(FRAC_PI_2, qs[0]);
+        sim.ryy(PI, qs[0], qs[2]);
+        sim.rzz(-FRAC_PI_2, qs[2], qs[3]);
+    }
+
+    let mut stabilizer = StabilizerSim::new();
+    circuit(&mut stabilizer);
+    let mut sparse = SparseSim::new();
+    circuit(&mut sparse);
+
+    // The simulators may disagree on the global phase, so the amplitudes are compared after
+    // dividing them by the first one.
+    let normalize = |(state, _): (Vec<(BigUint, Complex<f64>)>, usize)| {
+        let first = state[0].1;
+        state
+            .into_iter()
+            .map(|(idx, val)| (idx, val / first))
+            .collect::<Vec<_>>()
+    };
+    let expected = normalize(sparse.capture_quantum_state());
+    let actual = normalize(stabilizer.capture_quantum_state());
+    assert_eq!(actual.len(), expected.len());
+    for ((actual_idx, actual_val), (expected_idx, expected_val)) in actual.iter().zip(&expected) {
+        assert_eq!(actual_idx, expected_idx);
+        assert!(
+            (actual_val - expected_val).norm() < 1e-9,
+            "{actual_val} != {expected_val}"
+        );
+    }
+}
+
+#[test]
+fn ghz_state_on_many_qubits_is_correlated() {
+    let mut sim = StabilizerSim::new();
+    sim.set_seed(Some(1));
+    let qs = (0..1000).map(|_| sim.qubit_allocate()).collect::<Vec<_>>();
+    sim.h(qs[0]);
+    for pair in qs.windows(2) {
+        sim.cx(pair[0], pair[1]);
+    }
+    let first = sim.mresetz(qs[0]);
+    assert!(qs[1..].iter().all(|&q| sim.mresetz(q) == first));
+    assert!(qs.iter().all(|&q| sim.qubit_is_zero(q)));
+}
+
+#[test]
+fn measurement_collapses_state() {
+    let mut sim = StabilizerSim::new();
+    let q = sim.qubit_allocate();
+    sim.h(q);
+    let result = sim.m(q);
+    assert!((0..10).all(|_| sim.m(q) == result));
+    assert_eq!(sim.qubit_is_zero(q), !result);
+}
+
+#[test]
+fn reset_returns_qubit_to_zero() {
+    let mut sim = StabilizerSim::new();
+    let q = sim.qubit_allocate();
+    sim.h(q);
+    sim.s(q);
+    assert!(!sim.qubit_is_zero(q));
+    sim.reset(q);
+    assert!(sim.qubit_is_zero(q));
+}
+
+#[test]
+fn released_qubits_are_reused() {
+    let mut sim = StabilizerSim::new();
+    let q = sim.qubit_allocate();
+    sim.x(q);
+    sim.qubit_release(q);
+    assert_eq!(sim.qubit_allocate(), q);
+    assert!(sim.qubit_is_zero(q));
+}
+
+#[test]
+fn clifford_rotations_are_supported() {
+    let mut sim = StabilizerSim::new();
+    let q = sim.qubit_allocate();
+    sim.rx(PI, q);
+    assert!(!sim.qubit_is_zero(q));
+    sim.ry(-PI, q);
+    sim.rz(3.0 * FRAC_PI_2, q);
+    assert!(sim.qubit_is_zero(q));
+    assert_eq!(sim.take_error(), None);
+}
+
+#[test]
+fn non_clifford_gates_report_an_error() {
+    let mut sim = StabilizerSim::new();
+    let q = sim.qubit_allocate();
+    sim.t(q);
+    expect!["the stabilizer simulator only supports Clifford operations, and T is not a Clifford operation"]
+        .assert_eq(&sim.take_error().expect("error should be reported"));
+    assert_eq!(sim.take_error(), None);
+    sim.rx(0.1, q);
+    expect!["the stabilizer simulator only supports rotations by multiples of π/2, but Rx was called with an angle of 0.1"]
+        .assert_eq(&sim.take_error().expect("error should be reported"));
+    assert!(sim.qubit_is_zero(q));
+}
+
+#[test]
+fn dumping_too_many_qubits_reports_an_error() {
+    let mut sim = StabilizerSim::new();
+    for _ in 0..=MAX_DUMP_QUBITS {
+        sim.qubit_allocate();
+    }
+    let (state, count) = sim.capture_quantum_state();
+    assert!(state.is_empty());
+    assert_eq!(count, 0);
+    assert!(sim.take_error().is_some());
+}
+
+#[test]
+fn seeded_runs_are_reproducible() {
+    let sample = |seed| {
+        let mut sim = StabilizerSim::new();
+        sim.set_seed(Some(seed));
+        let q = sim.qubit_allocate();
+        (0..32)
+            .map(|_| {
+                sim.h(q);
+                sim.mresetz(q)
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(sample(42), sample(42));
+}
@@ -14,6 +14,7 @@ use crate::{
     Error, QubitSpans, Rc,
 };
 use num_bigint::BigInt;
+use num_complex::Complex64;
 use rand::{rngs::StdRng, Rng};
 use rustc_hash::{FxHashMap, FxHashSet};
 use std::array;
@@ -101,6 +102,72 @@ pub(crate) fn call(
                 Err(_) => Err(Error::OutputFail(name_span)),
             }
         }
+        "AssertStateApprox" => {
+            let [register, amplitudes, tolerance] = unwrap_tuple(arg);
+            let qubits = unwrap_distinct_qubits(register, arg_span)?;
+            let expected = amplitudes
+                .unwrap_array()
+                .iter()
+                .map(|amplitude| {
+                    let [re, im] = unwrap_tuple(amplitude.clone());
+                    Complex64::new(re.unwrap_double(), im.unwrap_double())
+                })
+                .collect::<Vec<_>>();
+            let amplitude_count = u32::try_from(qubits.len())
+                .ok()
+                .and_then(|count| 1_usize.checked_shl(count));
+            if amplitude_count != Some(expected.len()) {
+                return Err(Error::IntrinsicFail(
+                    name.to_string(),
+                    format!(
+                        "expected {} amplitudes for {} qubits, found {}",
+                        amplitude_count.map_or_else(
+                            || format!("2^{}", qubits.len()),
+                            |count| count.to_string()
+                        ),
+                        qubits.len(),
+                        expected.len()
+                    ),
+                    arg_span,
+                ));
+            }
+            let (state, qubit_count) = sim.capture_quantum_state_for_qubits(&qubits);
+            if state.is_empty() {
+                return Err(Error::QubitsNotSeparable(arg_span));
+            }
+            utils::compare_states(&state, &expected, qubit_count, tolerance.unwrap_double())
+                .map_err(|diff| Error::AssertionFailed(diff, name_span))?;
+            Ok(Value::unit())
+        }
+        "AssertRegistersEqualApprox" => {
+            let [actual, expected, tolerance] = unwrap_tuple(arg);
+            let actual = unwrap_distinct_qubits(actual, arg_span)?;
+            let expected = unwrap_distinct_qubits(expected, arg_span)?;
+            if actual.len() != expected.len() {
+                return Err(Error::AssertionFailed(
+                    format!(
+                        "registers have different lengths: actual has {} qubits, expected has {}",
+                        actual.len(),
+                        expected.len()
+                    ),
+                    name_span,
+                ));
+            }
+            let (expected_state, qubit_count) = sim.capture_quantum_state_for_qubits(&expected);
+            let (actual_state, _) = sim.capture_quantum_state_for_qubits(&actual);
+            if expected_state.is_empty() || actual_state.is_empty() {
+                return Err(Error::QubitsNotSeparable(arg_span));
+            }
+            let expected_state = utils::to_dense_state(&expected_state, qubit_count);
+            utils::compare_states(
+                &actual_state,
+                &expected_state,
+                qubit_count,
+                tolerance.unwrap_double(),
+            )
+            .map_err(|diff| Error::AssertionFailed(diff, name_span))?;
+            Ok(Value::unit())
+        }
         "PermuteLabels" => qubit_relabel(arg, arg_span, |q0, q1| sim.qubit_swap_id(q0, q1)),
         "Message" => match out.message(&arg.unwrap_string()) {
             Ok(()) => Ok(Value::unit()),
@@ -356,6 +423,20 @@ pub fn qubit_relabel(
     Ok(Value::unit())
 }
 
+/// Unwraps an array of qubits into their ids, failing if any qubit appears more than once.
+fn unwrap_distinct_qubits(value: Value, arg_span: PackageSpan) -> Result<Vec<usize>, Error> {
+    let qubits = value
+        .unwrap_array()
+        .iter()
+        .map(|q| q.clone().unwrap_qubit().0)
+        .collect::<Vec<_>>();
+    if qubits.len() == qubits.iter().collect::<FxHashSet<_>>().len() {
+        Ok(qubits)
+    } else {
+        Err(Error::QubitUniqueness(arg_span))
+    }
+}
+
 fn unwrap_tuple<const N: usize>(value: Value) -> [Value; N] {
     let values = value.unwrap_tuple();
     array::from_fn(|i| values[i].clone())
@@ -7,19 +7,23 @@ use crate::backend::{Backend, SparseSim};
 use crate::tests::eval_graph;
 use crate::Env;
 use crate::{
+    error::PackageSpan,
     output::{GenericReceiver, Receiver},
-    val::Value,
-    Error,
+    val::{Qubit, Value},
+    Error, QubitSpans,
 };
 use expect_test::{expect, Expect};
 use indoc::indoc;
 use num_bigint::BigInt;
 use qsc_data_structures::language_features::LanguageFeatures;
+use qsc_data_structures::span::Span;
 use qsc_data_structures::target::TargetCapabilityFlags;
 use qsc_fir::fir;
 use qsc_frontend::compile::{self, compile, PackageStore, SourceMap};
+use qsc_hir::hir::PackageId;
 use qsc_lowerer::map_hir_package_to_fir;
 use qsc_passes::{run_core_passes, run_default_passes, PackageType};
+use rand::{rngs::StdRng, SeedableRng};
 
 #[derive(Default)]
 struct CustomSim {
@@ -1602,3 +1606,149 @@ fn start_counting_qubits_called_twice_before_stop_fails() {
         &expect!["qubits already counted"],
     );
 }
+
+#[test]
+fn assert_state_approx_passes_for_matching_state() {
+    check_intrinsic_result(
+        "",
+        indoc! {"{
+            use qs = Qubit[2];
+            H(qs[0]);
+            CNOT(qs[0], qs[1]);
+            let a = Std.Math.Complex(1.0 / Std.Math.Sqrt(2.0), 0.0);
+            let zero = Std.Math.Complex(0.0, 0.0);
+            Std.Diagnostics.AssertStateApprox(qs, [a, zero, zero, a], 1e-6);
+            ResetAll(qs);
+        }"},
+        &expect!["()"],
+    );
+}
+
+#[test]
+fn assert_state_approx_ignores_global_phase() {
+    check_intrinsic_result(
+        "",
+        indoc! {"{
+            use q = Qubit();
+            X(q);
+            Z(q);
+            let zero = Std.Math.Complex(0.0, 0.0);
+            let one = Std.Math.Complex(1.0, 0.0);
+            Std.Diagnostics.AssertStateApprox([q], [zero, one], 1e-6);
+            Reset(q);
+        }"},
+        &expect!["()"],
+    );
+}
+
+#[test]
+fn assert_state_approx_fails_with_diff() {
+    check_intrinsic_result(
+        "",
+        indoc! {"{
+            use q = Qubit();
+            H(q);
+            let zero = Std.Math.Complex(0.0, 0.0);
+            let one = Std.Math.Complex(1.0, 0.0);
+            Std.Diagnostics.AssertStateApprox([q], [one, zero], 1e-6);
+        }"},
+        &expect![[r#"
+            assertion failed: state does not match the expected amplitudes within tolerance 0.000001
+            |0⟩: expected 1.0000+0.0000𝑖, actual 0.7071+0.0000𝑖
+            |1⟩: expected 0.0000+0.0000𝑖, actual 0.7071+0.0000𝑖"#]],
+    );
+}
+
+#[test]
+fn assert_state_approx_wrong_number_of_amplitudes_fails() {
+    check_intrinsic_result(
+        "",
+        indoc! {"{
+            use q = Qubit();
+            let zero = Std.Math.Complex(0.0, 0.0);
+            Std.Diagnostics.AssertStateApprox([q], [zero, zero, zero], 1e-6);
+        }"},
+        &expect!["intrinsic callable `AssertStateApprox` failed: expected 2 amplitudes for 1 qubits, found 3"],
+    );
+}
+
+#[test]
+fn assert_state_approx_too_many_qubits_fails() {
+    check_intrinsic_result(
+        "",
+        indoc! {"{
+            use qs = Qubit[64];
+            let zero = Std.Math.Complex(0.0, 0.0);
+            Std.Diagnostics.AssertStateApprox(qs, [zero], 1e-6);
+        }"},
+        &expect!["intrinsic callable `AssertStateApprox` failed: expected 2^64 amplitudes for 64 qubits, found 1"],
+    );
+}
+
+#[test]
+fn assert_operations_equal_passes_for_equivalent_operations() {
+    check_intrinsic_result(
+        "",
+        indoc! {"{
+            Std.Diagnostics.AssertOperationsEqual(
+                qs => CNOT(qs[0], qs[1]),
+                qs => { H(qs[1]); CZ(qs[0], qs[1]); H(qs[1]); },
+                2
+            );
+        }"},
+        &expect!["()"],
+    );
+}
+
+#[test]
+fn assert_operations_equal_fails_with_diff() {
+    check_intrinsic_result(
+        "",
+        indoc! {"{
+            Std.Diagnostics.AssertOperationsEqual(qs => X(qs[0]), qs => Z(qs[0]), 1);
+        }"},
+        &expect![[r#"
+            assertion failed: state does not match the expected amplitudes within tolerance 0.000001
+            |00⟩: expected 0.7071+0.0000𝑖, actual 0.0000+0.0000𝑖
+            |01⟩: expected 0.0000+0.0000𝑖, actual 0.7071+0.0000𝑖
+            |10⟩: expected 0.0000+0.0000𝑖, actual 0.7071+0.0000𝑖
+            |11⟩: expected −0.7071+0.0000𝑖, actual 0.0000+0.0000𝑖"#]],
+    );
+}
+
+#[test]
+fn assert_registers_equal_approx_different_lengths_fails() {
+    // The intrinsic isn't exported from the standard library, so it's called directly.
+    let mut sim = SparseSim::new();
+    let qubits = (0..3)
+        .map(|_| Value::Qubit(Qubit(sim.qubit_allocate())))
+        .collect::<Vec<_>>();
+    let arg = Value::Tuple(
+        vec![
+            Value::Array(qubits[..1].to_vec().into()),
+            Value::Array(qubits[1..].to_vec().into()),
+            Value::Double(1e-6),
+        ]
+        .into(),
+    );
+    let span = PackageSpan {
+        package: PackageId::CORE,
+        span: Span::default(),
+    };
+    let error = super::call(
+        "AssertRegistersEqualApprox",
+        span,
+        arg,
+        None,
+        span,
+        &mut sim,
+        &mut StdRng::seed_from_u64(0),
+        &mut GenericReceiver::new(&mut std::io::sink()),
+        &mut QubitSpans::default(),
+    )
+    .expect_err("registers of different lengths should fail the assertion");
+    expect![
+        "assertion failed: registers have different lengths: actual has 1 qubits, expected has 2"
+    ]
+    .assert_eq(&error.to_string());
+}
@@ -8,6 +8,8 @@ use num_complex::{Complex, Complex64};
 use num_traits::Zero;
 use rustc_hash::{FxHashMap, FxHashSet};
 
+use crate::state::{fmt_complex, format_state_id};
+
 /// Given a state and a set of qubits, split the state into two parts: the qubits to dump and the remaining qubits.
 /// This function will return an error if the state is not separable using the provided qubit identifiers.
 pub fn split_state(
@@ -171,7 +173,11 @@ where
     }
 }
 
-pub(crate) fn state_to_matrix(
+/// Reads the matrix of an operation on `qubit_count` qubits from the state of a register of
+/// twice as many qubits, where the operation was applied to the first half after each of its
+/// qubits was maximally entangled with the matching qubit of the second half.
+#[must_use]
+pub fn state_to_matrix(
     state: Vec<(BigUint, Complex64)>,
     qubit_count: usize,
 ) -> Vec<Vec<Complex64>> {
@@ -195,3 +201,62 @@ pub(crate) fn state_to_matrix(
 
     matrix
 }
+
+/// Expands a sparse state into the amplitude of every basis state, indexed by basis state label.
+pub(crate) fn to_dense_state(state: &[(BigUint, Complex64)], qubit_count: usize) -> Vec<Complex64> {
+    let mut dense = vec![Complex64::zero(); 1 << qubit_count];
+    for (label, val) in state {
+        let index = usize::try_from(label).expect("basis state label should fit in usize");
+        dense[index] = *val;
+    }
+    dense
+}
+
+/// Compares a state against the expected amplitude of every basis state, ignoring global phase.
+/// On a mismatch, returns a description listing each basis state whose amplitude differs from
+/// the expected one by more than the tolerance, with the actual amplitudes shown in the global
+/// phase of the expected state.
+pub(crate) fn compare_states(
+    state: &[(BigUint, Complex64)],
+    expected: &[Complex64],
+    qubit_count: usize,
+    tolerance: f64,
+) -> Result<(), String> {
+    let actual = to_dense_state(state, qubit_count);
+
+    // The global phase is taken from the largest expected amplitude.
+    let phase = expected
+        .iter()
+        .zip(&actual)
+        .max_by(|(a, _), (b, _)| a.norm().total_cmp(&b.norm()))
+        .filter(|(expected, actual)| !expected.is_nearly_zero() && !actual.is_nearly_zero())
+        .map_or(Complex64::new(1.0, 0.0), |(expected, actual)| {
+            (expected / expected.norm()) * (actual / actual.norm()).conj()
+        });
+
+    let diff = expected
+        .iter()
+        .zip(actual)
+        .enumerate()
+        .filter_map(|(index, (expected, actual))| {
+            let actual = actual * phase;
+            ((actual - expected).norm() > tolerance).then(|| {
+                format!(
+                    "{}: expected {}, actual {}",
+                    format_state_id(&BigUint::from(index), qubit_count),
+                    fmt_complex(expected),
+                    fmt_complex(&actual)
+                )
+            })
+        })
+        .collect::<Vec<_>>();
+
+    if diff.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "state does not match the expected amplitudes within tolerance {tolerance}\n{}",
+            diff.join("\n")
+        ))
+    }
+}
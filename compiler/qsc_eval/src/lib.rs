@@ -21,6 +21,7 @@ pub mod backend;
 pub mod debug;
 mod error;
 pub mod intrinsic;
+pub mod noise;
 pub mod output;
 pub mod state;
 pub mod val;
@@ -31,6 +32,7 @@ use crate::val::{
 use backend::Backend;
 use debug::{CallStack, Frame};
 pub use error::PackageSpan;
+pub use intrinsic::utils::state_to_matrix;
 use miette::Diagnostic;
 use num_bigint::BigInt;
 use output::Receiver;
@@ -51,6 +53,7 @@ use std::{
     iter,
     ops::Neg,
     rc::Rc,
+    sync::Arc,
 };
 use thiserror::Error;
 use val::update_functor_app;
@@ -61,6 +64,10 @@ pub enum Error {
     #[diagnostic(code("Qsc.Eval.ArrayTooLarge"))]
     ArrayTooLarge(#[label("this array has too many items")] PackageSpan),
 
+    #[error("assertion failed: {0}")]
+    #[diagnostic(code("Qsc.Eval.AssertionFailed"))]
+    AssertionFailed(String, #[label("assertion failed")] PackageSpan),
+
     #[error("callable already counted")]
     #[diagnostic(help(
         "counting for a given callable must be stopped before it can be started again"
@@ -167,6 +174,13 @@ pub enum Error {
     #[diagnostic(code("Qsc.Eval.UnsupportedIntrinsicType"))]
     UnsupportedIntrinsicType(String, #[label] PackageSpan),
 
+    #[error("unsupported operation: {0}")]
+    #[diagnostic(code("Qsc.Eval.UnsupportedOperation"))]
+    UnsupportedOperation(
+        String,
+        #[label("this operation is not supported by the simulator")] PackageSpan,
+    ),
+
     #[error("program failed: {0}")]
     #[diagnostic(code("Qsc.Eval.UserFail"))]
     UserFail(String, #[label("explicit fail")] PackageSpan),
@@ -177,6 +191,7 @@ impl Error {
     pub fn span(&self) -> &PackageSpan {
         match self {
             Error::ArrayTooLarge(span)
+            | Error::AssertionFailed(_, span)
             | Error::CallableAlreadyCounted(span)
             | Error::CallableNotCounted(span)
             | Error::DivZero(span)
@@ -199,6 +214,7 @@ impl Error {
             | Error::UnboundName(span)
             | Error::UnknownIntrinsic(_, span)
             | Error::UnsupportedIntrinsicType(_, span)
+            | Error::UnsupportedOperation(_, span)
             | Error::UserFail(_, span)
             | Error::InvalidArrayLength(_, span) => span,
         }
@@ -269,7 +285,7 @@ pub fn eval(
 pub fn eval_and_get_qubit_table(
     package: PackageId,
     seed: Option<u64>,
-    exec_graph: Rc<[ExecGraphNode]>,
+    exec_graph: ExecGraph,
     globals: &impl PackageStoreLookup,
     env: &mut Env,
     sim: &mut impl Backend<ResultType = impl Into<val::Result>>,
@@ -332,7 +348,7 @@ impl AsIndex for i64 {
 
 #[derive(Debug, Clone)]
 pub struct Variable {
-    pub name: Rc<str>,
+    pub name: Arc<str>,
     pub value: Value,
     pub span: Span,
 }
@@ -340,7 +356,7 @@ pub struct Variable {
 #[derive(Debug, Clone)]
 pub struct VariableInfo {
     pub value: Value,
-    pub name: Rc<str>,
+    pub name: Arc<str>,
     pub type_name: String,
     pub span: Span,
 }
@@ -375,6 +391,7 @@ impl Range {
     }
 }
 
+#[derive(Clone)]
 pub struct Env(Vec<Scope>);
 
 impl Default for Env {
@@ -485,7 +502,7 @@ impl Env {
     }
 }
 
-#[derive(Default)]
+#[derive(Clone, Default)]
 struct Scope {
     bindings: IndexMap<LocalVarId, Variable>,
     frame_id: usize,
@@ -494,6 +511,7 @@ struct Scope {
 type CallableCountKey = (StoreItemId, bool, bool);
 pub type QubitSpans = IndexMap<usize, (Option<PackageSpan>, PackageSpan)>;
 
+#[derive(Clone)]
 pub struct State {
     exec_graph_stack: Vec<ExecGraph>,
     idx: u32,
@@ -504,6 +522,7 @@ pub struct State {
     package: PackageId,
     call_stack: CallStack,
     current_span: Span,
+    stmt_count: u64,
     rng: RefCell<StdRng>,
     call_counts: FxHashMap<CallableCountKey, i64>,
     qubit_counter: Option<QubitCounter>,
@@ -527,6 +546,7 @@ impl State {
             package,
             call_stack: CallStack::default(),
             current_span: Span::default(),
+            stmt_count: 0,
             rng,
             call_counts: FxHashMap::default(),
             qubit_counter: None,
@@ -604,6 +624,25 @@ impl State {
         frames
     }
 
+    /// The number of statements evaluated so far, which identifies the current position in the
+    /// execution.
+    #[must_use]
+    pub fn stmt_count(&self) -> u64 {
+        self.stmt_count
+    }
+
+    /// The number of frames on the call stack.
+    #[must_use]
+    pub fn call_depth(&self) -> usize {
+        self.call_stack.len()
+    }
+
+    /// Returns true if evaluation has run to completion.
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.exec_graph_stack.is_empty()
+    }
+
     /// # Errors
     /// Returns the first error encountered during execution.
     /// # Panics
@@ -618,6 +657,39 @@ impl State {
         step: StepAction,
     ) -> Result<StepResult, (Error, Vec<Frame>)> {
         let current_frame = self.call_stack.len();
+        self.eval_until(
+            globals,
+            env,
+            sim,
+            out,
+            breakpoints,
+            step,
+            current_frame,
+            u64::MAX,
+        )
+        .map(|res| res.expect("evaluation without a statement limit should not pause"))
+    }
+
+    /// Evaluates like [`State::eval`], but pauses as soon as `limit` statements have been
+    /// evaluated in total, returning `None` if execution did not stop for another reason first.
+    /// `current_frame` is the call depth at which the step began, so that a step can be resumed
+    /// after a pause.
+    /// # Errors
+    /// Returns the first error encountered during execution.
+    /// # Panics
+    /// When returning a value in the middle of execution.
+    #[allow(clippy::too_many_arguments)]
+    pub fn eval_until(
+        &mut self,
+        globals: &impl PackageStoreLookup,
+        env: &mut Env,
+        sim: &mut impl Backend<ResultType = impl Into<val::Result>>,
+        out: &mut impl Receiver,
+        breakpoints: &[StmtId],
+        step: StepAction,
+        current_frame: usize,
+        limit: u64,
+    ) -> Result<Option<StepResult>, (Error, Vec<Frame>)> {
         while !self.exec_graph_stack.is_empty() {
             let exec_graph = self
                 .exec_graph_stack
@@ -637,10 +709,12 @@ impl State {
                 }
                 Some(ExecGraphNode::Stmt(stmt)) => {
                     self.idx += 1;
+                    self.stmt_count += 1;
                     self.current_span = globals.get_stmt((self.package, *stmt).into()).span;
 
                     match self.check_for_break(breakpoints, *stmt, step, current_frame) {
                         Some(value) => value,
+                        None if self.stmt_count >= limit => return Ok(None),
                         None => continue,
                     }
                 }
@@ -711,10 +785,10 @@ impl State {
                 panic!("unexpected return");
             }
 
-            return Ok(res);
+            return Ok(Some(res));
         }
 
-        Ok(StepResult::Return(self.get_result()))
+        Ok(Some(StepResult::Return(self.get_result())))
     }
 
     fn check_for_break(
@@ -866,7 +940,7 @@ impl State {
 
     fn collect_string(&mut self, components: &[StringComponent]) {
         if let [StringComponent::Lit(str)] = components {
-            self.set_val_register(Value::String(Rc::clone(str)));
+            self.set_val_register(Value::String(str.as_ref().into()));
             return;
         }
 
@@ -1056,7 +1130,14 @@ impl State {
                     &mut self.rng.borrow_mut(),
                     out,
                     &mut self.qubit_alloc_source_table,
-                )?;
+                );
+                if let Some(message) = sim.take_error() {
+                    return Err(Error::UnsupportedOperation(
+                        message,
+                        callerr_span.unwrap_or(callee_span),
+                    ));
+                }
+                let val = val?;
                 if val == Value::unit() && callee.output != Ty::UNIT {
                     return Err(Error::UnsupportedIntrinsicType(
                         callee.name.name.to_string(),
@@ -2085,7 +2166,7 @@ fn make_counting_key(id: StoreItemId, functor: FunctorApp) -> CallableCountKey {
     (id, functor.adjoint, functor.controlled > 0)
 }
 
-#[derive(Default)]
+#[derive(Clone, Default)]
 struct QubitCounter {
     seen: FxHashSet<usize>,
     count: i64,
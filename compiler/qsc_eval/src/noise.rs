@@ -0,0 +1,268 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use rand::{rngs::StdRng, Rng};
+use thiserror::Error;
+
+#[derive(Clone, Debug, Error, PartialEq)]
+pub enum Error {
+    #[error("invalid noise probability {0}: probabilities must be between 0 and 1")]
+    InvalidProbability(f64),
+    #[error("invalid Pauli noise: the probabilities of X, Y and Z errors add up to {0}, which is more than 1")]
+    InvalidDistribution(f64),
+    #[error("unknown noise channel `{0}`")]
+    UnknownChannel(String),
+    #[error("noise for `{0}` should be the probabilities of X, Y and Z errors")]
+    ExpectedPauliNoise(String),
+    #[error("measurement noise should be a single probability")]
+    ExpectedMeasurementProbability,
+}
+
+/// The noise given for a single named channel, as read from user input.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ChannelNoise {
+    /// The probabilities of X, Y and Z errors.
+    Pauli(f64, f64, f64),
+    /// A single probability, which is how the `measurement` channel is given.
+    Probability(f64),
+}
+
+/// A Pauli error that can be sampled from a `PauliNoise` distribution.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Fault {
+    None,
+    X,
+    Y,
+    Z,
+}
+
+/// A stochastic Pauli channel, which applies an X, Y or Z error with the given probabilities.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PauliNoise {
+    px: f64,
+    py: f64,
+    pz: f64,
+}
+
+impl PauliNoise {
+    /// Creates a Pauli channel from the probabilities of each Pauli error.
+    pub fn from_probabilities(px: f64, py: f64, pz: f64) -> Result<Self, Error> {
+        for p in [px, py, pz] {
+            check_probability(p)?;
+        }
+        let total = px + py + pz;
+        if total > 1.0 {
+            return Err(Error::InvalidDistribution(total));
+        }
+        Ok(Self { px, py, pz })
+    }
+
+    /// Creates a channel that applies a bit flip with probability `p`.
+    pub fn bit_flip(p: f64) -> Result<Self, Error> {
+        Self::from_probabilities(p, 0.0, 0.0)
+    }
+
+    /// Creates a channel that applies a phase flip with probability `p`.
+    pub fn phase_flip(p: f64) -> Result<Self, Error> {
+        Self::from_probabilities(0.0, 0.0, p)
+    }
+
+    /// Creates a depolarizing channel, which applies a uniformly random Pauli error with total
+    /// probability `p`.
+    pub fn depolarizing(p: f64) -> Result<Self, Error> {
+        check_probability(p)?;
+        Self::from_probabilities(p / 3.0, p / 3.0, p / 3.0)
+    }
+
+    #[must_use]
+    pub fn probabilities(&self) -> (f64, f64, f64) {
+        (self.px, self.py, self.pz)
+    }
+
+    #[must_use]
+    pub fn is_noiseless(&self) -> bool {
+        self.px == 0.0 && self.py == 0.0 && self.pz == 0.0
+    }
+
+    /// Returns the channel equivalent to applying this channel `times` times in a row.
+    #[must_use]
+    pub fn repeated(&self, times: u64) -> Self {
+        // A Pauli channel scales each of the X, Y and Z components of a state by a fixed factor,
+        // so repeating the channel raises those factors to the number of repetitions.
+        let times = i32::try_from(times).unwrap_or(i32::MAX);
+        let fx = (1.0 - 2.0 * (self.py + self.pz)).powi(times);
+        let fy = (1.0 - 2.0 * (self.px + self.pz)).powi(times);
+        let fz = (1.0 - 2.0 * (self.px + self.py)).powi(times);
+        Self {
+            px: ((1.0 + fx - fy - fz) / 4.0).max(0.0),
+            py: ((1.0 - fx + fy - fz) / 4.0).max(0.0),
+            pz: ((1.0 - fx - fy + fz) / 4.0).max(0.0),
+        }
+    }
+
+    /// Samples a Pauli error from the channel.
+    pub fn sample(&self, rng: &mut StdRng) -> Fault {
+        if self.is_noiseless() {
+            return Fault::None;
+        }
+        let sample: f64 = rng.gen();
+        if sample < self.px {
+            Fault::X
+        } else if sample < self.px + self.py {
+            Fault::Y
+        } else if sample < self.px + self.py + self.pz {
+            Fault::Z
+        } else {
+            Fault::None
+        }
+    }
+}
+
+/// The noise configuration for a noisy `SparseSim` run.
+///
+/// Each gate is followed by a Pauli error sampled from the channel configured for it. For multi-qubit
+/// gates the channel is sampled independently for each qubit the gate acts on.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NoiseConfig {
+    pub x: PauliNoise,
+    pub y: PauliNoise,
+    pub z: PauliNoise,
+    pub h: PauliNoise,
+    pub s: PauliNoise,
+    pub sadj: PauliNoise,
+    pub t: PauliNoise,
+    pub tadj: PauliNoise,
+    pub rx: PauliNoise,
+    pub ry: PauliNoise,
+    pub rz: PauliNoise,
+    pub cx: PauliNoise,
+    pub cy: PauliNoise,
+    pub cz: PauliNoise,
+    pub ccx: PauliNoise,
+    pub rxx: PauliNoise,
+    pub ryy: PauliNoise,
+    pub rzz: PauliNoise,
+    pub swap: PauliNoise,
+    /// Applied after a qubit is reset, including when it is freshly allocated, to model
+    /// imperfect state preparation.
+    pub reset: PauliNoise,
+    /// Applied to a qubit for every operation on other qubits during which it is idle. The
+    /// accumulated errors are applied right before the qubit is next operated on or measured.
+    pub idle: PauliNoise,
+    /// The probability that a measurement reports the opposite of the measured outcome.
+    measurement: f64,
+}
+
+impl NoiseConfig {
+    /// Creates a configuration that applies the same Pauli channel after every gate,
+    /// with noiseless measurements, resets and idling.
+    #[must_use]
+    pub fn uniform(noise: PauliNoise) -> Self {
+        Self {
+            x: noise,
+            y: noise,
+            z: noise,
+            h: noise,
+            s: noise,
+            sadj: noise,
+            t: noise,
+            tadj: noise,
+            rx: noise,
+            ry: noise,
+            rz: noise,
+            cx: noise,
+            cy: noise,
+            cz: noise,
+            ccx: noise,
+            rxx: noise,
+            ryy: noise,
+            rzz: noise,
+            swap: noise,
+            ..Self::default()
+        }
+    }
+
+    /// Creates a configuration from the noise of each named channel. The names are the ones
+    /// accepted by `set_noise`, plus `measurement` for the probability of a measurement error.
+    pub fn from_channels(
+        channels: impl IntoIterator<Item = (String, ChannelNoise)>,
+    ) -> Result<Self, Error> {
+        let mut config = Self::default();
+        for (name, noise) in channels {
+            config = match (name.as_str(), noise) {
+                ("measurement", ChannelNoise::Probability(p)) => {
+                    config.with_measurement_error(p)?
+                }
+                ("measurement", ChannelNoise::Pauli(..)) => {
+                    return Err(Error::ExpectedMeasurementProbability)
+                }
+                (_, ChannelNoise::Pauli(px, py, pz)) => {
+                    config.set_noise(&name, PauliNoise::from_probabilities(px, py, pz)?)?;
+                    config
+                }
+                (_, ChannelNoise::Probability(_)) => return Err(Error::ExpectedPauliNoise(name)),
+            };
+        }
+        Ok(config)
+    }
+
+    /// Sets the channel with the given name, which is the name of its field in the
+    /// configuration, such as `h`, `cx` or `idle`.
+    pub fn set_noise(&mut self, name: &str, noise: PauliNoise) -> Result<(), Error> {
+        let channel = match name {
+            "x" => &mut self.x,
+            "y" => &mut self.y,
+            "z" => &mut self.z,
+            "h" => &mut self.h,
+            "s" => &mut self.s,
+            "sadj" => &mut self.sadj,
+            "t" => &mut self.t,
+            "tadj" => &mut self.tadj,
+            "rx" => &mut self.rx,
+            "ry" => &mut self.ry,
+            "rz" => &mut self.rz,
+            "cx" => &mut self.cx,
+            "cy" => &mut self.cy,
+            "cz" => &mut self.cz,
+            "ccx" => &mut self.ccx,
+            "rxx" => &mut self.rxx,
+            "ryy" => &mut self.ryy,
+            "rzz" => &mut self.rzz,
+            "swap" => &mut self.swap,
+            "reset" => &mut self.reset,
+            "idle" => &mut self.idle,
+            _ => return Err(Error::UnknownChannel(name.to_string())),
+        };
+        *channel = noise;
+        Ok(())
+    }
+
+    /// Sets the probability that a measurement reports the wrong outcome.
+    pub fn with_measurement_error(mut self, p: f64) -> Result<Self, Error> {
+        check_probability(p)?;
+        self.measurement = p;
+        Ok(self)
+    }
+
+    /// The probability that a measurement reports the wrong outcome.
+    #[must_use]
+    pub fn measurement(&self) -> f64 {
+        self.measurement
+    }
+
+    #[must_use]
+    pub fn is_noiseless(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+fn check_probability(p: f64) -> Result<(), Error> {
+    if (0.0..=1.0).contains(&p) {
+        Ok(())
+    } else {
+        Err(Error::InvalidProbability(p))
+    }
+}
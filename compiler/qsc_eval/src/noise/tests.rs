@@ -0,0 +1,236 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{ChannelNoise, Error, Fault, NoiseConfig, PauliNoise};
+use crate::backend::{Backend, SparseSim};
+use rand::{rngs::StdRng, SeedableRng};
+
+#[test]
+fn probabilities_out_of_range_are_rejected() {
+    assert_eq!(
+        PauliNoise::from_probabilities(-0.1, 0.0, 0.0),
+        Err(Error::InvalidProbability(-0.1))
+    );
+    assert_eq!(
+        PauliNoise::bit_flip(1.5),
+        Err(Error::InvalidProbability(1.5))
+    );
+    assert_eq!(
+        PauliNoise::from_probabilities(0.5, 0.5, 0.5),
+        Err(Error::InvalidDistribution(1.5))
+    );
+    assert!(NoiseConfig::default().with_measurement_error(2.0).is_err());
+    assert!(NoiseConfig::default()
+        .with_measurement_error(f64::NAN)
+        .is_err());
+}
+
+#[test]
+fn noise_is_configured_per_channel() {
+    let config = NoiseConfig::from_channels([
+        ("x".to_string(), ChannelNoise::Pauli(1.0, 0.0, 0.0)),
+        ("idle".to_string(), ChannelNoise::Pauli(0.0, 0.0, 0.5)),
+        ("measurement".to_string(), ChannelNoise::Probability(0.25)),
+    ])
+    .expect("noise should be valid");
+    assert_eq!(config.x.probabilities(), (1.0, 0.0, 0.0));
+    assert_eq!(config.idle.probabilities(), (0.0, 0.0, 0.5));
+    assert!(config.h.is_noiseless());
+    assert!((config.measurement() - 0.25).abs() < f64::EPSILON);
+}
+
+#[test]
+fn invalid_channels_are_rejected() {
+    assert_eq!(
+        NoiseConfig::from_channels([("foo".to_string(), ChannelNoise::Pauli(0.1, 0.0, 0.0))]),
+        Err(Error::UnknownChannel("foo".to_string()))
+    );
+    assert_eq!(
+        NoiseConfig::from_channels([("h".to_string(), ChannelNoise::Probability(0.1))]),
+        Err(Error::ExpectedPauliNoise("h".to_string()))
+    );
+    assert_eq!(
+        NoiseConfig::from_channels([(
+            "measurement".to_string(),
+            ChannelNoise::Pauli(0.1, 0.0, 0.0)
+        )]),
+        Err(Error::ExpectedMeasurementProbability)
+    );
+    assert_eq!(
+        NoiseConfig::from_channels([("measurement".to_string(), ChannelNoise::Probability(1.5))]),
+        Err(Error::InvalidProbability(1.5))
+    );
+}
+
+#[test]
+fn noiseless_channel_never_samples_a_fault() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let noise = PauliNoise::default();
+    assert!(noise.is_noiseless());
+    assert!((0..100).all(|_| noise.sample(&mut rng) == Fault::None));
+}
+
+#[test]
+fn certain_channel_always_samples_its_fault() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let noise = PauliNoise::phase_flip(1.0).expect("noise should be valid");
+    assert!((0..100).all(|_| noise.sample(&mut rng) == Fault::Z));
+}
+
+#[test]
+fn depolarizing_channel_splits_probability_evenly() {
+    let noise = PauliNoise::depolarizing(0.3).expect("noise should be valid");
+    let (px, py, pz) = noise.probabilities();
+    assert!((px - 0.1).abs() < 1e-12);
+    assert!((py - 0.1).abs() < 1e-12);
+    assert!((pz - 0.1).abs() < 1e-12);
+}
+
+#[test]
+fn uniform_config_only_sets_gate_noise() {
+    let noise = PauliNoise::bit_flip(0.1).expect("noise should be valid");
+    let config = NoiseConfig::uniform(noise);
+    assert_eq!(config.h, noise);
+    assert_eq!(config.rzz, noise);
+    assert!(config.reset.is_noiseless());
+    assert!(config.idle.is_noiseless());
+    assert!(!config.is_noiseless());
+    assert!(NoiseConfig::default().is_noiseless());
+}
+
+#[test]
+fn gate_bit_flip_is_applied_by_sparse_sim() {
+    let config = NoiseConfig {
+        x: PauliNoise::bit_flip(1.0).expect("noise should be valid"),
+        ..NoiseConfig::default()
+    };
+    let mut sim = SparseSim::new_with_noise(config);
+    let q = sim.qubit_allocate();
+    sim.x(q);
+    assert!(sim.qubit_is_zero(q));
+    sim.h(q);
+    sim.h(q);
+    assert!(sim.qubit_is_zero(q));
+}
+
+#[test]
+fn two_qubit_gate_noise_is_applied_to_both_qubits() {
+    let config = NoiseConfig {
+        cx: PauliNoise::bit_flip(1.0).expect("noise should be valid"),
+        ..NoiseConfig::default()
+    };
+    let mut sim = SparseSim::new_with_noise(config);
+    let q0 = sim.qubit_allocate();
+    let q1 = sim.qubit_allocate();
+    sim.cx(q0, q1);
+    assert!(!sim.qubit_is_zero(q0));
+    assert!(!sim.qubit_is_zero(q1));
+}
+
+#[test]
+fn measurement_error_flips_result_but_not_state() {
+    let config = NoiseConfig::default()
+        .with_measurement_error(1.0)
+        .expect("config should be valid");
+    let mut sim = SparseSim::new_with_noise(config);
+    let q = sim.qubit_allocate();
+    assert!(sim.m(q));
+    assert!(sim.qubit_is_zero(q));
+    sim.x(q);
+    assert!(!sim.mresetz(q));
+    assert!(sim.qubit_is_zero(q));
+}
+
+#[test]
+fn reset_noise_is_applied_on_allocation() {
+    let config = NoiseConfig {
+        reset: PauliNoise::bit_flip(1.0).expect("noise should be valid"),
+        ..NoiseConfig::default()
+    };
+    let mut sim = SparseSim::new_with_noise(config);
+    let q = sim.qubit_allocate();
+    assert!(!sim.qubit_is_zero(q));
+    sim.reset(q);
+    assert!(!sim.qubit_is_zero(q));
+}
+
+#[test]
+fn seeded_noise_is_reproducible() {
+    let sample = |seed| {
+        let config = NoiseConfig::uniform(PauliNoise::depolarizing(0.5).expect("valid noise"));
+        let mut sim = SparseSim::new_with_noise(config);
+        sim.set_seed(Some(seed));
+        let q = sim.qubit_allocate();
+        (0..32)
+            .map(|_| {
+                sim.x(q);
+                sim.m(q)
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(sample(7), sample(7));
+}
+
+#[test]
+fn repeated_bit_flip_cancels_out_in_pairs() {
+    let noise = PauliNoise::bit_flip(1.0).expect("noise should be valid");
+    assert_eq!(noise.repeated(1), noise);
+    assert!(noise.repeated(2).is_noiseless());
+    assert!(noise.repeated(0).is_noiseless());
+}
+
+#[test]
+fn repeated_depolarizing_channel_approaches_fully_mixed() {
+    let noise = PauliNoise::depolarizing(0.1).expect("noise should be valid");
+    let (px, py, pz) = noise.repeated(1000).probabilities();
+    assert!((px - 0.25).abs() < 1e-9);
+    assert!((py - 0.25).abs() < 1e-9);
+    assert!((pz - 0.25).abs() < 1e-9);
+}
+
+#[test]
+fn idle_noise_is_applied_when_qubit_is_next_used() {
+    let config = NoiseConfig {
+        idle: PauliNoise::bit_flip(1.0).expect("noise should be valid"),
+        ..NoiseConfig::default()
+    };
+    let mut sim = SparseSim::new_with_noise(config);
+    let q0 = sim.qubit_allocate();
+    let q1 = sim.qubit_allocate();
+    // `q1` stays idle for the single operation on `q0`, so it is flipped once when measured.
+    sim.z(q0);
+    assert!(sim.m(q1));
+    // `q0` has now been idle for one operation as well.
+    assert!(sim.m(q0));
+}
+
+#[test]
+fn idle_noise_is_not_applied_on_release() {
+    let config = NoiseConfig {
+        idle: PauliNoise::bit_flip(1.0).expect("noise should be valid"),
+        ..NoiseConfig::default()
+    };
+    let mut sim = SparseSim::new_with_noise(config);
+    let q0 = sim.qubit_allocate();
+    let q1 = sim.qubit_allocate();
+    sim.z(q0);
+    assert!(sim.qubit_is_zero(q1));
+    sim.qubit_release(q1);
+    sim.qubit_release(q0);
+}
+
+#[test]
+fn noise_is_set_by_channel_name() {
+    let noise = PauliNoise::bit_flip(0.1).expect("noise should be valid");
+    let mut config = NoiseConfig::default();
+    config.set_noise("cx", noise).expect("channel should exist");
+    config
+        .set_noise("idle", noise)
+        .expect("channel should exist");
+    assert_eq!(config.cx, noise);
+    assert_eq!(config.idle, noise);
+    assert_eq!(
+        config.set_noise("foo", noise),
+        Err(Error::UnknownChannel("foo".to_string()))
+    );
+}
@@ -19,10 +19,9 @@ use std::{
     cmp::Ordering,
     fmt::{self, Debug, Display, Formatter, Write},
     hash::{Hash, Hasher},
-    ops,
-    rc::Rc,
-    result,
+    ops, result,
     str::FromStr,
+    sync::Arc,
 };
 
 fn set_indentation<'a, 'b>(
@@ -660,7 +659,7 @@ pub struct Item {
     /// The parent item.
     pub parent: Option<LocalItemId>,
     /// The documentation.
-    pub doc: Rc<str>,
+    pub doc: Arc<str>,
     /// The attributes.
     pub attrs: Vec<Attr>,
     /// The visibility.
@@ -895,7 +894,7 @@ impl Display for SpecDecl {
 }
 
 /// An execution graph represented by a reference counted vector of nodes.
-pub type ExecGraph = Rc<[ExecGraphNode]>;
+pub type ExecGraph = Arc<[ExecGraphNode]>;
 
 #[derive(Copy, Clone, Debug, PartialEq)]
 /// A node within the control flow graph.
@@ -1436,7 +1435,7 @@ pub enum StringComponent {
     /// An expression.
     Expr(ExprId),
     /// A string literal.
-    Lit(Rc<str>),
+    Lit(Arc<str>),
 }
 
 /// A pattern.
@@ -1505,7 +1504,7 @@ pub struct Ident {
     /// The span.
     pub span: Span,
     /// The identifier name.
-    pub name: Rc<str>,
+    pub name: Arc<str>,
 }
 
 impl Display for Ident {
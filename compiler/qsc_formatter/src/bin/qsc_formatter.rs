@@ -2,7 +2,10 @@
 // Licensed under the MIT License.
 
 use clap::{crate_version, Parser};
-use qsc_formatter::formatter::{calculate_format_edits, format_str};
+use qsc_formatter::formatter::{
+    calculate_format_edits_with_config, format_str_with_config, FormatterConfig,
+};
+use serde::Deserialize;
 use std::{
     env,
     fmt::Display,
@@ -26,6 +29,16 @@ struct Cli {
     write: bool,
 }
 
+/// The name of the project manifest file, which can configure the formatter.
+const MANIFEST_FILE_NAME: &str = "qsharp.json";
+
+/// The part of a project manifest that configures the formatter.
+#[derive(Deserialize)]
+struct Manifest {
+    #[serde(default)]
+    formatter: FormatterConfig,
+}
+
 struct FileWalker {
     roots: Vec<PathBuf>,
     is_write: bool,
@@ -51,11 +64,55 @@ impl FileWalker {
     fn format_from_roots(&mut self) {
         let temp = self.roots.clone();
         for root in temp {
-            self.format_file_or_dir(&root);
+            let config = self.config_for_root(&root);
+            self.format_file_or_dir(&root, &config);
+        }
+    }
+
+    /// Finds the formatter configuration of the closest project containing the given path.
+    fn config_for_root(&self, root: &Path) -> FormatterConfig {
+        let dir = if root.is_dir() {
+            root
+        } else {
+            root.parent().unwrap_or(root)
+        };
+        let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+        match dir
+            .ancestors()
+            .find(|ancestor| ancestor.join(MANIFEST_FILE_NAME).is_file())
+        {
+            Some(project_dir) => self.config_for_dir(project_dir, &FormatterConfig::default()),
+            None => FormatterConfig::default(),
+        }
+    }
+
+    /// Returns the formatter configuration from the manifest in the given folder,
+    /// or the inherited configuration if the folder has no manifest.
+    fn config_for_dir(&self, dir: &Path, inherited: &FormatterConfig) -> FormatterConfig {
+        use OutputFormatting::*;
+
+        let manifest_path = dir.join(MANIFEST_FILE_NAME);
+        if !manifest_path.is_file() {
+            return inherited.clone();
+        }
+        let manifest = std::fs::read_to_string(&manifest_path)
+            .map_err(|e| e.to_string())
+            .and_then(|contents| {
+                serde_json::from_str::<Manifest>(&contents).map_err(|e| e.to_string())
+            });
+        match manifest {
+            Ok(manifest) => manifest.formatter,
+            Err(e) => {
+                println!(
+                    "\t{Skip}Could not read formatter configuration from {}: {e}{Reset}",
+                    manifest_path.display()
+                );
+                inherited.clone()
+            }
         }
     }
 
-    fn format_file_or_dir(&mut self, path: &Path) {
+    fn format_file_or_dir(&mut self, path: &Path, config: &FormatterConfig) {
         use OutputFormatting::*;
 
         if path.is_dir() {
@@ -70,17 +127,18 @@ impl FileWalker {
             for item in items {
                 let subpath = &item.path();
                 if subpath.is_dir() && self.is_recursive {
-                    self.format_file_or_dir(subpath);
+                    let config = self.config_for_dir(subpath, config);
+                    self.format_file_or_dir(subpath, &config);
                 } else if is_path_qs(subpath) {
-                    self.format_file(subpath);
+                    self.format_file(subpath, config);
                 }
             }
         } else if is_path_qs(path) {
-            self.format_file(path);
+            self.format_file(path, config);
         }
     }
 
-    fn format_file(&mut self, path: &Path) {
+    fn format_file(&mut self, path: &Path, config: &FormatterConfig) {
         use OutputFormatting::*;
 
         if self.is_write {
@@ -98,7 +156,7 @@ impl FileWalker {
             }
         };
         if self.is_write {
-            let formatted = format_str(&file_as_string);
+            let formatted = format_str_with_config(&file_as_string, config);
             if file_as_string != formatted {
                 match std::fs::write(path, formatted) {
                     Ok(_) => {
@@ -111,7 +169,7 @@ impl FileWalker {
                     }
                 }
             }
-        } else if !calculate_format_edits(&file_as_string).is_empty() {
+        } else if !calculate_format_edits_with_config(&file_as_string, config).is_empty() {
             self.changed_files.push(path.display().to_string());
         }
         self.file_count += 1;
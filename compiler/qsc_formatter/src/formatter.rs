@@ -10,6 +10,8 @@ use qsc_frontend::{
         Delim, InterpolatedEnding, InterpolatedStart,
     },
 };
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
 
 #[cfg(test)]
 mod tests;
@@ -19,7 +21,13 @@ mod tests;
 /// Applies formatting rules to the give code str and returns
 /// the formatted string.
 pub fn format_str(code: &str) -> String {
-    let mut edits = calculate_format_edits(code);
+    format_str_with_config(code, &FormatterConfig::default())
+}
+
+/// Applies formatting rules with the given configuration to the
+/// given code str and returns the formatted string.
+pub fn format_str_with_config(code: &str, config: &FormatterConfig) -> String {
+    let mut edits = calculate_format_edits_with_config(code, config);
     edits.sort_by_key(|edit| edit.span.hi); // sort edits by their span's hi value from lowest to highest
     edits.reverse(); // sort from highest to lowest so that that as edits are applied they don't invalidate later applications of edits
     let mut new_code = String::from(code);
@@ -35,11 +43,46 @@ pub fn format_str(code: &str) -> String {
 /// Applies formatting rules to the given code str, generating edits where
 /// the source code needs to be changed to comply with the format rules.
 pub fn calculate_format_edits(code: &str) -> Vec<TextEdit> {
-    let tokens = concrete::ConcreteTokenIterator::new(code);
+    calculate_format_edits_with_config(code, &FormatterConfig::default())
+}
+
+/// Applies formatting rules with the given configuration to the given code str,
+/// generating edits where the source code needs to be changed to comply with the
+/// format rules.
+///
+/// When a maximum line width is configured, the lines of the result that are too wide
+/// are laid out once to choose every place they need to be broken, and the rules are
+/// applied a second time with those line breaks.
+pub fn calculate_format_edits_with_config(code: &str, config: &FormatterConfig) -> Vec<TextEdit> {
+    let tokens: Vec<ConcreteToken> = concrete::ConcreteTokenIterator::new(code).collect();
+    let lookahead = Lookahead::new(code, &tokens);
+    let edits = apply_format_rules(code, &tokens, config, &lookahead, &LineBreaks::default());
+    let Some(max_line_width) = config.max_line_width else {
+        return edits;
+    };
+
+    let line_breaks = LineBreaks::choose(code, &tokens, &lookahead, &edits, config, max_line_width);
+    if line_breaks.lists.is_empty() && line_breaks.operators.is_empty() {
+        edits
+    } else {
+        apply_format_rules(code, &tokens, config, &lookahead, &line_breaks)
+    }
+}
+
+fn apply_format_rules(
+    code: &str,
+    tokens: &[ConcreteToken],
+    config: &FormatterConfig,
+    lookahead: &Lookahead,
+    line_breaks: &LineBreaks,
+) -> Vec<TextEdit> {
     let mut edits = vec![];
 
     let mut formatter = Formatter {
         code,
+        config,
+        lookahead,
+        line_breaks,
         indent_level: 0,
         delim_newlines_stack: vec![],
         type_param_state: TypeParameterListState::NoState,
@@ -109,8 +152,390 @@ impl TextEdit {
     }
 }
 
+/// Options that control the output of the formatter. A project can set these in the
+/// `formatter` section of its `qsharp.json` manifest.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FormatterConfig {
+    /// The number of spaces used for each level of indentation.
+    pub indent_width: usize,
+    /// The maximum width of a line. Lines that are wider than this are wrapped by
+    /// laying out argument lists and array literals one item per line, and by breaking
+    /// chains of binary operators. Lines are not wrapped if this is not set.
+    pub max_line_width: Option<usize>,
+    /// Whether lists laid out one item per line end with a trailing comma.
+    pub trailing_commas: bool,
+    /// The placement of the opening brace of blocks that span multiple lines.
+    pub brace_style: BraceStyle,
+}
+
+impl Default for FormatterConfig {
+    fn default() -> Self {
+        Self {
+            indent_width: 4,
+            max_line_width: None,
+            trailing_commas: false,
+            brace_style: BraceStyle::default(),
+        }
+    }
+}
+
+/// The placement of the opening brace of a block.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum BraceStyle {
+    /// The opening brace ends the line that starts the block.
+    #[default]
+    SameLine,
+    /// The opening brace is placed on a line of its own.
+    NextLine,
+}
+
 // Private types
 
+/// Facts about the token stream that the formatting rules need
+/// but that can't be seen from the sliding window of tokens.
+struct Lookahead {
+    /// The offsets of the `(` and `[` delimiters that start lists
+    /// with more than one item, sorted.
+    list_opens: Vec<u32>,
+    /// The offsets of the `)` and `]` delimiters that end lists
+    /// with more than one item, sorted.
+    list_closes: Vec<u32>,
+    /// The offsets of the `{` delimiters that start blocks whose
+    /// contents begin on a new line, sorted.
+    multiline_braces: Vec<u32>,
+}
+
+impl Lookahead {
+    fn new(code: &str, tokens: &[ConcreteToken]) -> Self {
+        let mut list_opens = vec![];
+        let mut list_closes = vec![];
+        let mut multiline_braces = vec![];
+        // For each open delimiter: its offset, whether it can start a list, and
+        // whether a comma has been seen directly inside it.
+        let mut open_delims: Vec<(u32, bool, bool)> = vec![];
+
+        for (i, token) in tokens.iter().enumerate() {
+            let ConcreteTokenKind::Syntax(cooked) = &token.kind else {
+                continue;
+            };
+            match cooked {
+                TokenKind::Open(delim) => {
+                    let can_be_list = matches!(delim, Delim::Paren | Delim::Bracket);
+                    open_delims.push((token.span.lo, can_be_list, false));
+                    if matches!(delim, Delim::Brace)
+                        && is_block_start_multiline(code, &tokens[i + 1..])
+                    {
+                        multiline_braces.push(token.span.lo);
+                    }
+                }
+                TokenKind::Close(_) => {
+                    if let Some((open, true, true)) = open_delims.pop() {
+                        list_opens.push(open);
+                        list_closes.push(token.span.lo);
+                    }
+                }
+                TokenKind::Comma => {
+                    if let Some((_, _, has_comma)) = open_delims.last_mut() {
+                        *has_comma = true;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        list_opens.sort_unstable();
+        Self {
+            list_opens,
+            list_closes,
+            multiline_braces,
+        }
+    }
+}
+
+/// Places where lines are broken to keep them within the maximum line width.
+#[derive(Default)]
+struct LineBreaks {
+    /// The offsets of the open delimiters of lists that are laid out one item per line.
+    lists: Vec<u32>,
+    /// The offsets of binary operators that start a new line.
+    operators: Vec<u32>,
+}
+
+/// A line of the formatted code.
+#[derive(Default)]
+struct Line {
+    width: usize,
+    /// The indices of the tokens that start on this line, with their start and end columns.
+    tokens: Vec<(usize, usize, usize)>,
+}
+
+/// A run of the tokens of a line that will be laid out on a line of its own
+/// once the chosen line breaks are applied.
+struct Segment {
+    /// The position of the first token of the run in the tokens of the line.
+    start: usize,
+    /// The position of the last token of the run in the tokens of the line.
+    end: usize,
+    /// The indentation level of the delimiters enclosing the run.
+    level: usize,
+    /// The column the run starts at.
+    indent: usize,
+    /// The width of text added after the run, such as a trailing comma.
+    extra: usize,
+}
+
+impl LineBreaks {
+    /// Chooses the line breaks for every line of the code formatted without line breaks
+    /// that is wider than the maximum line width. Each line is split into segments that
+    /// are laid out the way the formatter will lay them out once broken, and segments
+    /// that are still too wide are broken further until they fit or cannot be broken.
+    fn choose(
+        code: &str,
+        tokens: &[ConcreteToken],
+        lookahead: &Lookahead,
+        edits: &[TextEdit],
+        config: &FormatterConfig,
+        max_line_width: usize,
+    ) -> Self {
+        let mut line_breaks = Self::default();
+        for line in lay_out_lines(code, tokens, edits) {
+            if line.width <= max_line_width || line.tokens.is_empty() {
+                continue;
+            }
+
+            let indent = line.tokens[0].1;
+            let mut level = indent / config.indent_width.max(1);
+            if matches!(&tokens[line.tokens[0].0].kind, ConcreteTokenKind::Syntax(cooked) if is_chain_op(cooked))
+            {
+                level = level.saturating_sub(1);
+            }
+            let mut segments = vec![Segment {
+                start: 0,
+                end: line.tokens.len() - 1,
+                level,
+                indent,
+                extra: 0,
+            }];
+
+            while let Some(segment) = segments.pop() {
+                let width = segment.indent + line.tokens[segment.end].2
+                    - line.tokens[segment.start].1
+                    + segment.extra;
+                if width > max_line_width {
+                    segments.extend(line_breaks.break_segment(
+                        tokens,
+                        lookahead,
+                        config,
+                        &line,
+                        &segment,
+                        max_line_width,
+                    ));
+                }
+            }
+        }
+        line_breaks
+    }
+
+    /// Chooses where to break the segment and returns the segments it is broken into.
+    /// Breaks at the shallowest nesting level are preferred, and at the same level,
+    /// operator chains are broken before lists. An operator chain is broken at its last
+    /// operator that keeps the line within the maximum width, and lists are broken
+    /// starting from the leftmost one.
+    fn break_segment(
+        &mut self,
+        tokens: &[ConcreteToken],
+        lookahead: &Lookahead,
+        config: &FormatterConfig,
+        line: &Line,
+        segment: &Segment,
+        max_line_width: usize,
+    ) -> Vec<Segment> {
+        let kind_at = |pos: usize| &tokens[line.tokens[pos].0].kind;
+        let mut depth = 0_isize;
+        let mut opens = vec![];
+        let mut lists = vec![];
+        let mut operators = vec![];
+
+        for pos in segment.start..=segment.end {
+            let ConcreteTokenKind::Syntax(cooked) = kind_at(pos) else {
+                continue;
+            };
+            match cooked {
+                TokenKind::Open(_) => {
+                    opens.push((depth, pos));
+                    depth += 1;
+                }
+                TokenKind::Close(_) => {
+                    depth -= 1;
+                    // Only lists that are opened and closed within the segment and have
+                    // items can be broken here; any other list is already broken.
+                    if let Some((open_depth, open)) = opens.pop() {
+                        let lo = tokens[line.tokens[open].0].span.lo;
+                        if open + 1 < pos && lookahead.list_opens.binary_search(&lo).is_ok() {
+                            lists.push((open_depth, open, pos));
+                        }
+                    }
+                }
+                _ if is_chain_op(cooked)
+                    && pos > segment.start
+                    && ends_operand(kind_at(pos - 1)) =>
+                {
+                    let column = segment.indent + line.tokens[pos].1 - line.tokens[segment.start].1;
+                    operators.push((depth, pos, column));
+                }
+                _ => {}
+            }
+        }
+
+        let Some(min_depth) = lists
+            .iter()
+            .map(|(depth, _, _)| *depth)
+            .chain(operators.iter().map(|(depth, _, _)| *depth))
+            .min()
+        else {
+            return vec![];
+        };
+
+        let operators = operators
+            .into_iter()
+            .filter(|(depth, _, _)| *depth == min_depth)
+            .collect::<Vec<_>>();
+        let operator = operators
+            .iter()
+            .rev()
+            .find(|(_, _, column)| *column <= max_line_width)
+            .or(operators.first());
+        if let Some(&(_, pos, _)) = operator {
+            self.operators.push(tokens[line.tokens[pos].0].span.lo);
+            return vec![
+                Segment {
+                    start: segment.start,
+                    end: pos - 1,
+                    level: segment.level,
+                    indent: segment.indent,
+                    extra: 0,
+                },
+                Segment {
+                    start: pos,
+                    end: segment.end,
+                    level: segment.level,
+                    indent: (segment.level + 1) * config.indent_width,
+                    extra: segment.extra,
+                },
+            ];
+        }
+
+        let Some(&(_, open, close)) = lists
+            .iter()
+            .filter(|(depth, _, _)| *depth == min_depth)
+            .min_by_key(|(_, open, _)| *open)
+        else {
+            return vec![];
+        };
+        self.lists.push(tokens[line.tokens[open].0].span.lo);
+
+        let item_level = segment.level + 1;
+        let item_indent = item_level * config.indent_width;
+        let mut segments = vec![Segment {
+            start: segment.start,
+            end: open,
+            level: segment.level,
+            indent: segment.indent,
+            extra: 0,
+        }];
+        let mut item_start = open + 1;
+        let mut depth = 0;
+        for pos in open + 1..close {
+            match kind_at(pos) {
+                ConcreteTokenKind::Syntax(TokenKind::Open(_)) => depth += 1,
+                ConcreteTokenKind::Syntax(TokenKind::Close(_)) => depth -= 1,
+                ConcreteTokenKind::Syntax(TokenKind::Comma) if depth == 0 => {
+                    segments.push(Segment {
+                        start: item_start,
+                        end: pos,
+                        level: item_level,
+                        indent: item_indent,
+                        extra: 0,
+                    });
+                    item_start = pos + 1;
+                }
+                _ => {}
+            }
+        }
+        if item_start < close {
+            segments.push(Segment {
+                start: item_start,
+                end: close - 1,
+                level: item_level,
+                indent: item_indent,
+                extra: usize::from(config.trailing_commas),
+            });
+        }
+        segments.push(Segment {
+            start: close,
+            end: segment.end,
+            level: segment.level,
+            indent: segment.level * config.indent_width,
+            extra: segment.extra,
+        });
+        segments
+    }
+}
+
+/// Computes the lines of the code that results from applying the edits,
+/// which must be edits generated by the formatting rules.
+fn lay_out_lines(code: &str, tokens: &[ConcreteToken], edits: &[TextEdit]) -> Vec<Line> {
+    let replacements: FxHashMap<(u32, u32), &str> = edits
+        .iter()
+        .map(|edit| ((edit.span.lo, edit.span.hi), edit.new_text.as_str()))
+        .collect();
+    let mut lines = vec![Line::default()];
+    let mut prev_hi = 0;
+
+    for (index, token) in tokens.iter().enumerate() {
+        if matches!(token.kind, ConcreteTokenKind::WhiteSpace) {
+            continue;
+        }
+        let whitespace = replacements
+            .get(&(prev_hi, token.span.lo))
+            .copied()
+            .unwrap_or(&code[prev_hi as usize..token.span.lo as usize]);
+        extend_lines(&mut lines, whitespace);
+
+        let line_index = lines.len() - 1;
+        let column = lines[line_index].width;
+
+        let contents = replacements
+            .get(&(token.span.lo, token.span.hi))
+            .copied()
+            .unwrap_or_else(|| get_token_contents(code, token));
+        extend_lines(&mut lines, contents);
+        // A token that spans several lines ends where its first line ends.
+        let end = lines[line_index].width;
+        lines[line_index].tokens.push((index, column, end));
+        prev_hi = token.span.hi;
+    }
+
+    lines
+}
+
+fn extend_lines(lines: &mut Vec<Line>, text: &str) {
+    for c in text.chars() {
+        match c {
+            '\n' => lines.push(Line::default()),
+            '\r' => {}
+            _ => {
+                lines
+                    .last_mut()
+                    .expect("there should be at least one line")
+                    .width += 1
+            }
+        }
+    }
+}
+
 /// This is to keep track of whether the formatter is currently
 /// processing a sequence with newline separators or single-space
 /// separator.
@@ -220,6 +645,9 @@ impl Delimiter {
 
 struct Formatter<'a> {
     code: &'a str,
+    config: &'a FormatterConfig,
+    lookahead: &'a Lookahead,
+    line_breaks: &'a LineBreaks,
     indent_level: usize,
     delim_newlines_stack: Vec<NewlineContext>,
     type_param_state: TypeParameterListState,
@@ -263,10 +691,14 @@ impl<'a> Formatter<'a> {
             self.import_export_state,
         );
 
+        // Lists that are broken to keep lines within the maximum width are
+        // laid out as if their contents started on a new line.
+        let is_broken_list = self.line_breaks.lists.contains(&left.span.lo);
+
         let newline_context = self.update_indent_level(
             left_delim_state,
             right_delim_state,
-            are_newlines_in_spaces,
+            are_newlines_in_spaces || is_broken_list,
             does_right_required_newline,
             matches!(right.kind, Comment),
         );
@@ -275,10 +707,24 @@ impl<'a> Formatter<'a> {
             (Comment | Syntax(DocComment), _) => {
                 // remove whitespace at the ends of comments
                 effect_trim_comment(left, &mut edits, self.code);
-                effect_correct_indentation(left, whitespace, right, &mut edits, self.indent_level);
+                effect_correct_indentation(
+                    left,
+                    whitespace,
+                    right,
+                    &mut edits,
+                    self.indent_level,
+                    self.config.indent_width,
+                );
             }
             (_, Comment) if matches!(left_delim_state, Delimiter::Open) => {
-                effect_correct_indentation(left, whitespace, right, &mut edits, self.indent_level);
+                effect_correct_indentation(
+                    left,
+                    whitespace,
+                    right,
+                    &mut edits,
+                    self.indent_level,
+                    self.config.indent_width,
+                );
             }
             (_, Comment) => {
                 if are_newlines_in_spaces {
@@ -288,6 +734,7 @@ impl<'a> Formatter<'a> {
                         right,
                         &mut edits,
                         self.indent_level,
+                        self.config.indent_width,
                     );
                 }
                 // else do nothing, preserving the user's spaces before the comment
@@ -319,6 +766,7 @@ impl<'a> Formatter<'a> {
                         right,
                         &mut edits,
                         self.indent_level,
+                        self.config.indent_width,
                     );
                 }
                 (_, Semi) => {
@@ -347,6 +795,7 @@ impl<'a> Formatter<'a> {
                         right,
                         &mut edits,
                         self.indent_level,
+                        self.config.indent_width,
                     );
                 }
                 (Comma, _) if matches!(newline_context, NewlineContext::Newlines) => {
@@ -356,11 +805,30 @@ impl<'a> Formatter<'a> {
                         right,
                         &mut edits,
                         self.indent_level,
+                        self.config.indent_width,
                     );
                 }
                 (Comma, _) => {
                     effect_single_space(left, whitespace, right, &mut edits);
                 }
+                (_, Close(_))
+                    if self.config.trailing_commas
+                        && matches!(newline_context, NewlineContext::Newlines)
+                        && self
+                            .lookahead
+                            .list_closes
+                            .binary_search(&right.span.lo)
+                            .is_ok() =>
+                {
+                    effect_trailing_comma(
+                        left,
+                        whitespace,
+                        right,
+                        &mut edits,
+                        self.indent_level,
+                        self.config.indent_width,
+                    );
+                }
                 (_, _)
                     if matches!(right_delim_state, Delimiter::Close)
                         && matches!(newline_context, NewlineContext::Newlines) =>
@@ -371,6 +839,7 @@ impl<'a> Formatter<'a> {
                         right,
                         &mut edits,
                         self.indent_level,
+                        self.config.indent_width,
                     );
                 }
                 (Open(Delim::Bracket | Delim::Paren), _)
@@ -405,6 +874,7 @@ impl<'a> Formatter<'a> {
                         right,
                         &mut edits,
                         self.indent_level,
+                        self.config.indent_width,
                     );
                 }
                 (_, Keyword(Keyword::Until))
@@ -431,12 +901,23 @@ impl<'a> Formatter<'a> {
                         right,
                         &mut edits,
                         self.indent_level,
+                        self.config.indent_width,
                     );
                 }
                 (String(StringToken::Interpolated(_, InterpolatedEnding::LBrace)), _)
                 | (_, String(StringToken::Interpolated(InterpolatedStart::RBrace, _))) => {
                     effect_no_space(left, whitespace, right, &mut edits);
                 }
+                (_, Open(Delim::Brace)) if self.is_brace_on_next_line(cooked_left, right) => {
+                    effect_correct_indentation(
+                        left,
+                        whitespace,
+                        right,
+                        &mut edits,
+                        self.indent_level,
+                        self.config.indent_width,
+                    );
+                }
                 (DotDotDot, _) if matches!(self.spec_decl_state, SpecDeclState::OnEllipse) => {
                     // Special-case specialization declaration ellipses to have a space after
                     effect_single_space(left, whitespace, right, &mut edits);
@@ -503,6 +984,23 @@ impl<'a> Formatter<'a> {
                         right,
                         &mut edits,
                         self.indent_level + 1,
+                        self.config.indent_width,
+                    );
+                }
+                (_, _)
+                    if is_chain_op(cooked_right)
+                        && (self.line_breaks.operators.contains(&right.span.lo)
+                            || (are_newlines_in_spaces
+                                && self.config.max_line_width.is_some())) =>
+                {
+                    // Operator chains broken across lines continue with an extra level of indentation
+                    effect_correct_indentation(
+                        left,
+                        whitespace,
+                        right,
+                        &mut edits,
+                        self.indent_level + 1,
+                        self.config.indent_width,
                     );
                 }
                 (_, _) if is_bin_op(cooked_right) => {
@@ -515,6 +1013,26 @@ impl<'a> Formatter<'a> {
         edits
     }
 
+    /// Returns true if the `{` on the right starts a multi-line block whose brace
+    /// should be placed on its own line according to the configured brace style.
+    /// Braces following operators, separators, or keywords that start an expression
+    /// stay on the same line, since those blocks are values.
+    fn is_brace_on_next_line(&self, cooked_left: &TokenKind, right: &ConcreteToken) -> bool {
+        matches!(self.config.brace_style, BraceStyle::NextLine)
+            && !is_bin_op(cooked_left)
+            && !matches!(
+                cooked_left,
+                TokenKind::Comma
+                    | TokenKind::Open(_)
+                    | TokenKind::Keyword(Keyword::Return | Keyword::Fail)
+            )
+            && self
+                .lookahead
+                .multiline_braces
+                .binary_search(&right.span.lo)
+                .is_ok()
+    }
+
     fn update_spec_decl_state(&mut self, left_kind: &ConcreteTokenKind) {
         use qsc_frontend::keyword::Keyword;
         use ConcreteTokenKind::*;
@@ -668,8 +1186,8 @@ impl<'a> Formatter<'a> {
 
 // Helper Functions
 
-fn make_indent_string(level: usize) -> String {
-    "    ".repeat(level)
+fn make_indent_string(level: usize, indent_width: usize) -> String {
+    " ".repeat(level * indent_width)
 }
 
 fn get_token_contents<'a>(code: &'a str, token: &ConcreteToken) -> &'a str {
@@ -784,6 +1302,39 @@ fn is_newline_after_brace(cooked: &TokenKind, delim_state: Delimiter) -> bool {
         || matches!(cooked, TokenKind::Keyword(keyword) if is_prefix_keyword(keyword))
 }
 
+/// Returns true if the contents of a block start on a new line,
+/// given the tokens that follow the `{` of the block.
+fn is_block_start_multiline(code: &str, rest: &[ConcreteToken]) -> bool {
+    for token in rest {
+        match &token.kind {
+            ConcreteTokenKind::WhiteSpace => {
+                if get_token_contents(code, token).contains('\n') {
+                    return true;
+                }
+            }
+            ConcreteTokenKind::Comment => return true,
+            ConcreteTokenKind::Syntax(cooked) => return is_newline_keyword_or_ampersat(cooked),
+            ConcreteTokenKind::Error(_) => return false,
+        }
+    }
+    false
+}
+
+/// Binary operators that a long chain of operations can be broken before.
+fn is_chain_op(cooked: &TokenKind) -> bool {
+    matches!(cooked, TokenKind::ClosedBinOp(op) if !matches!(op, ClosedBinOp::Caret | ClosedBinOp::Minus))
+}
+
+/// Returns true if the token can be the last token of an operand,
+/// so that an operator following it must be a binary operator.
+fn ends_operand(kind: &ConcreteTokenKind) -> bool {
+    match kind {
+        ConcreteTokenKind::Syntax(TokenKind::Close(_)) => true,
+        ConcreteTokenKind::Syntax(cooked) => is_value_token_left(cooked, Delimiter::NonDelim),
+        _ => false,
+    }
+}
+
 /// Note that this does not include interpolated string literals
 fn is_value_lit(cooked: &TokenKind) -> bool {
     matches!(
@@ -869,7 +1420,39 @@ fn effect_correct_indentation(
     right: &ConcreteToken,
     edits: &mut Vec<TextEdit>,
     indent_level: usize,
+    indent_width: usize,
 ) {
+    let new_whitespace = make_indented_newlines(whitespace, indent_level, indent_width);
+    if whitespace != new_whitespace {
+        edits.push(TextEdit::new(
+            new_whitespace.as_str(),
+            left.span.hi,
+            right.span.lo,
+        ));
+    }
+}
+
+/// Like `effect_correct_indentation`, but also adds a comma after the left token.
+/// Used for the last item of a list that is laid out one item per line.
+fn effect_trailing_comma(
+    left: &ConcreteToken,
+    whitespace: &str,
+    right: &ConcreteToken,
+    edits: &mut Vec<TextEdit>,
+    indent_level: usize,
+    indent_width: usize,
+) {
+    let new_whitespace = make_indented_newlines(whitespace, indent_level, indent_width);
+    edits.push(TextEdit::new(
+        &format!(",{new_whitespace}"),
+        left.span.hi,
+        right.span.lo,
+    ));
+}
+
+/// Creates the whitespace that replaces `whitespace` to start a new line with the given
+/// indentation, keeping any blank lines and the kind of line endings used.
+fn make_indented_newlines(whitespace: &str, indent_level: usize, indent_width: usize) -> String {
     let mut count_newlines = whitespace.chars().filter(|c| *c == '\n').count();
 
     // There should always be at least one newline
@@ -882,12 +1465,6 @@ fn effect_correct_indentation(
     } else {
         "\n".repeat(count_newlines)
     };
-    new_whitespace.push_str(&make_indent_string(indent_level));
-    if whitespace != new_whitespace {
-        edits.push(TextEdit::new(
-            new_whitespace.as_str(),
-            left.span.hi,
-            right.span.lo,
-        ));
-    }
+    new_whitespace.push_str(&make_indent_string(indent_level, indent_width));
+    new_whitespace
 }
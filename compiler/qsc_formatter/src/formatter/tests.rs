@@ -1,6 +1,7 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
+use super::{BraceStyle, FormatterConfig};
 use expect_test::{expect, Expect};
 use indoc::indoc;
 
@@ -9,6 +10,16 @@ fn check(input: &str, expect: &Expect) {
     expect.assert_eq(&actual);
 }
 
+fn check_with_config(input: &str, config: &FormatterConfig, expect: &Expect) {
+    let actual = super::format_str_with_config(input, config);
+    expect.assert_eq(&actual);
+    assert_eq!(
+        super::format_str_with_config(&actual, config),
+        actual,
+        "formatting should be stable"
+    );
+}
+
 fn check_edits(input: &str, expect: &Expect) {
     let actual = super::calculate_format_edits(input);
     expect.assert_debug_eq(&actual);
@@ -1382,3 +1393,208 @@ namespace Microsoft.Quantum.Arrays {
     "#]],
     );
 }
+
+// Formatter configuration
+
+#[test]
+fn config_is_read_from_camel_case_json() {
+    let config: FormatterConfig = serde_json::from_str(
+        r#"{ "indentWidth": 2, "maxLineWidth": 80, "trailingCommas": true, "braceStyle": "nextLine" }"#,
+    )
+    .expect("config should be valid");
+    expect![[r#"
+        FormatterConfig {
+            indent_width: 2,
+            max_line_width: Some(
+                80,
+            ),
+            trailing_commas: true,
+            brace_style: NextLine,
+        }
+    "#]]
+    .assert_debug_eq(&config);
+}
+
+#[test]
+fn missing_config_options_use_defaults() {
+    let config: FormatterConfig =
+        serde_json::from_str(r#"{ "trailingCommas": true }"#).expect("config should be valid");
+    assert_eq!(
+        config,
+        FormatterConfig {
+            trailing_commas: true,
+            ..FormatterConfig::default()
+        }
+    );
+}
+
+#[test]
+fn configured_indent_width() {
+    check_with_config(
+        indoc! {"
+        operation Foo() : Unit {
+        if true {
+        let x = 3;
+        }
+        }
+        "},
+        &FormatterConfig {
+            indent_width: 2,
+            ..FormatterConfig::default()
+        },
+        &expect![[r#"
+            operation Foo() : Unit {
+              if true {
+                let x = 3;
+              }
+            }
+        "#]],
+    );
+}
+
+#[test]
+fn next_line_brace_style() {
+    check_with_config(
+        indoc! {"
+        operation Foo() : Unit {
+            if true {
+                let x = { 3 };
+            }
+        }
+        "},
+        &FormatterConfig {
+            brace_style: BraceStyle::NextLine,
+            ..FormatterConfig::default()
+        },
+        &expect![[r#"
+            operation Foo() : Unit
+            {
+                if true
+                {
+                    let x = { 3 };
+                }
+            }
+        "#]],
+    );
+}
+
+#[test]
+fn long_argument_list_is_wrapped() {
+    check_with_config(
+        indoc! {"
+        operation Foo() : Unit {
+            Bar(first_argument, second_argument, third_argument);
+        }
+        "},
+        &FormatterConfig {
+            max_line_width: Some(40),
+            ..FormatterConfig::default()
+        },
+        &expect![[r#"
+            operation Foo() : Unit {
+                Bar(
+                    first_argument,
+                    second_argument,
+                    third_argument
+                );
+            }
+        "#]],
+    );
+}
+
+#[test]
+fn wrapped_list_gets_trailing_comma() {
+    check_with_config(
+        indoc! {"
+        operation Foo() : Unit {
+            let xs = [first_element, second_element, third_element];
+        }
+        "},
+        &FormatterConfig {
+            max_line_width: Some(40),
+            trailing_commas: true,
+            ..FormatterConfig::default()
+        },
+        &expect![[r#"
+            operation Foo() : Unit {
+                let xs = [
+                    first_element,
+                    second_element,
+                    third_element,
+                ];
+            }
+        "#]],
+    );
+}
+
+#[test]
+fn short_lines_are_not_wrapped() {
+    check_with_config(
+        indoc! {"
+        operation Foo() : Unit {
+            Bar(a, b, c);
+        }
+        "},
+        &FormatterConfig {
+            max_line_width: Some(40),
+            trailing_commas: true,
+            ..FormatterConfig::default()
+        },
+        &expect![[r#"
+            operation Foo() : Unit {
+                Bar(a, b, c);
+            }
+        "#]],
+    );
+}
+
+#[test]
+fn long_operator_chain_is_wrapped() {
+    check_with_config(
+        indoc! {"
+        function Foo() : Int {
+            let value = first_value + second_value + third_value;
+            value
+        }
+        "},
+        &FormatterConfig {
+            max_line_width: Some(45),
+            ..FormatterConfig::default()
+        },
+        &expect![[r#"
+            function Foo() : Int {
+                let value = first_value + second_value
+                    + third_value;
+                value
+            }
+        "#]],
+    );
+}
+
+#[test]
+fn nested_lists_and_operator_chains_are_wrapped_together() {
+    check_with_config(
+        indoc! {"
+        operation Foo() : Unit {
+            Bar(first_argument, Baz(second_argument, third_argument + fourth_argument));
+        }
+        "},
+        &FormatterConfig {
+            max_line_width: Some(40),
+            trailing_commas: true,
+            ..FormatterConfig::default()
+        },
+        &expect![[r#"
+            operation Foo() : Unit {
+                Bar(
+                    first_argument,
+                    Baz(
+                        second_argument,
+                        third_argument
+                            + fourth_argument,
+                    ),
+                );
+            }
+        "#]],
+    );
+}
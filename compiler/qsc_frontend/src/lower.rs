@@ -24,7 +24,7 @@ use thiserror::Error;
 #[derive(Clone, Debug, Diagnostic, Error)]
 pub(super) enum Error {
     #[error("unknown attribute {0}")]
-    #[diagnostic(help("supported attributes are: EntryPoint, Config"))]
+    #[diagnostic(help("supported attributes are: EntryPoint, Config, Test"))]
     #[diagnostic(code("Qsc.LowerAst.UnknownAttr"))]
     UnknownAttr(String, #[label] Span),
     #[error("invalid attribute arguments: expected {0}")]
@@ -42,6 +42,12 @@ pub(super) enum Error {
     #[error("invalid pattern for specialization declaration")]
     #[diagnostic(code("Qsc.LowerAst.InvalidSpecPat"))]
     InvalidSpecPat(#[label] Span),
+    #[error("invalid test callable")]
+    #[diagnostic(help(
+        "test callables must take no parameters, have no type parameters, and return Unit"
+    ))]
+    #[diagnostic(code("Qsc.LowerAst.InvalidTestSignature"))]
+    InvalidTestSignature(#[label] Span),
 }
 
 pub(super) struct Lowerer {
@@ -236,6 +242,15 @@ impl With<'_> {
                 self.lowerer.parent = Some(id.item);
                 let callable = self.lower_callable_decl(callable);
                 self.lowerer.parent = grandparent;
+                if attrs.contains(&hir::Attr::Test)
+                    && (!callable.generics.is_empty()
+                        || callable.input.ty != Ty::UNIT
+                        || callable.output != Ty::UNIT)
+                {
+                    self.lowerer
+                        .errors
+                        .push(Error::InvalidTestSignature(callable.name.span));
+                }
                 (id, hir::ItemKind::Callable(callable))
             }
             ast::ItemKind::Ty(name, _) => {
@@ -320,6 +335,15 @@ impl With<'_> {
                     None
                 }
             },
+            Ok(hir::Attr::Test) => match &*attr.arg.kind {
+                ast::ExprKind::Tuple(args) if args.is_empty() => Some(hir::Attr::Test),
+                _ => {
+                    self.lowerer
+                        .errors
+                        .push(Error::InvalidAttrArgs("()".to_string(), attr.arg.span));
+                    None
+                }
+            },
             Ok(hir::Attr::Config) => {
                 match &*attr.arg.kind {
                     // @Config(Capability)
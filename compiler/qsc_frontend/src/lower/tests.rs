@@ -87,6 +87,71 @@ fn test_entrypoint_attr_wrong_args() {
     );
 }
 
+#[test]
+fn test_attr_allowed() {
+    check_errors(
+        indoc! {"
+            namespace input {
+                @Test()
+                operation Foo() : Unit {
+                    body ... {}
+                }
+            }
+        "},
+        &expect![[r#"
+            []
+        "#]],
+    );
+}
+
+#[test]
+fn test_attr_on_callable_with_params() {
+    check_errors(
+        indoc! {"
+            namespace input {
+                @Test()
+                operation Foo(x : Int) : Unit {
+                    body ... {}
+                }
+            }
+        "},
+        &expect![[r#"
+            [
+                InvalidTestSignature(
+                    Span {
+                        lo: 44,
+                        hi: 47,
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn test_attr_on_callable_returning_non_unit() {
+    check_errors(
+        indoc! {"
+            namespace input {
+                @Test()
+                function Foo() : Int {
+                    0
+                }
+            }
+        "},
+        &expect![[r#"
+            [
+                InvalidTestSignature(
+                    Span {
+                        lo: 43,
+                        hi: 46,
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
+
 #[test]
 fn test_target_profile_base_attr_allowed() {
     check_errors(
@@ -1351,6 +1351,8 @@ pub enum Attr {
     /// Indicates that an item should be treated as an intrinsic callable for QIR code generation
     /// and any implementation should be ignored.
     SimulatableIntrinsic,
+    /// Indicates that a callable is a test case.
+    Test,
 }
 
 impl FromStr for Attr {
@@ -1362,6 +1364,7 @@ impl FromStr for Attr {
             "EntryPoint" => Ok(Self::EntryPoint),
             "Unimplemented" => Ok(Self::Unimplemented),
             "SimulatableIntrinsic" => Ok(Self::SimulatableIntrinsic),
+            "Test" => Ok(Self::Test),
             _ => Err(()),
         }
     }
@@ -41,9 +41,9 @@ pub(crate) struct Compilation<'a> {
     pub compile_unit: &'a CompileUnit,
 }
 
-impl Compilation<'_> {
+impl<'a> Compilation<'a> {
     /// Resolves an item id to an item.
-    pub fn resolve_item_id(&self, item_id: &ItemId) -> &Item {
+    pub fn resolve_item_id(&self, item_id: &ItemId) -> &'a Item {
         let package = match item_id.package {
             Some(package_id) => {
                 &self
@@ -3,15 +3,17 @@
 
 use std::rc::Rc;
 
+use qsc_ast::ast;
 use qsc_data_structures::span::Span;
 use qsc_hir::{
     hir::{
-        CallableDecl, CallableKind, Expr, ExprKind, Field, ItemKind, Res, SpecBody, SpecDecl, Stmt,
-        StmtKind,
+        BinOp, Block, CallableDecl, CallableKind, Expr, ExprKind, Field, Functor, Ident, ItemKind,
+        NodeId, Pat, PatKind, Res, SpecBody, SpecDecl, Stmt, StmtKind, UnOp,
     },
-    ty::Ty,
+    ty::{Prim, Ty},
     visit::{self, Visitor},
 };
+use rustc_hash::FxHashSet;
 
 use crate::linter::{hir::declare_hir_lints, Compilation};
 
@@ -39,6 +41,11 @@ declare_hir_lints! {
     (DeprecatedFunctionConstructor, LintLevel::Allow, "deprecated function constructors", "function constructors for struct types are deprecated, use `new` instead"),
     (DeprecatedWithOperator, LintLevel::Allow, "deprecated `w/` and `w/=` operators for structs", "`w/` and `w/=` operators for structs are deprecated, use `new` instead"),
     (DeprecatedDoubleColonOperator, LintLevel::Allow, "deprecated `::` for field access", "`::` operator is deprecated, use `.` instead"),
+    (QubitReleasedWithoutReset, LintLevel::Allow, "qubit may be released in a non-zero or entangled state", "measure or reset the qubit before it is released"),
+    (UnusedMeasurementResult, LintLevel::Allow, "measurement result is never used", "discard the result with `_` if the measurement is only needed for its effect on the qubit"),
+    (UnusedQubit, LintLevel::Allow, "qubit is allocated but never used", "remove the qubit allocation"),
+    (AdjointOfMeasurement, LintLevel::Allow, "adjoint of an operation that performs measurements", "measurements are not reversible, so this operation does not have a meaningful adjoint"),
+    (DoubleEquality, LintLevel::Allow, "floating-point values compared for exact equality", "compare the absolute difference of the values against a tolerance instead"),
 }
 
 /// Helper to check if an operation has desired operation characteristics
@@ -307,3 +314,541 @@ impl HirLintPass for DeprecatedDoubleColonOperator {
         }
     }
 }
+
+/// Pushes a lint unless the same lint was already reported at the same span. Specialization
+/// generation copies the body of a callable into the specializations it generates, so the
+/// same code can be visited more than once.
+fn push_unique(buffer: &mut Vec<Lint>, lint: Lint) {
+    if !buffer
+        .iter()
+        .any(|existing| existing.kind == lint.kind && existing.span == lint.span)
+    {
+        buffer.push(lint);
+    }
+}
+
+/// Name of the runtime callable that allocates a single qubit.
+const QUBIT_ALLOCATE: &str = "__quantum__rt__qubit_allocate";
+/// Name of the runtime callable that releases a single qubit.
+const QUBIT_RELEASE: &str = "__quantum__rt__qubit_release";
+/// Name of the core callable that allocates an array of qubits.
+const QUBIT_ARRAY_ALLOCATE: &str = "AllocateQubitArray";
+/// Name of the core callable that releases an array of qubits.
+const QUBIT_ARRAY_RELEASE: &str = "ReleaseQubitArray";
+
+/// Returns `true` if the callee is an operation.
+fn is_operation(callee: &Expr) -> bool {
+    matches!(&callee.ty, Ty::Arrow(arrow) if arrow.kind == CallableKind::Operation)
+}
+
+/// Returns `true` if the type is a `Result` or a (possibly nested) array of `Result`s.
+fn is_result_ty(ty: &Ty) -> bool {
+    match ty {
+        Ty::Prim(Prim::Result) => true,
+        Ty::Array(item) => is_result_ty(item),
+        _ => false,
+    }
+}
+
+/// Returns `true` if the expression is a call to an operation returning measurement results.
+fn is_measurement(expr: &Expr) -> bool {
+    matches!(&expr.kind, ExprKind::Call(callee, _) if is_operation(callee) && is_result_ty(&expr.ty))
+}
+
+/// Returns the declaration of the global callable a callee refers to, looking through
+/// any functor applications.
+fn resolve_callee<'a>(callee: &Expr, compilation: Compilation<'a>) -> Option<&'a CallableDecl> {
+    match &callee.kind {
+        ExprKind::UnOp(UnOp::Functor(_), inner) => resolve_callee(inner, compilation),
+        ExprKind::Var(Res::Item(item_id), _) => match &compilation.resolve_item_id(item_id).kind {
+            ItemKind::Callable(decl) => Some(decl),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Collects the local variables referenced by an expression or statement.
+#[derive(Default)]
+struct LocalRefs(Vec<NodeId>);
+
+impl Visitor<'_> for LocalRefs {
+    fn visit_expr(&mut self, expr: &Expr) {
+        if let ExprKind::Var(Res::Local(id), _) = &expr.kind {
+            self.0.push(*id);
+        }
+        visit::walk_expr(self, expr);
+    }
+}
+
+/// A qubit allocated by a `use` or `borrow` statement. The qubit allocation pass has
+/// already replaced these statements by calls to the runtime by the time the linter runs,
+/// so the allocations are recognized by those calls.
+struct AllocatedQubit {
+    name: Rc<str>,
+    /// The span of the name of the qubit.
+    span: Span,
+    /// The span of the statement allocating the qubit.
+    stmt_span: Span,
+    is_array: bool,
+    is_used: bool,
+    /// Whether the last statement of the block to act on the qubit is a gate that leaves
+    /// it in an unknown state.
+    needs_reset: bool,
+}
+
+/// Tracks the qubits allocated in a block and how the rest of the block uses them.
+struct QubitUsage<'a> {
+    compilation: Compilation<'a>,
+    qubits: Vec<AllocatedQubit>,
+    /// Maps the local variables bound to qubits to their index in `qubits`.
+    locals: Vec<(NodeId, usize)>,
+}
+
+impl<'a> QubitUsage<'a> {
+    fn of_block(block: &Block, compilation: Compilation<'a>) -> Vec<AllocatedQubit> {
+        let mut usage = Self {
+            compilation,
+            qubits: Vec::new(),
+            locals: Vec::new(),
+        };
+        for stmt in &block.stmts {
+            if let StmtKind::Local(_, pat, expr) = &stmt.kind {
+                if usage.track_allocation(stmt.span, pat, expr) || usage.track_alias(pat, expr) {
+                    continue;
+                }
+            }
+            if is_release(stmt, compilation) {
+                continue;
+            }
+            usage.visit_stmt(stmt);
+            usage.track_state(stmt);
+        }
+        usage.qubits
+    }
+
+    /// Updates whether the qubits acted on by the statement need a reset. Only a gate
+    /// applied directly by the statement is known to leave its targets in a non-zero
+    /// state, while any other operation, such as a measurement or a nested block that
+    /// may uncompute its qubits, is assumed to leave them clean. Statements that call
+    /// no operations, and the controls of a controlled gate, keep the qubit's state.
+    fn track_state(&mut self, stmt: &Stmt) {
+        let mut contains_operation = ContainsOperationCall(false);
+        contains_operation.visit_stmt(stmt);
+        if !contains_operation.0 {
+            return;
+        }
+        let mut refs = LocalRefs::default();
+        refs.visit_stmt(stmt);
+        let targets = gate_targets(stmt, self.compilation);
+        for id in refs.0 {
+            if let Some(index) = self.index_of(id) {
+                match &targets {
+                    Some((targets, _)) if targets.contains(&id) => {
+                        self.qubits[index].needs_reset = true;
+                    }
+                    Some((_, controls)) if controls.contains(&id) => {}
+                    _ => self.qubits[index].needs_reset = false,
+                }
+            }
+        }
+    }
+
+    fn track_allocation(&mut self, stmt_span: Span, pat: &Pat, expr: &Expr) -> bool {
+        let (PatKind::Bind(ident), ExprKind::Call(callee, _)) = (&pat.kind, &expr.kind) else {
+            return false;
+        };
+        let is_array = match resolve_callee(callee, self.compilation) {
+            Some(decl) if decl.name.name.as_ref() == QUBIT_ALLOCATE => false,
+            Some(decl) if decl.name.name.as_ref() == QUBIT_ARRAY_ALLOCATE => true,
+            _ => return false,
+        };
+        let source = self.compilation.get_source_code(stmt_span);
+        self.locals.push((ident.id, self.qubits.len()));
+        self.qubits.push(AllocatedQubit {
+            name: ident.name.clone(),
+            span: find_name(&source, &ident.name).map_or(stmt_span, |offset| Span {
+                lo: stmt_span.lo + offset,
+                hi: stmt_span.lo
+                    + offset
+                    + u32::try_from(ident.name.len()).expect("name length should fit in u32"),
+            }),
+            stmt_span,
+            is_array,
+            is_used: false,
+            needs_reset: false,
+        });
+        true
+    }
+
+    /// Tuple allocations like `use (a, b) = (Qubit(), Qubit());` allocate each qubit into a
+    /// generated local and then bind the user's names to those locals.
+    fn track_alias(&mut self, pat: &Pat, expr: &Expr) -> bool {
+        match (&pat.kind, &expr.kind) {
+            (PatKind::Bind(ident), ExprKind::Var(Res::Local(id), _)) => {
+                if let Some(index) = self.index_of(*id) {
+                    self.alias(ident, index);
+                    true
+                } else {
+                    false
+                }
+            }
+            (PatKind::Tuple(pats), ExprKind::Tuple(exprs)) if pats.len() == exprs.len() => {
+                let mut is_alias = false;
+                for (pat, expr) in pats.iter().zip(exprs) {
+                    is_alias |= self.track_alias(pat, expr);
+                }
+                is_alias
+            }
+            _ => false,
+        }
+    }
+
+    fn alias(&mut self, ident: &Ident, index: usize) {
+        self.locals.push((ident.id, index));
+        let qubit = &mut self.qubits[index];
+        qubit.name = ident.name.clone();
+        qubit.span = ident.span;
+    }
+
+    fn index_of(&self, id: NodeId) -> Option<usize> {
+        self.locals
+            .iter()
+            .find_map(|(local, index)| (*local == id).then_some(*index))
+    }
+}
+
+impl Visitor<'_> for QubitUsage<'_> {
+    fn visit_expr(&mut self, expr: &Expr) {
+        if let ExprKind::Var(Res::Local(id), _) = &expr.kind {
+            if let Some(index) = self.index_of(*id) {
+                self.qubits[index].is_used = true;
+            }
+        }
+        visit::walk_expr(self, expr);
+    }
+}
+
+/// Returns `true` if the statement releases qubits, which the qubit allocation pass
+/// generates at the end of the block that allocated them.
+fn is_release(stmt: &Stmt, compilation: Compilation) -> bool {
+    match &stmt.kind {
+        StmtKind::Semi(expr) | StmtKind::Expr(expr) => matches!(
+            &expr.kind,
+            ExprKind::Call(callee, _) if matches!(
+                resolve_callee(callee, compilation).map(|decl| &*decl.name.name),
+                Some(QUBIT_RELEASE | QUBIT_ARRAY_RELEASE)
+            )
+        ),
+        _ => false,
+    }
+}
+
+/// If the statement is a direct call to a gate, that is an operation returning `Unit`
+/// other than a reset, returns the locals referenced by its target arguments and by
+/// its control qubits.
+fn gate_targets(stmt: &Stmt, compilation: Compilation) -> Option<(Vec<NodeId>, Vec<NodeId>)> {
+    let (StmtKind::Semi(expr) | StmtKind::Expr(expr)) = &stmt.kind else {
+        return None;
+    };
+    let ExprKind::Call(callee, args) = &expr.kind else {
+        return None;
+    };
+    if !is_operation(callee) || expr.ty != Ty::UNIT {
+        return None;
+    }
+    if let Some("Reset" | "ResetAll") =
+        resolve_callee(callee, compilation).map(|decl| &*decl.name.name)
+    {
+        return None;
+    }
+    let mut controls = LocalRefs::default();
+    let (mut callee, mut args) = (&**callee, &**args);
+    loop {
+        match &callee.kind {
+            ExprKind::UnOp(UnOp::Functor(Functor::Adj), inner) => callee = &**inner,
+            ExprKind::UnOp(UnOp::Functor(Functor::Ctl), inner) => match &args.kind {
+                ExprKind::Tuple(items) if items.len() == 2 => {
+                    controls.visit_expr(&items[0]);
+                    callee = &**inner;
+                    args = &items[1];
+                }
+                _ => return None,
+            },
+            _ => break,
+        }
+    }
+    let mut targets = LocalRefs::default();
+    targets.visit_expr(args);
+    Some((targets.0, controls.0))
+}
+
+/// Looks for calls to operations.
+#[derive(Default)]
+struct ContainsOperationCall(bool);
+
+impl Visitor<'_> for ContainsOperationCall {
+    fn visit_expr(&mut self, expr: &Expr) {
+        if matches!(&expr.kind, ExprKind::Call(callee, _) if is_operation(callee)) {
+            self.0 = true;
+        } else if !self.0 {
+            visit::walk_expr(self, expr);
+        }
+    }
+}
+
+/// Collects the spans of the statements that borrow qubits. The qubit allocation pass
+/// lowers the `Qubit` statements of the HIR before the linter runs, so the `QubitSource`
+/// is taken from the same statement in the AST.
+#[derive(Default)]
+struct BorrowedQubits(FxHashSet<Span>);
+
+impl<'a> qsc_ast::visit::Visitor<'a> for BorrowedQubits {
+    fn visit_stmt(&mut self, stmt: &'a ast::Stmt) {
+        if let ast::StmtKind::Qubit(ast::QubitSource::Dirty, ..) = &*stmt.kind {
+            self.0.insert(stmt.span);
+        }
+        qsc_ast::visit::walk_stmt(self, stmt);
+    }
+}
+
+impl BorrowedQubits {
+    fn of_package(compilation: Compilation) -> FxHashSet<Span> {
+        let mut borrowed = Self::default();
+        qsc_ast::visit::Visitor::visit_package(
+            &mut borrowed,
+            &compilation.compile_unit.ast.package,
+        );
+        borrowed.0
+    }
+}
+
+/// Finds the offset of `name` as a whole identifier in `source`.
+fn find_name(source: &str, name: &str) -> Option<u32> {
+    let is_ident_char = |c: char| c.is_alphanumeric() || c == '_';
+    source
+        .match_indices(name)
+        .find(|(offset, _)| {
+            !source[..*offset].ends_with(is_ident_char)
+                && !source[offset + name.len()..].starts_with(is_ident_char)
+        })
+        .map(|(offset, _)| u32::try_from(offset).expect("offset should fit in u32"))
+}
+
+#[derive(Default)]
+struct QubitReleasedWithoutReset {
+    level: LintLevel,
+    /// Whether the callable being checked is adjointable.
+    is_adjointable: bool,
+    /// Spans of the statements that borrow qubits, collected on first use.
+    borrowed: Option<FxHashSet<Span>>,
+}
+
+/// Creates a lint for qubits whose last use before being released is a gate rather than
+/// a measurement or a reset. Borrowed qubits are expected to be returned in the state
+/// they were borrowed in, and adjointable operations can neither measure nor reset, so
+/// neither is reported.
+impl HirLintPass for QubitReleasedWithoutReset {
+    fn check_callable_decl(
+        &mut self,
+        decl: &CallableDecl,
+        _buffer: &mut Vec<Lint>,
+        _compilation: Compilation,
+    ) {
+        self.is_adjointable = decl.functors.contains(&Functor::Adj);
+    }
+
+    fn check_block(&mut self, block: &Block, buffer: &mut Vec<Lint>, compilation: Compilation) {
+        if self.is_adjointable {
+            return;
+        }
+        let borrowed = self
+            .borrowed
+            .get_or_insert_with(|| BorrowedQubits::of_package(compilation));
+        let unreset = QubitUsage::of_block(block, compilation)
+            .into_iter()
+            .filter(|qubit| qubit.needs_reset && !borrowed.contains(&qubit.stmt_span))
+            .collect::<Vec<_>>();
+        for qubit in unreset {
+            let reset = if qubit.is_array {
+                format!("ResetAll({});", qubit.name)
+            } else {
+                format!("Reset({});", qubit.name)
+            };
+            let code_action_edits = reset_insertion(block, &reset, compilation)
+                .into_iter()
+                .collect();
+            push_unique(buffer, lint!(self, qubit.span, code_action_edits));
+        }
+    }
+}
+
+/// Returns an edit inserting `reset` as the last statement of the block before its
+/// qubits are released. Statements generated by the qubit allocation pass have empty
+/// spans, and a trailing expression is captured into a local before the qubits are
+/// released so the reset has to go before that capture.
+fn reset_insertion(block: &Block, reset: &str, compilation: Compilation) -> Option<(String, Span)> {
+    let has_trailing_expr = matches!(
+        block.stmts.last(),
+        Some(Stmt { kind: StmtKind::Expr(_), span, .. }) if *span == Span::default()
+    );
+    let last = block
+        .stmts
+        .iter()
+        .rev()
+        .find(|stmt| stmt.span != Span::default())?;
+    let indentation = compilation.indentation_at_offset(last.span.lo) as usize;
+    if has_trailing_expr {
+        let at = Span {
+            lo: last.span.lo,
+            hi: last.span.lo,
+        };
+        Some((format!("{reset}\n{:indentation$}", ""), at))
+    } else {
+        let at = Span {
+            lo: last.span.hi,
+            hi: last.span.hi,
+        };
+        Some((format!("\n{:indentation$}{reset}", ""), at))
+    }
+}
+
+#[derive(Default)]
+struct UnusedQubit {
+    level: LintLevel,
+}
+
+/// Creates a lint for qubits that are allocated and then never referenced.
+impl HirLintPass for UnusedQubit {
+    fn check_block(&mut self, block: &Block, buffer: &mut Vec<Lint>, compilation: Compilation) {
+        let qubits = QubitUsage::of_block(block, compilation);
+        for qubit in &qubits {
+            if !qubit.is_used {
+                // The allocation can only be removed if it allocates no other qubits and
+                // doesn't have a block of its own.
+                let is_removable = qubits
+                    .iter()
+                    .filter(|other| other.stmt_span == qubit.stmt_span)
+                    .count()
+                    == 1
+                    && compilation
+                        .get_source_code(qubit.stmt_span)
+                        .trim_end()
+                        .ends_with(';');
+                let code_action_edits = if is_removable {
+                    vec![(String::new(), qubit.stmt_span)]
+                } else {
+                    vec![]
+                };
+                push_unique(buffer, lint!(self, qubit.span, code_action_edits));
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct UnusedMeasurementResult {
+    level: LintLevel,
+}
+
+/// Creates a lint for variables bound to measurement results that are never read.
+impl HirLintPass for UnusedMeasurementResult {
+    fn check_block(&mut self, block: &Block, buffer: &mut Vec<Lint>, _compilation: Compilation) {
+        for (i, stmt) in block.stmts.iter().enumerate() {
+            if let StmtKind::Local(_, pat, expr) = &stmt.kind {
+                if let PatKind::Bind(ident) = &pat.kind {
+                    if is_measurement(expr) {
+                        let mut refs = LocalRefs::default();
+                        for stmt in &block.stmts[i + 1..] {
+                            refs.visit_stmt(stmt);
+                        }
+                        if !refs.0.contains(&ident.id) {
+                            push_unique(
+                                buffer,
+                                lint!(self, ident.span, vec![("_".to_string(), ident.span)]),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Looks for measurements in the body of an operation.
+#[derive(Default)]
+struct ContainsMeasurement(bool);
+
+impl Visitor<'_> for ContainsMeasurement {
+    fn visit_expr(&mut self, expr: &Expr) {
+        if is_measurement(expr) {
+            self.0 = true;
+        } else if !self.0 {
+            visit::walk_expr(self, expr);
+        }
+    }
+}
+
+#[derive(Default)]
+struct AdjointOfMeasurement {
+    level: LintLevel,
+}
+
+/// Creates a lint for `Adjoint` applied to an operation whose body measures qubits. Such an
+/// operation can only be adjointable through an explicit adjoint specialization, which
+/// cannot undo the measurement.
+impl HirLintPass for AdjointOfMeasurement {
+    fn check_expr(&mut self, expr: &Expr, buffer: &mut Vec<Lint>, compilation: Compilation) {
+        if let ExprKind::UnOp(UnOp::Functor(Functor::Adj), op) = &expr.kind {
+            if let Some(decl) = resolve_callee(op, compilation) {
+                let mut contains_measurement = ContainsMeasurement::default();
+                contains_measurement.visit_spec_decl(&decl.body);
+                if contains_measurement.0 {
+                    push_unique(buffer, lint!(self, expr.span));
+                }
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct DoubleEquality {
+    level: LintLevel,
+}
+
+/// Creates a lint for `==` and `!=` comparisons of `Double` values. The code action
+/// compares the absolute difference of the values against a tolerance instead.
+impl HirLintPass for DoubleEquality {
+    fn check_expr(&mut self, expr: &Expr, buffer: &mut Vec<Lint>, compilation: Compilation) {
+        if let ExprKind::BinOp(op @ (BinOp::Eq | BinOp::Neq), lhs, rhs) = &expr.kind {
+            if lhs.ty == Ty::Prim(Prim::Double) {
+                let cmp = if *op == BinOp::Eq { "<" } else { ">=" };
+                let new_expr = format!(
+                    "Std.Math.AbsD({} - {}) {cmp} {DOUBLE_EQUALITY_TOLERANCE}",
+                    operand_source(lhs, compilation),
+                    operand_source(rhs, compilation),
+                );
+                push_unique(buffer, lint!(self, expr.span, vec![(new_expr, expr.span)]));
+            }
+        }
+    }
+}
+
+/// The tolerance used by the code action for [`DoubleEquality`].
+const DOUBLE_EQUALITY_TOLERANCE: &str = "1e-10";
+
+/// Returns the source code of an operand of a subtraction, in parentheses unless it
+/// binds more tightly than the subtraction.
+fn operand_source(expr: &Expr, compilation: Compilation) -> String {
+    let source = compilation.get_source_code(expr.span);
+    match &expr.kind {
+        ExprKind::Lit(_)
+        | ExprKind::Var(..)
+        | ExprKind::Call(..)
+        | ExprKind::Field(..)
+        | ExprKind::Index(..)
+        | ExprKind::BinOp(BinOp::Mul | BinOp::Div | BinOp::Mod | BinOp::Exp, ..)
+        | ExprKind::UnOp(UnOp::Neg, _) => source,
+        _ => format!("({source})"),
+    }
+}
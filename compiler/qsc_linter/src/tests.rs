@@ -597,6 +597,312 @@ fn needless_operation_inside_function_call() {
     );
 }
 
+#[test]
+fn qubit_released_without_reset() {
+    check(
+        indoc! {"
+    operation Main() : Unit {
+        use q = Qubit();
+        H(q);
+    }
+    "},
+        &expect![[r#"
+            [
+                SrcLint {
+                    source: "q",
+                    level: Allow,
+                    message: "qubit may be released in a non-zero or entangled state",
+                    help: "measure or reset the qubit before it is released",
+                    code_action_edits: [
+                        (
+                            "\n    Reset(q);",
+                            Span {
+                                lo: 84,
+                                hi: 84,
+                            },
+                        ),
+                    ],
+                },
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn qubit_released_without_reset_before_trailing_expr() {
+    check(
+        indoc! {"
+    operation Main() : Int {
+        use qs = Qubit[2];
+        ApplyToEach(H, qs);
+        Length(qs)
+    }
+    "},
+        &expect![[r#"
+            [
+                SrcLint {
+                    source: "qs",
+                    level: Allow,
+                    message: "qubit may be released in a non-zero or entangled state",
+                    help: "measure or reset the qubit before it is released",
+                    code_action_edits: [
+                        (
+                            "ResetAll(qs);\n    ",
+                            Span {
+                                lo: 104,
+                                hi: 104,
+                            },
+                        ),
+                    ],
+                },
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn qubit_measured_before_release() {
+    check(
+        indoc! {"
+    operation Main() : Result {
+        use q = Qubit();
+        H(q);
+        M(q)
+    }
+    "},
+        &expect![[r"
+            []
+        "]],
+    );
+}
+
+#[test]
+fn borrowed_qubit_released_without_reset() {
+    check(
+        indoc! {"
+    operation Main() : Unit {
+        borrow q = Qubit();
+        X(q);
+        X(q);
+    }
+    "},
+        &expect![[r"
+            []
+        "]],
+    );
+}
+
+#[test]
+fn qubit_only_used_as_control_is_not_reported() {
+    check(
+        indoc! {"
+    operation Main() : Unit {
+        use target = Qubit();
+        use ctl = Qubit();
+        Reset(target);
+        Controlled X([ctl], target);
+        Reset(target);
+    }
+    "},
+        &expect![[r"
+            []
+        "]],
+    );
+}
+
+#[test]
+fn qubit_uncomputed_by_conjugation_is_not_reported() {
+    check(
+        indoc! {"
+    operation Main() : Unit {
+        use q = Qubit();
+        within {
+            H(q);
+        } apply {
+            Z(q);
+        }
+    }
+    "},
+        &expect![[r"
+            []
+        "]],
+    );
+}
+
+#[test]
+fn qubit_released_in_adjointable_operation_is_not_reported() {
+    check(
+        indoc! {"
+    operation ApplyWithHelper(target : Qubit) : Unit is Adj {
+        use helper = Qubit();
+        CNOT(target, helper);
+        CNOT(target, helper);
+    }
+    "},
+        &expect![[r"
+            []
+        "]],
+    );
+}
+
+#[test]
+fn unused_qubit() {
+    check(
+        indoc! {"
+    operation Main() : Unit {
+        use q = Qubit();
+        use aux = Qubit();
+        H(q);
+        Reset(q);
+    }
+    "},
+        &expect![[r#"
+            [
+                SrcLint {
+                    source: "aux",
+                    level: Allow,
+                    message: "qubit is allocated but never used",
+                    help: "remove the qubit allocation",
+                    code_action_edits: [
+                        (
+                            "",
+                            Span {
+                                lo: 79,
+                                hi: 97,
+                            },
+                        ),
+                    ],
+                },
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn unused_measurement_result() {
+    check(
+        indoc! {"
+    operation Main() : Unit {
+        use q = Qubit();
+        let r = M(q);
+        Reset(q);
+    }
+    "},
+        &expect![[r#"
+            [
+                SrcLint {
+                    source: "r",
+                    level: Allow,
+                    message: "measurement result is never used",
+                    help: "discard the result with `_` if the measurement is only needed for its effect on the qubit",
+                    code_action_edits: [
+                        (
+                            "_",
+                            Span {
+                                lo: 83,
+                                hi: 84,
+                            },
+                        ),
+                    ],
+                },
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn adjoint_of_measurement() {
+    check(
+        indoc! {"
+    operation Main() : Unit {
+        use q = Qubit();
+        Adjoint MeasureAndFlip(q);
+        Reset(q);
+    }
+
+    operation MeasureAndFlip(q : Qubit) : Unit is Adj {
+        body ... {
+            if M(q) == One {
+                X(q);
+            }
+        }
+        adjoint self;
+    }
+    "},
+        &expect![[r#"
+            [
+                SrcLint {
+                    source: "Adjoint MeasureAndFlip",
+                    level: Allow,
+                    message: "adjoint of an operation that performs measurements",
+                    help: "measurements are not reversible, so this operation does not have a meaningful adjoint",
+                    code_action_edits: [],
+                },
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn double_equality() {
+    check(
+        indoc! {"
+    function IsHalf(x : Double) : Bool {
+        x == 0.5
+    }
+    "},
+        &expect![[r#"
+            [
+                SrcLint {
+                    source: "x == 0.5",
+                    level: Allow,
+                    message: "floating-point values compared for exact equality",
+                    help: "compare the absolute difference of the values against a tolerance instead",
+                    code_action_edits: [
+                        (
+                            "Std.Math.AbsD(x - 0.5) < 1e-10",
+                            Span {
+                                lo: 69,
+                                hi: 77,
+                            },
+                        ),
+                    ],
+                },
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn double_inequality_parenthesizes_operands() {
+    check(
+        indoc! {"
+    function IsDifferent(x : Double, y : Double) : Bool {
+        x + 1.0 != y * 2.0
+    }
+    "},
+        &expect![[r#"
+            [
+                SrcLint {
+                    source: "x + 1.0 != y * 2.0",
+                    level: Allow,
+                    message: "floating-point values compared for exact equality",
+                    help: "compare the absolute difference of the values against a tolerance instead",
+                    code_action_edits: [
+                        (
+                            "Std.Math.AbsD((x + 1.0) - y * 2.0) >= 1e-10",
+                            Span {
+                                lo: 86,
+                                hi: 104,
+                            },
+                        ),
+                    ],
+                },
+            ]
+        "#]],
+    );
+}
+
 fn check(source: &str, expected: &Expect) {
     let source = wrap_in_namespace(source);
     let mut store = PackageStore::new(compile::core());
@@ -9,8 +9,8 @@ use qsc_fir::{
     ty::{Arrow, InferFunctorId, ParamId, Ty},
 };
 use qsc_hir::hir::{self, SpecBody, SpecGen};
+use std::clone::Clone;
 use std::iter::once;
-use std::{clone::Clone, rc::Rc};
 
 #[must_use]
 pub fn map_hir_package_to_fir(package: hir::PackageId) -> fir::PackageId {
@@ -210,7 +210,7 @@ impl Lowerer {
                             .id,
                     ),
                     span: name.span(),
-                    name: name.name(),
+                    name: name.name().as_ref().into(),
                 };
                 let items = items.iter().map(|i| lower_local_item_id(*i)).collect();
                 fir::ItemKind::Namespace(name, items)
@@ -238,7 +238,7 @@ impl Lowerer {
             id: lower_local_item_id(item.id),
             span: item.span,
             parent: item.parent.map(lower_local_item_id),
-            doc: Rc::clone(&item.doc),
+            doc: item.doc.as_ref().into(),
             attrs,
             visibility: lower_visibility(item.visibility),
             kind,
@@ -745,7 +745,7 @@ impl Lowerer {
                 self.exec_graph.push(ExecGraphNode::Store);
                 fir::StringComponent::Expr(expr)
             }
-            hir::StringComponent::Lit(str) => fir::StringComponent::Lit(Rc::clone(str)),
+            hir::StringComponent::Lit(str) => fir::StringComponent::Lit(str.as_ref().into()),
         }
     }
 
@@ -802,13 +802,13 @@ impl Lowerer {
         fir::Ident {
             id: self.lower_local_id(ident.id),
             span: ident.span,
-            name: ident.name.clone(),
+            name: ident.name.as_ref().into(),
         }
     }
 
     fn lower_udt(&mut self, udt: &qsc_hir::ty::Udt) -> qsc_fir::ty::Udt {
         let span = udt.span;
-        let name = udt.name.clone();
+        let name = udt.name.as_ref().into();
         let definition = self.lower_udt_defn(&udt.definition);
         qsc_fir::ty::Udt {
             span,
@@ -872,7 +872,7 @@ impl Lowerer {
     fn lower_udt_field(&mut self, field: &qsc_hir::ty::UdtField) -> qsc_fir::ty::UdtField {
         qsc_fir::ty::UdtField {
             ty: self.lower_ty(&field.ty),
-            name: field.name.clone(),
+            name: field.name.as_deref().map(Into::into),
             name_span: field.name_span,
         }
     }
@@ -887,7 +887,10 @@ fn lower_attrs(attrs: &[hir::Attr]) -> Vec<fir::Attr> {
         .iter()
         .filter_map(|attr| match attr {
             hir::Attr::EntryPoint => Some(fir::Attr::EntryPoint),
-            hir::Attr::SimulatableIntrinsic | hir::Attr::Unimplemented | hir::Attr::Config => None,
+            hir::Attr::SimulatableIntrinsic
+            | hir::Attr::Unimplemented
+            | hir::Attr::Config
+            | hir::Attr::Test => None,
         })
         .collect()
 }
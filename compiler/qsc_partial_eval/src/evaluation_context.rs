@@ -86,6 +86,9 @@ pub struct BlockNode {
     pub successor: Option<BlockId>,
 }
 
+/// The frame ID the classical evaluator environment of a call scope uses.
+const CLASSICAL_EVALUATOR_CALL_SCOPE_ID: usize = 1;
+
 /// A call scope.
 pub struct Scope {
     /// The package ID of the callable.
@@ -118,7 +121,6 @@ impl Scope {
         // A default classical evaluator environment is created with one scope. However, we need to push an additional
         // scope to the environment to be able to detect whether the classical evaluator has returned from the call
         // scope.
-        const CLASSICAL_EVALUATOR_CALL_SCOPE_ID: usize = 1;
         let mut env = Env::default();
         env.push_scope(CLASSICAL_EVALUATOR_CALL_SCOPE_ID);
 
@@ -184,6 +186,11 @@ impl Scope {
             .value
     }
 
+    /// Gets the value of a hybrid local variable if it exists.
+    pub fn find_hybrid_local_value(&self, local_var_id: LocalVarId) -> Option<&Value> {
+        self.hybrid_vars.get(&local_var_id)
+    }
+
     /// Gets the value of a hybrid local variable.
     pub fn get_hybrid_local_value(&self, local_var_id: LocalVarId) -> &Value {
         self.hybrid_vars
@@ -204,6 +211,16 @@ impl Scope {
         vacant.insert(mutable_kind);
     }
 
+    /// Pushes a scope to the classical environment so the locals declared within a block do not outlive it.
+    pub fn push_classical_block_scope(&mut self) {
+        self.env.push_scope(CLASSICAL_EVALUATOR_CALL_SCOPE_ID);
+    }
+
+    /// Pops the scope pushed for a block from the classical environment.
+    pub fn pop_classical_block_scope(&mut self) {
+        self.env.leave_scope();
+    }
+
     /// Determines whether we are currently evaluating a branch within the scope.
     pub fn is_currently_evaluating_branch(&self) -> bool {
         self.active_block_count > 1
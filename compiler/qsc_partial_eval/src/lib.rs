@@ -32,11 +32,12 @@ use qsc_eval::{
 use qsc_fir::{
     fir::{
         self, BinOp, Block, BlockId, CallableDecl, CallableImpl, ExecGraph, Expr, ExprId, ExprKind,
-        Global, Ident, LocalVarId, Mutability, PackageId, PackageStore, PackageStoreLookup, Pat,
-        PatId, PatKind, Res, SpecDecl, SpecImpl, Stmt, StmtId, StmtKind, StoreBlockId, StoreExprId,
-        StoreItemId, StorePatId, StoreStmtId, UnOp,
+        Global, Ident, LocalVarId, Mutability, Package, PackageId, PackageLookup, PackageStore,
+        PackageStoreLookup, Pat, PatId, PatKind, Res, SpecDecl, SpecImpl, Stmt, StmtId, StmtKind,
+        StoreBlockId, StoreExprId, StoreItemId, StorePatId, StoreStmtId, UnOp,
     },
     ty::{Prim, Ty},
+    visit::{walk_expr, Visitor},
 };
 use qsc_lowerer::map_fir_package_to_hir;
 use qsc_rca::{
@@ -50,11 +51,11 @@ use qsc_rca::{
 use qsc_rir::{
     builder,
     rir::{
-        self, Callable, CallableId, CallableType, ConditionCode, Instruction, Literal, Operand,
-        Program,
+        self, Callable, CallableId, CallableType, ConditionCode, FcmpConditionCode, Instruction,
+        Literal, Operand, Program,
     },
 };
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 use std::{collections::hash_map::Entry, rc::Rc, result::Result};
 use thiserror::Error;
 
@@ -416,10 +417,15 @@ impl<'a> PartialEvaluator<'a> {
                     bin_op_expr_span,
                 )
             }
-            Value::Double(_lhs_double) => Err(Error::Unimplemented(
-                "double binary operation".to_string(),
-                lhs_span,
-            )),
+            Value::Double(lhs_double) => {
+                let lhs_operand = Operand::Literal(Literal::Double(lhs_double));
+                self.eval_bin_op_with_lhs_double_operand(
+                    bin_op,
+                    lhs_operand,
+                    rhs_expr_id,
+                    bin_op_expr_span,
+                )
+            }
             Value::Var(lhs_eval_var) => {
                 self.eval_bin_op_with_lhs_var(bin_op, lhs_eval_var, rhs_expr_id, bin_op_expr_span)
             }
@@ -842,6 +848,62 @@ impl<'a> PartialEvaluator<'a> {
         Ok(EvalControlFlow::Continue(value))
     }
 
+    fn eval_bin_op_with_lhs_double_operand(
+        &mut self,
+        bin_op: BinOp,
+        lhs_operand: Operand,
+        rhs_expr_id: ExprId,
+        bin_op_expr_span: PackageSpan, // For diagnostic purposes only.
+    ) -> Result<EvalControlFlow, Error> {
+        assert!(
+            matches!(lhs_operand.get_type(), rir::Ty::Double),
+            "LHS is expected to be of double type"
+        );
+
+        // Try to evaluate the RHS expression to get its value and construct its operand.
+        let rhs_control_flow = self.try_eval_expr(rhs_expr_id)?;
+        let EvalControlFlow::Continue(rhs_value) = rhs_control_flow else {
+            return Err(Error::Unexpected(
+                "embedded return in RHS expression".to_string(),
+                self.get_expr_package_span(rhs_expr_id),
+            ));
+        };
+        let rhs_operand = self.map_eval_value_to_rir_operand(&rhs_value);
+        assert!(
+            matches!(rhs_operand.get_type(), rir::Ty::Double),
+            "RHS value is expected to be of double type"
+        );
+
+        // If both operands are literals, evaluate the binary operation and return its value.
+        if let (Operand::Literal(lhs_literal), Operand::Literal(rhs_literal)) =
+            (lhs_operand, rhs_operand)
+        {
+            let value = eval_bin_op_with_double_literals(
+                bin_op,
+                lhs_literal,
+                rhs_literal,
+                bin_op_expr_span,
+            )?;
+            return Ok(EvalControlFlow::Continue(value));
+        }
+
+        // Generate the instructions.
+        let bin_op_rir_variable = self
+            .generate_instructions_for_binary_operation_with_double_operands(
+                bin_op,
+                lhs_operand,
+                rhs_operand,
+                bin_op_expr_span,
+            )?;
+        let value = Value::Var(map_rir_var_to_eval_var(bin_op_rir_variable).map_err(|()| {
+            Error::Unexpected(
+                format!("{} type in binop", bin_op_rir_variable.ty),
+                bin_op_expr_span,
+            )
+        })?);
+        Ok(EvalControlFlow::Continue(value))
+    }
+
     fn eval_bin_op_with_lhs_var(
         &mut self,
         bin_op: BinOp,
@@ -863,14 +925,39 @@ impl<'a> PartialEvaluator<'a> {
                     bin_op_expr_span,
                 )
             }
-            VarTy::Double => Err(Error::Unimplemented(
-                "double binary operation with dynamic LHS".to_string(),
-                bin_op_expr_span,
-            )),
+            VarTy::Double => {
+                let lhs_rir_var = map_eval_var_to_rir_var(lhs_eval_var);
+                let lhs_operand = Operand::Variable(lhs_rir_var);
+                self.eval_bin_op_with_lhs_double_operand(
+                    bin_op,
+                    lhs_operand,
+                    rhs_expr_id,
+                    bin_op_expr_span,
+                )
+            }
         }
     }
 
     fn eval_classical_expr(&mut self, expr_id: ExprId) -> Result<EvalControlFlow, Error> {
+        let eval_result = self.eval_expr_in_classical_env(expr_id);
+
+        // If this was an assign expression, update the bindings in the hybrid side to keep them in sync and to insert
+        // store instructions for variables of type `Bool`, `Int` or `Double`.
+        if let Ok(EvalControlFlow::Continue(_)) = eval_result {
+            let expr = self.get_expr(expr_id);
+            if let ExprKind::Assign(lhs_expr_id, _)
+            | ExprKind::AssignField(lhs_expr_id, _, _)
+            | ExprKind::AssignIndex(lhs_expr_id, _, _)
+            | ExprKind::AssignOp(_, lhs_expr_id, _) = &expr.kind
+            {
+                self.update_hybrid_bindings_from_classical_bindings(*lhs_expr_id)?;
+            }
+        }
+
+        eval_result
+    }
+
+    fn eval_expr_in_classical_env(&mut self, expr_id: ExprId) -> Result<EvalControlFlow, Error> {
         let current_package_id = self.get_current_package_id();
         let store_expr_id = StoreExprId::from((current_package_id, expr_id));
         let expr = self.package_store.get_expr(store_expr_id);
@@ -886,7 +973,7 @@ impl<'a> PartialEvaluator<'a> {
             &[],
             StepAction::Continue,
         );
-        let eval_result = match classical_result {
+        match classical_result {
             Ok(step_result) => {
                 let StepResult::Return(value) = step_result else {
                     panic!("evaluating a classical expression should always return a value");
@@ -902,22 +989,7 @@ impl<'a> PartialEvaluator<'a> {
                 Ok(eval_control_flow)
             }
             Err((error, _)) => Err(Error::from(error)),
-        };
-
-        // If this was an assign expression, update the bindings in the hybrid side to keep them in sync and to insert
-        // store instructions for variables of type `Bool`, `Int` or `Double`.
-        if let Ok(EvalControlFlow::Continue(_)) = eval_result {
-            let expr = self.get_expr(expr_id);
-            if let ExprKind::Assign(lhs_expr_id, _)
-            | ExprKind::AssignField(lhs_expr_id, _, _)
-            | ExprKind::AssignIndex(lhs_expr_id, _, _)
-            | ExprKind::AssignOp(_, lhs_expr_id, _) = &expr.kind
-            {
-                self.update_hybrid_bindings_from_classical_bindings(*lhs_expr_id)?;
-            }
         }
-
-        eval_result
     }
 
     fn eval_hybrid_expr(&mut self, expr_id: ExprId) -> Result<EvalControlFlow, Error> {
@@ -1323,6 +1395,9 @@ impl<'a> PartialEvaluator<'a> {
             "__quantum__qis__mresetz__body" => {
                 Ok(self.measure_qubit(builder::mresetz_decl(), args_value))
             }
+            // Conversions between integers and doubles map to cast instructions.
+            "IntAsDouble" => Ok(self.cast_value(&args_value, rir::Ty::Double)),
+            "Truncate" => Ok(self.cast_value(&args_value, rir::Ty::Integer)),
             // The following intrinsic operations and functions are no-ops.
             "BeginEstimateCaching" => Ok(Value::Bool(true)),
             "DumpRegister"
@@ -1705,14 +1780,19 @@ impl<'a> PartialEvaluator<'a> {
         // Generate the instruction depending on the unary operator.
         let value_operand = self.map_eval_value_to_rir_operand(&value);
         let instruction = match un_op {
-            UnOp::Neg => {
-                let constant = match rir_variable_type {
-                    rir::Ty::Integer => Operand::Literal(Literal::Integer(-1)),
-                    rir::Ty::Double => Operand::Literal(Literal::Double(-1.0)),
-                    _ => panic!("invalid type for negation operator {rir_variable_type}"),
-                };
-                Instruction::Mul(constant, value_operand, rir_variable)
-            }
+            UnOp::Neg => match rir_variable_type {
+                rir::Ty::Integer => Instruction::Mul(
+                    Operand::Literal(Literal::Integer(-1)),
+                    value_operand,
+                    rir_variable,
+                ),
+                rir::Ty::Double => Instruction::Fmul(
+                    Operand::Literal(Literal::Double(-1.0)),
+                    value_operand,
+                    rir_variable,
+                ),
+                _ => panic!("invalid type for negation operator {rir_variable_type}"),
+            },
             UnOp::NotB => {
                 assert!(matches!(rir_variable_type, rir::Ty::Integer));
                 Instruction::BitwiseNot(value_operand, rir_variable)
@@ -1800,10 +1880,6 @@ impl<'a> PartialEvaluator<'a> {
         body_block_id: BlockId,
     ) -> Result<EvalControlFlow, Error> {
         // Verify assumptions.
-        assert!(
-            self.is_classical_expr(condition_expr_id),
-            "loop conditions must be purely classical"
-        );
         let body_block = self.get_block(body_block_id);
         assert_eq!(
             body_block.ty,
@@ -1811,6 +1887,17 @@ impl<'a> PartialEvaluator<'a> {
             "the type of a loop block is expected to be Unit"
         );
 
+        // If the loop condition is dynamic, the loop cannot be unrolled so generate a loop structure instead.
+        if self.is_dynamic_expr(condition_expr_id) {
+            return self.eval_expr_while_with_dynamic_condition(condition_expr_id, body_block_id);
+        }
+
+        // If the target supports backwards branching and the body does not depend on the iteration being evaluated, keep
+        // the loop structure instead of unrolling it.
+        if let Some(counted_loop) = self.try_get_counted_loop(condition_expr_id, body_block_id) {
+            return self.eval_counted_loop(condition_expr_id, body_block_id, &counted_loop);
+        }
+
         // Evaluate the block until the loop condition is false.
         let condition_expr_span = self.get_expr_package_span(condition_expr_id);
         let mut condition_control_flow = self.try_eval_expr(condition_expr_id)?;
@@ -1843,6 +1930,335 @@ impl<'a> PartialEvaluator<'a> {
         Ok(EvalControlFlow::Continue(Value::unit()))
     }
 
+    fn eval_expr_while_with_dynamic_condition(
+        &mut self,
+        condition_expr_id: ExprId,
+        body_block_id: BlockId,
+    ) -> Result<EvalControlFlow, Error> {
+        // Loops with a dynamic condition are only possible when the target supports backwards branching, which
+        // should have been verified by the capabilities check before reaching this point.
+        assert!(
+            self.program
+                .config
+                .capabilities
+                .contains(TargetCapabilityFlags::BackwardsBranching),
+            "loops with dynamic conditions require backwards branching"
+        );
+
+        // The body of the loop is only evaluated once, so any mutable variable updated within the loop can have a
+        // different value on each iteration and must be treated as dynamic from this point on.
+        self.make_locals_assigned_in_block_dynamic(body_block_id)?;
+
+        // Pop the current block node and generate the block that execution continues to once the loop finishes.
+        let current_block_node = self.eval_context.pop_block_node();
+        let exit_block_node_id = self.create_program_block();
+        let exit_block_node = BlockNode {
+            id: exit_block_node_id,
+            successor: current_block_node.successor,
+        };
+        self.eval_context.push_block_node(exit_block_node);
+
+        // Create the loop header block, which evaluates the condition on every iteration, and jump to it.
+        let header_block_node_id = self.create_program_block();
+        self.get_program_block_mut(current_block_node.id)
+            .0
+            .push(Instruction::Jump(header_block_node_id));
+        let header_block_node = BlockNode {
+            id: header_block_node_id,
+            successor: Some(exit_block_node_id),
+        };
+        self.eval_context.push_block_node(header_block_node);
+        let condition_expr_span = self.get_expr_package_span(condition_expr_id);
+        let condition_control_flow = self.try_eval_expr(condition_expr_id)?;
+        if condition_control_flow.is_return() {
+            return Err(Error::Unexpected(
+                "embedded return in loop condition".to_string(),
+                condition_expr_span,
+            ));
+        }
+        let condition_value = condition_control_flow.into_value();
+
+        // Evaluating the condition could have generated additional blocks, so pop whichever block is active now since
+        // that is where the branch instruction has to be inserted.
+        let condition_block_node = self.eval_context.pop_block_node();
+
+        // Evaluate the loop body in its own block, which jumps back to the header once it is done.
+        let body_block_node_id = self.create_program_block();
+        let body_block_node = BlockNode {
+            id: body_block_node_id,
+            successor: Some(header_block_node_id),
+        };
+        self.eval_context.push_block_node(body_block_node);
+        let body_control_flow = self.try_eval_block(body_block_id)?;
+        if body_control_flow.is_return() {
+            let body_block = self.get_block(body_block_id);
+            return Err(Error::Unimplemented(
+                "return within a loop with a dynamic condition".to_string(),
+                PackageSpan {
+                    package: map_fir_package_to_hir(self.get_current_package_id()),
+                    span: body_block.span,
+                },
+            ));
+        }
+        self.get_current_rir_block_mut()
+            .0
+            .push(Instruction::Jump(header_block_node_id));
+        let _ = self.eval_context.pop_block_node();
+
+        // Finally, insert the branch instruction that either enters the loop body or exits the loop.
+        let Value::Var(condition_var) = condition_value else {
+            return Err(Error::Unexpected(
+                "dynamic loop condition evaluated to a static value".to_string(),
+                condition_expr_span,
+            ));
+        };
+        let condition_rir_var = map_eval_var_to_rir_var(condition_var);
+        let branch_ins =
+            Instruction::Branch(condition_rir_var, body_block_node_id, exit_block_node_id);
+        self.get_program_block_mut(condition_block_node.id)
+            .0
+            .push(branch_ins);
+
+        // The value of a loop expression is always unit.
+        Ok(EvalControlFlow::Continue(Value::unit()))
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn try_get_counted_loop(
+        &self,
+        condition_expr_id: ExprId,
+        body_block_id: BlockId,
+    ) -> Option<CountedLoop> {
+        let capabilities = self.program.config.capabilities;
+        if !capabilities.contains(
+            TargetCapabilityFlags::BackwardsBranching | TargetCapabilityFlags::IntegerComputations,
+        ) || !self.is_classical_expr(condition_expr_id)
+        {
+            return None;
+        }
+
+        // The locals carried across iterations are the ones assigned within the body but declared outside of it.
+        let package = self.package_store.get(self.get_current_package_id());
+        let mut assigned_locals = AssignedLocalsCollector {
+            package,
+            locals: FxHashMap::default(),
+        };
+        assigned_locals.visit_block(body_block_id);
+        let mut body_locals = LocalsCollector::new(package);
+        body_locals.visit_block(body_block_id);
+        if body_locals.has_return {
+            return None;
+        }
+        let carried_locals: FxHashSet<LocalVarId> = assigned_locals
+            .locals
+            .into_keys()
+            .filter(|local_var_id| !body_locals.declared.contains(local_var_id))
+            .collect();
+
+        // Carried locals can only be read by the statements that update them, so those statements can be classically
+        // evaluated to count the iterations while the rest of the body only needs to be evaluated once.
+        let mut counted_loop = CountedLoop::default();
+        for stmt_id in &self.get_block(body_block_id).stmts {
+            let mut stmt_locals = LocalsCollector::new(package);
+            stmt_locals.visit_stmt(*stmt_id);
+            if stmt_locals.referenced.is_disjoint(&carried_locals) {
+                counted_loop.body_stmts.push(*stmt_id);
+                continue;
+            }
+
+            let stmt = self.get_stmt(*stmt_id);
+            let counter_expr_id = match stmt.kind {
+                StmtKind::Expr(expr_id) | StmtKind::Semi(expr_id) => {
+                    let (ExprKind::Assign(lhs_expr_id, _) | ExprKind::AssignOp(_, lhs_expr_id, _)) =
+                        self.get_expr(expr_id).kind
+                    else {
+                        return None;
+                    };
+                    let ExprKind::Var(Res::Local(local_var_id), _) =
+                        self.get_expr(lhs_expr_id).kind
+                    else {
+                        return None;
+                    };
+                    if !carried_locals.contains(&local_var_id) {
+                        return None;
+                    }
+                    if !counted_loop
+                        .carried_local_exprs
+                        .iter()
+                        .any(|(id, _)| *id == local_var_id)
+                    {
+                        counted_loop
+                            .carried_local_exprs
+                            .push((local_var_id, lhs_expr_id));
+                    }
+                    expr_id
+                }
+                StmtKind::Local(_, pat_id, expr_id)
+                    if matches!(self.get_pat(pat_id).kind, PatKind::Discard) =>
+                {
+                    expr_id
+                }
+                StmtKind::Local(..) | StmtKind::Item(_) => return None,
+            };
+            if !self.is_classical_expr(counter_expr_id)
+                || !stmt_locals.referenced.is_disjoint(&body_locals.declared)
+            {
+                return None;
+            }
+            counted_loop.counter_exprs.push(counter_expr_id);
+        }
+
+        // Only keep the loop when there is something to count and the body generates instructions at all.
+        let body_is_classical =
+            counted_loop
+                .body_stmts
+                .iter()
+                .all(|stmt_id| match self.get_stmt(*stmt_id).kind {
+                    StmtKind::Expr(expr_id)
+                    | StmtKind::Semi(expr_id)
+                    | StmtKind::Local(_, _, expr_id) => self.is_classical_expr(expr_id),
+                    StmtKind::Item(_) => true,
+                });
+        if counted_loop.counter_exprs.is_empty() || body_is_classical {
+            return None;
+        }
+
+        Some(counted_loop)
+    }
+
+    fn count_loop_iterations(
+        &mut self,
+        condition_expr_id: ExprId,
+        counted_loop: &CountedLoop,
+    ) -> Result<i64, Error> {
+        // Classically evaluate the condition and the statements that update the carried locals to find out how many
+        // times the body runs.
+        let condition_expr_span = self.get_expr_package_span(condition_expr_id);
+        let mut iteration_count: i64 = 0;
+        loop {
+            let condition_control_flow = self.eval_expr_in_classical_env(condition_expr_id)?;
+            if condition_control_flow.is_return() {
+                return Err(Error::Unexpected(
+                    "embedded return in loop condition".to_string(),
+                    condition_expr_span,
+                ));
+            }
+            if !condition_control_flow.into_value().unwrap_bool() {
+                break;
+            }
+            for counter_expr_id in &counted_loop.counter_exprs {
+                let _ = self.eval_expr_in_classical_env(*counter_expr_id)?;
+            }
+            iteration_count += 1;
+        }
+        Ok(iteration_count)
+    }
+
+    fn eval_counted_loop(
+        &mut self,
+        condition_expr_id: ExprId,
+        body_block_id: BlockId,
+        counted_loop: &CountedLoop,
+    ) -> Result<EvalControlFlow, Error> {
+        let iteration_count = self.count_loop_iterations(condition_expr_id, counted_loop)?;
+        if iteration_count == 0 {
+            return Ok(EvalControlFlow::Continue(Value::unit()));
+        }
+
+        // Initialize the counter that drives the loop.
+        let counter_var = rir::Variable {
+            variable_id: self.resource_manager.next_var(),
+            ty: rir::Ty::Integer,
+        };
+        self.get_current_rir_block_mut().0.push(Instruction::Store(
+            Operand::Literal(Literal::Integer(0)),
+            counter_var,
+        ));
+
+        // Pop the current block node and generate the block that execution continues to once the loop finishes.
+        let current_block_node = self.eval_context.pop_block_node();
+        let exit_block_node_id = self.create_program_block();
+        let exit_block_node = BlockNode {
+            id: exit_block_node_id,
+            successor: current_block_node.successor,
+        };
+        self.eval_context.push_block_node(exit_block_node);
+
+        // Create the loop header block, which compares the counter against the iteration count, and jump to it.
+        let header_block_node_id = self.create_program_block();
+        self.get_program_block_mut(current_block_node.id)
+            .0
+            .push(Instruction::Jump(header_block_node_id));
+        let body_block_node_id = self.create_program_block();
+        let condition_var = rir::Variable {
+            variable_id: self.resource_manager.next_var(),
+            ty: rir::Ty::Boolean,
+        };
+        let header_block = self.get_program_block_mut(header_block_node_id);
+        header_block.0.push(Instruction::Icmp(
+            ConditionCode::Slt,
+            Operand::Variable(counter_var),
+            Operand::Literal(Literal::Integer(iteration_count)),
+            condition_var,
+        ));
+        header_block.0.push(Instruction::Branch(
+            condition_var,
+            body_block_node_id,
+            exit_block_node_id,
+        ));
+
+        // Evaluate the body once, increment the counter and jump back to the header.
+        let body_block_node = BlockNode {
+            id: body_block_node_id,
+            successor: Some(header_block_node_id),
+        };
+        self.eval_context.push_block_node(body_block_node);
+        self.eval_context
+            .get_current_scope_mut()
+            .push_classical_block_scope();
+        for stmt_id in &counted_loop.body_stmts {
+            if self.try_eval_stmt(*stmt_id)?.is_return() {
+                let body_block = self.get_block(body_block_id);
+                return Err(Error::Unexpected(
+                    "embedded return in counted loop".to_string(),
+                    PackageSpan {
+                        package: map_fir_package_to_hir(self.get_current_package_id()),
+                        span: body_block.span,
+                    },
+                ));
+            }
+        }
+        self.eval_context
+            .get_current_scope_mut()
+            .pop_classical_block_scope();
+        let incremented_counter_var = rir::Variable {
+            variable_id: self.resource_manager.next_var(),
+            ty: rir::Ty::Integer,
+        };
+        let current_block = self.get_current_rir_block_mut();
+        current_block.0.push(Instruction::Add(
+            Operand::Variable(counter_var),
+            Operand::Literal(Literal::Integer(1)),
+            incremented_counter_var,
+        ));
+        current_block.0.push(Instruction::Store(
+            Operand::Variable(incremented_counter_var),
+            counter_var,
+        ));
+        current_block
+            .0
+            .push(Instruction::Jump(header_block_node_id));
+        let _ = self.eval_context.pop_block_node();
+
+        // The carried locals hold their final values in the classical environment, so sync the hybrid side with them.
+        for (_, lhs_expr_id) in &counted_loop.carried_local_exprs {
+            self.update_hybrid_bindings_from_classical_bindings(*lhs_expr_id)?;
+        }
+
+        // The value of a loop expression is always unit.
+        Ok(EvalControlFlow::Continue(Value::unit()))
+    }
+
     fn eval_result_as_bool_operand(&mut self, result: val::Result) -> Operand {
         match result {
             val::Result::Id(id) => {
@@ -2079,6 +2495,57 @@ impl<'a> PartialEvaluator<'a> {
         Ok(rir_variable)
     }
 
+    fn generate_instructions_for_binary_operation_with_double_operands(
+        &mut self,
+        bin_op: BinOp,
+        lhs_operand: Operand,
+        rhs_operand: Operand,
+        bin_op_expr_span: PackageSpan, // For diagnostic purposes only.
+    ) -> Result<rir::Variable, Error> {
+        let bin_op_variable_id = self.resource_manager.next_var();
+        let (bin_op_rir_ins, bin_op_rir_variable) = match bin_op {
+            BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div => {
+                let bin_op_rir_variable = rir::Variable::new_double(bin_op_variable_id);
+                let bin_op_rir_ins = match bin_op {
+                    BinOp::Add => Instruction::Fadd(lhs_operand, rhs_operand, bin_op_rir_variable),
+                    BinOp::Sub => Instruction::Fsub(lhs_operand, rhs_operand, bin_op_rir_variable),
+                    BinOp::Mul => Instruction::Fmul(lhs_operand, rhs_operand, bin_op_rir_variable),
+                    BinOp::Div => Instruction::Fdiv(lhs_operand, rhs_operand, bin_op_rir_variable),
+                    _ => panic!("invalid arithmetic operator: {bin_op:?}"),
+                };
+                (bin_op_rir_ins, bin_op_rir_variable)
+            }
+            BinOp::Eq | BinOp::Neq | BinOp::Gt | BinOp::Gte | BinOp::Lt | BinOp::Lte => {
+                let bin_op_rir_variable = rir::Variable::new_boolean(bin_op_variable_id);
+                let condition_code = match bin_op {
+                    BinOp::Eq => FcmpConditionCode::Oeq,
+                    BinOp::Neq => FcmpConditionCode::One,
+                    BinOp::Gt => FcmpConditionCode::Ogt,
+                    BinOp::Gte => FcmpConditionCode::Oge,
+                    BinOp::Lt => FcmpConditionCode::Olt,
+                    BinOp::Lte => FcmpConditionCode::Ole,
+                    _ => panic!("invalid comparison operator: {bin_op:?}"),
+                };
+                let bin_op_rir_ins = Instruction::Fcmp(
+                    condition_code,
+                    lhs_operand,
+                    rhs_operand,
+                    bin_op_rir_variable,
+                );
+                (bin_op_rir_ins, bin_op_rir_variable)
+            }
+            BinOp::Mod | BinOp::Exp => {
+                return Err(Error::Unimplemented(
+                    format!("dynamic double {bin_op:?} operation"),
+                    bin_op_expr_span,
+                ));
+            }
+            _ => panic!("unsupported binary operation for doubles: {bin_op:?}"),
+        };
+        self.get_current_rir_block_mut().0.push(bin_op_rir_ins);
+        Ok(bin_op_rir_variable)
+    }
+
     fn get_block(&self, id: BlockId) -> &'a Block {
         let block_id = StoreBlockId::from((self.get_current_package_id(), id));
         self.package_store.get_block(block_id)
@@ -2250,16 +2717,74 @@ impl<'a> PartialEvaluator<'a> {
             .expect("program block does not exist")
     }
 
+    fn make_locals_assigned_in_block_dynamic(&mut self, block_id: BlockId) -> Result<(), Error> {
+        let package = self.package_store.get(self.get_current_package_id());
+        let mut collector = AssignedLocalsCollector {
+            package,
+            locals: FxHashMap::default(),
+        };
+        collector.visit_block(block_id);
+
+        for (local_var_id, lhs_expr_id) in collector.locals {
+            let current_scope = self.eval_context.get_current_scope_mut();
+            match current_scope.find_hybrid_local_value(local_var_id) {
+                Some(Value::Var(var)) => {
+                    let variable_id = var.id.into();
+                    if let Some(mutable_kind) = current_scope.find_mutable_var_mut(variable_id) {
+                        *mutable_kind = MutableKind::Dynamic;
+                    }
+                }
+                // Locals declared within the block do not exist yet.
+                None => {}
+                // Any other local would keep the value from the single evaluation of the block, which is not
+                // necessarily the value it has when the block executes.
+                Some(_) => {
+                    let lhs_expr = self.get_expr(lhs_expr_id);
+                    return Err(Error::Unimplemented(
+                        format!(
+                            "re-assignment within a loop with a dynamic condition for type {}",
+                            lhs_expr.ty
+                        ),
+                        self.get_expr_package_span(lhs_expr_id),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn is_classical_expr(&self, expr_id: ExprId) -> bool {
         let compute_kind = self.get_expr_compute_kind(expr_id);
         matches!(compute_kind, ComputeKind::Classical)
     }
 
+    fn is_dynamic_expr(&self, expr_id: ExprId) -> bool {
+        let compute_kind = self.get_expr_compute_kind(expr_id);
+        matches!(compute_kind, ComputeKind::Quantum(QuantumProperties { value_kind, .. }) if value_kind.is_dynamic())
+    }
+
     fn allocate_qubit(&mut self) -> Value {
         let qubit = self.resource_manager.allocate_qubit();
         Value::Qubit(qubit)
     }
 
+    fn cast_value(&mut self, value: &Value, output_ty: rir::Ty) -> Value {
+        let value_operand = self.map_eval_value_to_rir_operand(value);
+        let variable = rir::Variable {
+            variable_id: self.resource_manager.next_var(),
+            ty: output_ty,
+        };
+        let instruction = match (value_operand.get_type(), output_ty) {
+            (rir::Ty::Integer, rir::Ty::Double) => Instruction::Sitofp(value_operand, variable),
+            (rir::Ty::Double, rir::Ty::Integer) => Instruction::Fptosi(value_operand, variable),
+            (input_ty, _) => panic!("unsupported cast from {input_ty} to {output_ty}"),
+        };
+        self.get_current_rir_block_mut().0.push(instruction);
+        Value::Var(
+            map_rir_var_to_eval_var(variable).expect("cast output should be a valid variable"),
+        )
+    }
+
     fn measure_qubit(&mut self, measure_callable: Callable, args_value: Value) -> Value {
         // Get the qubit and result IDs to use in the qubit measure instruction.
         let qubit = args_value.unwrap_qubit();
@@ -2597,10 +3122,10 @@ impl<'a> PartialEvaluator<'a> {
             Value::Var(var) => self.record_variable(ty, &mut instrs, var),
             Value::Bool(val) => self.record_bool(&mut instrs, val),
             Value::Int(val) => self.record_int(&mut instrs, val),
+            Value::Double(val) => self.record_double(&mut instrs, val),
 
             Value::BigInt(_)
             | Value::Closure(_)
-            | Value::Double(_)
             | Value::Global(_, _)
             | Value::Pauli(_)
             | Value::Qubit(_)
@@ -2623,6 +3148,18 @@ impl<'a> PartialEvaluator<'a> {
         ));
     }
 
+    fn record_double(&mut self, instrs: &mut Vec<Instruction>, val: f64) {
+        let double_record_callable_id = self.get_double_record_callable();
+        instrs.push(Instruction::Call(
+            double_record_callable_id,
+            vec![
+                Operand::Literal(Literal::Double(val)),
+                Operand::Literal(Literal::Pointer),
+            ],
+            None,
+        ));
+    }
+
     fn record_bool(&mut self, instrs: &mut Vec<Instruction>, val: bool) {
         let bool_record_callable_id = self.get_bool_record_callable();
         instrs.push(Instruction::Call(
@@ -2639,6 +3176,7 @@ impl<'a> PartialEvaluator<'a> {
         let record_callable_id = match ty {
             Ty::Prim(Prim::Bool) => self.get_bool_record_callable(),
             Ty::Prim(Prim::Int) => self.get_int_record_callable(),
+            Ty::Prim(Prim::Double) => self.get_double_record_callable(),
             _ => panic!("unsupported variable type in output recording"),
         };
         instrs.push(Instruction::Call(
@@ -2780,6 +3318,22 @@ impl<'a> PartialEvaluator<'a> {
         callable_id
     }
 
+    fn get_double_record_callable(&mut self) -> CallableId {
+        if let Some(id) = self
+            .callables_map
+            .get("__quantum__rt__double_record_output")
+        {
+            return *id;
+        }
+
+        let callable = builder::double_record_decl();
+        let callable_id = self.resource_manager.next_callable();
+        self.callables_map
+            .insert("__quantum__rt__double_record_output".into(), callable_id);
+        self.program.callables.insert(callable_id, callable);
+        callable_id
+    }
+
     fn get_int_record_callable(&mut self) -> CallableId {
         if let Some(id) = self.callables_map.get("__quantum__rt__int_record_output") {
             return *id;
@@ -2818,6 +3372,126 @@ impl<'a> PartialEvaluator<'a> {
     }
 }
 
+/// A loop with a classical condition whose body is the same on every iteration, so it can be kept as a loop driven by a
+/// counter instead of being unrolled.
+#[derive(Default)]
+struct CountedLoop {
+    /// The statements evaluated once to generate the body of the loop.
+    body_stmts: Vec<StmtId>,
+    /// The classical expressions that update the locals carried across iterations.
+    counter_exprs: Vec<ExprId>,
+    /// The locals carried across iterations along with an expression that refers to each of them.
+    carried_local_exprs: Vec<(LocalVarId, ExprId)>,
+}
+
+/// Collects the local variables that are declared and referenced within a block or statement.
+struct LocalsCollector<'a> {
+    package: &'a Package,
+    declared: FxHashSet<LocalVarId>,
+    referenced: FxHashSet<LocalVarId>,
+    has_return: bool,
+}
+
+impl<'a> LocalsCollector<'a> {
+    fn new(package: &'a Package) -> Self {
+        Self {
+            package,
+            declared: FxHashSet::default(),
+            referenced: FxHashSet::default(),
+            has_return: false,
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for LocalsCollector<'a> {
+    fn get_block(&self, id: BlockId) -> &'a Block {
+        self.package.get_block(id)
+    }
+
+    fn get_expr(&self, id: ExprId) -> &'a Expr {
+        self.package.get_expr(id)
+    }
+
+    fn get_pat(&self, id: PatId) -> &'a Pat {
+        self.package.get_pat(id)
+    }
+
+    fn get_stmt(&self, id: StmtId) -> &'a Stmt {
+        self.package.get_stmt(id)
+    }
+
+    fn visit_expr(&mut self, expr_id: ExprId) {
+        let expr = self.get_expr(expr_id);
+        match &expr.kind {
+            ExprKind::Var(Res::Local(local_var_id), _) => {
+                self.referenced.insert(*local_var_id);
+            }
+            ExprKind::Closure(captures, _) => self.referenced.extend(captures.iter().copied()),
+            ExprKind::Return(_) => self.has_return = true,
+            _ => {}
+        }
+        walk_expr(self, expr_id);
+    }
+
+    fn visit_ident(&mut self, ident: &'a Ident) {
+        self.declared.insert(ident.id);
+    }
+}
+
+/// Collects the local variables that are assigned to within a block.
+struct AssignedLocalsCollector<'a> {
+    package: &'a Package,
+    /// Maps each assigned local to the first expression that assigns it.
+    locals: FxHashMap<LocalVarId, ExprId>,
+}
+
+impl AssignedLocalsCollector<'_> {
+    fn collect_lhs_locals(&mut self, lhs_expr_id: ExprId) {
+        let lhs_expr = self.package.get_expr(lhs_expr_id);
+        match &lhs_expr.kind {
+            ExprKind::Var(Res::Local(local_var_id), _) => {
+                self.locals.entry(*local_var_id).or_insert(lhs_expr_id);
+            }
+            ExprKind::Tuple(exprs) => {
+                for expr_id in exprs {
+                    self.collect_lhs_locals(*expr_id);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for AssignedLocalsCollector<'a> {
+    fn get_block(&self, id: BlockId) -> &'a Block {
+        self.package.get_block(id)
+    }
+
+    fn get_expr(&self, id: ExprId) -> &'a Expr {
+        self.package.get_expr(id)
+    }
+
+    fn get_pat(&self, id: PatId) -> &'a Pat {
+        self.package.get_pat(id)
+    }
+
+    fn get_stmt(&self, id: StmtId) -> &'a Stmt {
+        self.package.get_stmt(id)
+    }
+
+    fn visit_expr(&mut self, expr_id: ExprId) {
+        let expr = self.get_expr(expr_id);
+        match &expr.kind {
+            ExprKind::Assign(lhs_expr_id, _)
+            | ExprKind::AssignOp(_, lhs_expr_id, _)
+            | ExprKind::AssignField(lhs_expr_id, _, _)
+            | ExprKind::AssignIndex(lhs_expr_id, _, _) => self.collect_lhs_locals(*lhs_expr_id),
+            _ => {}
+        }
+        walk_expr(self, expr_id);
+    }
+}
+
 fn eval_un_op_with_literals(un_op: UnOp, value: Value) -> Value {
     match un_op {
         UnOp::Neg => match value {
@@ -2875,6 +3549,46 @@ fn eval_bin_op_with_bool_literals(
     Value::Bool(bin_op_result)
 }
 
+// Doubles are compared exactly, matching the semantics of the classical evaluator.
+#[allow(clippy::float_cmp)]
+fn eval_bin_op_with_double_literals(
+    bin_op: BinOp,
+    lhs_literal: Literal,
+    rhs_literal: Literal,
+    bin_op_expr_span: PackageSpan, // For diagnostic purposes only
+) -> Result<Value, Error> {
+    // Validate that both literals are doubles.
+    let (Literal::Double(lhs_double), Literal::Double(rhs_double)) = (lhs_literal, rhs_literal)
+    else {
+        panic!("at least one literal is not a double: {lhs_literal}, {rhs_literal}");
+    };
+
+    match bin_op {
+        BinOp::Eq => Ok(Value::Bool(lhs_double == rhs_double)),
+        BinOp::Neq => Ok(Value::Bool(lhs_double != rhs_double)),
+        BinOp::Gt => Ok(Value::Bool(lhs_double > rhs_double)),
+        BinOp::Gte => Ok(Value::Bool(lhs_double >= rhs_double)),
+        BinOp::Lt => Ok(Value::Bool(lhs_double < rhs_double)),
+        BinOp::Lte => Ok(Value::Bool(lhs_double <= rhs_double)),
+        BinOp::Add => Ok(Value::Double(lhs_double + rhs_double)),
+        BinOp::Sub => Ok(Value::Double(lhs_double - rhs_double)),
+        BinOp::Mul => Ok(Value::Double(lhs_double * rhs_double)),
+        BinOp::Div => Ok(Value::Double(lhs_double / rhs_double)),
+        BinOp::Mod => {
+            if rhs_double == 0.0 {
+                Err(Error::EvaluationFailed(
+                    "division by zero".to_string(),
+                    bin_op_expr_span,
+                ))
+            } else {
+                Ok(Value::Double(lhs_double % rhs_double))
+            }
+        }
+        BinOp::Exp => Ok(Value::Double(lhs_double.powf(rhs_double))),
+        _ => panic!("invalid double operator: {bin_op:?}"),
+    }
+}
+
 fn eval_bin_op_with_integer_literals(
     bin_op: BinOp,
     lhs_literal: Literal,
@@ -1126,3 +1126,57 @@ fn call_to_operation_with_codegen_intrinsic_override_should_skip_impl() {
             Return"#]],
     );
 }
+
+#[test]
+fn call_to_int_as_double_with_dynamic_argument_generates_sitofp_instruction() {
+    let program = get_rir_program(indoc! {
+        r#"
+        namespace Test {
+            import Std.Convert.*;
+            @EntryPoint()
+            operation Main() : Unit {
+                use q = Qubit();
+                let i = MResetZ(q) == Zero ? 0 | 1;
+                Rx(IntAsDouble(i) * 0.5, q);
+            }
+        }
+        "#,
+    });
+    assert_block_instructions(
+        &program,
+        BlockId(1),
+        &expect![[r#"
+            Block:
+                Variable(3, Double) = Sitofp Variable(2, Integer)
+                Variable(4, Double) = Fmul Variable(3, Double), Double(0.5)
+                Call id(3), args( Variable(4, Double), Qubit(0), )
+                Call id(4), args( Integer(0), Pointer, )
+                Return"#]],
+    );
+}
+
+#[test]
+fn call_to_truncate_with_dynamic_argument_generates_fptosi_instruction() {
+    let program = get_rir_program(indoc! {
+        r#"
+        namespace Test {
+            import Std.Math.*;
+            @EntryPoint()
+            operation Main() : Int {
+                use q = Qubit();
+                let d = MResetZ(q) == Zero ? 0.0 | 1.5;
+                Truncate(d)
+            }
+        }
+        "#,
+    });
+    assert_block_instructions(
+        &program,
+        BlockId(1),
+        &expect![[r#"
+            Block:
+                Variable(3, Integer) = Fptosi Variable(2, Double)
+                Call id(3), args( Variable(3, Integer), Pointer, )
+                Return"#]],
+    );
+}
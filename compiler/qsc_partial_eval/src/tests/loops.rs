@@ -1,9 +1,13 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
-use super::{assert_block_instructions, assert_callable, get_rir_program};
+use super::{
+    assert_block_instructions, assert_blocks, assert_callable, assert_error,
+    get_partial_evaluation_error, get_rir_program, get_rir_program_with_capabilities,
+};
 use expect_test::expect;
 use indoc::indoc;
+use qsc_data_structures::target::TargetCapabilityFlags;
 use qsc_rir::rir::{BlockId, CallableId};
 
 #[test]
@@ -36,20 +40,26 @@ fn unitary_call_within_a_for_loop() {
             output_type: <VOID>
             body: <NONE>"#]],
     );
-    assert_block_instructions(
+    assert_blocks(
         &program,
-        BlockId(0),
         &expect![[r#"
-            Block:
+            Blocks:
+            Block 0:Block:
                 Variable(0, Integer) = Store Integer(1)
-                Call id(1), args( Qubit(0), )
-                Variable(0, Integer) = Store Integer(2)
-                Call id(1), args( Qubit(0), )
-                Variable(0, Integer) = Store Integer(3)
-                Call id(1), args( Qubit(0), )
+                Variable(1, Integer) = Store Integer(0)
+                Jump(2)
+            Block 1:Block:
                 Variable(0, Integer) = Store Integer(4)
                 Call id(2), args( Integer(0), Pointer, )
-                Return"#]],
+                Return
+            Block 2:Block:
+                Variable(2, Boolean) = Icmp Slt, Variable(1, Integer), Integer(3)
+                Branch Variable(2, Boolean), 3, 1
+            Block 3:Block:
+                Call id(1), args( Qubit(0), )
+                Variable(3, Integer) = Add Variable(1, Integer), Integer(1)
+                Variable(1, Integer) = Store Variable(3, Integer)
+                Jump(2)"#]],
     );
 }
 
@@ -85,20 +95,26 @@ fn unitary_call_within_a_while_loop() {
             output_type: <VOID>
             body: <NONE>"#]],
     );
-    assert_block_instructions(
+    assert_blocks(
         &program,
-        BlockId(0),
         &expect![[r#"
-            Block:
+            Blocks:
+            Block 0:Block:
                 Variable(0, Integer) = Store Integer(0)
-                Call id(1), args( Qubit(0), )
-                Variable(0, Integer) = Store Integer(1)
-                Call id(1), args( Qubit(0), )
-                Variable(0, Integer) = Store Integer(2)
-                Call id(1), args( Qubit(0), )
+                Variable(1, Integer) = Store Integer(0)
+                Jump(2)
+            Block 1:Block:
                 Variable(0, Integer) = Store Integer(3)
                 Call id(2), args( Integer(0), Pointer, )
-                Return"#]],
+                Return
+            Block 2:Block:
+                Variable(2, Boolean) = Icmp Slt, Variable(1, Integer), Integer(3)
+                Branch Variable(2, Boolean), 3, 1
+            Block 3:Block:
+                Call id(1), args( Qubit(0), )
+                Variable(3, Integer) = Add Variable(1, Integer), Integer(1)
+                Variable(1, Integer) = Store Variable(3, Integer)
+                Jump(2)"#]],
     );
 }
 
@@ -134,27 +150,113 @@ fn unitary_call_within_a_repeat_until_loop() {
             output_type: <VOID>
             body: <NONE>"#]],
     );
-    assert_block_instructions(
+    assert_blocks(
         &program,
-        BlockId(0),
         &expect![[r#"
-            Block:
+            Blocks:
+            Block 0:Block:
                 Variable(0, Integer) = Store Integer(0)
                 Variable(1, Boolean) = Store Bool(true)
+                Variable(2, Integer) = Store Integer(0)
+                Jump(2)
+            Block 1:Block:
+                Variable(0, Integer) = Store Integer(3)
+                Variable(1, Boolean) = Store Bool(false)
+                Call id(2), args( Integer(0), Pointer, )
+                Return
+            Block 2:Block:
+                Variable(3, Boolean) = Icmp Slt, Variable(2, Integer), Integer(3)
+                Branch Variable(3, Boolean), 3, 1
+            Block 3:Block:
                 Call id(1), args( Qubit(0), )
+                Variable(4, Integer) = Add Variable(2, Integer), Integer(1)
+                Variable(2, Integer) = Store Variable(4, Integer)
+                Jump(2)"#]],
+    );
+}
+
+#[test]
+fn unitary_call_within_a_for_loop_is_unrolled_without_backwards_branching() {
+    let program = get_rir_program_with_capabilities(
+        indoc! {
+            r#"
+            namespace Test {
+                operation op(q : Qubit) : Unit { body intrinsic; }
+                @EntryPoint()
+                operation Main() : Unit {
+                    use q = Qubit();
+                    for _ in 1..3 {
+                        op(q);
+                    }
+                }
+            }
+            "#,
+        },
+        TargetCapabilityFlags::all().difference(TargetCapabilityFlags::BackwardsBranching),
+    );
+
+    assert_blocks(
+        &program,
+        &expect![[r#"
+            Blocks:
+            Block 0:Block:
                 Variable(0, Integer) = Store Integer(1)
-                Variable(1, Boolean) = Store Bool(true)
                 Call id(1), args( Qubit(0), )
                 Variable(0, Integer) = Store Integer(2)
-                Variable(1, Boolean) = Store Bool(true)
                 Call id(1), args( Qubit(0), )
                 Variable(0, Integer) = Store Integer(3)
-                Variable(1, Boolean) = Store Bool(false)
+                Call id(1), args( Qubit(0), )
+                Variable(0, Integer) = Store Integer(4)
                 Call id(2), args( Integer(0), Pointer, )
                 Return"#]],
     );
 }
 
+#[test]
+fn mutable_int_updated_within_a_for_loop_keeps_its_final_value_after_the_loop() {
+    let program = get_rir_program(indoc! {
+        r#"
+        namespace Test {
+            operation op(q : Qubit) : Unit { body intrinsic; }
+            @EntryPoint()
+            operation Main() : Int {
+                use q = Qubit();
+                mutable count = 0;
+                for _ in 1..3 {
+                    op(q);
+                    set count += 2;
+                }
+                count
+            }
+        }
+        "#,
+    });
+
+    assert_blocks(
+        &program,
+        &expect![[r#"
+            Blocks:
+            Block 0:Block:
+                Variable(0, Integer) = Store Integer(0)
+                Variable(1, Integer) = Store Integer(1)
+                Variable(2, Integer) = Store Integer(0)
+                Jump(2)
+            Block 1:Block:
+                Variable(0, Integer) = Store Integer(6)
+                Variable(1, Integer) = Store Integer(4)
+                Call id(2), args( Integer(6), Pointer, )
+                Return
+            Block 2:Block:
+                Variable(3, Boolean) = Icmp Slt, Variable(2, Integer), Integer(3)
+                Branch Variable(3, Boolean), 3, 1
+            Block 3:Block:
+                Call id(1), args( Qubit(0), )
+                Variable(4, Integer) = Add Variable(2, Integer), Integer(1)
+                Variable(2, Integer) = Store Variable(4, Integer)
+                Jump(2)"#]],
+    );
+}
+
 #[test]
 fn rotation_call_within_a_for_loop() {
     let program = get_rir_program(indoc! {
@@ -377,3 +479,165 @@ fn mutable_int_updated_in_loop() {
                 Branch Variable(3, Boolean), 2, 1"#]],
     );
 }
+
+#[test]
+fn while_loop_with_dynamic_condition_generates_loop_blocks() {
+    let program = get_rir_program(indoc! {
+        r#"
+        namespace Test {
+            operation opA(q : Qubit) : Unit { body intrinsic; }
+            @EntryPoint()
+            operation Main() : Unit {
+                use q = Qubit();
+                mutable flag = true;
+                while flag {
+                    opA(q);
+                    set flag = MResetZ(q) == One;
+                }
+            }
+        }
+        "#,
+    });
+
+    assert_blocks(
+        &program,
+        &expect![[r#"
+            Blocks:
+            Block 0:Block:
+                Variable(0, Boolean) = Store Bool(true)
+                Jump(2)
+            Block 1:Block:
+                Call id(4), args( Integer(0), Pointer, )
+                Return
+            Block 2:Block:
+                Branch Variable(0, Boolean), 3, 1
+            Block 3:Block:
+                Call id(1), args( Qubit(0), )
+                Call id(2), args( Qubit(0), Result(0), )
+                Variable(1, Boolean) = Call id(3), args( Result(0), )
+                Variable(2, Boolean) = Store Variable(1, Boolean)
+                Variable(0, Boolean) = Store Variable(2, Boolean)
+                Jump(2)"#]],
+    );
+}
+
+#[test]
+fn mutable_int_updated_in_loop_with_dynamic_condition() {
+    let program = get_rir_program(indoc! {
+        r#"
+        namespace Test {
+            @EntryPoint()
+            operation Main() : Int {
+                use q = Qubit();
+                mutable count = 0;
+                mutable flag = true;
+                while flag {
+                    set count += 1;
+                    set flag = MResetZ(q) == One;
+                }
+                count
+            }
+        }
+        "#,
+    });
+
+    assert_blocks(
+        &program,
+        &expect![[r#"
+            Blocks:
+            Block 0:Block:
+                Variable(0, Integer) = Store Integer(0)
+                Variable(1, Boolean) = Store Bool(true)
+                Jump(2)
+            Block 1:Block:
+                Call id(3), args( Variable(0, Integer), Pointer, )
+                Return
+            Block 2:Block:
+                Branch Variable(1, Boolean), 3, 1
+            Block 3:Block:
+                Variable(2, Integer) = Add Variable(0, Integer), Integer(1)
+                Variable(0, Integer) = Store Variable(2, Integer)
+                Call id(1), args( Qubit(0), Result(0), )
+                Variable(3, Boolean) = Call id(2), args( Result(0), )
+                Variable(4, Boolean) = Store Variable(3, Boolean)
+                Variable(1, Boolean) = Store Variable(4, Boolean)
+                Jump(2)"#]],
+    );
+}
+
+#[test]
+fn mutable_array_updated_in_loop_with_dynamic_condition_raises_error() {
+    let error = get_partial_evaluation_error(indoc! {
+        r#"
+        namespace Test {
+            @EntryPoint()
+            operation Main() : Result[] {
+                use q = Qubit();
+                mutable results = [Zero, Zero];
+                mutable flag = true;
+                while flag {
+                    set results w/= 0 <- MResetZ(q);
+                    set flag = MResetZ(q) == One;
+                }
+                results
+            }
+        }
+        "#,
+    });
+
+    assert_error(
+        &error,
+        &expect![[
+            r#"Unimplemented("re-assignment within a loop with a dynamic condition for type (Result)[]", PackageSpan { package: PackageId(2), span: Span { lo: 201, hi: 208 } })"#
+        ]],
+    );
+}
+
+#[test]
+fn locals_declared_in_counted_loop_body_are_scoped_to_the_body() {
+    let program = get_rir_program(indoc! {
+        r#"
+        namespace Test {
+            operation rx(theta : Double, q : Qubit) : Unit { body intrinsic; }
+            @EntryPoint()
+            operation Main() : Int {
+                use q = Qubit();
+                mutable count = 0;
+                for _ in 1..3 {
+                    let angle = 0.5;
+                    rx(angle, q);
+                    set count += 1;
+                }
+                let angle = 1.5;
+                rx(angle, q);
+                count
+            }
+        }
+        "#,
+    });
+
+    assert_blocks(
+        &program,
+        &expect![[r#"
+            Blocks:
+            Block 0:Block:
+                Variable(0, Integer) = Store Integer(0)
+                Variable(1, Integer) = Store Integer(1)
+                Variable(2, Integer) = Store Integer(0)
+                Jump(2)
+            Block 1:Block:
+                Variable(0, Integer) = Store Integer(3)
+                Variable(1, Integer) = Store Integer(4)
+                Call id(1), args( Double(1.5), Qubit(0), )
+                Call id(2), args( Integer(3), Pointer, )
+                Return
+            Block 2:Block:
+                Variable(3, Boolean) = Icmp Slt, Variable(2, Integer), Integer(3)
+                Branch Variable(3, Boolean), 3, 1
+            Block 3:Block:
+                Call id(1), args( Double(0.5), Qubit(0), )
+                Variable(4, Integer) = Add Variable(2, Integer), Integer(1)
+                Variable(2, Integer) = Store Variable(4, Integer)
+                Jump(2)"#]],
+    );
+}
@@ -3170,3 +3170,170 @@ fn integer_less_or_equal_than_comparison_with_lhs_classical_integer_and_rhs_dyna
                 Jump(1)"#]],
     );
 }
+
+#[test]
+fn leading_negative_unary_operator_on_dynamic_double_generates_fmul_rir_instruction() {
+    let program = get_rir_program(indoc! {r#"
+        namespace Test {
+            @EntryPoint()
+            operation Main() : Double {
+                use q = Qubit();
+                let d = MResetZ(q) == Zero ? 0.0 | 1.0;
+                -d
+            }
+        }
+    "#});
+    let mresetz_callable_id = CallableId(1);
+    assert_callable(
+        &program,
+        mresetz_callable_id,
+        &expect![[r#"
+        Callable:
+            name: __quantum__qis__mresetz__body
+            call_type: Measurement
+            input_type:
+                [0]: Qubit
+                [1]: Result
+            output_type: <VOID>
+            body: <NONE>"#]],
+    );
+    let readout_callable_id = CallableId(2);
+    assert_callable(
+        &program,
+        readout_callable_id,
+        &expect![[r#"
+        Callable:
+            name: __quantum__qis__read_result__body
+            call_type: Readout
+            input_type:
+                [0]: Result
+            output_type: Boolean
+            body: <NONE>"#]],
+    );
+    let output_recording_callable_id = CallableId(3);
+    assert_callable(
+        &program,
+        output_recording_callable_id,
+        &expect![[r#"
+            Callable:
+                name: __quantum__rt__double_record_output
+                call_type: OutputRecording
+                input_type:
+                    [0]: Double
+                    [1]: Pointer
+                output_type: <VOID>
+                body: <NONE>"#]],
+    );
+    assert_blocks(
+        &program,
+        &expect![[r#"
+            Blocks:
+            Block 0:Block:
+                Call id(1), args( Qubit(0), Result(0), )
+                Variable(0, Boolean) = Call id(2), args( Result(0), )
+                Variable(1, Boolean) = Icmp Eq, Variable(0, Boolean), Bool(false)
+                Branch Variable(1, Boolean), 2, 3
+            Block 1:Block:
+                Variable(3, Double) = Fmul Double(-1), Variable(2, Double)
+                Call id(3), args( Variable(3, Double), Pointer, )
+                Return
+            Block 2:Block:
+                Variable(2, Double) = Store Double(0)
+                Jump(1)
+            Block 3:Block:
+                Variable(2, Double) = Store Double(1)
+                Jump(1)"#]],
+    );
+}
+
+#[test]
+fn double_arithmetic_with_dynamic_operands_generates_floating_point_rir_instructions() {
+    let program = get_rir_program(indoc! {r#"
+        namespace Test {
+            @EntryPoint()
+            operation Main() : Double {
+                use q = Qubit();
+                let d = MResetZ(q) == Zero ? 0.0 | 1.0;
+                (1.5 + d) * d - d / 2.0
+            }
+        }
+    "#});
+    assert_block_instructions(
+        &program,
+        BlockId(1),
+        &expect![[r#"
+            Block:
+                Variable(3, Double) = Fadd Double(1.5), Variable(2, Double)
+                Variable(4, Double) = Fmul Variable(3, Double), Variable(2, Double)
+                Variable(5, Double) = Fdiv Variable(2, Double), Double(2)
+                Variable(6, Double) = Fsub Variable(4, Double), Variable(5, Double)
+                Call id(3), args( Variable(6, Double), Pointer, )
+                Return"#]],
+    );
+}
+
+#[test]
+fn double_arithmetic_with_classical_operands_is_folded() {
+    let program = get_rir_program(indoc! {r#"
+        namespace Test {
+            @EntryPoint()
+            operation Main() : Double {
+                let d = 1.5;
+                (d + 0.5) * 2.0
+            }
+        }
+    "#});
+    assert_block_instructions(
+        &program,
+        BlockId(0),
+        &expect![[r#"
+            Block:
+                Call id(1), args( Double(4), Pointer, )
+                Return"#]],
+    );
+}
+
+#[test]
+fn double_comparison_with_dynamic_operands_generates_fcmp_rir_instructions() {
+    let program = get_rir_program(indoc! {r#"
+        namespace Test {
+            @EntryPoint()
+            operation Main() : (Bool, Bool) {
+                use q = Qubit();
+                let d = MResetZ(q) == Zero ? 0.0 | 1.0;
+                (d < 0.5, 1.0 != d)
+            }
+        }
+    "#});
+    assert_block_instructions(
+        &program,
+        BlockId(1),
+        &expect![[r#"
+            Block:
+                Variable(3, Boolean) = Fcmp Olt, Variable(2, Double), Double(0.5)
+                Variable(4, Boolean) = Fcmp One, Double(1), Variable(2, Double)
+                Call id(3), args( Integer(2), Pointer, )
+                Call id(4), args( Variable(3, Boolean), Pointer, )
+                Call id(4), args( Variable(4, Boolean), Pointer, )
+                Return"#]],
+    );
+}
+
+#[test]
+fn double_exponentiation_with_dynamic_operands_is_unimplemented() {
+    let error = get_partial_evaluation_error(indoc! {r#"
+        namespace Test {
+            @EntryPoint()
+            operation Main() : Double {
+                use q = Qubit();
+                let d = MResetZ(q) == Zero ? 0.0 | 1.0;
+                d ^ 2.0
+            }
+        }
+    "#});
+    assert_error(
+        &error,
+        &expect![[r#"
+            Unimplemented("dynamic double Exp operation", PackageSpan { package: PackageId(2), span: Span { lo: 149, hi: 156 } })"#]],
+    );
+}
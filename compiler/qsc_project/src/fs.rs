@@ -133,4 +133,15 @@ impl FileSystem for StdFs {
             "github references not supported for this file system",
         ))
     }
+
+    fn write_file(&self, path: &Path, contents: &str) -> miette::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .into_diagnostic()
+                .with_context(|| format!("could not create directory `{}`", parent.display()))?;
+        }
+        std::fs::write(path, contents)
+            .into_diagnostic()
+            .with_context(|| format!("could not write file `{}`", path.display()))
+    }
 }
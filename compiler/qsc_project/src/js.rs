@@ -37,6 +37,12 @@ pub trait JSProjectHost {
         r#ref: &str,
         path: &str,
     ) -> miette::Result<Arc<str>>;
+    async fn resolve_github_commit(
+        &self,
+        owner: &str,
+        repo: &str,
+        r#ref: &str,
+    ) -> miette::Result<Arc<str>>;
     async fn find_manifest_directory(&self, doc_uri: &str) -> Option<Arc<str>>;
 }
 
@@ -80,4 +86,13 @@ where
     ) -> miette::Result<std::sync::Arc<str>> {
         self.fetch_github(owner, repo, r#ref, path).await
     }
+
+    async fn resolve_github_commit(
+        &self,
+        owner: &str,
+        repo: &str,
+        r#ref: &str,
+    ) -> miette::Result<std::sync::Arc<str>> {
+        self.resolve_github_commit(owner, repo, r#ref).await
+    }
 }
@@ -12,16 +12,22 @@ mod error;
 #[cfg(feature = "fs")]
 mod fs;
 mod js;
+mod lockfile;
 mod manifest;
 mod project;
+mod workspace;
 
 pub use error::StdFsError;
 #[cfg(feature = "fs")]
 pub use fs::StdFs;
 pub use js::{JSFileEntry, JSProjectHost};
-pub use manifest::{Manifest, ManifestDescriptor, PackageRef, PackageType, MANIFEST_FILE_NAME};
+pub use lockfile::{content_hash, LockedPackage, Lockfile, LOCKFILE_NAME, PACKAGE_CACHE_DIR};
+pub use manifest::{
+    Dependency, Manifest, ManifestDescriptor, PackageRef, PackageType, MANIFEST_FILE_NAME,
+};
 pub use project::FileSystemAsync;
 pub use project::{
     key_for_package_ref, package_ref_from_key, DependencyCycle, DirEntry, EntryType, Error,
     FileSystem, PackageCache, PackageGraphSources, PackageInfo, Project, GITHUB_SCHEME,
 };
+pub use workspace::{Workspace, WorkspaceManifest, WORKSPACE_MANIFEST_FILE_NAME};
@@ -0,0 +1,164 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! The `qsharp.lock` lockfile pins the GitHub dependencies of a project to the
+//! commit they resolved to, along with a hash of their sources, so that
+//! subsequent loads of the project are reproducible.
+
+use crate::{manifest::GitHubRef, project::Error};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{fmt::Write, path::PathBuf, sync::Arc};
+
+pub const LOCKFILE_NAME: &str = "qsharp.lock";
+
+/// Directory, relative to the project root, where the sources of GitHub
+/// dependencies are cached. Dependencies found in the cache are not fetched
+/// again, which allows locked projects to be loaded without network access.
+pub const PACKAGE_CACHE_DIR: &str = ".qsharp/packages";
+
+const LOCKFILE_VERSION: u32 = 1;
+
+/// The contents of a `qsharp.lock` file.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Lockfile {
+    pub version: u32,
+    #[serde(default)]
+    pub packages: Vec<LockedPackage>,
+}
+
+impl Default for Lockfile {
+    fn default() -> Self {
+        Self {
+            version: LOCKFILE_VERSION,
+            packages: Vec::default(),
+        }
+    }
+}
+
+/// A GitHub dependency pinned to a specific commit.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct LockedPackage {
+    #[serde(flatten)]
+    pub github: GitHubRef,
+    /// The full SHA of the commit that `ref` resolved to.
+    pub commit: String,
+    /// Hash of the package sources, see [`content_hash`].
+    pub hash: String,
+}
+
+impl Lockfile {
+    #[must_use]
+    pub fn find(&self, github: &GitHubRef) -> Option<&LockedPackage> {
+        self.packages.iter().find(|p| p.github == *github)
+    }
+}
+
+/// Tracks the lockfile entries while a project is being loaded.
+pub struct LockState {
+    /// Resolved location of [`PACKAGE_CACHE_DIR`] for the project being loaded.
+    pub cache_dir: PathBuf,
+    /// Whether the lockfile and the package cache are written during this load.
+    pub update: bool,
+    /// The lockfile as it was read from disk, if there was one.
+    existing: Option<Lockfile>,
+    /// Entries for the GitHub dependencies encountered during this load.
+    resolved: Lockfile,
+}
+
+impl LockState {
+    pub fn new(existing: Option<Lockfile>, cache_dir: PathBuf, update: bool) -> Self {
+        Self {
+            cache_dir,
+            update,
+            existing,
+            resolved: Lockfile::default(),
+        }
+    }
+
+    /// The commit a dependency is pinned to, if it is locked.
+    pub fn locked_commit(&self, github: &GitHubRef) -> Option<String> {
+        self.existing
+            .as_ref()
+            .and_then(|lockfile| lockfile.find(github))
+            .map(|locked| locked.commit.clone())
+    }
+
+    /// Records the sources loaded for a dependency, verifying them against
+    /// the existing lockfile entry if there is one.
+    pub fn record(
+        &mut self,
+        github: &GitHubRef,
+        commit: String,
+        sources: &[(Arc<str>, Arc<str>)],
+    ) -> Result<(), Error> {
+        if self.resolved.find(github).is_some() {
+            return Ok(());
+        }
+
+        let hash = content_hash(sources);
+        if let Some(locked) = self
+            .existing
+            .as_ref()
+            .and_then(|lockfile| lockfile.find(github))
+        {
+            if locked.hash != hash {
+                // Keep the pinned entry so that the lockfile is not silently updated.
+                self.resolved.packages.push(locked.clone());
+                return Err(Error::LockfileMismatch(format!(
+                    "{}/{}@{}",
+                    github.owner, github.repo, github.r#ref
+                )));
+            }
+        }
+
+        self.resolved.packages.push(LockedPackage {
+            github: github.clone(),
+            commit,
+            hash,
+        });
+        Ok(())
+    }
+
+    /// Returns the lockfile to write, or `None` if the existing lockfile is up to date.
+    /// Projects without GitHub dependencies don't get a lockfile.
+    pub fn into_updated(mut self) -> Option<Lockfile> {
+        self.resolved
+            .packages
+            .sort_by(|a, b| a.github.cmp(&b.github));
+        match self.existing {
+            Some(existing) if existing == self.resolved => None,
+            None if self.resolved.packages.is_empty() => None,
+            _ => Some(self.resolved),
+        }
+    }
+}
+
+/// Returns true if the string is a full, lowercase hex-encoded commit SHA.
+pub(crate) fn is_commit_sha(commit: &str) -> bool {
+    commit.len() == 40
+        && commit
+            .bytes()
+            .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+}
+
+/// Computes the hex-encoded SHA-256 hash of the names and contents of the given sources.
+/// The algorithm is included in the result so that it can be changed in the future.
+#[must_use]
+pub fn content_hash(sources: &[(Arc<str>, Arc<str>)]) -> String {
+    let mut hasher = Sha256::new();
+    for (name, contents) in sources {
+        // Length-prefix each field so that different splits of the same bytes hash differently.
+        for field in [name.as_bytes(), contents.as_bytes()] {
+            hasher.update((field.len() as u64).to_le_bytes());
+            hasher.update(field);
+        }
+    }
+    hasher
+        .finalize()
+        .iter()
+        .fold(String::from("sha256:"), |mut hash, byte| {
+            write!(hash, "{byte:02x}").expect("writing to a string should succeed");
+            hash
+        })
+}
@@ -9,6 +9,7 @@ use std::{
     fs::{self, DirEntry, FileType},
 };
 
+pub use qsc_formatter::formatter::FormatterConfig;
 pub use qsc_linter::LintConfig;
 use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
@@ -22,12 +23,16 @@ pub const MANIFEST_FILE_NAME: &str = "qsharp.json";
 pub struct Manifest {
     pub author: Option<String>,
     pub license: Option<String>,
+    /// The semantic version of the package, e.g. `1.2.0`.
+    pub version: Option<String>,
     #[serde(default)]
     pub language_features: Vec<String>,
     #[serde(default)]
     pub lints: Vec<LintConfig>,
     #[serde(default)]
-    pub dependencies: FxHashMap<String, PackageRef>,
+    pub formatter: FormatterConfig,
+    #[serde(default)]
+    pub dependencies: FxHashMap<String, Dependency>,
     #[serde(default)]
     pub files: Vec<String>,
     #[serde(default)]
@@ -42,6 +47,16 @@ pub enum PackageType {
     Lib,
 }
 
+/// A dependency in the manifest: where to find the package, and optionally
+/// a requirement on its version, e.g. `^1.2`.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Dependency {
+    #[serde(flatten)]
+    pub package: PackageRef,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(untagged)]
 pub enum PackageRef {
@@ -49,7 +64,7 @@ pub enum PackageRef {
     Path { path: String },
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct GitHubRef {
     pub owner: String,
     pub repo: String,
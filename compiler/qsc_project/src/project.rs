@@ -2,17 +2,22 @@
 // Licensed under the MIT License.
 
 use crate::{
-    manifest::{GitHubRef, PackageType},
+    lockfile::{is_commit_sha, LockState, Lockfile, LOCKFILE_NAME, PACKAGE_CACHE_DIR},
+    manifest::{Dependency, GitHubRef, PackageType},
+    workspace::{self, Workspace, WorkspaceManifest, WORKSPACE_MANIFEST_FILE_NAME},
     Manifest, ManifestDescriptor, PackageRef,
 };
 use async_trait::async_trait;
 use futures::FutureExt;
 use miette::Diagnostic;
 use qsc_data_structures::language_features::LanguageFeatures;
+use qsc_formatter::formatter::FormatterConfig;
 use qsc_linter::LintConfig;
 use rustc_hash::FxHashMap;
+use semver::{Version, VersionReq};
 use std::{
     cell::RefCell,
+    iter::once,
     path::{Path, PathBuf},
     sync::Arc,
 };
@@ -34,6 +39,8 @@ pub struct Project {
     pub package_graph_sources: PackageGraphSources,
     /// Lint configuration for the project, typically comes from the root `qsharp.json`.
     pub lints: Vec<LintConfig>,
+    /// Formatter configuration for the project, typically comes from the root `qsharp.json`.
+    pub formatter: FormatterConfig,
     /// Any errors encountered while loading the project.
     pub errors: Vec<Error>,
 }
@@ -54,12 +61,15 @@ impl Project {
                     language_features: LanguageFeatures::default(),
                     dependencies: FxHashMap::default(),
                     package_type: None,
+                    version: None,
+                    version_requirements: FxHashMap::default(),
                 },
                 packages: FxHashMap::default(),
             },
             path: name,
             name: display_name,
             lints: Vec::default(),
+            formatter: FormatterConfig::default(),
             errors: Vec::default(),
         }
     }
@@ -129,6 +139,34 @@ pub enum Error {
     #[error("Error fetching from GitHub: {0}")]
     #[diagnostic(code("Qsc.Project.GitHub"))]
     GitHub(String),
+
+    #[error("Failed to parse lockfile: {error}")]
+    #[diagnostic(code("Qsc.Project.LockfileParse"))]
+    LockfileParse { path: String, error: String },
+
+    #[error("Sources of GitHub dependency {0} do not match the hash in qsharp.lock")]
+    #[diagnostic(code("Qsc.Project.LockfileMismatch"))]
+    #[diagnostic(help(
+        "if the dependency was changed intentionally, remove its entry from qsharp.lock"
+    ))]
+    LockfileMismatch(String),
+
+    #[error("Invalid version in package {package}: {error}")]
+    #[diagnostic(code("Qsc.Project.InvalidVersion"))]
+    InvalidVersion { package: String, error: String },
+
+    #[error("Dependency {alias} of {package} requires version {requirement}, but found {found}")]
+    #[diagnostic(code("Qsc.Project.VersionMismatch"))]
+    VersionMismatch {
+        package: String,
+        alias: String,
+        requirement: String,
+        found: String,
+    },
+
+    #[error("Incompatible versions of {package} are required: {versions}")]
+    #[diagnostic(code("Qsc.Project.VersionConflict"))]
+    VersionConflict { package: String, versions: String },
 }
 
 impl Error {
@@ -138,7 +176,8 @@ impl Error {
         match self {
             Error::GitHubManifestParse { path, .. }
             | Error::NoSrcDir { path }
-            | Error::ManifestParse { path, .. } => Some(path),
+            | Error::ManifestParse { path, .. }
+            | Error::LockfileParse { path, .. } => Some(path),
             // Note we don't return the path for `FileSystem` errors,
             // since for most errors such as "file not found", it's more meaningful
             // to report the error for the manifest that was *referencing* the file,
@@ -146,7 +185,11 @@ impl Error {
             Error::FileSystem { .. }
             | Error::GitHubToLocal(_, _)
             | Error::Circular(_, _)
-            | Error::GitHub(_) => None,
+            | Error::GitHub(_)
+            | Error::LockfileMismatch(_)
+            | Error::InvalidVersion { .. }
+            | Error::VersionMismatch { .. }
+            | Error::VersionConflict { .. } => None,
         }
     }
 }
@@ -181,6 +224,31 @@ pub trait FileSystemAsync {
         path: &str,
     ) -> miette::Result<Arc<str>>;
 
+    /// Given a GitHub ref, returns the full SHA of the commit it currently points to.
+    /// Used to pin GitHub dependencies that aren't in the lockfile yet. Hosts that
+    /// can't resolve refs return an error, and such dependencies fail to load.
+    async fn resolve_github_commit(
+        &self,
+        owner: &str,
+        repo: &str,
+        r#ref: &str,
+    ) -> miette::Result<Arc<str>> {
+        Err(miette::Error::msg(format!(
+            "cannot resolve {owner}/{repo}@{ref}: resolving GitHub refs is not supported for this file system"
+        )))
+    }
+
+    /// Writes a file, creating its parent directories if needed. Used to write
+    /// the lockfile and the package cache, see [`FileSystemAsync::load_project_and_update_lockfile`].
+    /// Hosts that don't support writing return an error, and the project loads
+    /// without updating either.
+    async fn write_file(&self, path: &Path, _contents: &str) -> miette::Result<()> {
+        Err(miette::Error::msg(format!(
+            "cannot write {}: writing files is not supported for this file system",
+            path.display()
+        )))
+    }
+
     /// Given an initial path, fetch files matching <initial_path>/**/*.qs
     async fn collect_project_sources(
         &self,
@@ -233,10 +301,31 @@ pub trait FileSystemAsync {
 
     /// Given a directory, loads the project sources
     /// and the sources for all its dependencies.
+    /// This doesn't write the lockfile or the package cache.
     async fn load_project(
         &self,
         directory: &Path,
         global_cache: Option<&RefCell<PackageCache>>,
+    ) -> Result<Project, Vec<Error>> {
+        self.load_project_inner(directory, global_cache, false)
+            .await
+    }
+
+    /// Like [`FileSystemAsync::load_project`], but also writes the lockfile after
+    /// a clean load and adds the fetched GitHub sources to the package cache.
+    async fn load_project_and_update_lockfile(
+        &self,
+        directory: &Path,
+        global_cache: Option<&RefCell<PackageCache>>,
+    ) -> Result<Project, Vec<Error>> {
+        self.load_project_inner(directory, global_cache, true).await
+    }
+
+    async fn load_project_inner(
+        &self,
+        directory: &Path,
+        global_cache: Option<&RefCell<PackageCache>>,
+        update_lockfile: bool,
     ) -> Result<Project, Vec<Error>> {
         let manifest = self
             .parse_manifest_in_dir(directory)
@@ -252,13 +341,42 @@ pub trait FileSystemAsync {
         let mut packages = FxHashMap::default();
         let mut stack = vec![];
 
+        let lockfile_path = self
+            .resolve_path(directory, Path::new(LOCKFILE_NAME))
+            .await
+            .map_err(|e| {
+                vec![Error::FileSystem {
+                    about_path: directory.to_string_lossy().to_string(),
+                    error: e.to_string(),
+                }]
+            })?;
+        let cache_dir = self
+            .resolve_path(directory, Path::new(PACKAGE_CACHE_DIR))
+            .await
+            .map_err(|e| {
+                vec![Error::FileSystem {
+                    about_path: directory.to_string_lossy().to_string(),
+                    error: e.to_string(),
+                }]
+            })?;
+        let lockfile = self
+            .read_lockfile(&lockfile_path)
+            .await
+            .unwrap_or_else(|e| {
+                errors.push(e);
+                None
+            });
+        let mut lock = LockState::new(lockfile, cache_dir, update_lockfile);
+
         let root_path = directory.to_string_lossy().to_string();
         let root_ref = PackageRef::Path { path: root_path };
+        let root_key = key_for_package_ref(&root_ref);
 
         self.collect_deps(
-            key_for_package_ref(&root_ref),
+            root_key.clone(),
             &root,
-            global_cache.unwrap_or(&RefCell::new(FxHashMap::default())),
+            global_cache.unwrap_or(&RefCell::default()),
+            &mut lock,
             &mut stack,
             &mut packages,
             &mut errors,
@@ -266,6 +384,16 @@ pub trait FileSystemAsync {
         )
         .await;
 
+        errors.extend(resolve_versions(&root_key, &root, &packages));
+
+        // Only update the lockfile after a clean load, so that entries for
+        // dependencies that failed to load are not dropped.
+        if update_lockfile && errors.is_empty() {
+            if let Some(lockfile) = lock.into_updated() {
+                self.write_lockfile(&lockfile_path, &lockfile).await;
+            }
+        }
+
         let name = directory
             .file_name()
             .map(|f| f.to_string_lossy().into())
@@ -287,12 +415,235 @@ pub trait FileSystemAsync {
         Ok(Project {
             package_graph_sources: PackageGraphSources { root, packages },
             lints: manifest.lints,
+            formatter: manifest.formatter,
             errors,
             name,
             path: manifest_path,
         })
     }
 
+    /// Given a directory containing a workspace manifest, loads all the member
+    /// projects and applies the workspace defaults to them. The members are
+    /// returned in build order.
+    /// This doesn't write the lockfiles or the package caches of the members.
+    async fn load_workspace(
+        &self,
+        directory: &Path,
+        global_cache: Option<&RefCell<PackageCache>>,
+    ) -> Result<Workspace, Vec<Error>> {
+        self.load_workspace_inner(directory, global_cache, false)
+            .await
+    }
+
+    /// Like [`FileSystemAsync::load_workspace`], but also updates the lockfile
+    /// of each member, see [`FileSystemAsync::load_project_and_update_lockfile`].
+    async fn load_workspace_and_update_lockfile(
+        &self,
+        directory: &Path,
+        global_cache: Option<&RefCell<PackageCache>>,
+    ) -> Result<Workspace, Vec<Error>> {
+        self.load_workspace_inner(directory, global_cache, true)
+            .await
+    }
+
+    async fn load_workspace_inner(
+        &self,
+        directory: &Path,
+        global_cache: Option<&RefCell<PackageCache>>,
+        update_lockfile: bool,
+    ) -> Result<Workspace, Vec<Error>> {
+        let manifest_path = self
+            .resolve_path(directory, Path::new(WORKSPACE_MANIFEST_FILE_NAME))
+            .await
+            .map_err(|e| {
+                vec![Error::FileSystem {
+                    about_path: directory.to_string_lossy().to_string(),
+                    error: e.to_string(),
+                }]
+            })?;
+        let manifest = self
+            .parse_workspace_manifest(&manifest_path)
+            .await
+            .map_err(|e| vec![e])?;
+
+        // Members share a package cache, so that members that depend on
+        // each other are only read once.
+        let local_cache = RefCell::default();
+        let cache = global_cache.unwrap_or(&local_cache);
+
+        let mut errors = vec![];
+        let mut members = vec![];
+        for member in &manifest.members {
+            let member_dir = match self.resolve_path(directory, Path::new(member)).await {
+                Ok(dir) => dir,
+                Err(e) => {
+                    errors.push(Error::FileSystem {
+                        about_path: directory.to_string_lossy().to_string(),
+                        error: e.to_string(),
+                    });
+                    continue;
+                }
+            };
+            match self
+                .load_project_inner(&member_dir, Some(cache), update_lockfile)
+                .await
+            {
+                Ok(project) => {
+                    let key = key_for_package_ref(&PackageRef::Path {
+                        path: member_dir.to_string_lossy().into(),
+                    });
+                    members.push((key, project));
+                }
+                Err(e) => errors.extend(e),
+            }
+        }
+
+        let member_keys = members.iter().map(|(key, _)| key.clone()).collect();
+        for (_, project) in &mut members {
+            manifest.apply_defaults(project, &member_keys);
+        }
+
+        let name = directory
+            .file_name()
+            .map(|f| f.to_string_lossy().into())
+            .unwrap_or(format!("Q# workspace at {}", directory.display()))
+            .into();
+
+        Ok(Workspace {
+            name,
+            path: manifest_path.to_string_lossy().into(),
+            members: workspace::build_order(members),
+            errors,
+        })
+    }
+
+    /// Starting from a project directory, searches its ancestors for a workspace
+    /// manifest that lists the project as a member. Returns the workspace directory
+    /// if one is found.
+    async fn find_workspace(&self, project_dir: &Path) -> Option<PathBuf> {
+        for dir in project_dir.ancestors() {
+            let Ok(manifest_path) = self
+                .resolve_path(dir, Path::new(WORKSPACE_MANIFEST_FILE_NAME))
+                .await
+            else {
+                continue;
+            };
+            let Ok(manifest) = self.parse_workspace_manifest(&manifest_path).await else {
+                continue;
+            };
+            for member in &manifest.members {
+                if let Ok(member_dir) = self.resolve_path(dir, Path::new(member)).await {
+                    if member_dir == project_dir {
+                        return Some(dir.to_path_buf());
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    async fn parse_workspace_manifest(&self, path: &Path) -> ProjectResult<WorkspaceManifest> {
+        let (_, contents) = self.read_file(path).await.map_err(|e| Error::FileSystem {
+            about_path: path.to_string_lossy().to_string(),
+            error: e.to_string(),
+        })?;
+        serde_json::from_str::<WorkspaceManifest>(&contents).map_err(|e| Error::ManifestParse {
+            path: path.to_string_lossy().to_string(),
+            error: e.to_string(),
+        })
+    }
+
+    /// Reads the lockfile at the given path. Returns `Ok(None)` if it doesn't exist.
+    async fn read_lockfile(&self, path: &Path) -> ProjectResult<Option<Lockfile>> {
+        let Ok((_, contents)) = self.read_file(path).await else {
+            return Ok(None);
+        };
+        serde_json::from_str::<Lockfile>(&contents)
+            .map(Some)
+            .map_err(|e| Error::LockfileParse {
+                path: path.to_string_lossy().to_string(),
+                error: e.to_string(),
+            })
+    }
+
+    async fn write_lockfile(&self, path: &Path, lockfile: &Lockfile) {
+        let mut contents =
+            serde_json::to_string_pretty(lockfile).expect("lockfile should be serializable");
+        contents.push('\n');
+        if let Err(e) = self.write_file(path, &contents).await {
+            log::debug!("lockfile not written: {e}");
+        }
+    }
+
+    /// Resolves the ref of a GitHub dependency that isn't in the lockfile to the commit
+    /// it currently points to, failing if the host doesn't return a full commit SHA.
+    /// Refs are only resolved once per package cache.
+    async fn resolve_github_ref(
+        &self,
+        global_cache: &RefCell<PackageCache>,
+        github: &GitHubRef,
+    ) -> ProjectResult<String> {
+        if let Some(commit) = global_cache.borrow().commits.get(github) {
+            return Ok(commit.clone());
+        }
+        let commit = self
+            .resolve_github_commit(&github.owner, &github.repo, &github.r#ref)
+            .await
+            .map_err(|e| Error::GitHub(e.to_string()))?;
+        if !is_commit_sha(&commit) {
+            return Err(Error::GitHub(format!(
+                "{}/{}@{} resolved to `{commit}`, which is not a commit SHA",
+                github.owner, github.repo, github.r#ref
+            )));
+        }
+        global_cache
+            .borrow_mut()
+            .commits
+            .insert(github.clone(), commit.to_string());
+        Ok(commit.to_string())
+    }
+
+    /// Reads a file from a GitHub repository at the given commit, preferring
+    /// the copy in the package cache. Fetched files are added to the cache
+    /// if the lockfile is being updated.
+    async fn fetch_github_cached(
+        &self,
+        lock: &LockState,
+        dep: &GitHubRef,
+        commit: &str,
+        path: &str,
+    ) -> ProjectResult<Arc<str>> {
+        let cached_path = self
+            .resolve_path(
+                &lock.cache_dir,
+                Path::new(&format!(
+                    "{}/{}/{commit}/{}",
+                    dep.owner,
+                    dep.repo,
+                    path.trim_start_matches('/')
+                )),
+            )
+            .await
+            .map_err(|e| Error::FileSystem {
+                about_path: lock.cache_dir.to_string_lossy().to_string(),
+                error: e.to_string(),
+            })?;
+        if let Ok((_, contents)) = self.read_file(&cached_path).await {
+            return Ok(contents);
+        }
+
+        let contents = self
+            .fetch_github(&dep.owner, &dep.repo, commit, path)
+            .await
+            .map_err(|e| Error::GitHub(e.to_string()))?;
+        if lock.update {
+            if let Err(e) = self.write_file(&cached_path, &contents).await {
+                log::debug!("package cache not updated: {e}");
+            }
+        }
+        Ok(contents)
+    }
+
     /// Given a directory, attemps to parse a `qsharp.json` in that directory
     /// according to the manifest schema.
     async fn parse_manifest_in_dir(&self, directory: &Path) -> ProjectResult<Manifest> {
@@ -363,11 +714,18 @@ pub trait FileSystemAsync {
         }
 
         let mut dependencies = FxHashMap::default();
+        let version_requirements = version_requirements(&manifest.manifest.dependencies);
 
         // For any local dependencies, convert relative paths to absolute,
         // so that multiple references to the same package, from different packages,
         // get merged correctly.
-        for (alias, mut dep) in manifest.manifest.dependencies {
+        for (
+            alias,
+            Dependency {
+                package: mut dep, ..
+            },
+        ) in manifest.manifest.dependencies
+        {
             if let PackageRef::Path { path: dep_path } = &mut dep {
                 *dep_path = self
                     .resolve_path(&project_path, &PathBuf::from(dep_path.clone()))
@@ -387,14 +745,18 @@ pub trait FileSystemAsync {
             language_features: LanguageFeatures::from_iter(&manifest.manifest.language_features),
             dependencies,
             package_type: manifest.manifest.package_type,
+            version: manifest.manifest.version.map(Into::into),
+            version_requirements,
         })
     }
 
-    /// Load the sources for a single package at the given GitHub ref. Also load its
+    /// Load the sources for a single package at the given GitHub commit. Also load its
     /// dependency information but don't recurse into dependencies yet.
     async fn read_github_manifest_and_sources(
         &self,
         dep: &GitHubRef,
+        commit: &str,
+        lock: &LockState,
     ) -> ProjectResult<PackageInfo> {
         let path_trimmed_seps = dep
             .path
@@ -409,9 +771,8 @@ pub trait FileSystemAsync {
 
         let manifest_path = format!("{path_trimmed_seps}/qsharp.json",);
         let manifest_content = self
-            .fetch_github(&dep.owner, &dep.repo, &dep.r#ref, &manifest_path)
-            .await
-            .map_err(|e| Error::GitHub(e.to_string()))?;
+            .fetch_github_cached(lock, dep, commit, &manifest_path)
+            .await?;
 
         let manifest = serde_json::from_str::<Manifest>(&manifest_content).map_err(|e| {
             Error::GitHubManifestParse {
@@ -440,10 +801,7 @@ pub trait FileSystemAsync {
             } else {
                 format!("/{path_trimmed_seps}/{file}")
             };
-            let contents = self
-                .fetch_github(&dep.owner, &dep.repo, &dep.r#ref, &path)
-                .await
-                .map_err(|e| Error::GitHub(e.to_string()))?;
+            let contents = self.fetch_github_cached(lock, dep, commit, &path).await?;
 
             // Use the well-known URI scheme for the generated GitHub source paths.
             // This will allow the editor to recognize these URIs as GitHub sources
@@ -461,38 +819,54 @@ pub trait FileSystemAsync {
         Ok(PackageInfo {
             sources,
             language_features: LanguageFeatures::from_iter(&manifest.language_features),
+            version_requirements: version_requirements(&manifest.dependencies),
             dependencies: manifest
                 .dependencies
                 .into_iter()
-                .map(|(k, v)| (k.into(), key_for_package_ref(&v)))
+                .map(|(k, v)| (k.into(), key_for_package_ref(&v.package)))
                 .collect(),
             package_type: manifest.package_type,
+            version: manifest.version.map(Into::into),
         })
     }
 
     /// Load the sources and dependency information for a single package,
-    /// using a previously cached version if available.
+    /// using a previously cached version if available. GitHub packages are
+    /// loaded at the commit pinned by the lockfile, and verified against it.
+    /// They are cached by commit, so that a project pinned to a different
+    /// commit of the same ref doesn't get the sources loaded for another.
     async fn read_manifest_and_sources(
         &self,
         global_cache: &RefCell<PackageCache>,
+        lock: &mut LockState,
         key: PackageKey,
         this_pkg: &PackageRef,
     ) -> ProjectResult<PackageInfo> {
         match this_pkg {
             PackageRef::GitHub { github } => {
-                {
-                    let cache = global_cache.borrow();
-                    if let Some(cached) = cache.get(&key) {
-                        return cached.clone();
-                    }
-                }
-
-                let result = self.read_github_manifest_and_sources(github).await;
-
-                let mut cache = global_cache.borrow_mut();
-                cache.insert(key, result.clone());
-
-                result
+                let commit = match lock.locked_commit(github) {
+                    Some(commit) => commit,
+                    None => self.resolve_github_ref(global_cache, github).await?,
+                };
+
+                let cache_key: PackageKey = format!("{key}@{commit}").into();
+                let cached = global_cache.borrow().packages.get(&cache_key).cloned();
+                let result = if let Some(cached) = cached {
+                    cached
+                } else {
+                    let result = self
+                        .read_github_manifest_and_sources(github, &commit, lock)
+                        .await;
+                    global_cache
+                        .borrow_mut()
+                        .packages
+                        .insert(cache_key, result.clone());
+                    result
+                };
+
+                let pkg = result?;
+                lock.record(github, commit, &pkg.sources)?;
+                Ok(pkg)
             }
             PackageRef::Path { path } => {
                 // Local dependencies are not cached at the moment, to make the multi-project
@@ -513,6 +887,7 @@ pub trait FileSystemAsync {
         key: Arc<str>,
         pkg: &PackageInfo,
         global_cache: &RefCell<PackageCache>,
+        lock: &mut LockState,
         stack: &mut Vec<PackageKey>,
         packages: &mut FxHashMap<PackageKey, PackageInfo>,
         errors: &mut Vec<Error>,
@@ -535,7 +910,7 @@ pub trait FileSystemAsync {
             }
 
             let dep_result = self
-                .read_manifest_and_sources(global_cache, dep_key.clone(), &dependency)
+                .read_manifest_and_sources(global_cache, lock, dep_key.clone(), &dependency)
                 .await;
 
             match dep_result {
@@ -544,6 +919,7 @@ pub trait FileSystemAsync {
                         dep_key.clone(),
                         &pkg,
                         global_cache,
+                        lock,
                         stack,
                         packages,
                         errors,
@@ -569,6 +945,139 @@ fn filter_hidden_files<Entry: DirEntry>(
     listing.filter(|x| !x.entry_name().starts_with('.'))
 }
 
+fn version_requirements(
+    dependencies: &FxHashMap<String, Dependency>,
+) -> FxHashMap<PackageAlias, Arc<str>> {
+    dependencies
+        .iter()
+        .filter_map(|(alias, dep)| {
+            dep.version
+                .as_deref()
+                .map(|version| (alias.as_str().into(), version.into()))
+        })
+        .collect()
+}
+
+/// The version resolution step of project loading. Checks that every dependency
+/// with a version requirement resolved to a package with a matching version,
+/// and that all the packages loaded for the same library agree on its version.
+fn resolve_versions(
+    root_key: &PackageKey,
+    root: &PackageInfo,
+    packages: &FxHashMap<PackageKey, PackageInfo>,
+) -> Vec<Error> {
+    let mut errors = vec![];
+
+    // Sort the packages so that errors are reported in a stable order.
+    let mut all_packages = once((root_key, root))
+        .chain(packages.iter())
+        .collect::<Vec<_>>();
+    all_packages.sort_by_key(|(key, _)| *key);
+
+    let mut versions = FxHashMap::default();
+    for (key, pkg) in &all_packages {
+        if let Some(version) = &pkg.version {
+            match version.parse::<Version>() {
+                Ok(version) => {
+                    versions.insert(*key, version);
+                }
+                Err(e) => errors.push(Error::InvalidVersion {
+                    package: package_display_name(key),
+                    error: e.to_string(),
+                }),
+            }
+        }
+    }
+    let found_version = |key: &PackageKey| {
+        versions
+            .get(key)
+            .map_or_else(|| "no version".to_string(), ToString::to_string)
+    };
+
+    for (key, pkg) in &all_packages {
+        let mut requirements = pkg.version_requirements.iter().collect::<Vec<_>>();
+        requirements.sort();
+        for (alias, requirement) in requirements {
+            let req = match requirement.parse::<VersionReq>() {
+                Ok(req) => req,
+                Err(e) => {
+                    errors.push(Error::InvalidVersion {
+                        package: package_display_name(key),
+                        error: e.to_string(),
+                    });
+                    continue;
+                }
+            };
+            // Dependencies that failed to load have already been reported.
+            let Some(dep_key) = pkg.dependencies.get(alias) else {
+                continue;
+            };
+            if !packages.contains_key(dep_key) {
+                continue;
+            }
+            if !versions.get(dep_key).is_some_and(|v| req.matches(v)) {
+                errors.push(Error::VersionMismatch {
+                    package: package_display_name(key),
+                    alias: alias.to_string(),
+                    requirement: requirement.to_string(),
+                    found: found_version(dep_key),
+                });
+            }
+        }
+    }
+
+    // The same library can be loaded more than once, e.g. from different GitHub refs.
+    let mut libraries: FxHashMap<String, Vec<String>> = FxHashMap::default();
+    for (key, _) in &all_packages {
+        if let PackageRef::GitHub { github } = package_ref_from_key(key) {
+            let library = match &github.path {
+                Some(path) => format!(
+                    "{}/{}/{}",
+                    github.owner,
+                    github.repo,
+                    path.trim_matches('/')
+                ),
+                None => format!("{}/{}", github.owner, github.repo),
+            };
+            libraries
+                .entry(library)
+                .or_default()
+                .push(found_version(key));
+        }
+    }
+    let mut libraries = libraries.into_iter().collect::<Vec<_>>();
+    libraries.sort();
+    for (library, mut found) in libraries {
+        found.sort();
+        found.dedup();
+        if found.len() > 1 {
+            errors.push(Error::VersionConflict {
+                package: library,
+                versions: found.join(", "),
+            });
+        }
+    }
+
+    errors
+}
+
+/// A readable name for a package in error messages.
+fn package_display_name(key: &PackageKey) -> String {
+    match package_ref_from_key(key) {
+        PackageRef::Path { path } => path,
+        PackageRef::GitHub { github } => match &github.path {
+            Some(path) => format!(
+                "{}/{}/{}@{}",
+                github.owner,
+                github.repo,
+                path.trim_matches('/'),
+                github.r#ref
+            ),
+            None => format!("{}/{}@{}", github.owner, github.repo, github.r#ref),
+        },
+    }
+}
+
 /// We're using JSON to generate a key for the packages,
 /// but something more readable would also be okay as long as we can
 /// guarantee uniqueness.
@@ -587,7 +1096,7 @@ pub fn package_ref_from_key(key: &PackageKey) -> PackageRef {
 /// A `PackageKey` is a global unique identifier for a package, and it's
 /// simply generated from the dependency information in the manifest
 /// (local or GitHub path).
-type PackageKey = Arc<str>;
+pub(crate) type PackageKey = Arc<str>;
 
 /// A `PackageAlias` is the name that a package uses to refer to one of its
 /// dependencies. The same package can live under different aliases in the
@@ -595,7 +1104,14 @@ type PackageKey = Arc<str>;
 type PackageAlias = Arc<str>;
 
 /// Long-lived cache that can optionally be used for loading packages.
-pub type PackageCache = FxHashMap<PackageKey, ProjectResult<PackageInfo>>;
+#[derive(Default)]
+pub struct PackageCache {
+    /// Loaded packages. GitHub packages are keyed by their package key and the commit
+    /// they were loaded at.
+    packages: FxHashMap<PackageKey, ProjectResult<PackageInfo>>,
+    /// Commits that the refs of unlocked GitHub dependencies resolved to.
+    commits: FxHashMap<GitHubRef, String>,
+}
 
 type Sources = Vec<(Arc<str>, Arc<str>)>;
 
@@ -605,6 +1121,10 @@ pub struct PackageInfo {
     pub language_features: LanguageFeatures,
     pub dependencies: FxHashMap<PackageAlias, PackageKey>,
     pub package_type: Option<PackageType>,
+    /// The version of the package, as declared in its manifest.
+    pub version: Option<Arc<str>>,
+    /// Version requirements on the dependencies of this package, by alias.
+    pub version_requirements: FxHashMap<PackageAlias, Arc<str>>,
 }
 
 #[derive(Clone, Debug)]
@@ -709,6 +1229,8 @@ impl PackageGraphSources {
                 language_features,
                 dependencies: FxHashMap::default(),
                 package_type,
+                version: None,
+                version_requirements: FxHashMap::default(),
             },
             packages: FxHashMap::default(),
         }
@@ -738,6 +1260,26 @@ pub trait FileSystem {
         path: &str,
     ) -> miette::Result<Arc<str>>;
 
+    /// See [`FileSystemAsync::resolve_github_commit`].
+    fn resolve_github_commit(
+        &self,
+        owner: &str,
+        repo: &str,
+        r#ref: &str,
+    ) -> miette::Result<Arc<str>> {
+        Err(miette::Error::msg(format!(
+            "cannot resolve {owner}/{repo}@{ref}: resolving GitHub refs is not supported for this file system"
+        )))
+    }
+
+    /// See [`FileSystemAsync::write_file`].
+    fn write_file(&self, path: &Path, _contents: &str) -> miette::Result<()> {
+        Err(miette::Error::msg(format!(
+            "cannot write {}: writing files is not supported for this file system",
+            path.display()
+        )))
+    }
+
     fn load_project(
         &self,
         directory: &Path,
@@ -757,6 +1299,45 @@ pub trait FileSystem {
         FutureExt::now_or_never(fs.load_project(directory, global_cache))
             .expect("load_project should never await")
     }
+
+    /// See [`FileSystemAsync::load_project_and_update_lockfile`].
+    fn load_project_and_update_lockfile(
+        &self,
+        directory: &Path,
+        global_cache: Option<&RefCell<PackageCache>>,
+    ) -> Result<Project, Vec<Error>> {
+        let fs = ToFileSystemAsync { fs: self };
+
+        // See the warning in `load_project` above.
+        FutureExt::now_or_never(fs.load_project_and_update_lockfile(directory, global_cache))
+            .expect("load_project_and_update_lockfile should never await")
+    }
+
+    /// See [`FileSystemAsync::load_workspace`].
+    fn load_workspace(
+        &self,
+        directory: &Path,
+        global_cache: Option<&RefCell<PackageCache>>,
+    ) -> Result<Workspace, Vec<Error>> {
+        let fs = ToFileSystemAsync { fs: self };
+
+        // See the warning in `load_project` above.
+        FutureExt::now_or_never(fs.load_workspace(directory, global_cache))
+            .expect("load_workspace should never await")
+    }
+
+    /// See [`FileSystemAsync::load_workspace_and_update_lockfile`].
+    fn load_workspace_and_update_lockfile(
+        &self,
+        directory: &Path,
+        global_cache: Option<&RefCell<PackageCache>>,
+    ) -> Result<Workspace, Vec<Error>> {
+        let fs = ToFileSystemAsync { fs: self };
+
+        // See the warning in `load_project` above.
+        FutureExt::now_or_never(fs.load_workspace_and_update_lockfile(directory, global_cache))
+            .expect("load_workspace_and_update_lockfile should never await")
+    }
 }
 
 /// Trivial wrapper to turn a `FileSystem` into a `FileSystemAsync`
@@ -796,4 +1377,17 @@ where
     ) -> miette::Result<Arc<str>> {
         self.fs.fetch_github(owner, repo, r#ref, path)
     }
+
+    async fn resolve_github_commit(
+        &self,
+        owner: &str,
+        repo: &str,
+        r#ref: &str,
+    ) -> miette::Result<Arc<str>> {
+        self.fs.resolve_github_commit(owner, repo, r#ref)
+    }
+
+    async fn write_file(&self, path: &Path, contents: &str) -> miette::Result<()> {
+        self.fs.write_file(path, contents)
+    }
 }
@@ -8,7 +8,7 @@
 mod harness;
 
 use expect_test::expect;
-use harness::check;
+use harness::{check, check_errors, check_workspace, check_writes, count_github_ref_resolutions};
 
 #[test]
 fn basic_manifest() {
@@ -39,10 +39,18 @@ fn basic_manifest() {
                         ),
                         dependencies: {},
                         package_type: None,
+                        version: None,
+                        version_requirements: {},
                     },
                     packages: {},
                 },
                 lints: [],
+                formatter: FormatterConfig {
+                    indent_width: 4,
+                    max_line_width: None,
+                    trailing_commas: false,
+                    brace_style: SameLine,
+                },
                 errors: [],
             }"#]],
     );
@@ -77,10 +85,18 @@ fn circular_imports() {
                         ),
                         dependencies: {},
                         package_type: None,
+                        version: None,
+                        version_requirements: {},
                     },
                     packages: {},
                 },
                 lints: [],
+                formatter: FormatterConfig {
+                    indent_width: 4,
+                    max_line_width: None,
+                    trailing_commas: false,
+                    brace_style: SameLine,
+                },
                 errors: [],
             }"#]],
     );
@@ -115,10 +131,18 @@ fn different_files_same_manifest() {
                         ),
                         dependencies: {},
                         package_type: None,
+                        version: None,
+                        version_requirements: {},
                     },
                     packages: {},
                 },
                 lints: [],
+                formatter: FormatterConfig {
+                    indent_width: 4,
+                    max_line_width: None,
+                    trailing_commas: false,
+                    brace_style: SameLine,
+                },
                 errors: [],
             }"#]],
     );
@@ -145,10 +169,18 @@ fn empty_manifest() {
                         ),
                         dependencies: {},
                         package_type: None,
+                        version: None,
+                        version_requirements: {},
                     },
                     packages: {},
                 },
                 lints: [],
+                formatter: FormatterConfig {
+                    indent_width: 4,
+                    max_line_width: None,
+                    trailing_commas: false,
+                    brace_style: SameLine,
+                },
                 errors: [],
             }"#]],
     );
@@ -187,10 +219,18 @@ fn folder_structure() {
                         ),
                         dependencies: {},
                         package_type: None,
+                        version: None,
+                        version_requirements: {},
                     },
                     packages: {},
                 },
                 lints: [],
+                formatter: FormatterConfig {
+                    indent_width: 4,
+                    max_line_width: None,
+                    trailing_commas: false,
+                    brace_style: SameLine,
+                },
                 errors: [],
             }"#]],
     );
@@ -224,10 +264,18 @@ fn hidden_files() {
                         ),
                         dependencies: {},
                         package_type: None,
+                        version: None,
+                        version_requirements: {},
                     },
                     packages: {},
                 },
                 lints: [],
+                formatter: FormatterConfig {
+                    indent_width: 4,
+                    max_line_width: None,
+                    trailing_commas: false,
+                    brace_style: SameLine,
+                },
                 errors: [],
             }"#]],
     );
@@ -265,10 +313,18 @@ fn peer_file() {
                         ),
                         dependencies: {},
                         package_type: None,
+                        version: None,
+                        version_requirements: {},
                     },
                     packages: {},
                 },
                 lints: [],
+                formatter: FormatterConfig {
+                    indent_width: 4,
+                    max_line_width: None,
+                    trailing_commas: false,
+                    brace_style: SameLine,
+                },
                 errors: [],
             }"#]],
     );
@@ -295,10 +351,58 @@ fn language_feature() {
                         ),
                         dependencies: {},
                         package_type: None,
+                        version: None,
+                        version_requirements: {},
+                    },
+                    packages: {},
+                },
+                lints: [],
+                formatter: FormatterConfig {
+                    indent_width: 4,
+                    max_line_width: None,
+                    trailing_commas: false,
+                    brace_style: SameLine,
+                },
+                errors: [],
+            }"#]],
+    );
+}
+
+#[test]
+fn formatter_config() {
+    check(
+        &"formatter_config".into(),
+        &expect![[r#"
+            Project {
+                name: "formatter_config",
+                path: "formatter_config/qsharp.json",
+                package_graph_sources: PackageGraphSources {
+                    root: PackageInfo {
+                        sources: [
+                            (
+                                "formatter_config/src/Main.qs",
+                                "namespace Main\n{\n  @EntryPoint()\n  operation Main() : Unit {}\n}\n",
+                            ),
+                        ],
+                        language_features: LanguageFeatures(
+                            0,
+                        ),
+                        dependencies: {},
+                        package_type: None,
+                        version: None,
+                        version_requirements: {},
                     },
                     packages: {},
                 },
                 lints: [],
+                formatter: FormatterConfig {
+                    indent_width: 2,
+                    max_line_width: Some(
+                        80,
+                    ),
+                    trailing_commas: false,
+                    brace_style: NextLine,
+                },
                 errors: [],
             }"#]],
     );
@@ -327,6 +431,8 @@ fn with_local_dep() {
                             "MyDep": "{\"path\":\"local_dep\"}",
                         },
                         package_type: None,
+                        version: None,
+                        version_requirements: {},
                     },
                     packages: {
                         "{\"path\":\"local_dep\"}": PackageInfo {
@@ -341,10 +447,18 @@ fn with_local_dep() {
                             ),
                             dependencies: {},
                             package_type: None,
+                            version: None,
+                            version_requirements: {},
                         },
                     },
                 },
                 lints: [],
+                formatter: FormatterConfig {
+                    indent_width: 4,
+                    max_line_width: None,
+                    trailing_commas: false,
+                    brace_style: SameLine,
+                },
                 errors: [],
             }"#]],
     );
@@ -373,6 +487,8 @@ fn transitive_dep() {
                             "MyDep": "{\"path\":\"with_local_dep\"}",
                         },
                         package_type: None,
+                        version: None,
+                        version_requirements: {},
                     },
                     packages: {
                         "{\"path\":\"local_dep\"}": PackageInfo {
@@ -387,6 +503,8 @@ fn transitive_dep() {
                             ),
                             dependencies: {},
                             package_type: None,
+                            version: None,
+                            version_requirements: {},
                         },
                         "{\"path\":\"with_local_dep\"}": PackageInfo {
                             sources: [
@@ -402,10 +520,18 @@ fn transitive_dep() {
                                 "MyDep": "{\"path\":\"local_dep\"}",
                             },
                             package_type: None,
+                            version: None,
+                            version_requirements: {},
                         },
                     },
                 },
                 lints: [],
+                formatter: FormatterConfig {
+                    indent_width: 4,
+                    max_line_width: None,
+                    trailing_commas: false,
+                    brace_style: SameLine,
+                },
                 errors: [],
             }"#]],
     );
@@ -432,10 +558,18 @@ fn explicit_files_list() {
                         ),
                         dependencies: {},
                         package_type: None,
+                        version: None,
+                        version_requirements: {},
                     },
                     packages: {},
                 },
                 lints: [],
+                formatter: FormatterConfig {
+                    indent_width: 4,
+                    max_line_width: None,
+                    trailing_commas: false,
+                    brace_style: SameLine,
+                },
                 errors: [],
             }"#]],
     );
@@ -464,10 +598,18 @@ fn circular_dep() {
                             "MyCircularDep": "{\"path\":\"circular_dep\"}",
                         },
                         package_type: None,
+                        version: None,
+                        version_requirements: {},
                     },
                     packages: {},
                 },
                 lints: [],
+                formatter: FormatterConfig {
+                    indent_width: 4,
+                    max_line_width: None,
+                    trailing_commas: false,
+                    brace_style: SameLine,
+                },
                 errors: [
                     Circular(
                         "REPLACED",
@@ -477,3 +619,308 @@ fn circular_dep() {
             }"#]],
     );
 }
+
+#[test]
+fn github_dep_locked() {
+    check(
+        &"github_dep_locked".into(),
+        &expect![[r#"
+            Project {
+                name: "github_dep_locked",
+                path: "github_dep_locked/qsharp.json",
+                package_graph_sources: PackageGraphSources {
+                    root: PackageInfo {
+                        sources: [
+                            (
+                                "github_dep_locked/src/Main.qs",
+                                "namespace Main {\n    open Dep;\n    @EntryPoint()\n    operation Main() : Unit {\n        DepFunction();\n    }\n}\n",
+                            ),
+                        ],
+                        language_features: LanguageFeatures(
+                            0,
+                        ),
+                        dependencies: {
+                            "MyGitHubDep": "{\"github\":{\"owner\":\"test-owner\",\"repo\":\"test-repo\",\"ref\":\"v1.0.0\",\"path\":null}}",
+                        },
+                        package_type: None,
+                        version: None,
+                        version_requirements: {},
+                    },
+                    packages: {
+                        "{\"github\":{\"owner\":\"test-owner\",\"repo\":\"test-repo\",\"ref\":\"v1.0.0\",\"path\":null}}": PackageInfo {
+                            sources: [
+                                (
+                                    "qsharp-github-source:test-owner/test-repo/v1.0.0/src/Dep.qs",
+                                    "namespace Dep {\n    function DepFunction() : Unit {}\n}\n",
+                                ),
+                            ],
+                            language_features: LanguageFeatures(
+                                0,
+                            ),
+                            dependencies: {},
+                            package_type: None,
+                            version: None,
+                            version_requirements: {},
+                        },
+                    },
+                },
+                lints: [],
+                formatter: FormatterConfig {
+                    indent_width: 4,
+                    max_line_width: None,
+                    trailing_commas: false,
+                    brace_style: SameLine,
+                },
+                errors: [],
+            }"#]],
+    );
+}
+
+#[test]
+fn github_dep_lock_mismatch() {
+    check(
+        &"github_dep_lock_mismatch".into(),
+        &expect![[r#"
+            Project {
+                name: "github_dep_lock_mismatch",
+                path: "github_dep_lock_mismatch/qsharp.json",
+                package_graph_sources: PackageGraphSources {
+                    root: PackageInfo {
+                        sources: [
+                            (
+                                "github_dep_lock_mismatch/src/Main.qs",
+                                "namespace Main {\n    open Dep;\n    @EntryPoint()\n    operation Main() : Unit {\n        DepFunction();\n    }\n}\n",
+                            ),
+                        ],
+                        language_features: LanguageFeatures(
+                            0,
+                        ),
+                        dependencies: {
+                            "MyGitHubDep": "{\"github\":{\"owner\":\"test-owner\",\"repo\":\"test-repo\",\"ref\":\"v1.0.0\",\"path\":null}}",
+                        },
+                        package_type: None,
+                        version: None,
+                        version_requirements: {},
+                    },
+                    packages: {},
+                },
+                lints: [],
+                formatter: FormatterConfig {
+                    indent_width: 4,
+                    max_line_width: None,
+                    trailing_commas: false,
+                    brace_style: SameLine,
+                },
+                errors: [
+                    LockfileMismatch(
+                        "test-owner/test-repo@v1.0.0",
+                    ),
+                ],
+            }"#]],
+    );
+}
+
+#[test]
+fn version_mismatch() {
+    check(
+        &"version_mismatch".into(),
+        &expect![[r#"
+            Project {
+                name: "version_mismatch",
+                path: "version_mismatch/qsharp.json",
+                package_graph_sources: PackageGraphSources {
+                    root: PackageInfo {
+                        sources: [
+                            (
+                                "version_mismatch/src/Main.qs",
+                                "namespace Main {\n    @EntryPoint()\n    function Main() : Unit {}\n}\n",
+                            ),
+                        ],
+                        language_features: LanguageFeatures(
+                            0,
+                        ),
+                        dependencies: {
+                            "MyDep": "{\"path\":\"versioned_dep\"}",
+                        },
+                        package_type: None,
+                        version: Some(
+                            "0.1.0",
+                        ),
+                        version_requirements: {
+                            "MyDep": "^2.0",
+                        },
+                    },
+                    packages: {
+                        "{\"path\":\"versioned_dep\"}": PackageInfo {
+                            sources: [
+                                (
+                                    "versioned_dep/src/Dep.qs",
+                                    "namespace Dep {\n    function DepFunction() : Unit {}\n}\n",
+                                ),
+                            ],
+                            language_features: LanguageFeatures(
+                                0,
+                            ),
+                            dependencies: {},
+                            package_type: None,
+                            version: Some(
+                                "1.3.0",
+                            ),
+                            version_requirements: {},
+                        },
+                    },
+                },
+                lints: [],
+                formatter: FormatterConfig {
+                    indent_width: 4,
+                    max_line_width: None,
+                    trailing_commas: false,
+                    brace_style: SameLine,
+                },
+                errors: [
+                    VersionMismatch {
+                        package: "version_mismatch",
+                        alias: "MyDep",
+                        requirement: "^2.0",
+                        found: "1.3.0",
+                    },
+                ],
+            }"#]],
+    );
+}
+
+#[test]
+fn version_conflict() {
+    check_errors(
+        &"version_conflict".into(),
+        &expect![[r#"
+            [
+                VersionConflict {
+                    package: "test-owner/test-lib",
+                    versions: "1.0.0, 2.0.0",
+                },
+            ]"#]],
+    );
+}
+
+#[test]
+fn workspace_members_in_build_order() {
+    check_workspace(
+        &"workspace".into(),
+        &expect![[r#"
+            name: workspace
+            path: workspace/qsharp.workspace.json
+            members: [
+                (
+                    "workspace/lib_a/qsharp.json",
+                    LanguageFeatures(
+                        1,
+                    ),
+                    [
+                        LintConfig {
+                            kind: Ast(
+                                DivisionByZero,
+                            ),
+                            level: Error,
+                        },
+                        LintConfig {
+                            kind: Ast(
+                                NeedlessParens,
+                            ),
+                            level: Warn,
+                        },
+                        LintConfig {
+                            kind: Ast(
+                                NeedlessParens,
+                            ),
+                            level: Allow,
+                        },
+                    ],
+                    [],
+                ),
+                (
+                    "workspace/lib_b/qsharp.json",
+                    LanguageFeatures(
+                        1,
+                    ),
+                    [
+                        LintConfig {
+                            kind: Ast(
+                                DivisionByZero,
+                            ),
+                            level: Error,
+                        },
+                        LintConfig {
+                            kind: Ast(
+                                NeedlessParens,
+                            ),
+                            level: Warn,
+                        },
+                    ],
+                    [],
+                ),
+                (
+                    "workspace/app/qsharp.json",
+                    LanguageFeatures(
+                        1,
+                    ),
+                    [
+                        LintConfig {
+                            kind: Ast(
+                                DivisionByZero,
+                            ),
+                            level: Error,
+                        },
+                        LintConfig {
+                            kind: Ast(
+                                NeedlessParens,
+                            ),
+                            level: Warn,
+                        },
+                    ],
+                    [],
+                ),
+            ]
+            errors: []"#]],
+    );
+}
+
+#[test]
+fn github_dep_unlocked() {
+    check_errors(
+        &"github_dep_unlocked".into(),
+        &expect![[r#"
+            [
+                GitHub(
+                    "REPLACED",
+                ),
+            ]"#]],
+    );
+}
+
+#[test]
+fn loading_project_does_not_write_files() {
+    check_writes(&"github_dep_unlocked".into(), false, &expect!["[]"]);
+}
+
+#[test]
+fn updating_lockfile_writes_lockfile_and_package_cache() {
+    check_writes(
+        &"github_dep_unlocked".into(),
+        true,
+        &expect![[r#"
+            [
+                "github_dep_unlocked/.qsharp/packages/test-owner/test-repo/0123456789abcdef0123456789abcdef01234567/qsharp.json",
+                "github_dep_unlocked/.qsharp/packages/test-owner/test-repo/0123456789abcdef0123456789abcdef01234567/src/Dep.qs",
+                "github_dep_unlocked/qsharp.lock",
+            ]"#]],
+    );
+}
+
+#[test]
+fn github_refs_are_resolved_once_per_package_cache() {
+    assert_eq!(
+        count_github_ref_resolutions(&"github_dep_unlocked".into(), 2),
+        1
+    );
+}
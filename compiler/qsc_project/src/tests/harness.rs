@@ -2,22 +2,166 @@
 // Licensed under the MIT License.
 
 use std::{
+    cell::{Cell, RefCell},
     path::{Path, PathBuf},
     sync::Arc,
 };
 
 use expect_test::Expect;
 use qsc_project::{
-    key_for_package_ref, package_ref_from_key, Error, FileSystem, Manifest, PackageRef, Project,
-    StdFs,
+    key_for_package_ref, package_ref_from_key, Error, FileSystem, Manifest, PackageCache,
+    PackageRef, Project, StdFs, GITHUB_SCHEME, PACKAGE_CACHE_DIR,
 };
 use rustc_hash::FxHashMap;
 
 pub fn check(project_path: &PathBuf, expect: &Expect) {
+    let project = load_normalized(project_path);
+    expect.assert_eq(&format!("{project:#?}"));
+}
+
+/// Like [`check`], but only compares the errors reported while loading the project.
+pub fn check_errors(project_path: &PathBuf, expect: &Expect) {
+    let project = load_normalized(project_path);
+    expect.assert_eq(&format!("{:#?}", project.errors));
+}
+
+/// Loads the workspace in the given directory, and compares the names of the members,
+/// in build order, along with the settings they got from the workspace.
+pub fn check_workspace(workspace_path: &PathBuf, expect: &Expect) {
+    let root_path = projects_root();
+    let fs = StdFs;
+    let mut workspace = fs
+        .load_workspace(&root_path.join(workspace_path), None)
+        .expect("workspace should load");
+
+    remove_absolute_path_prefix(&mut workspace.path, &root_path);
+    normalize_errors(&mut workspace.errors, &root_path);
+    let members = workspace
+        .members
+        .into_iter()
+        .map(|mut project| {
+            normalize(&mut project, &root_path);
+            (
+                project.path,
+                project.package_graph_sources.root.language_features,
+                project.lints,
+                project.errors,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    expect.assert_eq(&format!(
+        "name: {}\npath: {}\nmembers: {members:#?}\nerrors: {:#?}",
+        workspace.name, workspace.path, workspace.errors
+    ));
+}
+
+/// Loads the project in the given directory with a file system that serves GitHub
+/// dependencies from the package cache of the `github_dep_locked` project, and
+/// compares the files that were written while loading it.
+pub fn check_writes(project_path: &PathBuf, update_lockfile: bool, expect: &Expect) {
+    let root_path = projects_root();
+    let fs = GitHubFs::default();
+    let project_dir = root_path.join(project_path);
+    let project = if update_lockfile {
+        fs.load_project_and_update_lockfile(&project_dir, None)
+    } else {
+        fs.load_project(&project_dir, None)
+    }
+    .expect("project should load");
+    assert!(project.errors.is_empty(), "{:#?}", project.errors);
+
+    let mut written = fs.written.take();
+    for path in &mut written {
+        remove_absolute_path_prefix(path, &root_path);
+    }
+    expect.assert_eq(&format!("{written:#?}"));
+}
+
+/// Loads the project in the given directory the given number of times with a shared
+/// package cache, and returns how many times a GitHub ref was resolved to a commit.
+pub fn count_github_ref_resolutions(project_path: &PathBuf, loads: usize) -> usize {
+    let fs = GitHubFs::default();
+    let cache = RefCell::new(PackageCache::default());
+    for _ in 0..loads {
+        let project = fs
+            .load_project(&projects_root().join(project_path), Some(&cache))
+            .expect("project should load");
+        assert!(project.errors.is_empty(), "{:#?}", project.errors);
+    }
+    fs.resolved.get()
+}
+
+/// Wraps `StdFs`, resolving every GitHub ref to the commit in the package cache of
+/// the `github_dep_locked` project, and recording writes instead of performing them.
+#[derive(Default)]
+struct GitHubFs {
+    resolved: Cell<usize>,
+    written: RefCell<Vec<Arc<str>>>,
+}
+
+const GITHUB_COMMIT: &str = "0123456789abcdef0123456789abcdef01234567";
+
+impl FileSystem for GitHubFs {
+    type Entry = <StdFs as FileSystem>::Entry;
+
+    fn read_file(&self, path: &Path) -> miette::Result<(Arc<str>, Arc<str>)> {
+        StdFs.read_file(path)
+    }
+
+    fn list_directory(&self, path: &Path) -> miette::Result<Vec<Self::Entry>> {
+        StdFs.list_directory(path)
+    }
+
+    fn resolve_path(&self, base: &Path, path: &Path) -> miette::Result<PathBuf> {
+        StdFs.resolve_path(base, path)
+    }
+
+    fn fetch_github(
+        &self,
+        owner: &str,
+        repo: &str,
+        r#ref: &str,
+        path: &str,
+    ) -> miette::Result<Arc<str>> {
+        let path = projects_root()
+            .join("github_dep_locked")
+            .join(PACKAGE_CACHE_DIR)
+            .join(owner)
+            .join(repo)
+            .join(r#ref)
+            .join(path.trim_start_matches('/'));
+        StdFs.read_file(&path).map(|(_, contents)| contents)
+    }
+
+    fn resolve_github_commit(
+        &self,
+        _owner: &str,
+        _repo: &str,
+        _ref: &str,
+    ) -> miette::Result<Arc<str>> {
+        self.resolved.set(self.resolved.get() + 1);
+        Ok(GITHUB_COMMIT.into())
+    }
+
+    fn write_file(&self, path: &Path, _contents: &str) -> miette::Result<()> {
+        self.written
+            .borrow_mut()
+            .push(path.to_string_lossy().into());
+        Ok(())
+    }
+}
+
+fn projects_root() -> PathBuf {
     let mut root_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     root_path.push(PathBuf::from("src"));
     root_path.push(PathBuf::from("tests"));
     root_path.push(PathBuf::from("projects"));
+    root_path
+}
+
+fn load_normalized(project_path: &PathBuf) -> Project {
+    let root_path = projects_root();
     let mut absolute_project_path = root_path.clone();
     absolute_project_path.push(project_path);
     let manifest = Manifest::load_from_path(absolute_project_path)
@@ -29,8 +173,7 @@ pub fn check(project_path: &PathBuf, expect: &Expect) {
         .expect("project should load");
 
     normalize(&mut project, &root_path);
-
-    expect.assert_eq(&format!("{project:#?}"));
+    project
 }
 
 /// If the `Project` contains absolute paths, replace them with relative paths
@@ -55,11 +198,16 @@ fn normalize(project: &mut Project, root_path: &Path) {
 
     remove_absolute_path_prefix(&mut project.path, root_path);
 
-    for err in &mut project.errors {
+    normalize_errors(&mut project.errors, root_path);
+}
+
+fn normalize_errors(errors: &mut [Error], root_path: &Path) {
+    for err in errors {
         match err {
             Error::NoSrcDir { path }
             | Error::ManifestParse { path, .. }
-            | Error::GitHubManifestParse { path, .. } => {
+            | Error::GitHubManifestParse { path, .. }
+            | Error::LockfileParse { path, .. } => {
                 let mut str = std::mem::take(path).into();
                 remove_absolute_path_prefix(&mut str, root_path);
                 *path = str.to_string();
@@ -81,13 +229,26 @@ fn normalize(project: &mut Project, root_path: &Path) {
             Error::GitHub(s) => {
                 *s = "REPLACED".to_string();
             }
+            Error::VersionMismatch { package, .. } | Error::InvalidVersion { package, .. }
+                if Path::new(package.as_str()).starts_with(root_path) =>
+            {
+                let mut str = std::mem::take(package).into();
+                remove_absolute_path_prefix(&mut str, root_path);
+                *package = str.to_string();
+            }
+            Error::LockfileMismatch(_)
+            | Error::InvalidVersion { .. }
+            | Error::VersionMismatch { .. }
+            | Error::VersionConflict { .. } => {}
         }
     }
 }
 
 fn normalize_pkg(pkg: &mut qsc_project::PackageInfo, root_path: &Path) {
     for (path, _contents) in &mut pkg.sources {
-        remove_absolute_path_prefix(path, root_path);
+        if !path.starts_with(GITHUB_SCHEME) {
+            remove_absolute_path_prefix(path, root_path);
+        }
     }
     pkg.sources.sort();
 
@@ -0,0 +1,105 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! A workspace groups several Q# projects that are developed together.
+//! The workspace manifest lists the member projects, and provides default
+//! settings that apply to all of them.
+
+use crate::{
+    project::{DependencyCycle, PackageKey},
+    Error, PackageGraphSources, Project,
+};
+use qsc_data_structures::language_features::LanguageFeatures;
+use qsc_linter::LintConfig;
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+pub const WORKSPACE_MANIFEST_FILE_NAME: &str = "qsharp.workspace.json";
+
+/// A Q# workspace manifest, listing the member projects of the workspace.
+#[derive(Deserialize, Serialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceManifest {
+    /// Paths to the member project directories, relative to the workspace manifest.
+    pub members: Vec<String>,
+    /// Language features enabled for every member, in addition to their own.
+    #[serde(default)]
+    pub language_features: Vec<String>,
+    /// Default lint configuration. Lints configured by a member take precedence.
+    #[serde(default)]
+    pub lints: Vec<LintConfig>,
+}
+
+/// Describes a Q# workspace with all its member projects loaded.
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    /// Friendly name, typically based on the workspace directory name.
+    pub name: Arc<str>,
+    /// The path to the workspace manifest.
+    pub path: Arc<str>,
+    /// The member projects, in the order they should be built: every member
+    /// comes after the members it depends on.
+    pub members: Vec<Project>,
+    /// Errors encountered while loading the workspace manifest and its members.
+    /// Errors specific to a member are reported on that member's project.
+    pub errors: Vec<Error>,
+}
+
+impl WorkspaceManifest {
+    /// Applies the workspace defaults to a member project. The language features
+    /// are also enabled for other members that appear in its package graph.
+    pub(crate) fn apply_defaults(
+        &self,
+        project: &mut Project,
+        member_keys: &FxHashSet<PackageKey>,
+    ) {
+        let language_features = LanguageFeatures::from_iter(&self.language_features);
+        let graph = &mut project.package_graph_sources;
+        graph.root.language_features.merge(language_features);
+        for (key, package) in &mut graph.packages {
+            if member_keys.contains(key) {
+                package.language_features.merge(language_features);
+            }
+        }
+
+        project.lints = self
+            .lints
+            .iter()
+            .cloned()
+            .chain(project.lints.drain(..))
+            .collect();
+    }
+}
+
+/// Sorts the members of a workspace into build order, using the same ordering
+/// as the packages of a single project.
+pub(crate) fn build_order(members: Vec<(PackageKey, Project)>) -> Vec<Project> {
+    let member_keys = members
+        .iter()
+        .map(|(key, _)| key.clone())
+        .collect::<FxHashSet<_>>();
+    let mut graph =
+        PackageGraphSources::with_no_dependencies(Vec::new(), LanguageFeatures::default(), None);
+    for (key, project) in &members {
+        let mut package = project.package_graph_sources.root.clone();
+        // Only the dependencies between members affect the order.
+        package
+            .dependencies
+            .retain(|_, dep| member_keys.contains(dep));
+        graph.packages.insert(key.clone(), package);
+    }
+
+    match graph.compilation_order() {
+        (Ok(order), _) => {
+            let mut members = members.into_iter().collect::<FxHashMap<_, _>>();
+            order
+                .into_iter()
+                .filter_map(|(key, _)| members.remove(&key))
+                .collect()
+        }
+        // Cycles between members are already reported when loading the members
+        // themselves, so the members are left in manifest order.
+        (Err(DependencyCycle), _) => members.into_iter().map(|(_, project)| project).collect(),
+    }
+}
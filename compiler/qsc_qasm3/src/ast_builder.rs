@@ -248,6 +248,15 @@ pub(crate) fn build_expr_array_expr(values: Vec<qsc::ast::Expr>, span: Span) ->
     }
 }
 
+pub(crate) fn build_array_repeat_expr(item: Expr, size: usize, span: Span) -> Expr {
+    let size = build_lit_int_expr(size.try_into().unwrap_or(i64::MAX), Span::default());
+    Expr {
+        id: NodeId::default(),
+        span,
+        kind: Box::new(ExprKind::ArrayRepeat(Box::new(item), Box::new(size))),
+    }
+}
+
 pub(crate) fn build_default_result_array_expr(len: usize, span: Span) -> Expr {
     let exprs: Vec<_> = (0..len)
         .map(|_| Box::new(build_lit_result_expr(ast::Result::Zero, Span::default())))
@@ -1125,6 +1134,9 @@ pub(crate) fn map_qsharp_type_to_ast_ty(output_ty: &crate::types::Type) -> Ty {
         crate::types::Type::ResultArray(dims, _) => build_array_type_name("Result", dims),
         crate::types::Type::QubitArray(dims) => build_array_type_name("Qubit", dims),
         crate::types::Type::BigIntArray(dims, _) => build_array_type_name("BigInt", dims),
+        crate::types::Type::ComplexArray(dims) => {
+            wrap_array_ty_by_dims(dims, build_complex_ty_ident())
+        }
         crate::types::Type::IntArray(dims, _) => build_array_type_name("Int", dims),
         crate::types::Type::DoubleArray(dims) => build_array_type_name("Double", dims),
         crate::types::Type::BoolArray(dims, _) => build_array_type_name("Bool", dims),
@@ -1274,6 +1286,20 @@ pub(crate) fn build_index_expr(expr: Expr, index_expr: Expr, span: Span) -> Expr
     }
 }
 
+pub(crate) fn build_array_update_expr(array: Expr, index: Expr, value: Expr, span: Span) -> Expr {
+    let kind = ExprKind::TernOp(
+        ast::TernOp::Update,
+        Box::new(array),
+        Box::new(index),
+        Box::new(value),
+    );
+    Expr {
+        kind: Box::new(kind),
+        span,
+        ..Default::default()
+    }
+}
+
 pub(crate) fn build_barrier_call(span: Span) -> Stmt {
     let expr = build_call_no_params("__quantum__qis__barrier__body", &[], span);
     build_stmt_semi_from_expr(expr)
@@ -1477,6 +1503,166 @@ pub(crate) fn build_gate_decl_lambda<S: AsRef<str>>(
     }
 }
 
+pub(crate) fn build_function_or_operation(
+    name: String,
+    args: Vec<(String, Ty, Pat)>,
+    return_type: Ty,
+    body: Block,
+    kind: CallableKind,
+    name_span: Span,
+    decl_span: Span,
+) -> Stmt {
+    let args = args
+        .into_iter()
+        .map(|(_, _, pat)| Box::new(pat))
+        .collect::<Vec<_>>();
+
+    let lo = args
+        .iter()
+        .min_by_key(|x| x.span.lo)
+        .map(|x| x.span.lo)
+        .unwrap_or_default();
+
+    let hi = args
+        .iter()
+        .max_by_key(|x| x.span.hi)
+        .map(|x| x.span.hi)
+        .unwrap_or_default();
+
+    let input_pat_kind = if args.len() == 1 {
+        PatKind::Paren(args[0].clone())
+    } else {
+        PatKind::Tuple(args.into_boxed_slice())
+    };
+
+    let input_pat = Pat {
+        kind: Box::new(input_pat_kind),
+        span: Span { lo, hi },
+        ..Default::default()
+    };
+    let decl = CallableDecl {
+        id: NodeId::default(),
+        span: name_span,
+        kind,
+        name: Box::new(Ident {
+            name: name.into(),
+            ..Default::default()
+        }),
+        generics: Box::new([]),
+        input: Box::new(input_pat),
+        output: Box::new(return_type),
+        functors: None,
+        body: Box::new(CallableBody::Block(Box::new(body))),
+    };
+    let item = Item {
+        span: decl_span,
+        kind: Box::new(ast::ItemKind::Callable(Box::new(decl))),
+        ..Default::default()
+    };
+
+    Stmt {
+        kind: Box::new(StmtKind::Item(Box::new(item))),
+        span: decl_span,
+        ..Default::default()
+    }
+}
+
+pub(crate) fn build_function_or_operation_lambda<S: AsRef<str>>(
+    name: S,
+    args: Vec<(String, Ty, Pat)>,
+    return_type: Ty,
+    body: Block,
+    kind: CallableKind,
+    name_span: Span,
+    decl_span: Span,
+) -> Stmt {
+    let lo = args
+        .iter()
+        .min_by_key(|(_, _, pat)| pat.span.lo)
+        .map(|(_, _, pat)| pat.span.lo)
+        .unwrap_or_default();
+
+    let hi = args
+        .iter()
+        .max_by_key(|(_, _, pat)| pat.span.hi)
+        .map(|(_, _, pat)| pat.span.hi)
+        .unwrap_or_default();
+
+    let name_args = args
+        .iter()
+        .map(|(name, _, pat)| Pat {
+            kind: Box::new(PatKind::Bind(
+                Box::new(Ident {
+                    span: pat.span,
+                    name: Rc::from(name.as_ref()),
+                    ..Default::default()
+                }),
+                None,
+            )),
+            ..Default::default()
+        })
+        .map(Box::new)
+        .collect::<Vec<_>>();
+    let input_pat_kind = if name_args.len() == 1 {
+        PatKind::Paren(name_args[0].clone())
+    } else {
+        PatKind::Tuple(name_args.into_boxed_slice())
+    };
+    let input_pat = Pat {
+        kind: Box::new(input_pat_kind),
+        span: Span { lo, hi },
+        ..Default::default()
+    };
+
+    let lambda_expr = Expr {
+        id: NodeId::default(),
+        kind: Box::new(ExprKind::Lambda(
+            kind,
+            Box::new(input_pat),
+            Box::new(build_wrapped_block_expr(body)),
+        )),
+        span: decl_span,
+    };
+    let mut ty_args = args.into_iter().map(|(_, ty, _)| ty).collect::<Vec<_>>();
+    let input_ty_kind = if ty_args.len() == 1 {
+        TyKind::Paren(Box::new(ty_args.remove(0)))
+    } else {
+        TyKind::Tuple(ty_args.into_boxed_slice())
+    };
+    let input_ty = Ty {
+        kind: Box::new(input_ty_kind),
+        ..Default::default()
+    };
+    let lambda_ty = Ty {
+        kind: Box::new(TyKind::Arrow(
+            kind,
+            Box::new(input_ty),
+            Box::new(return_type),
+            None,
+        )),
+        ..Default::default()
+    };
+    Stmt {
+        span: decl_span,
+        kind: Box::new(StmtKind::Local(
+            Mutability::Immutable,
+            Box::new(Pat {
+                kind: Box::new(PatKind::Bind(
+                    Box::new(Ident {
+                        span: name_span,
+                        name: Rc::from(name.as_ref()),
+                        ..Default::default()
+                    }),
+                    Some(Box::new(lambda_ty)),
+                )),
+                ..Default::default()
+            }),
+            Box::new(lambda_expr),
+        )),
+        ..Default::default()
+    }
+}
+
 fn build_idents(idents: &[&str]) -> Option<Box<[Ident]>> {
     let idents = idents
         .iter()
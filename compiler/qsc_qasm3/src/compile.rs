@@ -5,22 +5,23 @@ use core::panic;
 use std::path::PathBuf;
 
 use crate::ast_builder::{
-    self, build_arg_pat, build_array_reverse_expr, build_assignment_statement, build_attr,
-    build_barrier_call, build_binary_expr, build_cast_call, build_cast_call_two_params,
-    build_classical_decl, build_complex_binary_expr, build_complex_from_expr,
-    build_convert_call_expr, build_default_result_array_expr, build_expr_array_expr,
-    build_gate_call_param_expr, build_gate_decl_lambda, build_if_expr_then_block,
-    build_if_expr_then_block_else_block, build_if_expr_then_block_else_expr,
-    build_if_expr_then_expr_else_expr, build_implicit_return_stmt,
-    build_indexed_assignment_statement, build_lit_bigint_expr, build_lit_bool_expr,
-    build_lit_complex_expr, build_lit_double_expr, build_lit_int_expr,
+    self, build_arg_pat, build_array_repeat_expr, build_array_reverse_expr,
+    build_array_update_expr, build_assignment_statement, build_attr, build_barrier_call,
+    build_binary_expr, build_cast_call, build_cast_call_two_params, build_classical_decl,
+    build_complex_binary_expr, build_complex_from_expr, build_convert_call_expr,
+    build_default_result_array_expr, build_expr_array_expr, build_function_or_operation,
+    build_function_or_operation_lambda, build_gate_call_param_expr, build_gate_decl_lambda,
+    build_if_expr_then_block, build_if_expr_then_block_else_block,
+    build_if_expr_then_block_else_expr, build_if_expr_then_expr_else_expr,
+    build_implicit_return_stmt, build_indexed_assignment_statement, build_lit_bigint_expr,
+    build_lit_bool_expr, build_lit_complex_expr, build_lit_double_expr, build_lit_int_expr,
     build_lit_result_array_expr_from_bitstring, build_lit_result_expr, build_managed_qubit_alloc,
     build_math_call_no_params, build_measure_call, build_operation_with_stmts,
     build_path_ident_expr, build_range_expr, build_reset_call, build_stmt_semi_from_expr,
     build_stmt_wrapped_block_expr, build_top_level_ns_with_item, build_tuple_expr,
     build_unary_op_expr, build_unmanaged_qubit_alloc, build_unmanaged_qubit_alloc_array,
     build_wrapped_block_expr, is_complex_binop_supported, managed_qubit_alloc_array,
-    map_qsharp_type_to_ast_ty,
+    map_qsharp_type_to_ast_ty, wrap_expr_in_parens,
 };
 
 use crate::oqasm_helpers::{
@@ -48,13 +49,14 @@ use oq3_semantics::types::{ArrayDims, IsConst, Type};
 use oq3_syntax::ast::{
     AnnotationStatement, ArithOp, BinaryOp, BitString, CastExpression, DelayStmt, Expr,
     GateOperand, HasArgList, HasName, Literal, LiteralKind, Modifier, ParamList, ParenExpr,
-    PragmaStatement, Stmt, TimeUnit, TimingLiteral, UnaryOp,
+    PragmaStatement, Stmt, TimeUnit, TimingLiteral, TypedParamList, UnaryOp,
 };
 use oq3_syntax::SyntaxNode;
 use oq3_syntax::{AstNode, HasTextName};
 use qsc::ast;
 use qsc::Span;
 use qsc::{error::WithSource, SourceMap};
+use rustc_hash::FxHashMap;
 
 use crate::{parse::QasmSource, QasmCompileUnit};
 
@@ -84,6 +86,9 @@ pub fn qasm_to_program(
         symbols: SymbolTable::new(),
         version: None,
         next_gate_as_item: false,
+        subroutines: FxHashMap::default(),
+        subroutine_return_ty: None,
+        loop_jumps: Vec::new(),
     };
     compiler.compile_program()
 }
@@ -114,7 +119,8 @@ struct QasmCompiler {
     /// for future use. We may want to use this to generate/parse different code
     /// based on the QASM version.
     version: Option<String>,
-    /// If the next gate should be compiled as a top level item instead of a lambda.
+    /// If the next gate or subroutine should be compiled as a top level item
+    /// instead of a lambda.
     /// In order to close over captured variables, we compile gates as a lambda
     /// operations; however, if the gate is annotated, we need to compile it as a
     /// top level item as attributes are not supported on lambdas. This isn't an
@@ -123,6 +129,35 @@ struct QasmCompiler {
     /// after the next gate is compiled, we run out of statements, or we encounter
     /// an error.
     next_gate_as_item: bool,
+    /// The signatures of the subroutines declared so far, keyed by name.
+    /// These are needed to cast the arguments of calls to the parameter types.
+    subroutines: FxHashMap<String, SubroutineSignature>,
+    /// The return type of the subroutine currently being compiled, if any.
+    subroutine_return_ty: Option<Type>,
+    /// The jump flags of the loops currently being compiled, innermost last.
+    loop_jumps: Vec<LoopJumpFlags>,
+}
+
+/// The parameter and return types of a `def` subroutine.
+#[derive(Clone, Debug)]
+struct SubroutineSignature {
+    params: Vec<Type>,
+    return_ty: Type,
+}
+
+/// Q# has no equivalent of `break` and `continue`, so loops containing them
+/// are lowered using flag variables. A `break` sets the break flag, which
+/// also ends the loop, and a `continue` sets the continue flag, which is reset
+/// at the start of every iteration. Statements following a jump are only
+/// executed if neither flag is set. A flag is only declared if the loop
+/// contains a statement that sets it.
+struct LoopJumpFlags {
+    break_flag: String,
+    continue_flag: String,
+    /// The number of break statements compiled so far in the loop body.
+    breaks: usize,
+    /// The number of continue statements compiled so far in the loop body.
+    continues: usize,
 }
 
 impl QasmCompiler {
@@ -295,10 +330,10 @@ impl QasmCompiler {
         self.file_stack.pop();
     }
 
-    fn compile_stmts(&mut self, stmt: &[oq3_syntax::ast::Stmt]) -> Vec<ast::Stmt> {
+    fn compile_stmts(&mut self, syntax_stmts: &[oq3_syntax::ast::Stmt]) -> Vec<ast::Stmt> {
         let mut annotations = Vec::new();
         let mut stmts = Vec::new();
-        for stmt in stmt {
+        for (index, stmt) in syntax_stmts.iter().enumerate() {
             if let Stmt::AnnotationStatement(annotation) = stmt {
                 // we compile the annotation and push it to the annotations.
                 // If compiling fails, we record the error and continue.
@@ -308,7 +343,16 @@ impl QasmCompiler {
                 }
                 continue;
             }
+            let jump_count = self.loop_jump_count();
             let stmt = self.compile_stmt(stmt);
+            if self.loop_jump_count() != jump_count {
+                // the statement contains a break or continue, so the
+                // remaining statements may only run if no jump was taken.
+                stmts.extend(stmt);
+                let remaining = self.compile_stmts(&syntax_stmts[index + 1..]);
+                stmts.extend(self.build_loop_jump_guard(remaining));
+                break;
+            }
             if stmt.is_none() {
                 continue;
             }
@@ -552,6 +596,11 @@ impl QasmCompiler {
     }
 
     /// `ident[index] = expr;`
+    ///
+    /// Assignments to an element of a multi-dimensional array,
+    /// `ident[i][j] = expr;` or `ident[i, j] = expr;`, are compiled to
+    /// nested copy-and-update expressions:
+    /// `set ident w/= i <- (ident[i] w/ j <- expr);`
     fn compile_indexed_assignment_stmt(
         &mut self,
         assignment: &oq3_syntax::ast::AssignmentStmt,
@@ -567,43 +616,76 @@ impl QasmCompiler {
         let stmt_span = span_for_syntax_node(assignment.syntax());
         let rhs_span = span_for_syntax_node(assignment.rhs()?.syntax());
 
-        // resolve the index expression
-        // we only support single index expressions for now
-        // but in the future we may support slice/range/array indexing
-        let indices: Vec<_> = indexed_ident
-            .index_operators()
-            .filter_map(|op| self.compile_index_operator(&op))
-            .flatten()
+        // resolve the index expressions, `a[i][j]` and `a[i, j]` are equivalent
+        let ops: Vec<_> = indexed_ident.index_operators().collect();
+        let indices: Vec<_> = ops
+            .iter()
+            .filter_map(|op| self.compile_index_operator(op))
             .collect();
-
-        if indices.len() != 1 {
-            // This is a temporary limitation. We can only handle
-            // single index expressions for now.
+        if indices.len() != ops.len() {
+            // an error has already been pushed
+            return None;
+        }
+        let indices: Vec<_> = indices.into_iter().flatten().collect();
+        if indices.is_empty() {
             let kind = SemanticErrorKind::IndexMustBeSingleExpr(span_for_syntax_node(
                 indexed_ident.syntax(),
             ));
             self.push_semantic_error(kind);
             return None;
         }
-        let index = indices[0].clone();
+        if let Some(index) = indices.iter().find(|index| matches!(index.ty, Type::Range)) {
+            let message = "Assignments to array slices".to_string();
+            let kind = crate::ErrorKind::NotSupported(message, index.expr.span);
+            let error = self.create_err(kind);
+            self.errors.push(error);
+            return None;
+        }
 
         let lhs_symbol = self
             .symbols
             .get_symbol_by_name(name.to_string().as_str())?
             .clone();
-        if index.ty.num_dims() > lhs_symbol.ty.num_dims() {
-            let kind = SemanticErrorKind::TypeRankError(rhs_span);
-            self.push_semantic_error(kind);
-        }
-        let index_expr = index.expr.clone();
 
-        let Some(indexed_ty) = get_indexed_type(&lhs_symbol.ty) else {
-            let kind = SemanticErrorKind::CannotIndexType(format!("{:?}", lhs_symbol.ty), rhs_span);
-            self.push_semantic_error(kind);
-            return None;
+        // walk the indices to find the type of the assigned element, keeping
+        // the partially indexed arrays around to build the nested updates.
+        let mut lhs = QasmTypedExpr {
+            ty: lhs_symbol.ty.clone(),
+            expr: build_path_ident_expr(&string_name, name_span, name_span),
         };
-        let rhs =
-            self.compile_expr_to_ty_with_casts(assignment.rhs(), &indexed_ty, assignment.syntax())?;
+        let mut partials = Vec::with_capacity(indices.len());
+        for index in &indices {
+            if index.ty.num_dims() > lhs.ty.num_dims() {
+                let kind = SemanticErrorKind::TypeRankError(rhs_span);
+                self.push_semantic_error(kind);
+            }
+            let Some(indexed_ty) = get_indexed_type(&lhs.ty).map(angle_array_item_as_float) else {
+                let kind = SemanticErrorKind::CannotIndexType(format!("{:?}", lhs.ty), rhs_span);
+                self.push_semantic_error(kind);
+                return None;
+            };
+            let expr =
+                ast_builder::build_index_expr(lhs.expr.clone(), index.expr.clone(), name_span);
+            partials.push(lhs.expr);
+            lhs = QasmTypedExpr {
+                ty: indexed_ty,
+                expr,
+            };
+        }
+        let mut rhs =
+            self.compile_expr_to_ty_with_casts(assignment.rhs(), &lhs.ty, assignment.syntax())?;
+
+        // build the updates from the innermost index outwards, the outermost
+        // index is applied by the assignment statement itself.
+        let mut indices = indices
+            .into_iter()
+            .map(|index| index.expr)
+            .collect::<Vec<_>>();
+        let index_expr = indices.remove(0);
+        for (array, index) in partials.into_iter().skip(1).zip(indices).rev() {
+            let update = build_array_update_expr(array, index, rhs, rhs_span);
+            rhs = wrap_expr_in_parens(update, rhs_span);
+        }
         let stmt =
             build_indexed_assignment_statement(name_span, string_name, index_expr, rhs, stmt_span);
         Some(stmt)
@@ -638,10 +720,22 @@ impl QasmCompiler {
         Some(build_barrier_call(call_span))
     }
 
-    /// Need to add break support in Q# to support break statements
+    /// Q# doesn't have break statements, so we set the break flag of the
+    /// enclosing loop. See [`LoopJumpFlags`] for how the flag is used.
     fn compile_break_stmt(&mut self, break_stmt: &oq3_syntax::ast::BreakStmt) -> Option<ast::Stmt> {
-        self.push_unsupported_error_message("break statements", break_stmt.syntax());
-        None
+        let span = span_for_syntax_node(break_stmt.syntax());
+        let Some(jumps) = self.loop_jumps.last_mut() else {
+            self.push_semantic_error(SemanticErrorKind::BreakNotInLoop(span));
+            return None;
+        };
+        jumps.breaks += 1;
+        let flag = jumps.break_flag.clone();
+        Some(build_assignment_statement(
+            span,
+            flag,
+            build_lit_bool_expr(true, span),
+            span,
+        ))
     }
 
     /// Classical decls are used to declare classical variables. They have two
@@ -661,12 +755,18 @@ impl QasmCompiler {
     ) -> Option<ast::Stmt> {
         let name = decl.name().expect("classical declaration must have a name");
         let name_span = span_for_syntax_node(name.syntax());
-        let scalar_ty = decl
-            .scalar_type()
-            .expect("Classical declaration must have a scalar type");
         let is_const = decl.const_token().is_some();
-        // if we can't convert the scalar type, we can't proceed, an error has been pushed
-        let ty = self.get_semantic_type_from_scalar_type(&scalar_ty, is_const)?;
+        // if we can't convert the type, we can't proceed, an error has been pushed
+        let (ty, ty_span) = if let Some(array_ty) = decl.array_type() {
+            let ty = self.get_semantic_type_from_array_type(&array_ty, is_const)?;
+            (ty, span_for_syntax_node(array_ty.syntax()))
+        } else {
+            let scalar_ty = decl
+                .scalar_type()
+                .expect("Classical declaration must have a scalar or array type");
+            let ty = self.get_semantic_type_from_scalar_type(&scalar_ty, is_const)?;
+            (ty, span_for_syntax_node(scalar_ty.syntax()))
+        };
         let qsharp_ty = self.convert_semantic_type_to_qsharp_type(&ty, name.syntax())?;
 
         let symbol = Symbol {
@@ -686,7 +786,6 @@ impl QasmCompiler {
         let rhs = self.compile_expr_to_ty_with_casts(decl.expr(), &ty, decl.syntax())?;
 
         // create the let binding and assign the rhs to the lhs
-        let ty_span = span_for_syntax_node(scalar_ty.syntax());
         let stmt_span = span_for_syntax_node(decl.syntax());
         let stmt = build_classical_decl(
             name.to_string(),
@@ -719,6 +818,12 @@ impl QasmCompiler {
         // since we have an expr, we can refine the node for errors
         let span = span_for_syntax_node(expr.syntax());
 
+        if let Expr::ArrayLiteral(array_literal) = &expr {
+            // array literals don't carry their own type, so we use the
+            // target type to type each of the elements.
+            return self.compile_array_literal_to_ty(array_literal, ty, span);
+        }
+
         let rhs = self.compile_expr(&expr)?;
         let rhs_ty = rhs.ty.clone();
 
@@ -795,28 +900,182 @@ impl QasmCompiler {
         Some(self.cast_expr_to_type(ty, &rhs, node)?.expr)
     }
 
-    /// Need to add continue support in Q# to support continue statements
+    /// Q# doesn't have continue statements, so we set the continue flag of
+    /// the enclosing loop. See [`LoopJumpFlags`] for how the flag is used.
     fn compile_continue_stmt(
         &mut self,
         continue_stmt: &oq3_syntax::ast::ContinueStmt,
     ) -> Option<ast::Stmt> {
-        self.push_unsupported_error_message("continue statements", continue_stmt.syntax());
-        None
+        let span = span_for_syntax_node(continue_stmt.syntax());
+        let Some(jumps) = self.loop_jumps.last_mut() else {
+            self.push_semantic_error(SemanticErrorKind::ContinueNotInLoop(span));
+            return None;
+        };
+        jumps.continues += 1;
+        let flag = jumps.continue_flag.clone();
+        Some(build_assignment_statement(
+            span,
+            flag,
+            build_lit_bool_expr(true, span),
+            span,
+        ))
     }
 
     /// <https://openqasm.com/language/subroutines.html>
-    /// Qiskit can't handle def statements, so we push an error
-    /// and return None. We also push a semantic error if the def
-    /// statement is not in the global scope.
+    /// Subroutines are compiled to Q# callables. Subroutines with qubit
+    /// parameters are compiled to operations, all others to functions.
+    /// Like gates, they are compiled as lambdas so that they can close over
+    /// global constants, unless they are annotated, in which case they are
+    /// compiled as top level items.
+    #[allow(clippy::too_many_lines)]
     fn compile_def_decl(&mut self, def: &oq3_syntax::ast::Def) -> Option<ast::Stmt> {
         let def_span = span_for_syntax_node(def.syntax());
         if !self.symbols.is_current_scope_global() {
-            let kind = SemanticErrorKind::QuantumDeclarationInNonGlobalScope(def_span);
+            let kind = SemanticErrorKind::DefDeclarationInNonGlobalScope(def_span);
             self.push_semantic_error(kind);
             return None;
         }
-        self.push_unsupported_error_message("def declarations", def.syntax());
-        None
+        let name = def.name()?;
+        let name_span = span_for_syntax_node(name.syntax());
+
+        let mut params = Vec::new();
+        let mut param_symbols = Vec::new();
+        for param in def
+            .typed_param_list()
+            .iter()
+            .flat_map(TypedParamList::typed_params)
+        {
+            let param_name = param.name()?;
+            let param_span = span_for_syntax_node(param_name.syntax());
+            let ty = self.get_semantic_type_from_typed_param(&param)?;
+            let qsharp_ty = self.convert_semantic_type_to_qsharp_type(&ty, param.syntax())?;
+            let ast_ty = map_qsharp_type_to_ast_ty(&qsharp_ty);
+            params.push((
+                param_name.to_string(),
+                ast_ty.clone(),
+                build_arg_pat(param_name.to_string(), param_span, ast_ty),
+            ));
+            param_symbols.push(Symbol {
+                name: param_name.to_string(),
+                span: param_span,
+                ty,
+                qsharp_ty,
+                io_kind: IOKind::Default,
+            });
+        }
+
+        let return_ty = match def
+            .return_signature()
+            .and_then(|signature| signature.scalar_type())
+        {
+            Some(scalar_ty) => self.get_semantic_type_from_scalar_type(&scalar_ty, false)?,
+            None => Type::Void,
+        };
+        let qsharp_return_ty =
+            self.convert_semantic_type_to_qsharp_type(&return_ty, def.syntax())?;
+
+        let num_qubit_params = param_symbols
+            .iter()
+            .filter(|symbol| matches!(symbol.ty, Type::Qubit | Type::QubitArray(..)))
+            .count();
+        let num_classical_params = param_symbols.len() - num_qubit_params;
+        let kind = if num_qubit_params > 0 {
+            crate::types::CallableKind::Operation
+        } else {
+            crate::types::CallableKind::Function
+        };
+        let signature = SubroutineSignature {
+            params: param_symbols
+                .iter()
+                .map(|symbol| symbol.ty.clone())
+                .collect(),
+            return_ty: return_ty.clone(),
+        };
+
+        self.symbols.push_scope(crate::symbols::ScopeKind::Function);
+        for symbol in param_symbols {
+            let (param_name, param_span) = (symbol.name.clone(), symbol.span);
+            if self.symbols.insert_symbol(symbol).is_err() {
+                self.push_redefined_symbol_error(param_name, param_span);
+            }
+        }
+        let outer_return_ty = self.subroutine_return_ty.replace(return_ty);
+        let body = def.body().map(|body| self.compile_block_expr(&body));
+        self.subroutine_return_ty = outer_return_ty;
+        self.symbols.pop_scope();
+        let body = body.unwrap_or_else(|| ast::Block {
+            id: NodeId::default(),
+            span: def_span,
+            stmts: Box::new([]),
+        });
+
+        let symbol = Symbol {
+            name: name.to_string(),
+            span: name_span,
+            ty: Type::Void,
+            qsharp_ty: crate::types::Type::Callable(
+                kind.clone(),
+                num_classical_params,
+                num_qubit_params,
+            ),
+            io_kind: IOKind::Default,
+        };
+        if self.symbols.insert_symbol(symbol).is_err() {
+            self.push_redefined_symbol_error(name.to_string(), name_span);
+            return None;
+        }
+        self.subroutines.insert(name.to_string(), signature);
+
+        let kind = match kind {
+            crate::types::CallableKind::Function => ast::CallableKind::Function,
+            crate::types::CallableKind::Operation => ast::CallableKind::Operation,
+        };
+        let return_ty = map_qsharp_type_to_ast_ty(&qsharp_return_ty);
+        if self.next_gate_as_item {
+            Some(build_function_or_operation(
+                name.to_string(),
+                params,
+                return_ty,
+                body,
+                kind,
+                name_span,
+                def_span,
+            ))
+        } else {
+            Some(build_function_or_operation_lambda(
+                name.to_string(),
+                params,
+                return_ty,
+                body,
+                kind,
+                name_span,
+                def_span,
+            ))
+        }
+    }
+
+    /// Subroutine parameters are either classical scalars or qubits.
+    fn get_semantic_type_from_typed_param(
+        &mut self,
+        param: &oq3_syntax::ast::TypedParam,
+    ) -> Option<Type> {
+        if let Some(scalar_ty) = param.scalar_type() {
+            self.get_semantic_type_from_scalar_type(&scalar_ty, false)
+        } else if let Some(qubit_ty) = param.qubit_type() {
+            match qubit_ty.designator() {
+                Some(designator) => {
+                    let width = extract_dims_from_designator(Some(designator))?;
+                    Some(Type::QubitArray(ArrayDims::D1(width as usize)))
+                }
+                None => Some(Type::Qubit),
+            }
+        } else {
+            self.push_unsupported_error_message(
+                "subroutine parameters other than scalars and qubits",
+                param.syntax(),
+            );
+            None
+        }
     }
 
     /// Some statements don't fall into the normal categories, so they
@@ -891,18 +1150,70 @@ impl QasmCompiler {
         None
     }
 
-    /// Qubit and bit registers are handled in the their own statements.
-    /// Arrays for classical variables would be handled here. Qiskit
-    /// can't handle array expressions yet, so we push an error and return None.
+    /// Array literals don't have a type of their own, so they can only be
+    /// compiled where the array type is known. That case is handled by
+    /// `compile_array_literal_to_ty`, so we push an error and return None.
     fn compile_array_literal_expr(
         &mut self,
         _array_literal: &oq3_syntax::ast::ArrayLiteral,
         expr: &Expr,
     ) -> Option<QasmTypedExpr> {
-        self.push_unimplemented_error_message("array literal expressions", expr.syntax());
+        self.push_unsupported_error_message(
+            "array literals without a declared array type",
+            expr.syntax(),
+        );
         None
     }
 
+    /// Compiles `{a, b, ...}` to a Q# array literal `[a, b, ...]` where each
+    /// element is cast to the indexed type of `ty`. Nested literals are used
+    /// for multi-dimensional arrays and are handled recursively.
+    fn compile_array_literal_to_ty(
+        &mut self,
+        array_literal: &oq3_syntax::ast::ArrayLiteral,
+        ty: &Type,
+        span: Span,
+    ) -> Option<ast::Expr> {
+        let item_ty = match ty {
+            Type::QubitArray(_) => None,
+            _ => get_indexed_type(ty).map(angle_array_item_as_float),
+        };
+        let Some(item_ty) = item_ty else {
+            let kind = SemanticErrorKind::CannotAssignToType(
+                "array literal".to_string(),
+                format!("{ty:?}"),
+                span,
+            );
+            self.push_semantic_error(kind);
+            return None;
+        };
+        let items: Vec<_> = array_literal
+            .expression_list()
+            .map(|list| list.exprs().collect())
+            .unwrap_or_default();
+        let expected_len = ty.dims().map_or(0, |dims| dims[0]);
+        if items.len() != expected_len {
+            let kind =
+                SemanticErrorKind::InvalidArrayLiteralLength(expected_len, items.len(), span);
+            self.push_semantic_error(kind);
+            return None;
+        }
+        let items = items
+            .into_iter()
+            .map(|item| {
+                let node = item.syntax().clone();
+                self.compile_expr_to_ty_with_casts(Some(item), &item_ty, &node)
+            })
+            .collect::<Vec<_>>();
+        if !items.iter().all(Option::is_some) {
+            // There was an issue with one of the expressions
+            // and an error was pushed
+            return None;
+        }
+        let items = items.into_iter().flatten().collect::<Vec<_>>();
+        Some(build_expr_array_expr(items, span))
+    }
+
     /// Create a binary expression from the given binary expression node
     /// The binary expression is created by recursively compiling the left and right
     /// expressions and then creating a binary expression from the compiled expressions
@@ -1235,13 +1546,147 @@ impl QasmCompiler {
         None
     }
 
-    /// Qiskit can't handle call expressions yet, so we push an error and return None.
+    /// Call expressions invoke subroutines declared with `def`. The arguments
+    /// are cast to the parameter types of the subroutine and the call has
+    /// the return type of the subroutine.
     fn compile_call_expr(
         &mut self,
         call_expr: &oq3_syntax::ast::CallExpr,
     ) -> Option<QasmTypedExpr> {
-        self.push_unimplemented_error_message("call expressions", call_expr.syntax());
-        None
+        let name = call_expr.identifier()?;
+        let name_text = name.to_string();
+        let name_span = span_for_syntax_node(name.syntax());
+        let call_span = span_for_syntax_node(call_expr.syntax());
+        if name_text == "sizeof" && self.symbols.get_symbol_by_name(&name_text).is_none() {
+            return self.compile_sizeof_call_expr(call_expr);
+        }
+        let Some(symbol) = self.symbols.get_symbol_by_name(&name_text) else {
+            self.push_missing_symbol_error(&name_text, call_expr.syntax());
+            return None;
+        };
+        let signature = if matches!(symbol.qsharp_ty, crate::types::Type::Callable(..)) {
+            self.subroutines.get(&name_text).cloned()
+        } else {
+            None
+        };
+        let Some(signature) = signature else {
+            let kind = SemanticErrorKind::CannotCallNonSubroutine(call_span);
+            self.push_semantic_error(kind);
+            return None;
+        };
+
+        let args = call_expr
+            .arg_list()
+            .and_then(|arg_list| arg_list.expression_list())
+            .map(|list| list.exprs().collect::<Vec<_>>())
+            .unwrap_or_default();
+        if args.len() != signature.params.len() {
+            let kind = SemanticErrorKind::InvalidNumberOfSubroutineArgs(
+                signature.params.len(),
+                args.len(),
+                call_span,
+            );
+            self.push_semantic_error(kind);
+            return None;
+        }
+        let args = args
+            .into_iter()
+            .zip(signature.params.iter())
+            .map(|(arg, ty)| {
+                let node = arg.syntax().clone();
+                self.compile_expr_to_ty_with_casts(Some(arg), ty, &node)
+            })
+            .collect::<Vec<_>>();
+        if !args.iter().all(Option::is_some) {
+            // There was an issue with one of the expressions
+            // and an error was pushed
+            return None;
+        }
+        let args = args.into_iter().flatten().collect::<Vec<_>>();
+
+        let callee = build_path_ident_expr(&name_text, name_span, name_span);
+        let expr = ast_builder::build_gate_call_with_params_and_callee(
+            build_gate_call_param_expr(args, 0),
+            callee,
+            call_span,
+        );
+        Some(QasmTypedExpr {
+            ty: signature.return_ty,
+            expr,
+        })
+    }
+
+    /// `sizeof(a)` and `sizeof(a, dim)` return the length of a dimension of
+    /// the array `a`. The outer dimension is the `Length` of the Q# array.
+    /// Q# arrays are nested, so the inner dimensions can't be read from an
+    /// empty outer array at runtime. Arrays are statically sized though, so
+    /// we use their declared size instead.
+    fn compile_sizeof_call_expr(
+        &mut self,
+        call_expr: &oq3_syntax::ast::CallExpr,
+    ) -> Option<QasmTypedExpr> {
+        let call_span = span_for_syntax_node(call_expr.syntax());
+        let args = call_expr
+            .arg_list()
+            .and_then(|arg_list| arg_list.expression_list())
+            .map(|list| list.exprs().collect::<Vec<_>>())
+            .unwrap_or_default();
+        if args.is_empty() || args.len() > 2 {
+            let expected = if args.is_empty() { 1 } else { 2 };
+            let kind =
+                SemanticErrorKind::InvalidNumberOfSubroutineArgs(expected, args.len(), call_span);
+            self.push_semantic_error(kind);
+            return None;
+        }
+        let array = self.compile_expr(&args[0])?;
+        let array_span = array.expr.span;
+        let Some(dims) = array.ty.dims() else {
+            let kind = SemanticErrorKind::CannotIndexType(format!("{:?}", array.ty), array_span);
+            self.push_semantic_error(kind);
+            return None;
+        };
+        let dim = match args.get(1) {
+            None => 0,
+            Some(Expr::Literal(literal)) => match literal.kind() {
+                LiteralKind::IntNumber(int_num) => int_num
+                    .value()
+                    .and_then(|v| usize::try_from(v).ok())
+                    .unwrap_or(usize::MAX),
+                _ => {
+                    let span = span_for_syntax_node(literal.syntax());
+                    let kind = SemanticErrorKind::DesignatorMustBeIntLiteral(span);
+                    self.push_semantic_error(kind);
+                    return None;
+                }
+            },
+            Some(expr) => {
+                self.push_unsupported_error_message("Non-literal sizeof dimensions", expr.syntax());
+                return None;
+            }
+        };
+        if dim >= dims.len() {
+            let kind = SemanticErrorKind::TypeRankError(call_span);
+            self.push_semantic_error(kind);
+            return None;
+        }
+        let expr = if dim == 0 {
+            let name_span = span_for_syntax_node(call_expr.identifier()?.syntax());
+            ast_builder::build_call_with_param(
+                "Length",
+                &[],
+                array.expr,
+                name_span,
+                array_span,
+                call_span,
+            )
+        } else {
+            let size = i64::try_from(dims[dim]).expect("array dimension should fit in an i64");
+            build_lit_int_expr(size, call_span)
+        };
+        Some(QasmTypedExpr {
+            ty: Type::UInt(None, IsConst::False),
+            expr,
+        })
     }
 
     /// explicit casts have no defined behavior AFAICT from the spec. I'm
@@ -1754,38 +2199,13 @@ impl QasmCompiler {
         let texpr = self.compile_expr(&expr)?;
         let index = index_expr.index_operator()?;
         let indices = self.compile_index_operator(&index)?;
-        let index_span = span_for_syntax_node(index.syntax());
-
-        if indices.len() != 1 {
-            // This is a temporary limitation. We can only handle
-            // single index expressions for now.
-            let kind = SemanticErrorKind::IndexMustBeSingleExpr(index_span);
-            self.push_semantic_error(kind);
-            return None;
-        }
-        let index = indices[0].clone();
-        if index.ty.num_dims() > texpr.ty.num_dims() {
-            let kind = SemanticErrorKind::TypeRankError(index_span);
-            self.push_semantic_error(kind);
-        }
-        let index_expr = index.expr.clone();
-        let Some(indexed_ty) = get_indexed_type(&texpr.ty) else {
-            let kind =
-                SemanticErrorKind::CannotIndexType(format!("{:?}", texpr.ty), texpr.expr.span);
-            self.push_semantic_error(kind);
-            return None;
-        };
-
-        let expr = ast_builder::build_index_expr(texpr.expr, index_expr, expr_span);
-        Some(QasmTypedExpr {
-            ty: indexed_ty,
-            expr,
-        })
+        self.apply_index_list(texpr, indices, expr_span)
     }
 
     /// Compiles a indexed expr `a[i]` where `a` is an identifier and `i` is an expression.
+    /// Multiple indices, `a[i, j]` or `a[i][j]`, are applied in order.
     /// The type of the expression is determined by the indexed type of the identifier
-    /// resolved by `self.get_indexed_type`.
+    /// resolved by `get_indexed_type`.
     fn compile_indexed_identifier_expr(
         &mut self,
         indexed_ident: &oq3_syntax::ast::IndexedIdentifier,
@@ -1799,31 +2219,79 @@ impl QasmCompiler {
         let sym = sym.clone();
         let op_span = span_for_syntax_node(indexed_ident.syntax());
 
-        let index: Vec<_> = indexed_ident
-            .index_operators()
-            .filter_map(|op| self.compile_index_operator(&op))
-            .flatten()
+        let ops: Vec<_> = indexed_ident.index_operators().collect();
+        let indices: Vec<_> = ops
+            .iter()
+            .filter_map(|op| self.compile_index_operator(op))
             .collect();
+        if indices.len() != ops.len() {
+            // an error has already been pushed
+            return None;
+        }
 
-        assert!(index.len() == 1, "index must be a single expression");
         let ident = build_path_ident_expr(name, name_span, op_span);
-        let expr = ast::Expr {
-            id: NodeId::default(),
-            span: span_for_syntax_node(indexed_ident.syntax()),
-            kind: Box::new(ast::ExprKind::Index(
-                Box::new(ident),
-                Box::new(index[0].expr.clone()),
-            )),
-        };
-        let Some(indexed_ty) = get_indexed_type(&sym.ty) else {
-            let kind = SemanticErrorKind::CannotIndexType(format!("{:?}", sym.ty), op_span);
-            self.push_semantic_error(kind);
-            return None;
+        let texpr = QasmTypedExpr {
+            ty: sym.ty.clone(),
+            expr: ident,
         };
-        Some(QasmTypedExpr {
-            ty: indexed_ty,
-            expr,
-        })
+        self.apply_indices(texpr, indices, op_span)
+    }
+
+    /// Indexes `texpr` by each index operator in turn, and by each index in
+    /// the operator's list. Integer indices remove the outermost dimension of
+    /// the type. A range index slices the array and keeps its type. Q# can't
+    /// index the remaining dimensions of each element of a slice, so a range
+    /// must be the last index in its list: `a[1:2][0]` is allowed, but
+    /// `a[1:2, 0]` is not.
+    fn apply_indices(
+        &mut self,
+        mut texpr: QasmTypedExpr,
+        index_lists: Vec<Vec<QasmTypedExpr>>,
+        span: Span,
+    ) -> Option<QasmTypedExpr> {
+        for indices in index_lists {
+            texpr = self.apply_index_list(texpr, indices, span)?;
+        }
+        Some(texpr)
+    }
+
+    fn apply_index_list(
+        &mut self,
+        mut texpr: QasmTypedExpr,
+        indices: Vec<QasmTypedExpr>,
+        span: Span,
+    ) -> Option<QasmTypedExpr> {
+        let count = indices.len();
+        for (i, index) in indices.into_iter().enumerate() {
+            let index_span = index.expr.span;
+            if index.ty.num_dims() > texpr.ty.num_dims() {
+                let kind = SemanticErrorKind::TypeRankError(index_span);
+                self.push_semantic_error(kind);
+            }
+            let indexed_ty = if matches!(index.ty, Type::Range) {
+                if i + 1 != count {
+                    let message = "Indexes into array slices".to_string();
+                    let kind = crate::ErrorKind::NotSupported(message, index_span);
+                    let error = self.create_err(kind);
+                    self.errors.push(error);
+                    return None;
+                }
+                get_indexed_type(&texpr.ty).map(|_| texpr.ty.clone())
+            } else {
+                get_indexed_type(&texpr.ty).map(angle_array_item_as_float)
+            };
+            let Some(indexed_ty) = indexed_ty else {
+                let kind =
+                    SemanticErrorKind::CannotIndexType(format!("{:?}", texpr.ty), texpr.expr.span);
+                self.push_semantic_error(kind);
+                return None;
+            };
+            texpr = QasmTypedExpr {
+                ty: indexed_ty,
+                expr: ast_builder::build_index_expr(texpr.expr, index.expr, span),
+            };
+        }
+        Some(texpr)
     }
 
     fn compile_literal_expr(
@@ -2259,11 +2727,19 @@ impl QasmCompiler {
             self.push_semantic_error(kind);
         }
         // the containing function will have an explicit return type
-        // or default of Void. We don't need to check the return type
-        // as that will be handled by Q# type checker. If there is no
-        // expression, we return Unit which Void maps to in Q#.
+        // or default of Void. We cast the returned value to the declared
+        // return type where we can; any remaining mismatch will be handled
+        // by the Q# type checker. If there is no expression, we return Unit
+        // which Void maps to in Q#.
         if let Some(expr) = return_expr.expr() {
-            let texpr = self.compile_expr(&expr)?;
+            let texpr = match self.subroutine_return_ty.clone() {
+                Some(ty) if ty != Type::Void => {
+                    let expr =
+                        self.compile_expr_to_ty_with_casts(Some(expr), &ty, return_expr.syntax())?;
+                    QasmTypedExpr { ty, expr }
+                }
+                _ => self.compile_expr(&expr)?,
+            };
             let expr = ast_builder::build_return_expr(texpr.expr, stmt_span);
             Some(QasmTypedExpr { ty: texpr.ty, expr })
         } else {
@@ -2307,27 +2783,42 @@ impl QasmCompiler {
             return None;
         }
 
+        self.push_loop_jumps();
         let body = if let Some(stmt) = for_stmt.stmt() {
             let stmt = self.compile_stmt(&stmt);
             self.symbols.pop_scope();
-            build_stmt_wrapped_block_expr(stmt?)
+            stmt.map(build_stmt_wrapped_block_expr)
         } else if let Some(block) = for_stmt.body() {
             let block = self.compile_block_expr(&block);
             self.symbols.pop_scope();
-            block
+            Some(block)
         } else {
             let span = span_for_syntax_node(for_stmt.syntax());
             let kind = SemanticErrorKind::ForStatementsMustHaveABodyOrStatement(span);
             self.push_semantic_error(kind);
-            None?
+            None
+        };
+        let jumps = self
+            .loop_jumps
+            .pop()
+            .expect("loop jumps should have been pushed");
+        let body = build_loop_body_with_continue_flag(&jumps, body?);
+        let Some(no_break_expr) = build_no_jump_taken_expr(&jumps, false) else {
+            return Some(ast_builder::build_for_stmt(
+                &loop_var_symbol,
+                iterable,
+                body,
+                stmt_span,
+            ));
         };
 
-        Some(ast_builder::build_for_stmt(
-            &loop_var_symbol,
-            iterable,
-            body,
-            stmt_span,
-        ))
+        // Q# for loops can't exit early, so once the break flag is set
+        // the remaining iterations are skipped.
+        let body_span = body.span;
+        let guard = build_if_expr_then_block(no_break_expr, body, body_span);
+        let body = build_stmt_wrapped_block_expr(build_stmt_semi_from_expr(guard));
+        let for_stmt = ast_builder::build_for_stmt(&loop_var_symbol, iterable, body, stmt_span);
+        Some(build_loop_with_break_flag(&jumps, for_stmt))
     }
 
     fn compile_for_iterable(
@@ -2538,20 +3029,22 @@ impl QasmCompiler {
         &mut self,
         decl: &oq3_syntax::ast::IODeclarationStatement,
     ) -> Option<ast::Stmt> {
-        if decl.array_type().is_some() {
-            self.push_unimplemented_error_message("array io declarations", decl.syntax());
-            return None;
-        }
         let name = decl.name().expect("io declaration must have a name");
-        let scalar_ty = decl
-            .scalar_type()
-            .expect("io declaration must have a scalar type");
         let io_kind = match decl.input_token() {
             Some(_) => IOKind::Input,
             None => IOKind::Output,
         };
-        // if we can't convert the scalar type, we can't proceed, an error has been pushed
-        let ty = self.get_semantic_type_from_scalar_type(&scalar_ty, false)?;
+        // if we can't convert the type, we can't proceed, an error has been pushed
+        let (ty, ty_span) = if let Some(array_ty) = decl.array_type() {
+            let ty = self.get_semantic_type_from_array_type(&array_ty, false)?;
+            (ty, span_for_syntax_node(array_ty.syntax()))
+        } else {
+            let scalar_ty = decl
+                .scalar_type()
+                .expect("io declaration must have a scalar or array type");
+            let ty = self.get_semantic_type_from_scalar_type(&scalar_ty, false)?;
+            (ty, span_for_syntax_node(scalar_ty.syntax()))
+        };
         let qsharp_ty = self.convert_semantic_type_to_qsharp_type(&ty, name.syntax())?;
         let symbol = Symbol {
             name: name.to_string(),
@@ -2574,7 +3067,7 @@ impl QasmCompiler {
             let stmt = build_classical_decl(
                 name.to_string(),
                 false,
-                span_for_syntax_node(scalar_ty.syntax()),
+                ty_span,
                 span_for_syntax_node(decl.syntax()),
                 span_for_syntax_node(name.syntax()),
                 &qsharp_ty,
@@ -2862,12 +3355,77 @@ impl QasmCompiler {
             return None;
         };
 
+        self.push_loop_jumps();
         self.symbols.push_scope(crate::symbols::ScopeKind::Block);
         let block_body = self.compile_block_expr(&block);
         self.symbols.pop_scope();
-        Some(ast_builder::build_while_stmt(
-            cond.expr, block_body, stmt_span,
-        ))
+        let jumps = self
+            .loop_jumps
+            .pop()
+            .expect("loop jumps should have been pushed");
+
+        let block_body = build_loop_body_with_continue_flag(&jumps, block_body);
+        let Some(no_break_expr) = build_no_jump_taken_expr(&jumps, false) else {
+            return Some(ast_builder::build_while_stmt(
+                cond.expr, block_body, stmt_span,
+            ));
+        };
+
+        // The loop ends once the break flag is set.
+        let cond_expr = if matches!(*cond.expr.kind, ast::ExprKind::BinOp(..)) {
+            let span = cond.expr.span;
+            wrap_expr_in_parens(cond.expr, span)
+        } else {
+            cond.expr
+        };
+        let cond_expr = build_binary_expr(
+            false,
+            ast::BinOp::AndL,
+            no_break_expr,
+            cond_expr,
+            span_for_syntax_node(condition.syntax()),
+        );
+        let while_stmt = ast_builder::build_while_stmt(cond_expr, block_body, stmt_span);
+        Some(build_loop_with_break_flag(&jumps, while_stmt))
+    }
+
+    /// Starts tracking the jump statements of a new innermost loop.
+    /// The flags are named by loop depth so that nested loops don't
+    /// shadow the flags of their enclosing loops.
+    fn push_loop_jumps(&mut self) {
+        let depth = self.loop_jumps.len();
+        self.loop_jumps.push(LoopJumpFlags {
+            break_flag: format!("__break_{depth}__"),
+            continue_flag: format!("__continue_{depth}__"),
+            breaks: 0,
+            continues: 0,
+        });
+    }
+
+    /// The number of jump statements compiled so far in the innermost loop.
+    fn loop_jump_count(&self) -> usize {
+        self.loop_jumps
+            .last()
+            .map_or(0, |jumps| jumps.breaks + jumps.continues)
+    }
+
+    /// Wraps the statements following a jump in the innermost loop body so
+    /// that they only run if no jump was taken.
+    fn build_loop_jump_guard(&self, stmts: Vec<ast::Stmt>) -> Option<ast::Stmt> {
+        let jumps = self.loop_jumps.last()?;
+        let lo = stmts.first()?.span.lo;
+        let hi = stmts.last()?.span.hi;
+        let span = Span { lo, hi };
+        let block = ast::Block {
+            id: NodeId::default(),
+            span,
+            stmts: stmts.into_iter().map(Box::new).collect(),
+        };
+        let cond = build_no_jump_taken_expr(jumps, true)
+            .expect("a jump should have been compiled before the guarded statements");
+        Some(build_stmt_semi_from_expr(build_if_expr_then_block(
+            cond, block, span,
+        )))
     }
 
     fn convert_semantic_type_to_qsharp_type(
@@ -2911,9 +3469,10 @@ impl QasmCompiler {
             Type::IntArray(dims) | Type::UIntArray(dims) => {
                 Some(crate::types::Type::IntArray(dims.into(), is_const))
             }
-            Type::FloatArray(dims) => Some(crate::types::Type::DoubleArray(dims.into())),
-            Type::AngleArray(_) => todo!("AngleArray to Q# type"),
-            Type::ComplexArray(_) => todo!("ComplexArray to Q# type"),
+            Type::FloatArray(dims) | Type::AngleArray(dims) => {
+                Some(crate::types::Type::DoubleArray(dims.into()))
+            }
+            Type::ComplexArray(dims) => Some(crate::types::Type::ComplexArray(dims.into())),
             Type::BoolArray(dims) => Some(crate::types::Type::BoolArray(dims.into(), is_const)),
             Type::Gate(cargs, qargs) => Some(crate::types::Type::Callable(
                 crate::types::CallableKind::Operation,
@@ -2974,36 +3533,27 @@ impl QasmCompiler {
                 self.push_unsupported_error_message("Stretch type values are not supported.", node);
                 None
             }
-            Type::BitArray(dims, _) => match dims {
-                ArrayDims::D1(len) => Some(build_default_result_array_expr(*len, span)),
-                ArrayDims::D2(_, _) => {
-                    self.push_unsupported_error_message(
-                        "2-dim Bit Arrays without default values",
-                        node,
-                    );
-                    None
-                }
-                ArrayDims::D3(_, _, _) => {
-                    self.push_unsupported_error_message(
-                        "3-dim Bit Arrays without default values",
-                        node,
-                    );
-                    None
-                }
-            },
+            Type::BitArray(ArrayDims::D1(len), _) => {
+                Some(build_default_result_array_expr(*len, span))
+            }
             Type::QubitArray(_) => {
                 let message = "QubitArray default values";
                 self.push_unsupported_error_message(message, node);
                 None
             }
-            Type::IntArray(_)
+            Type::BitArray(..)
+            | Type::IntArray(_)
             | Type::UIntArray(_)
             | Type::FloatArray(_)
             | Type::AngleArray(_)
             | Type::ComplexArray(_)
             | Type::BoolArray(_) => {
-                self.push_unsupported_error_message("Arrays without default values", node);
-                None
+                // arrays are filled with the default value of their elements,
+                // `array[int, 2, 3]` becomes `[[0, size = 3], size = 2]`.
+                let item_ty = angle_array_item_as_float(get_indexed_type(ty)?);
+                let item = self.get_default_value(&item_ty, node)?;
+                let len = ty.dims().map_or(0, |dims| dims[0]);
+                Some(build_array_repeat_expr(item, len, span))
             }
             Type::DurationArray(_) => {
                 self.push_unsupported_error_message(
@@ -3143,6 +3693,73 @@ impl QasmCompiler {
         Some(ty)
     }
 
+    /// Converts `array[T, n, ...]` to the matching array type. The dimensions
+    /// must be integer literals. For `bit[w]` elements the width becomes the
+    /// innermost dimension, so `array[bit[2], 3]` is a 3x2 bit array.
+    fn get_semantic_type_from_array_type(
+        &mut self,
+        array_ty: &oq3_syntax::ast::ArrayType,
+        is_const: bool,
+    ) -> Option<oq3_semantics::types::Type> {
+        let scalar_ty = array_ty
+            .scalar_type()
+            .expect("Array type must have a scalar type");
+        let item_ty = self.get_semantic_type_from_scalar_type(&scalar_ty, is_const)?;
+        let dim_exprs: Vec<_> = array_ty
+            .expression_list()
+            .map(|list| list.exprs().collect())
+            .unwrap_or_default();
+        let mut dims = Vec::with_capacity(dim_exprs.len() + 1);
+        for expr in dim_exprs {
+            let dim = match &expr {
+                Expr::Literal(literal) => match literal.kind() {
+                    LiteralKind::IntNumber(int_num) => {
+                        int_num.value().and_then(|v| usize::try_from(v).ok())
+                    }
+                    _ => None,
+                },
+                _ => None,
+            };
+            let Some(dim) = dim else {
+                let span = span_for_syntax_node(expr.syntax());
+                let kind = SemanticErrorKind::DesignatorMustBeIntLiteral(span);
+                self.push_semantic_error(kind);
+                return None;
+            };
+            dims.push(dim);
+        }
+        if let Type::BitArray(ArrayDims::D1(width), _) = &item_ty {
+            dims.push(*width);
+        }
+        let dims = match dims.as_slice() {
+            [d1] => ArrayDims::D1(*d1),
+            [d1, d2] => ArrayDims::D2(*d1, *d2),
+            [d1, d2, d3] => ArrayDims::D3(*d1, *d2, *d3),
+            _ => {
+                self.push_unsupported_error_message(
+                    "Arrays with more than 3 dimensions",
+                    array_ty.syntax(),
+                );
+                return None;
+            }
+        };
+        let ty = match item_ty {
+            Type::Angle(..) => Type::AngleArray(dims),
+            Type::Bit(is_const) | Type::BitArray(_, is_const) => Type::BitArray(dims, is_const),
+            Type::Bool(..) => Type::BoolArray(dims),
+            Type::Complex(..) => Type::ComplexArray(dims),
+            Type::Float(..) => Type::FloatArray(dims),
+            Type::Int(..) => Type::IntArray(dims),
+            Type::UInt(..) => Type::UIntArray(dims),
+            _ => {
+                let message = format!("Arrays of {item_ty:?}");
+                self.push_unsupported_error_message(message, array_ty.syntax());
+                return None;
+            }
+        };
+        Some(ty)
+    }
+
     fn try_cast_expr_to_type(
         &mut self,
         ty: &Type,
@@ -3427,7 +4044,7 @@ impl QasmCompiler {
         rhs: &QasmTypedExpr,
         node: &SyntaxNode,
     ) -> Option<QasmTypedExpr> {
-        assert!(matches!(rhs.ty, Type::Bit(..)));
+        assert!(matches!(rhs.ty, Type::Angle(..)));
         match ty {
             Type::Bit(..) => {
                 let msg = "Cast angle to bit";
@@ -3973,6 +4590,80 @@ impl QasmCompiler {
     }
 }
 
+/// Angle arrays are lowered to Q# `Double` arrays, so their scalar items are
+/// compiled and indexed as floats. Nested arrays are returned as is.
+fn angle_array_item_as_float(item_ty: Type) -> Type {
+    match item_ty {
+        Type::Angle(_, is_const) => Type::Float(None, is_const),
+        _ => item_ty,
+    }
+}
+
+/// Builds `not break_flag and not continue_flag` for the flags set by the
+/// jumps compiled so far. The continue flag is only included when
+/// `include_continue` is set. Returns `None` if none of the flags is set.
+fn build_no_jump_taken_expr(jumps: &LoopJumpFlags, include_continue: bool) -> Option<ast::Expr> {
+    let not_flag = |flag: &str| {
+        let flag = build_path_ident_expr(flag, Span::default(), Span::default());
+        build_unary_op_expr(ast::UnOp::NotL, flag, Span::default())
+    };
+    let break_expr = (jumps.breaks > 0).then(|| not_flag(&jumps.break_flag));
+    let continue_expr =
+        (include_continue && jumps.continues > 0).then(|| not_flag(&jumps.continue_flag));
+    match (break_expr, continue_expr) {
+        (Some(break_expr), Some(continue_expr)) => Some(build_binary_expr(
+            false,
+            ast::BinOp::AndL,
+            break_expr,
+            continue_expr,
+            Span::default(),
+        )),
+        (expr, None) | (None, expr) => expr,
+    }
+}
+
+/// Prepends the declaration of the continue flag to the loop body so that
+/// the flag is reset at the start of every iteration. The body is returned
+/// as is if the loop contains no continue statements.
+fn build_loop_body_with_continue_flag(jumps: &LoopJumpFlags, body: ast::Block) -> ast::Block {
+    if jumps.continues == 0 {
+        return body;
+    }
+    let decl = build_classical_decl(
+        &jumps.continue_flag,
+        false,
+        Span::default(),
+        Span::default(),
+        Span::default(),
+        &crate::types::Type::Bool(false),
+        build_lit_bool_expr(false, Span::default()),
+    );
+    let stmts = std::iter::once(Box::new(decl))
+        .chain(body.stmts.into_vec())
+        .collect();
+    ast::Block { stmts, ..body }
+}
+
+/// Wraps the loop in a block which declares the break flag before the loop.
+fn build_loop_with_break_flag(jumps: &LoopJumpFlags, loop_stmt: ast::Stmt) -> ast::Stmt {
+    let span = loop_stmt.span;
+    let decl = build_classical_decl(
+        &jumps.break_flag,
+        false,
+        Span::default(),
+        Span::default(),
+        Span::default(),
+        &crate::types::Type::Bool(false),
+        build_lit_bool_expr(false, Span::default()),
+    );
+    let block = ast::Block {
+        id: NodeId::default(),
+        span,
+        stmts: Box::new([Box::new(decl), Box::new(loop_stmt)]),
+    };
+    build_stmt_semi_from_expr(build_wrapped_block_expr(block))
+}
+
 fn compile_end_stmt(end: &oq3_syntax::ast::EndStmt) -> ast::Stmt {
     ast_builder::build_end_stmt(span_for_syntax_node(end.syntax()))
 }
@@ -110,6 +110,9 @@ enum SemanticErrorKind {
     #[error("Annotation missing target statement.")]
     #[diagnostic(code("Qsc.Qasm3.Compile.AnnotationWithoutStatement"))]
     AnnotationWithoutStatement(#[label] Span),
+    #[error("Break statements are only allowed within loops.")]
+    #[diagnostic(code("Qsc.Qasm3.Compile.BreakNotInLoop"))]
+    BreakNotInLoop(#[label] Span),
     #[error("Cannot alias type {0}. Only qubit and qubit[] can be aliased.")]
     #[diagnostic(code("Qsc.Qasm3.Compile.CannotAliasType"))]
     CannotAliasType(String, Span),
@@ -122,6 +125,9 @@ enum SemanticErrorKind {
     #[error("Cannot call a gate that is not a gate.")]
     #[diagnostic(code("Qsc.Qasm3.Compile.CannotCallNonGate"))]
     CannotCallNonGate(#[label] Span),
+    #[error("Cannot call an expression that is not a subroutine.")]
+    #[diagnostic(code("Qsc.Qasm3.Compile.CannotCallNonSubroutine"))]
+    CannotCallNonSubroutine(#[label] Span),
     #[error("Cannot cast expression of type {0} to type {1}")]
     #[diagnostic(code("Qsc.Qasm3.Compile.CannotCast"))]
     CannotCast(String, String, #[label] Span),
@@ -138,6 +144,12 @@ enum SemanticErrorKind {
     #[error("Complex numbers in assignment binary expressions are not yet supported.")]
     #[diagnostic(code("Qsc.Qasm3.Compile.ComplexBinaryAssignment"))]
     ComplexBinaryAssignment(#[label] Span),
+    #[error("Continue statements are only allowed within loops.")]
+    #[diagnostic(code("Qsc.Qasm3.Compile.ContinueNotInLoop"))]
+    ContinueNotInLoop(#[label] Span),
+    #[error("Subroutine declarations must be done in global scope.")]
+    #[diagnostic(code("Qsc.Qasm3.Compile.DefDeclarationInNonGlobalScope"))]
+    DefDeclarationInNonGlobalScope(#[label] Span),
     #[error("Designator must be a literal integer.")]
     #[diagnostic(code("Qsc.Qasm3.Compile.DesignatorMustBeIntLiteral"))]
     DesignatorMustBeIntLiteral(#[label] Span),
@@ -165,6 +177,9 @@ enum SemanticErrorKind {
     #[error("Indexed must be a single expression.")]
     #[diagnostic(code("Qsc.Qasm3.Compile.IndexMustBeSingleExpr"))]
     IndexMustBeSingleExpr(#[label] Span),
+    #[error("Array literal has {1} elements, but the array type expects {0}.")]
+    #[diagnostic(code("Qsc.Qasm3.Compile.InvalidArrayLiteralLength"))]
+    InvalidArrayLiteralLength(usize, usize, #[label] Span),
     #[error("Annotations only valid on gate definitions.")]
     #[diagnostic(code("Qsc.Qasm3.Compile.InvalidAnnotationTarget"))]
     InvalidAnnotationTarget(Span),
@@ -182,6 +197,9 @@ enum SemanticErrorKind {
     #[error("Gate expects {0} classical arguments, but {1} were provided.")]
     #[diagnostic(code("Qsc.Qasm3.Compile.InvalidNumberOfClassicalArgs"))]
     InvalidNumberOfClassicalArgs(usize, usize, #[label] Span),
+    #[error("Subroutine expects {0} arguments, but {1} were provided.")]
+    #[diagnostic(code("Qsc.Qasm3.Compile.InvalidNumberOfSubroutineArgs"))]
+    InvalidNumberOfSubroutineArgs(usize, usize, #[label] Span),
     #[error("Gate expects {0} qubit arguments, but {1} were provided.")]
     #[diagnostic(code("Qsc.Qasm3.Compile.InvalidNumberOfQubitArgs"))]
     InvalidNumberOfQubitArgs(usize, usize, #[label] Span),
@@ -261,6 +279,7 @@ impl SemanticErrorKind {
             Self::AnnotationWithoutStatement(span) => {
                 Self::AnnotationWithoutStatement(span + offset)
             }
+            Self::BreakNotInLoop(span) => Self::BreakNotInLoop(span + offset),
             Self::CannotCast(lhs, rhs, span) => Self::CannotCast(lhs, rhs, span + offset),
             Self::CastWouldCauseTruncation(lhs, rhs, span) => {
                 Self::CastWouldCauseTruncation(lhs, rhs, span + offset)
@@ -273,11 +292,16 @@ impl SemanticErrorKind {
                 Self::CannotAssignToType(lhs, rhs, span + offset)
             }
             Self::CannotCallNonGate(span) => Self::CannotCallNonGate(span + offset),
+            Self::CannotCallNonSubroutine(span) => Self::CannotCallNonSubroutine(span + offset),
             Self::CannotIndexType(name, span) => Self::CannotIndexType(name, span + offset),
             Self::CannotUpdateConstVariable(name, span) => {
                 Self::CannotUpdateConstVariable(name, span + offset)
             }
             Self::ComplexBinaryAssignment(span) => Self::ComplexBinaryAssignment(span + offset),
+            Self::ContinueNotInLoop(span) => Self::ContinueNotInLoop(span + offset),
+            Self::DefDeclarationInNonGlobalScope(span) => {
+                Self::DefDeclarationInNonGlobalScope(span + offset)
+            }
             Self::DesignatorMustBeIntLiteral(span) => {
                 Self::DesignatorMustBeIntLiteral(span + offset)
             }
@@ -302,6 +326,9 @@ impl SemanticErrorKind {
             }
             Self::IndexMustBeSingleExpr(span) => Self::IndexMustBeSingleExpr(span + offset),
             Self::InvalidAnnotationTarget(span) => Self::InvalidAnnotationTarget(span + offset),
+            Self::InvalidArrayLiteralLength(expected, actual, span) => {
+                Self::InvalidArrayLiteralLength(expected, actual, span + offset)
+            }
             Self::InvalidControlCount(span) => Self::InvalidControlCount(span + offset),
             Self::InvalidNumberOfClassicalArgs(expected, actual, span) => {
                 Self::InvalidNumberOfClassicalArgs(expected, actual, span + offset)
@@ -309,6 +336,9 @@ impl SemanticErrorKind {
             Self::InvalidNumberOfQubitArgs(expected, actual, span) => {
                 Self::InvalidNumberOfQubitArgs(expected, actual, span + offset)
             }
+            Self::InvalidNumberOfSubroutineArgs(expected, actual, span) => {
+                Self::InvalidNumberOfSubroutineArgs(expected, actual, span + offset)
+            }
             Self::InvalidCastValueRange(lhs, rhs, span) => {
                 Self::InvalidCastValueRange(lhs, rhs, span + offset)
             }
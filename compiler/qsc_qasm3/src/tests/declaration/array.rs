@@ -2,6 +2,7 @@
 // Licensed under the MIT License.
 
 mod bit;
+mod classical;
 mod qubit;
 
 use crate::tests::{fail_on_compilation_errors, parse, qasm_to_program_fragments};
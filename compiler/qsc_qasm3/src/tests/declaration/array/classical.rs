@@ -0,0 +1,165 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use crate::tests::compile_qasm_stmt_to_qsharp;
+use expect_test::expect;
+use miette::Report;
+
+#[test]
+fn int_array_with_no_initializer() -> miette::Result<(), Vec<Report>> {
+    let source = "
+        array[int[8], 4] x;
+    ";
+
+    let qsharp = compile_qasm_stmt_to_qsharp(source)?;
+    expect![
+        r#"
+        mutable x = [0, size = 4];
+    "#
+    ]
+    .assert_eq(&qsharp);
+    Ok(())
+}
+
+#[test]
+fn multi_dim_float_array_with_no_initializer() -> miette::Result<(), Vec<Report>> {
+    let source = "
+        array[float[64], 4, 2] x;
+    ";
+
+    let qsharp = compile_qasm_stmt_to_qsharp(source)?;
+    expect![
+        r#"
+        mutable x = [[0., size = 2], size = 4];
+    "#
+    ]
+    .assert_eq(&qsharp);
+    Ok(())
+}
+
+#[test]
+fn angle_array_with_no_initializer() -> miette::Result<(), Vec<Report>> {
+    let source = "
+        array[angle[32], 3] x;
+    ";
+
+    let qsharp = compile_qasm_stmt_to_qsharp(source)?;
+    expect![
+        r#"
+        mutable x = [0., size = 3];
+    "#
+    ]
+    .assert_eq(&qsharp);
+    Ok(())
+}
+
+#[test]
+fn array_of_bit_registers_with_no_initializer() -> miette::Result<(), Vec<Report>> {
+    let source = "
+        array[bit[2], 3] x;
+    ";
+
+    let qsharp = compile_qasm_stmt_to_qsharp(source)?;
+    expect![
+        r#"
+        mutable x = [[Zero, Zero], size = 3];
+    "#
+    ]
+    .assert_eq(&qsharp);
+    Ok(())
+}
+
+#[test]
+fn int_array_with_literal_initializer() -> miette::Result<(), Vec<Report>> {
+    let source = "
+        array[int[8], 4] x = {1, 2, 3, 4};
+    ";
+
+    let qsharp = compile_qasm_stmt_to_qsharp(source)?;
+    expect![
+        r#"
+        mutable x = [1, 2, 3, 4];
+    "#
+    ]
+    .assert_eq(&qsharp);
+    Ok(())
+}
+
+#[test]
+fn bool_array_with_literal_initializer() -> miette::Result<(), Vec<Report>> {
+    let source = "
+        array[bool, 2] x = {true, false};
+    ";
+
+    let qsharp = compile_qasm_stmt_to_qsharp(source)?;
+    expect![
+        r#"
+        mutable x = [true, false];
+    "#
+    ]
+    .assert_eq(&qsharp);
+    Ok(())
+}
+
+#[test]
+fn multi_dim_uint_array_with_nested_literal_initializer() -> miette::Result<(), Vec<Report>> {
+    let source = "
+        array[uint[32], 2, 2] x = {{3, 4}, {2, 5}};
+    ";
+
+    let qsharp = compile_qasm_stmt_to_qsharp(source)?;
+    expect![
+        r#"
+        mutable x = [[3, 4], [2, 5]];
+    "#
+    ]
+    .assert_eq(&qsharp);
+    Ok(())
+}
+
+#[test]
+fn angle_array_with_float_literal_initializer() -> miette::Result<(), Vec<Report>> {
+    let source = "
+        array[angle, 2] x = {0.5, 1.5};
+    ";
+
+    let qsharp = compile_qasm_stmt_to_qsharp(source)?;
+    expect![
+        r#"
+        mutable x = [0.5, 1.5];
+    "#
+    ]
+    .assert_eq(&qsharp);
+    Ok(())
+}
+
+#[test]
+fn array_literal_with_wrong_length_fails() {
+    let source = "
+        array[int, 3] x = {1, 2};
+    ";
+
+    let Err(errors) = compile_qasm_stmt_to_qsharp(source) else {
+        panic!("Expected an error");
+    };
+
+    assert_eq!(1, errors.len(), "Expected one error");
+    expect![r#"Array literal has 2 elements, but the array type expects 3."#]
+        .assert_eq(&errors[0].to_string());
+}
+
+#[test]
+fn scalar_angle_is_not_lowered_as_float() {
+    let source = "
+        angle x = 1.0;
+    ";
+
+    let Err(errors) = compile_qasm_stmt_to_qsharp(source) else {
+        panic!("Expected an error");
+    };
+
+    expect![
+        r#"Cannot assign a value of Float(None, True) type to a classical variable of Angle(None, False) type."#
+    ]
+    .assert_eq(&errors[0].to_string());
+}
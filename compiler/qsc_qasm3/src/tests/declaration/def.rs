@@ -0,0 +1,169 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use crate::tests::{compile_qasm_stmt_to_qsharp, compile_qasm_to_qsharp};
+use expect_test::expect;
+use miette::Report;
+
+#[test]
+fn classical_subroutine_is_function() -> miette::Result<(), Vec<Report>> {
+    let source = r#"
+        def square(int a) -> int {
+            return a * a;
+        }
+    "#;
+
+    let qsharp = compile_qasm_stmt_to_qsharp(source)?;
+    expect![
+        r#"
+        let square : (Int) -> Int = (a) -> {
+            return a * a;
+        };
+        "#
+    ]
+    .assert_eq(&qsharp);
+    Ok(())
+}
+
+#[test]
+fn subroutine_with_qubit_param_is_operation() -> miette::Result<(), Vec<Report>> {
+    let source = r#"
+        include "stdgates.inc";
+        def flip(int n, qubit q) {
+            for int i in {1, 2} {
+                x q;
+            }
+        }
+    "#;
+
+    let qsharp = compile_qasm_stmt_to_qsharp(source)?;
+    expect![
+        r#"
+        let flip : (Int, Qubit) => Unit = (n, q) => {
+            for i : Int in [1, 2] {
+                X(q);
+            }
+        };
+        "#
+    ]
+    .assert_eq(&qsharp);
+    Ok(())
+}
+
+#[test]
+fn subroutine_without_params() -> miette::Result<(), Vec<Report>> {
+    let source = r#"
+        def zero() -> int {
+            return 0;
+        }
+        int x = zero();
+    "#;
+
+    let qsharp = compile_qasm_to_qsharp(source)?;
+    expect![
+        r#"
+        let zero : () -> Int = () -> {
+            return 0;
+        };
+        mutable x = zero();
+        "#
+    ]
+    .assert_eq(&qsharp);
+    Ok(())
+}
+
+#[test]
+fn annotated_subroutine_is_item() -> miette::Result<(), Vec<Report>> {
+    let source = r#"
+        include "stdgates.inc";
+        @SimulatableIntrinsic
+        def my_h(qubit q) {
+            h q;
+        }
+    "#;
+
+    let qsharp = compile_qasm_stmt_to_qsharp(source)?;
+    expect![
+        r#"
+        @SimulatableIntrinsic()
+        operation my_h(q : Qubit) : Unit {
+            H(q);
+        }
+        "#
+    ]
+    .assert_eq(&qsharp);
+    Ok(())
+}
+
+#[test]
+fn call_to_subroutine_returning_bit() -> miette::Result<(), Vec<Report>> {
+    let source = r#"
+        def measure_qubit(qubit q) -> bit {
+            return measure q;
+        }
+        qubit q;
+        bit c = measure_qubit(q);
+    "#;
+
+    let qsharp = compile_qasm_to_qsharp(source)?;
+    expect![
+        r#"
+        let measure_qubit : (Qubit) => Result = (q) => {
+            return QIR.Intrinsic.__quantum__qis__m__body(q);
+        };
+        let q = QIR.Runtime.__quantum__rt__qubit_allocate();
+        mutable c = measure_qubit(q);
+        "#
+    ]
+    .assert_eq(&qsharp);
+    Ok(())
+}
+
+#[test]
+fn def_in_non_global_scope_raises_error() {
+    let source = r#"
+        int i = 0;
+        if (i == 0) {
+            def f() {}
+        }
+    "#;
+
+    let Err(errors) = compile_qasm_to_qsharp(source) else {
+        panic!("Expected error");
+    };
+
+    expect![r#"Subroutine declarations must be done in global scope."#]
+        .assert_eq(&errors[0].to_string());
+}
+
+#[test]
+fn call_with_wrong_number_of_args_raises_error() {
+    let source = r#"
+        def square(int a) -> int {
+            return a * a;
+        }
+        int x = square(1, 2);
+    "#;
+
+    let Err(errors) = compile_qasm_to_qsharp(source) else {
+        panic!("Expected error");
+    };
+
+    expect![r#"Subroutine expects 1 arguments, but 2 were provided."#]
+        .assert_eq(&errors[0].to_string());
+}
+
+#[test]
+fn calling_non_subroutine_raises_error() {
+    let source = r#"
+        int a = 0;
+        int x = a(1);
+    "#;
+
+    let Err(errors) = compile_qasm_to_qsharp(source) else {
+        panic!("Expected error");
+    };
+
+    expect![r#"Cannot call an expression that is not a subroutine."#]
+        .assert_eq(&errors[0].to_string());
+}
@@ -221,3 +221,52 @@ operation Test(bi : BigInt, i : Int, ui : Int, u : Int, f : Double, b : Bool, c
     .assert_eq(&qsharp);
     Ok(())
 }
+
+#[test]
+fn angle_array_is_lifted() -> miette::Result<(), Vec<Report>> {
+    let source = r#"
+input array[angle, 4] theta;
+"#;
+
+    let qsharp = compile_qasm_to_qsharp_operation(source)?;
+    expect![
+        r#"
+operation Test(theta : Double[]) : Unit {}
+"#
+    ]
+    .assert_eq(&qsharp);
+    Ok(())
+}
+
+#[test]
+fn multi_dim_int_array_is_lifted() -> miette::Result<(), Vec<Report>> {
+    let source = r#"
+input array[int, 2, 3] a;
+"#;
+
+    let qsharp = compile_qasm_to_qsharp_operation(source)?;
+    expect![
+        r#"
+operation Test(a : Int[][]) : Unit {}
+"#
+    ]
+    .assert_eq(&qsharp);
+    Ok(())
+}
+
+#[test]
+fn angle_array_elements_can_be_used_as_gate_params() -> miette::Result<(), Vec<Report>> {
+    let source = r#"
+include "stdgates.inc";
+input array[angle, 2] theta;
+qubit[2] q;
+rx(theta[0]) q[0];
+ry(theta[1]) q[1];
+"#;
+
+    let qsharp = compile_qasm_to_qsharp_operation(source)?;
+    assert!(qsharp.contains("operation Test(theta : Double[]) : Unit {"));
+    assert!(qsharp.contains("Rx(theta[0], q[0]);"));
+    assert!(qsharp.contains("Ry(theta[1], q[1]);"));
+    Ok(())
+}
@@ -275,3 +275,23 @@ operation Test() : (BigInt, Int, Int, Int, Double, Bool, Result, Microsoft.Quant
     .assert_eq(&qsharp);
     Ok(())
 }
+
+#[test]
+fn float_array_is_returned() -> miette::Result<(), Vec<Report>> {
+    let source = r#"
+output array[float, 2] f;
+"#;
+
+    let qsharp = compile_qasm_to_qsharp_operation(source)?;
+    expect![
+        r#"
+@EntryPoint()
+operation Test() : Double[] {
+    mutable f = [0., size = 2];
+    f
+}
+"#
+    ]
+    .assert_eq(&qsharp);
+    Ok(())
+}
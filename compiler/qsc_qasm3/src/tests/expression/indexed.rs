@@ -113,3 +113,110 @@ fn bool_indexed_ty_is_same_as_element_ty() -> miette::Result<(), Vec<Report>> {
     .assert_eq(&qsharp);
     Ok(())
 }
+
+#[test]
+fn multi_dim_array_can_be_indexed_by_index_list() -> miette::Result<(), Vec<Report>> {
+    let source = "
+        array[int, 2, 3] x;
+        int y = x[1, 2];
+        x[0, 1] = y;
+    ";
+
+    let qsharp = compile_qasm_to_qsharp(source)?;
+    expect![
+        r#"
+        mutable x = [[0, size = 3], size = 2];
+        mutable y = x[1][2];
+        set x w/= 0 <- (x[0] w/ 1 <- y);
+    "#
+    ]
+    .assert_eq(&qsharp);
+    Ok(())
+}
+
+#[test]
+fn array_can_be_sliced_by_range() -> miette::Result<(), Vec<Report>> {
+    let source = "
+        array[float, 4] x;
+        float y = x[1:2][0];
+    ";
+
+    let qsharp = compile_qasm_to_qsharp(source)?;
+    expect![
+        r#"
+        mutable x = [0., size = 4];
+        mutable y = x[1..2][0];
+    "#
+    ]
+    .assert_eq(&qsharp);
+    Ok(())
+}
+
+#[test]
+fn multi_dim_array_cannot_be_indexed_after_range_in_index_list() {
+    let source = "
+        array[float, 4, 2] x;
+        float y = x[1:2, 0];
+    ";
+
+    let Err(errors) = compile_qasm_to_qsharp(source) else {
+        panic!("Expected an error");
+    };
+
+    assert_eq!(1, errors.len(), "Expected one error");
+    expect![r#"Indexes into array slices are not supported."#].assert_eq(&errors[0].to_string());
+}
+
+#[test]
+fn sizeof_returns_length_of_requested_dimension() -> miette::Result<(), Vec<Report>> {
+    let source = "
+        array[angle, 2, 3] x;
+        int rows = sizeof(x);
+        int cols = sizeof(x, 1);
+    ";
+
+    let qsharp = compile_qasm_to_qsharp(source)?;
+    expect![
+        r#"
+        mutable x = [[0., size = 3], size = 2];
+        mutable rows = Length(x);
+        mutable cols = 3;
+    "#
+    ]
+    .assert_eq(&qsharp);
+    Ok(())
+}
+
+#[test]
+fn sizeof_inner_dimension_of_empty_array_is_declared_size() -> miette::Result<(), Vec<Report>> {
+    let source = "
+        array[int, 0, 4] x;
+        int cols = sizeof(x, 1);
+    ";
+
+    let qsharp = compile_qasm_to_qsharp(source)?;
+    expect![
+        r#"
+        mutable x = [[0, size = 4], size = 0];
+        mutable cols = 4;
+    "#
+    ]
+    .assert_eq(&qsharp);
+    Ok(())
+}
+
+#[test]
+fn sizeof_dimension_out_of_range_fails() {
+    let source = "
+        array[int, 2] x;
+        int n = sizeof(x, 1);
+    ";
+
+    let Err(errors) = compile_qasm_to_qsharp(source) else {
+        panic!("Expected an error");
+    };
+
+    assert_eq!(1, errors.len(), "Expected one error");
+    expect![r#"Types differ by dimensions and are incompatible."#]
+        .assert_eq(&errors[0].to_string());
+}
@@ -2,6 +2,7 @@
 // Licensed under the MIT License.
 
 mod annotation;
+mod break_continue;
 mod end;
 mod for_loop;
 mod gate_call;
@@ -0,0 +1,278 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use crate::tests::{compile_qasm_to_qir, compile_qasm_to_qsharp};
+use expect_test::expect;
+use miette::Report;
+use qsc::target::Profile;
+
+#[test]
+fn break_in_while_loop_sets_flag_checked_by_condition() -> miette::Result<(), Vec<Report>> {
+    let source = r#"
+        int i = 0;
+        while (i < 10) {
+            i += 1;
+            if (i == 5) {
+                break;
+            }
+        }
+    "#;
+
+    let qsharp = compile_qasm_to_qsharp(source)?;
+    expect![
+        r#"
+        mutable i = 0;
+        {
+            mutable __break_0__ = false;
+            while not __break_0__ and (i < 10) {
+                set i += 1;
+                if i == 5 {
+                    set __break_0__ = true;
+                };
+            }
+        };
+        "#
+    ]
+    .assert_eq(&qsharp);
+    Ok(())
+}
+
+#[test]
+fn continue_in_for_loop_guards_remaining_stmts() -> miette::Result<(), Vec<Report>> {
+    let source = r#"
+        int sum = 0;
+        for int i in {1, 5, 10} {
+            if (i == 5) {
+                continue;
+            }
+            sum += i;
+        }
+    "#;
+
+    let qsharp = compile_qasm_to_qsharp(source)?;
+    expect![
+        r#"
+        mutable sum = 0;
+        for i : Int in [1, 5, 10] {
+            mutable __continue_0__ = false;
+            if i == 5 {
+                set __continue_0__ = true;
+            };
+            if not __continue_0__ {
+                set sum += i;
+            };
+        }
+        "#
+    ]
+    .assert_eq(&qsharp);
+    Ok(())
+}
+
+#[test]
+fn continue_in_while_loop_is_reset_in_body() -> miette::Result<(), Vec<Report>> {
+    let source = r#"
+        int i = 0;
+        int sum = 0;
+        while (i < 5) {
+            i += 1;
+            if (i == 3) {
+                continue;
+            }
+            sum += i;
+        }
+    "#;
+
+    let qsharp = compile_qasm_to_qsharp(source)?;
+    expect![
+        r#"
+        mutable i = 0;
+        mutable sum = 0;
+        while i < 5 {
+            mutable __continue_0__ = false;
+            set i += 1;
+            if i == 3 {
+                set __continue_0__ = true;
+            };
+            if not __continue_0__ {
+                set sum += i;
+            };
+        }
+        "#
+    ]
+    .assert_eq(&qsharp);
+    Ok(())
+}
+
+#[test]
+fn break_and_continue_in_while_loop_declare_both_flags() -> miette::Result<(), Vec<Report>> {
+    let source = r#"
+        int i = 0;
+        int sum = 0;
+        while (i < 10) {
+            i += 1;
+            if (i == 2) {
+                continue;
+            }
+            if (i == 5) {
+                break;
+            }
+            sum += i;
+        }
+    "#;
+
+    let qsharp = compile_qasm_to_qsharp(source)?;
+    expect![
+        r#"
+        mutable i = 0;
+        mutable sum = 0;
+        {
+            mutable __break_0__ = false;
+            while not __break_0__ and (i < 10) {
+                mutable __continue_0__ = false;
+                set i += 1;
+                if i == 2 {
+                    set __continue_0__ = true;
+                };
+                if not __break_0__ and not __continue_0__ {
+                    if i == 5 {
+                        set __break_0__ = true;
+                    };
+                    if not __break_0__ and not __continue_0__ {
+                        set sum += i;
+                    };
+                };
+            }
+        };
+        "#
+    ]
+    .assert_eq(&qsharp);
+    Ok(())
+}
+
+#[test]
+fn continue_in_while_loop_generates_qir() -> miette::Result<(), Vec<Report>> {
+    let source = r#"
+        include "stdgates.inc";
+        bit[1] c;
+        qubit[1] q;
+        int i = 0;
+        while (i < 3) {
+            i += 1;
+            if (i == 2) {
+                continue;
+            }
+            x q[0];
+        }
+        c[0] = measure q[0];
+    "#;
+
+    let qir = compile_qasm_to_qir(source, Profile::AdaptiveRI)?;
+    expect![
+        r#"
+        %Result = type opaque
+        %Qubit = type opaque
+
+        define void @ENTRYPOINT__main() #0 {
+        block_0:
+          call void @__quantum__qis__x__body(%Qubit* inttoptr (i64 0 to %Qubit*))
+          call void @__quantum__qis__x__body(%Qubit* inttoptr (i64 0 to %Qubit*))
+          call void @__quantum__qis__m__body(%Qubit* inttoptr (i64 0 to %Qubit*), %Result* inttoptr (i64 0 to %Result*))
+          call void @__quantum__rt__array_record_output(i64 1, i8* null)
+          call void @__quantum__rt__result_record_output(%Result* inttoptr (i64 0 to %Result*), i8* null)
+          ret void
+        }
+
+        declare void @__quantum__qis__x__body(%Qubit*)
+
+        declare void @__quantum__qis__m__body(%Qubit*, %Result*) #1
+
+        declare void @__quantum__rt__array_record_output(i64, i8*)
+
+        declare void @__quantum__rt__result_record_output(%Result*, i8*)
+
+        attributes #0 = { "entry_point" "output_labeling_schema" "qir_profiles"="adaptive_profile" "required_num_qubits"="1" "required_num_results"="1" }
+        attributes #1 = { "irreversible" }
+
+        ; module flags
+
+        !llvm.module.flags = !{!0, !1, !2, !3, !4, !5, !6, !7, !8, !9, !10}
+
+        !0 = !{i32 1, !"qir_major_version", i32 1}
+        !1 = !{i32 7, !"qir_minor_version", i32 0}
+        !2 = !{i32 1, !"dynamic_qubit_management", i1 false}
+        !3 = !{i32 1, !"dynamic_result_management", i1 false}
+        !4 = !{i32 1, !"classical_ints", i1 true}
+        !5 = !{i32 1, !"qubit_resetting", i1 true}
+        !6 = !{i32 1, !"classical_floats", i1 false}
+        !7 = !{i32 1, !"backwards_branching", i1 false}
+        !8 = !{i32 1, !"classical_fixed_points", i1 false}
+        !9 = !{i32 1, !"user_functions", i1 false}
+        !10 = !{i32 1, !"multiple_target_branching", i1 false}
+        "#
+    ]
+    .assert_eq(&qir);
+    Ok(())
+}
+
+#[test]
+fn break_in_nested_loop_only_affects_inner_loop() -> miette::Result<(), Vec<Report>> {
+    let source = r#"
+        int i = 0;
+        while (i < 3) {
+            i += 1;
+            for int j in {1, 2} {
+                break;
+            }
+        }
+    "#;
+
+    let qsharp = compile_qasm_to_qsharp(source)?;
+    expect![
+        r#"
+        mutable i = 0;
+        while i < 3 {
+            set i += 1;
+            {
+                mutable __break_1__ = false;
+                for j : Int in [1, 2] {
+                    if not __break_1__ {
+                        set __break_1__ = true;
+                    };
+                }
+            };
+        }
+        "#
+    ]
+    .assert_eq(&qsharp);
+    Ok(())
+}
+
+#[test]
+fn break_outside_loop_raises_error() {
+    let source = r#"
+        break;
+    "#;
+
+    let Err(errors) = compile_qasm_to_qsharp(source) else {
+        panic!("Expected error");
+    };
+
+    expect![r#"Break statements are only allowed within loops."#].assert_eq(&errors[0].to_string());
+}
+
+#[test]
+fn continue_outside_loop_raises_error() {
+    let source = r#"
+        int i = 0;
+        if (i == 0) {
+            continue;
+        }
+    "#;
+
+    let Err(errors) = compile_qasm_to_qsharp(source) else {
+        panic!("Expected error");
+    };
+
+    expect![r#"Continue statements are only allowed within loops."#]
+        .assert_eq(&errors[0].to_string());
+}
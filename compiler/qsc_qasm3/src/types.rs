@@ -111,6 +111,7 @@ pub(crate) enum Type {
     Range,
     BoolArray(ArrayDimensions, bool),
     BigIntArray(ArrayDimensions, bool),
+    ComplexArray(ArrayDimensions),
     IntArray(ArrayDimensions, bool),
     DoubleArray(ArrayDimensions),
     QubitArray(ArrayDimensions),
@@ -123,7 +124,6 @@ pub(crate) enum Type {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CallableKind {
     /// A function.
-    #[allow(dead_code)]
     Function,
     /// An operation.
     Operation,
@@ -179,6 +179,7 @@ impl Display for Type {
             }
             Type::BoolArray(dim, _) => write!(f, "bool{dim}"),
             Type::BigIntArray(dim, _) => write!(f, "BigInt{dim}"),
+            Type::ComplexArray(dim) => write!(f, "Complex{dim}"),
             Type::IntArray(dim, _) => write!(f, "Int{dim}"),
             Type::DoubleArray(dim) => write!(f, "Double{dim}"),
             Type::QubitArray(dim) => write!(f, "Qubit{dim}"),
@@ -211,8 +212,8 @@ impl Display for ArrayDimensions {
 }
 
 /// Get the indexed type of a given type.
-/// For example, if the type is `Int[2][3]`, the indexed type is `Int[2]`.
-/// If the type is `Int[2]`, the indexed type is `Int`.
+/// For example, if the type is `array[int, 2, 3]`, the indexed type is
+/// `array[int, 3]`. If the type is `array[int, 2]`, the indexed type is `int`.
 /// If the type is `Int`, the indexed type is `None`.
 ///
 /// This is useful for determining the type of an array element.
@@ -223,43 +224,43 @@ pub(crate) fn get_indexed_type(
     let ty = match &ty {
         Type::AngleArray(dims) => match dims {
             ArrayDims::D1(_) => Type::Angle(None, IsConst::False),
-            ArrayDims::D2(l, _) => Type::AngleArray(ArrayDims::D1(*l)),
-            ArrayDims::D3(l, w, _) => Type::AngleArray(ArrayDims::D2(*l, *w)),
+            ArrayDims::D2(_, w) => Type::AngleArray(ArrayDims::D1(*w)),
+            ArrayDims::D3(_, w, h) => Type::AngleArray(ArrayDims::D2(*w, *h)),
         },
         Type::BitArray(dims, is_const) => match dims {
             ArrayDims::D1(_) => Type::Bit(is_const.clone()),
-            ArrayDims::D2(l, _) => Type::BitArray(ArrayDims::D1(*l), is_const.clone()),
-            ArrayDims::D3(l, w, _) => Type::BitArray(ArrayDims::D2(*l, *w), is_const.clone()),
+            ArrayDims::D2(_, w) => Type::BitArray(ArrayDims::D1(*w), is_const.clone()),
+            ArrayDims::D3(_, w, h) => Type::BitArray(ArrayDims::D2(*w, *h), is_const.clone()),
         },
         Type::BoolArray(dims) => match dims {
             ArrayDims::D1(_) => Type::Bool(IsConst::False),
-            ArrayDims::D2(l, _) => Type::BoolArray(ArrayDims::D1(*l)),
-            ArrayDims::D3(l, w, _) => Type::BoolArray(ArrayDims::D2(*l, *w)),
+            ArrayDims::D2(_, w) => Type::BoolArray(ArrayDims::D1(*w)),
+            ArrayDims::D3(_, w, h) => Type::BoolArray(ArrayDims::D2(*w, *h)),
         },
         Type::ComplexArray(dims) => match dims {
             ArrayDims::D1(_) => Type::Complex(None, IsConst::False),
-            ArrayDims::D2(l, _) => Type::ComplexArray(ArrayDims::D1(*l)),
-            ArrayDims::D3(l, w, _) => Type::ComplexArray(ArrayDims::D2(*l, *w)),
+            ArrayDims::D2(_, w) => Type::ComplexArray(ArrayDims::D1(*w)),
+            ArrayDims::D3(_, w, h) => Type::ComplexArray(ArrayDims::D2(*w, *h)),
         },
         Type::FloatArray(dims) => match dims {
             ArrayDims::D1(_) => Type::Float(None, IsConst::False),
-            ArrayDims::D2(l, _) => Type::FloatArray(ArrayDims::D1(*l)),
-            ArrayDims::D3(l, w, _) => Type::FloatArray(ArrayDims::D2(*l, *w)),
+            ArrayDims::D2(_, w) => Type::FloatArray(ArrayDims::D1(*w)),
+            ArrayDims::D3(_, w, h) => Type::FloatArray(ArrayDims::D2(*w, *h)),
         },
         Type::IntArray(dims) => match dims {
             ArrayDims::D1(_) => Type::Int(None, IsConst::False),
-            ArrayDims::D2(l, _) => Type::IntArray(ArrayDims::D1(*l)),
-            ArrayDims::D3(l, w, _) => Type::IntArray(ArrayDims::D2(*l, *w)),
+            ArrayDims::D2(_, w) => Type::IntArray(ArrayDims::D1(*w)),
+            ArrayDims::D3(_, w, h) => Type::IntArray(ArrayDims::D2(*w, *h)),
         },
         Type::QubitArray(dims) => match dims {
             ArrayDims::D1(_) => Type::Qubit,
-            ArrayDims::D2(l, _) => Type::QubitArray(ArrayDims::D1(*l)),
-            ArrayDims::D3(l, w, _) => Type::QubitArray(ArrayDims::D2(*l, *w)),
+            ArrayDims::D2(_, w) => Type::QubitArray(ArrayDims::D1(*w)),
+            ArrayDims::D3(_, w, h) => Type::QubitArray(ArrayDims::D2(*w, *h)),
         },
         Type::UIntArray(dims) => match dims {
             ArrayDims::D1(_) => Type::UInt(None, IsConst::False),
-            ArrayDims::D2(l, _) => Type::UIntArray(ArrayDims::D1(*l)),
-            ArrayDims::D3(l, w, _) => Type::UIntArray(ArrayDims::D2(*l, *w)),
+            ArrayDims::D2(_, w) => Type::UIntArray(ArrayDims::D1(*w)),
+            ArrayDims::D3(_, w, h) => Type::UIntArray(ArrayDims::D2(*w, *h)),
         },
         _ => return None,
     };
@@ -3,7 +3,7 @@
 
 use std::collections::VecDeque;
 
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 
 use crate::{
     rir::{BlockId, Instruction, Program},
@@ -16,31 +16,57 @@ mod tests;
 /// Remaps block IDs in the given program to be contiguous, starting from 0,
 /// and in a topological ordering if the program is Directed Acyclic Graph (DAG).
 /// Toplogical ordering is useful for passes that assume each block's successors
-/// have higher IDs than the block itself. This is best effort; if the program has a cycle,
-/// the function will still remap block IDs but the ordering may not be topological.
+/// have higher IDs than the block itself. If the program has a cycle, the block IDs
+/// are instead remapped in reverse postorder, where every successor has a higher ID
+/// except for the target of a loop back edge.
 pub fn remap_block_ids(program: &mut Program) {
-    // Check if the program is acyclic, which lets us construct a topological ordering.
-    let is_acyclic = check_acyclic(program);
-
     // Only update the entry point.
     let entry_block_id = program
         .get_callable(program.entry)
         .body
         .expect("entry point should have a body block");
 
-    // Because we know the program is acyclic, we can keep a list as the map from old block IDs to new block IDs, where
-    // the new block ID is the index in the list.
+    // The new block ID is the index in the list.
+    let block_id_map = if check_acyclic(program) {
+        get_topological_ordering(program, entry_block_id)
+    } else {
+        get_reverse_postorder(program, entry_block_id)
+    };
+
+    let block_id_map = block_id_map
+        .into_iter()
+        .enumerate()
+        .map(|(new_id, old_id)| (old_id, new_id))
+        .collect::<FxHashMap<_, _>>();
+
+    let blocks = program.blocks.drain().collect::<Vec<_>>();
+    for (old_block_id, mut block) in blocks {
+        let new_block_id = block_id_map[&old_block_id];
+        update_phi_nodes(&block_id_map, &mut block.0);
+        update_terminator(
+            &block_id_map,
+            block
+                .0
+                .last_mut()
+                .expect("block should have at least one instruction"),
+        );
+        program.blocks.insert(new_block_id.into(), block);
+    }
+    program
+        .callables
+        .get_mut(program.entry)
+        .expect("entry should exist")
+        .body = Some(block_id_map[&entry_block_id].into());
+}
+
+fn get_topological_ordering(program: &Program, entry_block_id: BlockId) -> Vec<BlockId> {
+    // Because we know the program is acyclic, we can keep a list as the map from old block IDs to new block IDs.
     let mut block_id_map = Vec::new();
     let mut blocks_to_visit: VecDeque<BlockId> = vec![entry_block_id].into();
     while let Some(block_id) = blocks_to_visit.pop_front() {
         // If we've already visited this block, remove it from the previous ordering so that we can insert it at the end.
         // This effectively remaps all the blocks in the list and updates the mapped id of the current block.
-        // This is only safe without cycles, so on a cyclic graph the node is skipped and not remapped.
-        if is_acyclic {
-            block_id_map.retain(|id| *id != block_id);
-        } else if block_id_map.contains(&block_id) {
-            continue;
-        }
+        block_id_map.retain(|id| *id != block_id);
         block_id_map.push(block_id);
 
         let successors = get_block_successors(program.get_block(block_id));
@@ -60,31 +86,32 @@ pub fn remap_block_ids(program: &mut Program) {
         blocks_to_visit.retain(|id| !successors.contains(id));
         blocks_to_visit.extend(successors);
     }
+    block_id_map
+}
 
-    let block_id_map = block_id_map
-        .into_iter()
-        .enumerate()
-        .map(|(new_id, old_id)| (old_id, new_id))
-        .collect::<FxHashMap<_, _>>();
-
-    let blocks = program.blocks.drain().collect::<Vec<_>>();
-    for (old_block_id, mut block) in blocks {
-        let new_block_id = block_id_map[&old_block_id];
-        update_phi_nodes(&block_id_map, &mut block.0);
-        update_terminator(
-            &block_id_map,
-            block
-                .0
-                .last_mut()
-                .expect("block should have at least one instruction"),
-        );
-        program.blocks.insert(new_block_id.into(), block);
+fn get_reverse_postorder(program: &Program, entry_block_id: BlockId) -> Vec<BlockId> {
+    // Perform a depth-first traversal, tracking the successors left to visit for each block on the stack.
+    // Successors are visited last to first, so that the body of a loop is ordered before the blocks after the loop.
+    let mut visited = FxHashSet::default();
+    visited.insert(entry_block_id);
+    let mut postorder = Vec::new();
+    let mut stack = vec![(
+        entry_block_id,
+        get_block_successors(program.get_block(entry_block_id)),
+    )];
+    while let Some((block_id, successors)) = stack.last_mut() {
+        if let Some(successor) = successors.pop() {
+            if visited.insert(successor) {
+                let successor_successors = get_block_successors(program.get_block(successor));
+                stack.push((successor, successor_successors));
+            }
+        } else {
+            postorder.push(*block_id);
+            stack.pop();
+        }
     }
-    program
-        .callables
-        .get_mut(program.entry)
-        .expect("entry should exist")
-        .body = Some(block_id_map[&entry_block_id].into());
+    postorder.reverse();
+    postorder
 }
 
 fn check_acyclic(program: &Program) -> bool {
@@ -19,10 +19,11 @@ pub fn simplify_control_flow(program: &mut Program) {
     let mut merge_map = FxHashMap::default();
 
     // For each block, check if it has a single predecessor and that predecessor ends with a jump to the current block.
-    // If so, the block is redundant and can be merged.
+    // If so, the block is redundant and can be merged. A block that jumps to itself forms a loop and cannot be merged.
     let preds_map = build_predecessors_map(program);
     for (block_id, preds) in preds_map.iter() {
         if preds.len() == 1
+            && preds[0] != block_id
             && program.get_block(preds[0]).0.last() == Some(&Instruction::Jump(block_id))
         {
             merge_map.insert(block_id, preds[0]);
@@ -472,3 +472,24 @@ fn simplify_control_flow_removes_redundant_blocks_across_out_of_order_branches()
             num_results: 0"#]]
     .assert_eq(&program.to_string());
 }
+
+#[test]
+fn simplify_control_flow_leaves_self_loop_unchanged() {
+    let mut program = Program::new();
+    program.blocks.insert(
+        BlockId(0),
+        Block(vec![
+            Instruction::Store(
+                Operand::Literal(Literal::Bool(true)),
+                Variable {
+                    variable_id: VariableId(0),
+                    ty: Ty::Boolean,
+                },
+            ),
+            Instruction::Jump(BlockId(0)),
+        ]),
+    );
+    let program_before = program.to_string();
+    simplify_control_flow(&mut program);
+    assert_eq!(program.to_string(), program_before);
+}
@@ -124,6 +124,18 @@ fn get_variable_uses(program: &Program) -> IndexMap<VariableId, Vec<(BlockId, us
                 | Instruction::Ashr(Operand::Literal(_), Operand::Variable(var), _)
                 | Instruction::Icmp(_, Operand::Variable(var), Operand::Literal(_), _)
                 | Instruction::Icmp(_, Operand::Literal(_), Operand::Variable(var), _)
+                | Instruction::Fadd(Operand::Variable(var), Operand::Literal(_), _)
+                | Instruction::Fadd(Operand::Literal(_), Operand::Variable(var), _)
+                | Instruction::Fsub(Operand::Variable(var), Operand::Literal(_), _)
+                | Instruction::Fsub(Operand::Literal(_), Operand::Variable(var), _)
+                | Instruction::Fmul(Operand::Variable(var), Operand::Literal(_), _)
+                | Instruction::Fmul(Operand::Literal(_), Operand::Variable(var), _)
+                | Instruction::Fdiv(Operand::Variable(var), Operand::Literal(_), _)
+                | Instruction::Fdiv(Operand::Literal(_), Operand::Variable(var), _)
+                | Instruction::Fcmp(_, Operand::Variable(var), Operand::Literal(_), _)
+                | Instruction::Fcmp(_, Operand::Literal(_), Operand::Variable(var), _)
+                | Instruction::Sitofp(Operand::Variable(var), _)
+                | Instruction::Fptosi(Operand::Variable(var), _)
                 | Instruction::LogicalNot(Operand::Variable(var), _)
                 | Instruction::LogicalAnd(Operand::Variable(var), Operand::Literal(_), _)
                 | Instruction::LogicalAnd(Operand::Literal(_), Operand::Variable(var), _)
@@ -149,6 +161,11 @@ fn get_variable_uses(program: &Program) -> IndexMap<VariableId, Vec<(BlockId, us
                 | Instruction::Shl(Operand::Variable(var1), Operand::Variable(var2), _)
                 | Instruction::Ashr(Operand::Variable(var1), Operand::Variable(var2), _)
                 | Instruction::Icmp(_, Operand::Variable(var1), Operand::Variable(var2), _)
+                | Instruction::Fadd(Operand::Variable(var1), Operand::Variable(var2), _)
+                | Instruction::Fsub(Operand::Variable(var1), Operand::Variable(var2), _)
+                | Instruction::Fmul(Operand::Variable(var1), Operand::Variable(var2), _)
+                | Instruction::Fdiv(Operand::Variable(var1), Operand::Variable(var2), _)
+                | Instruction::Fcmp(_, Operand::Variable(var1), Operand::Variable(var2), _)
                 | Instruction::LogicalAnd(Operand::Variable(var1), Operand::Variable(var2), _)
                 | Instruction::LogicalOr(Operand::Variable(var1), Operand::Variable(var2), _)
                 | Instruction::BitwiseAnd(Operand::Variable(var1), Operand::Variable(var2), _)
@@ -186,6 +203,13 @@ fn get_variable_uses(program: &Program) -> IndexMap<VariableId, Vec<(BlockId, us
                 | Instruction::Shl(Operand::Literal(_), Operand::Literal(_), _)
                 | Instruction::Ashr(Operand::Literal(_), Operand::Literal(_), _)
                 | Instruction::Icmp(_, Operand::Literal(_), Operand::Literal(_), _)
+                | Instruction::Fadd(Operand::Literal(_), Operand::Literal(_), _)
+                | Instruction::Fsub(Operand::Literal(_), Operand::Literal(_), _)
+                | Instruction::Fmul(Operand::Literal(_), Operand::Literal(_), _)
+                | Instruction::Fdiv(Operand::Literal(_), Operand::Literal(_), _)
+                | Instruction::Fcmp(_, Operand::Literal(_), Operand::Literal(_), _)
+                | Instruction::Sitofp(Operand::Literal(_), _)
+                | Instruction::Fptosi(Operand::Literal(_), _)
                 | Instruction::LogicalNot(Operand::Literal(_), _)
                 | Instruction::LogicalAnd(Operand::Literal(_), Operand::Literal(_), _)
                 | Instruction::LogicalOr(Operand::Literal(_), Operand::Literal(_), _)
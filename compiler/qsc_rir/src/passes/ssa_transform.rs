@@ -9,14 +9,13 @@ use crate::{
     utils::get_variable_assignments,
 };
 use qsc_data_structures::index_map::IndexMap;
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 
 /// Transforms the program into Single Static Assignment (SSA) form by inserting phi nodes
 /// at the beginning of blocks where necessary, allowing the removal of store instructions.
+/// Block ids are expected to be in reverse postorder, such that any predecessor with an id greater than or equal
+/// to the block itself is a back edge from the end of a loop to the loop header.
 pub fn transform_to_ssa(program: &mut Program, preds: &IndexMap<BlockId, Vec<BlockId>>) {
-    // Ensure that the graph is acyclic before proceeding. Current approach does not support cycles.
-    ensure_acyclic(preds);
-
     // Get the next available variable ID for use in newly generated phi nodes.
     let mut next_var_id = get_variable_assignments(program)
         .iter()
@@ -24,6 +23,10 @@ pub fn transform_to_ssa(program: &mut Program, preds: &IndexMap<BlockId, Vec<Blo
         .map(|(var_id, _)| var_id.successor())
         .unwrap_or_default();
 
+    // Before any store instructions are removed, find the variables that are updated within each loop so
+    // that phi nodes can be inserted for them in the loop header.
+    let loop_vars = get_loop_stored_variables(program, preds);
+
     // First, remove store instructions and propagate variables through individual blocks.
     // This produces a per-block map of dynamic variables to their values.
     // Orphan variables may be left behind where a variable is defined in one block and used in another, which
@@ -32,20 +35,52 @@ pub fn transform_to_ssa(program: &mut Program, preds: &IndexMap<BlockId, Vec<Blo
 
     // Insert phi nodes where necessary, mapping any remaining orphaned uses to the new variable
     // created by the phi node.
-    // This can be done in one pass because the graph is assumed to be acyclic.
+    // This can be done in one pass over the forward edges. Phi nodes in loop headers have their back edge
+    // arguments filled in afterwards, once the variable maps for the end of each loop are known.
+    let mut loop_phi_nodes = Vec::new();
     for (block_id, block) in program.blocks.iter_mut() {
         let Some(block_preds) = preds.get(block_id) else {
             // The block with no predecessors is the entry block and has no phi nodes.
             continue;
         };
-
         // Use a map to track updates to the variable map for the block. These will be applied after
         // any phi nodes are inserted and will replace any orphaned variables.
         let mut var_map_updates = FxHashMap::default();
 
-        let (first_pred, rest_preds) = block_preds
-            .split_first()
-            .expect("block should have at least one predecessor");
+        // Predecessors that come later in the ordering are back edges, whose variable maps are not yet known.
+        let (forward_preds, back_edge_preds): (Vec<BlockId>, Vec<BlockId>) =
+            block_preds.iter().partition(|pred| **pred < block_id);
+        let Some((first_pred, rest_preds)) = forward_preds.split_first() else {
+            // A block only reachable through back edges is the entry block, which has no phi nodes.
+            continue;
+        };
+
+        // If this block is a loop header, each variable updated within the loop needs a phi node that
+        // merges the value coming into the loop with the value from the end of the previous iteration.
+        if !back_edge_preds.is_empty() {
+            'loop_var: for var_id in loop_vars.get(&block_id).into_iter().flatten() {
+                let mut phi_args = Vec::new();
+                for pred in &forward_preds {
+                    let pred_var_map = block_var_map
+                        .get(*pred)
+                        .expect("block should have variable map");
+                    let Some(operand) = pred_var_map.get(var_id) else {
+                        // The variable is not defined before entering the loop, so it cannot carry a value
+                        // between iterations.
+                        continue 'loop_var;
+                    };
+                    phi_args.push((operand.mapped(pred_var_map), *pred));
+                }
+                let new_var = Variable {
+                    variable_id: next_var_id,
+                    ty: phi_args[0].0.get_type(),
+                };
+                block.0.insert(0, Instruction::Phi(phi_args, new_var));
+                var_map_updates.insert(*var_id, Operand::Variable(new_var));
+                loop_phi_nodes.push((block_id, new_var, *var_id, back_edge_preds.clone()));
+                next_var_id = next_var_id.successor();
+            }
+        }
 
         // The block is only a candidate for phi nodes if it has multiple predecessors.
         if rest_preds.is_empty() {
@@ -55,7 +90,9 @@ pub fn transform_to_ssa(program: &mut Program, preds: &IndexMap<BlockId, Vec<Blo
             let pred_var_map = block_var_map
                 .get(*first_pred)
                 .expect("block should have variable map");
-            pred_var_map.clone_into(&mut var_map_updates);
+            for (var_id, operand) in pred_var_map {
+                var_map_updates.entry(*var_id).or_insert(*operand);
+            }
         } else {
             // Check each variable in the first predecessor's variable map, and if any other
             // predecessor has a different value for the variable, a phi node is needed.
@@ -63,6 +100,10 @@ pub fn transform_to_ssa(program: &mut Program, preds: &IndexMap<BlockId, Vec<Blo
                 .get(*first_pred)
                 .expect("block should have variable map");
             'var_loop: for (var_id, operand) in first_pred_map {
+                if var_map_updates.contains_key(var_id) {
+                    // The variable already has a phi node from the loop header handling above.
+                    continue;
+                }
                 let mut phi_nodes = FxHashMap::default();
 
                 if rest_preds.iter().any(|pred| {
@@ -124,17 +165,74 @@ pub fn transform_to_ssa(program: &mut Program, preds: &IndexMap<BlockId, Vec<Blo
             var_map.entry(var_id).or_insert(operand);
         }
     }
+
+    // Now that every block has a complete variable map, add the values from the end of each loop to the
+    // phi nodes in the corresponding loop header.
+    for (header_block_id, phi_var, var_id, back_edge_preds) in loop_phi_nodes {
+        let mut back_edge_args = Vec::new();
+        for pred in back_edge_preds {
+            let pred_var_map = block_var_map
+                .get(pred)
+                .expect("block should have variable map");
+            let operand = pred_var_map
+                .get(&var_id)
+                .expect("loop variable should be mapped at the end of the loop");
+            back_edge_args.push((operand.mapped(pred_var_map), pred));
+        }
+        let header_block = program
+            .blocks
+            .get_mut(header_block_id)
+            .expect("loop header block should exist");
+        for instr in &mut header_block.0 {
+            if let Instruction::Phi(args, var) = instr {
+                if var.variable_id == phi_var.variable_id {
+                    args.extend(back_edge_args);
+                    break;
+                }
+            }
+        }
+    }
 }
 
-// For now, SSA transform assumes the graph is acyclic, so verify that no block has a predecessor with
-// a block id less than itself, which would indicate a cycle.
-fn ensure_acyclic(preds: &IndexMap<BlockId, Vec<BlockId>>) {
-    for (block_id, block_preds) in preds.iter() {
-        assert!(
-            !block_preds.iter().any(|pred| *pred >= block_id),
-            "block {block_id:?} has a cycle in its predecessors"
-        );
+// Find the variables stored within each loop of the program, keyed by the loop header block.
+// A loop is identified by a back edge from a predecessor with a block id greater than or equal to the header,
+// and the blocks in the loop are those that can reach that predecessor without going through the header.
+fn get_loop_stored_variables(
+    program: &Program,
+    preds: &IndexMap<BlockId, Vec<BlockId>>,
+) -> FxHashMap<BlockId, Vec<VariableId>> {
+    let mut loop_vars = FxHashMap::default();
+    for (header_block_id, block_preds) in preds.iter() {
+        let mut loop_blocks = FxHashSet::default();
+        loop_blocks.insert(header_block_id);
+        let mut blocks_to_visit = block_preds
+            .iter()
+            .filter(|pred| **pred >= header_block_id)
+            .copied()
+            .collect::<Vec<_>>();
+        if blocks_to_visit.is_empty() {
+            // Not a loop header.
+            continue;
+        }
+        while let Some(block_id) = blocks_to_visit.pop() {
+            if loop_blocks.insert(block_id) {
+                blocks_to_visit.extend(preds.get(block_id).into_iter().flatten());
+            }
+        }
+
+        let mut vars = loop_blocks
+            .iter()
+            .flat_map(|block_id| program.get_block(*block_id).0.iter())
+            .filter_map(|instr| match instr {
+                Instruction::Store(_, var) => Some(var.variable_id),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        vars.sort_unstable();
+        vars.dedup();
+        loop_vars.insert(header_block_id, vars);
     }
+    loop_vars
 }
 
 // Remove store instructions and propagate variables through individual blocks.
@@ -201,6 +299,11 @@ fn map_variable_use_in_block(block: &mut Block, var_map: &mut FxHashMap<Variable
             | Instruction::Shl(lhs, rhs, _)
             | Instruction::Ashr(lhs, rhs, _)
             | Instruction::Icmp(_, lhs, rhs, _)
+            | Instruction::Fadd(lhs, rhs, _)
+            | Instruction::Fsub(lhs, rhs, _)
+            | Instruction::Fmul(lhs, rhs, _)
+            | Instruction::Fdiv(lhs, rhs, _)
+            | Instruction::Fcmp(_, lhs, rhs, _)
             | Instruction::LogicalAnd(lhs, rhs, _)
             | Instruction::LogicalOr(lhs, rhs, _)
             | Instruction::BitwiseAnd(lhs, rhs, _)
@@ -211,7 +314,10 @@ fn map_variable_use_in_block(block: &mut Block, var_map: &mut FxHashMap<Variable
             }
 
             // Single variable instructions, replace operand with new value.
-            Instruction::BitwiseNot(operand, _) | Instruction::LogicalNot(operand, _) => {
+            Instruction::BitwiseNot(operand, _)
+            | Instruction::LogicalNot(operand, _)
+            | Instruction::Sitofp(operand, _)
+            | Instruction::Fptosi(operand, _) => {
                 *operand = operand.mapped(var_map);
             }
 
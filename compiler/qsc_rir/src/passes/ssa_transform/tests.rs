@@ -2493,3 +2493,201 @@ fn ssa_transform_maps_store_with_variable_from_store_in_conditional_to_phi_node(
             num_qubits: 0
             num_results: 0"#]].assert_eq(&program.to_string());
 }
+
+#[test]
+fn ssa_transform_inserts_phi_nodes_for_variables_updated_in_loop() {
+    let mut program = new_program();
+    program.callables.insert(
+        CallableId(1),
+        Callable {
+            name: "dynamic_bool".to_string(),
+            input_type: Vec::new(),
+            output_type: Some(Ty::Boolean),
+            body: None,
+            call_type: CallableType::Regular,
+        },
+    );
+
+    program.blocks.insert(
+        BlockId(0),
+        Block(vec![
+            Instruction::Call(
+                CallableId(1),
+                Vec::new(),
+                Some(Variable {
+                    variable_id: VariableId(0),
+                    ty: Ty::Boolean,
+                }),
+            ),
+            Instruction::Store(
+                Operand::Variable(Variable {
+                    variable_id: VariableId(0),
+                    ty: Ty::Boolean,
+                }),
+                Variable {
+                    variable_id: VariableId(1),
+                    ty: Ty::Boolean,
+                },
+            ),
+            Instruction::Store(
+                Operand::Literal(Literal::Integer(0)),
+                Variable {
+                    variable_id: VariableId(2),
+                    ty: Ty::Integer,
+                },
+            ),
+            Instruction::Jump(BlockId(1)),
+        ]),
+    );
+    program.blocks.insert(
+        BlockId(1),
+        Block(vec![Instruction::Branch(
+            Variable {
+                variable_id: VariableId(1),
+                ty: Ty::Boolean,
+            },
+            BlockId(2),
+            BlockId(3),
+        )]),
+    );
+    program.blocks.insert(
+        BlockId(2),
+        Block(vec![
+            Instruction::Add(
+                Operand::Variable(Variable {
+                    variable_id: VariableId(2),
+                    ty: Ty::Integer,
+                }),
+                Operand::Literal(Literal::Integer(1)),
+                Variable {
+                    variable_id: VariableId(3),
+                    ty: Ty::Integer,
+                },
+            ),
+            Instruction::Store(
+                Operand::Variable(Variable {
+                    variable_id: VariableId(3),
+                    ty: Ty::Integer,
+                }),
+                Variable {
+                    variable_id: VariableId(2),
+                    ty: Ty::Integer,
+                },
+            ),
+            Instruction::Call(
+                CallableId(1),
+                Vec::new(),
+                Some(Variable {
+                    variable_id: VariableId(4),
+                    ty: Ty::Boolean,
+                }),
+            ),
+            Instruction::Store(
+                Operand::Variable(Variable {
+                    variable_id: VariableId(4),
+                    ty: Ty::Boolean,
+                }),
+                Variable {
+                    variable_id: VariableId(1),
+                    ty: Ty::Boolean,
+                },
+            ),
+            Instruction::Jump(BlockId(1)),
+        ]),
+    );
+    program.blocks.insert(
+        BlockId(3),
+        Block(vec![
+            Instruction::Add(
+                Operand::Variable(Variable {
+                    variable_id: VariableId(2),
+                    ty: Ty::Integer,
+                }),
+                Operand::Literal(Literal::Integer(1)),
+                Variable {
+                    variable_id: VariableId(5),
+                    ty: Ty::Integer,
+                },
+            ),
+            Instruction::Return,
+        ]),
+    );
+
+    // Before
+    expect![[r#"
+        Program:
+            entry: 0
+            callables:
+                Callable 0: Callable:
+                    name: main
+                    call_type: Regular
+                    input_type: <VOID>
+                    output_type: <VOID>
+                    body: 0
+                Callable 1: Callable:
+                    name: dynamic_bool
+                    call_type: Regular
+                    input_type: <VOID>
+                    output_type: Boolean
+                    body: <NONE>
+            blocks:
+                Block 0: Block:
+                    Variable(0, Boolean) = Call id(1), args( )
+                    Variable(1, Boolean) = Store Variable(0, Boolean)
+                    Variable(2, Integer) = Store Integer(0)
+                    Jump(1)
+                Block 1: Block:
+                    Branch Variable(1, Boolean), 2, 3
+                Block 2: Block:
+                    Variable(3, Integer) = Add Variable(2, Integer), Integer(1)
+                    Variable(2, Integer) = Store Variable(3, Integer)
+                    Variable(4, Boolean) = Call id(1), args( )
+                    Variable(1, Boolean) = Store Variable(4, Boolean)
+                    Jump(1)
+                Block 3: Block:
+                    Variable(5, Integer) = Add Variable(2, Integer), Integer(1)
+                    Return
+            config: Config:
+                capabilities: Base
+            num_qubits: 0
+            num_results: 0"#]]
+    .assert_eq(&program.to_string());
+
+    // After
+    transform_program(&mut program);
+    expect![[r#"
+        Program:
+            entry: 0
+            callables:
+                Callable 0: Callable:
+                    name: main
+                    call_type: Regular
+                    input_type: <VOID>
+                    output_type: <VOID>
+                    body: 0
+                Callable 1: Callable:
+                    name: dynamic_bool
+                    call_type: Regular
+                    input_type: <VOID>
+                    output_type: Boolean
+                    body: <NONE>
+            blocks:
+                Block 0: Block:
+                    Variable(0, Boolean) = Call id(1), args( )
+                    Jump(1)
+                Block 1: Block:
+                    Variable(7, Integer) = Phi ( [Integer(0), 0], [Variable(3, Integer), 2], )
+                    Variable(6, Boolean) = Phi ( [Variable(0, Boolean), 0], [Variable(4, Boolean), 2], )
+                    Branch Variable(6, Boolean), 2, 3
+                Block 2: Block:
+                    Variable(3, Integer) = Add Variable(7, Integer), Integer(1)
+                    Variable(4, Boolean) = Call id(1), args( )
+                    Jump(1)
+                Block 3: Block:
+                    Variable(5, Integer) = Add Variable(7, Integer), Integer(1)
+                    Return
+            config: Config:
+                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset)
+            num_qubits: 0
+            num_results: 0"#]].assert_eq(&program.to_string());
+}
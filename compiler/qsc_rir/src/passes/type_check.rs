@@ -41,6 +41,31 @@ fn check_instr_types(program: &Program, instr: &Instruction) {
             assert_eq!(Ty::Boolean, var.ty);
         }
 
+        Instruction::Fadd(opr1, opr2, var)
+        | Instruction::Fsub(opr1, opr2, var)
+        | Instruction::Fmul(opr1, opr2, var)
+        | Instruction::Fdiv(opr1, opr2, var) => {
+            assert_eq!(opr1.get_type(), Ty::Double);
+            assert_eq!(opr2.get_type(), Ty::Double);
+            assert_eq!(var.ty, Ty::Double);
+        }
+
+        Instruction::Fcmp(_, opr1, opr2, var) => {
+            assert_eq!(opr1.get_type(), Ty::Double);
+            assert_eq!(opr2.get_type(), Ty::Double);
+            assert_eq!(Ty::Boolean, var.ty);
+        }
+
+        Instruction::Sitofp(opr, var) => {
+            assert_eq!(opr.get_type(), Ty::Integer);
+            assert_eq!(var.ty, Ty::Double);
+        }
+
+        Instruction::Fptosi(opr, var) => {
+            assert_eq!(opr.get_type(), Ty::Double);
+            assert_eq!(var.ty, Ty::Integer);
+        }
+
         Instruction::Store(opr, var)
         | Instruction::LogicalNot(opr, var)
         | Instruction::BitwiseNot(opr, var) => {
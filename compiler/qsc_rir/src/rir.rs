@@ -238,6 +238,30 @@ impl Display for ConditionCode {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FcmpConditionCode {
+    Oeq,
+    One,
+    Olt,
+    Ole,
+    Ogt,
+    Oge,
+}
+
+impl Display for FcmpConditionCode {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match &self {
+            Self::Oeq => write!(f, "Oeq")?,
+            Self::One => write!(f, "One")?,
+            Self::Olt => write!(f, "Olt")?,
+            Self::Ole => write!(f, "Ole")?,
+            Self::Ogt => write!(f, "Ogt")?,
+            Self::Oge => write!(f, "Oge")?,
+        };
+        Ok(())
+    }
+}
+
 impl Display for CallableType {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         match &self {
@@ -265,6 +289,13 @@ pub enum Instruction {
     Shl(Operand, Operand, Variable),
     Ashr(Operand, Operand, Variable),
     Icmp(ConditionCode, Operand, Operand, Variable),
+    Fadd(Operand, Operand, Variable),
+    Fsub(Operand, Operand, Variable),
+    Fmul(Operand, Operand, Variable),
+    Fdiv(Operand, Operand, Variable),
+    Fcmp(FcmpConditionCode, Operand, Operand, Variable),
+    Sitofp(Operand, Variable),
+    Fptosi(Operand, Variable),
     LogicalNot(Operand, Variable),
     LogicalAnd(Operand, Operand, Variable),
     LogicalOr(Operand, Operand, Variable),
@@ -343,6 +374,18 @@ impl Display for Instruction {
             Ok(())
         }
 
+        fn write_fcmp_instruction(
+            f: &mut Formatter,
+            condition: FcmpConditionCode,
+            lhs: &Operand,
+            rhs: &Operand,
+            variable: Variable,
+        ) -> fmt::Result {
+            let mut indent = set_indentation(indented(f), 0);
+            write!(indent, "{variable} = Fcmp {condition}, {lhs}, {rhs}")?;
+            Ok(())
+        }
+
         fn write_phi_instruction(
             f: &mut Formatter,
             args: &[(Operand, BlockId)],
@@ -411,6 +454,27 @@ impl Display for Instruction {
             Self::Icmp(op, lhs, rhs, variable) => {
                 write_icmp_instruction(f, *op, lhs, rhs, *variable)?;
             }
+            Self::Fadd(lhs, rhs, variable) => {
+                write_binary_instruction(f, "Fadd", lhs, rhs, *variable)?;
+            }
+            Self::Fsub(lhs, rhs, variable) => {
+                write_binary_instruction(f, "Fsub", lhs, rhs, *variable)?;
+            }
+            Self::Fmul(lhs, rhs, variable) => {
+                write_binary_instruction(f, "Fmul", lhs, rhs, *variable)?;
+            }
+            Self::Fdiv(lhs, rhs, variable) => {
+                write_binary_instruction(f, "Fdiv", lhs, rhs, *variable)?;
+            }
+            Self::Fcmp(op, lhs, rhs, variable) => {
+                write_fcmp_instruction(f, *op, lhs, rhs, *variable)?;
+            }
+            Self::Sitofp(value, variable) => {
+                write_unary_instruction(f, "Sitofp", value, *variable)?;
+            }
+            Self::Fptosi(value, variable) => {
+                write_unary_instruction(f, "Fptosi", value, *variable)?;
+            }
             Self::Phi(args, variable) => {
                 write_phi_instruction(f, args, *variable)?;
             }
@@ -86,6 +86,13 @@ pub fn get_variable_assignments(program: &Program) -> IndexMap<VariableId, (Bloc
                 | Instruction::Shl(_, _, var)
                 | Instruction::Ashr(_, _, var)
                 | Instruction::Icmp(_, _, _, var)
+                | Instruction::Fadd(_, _, var)
+                | Instruction::Fsub(_, _, var)
+                | Instruction::Fmul(_, _, var)
+                | Instruction::Fdiv(_, _, var)
+                | Instruction::Fcmp(_, _, _, var)
+                | Instruction::Sitofp(_, var)
+                | Instruction::Fptosi(_, var)
                 | Instruction::LogicalNot(_, var)
                 | Instruction::LogicalAnd(_, _, var)
                 | Instruction::LogicalOr(_, _, var)
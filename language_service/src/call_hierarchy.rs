@@ -0,0 +1,407 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    compilation::Compilation,
+    name_locator::{Handler, Locator, LocatorContext},
+    protocol::{
+        CallHierarchyIncomingCall, CallHierarchyItem, CallHierarchyOutgoingCall, SymbolKind,
+    },
+    qsc_utils::into_location,
+};
+use qsc::{
+    ast::{
+        self,
+        visit::{walk_callable_decl, Visitor},
+    },
+    display::Lookup,
+    hir::{self, LocalItemId, PackageId},
+    line_column::{Encoding, Position},
+    resolve, CompileUnit, Span,
+};
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::sync::Arc;
+
+/// A callable, identified by the package it's declared in and its item id in that package.
+type CallableId = (PackageId, LocalItemId);
+
+pub(crate) fn prepare_call_hierarchy(
+    compilation: &Compilation,
+    source_name: &str,
+    position: Position,
+    position_encoding: Encoding,
+) -> Vec<CallHierarchyItem> {
+    let offset =
+        compilation.source_position_to_package_offset(source_name, position, position_encoding);
+    let mut finder = CallableFinder {
+        compilation,
+        callable: None,
+    };
+    let mut locator = Locator::new(&mut finder, offset, compilation);
+    locator.visit_package(&compilation.user_unit().ast.package);
+
+    finder
+        .callable
+        .map(|id| vec![call_hierarchy_item(compilation, id, position_encoding)])
+        .unwrap_or_default()
+}
+
+/// Finds the calls to the callable in all the given compilations, including the calls from
+/// dependencies and the standard library. The calls are grouped by the callable that makes them.
+pub(crate) fn get_incoming_calls<'a>(
+    compilations: impl Iterator<Item = &'a Compilation>,
+    item: &CallHierarchyItem,
+    position_encoding: Encoding,
+) -> Vec<CallHierarchyIncomingCall> {
+    let mut incoming_calls: Vec<CallHierarchyIncomingCall> = Vec::new();
+    // The index in `incoming_calls` of each caller, by the location of its name.
+    let mut caller_indices: FxHashMap<(Arc<str>, Position), usize> = FxHashMap::default();
+    // The same package can be part of several compilations, e.g. the standard library,
+    // so packages are identified by their first source and only searched once.
+    let mut searched_packages: FxHashSet<Arc<str>> = FxHashSet::default();
+
+    for compilation in compilations {
+        let Some(target) = find_callable(compilation, item, position_encoding) else {
+            continue;
+        };
+
+        // A package is added to the store after its dependencies, so only the package
+        // that declares the callable and the packages added after it can call it.
+        for (package_id, unit) in &compilation.package_store {
+            if package_id < target.0 {
+                continue;
+            }
+            if let Some(source) = unit.sources.iter().next() {
+                if !searched_packages.insert(source.name.clone()) {
+                    continue;
+                }
+            }
+
+            let mut callers: FxHashMap<CallableId, Vec<Span>> = FxHashMap::default();
+            for call in collect_calls(compilation, package_id, unit) {
+                if call.callee == target {
+                    callers.entry(call.caller).or_default().push(call.span);
+                }
+            }
+
+            for (caller, spans) in callers {
+                let from = call_hierarchy_item(compilation, caller, position_encoding);
+                let from_ranges = spans.into_iter().map(|span| {
+                    into_location(position_encoding, compilation, span, package_id).range
+                });
+                let key = (from.location.source.clone(), from.selection_range.start);
+                if let Some(&index) = caller_indices.get(&key) {
+                    incoming_calls[index].from_ranges.extend(from_ranges);
+                } else {
+                    caller_indices.insert(key, incoming_calls.len());
+                    incoming_calls.push(CallHierarchyIncomingCall {
+                        from,
+                        from_ranges: from_ranges.collect(),
+                    });
+                }
+            }
+        }
+    }
+
+    incoming_calls.sort_by(|a, b| location_key(&a.from).cmp(&location_key(&b.from)));
+    for call in &mut incoming_calls {
+        call.from_ranges
+            .sort_by_key(|range| (range.start.line, range.start.column));
+        call.from_ranges.dedup();
+    }
+    incoming_calls
+}
+
+/// Finds the calls that the callable makes, grouped by the callable that they call.
+pub(crate) fn get_outgoing_calls<'a>(
+    mut compilations: impl Iterator<Item = &'a Compilation>,
+    item: &CallHierarchyItem,
+    position_encoding: Encoding,
+) -> Vec<CallHierarchyOutgoingCall> {
+    // The calls that a callable makes are the same in every compilation
+    // that it's part of, so the first one is enough.
+    let Some((compilation, caller)) = compilations.find_map(|compilation| {
+        find_callable(compilation, item, position_encoding).map(|id| (compilation, id))
+    }) else {
+        return Vec::new();
+    };
+    let (package_id, _) = caller;
+    let unit = compilation
+        .package_store
+        .get(package_id)
+        .expect("package should exist in store");
+
+    let mut outgoing_calls: Vec<(CallableId, Vec<Span>)> = Vec::new();
+    for call in collect_calls(compilation, package_id, unit) {
+        if call.caller != caller {
+            continue;
+        }
+        match outgoing_calls
+            .iter_mut()
+            .find(|(callee, _)| *callee == call.callee)
+        {
+            Some((_, spans)) => spans.push(call.span),
+            None => outgoing_calls.push((call.callee, vec![call.span])),
+        }
+    }
+
+    outgoing_calls
+        .into_iter()
+        .map(|(callee, spans)| CallHierarchyOutgoingCall {
+            to: call_hierarchy_item(compilation, callee, position_encoding),
+            from_ranges: spans
+                .into_iter()
+                .map(|span| into_location(position_encoding, compilation, span, package_id).range)
+                .collect(),
+        })
+        .collect()
+}
+
+fn location_key(item: &CallHierarchyItem) -> (&str, u32, u32) {
+    (
+        &item.location.source,
+        item.selection_range.start.line,
+        item.selection_range.start.column,
+    )
+}
+
+fn call_hierarchy_item(
+    compilation: &Compilation,
+    (package_id, item_id): CallableId,
+    position_encoding: Encoding,
+) -> CallHierarchyItem {
+    let package = &compilation
+        .package_store
+        .get(package_id)
+        .expect("package should exist in store")
+        .package;
+    let item = package.items.get(item_id).expect("item should exist");
+    let hir::ItemKind::Callable(decl) = &item.kind else {
+        panic!("item should be a callable");
+    };
+
+    // Callables can be declared inside other callables, so the namespace
+    // is the closest ancestor that is a namespace.
+    let mut parent = item.parent;
+    let mut namespace = String::new();
+    while let Some(parent_item) = parent.and_then(|id| package.items.get(id)) {
+        if let hir::ItemKind::Namespace(name, _) = &parent_item.kind {
+            namespace = name.name().to_string();
+            break;
+        }
+        parent = parent_item.parent;
+    }
+
+    CallHierarchyItem {
+        name: decl.name.name.to_string(),
+        kind: match decl.kind {
+            hir::CallableKind::Function => SymbolKind::Function,
+            hir::CallableKind::Operation => SymbolKind::Operation,
+        },
+        detail: namespace,
+        location: into_location(position_encoding, compilation, item.span, package_id),
+        selection_range: into_location(position_encoding, compilation, decl.name.span, package_id)
+            .range,
+    }
+}
+
+/// Finds the callable that a call hierarchy item refers to, using the name of the callable.
+/// The item can be in any of the packages of the compilation.
+fn find_callable(
+    compilation: &Compilation,
+    item: &CallHierarchyItem,
+    position_encoding: Encoding,
+) -> Option<CallableId> {
+    compilation
+        .package_store
+        .iter()
+        .find_map(|(package_id, unit)| {
+            let source = unit.sources.find_by_name(&item.location.source)?;
+            let offset = source.offset
+                + item
+                    .selection_range
+                    .start
+                    .to_utf8_byte_offset(position_encoding, &source.contents);
+            unit.package
+                .items
+                .iter()
+                .find_map(|(item_id, item)| match &item.kind {
+                    hir::ItemKind::Callable(decl) if decl.name.span.touches(offset) => {
+                        Some((package_id, item_id))
+                    }
+                    _ => None,
+                })
+        })
+}
+
+/// Follows exports from the item that `item_id` refers to, relative to `package_id`,
+/// to the callable that it declares. Returns `None` if the item isn't a callable.
+fn resolve_callable(
+    compilation: &Compilation,
+    mut package_id: PackageId,
+    item_id: &hir::ItemId,
+) -> Option<CallableId> {
+    let mut item_id = *item_id;
+    loop {
+        package_id = item_id.package.unwrap_or(package_id);
+        let item = compilation
+            .package_store
+            .get(package_id)?
+            .package
+            .items
+            .get(item_id.item)?;
+        match &item.kind {
+            hir::ItemKind::Callable(_) => return Some((package_id, item_id.item)),
+            hir::ItemKind::Export(_, target) => item_id = *target,
+            hir::ItemKind::Namespace(..) | hir::ItemKind::Ty(..) => return None,
+        }
+    }
+}
+
+/// A reference to a callable from the body of another callable. References that aren't
+/// direct calls, such as functor applications, partial applications and callables passed
+/// as arguments, are included since the callable can be invoked through them.
+struct Call {
+    caller: CallableId,
+    callee: CallableId,
+    /// The span of the callee's name, in the package of the caller.
+    span: Span,
+}
+
+fn collect_calls(
+    compilation: &Compilation,
+    package_id: PackageId,
+    unit: &CompileUnit,
+) -> Vec<Call> {
+    let mut collector = CallCollector {
+        compilation,
+        package_id,
+        unit,
+        caller: None,
+        calls: Vec::new(),
+    };
+    collector.visit_package(&unit.ast.package);
+    collector.calls
+}
+
+struct CallCollector<'a> {
+    compilation: &'a Compilation,
+    package_id: PackageId,
+    unit: &'a CompileUnit,
+    /// The innermost callable being visited.
+    caller: Option<CallableId>,
+    calls: Vec<Call>,
+}
+
+impl CallCollector<'_> {
+    fn resolve(&self, id: ast::NodeId) -> Option<CallableId> {
+        match self.unit.ast.names.get(id)? {
+            resolve::Res::Item(item_id, _) | resolve::Res::ExportedItem(item_id, _) => {
+                resolve_callable(self.compilation, self.package_id, item_id)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for CallCollector<'_> {
+    fn visit_callable_decl(&mut self, decl: &'a ast::CallableDecl) {
+        let outer = self.caller;
+        self.caller = self.resolve(decl.name.id);
+        walk_callable_decl(self, decl);
+        self.caller = outer;
+    }
+
+    fn visit_path(&mut self, path: &'a ast::Path) {
+        if let Some(caller) = self.caller {
+            if let Some(callee) = self.resolve(path.id) {
+                self.calls.push(Call {
+                    caller,
+                    callee,
+                    span: path.name.span,
+                });
+            }
+        }
+    }
+}
+
+/// Finds the callable at the cursor, at either its declaration or a reference to it.
+struct CallableFinder<'a> {
+    compilation: &'a Compilation,
+    callable: Option<CallableId>,
+}
+
+impl CallableFinder<'_> {
+    fn resolve(&self, id: ast::NodeId) -> Option<CallableId> {
+        match self.compilation.get_res(id)? {
+            resolve::Res::Item(item_id, _) | resolve::Res::ExportedItem(item_id, _) => {
+                resolve_callable(self.compilation, self.compilation.user_package_id, item_id)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<'a> Handler<'a> for CallableFinder<'a> {
+    fn at_callable_def(
+        &mut self,
+        _: &LocatorContext<'a>,
+        name: &'a ast::Ident,
+        _: &'a ast::CallableDecl,
+    ) {
+        self.callable = self.resolve(name.id);
+    }
+
+    fn at_callable_ref(&mut self, path: &'a ast::Path, _: &hir::ItemId, _: &'a hir::CallableDecl) {
+        self.callable = self.resolve(path.id);
+    }
+
+    fn at_type_param_def(
+        &mut self,
+        _: &LocatorContext<'a>,
+        _: &'a ast::Ident,
+        _: hir::ty::ParamId,
+    ) {
+    }
+
+    fn at_type_param_ref(
+        &mut self,
+        _: &LocatorContext<'a>,
+        _: &'a ast::Ident,
+        _: hir::ty::ParamId,
+        _: &'a ast::Ident,
+    ) {
+    }
+
+    fn at_new_type_def(&mut self, _: &LocatorContext<'a>, _: &'a ast::Ident, _: &'a ast::TyDef) {}
+
+    fn at_struct_def(&mut self, _: &LocatorContext<'a>, _: &'a ast::Ident, _: &'a ast::StructDecl) {
+    }
+
+    fn at_new_type_ref(
+        &mut self,
+        _: &'a ast::Path,
+        _: &hir::ItemId,
+        _: &'a hir::Ident,
+        _: &'a hir::ty::Udt,
+    ) {
+    }
+
+    fn at_field_def(&mut self, _: &LocatorContext<'a>, _: &ast::Ident, _: &'a ast::Ty) {}
+
+    fn at_field_ref(&mut self, _: &ast::Ident, _: &hir::ItemId, _: &'a hir::ty::UdtField) {}
+
+    fn at_local_def(&mut self, _: &LocatorContext<'a>, _: &'a ast::Ident, _: &'a ast::Pat) {}
+
+    fn at_local_ref(
+        &mut self,
+        _: &LocatorContext<'a>,
+        _: &ast::Ident,
+        _: ast::NodeId,
+        _: &'a ast::Ident,
+    ) {
+    }
+}
@@ -0,0 +1,255 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{get_incoming_calls, get_outgoing_calls, prepare_call_hierarchy};
+use crate::{
+    compilation::Compilation,
+    protocol::{CallHierarchyIncomingCall, CallHierarchyItem, CallHierarchyOutgoingCall},
+    test_utils::compile_with_markers,
+    Encoding,
+};
+use expect_test::{expect, Expect};
+use indoc::indoc;
+use qsc::line_column::Range;
+use std::fmt::Write;
+
+fn format_item(item: &CallHierarchyItem) -> String {
+    format!(
+        "{:?} {} in {} ({})",
+        item.kind, item.name, item.detail, item.location.source
+    )
+}
+
+fn format_ranges(ranges: &[Range]) -> String {
+    ranges
+        .iter()
+        .map(|range| format!("{}:{}", range.start.line, range.start.column))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Prepares the call hierarchy at the cursor, which is indicated by a `↘` marker.
+fn prepare(source_with_markers: &str) -> (Compilation, CallHierarchyItem) {
+    let (compilation, cursor_position, _) = compile_with_markers(source_with_markers, true);
+    let mut items =
+        prepare_call_hierarchy(&compilation, "<source>", cursor_position, Encoding::Utf8);
+    assert_eq!(items.len(), 1, "expected one item, got {items:?}");
+    let item = items.remove(0);
+    (compilation, item)
+}
+
+fn format_incoming(calls: &[CallHierarchyIncomingCall]) -> String {
+    calls.iter().fold(String::new(), |mut output, call| {
+        let _ = writeln!(
+            output,
+            "{} from [{}]",
+            format_item(&call.from),
+            format_ranges(&call.from_ranges)
+        );
+        output
+    })
+}
+
+fn check_incoming(source_with_markers: &str, expect: &Expect) {
+    let (compilation, item) = prepare(source_with_markers);
+    let calls = get_incoming_calls(std::iter::once(&compilation), &item, Encoding::Utf8);
+    expect.assert_eq(&format_incoming(&calls));
+}
+
+fn format_outgoing(calls: &[CallHierarchyOutgoingCall]) -> String {
+    calls.iter().fold(String::new(), |mut output, call| {
+        let _ = writeln!(
+            output,
+            "{} from [{}]",
+            format_item(&call.to),
+            format_ranges(&call.from_ranges)
+        );
+        output
+    })
+}
+
+fn check_outgoing(source_with_markers: &str, expect: &Expect) {
+    let (compilation, item) = prepare(source_with_markers);
+    let calls = get_outgoing_calls(std::iter::once(&compilation), &item, Encoding::Utf8);
+    expect.assert_eq(&format_outgoing(&calls));
+}
+
+#[test]
+fn prepare_at_declaration() {
+    let (_, item) = prepare(indoc! {r#"
+        namespace Test {
+            operation ↘Target() : Unit {}
+        }
+    "#});
+    expect![[r#"
+        Operation Target in Test (<source>) at 1:14
+    "#]]
+    .assert_eq(&format!(
+        "{} at {}\n",
+        format_item(&item),
+        format_ranges(&[item.selection_range])
+    ));
+}
+
+#[test]
+fn prepare_at_reference_to_library_callable() {
+    let (_, item) = prepare(indoc! {r#"
+        namespace Test {
+            operation Main() : Unit {
+                FakeStdLib.↘FakeWithParam(1);
+            }
+        }
+    "#});
+    expect![[r#"
+        Operation FakeWithParam in FakeStdLib (qsharp-library-source:<std>)
+    "#]]
+    .assert_eq(&format!("{}\n", format_item(&item)));
+}
+
+#[test]
+fn prepare_at_local_is_empty() {
+    let (compilation, cursor_position, _) = compile_with_markers(
+        indoc! {r#"
+            namespace Test {
+                function Main() : Unit {
+                    let ↘x = 1;
+                }
+            }
+        "#},
+        true,
+    );
+    let items = prepare_call_hierarchy(&compilation, "<source>", cursor_position, Encoding::Utf8);
+    assert!(items.is_empty(), "expected no items, got {items:?}");
+}
+
+#[test]
+fn incoming_calls_through_functors_and_partial_application() {
+    check_incoming(
+        indoc! {r#"
+            namespace Test {
+                operation ↘Target(q : Qubit) : Unit is Adj + Ctl {}
+                operation UsesFunctors(q : Qubit) : Unit {
+                    Adjoint Target(q);
+                    Controlled Target([q], q);
+                }
+                operation UsesPartial(qs : Qubit[]) : Unit {
+                    let f = Target(_);
+                    f(qs[0]);
+                }
+                operation Unrelated() : Unit {}
+            }
+        "#},
+        &expect![[r#"
+            Operation UsesFunctors in Test (<source>) from [3:16, 4:19]
+            Operation UsesPartial in Test (<source>) from [7:16]
+        "#]],
+    );
+}
+
+#[test]
+fn incoming_calls_include_library_callers() {
+    check_incoming(
+        indoc! {r#"
+            namespace Test {
+                operation Main() : Unit {
+                    FakeStdLib.↘Fake();
+                }
+            }
+        "#},
+        &expect![[r#"
+            Operation Main in Test (<source>) from [2:19]
+            Operation RefFake in FakeStdLib (qsharp-library-source:<std>) from [13:12]
+        "#]],
+    );
+}
+
+#[test]
+fn incoming_calls_from_packages_shared_by_compilations_are_merged() {
+    let (compilation, item) = prepare(indoc! {r#"
+        namespace Test {
+            operation Main() : Unit {
+                FakeStdLib.↘Fake();
+            }
+        }
+    "#});
+    let calls = get_incoming_calls(
+        [&compilation, &compilation].into_iter(),
+        &item,
+        Encoding::Utf8,
+    );
+    expect![[r#"
+        Operation Main in Test (<source>) from [2:19]
+        Operation RefFake in FakeStdLib (qsharp-library-source:<std>) from [13:12]
+    "#]]
+    .assert_eq(&format_incoming(&calls));
+}
+
+#[test]
+fn incoming_calls_to_user_callable_exclude_library_callers_of_same_name() {
+    check_incoming(
+        indoc! {r#"
+            namespace Test {
+                operation ↘Fake() : Unit {}
+                operation Main() : Unit {
+                    Fake();
+                }
+            }
+        "#},
+        &expect![[r#"
+            Operation Main in Test (<source>) from [3:8]
+        "#]],
+    );
+}
+
+#[test]
+fn outgoing_calls_are_grouped_by_callee() {
+    check_outgoing(
+        indoc! {r#"
+            namespace Test {
+                operation Target() : Unit {}
+                operation ↘Main() : Unit {
+                    Target();
+                    FakeStdLib.Fake();
+                    Target();
+                }
+            }
+        "#},
+        &expect![[r#"
+            Operation Target in Test (<source>) from [3:8, 5:8]
+            Operation Fake in FakeStdLib (qsharp-library-source:<std>) from [4:19]
+        "#]],
+    );
+}
+
+#[test]
+fn outgoing_calls_of_library_callable() {
+    check_outgoing(
+        indoc! {r#"
+            namespace Test {
+                operation Main() : Unit {
+                    FakeStdLib.↘RefFake();
+                }
+            }
+        "#},
+        &expect![[r#"
+            Operation Fake in FakeStdLib (qsharp-library-source:<std>) from [13:12]
+        "#]],
+    );
+}
+
+#[test]
+fn calls_from_lambdas_belong_to_the_enclosing_callable() {
+    check_outgoing(
+        indoc! {r#"
+            namespace Test {
+                function Double(x : Int) : Int { 2 * x }
+                function ↘Main() : Unit {
+                    let f = x -> Double(x);
+                }
+            }
+        "#},
+        &expect![[r#"
+            Function Double in Test (<source>) from [3:21]
+        "#]],
+    );
+}
@@ -1,12 +1,21 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
+mod extract_callable;
+mod inline_callable;
+mod introduce_local;
+mod newtype_to_struct;
+#[cfg(test)]
+mod tests;
+
 use miette::Diagnostic;
 use qsc::{
+    ast,
     compile::ErrorKind,
+    display::Lookup,
     error::WithSource,
     line_column::{Encoding, Range},
-    Span,
+    resolve, Span,
 };
 
 use crate::{
@@ -22,7 +31,32 @@ pub(crate) fn get_code_actions(
 ) -> Vec<CodeAction> {
     // Compute quick_fixes and other code_actions, and then merge them together
     let span = compilation.source_range_to_package_span(source_name, range, position_encoding);
-    quick_fixes(compilation, source_name, span, position_encoding)
+    let mut code_actions = quick_fixes(compilation, source_name, span, position_encoding);
+    code_actions.extend(refactorings(
+        compilation,
+        source_name,
+        span,
+        position_encoding,
+    ));
+    code_actions
+}
+
+fn refactorings(
+    compilation: &Compilation,
+    source_name: &str,
+    span: Span,
+    encoding: Encoding,
+) -> Vec<CodeAction> {
+    let selection = Selection::new(compilation, source_name, span, encoding);
+    [
+        introduce_local::introduce_local(&selection),
+        extract_callable::extract_callable(&selection),
+        inline_callable::inline_callable(&selection),
+        newtype_to_struct::newtype_to_struct(&selection),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
 }
 
 fn quick_fixes(
@@ -95,3 +129,169 @@ fn resolve_span(e: &WithSource<ErrorKind>) -> Option<Span> {
         })
         .next()
 }
+
+/// The document that a refactoring applies to, and the selection in it.
+struct Selection<'a> {
+    compilation: &'a Compilation,
+    contents: &'a str,
+    /// The selected span, in package offsets, without surrounding whitespace.
+    span: Span,
+    encoding: Encoding,
+}
+
+impl<'a> Selection<'a> {
+    fn new(
+        compilation: &'a Compilation,
+        source_name: &str,
+        span: Span,
+        encoding: Encoding,
+    ) -> Self {
+        let source = compilation
+            .user_unit()
+            .sources
+            .find_by_name(source_name)
+            .expect("source should exist");
+        let mut selection = Self {
+            compilation,
+            contents: &source.contents,
+            span,
+            encoding,
+        };
+
+        let text = selection.text(span);
+        let trimmed = text.trim();
+        if !trimmed.is_empty() {
+            let lo = span.lo + to_u32(text.len() - text.trim_start().len());
+            selection.span = Span {
+                lo,
+                hi: lo + to_u32(trimmed.len()),
+            };
+        }
+        selection
+    }
+
+    fn is_empty(&self) -> bool {
+        self.span.lo == self.span.hi
+    }
+
+    /// Gets the source that contains the offset, which can be any source of the user package.
+    fn source(&self, offset: u32) -> (&'a str, &'a str, u32) {
+        let source = self
+            .compilation
+            .user_unit()
+            .sources
+            .find_by_offset(offset)
+            .expect("source should exist for offset");
+        (&*source.name, &*source.contents, source.offset)
+    }
+
+    /// Gets the text at the span, which is in package offsets.
+    fn text(&self, span: Span) -> &'a str {
+        let (_, contents, offset) = self.source(span.lo);
+        &contents[(span.lo - offset) as usize..(span.hi - offset) as usize]
+    }
+
+    /// Gets the whitespace at the start of the line that contains the offset.
+    fn indentation(&self, offset: u32) -> &'a str {
+        let (_, contents, source_offset) = self.source(offset);
+        let before = &contents[..(offset - source_offset) as usize];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let line = &contents[line_start..];
+        let len = line
+            .find(|c: char| c != ' ' && c != '\t')
+            .unwrap_or(line.len());
+        &line[..len]
+    }
+
+    /// Creates a code action from edits that replace the text at spans in package offsets.
+    /// The spans can be in any source of the user package.
+    fn code_action(
+        &self,
+        title: String,
+        kind: CodeActionKind,
+        edits: Vec<(Span, String)>,
+    ) -> CodeAction {
+        let mut changes: Vec<(String, Vec<TextEdit>)> = Vec::new();
+        for (span, new_text) in edits {
+            let (name, contents, offset) = self.source(span.lo);
+            let edit = TextEdit {
+                new_text,
+                range: Range::from_span(self.encoding, contents, &(span - offset)),
+            };
+            match changes
+                .iter_mut()
+                .find(|(source, _)| source.as_str() == name)
+            {
+                Some((_, edits)) => edits.push(edit),
+                None => changes.push((name.to_string(), vec![edit])),
+            }
+        }
+        CodeAction {
+            title,
+            edit: Some(WorkspaceEdit { changes }),
+            kind: Some(kind),
+            is_preferred: None,
+        }
+    }
+}
+
+fn to_u32(len: usize) -> u32 {
+    u32::try_from(len).expect("length should fit into u32")
+}
+
+fn encloses(outer: Span, inner: Span) -> bool {
+    outer.lo <= inner.lo && inner.hi <= outer.hi
+}
+
+/// Returns the base name, followed by a number if needed, so that it's not already used
+/// as a word in the text.
+fn unique_name(base: &str, text: &str) -> String {
+    let is_used = |name: &str| {
+        text.split(|c: char| !c.is_alphanumeric() && c != '_')
+            .any(|word| word == name)
+    };
+    if !is_used(base) {
+        return base.to_string();
+    }
+    let mut i = 2;
+    loop {
+        let name = format!("{base}{i}");
+        if !is_used(&name) {
+            return name;
+        }
+        i += 1;
+    }
+}
+
+/// If the path refers to a local variable, possibly as the start of a field access,
+/// returns the node id of the local's declaration and the identifier that refers to it.
+fn local_ref<'a>(
+    compilation: &Compilation,
+    path: &'a ast::Path,
+) -> Option<(ast::NodeId, &'a ast::Ident)> {
+    if let Some(&resolve::Res::Local(node_id)) = compilation.get_res(path.id) {
+        return Some((node_id, &path.name));
+    }
+    resolve::path_as_field_accessor(&compilation.user_unit().ast.names, path)
+        .map(|(node_id, parts)| (node_id, parts[0]))
+}
+
+/// Moves the lines of the text after the first from one indentation to another.
+fn reindent(text: &str, from: &str, to: &str) -> String {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| {
+            if i == 0 {
+                line.to_string()
+            } else if line.trim().is_empty() {
+                String::new()
+            } else {
+                format!(
+                    "{to}{}",
+                    line.strip_prefix(from).unwrap_or(line.trim_start())
+                )
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
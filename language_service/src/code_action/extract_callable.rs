@@ -0,0 +1,367 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{encloses, local_ref, reindent, unique_name, Selection};
+use crate::{
+    compilation::Compilation,
+    protocol::{CodeAction, CodeActionKind},
+};
+use qsc::{
+    ast::{
+        self,
+        visit::{walk_block, walk_expr, walk_item, walk_pat, walk_stmt, Visitor},
+    },
+    display::Lookup,
+    hir::{
+        self,
+        ty::{FunctorSet, Ty},
+    },
+    Span,
+};
+use rustc_hash::FxHashSet;
+
+/// Moves the selected statements into a new callable, declared after the enclosing one,
+/// and replaces them with a call to it. The locals that the statements use become the
+/// parameters, and the locals that they declare and that are used afterwards are returned.
+#[allow(clippy::too_many_lines)]
+pub(super) fn extract_callable(selection: &Selection) -> Option<CodeAction> {
+    if selection.is_empty() {
+        return None;
+    }
+    let compilation = selection.compilation;
+    let mut finder = StmtsFinder {
+        span: selection.span,
+        callable: None,
+        found: None,
+    };
+    finder.visit_package(&compilation.user_unit().ast.package);
+    let (item, decl, stmts) = finder.found?;
+
+    let mut analyzer = Analyzer {
+        compilation,
+        in_lambda: false,
+        declared: Vec::new(),
+        mutable: FxHashSet::default(),
+        qubits: FxHashSet::default(),
+        used: Vec::new(),
+        assigned: FxHashSet::default(),
+        needs_operation: false,
+        is_supported: true,
+    };
+    for stmt in stmts {
+        analyzer.visit_stmt(stmt);
+    }
+    if !analyzer.is_supported {
+        return None;
+    }
+
+    // Locals that are declared before the statements are passed in as parameters.
+    // They can't be updated, since the updates wouldn't be visible to the caller.
+    let mut params = Vec::new();
+    for (id, name) in &analyzer.used {
+        if !analyzer.declared.iter().any(|(declared, _)| declared == id) {
+            if analyzer.assigned.contains(id) {
+                return None;
+            }
+            params.push((name, concrete_ty(compilation, *id)?));
+        }
+    }
+
+    // Locals that are declared in the statements and used after them are returned.
+    let mut used_after = UsedAfter {
+        compilation,
+        after: selection.span.hi,
+        declared: &analyzer.declared,
+        used: FxHashSet::default(),
+    };
+    used_after.visit_callable_decl(decl);
+    let outputs = analyzer
+        .declared
+        .iter()
+        .filter(|(id, _)| used_after.used.contains(id))
+        .collect::<Vec<_>>();
+    if outputs.iter().any(|(id, _)| analyzer.qubits.contains(id)) {
+        // The qubits would be released at the end of the new callable.
+        return None;
+    }
+    let mut output_tys = Vec::new();
+    for (id, _) in &outputs {
+        output_tys.push(concrete_ty(compilation, *id)?);
+    }
+
+    // A final expression statement without a semicolon is the value of the statements.
+    let value = match &*stmts.last().expect("statements should not be empty").kind {
+        ast::StmtKind::Expr(expr) if compilation.get_ty(expr.id) != Some(&Ty::UNIT) => {
+            Some(concrete_ty(compilation, expr.id)?)
+        }
+        _ => None,
+    };
+    let output = match (&value, output_tys.len()) {
+        (Some(_), 1..) => return None,
+        (Some(ty), 0) => ty.clone(),
+        (None, 0) => "Unit".to_string(),
+        (None, 1) => output_tys[0].clone(),
+        (None, _) => format!("({})", output_tys.join(", ")),
+    };
+
+    let is_operation = decl.kind == ast::CallableKind::Operation && analyzer.needs_operation;
+    let (kind, base_name) = if is_operation {
+        ("operation", "NewOperation")
+    } else {
+        ("function", "NewFunction")
+    };
+    let name = unique_name(base_name, selection.contents);
+    let functors = match (&decl.functors, is_operation) {
+        (Some(functors), true) => format!(" is {}", selection.text(functors.span)),
+        _ => String::new(),
+    };
+    let params_text = params
+        .iter()
+        .map(|(name, ty)| format!("{name} : {ty}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let item_indent = selection.indentation(item.span.lo);
+    let body_indent = format!("{item_indent}    ");
+    let stmts_indent = selection.indentation(selection.span.lo);
+    let mut body = format!(
+        "{body_indent}{}\n",
+        reindent(selection.text(selection.span), stmts_indent, &body_indent)
+    );
+    let output_names = outputs
+        .iter()
+        .map(|(_, name)| name.as_str())
+        .collect::<Vec<_>>();
+    let output_pat = match output_names.as_slice() {
+        [] => None,
+        [name] => Some((*name).to_string()),
+        names => Some(format!("({})", names.join(", "))),
+    };
+    if let Some(output_pat) = &output_pat {
+        body.push_str(&format!("{body_indent}{output_pat}\n"));
+    }
+    let new_callable = format!(
+        "\n\n{item_indent}{kind} {name}({params_text}) : {output}{functors} {{\n{body}{item_indent}}}"
+    );
+
+    let args = params
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let call = format!("{name}({args})");
+    let call = match output_pat {
+        Some(output_pat) => {
+            let keyword = if outputs.iter().any(|(id, _)| analyzer.mutable.contains(id)) {
+                "mutable"
+            } else {
+                "let"
+            };
+            format!("{keyword} {output_pat} = {call};")
+        }
+        None if value.is_some() => call,
+        None => format!("{call};"),
+    };
+
+    let edits = vec![
+        (selection.span, call),
+        (
+            Span {
+                lo: item.span.hi,
+                hi: item.span.hi,
+            },
+            new_callable,
+        ),
+    ];
+    Some(selection.code_action(
+        format!("Extract into new {kind}"),
+        CodeActionKind::RefactorExtract,
+        edits,
+    ))
+}
+
+/// Gets the type of the node as it's written in a declaration, if it's fully known
+/// and doesn't depend on the type parameters of the enclosing callable.
+fn concrete_ty(compilation: &Compilation, id: ast::NodeId) -> Option<String> {
+    fn is_concrete(ty: &Ty) -> bool {
+        match ty {
+            Ty::Array(item) => is_concrete(item),
+            Ty::Arrow(arrow) => {
+                matches!(arrow.functors, FunctorSet::Value(_))
+                    && is_concrete(&arrow.input)
+                    && is_concrete(&arrow.output)
+            }
+            Ty::Tuple(items) => items.iter().all(is_concrete),
+            Ty::Prim(_) | Ty::Udt(..) => true,
+            Ty::Infer(_) | Ty::Param(..) | Ty::Err => false,
+        }
+    }
+
+    let ty = compilation.get_ty(id)?;
+    is_concrete(ty).then(|| ty.display())
+}
+
+/// Finds the consecutive statements of a block in a callable body that the selection covers.
+struct StmtsFinder<'a> {
+    span: Span,
+    /// The innermost callable being visited, and the item that declares it.
+    callable: Option<(&'a ast::Item, &'a ast::CallableDecl)>,
+    found: Option<(&'a ast::Item, &'a ast::CallableDecl, &'a [Box<ast::Stmt>])>,
+}
+
+impl<'a> Visitor<'a> for StmtsFinder<'a> {
+    fn visit_item(&mut self, item: &'a ast::Item) {
+        if self.found.is_some() || !encloses(item.span, self.span) {
+            return;
+        }
+        let outer = self.callable;
+        if let ast::ItemKind::Callable(decl) = &*item.kind {
+            self.callable = Some((item, decl));
+        }
+        walk_item(self, item);
+        self.callable = outer;
+    }
+
+    fn visit_block(&mut self, block: &'a ast::Block) {
+        if self.found.is_some() || !encloses(block.span, self.span) {
+            return;
+        }
+        let first = block
+            .stmts
+            .iter()
+            .position(|stmt| stmt.span.lo == self.span.lo);
+        let last = block
+            .stmts
+            .iter()
+            .position(|stmt| stmt.span.hi == self.span.hi);
+        if let (Some((item, decl)), Some(first), Some(last)) = (self.callable, first, last) {
+            if first <= last {
+                self.found = Some((item, decl, &block.stmts[first..=last]));
+                return;
+            }
+        }
+        walk_block(self, block);
+    }
+}
+
+/// Collects the locals that the statements declare and use, and whether they can be moved.
+struct Analyzer<'a> {
+    compilation: &'a Compilation,
+    in_lambda: bool,
+    /// The locals declared in the statements, in order.
+    declared: Vec<(ast::NodeId, String)>,
+    mutable: FxHashSet<ast::NodeId>,
+    qubits: FxHashSet<ast::NodeId>,
+    /// The locals used in the statements, in order of their first use.
+    used: Vec<(ast::NodeId, String)>,
+    assigned: FxHashSet<ast::NodeId>,
+    /// Whether the statements call operations or allocate qubits.
+    needs_operation: bool,
+    /// Whether the statements can be moved into a callable.
+    /// Statements that return early or declare items can't be.
+    is_supported: bool,
+}
+
+impl Analyzer<'_> {
+    fn assign(&mut self, lhs: &ast::Expr) {
+        match &*lhs.kind {
+            ast::ExprKind::Paren(item) => self.assign(item),
+            ast::ExprKind::Tuple(items) => items.iter().for_each(|item| self.assign(item)),
+            ast::ExprKind::Path(ast::PathKind::Ok(path)) => {
+                if let Some((id, _)) = local_ref(self.compilation, path) {
+                    self.assigned.insert(id);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for Analyzer<'_> {
+    fn visit_stmt(&mut self, stmt: &'a ast::Stmt) {
+        match &*stmt.kind {
+            ast::StmtKind::Item(_) => self.is_supported = false,
+            ast::StmtKind::Local(ast::Mutability::Mutable, pat, _) => {
+                bindings(pat, &mut self.mutable);
+            }
+            ast::StmtKind::Qubit(_, pat, _, _) => {
+                bindings(pat, &mut self.qubits);
+                self.needs_operation |= !self.in_lambda;
+            }
+            _ => {}
+        }
+        walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &'a ast::Expr) {
+        match &*expr.kind {
+            ast::ExprKind::Return(_) => self.is_supported = false,
+            ast::ExprKind::Assign(lhs, _)
+            | ast::ExprKind::AssignOp(_, lhs, _)
+            | ast::ExprKind::AssignUpdate(lhs, _, _) => self.assign(lhs),
+            ast::ExprKind::Conjugate(..) => self.needs_operation |= !self.in_lambda,
+            ast::ExprKind::Call(callee, _) => {
+                if let Some(Ty::Arrow(arrow)) = self.compilation.get_ty(callee.id) {
+                    self.needs_operation |=
+                        !self.in_lambda && arrow.kind == hir::CallableKind::Operation;
+                }
+            }
+            ast::ExprKind::Lambda(..) => {
+                let outer = self.in_lambda;
+                self.in_lambda = true;
+                walk_expr(self, expr);
+                self.in_lambda = outer;
+                return;
+            }
+            _ => {}
+        }
+        walk_expr(self, expr);
+    }
+
+    fn visit_pat(&mut self, pat: &'a ast::Pat) {
+        if let ast::PatKind::Bind(name, _) = &*pat.kind {
+            self.declared.push((name.id, name.name.to_string()));
+        }
+        walk_pat(self, pat);
+    }
+
+    fn visit_path(&mut self, path: &'a ast::Path) {
+        if let Some((id, ident)) = local_ref(self.compilation, path) {
+            if !self.used.iter().any(|(used, _)| *used == id) {
+                self.used.push((id, ident.name.to_string()));
+            }
+        }
+    }
+}
+
+fn bindings(pat: &ast::Pat, ids: &mut FxHashSet<ast::NodeId>) {
+    match &*pat.kind {
+        ast::PatKind::Bind(name, _) => {
+            ids.insert(name.id);
+        }
+        ast::PatKind::Paren(item) => bindings(item, ids),
+        ast::PatKind::Tuple(items) => items.iter().for_each(|item| bindings(item, ids)),
+        ast::PatKind::Discard(_) | ast::PatKind::Elided | ast::PatKind::Err => {}
+    }
+}
+
+/// Finds which of the declared locals are used after an offset.
+struct UsedAfter<'a> {
+    compilation: &'a Compilation,
+    after: u32,
+    declared: &'a [(ast::NodeId, String)],
+    used: FxHashSet<ast::NodeId>,
+}
+
+impl<'a> Visitor<'a> for UsedAfter<'_> {
+    fn visit_path(&mut self, path: &'a ast::Path) {
+        if path.span.lo < self.after {
+            return;
+        }
+        if let Some((id, _)) = local_ref(self.compilation, path) {
+            if self.declared.iter().any(|(declared, _)| *declared == id) {
+                self.used.insert(id);
+            }
+        }
+    }
+}
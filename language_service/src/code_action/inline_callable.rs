@@ -0,0 +1,414 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{encloses, local_ref, reindent, Selection};
+use crate::{
+    compilation::Compilation,
+    protocol::{CodeAction, CodeActionKind},
+};
+use qsc::{
+    ast::{
+        self,
+        visit::{walk_expr, walk_namespace, walk_pat, walk_stmt, Visitor},
+    },
+    display::Lookup,
+    hir::ty::Ty,
+    resolve, Span,
+};
+use rustc_hash::FxHashSet;
+
+/// Replaces a call to a callable declared in the same namespace with the body of the callable.
+/// Arguments that are names or literals are substituted for the parameters, and the others are
+/// bound to locals that have the names of the parameters.
+#[allow(clippy::too_many_lines)]
+pub(super) fn inline_callable(selection: &Selection) -> Option<CodeAction> {
+    let compilation = selection.compilation;
+    let mut finder = CallFinder {
+        compilation,
+        span: selection.span,
+        namespace: None,
+        stmt: None,
+        found: None,
+    };
+    finder.visit_package(&compilation.user_unit().ast.package);
+    let call = finder.found?;
+
+    let decl = find_callable_decl(compilation, call.namespace, call.path)?;
+    let ast::CallableBody::Block(block) = &*decl.body else {
+        return None;
+    };
+    if !decl.generics.is_empty() || encloses(decl.span, call.expr.span) {
+        return None;
+    }
+    let params = params(&decl.input)?;
+    let args = args(call.args);
+    if params.len() != args.len() {
+        return None;
+    }
+
+    let mut body = BodyAnalyzer {
+        compilation,
+        params: &params,
+        uses: vec![Vec::new(); params.len()],
+        bound: FxHashSet::default(),
+        is_supported: true,
+    };
+    body.visit_block(block);
+    if !body.is_supported {
+        return None;
+    }
+
+    // Arguments that aren't just names or literals are bound to locals, in order, so that
+    // they are evaluated exactly once and before the body, as they are in the call. Even an
+    // argument that is used once can't be substituted, since the use can be in a loop or
+    // after effects of the body that the argument would then observe.
+    let locals = args
+        .iter()
+        .enumerate()
+        .filter(|(_, arg)| !is_pure(arg))
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+
+    // The names that the inlined code binds must not capture the names in the arguments.
+    let mut bound = body.bound.clone();
+    bound.extend(
+        locals
+            .iter()
+            .filter_map(|&i| params[i].map(|p| p.name.to_string())),
+    );
+    let captures = args.iter().any(|arg| {
+        selection
+            .text(arg.span)
+            .split(|c: char| !c.is_alphanumeric() && c != '_')
+            .any(|word| bound.contains(word))
+    });
+    if captures {
+        return None;
+    }
+
+    let mut substitutions = Vec::new();
+    for (i, arg) in args.iter().enumerate() {
+        if !locals.contains(&i) {
+            let text = parenthesize(selection.text(arg.span), arg);
+            substitutions.extend(body.uses[i].iter().map(|&span| (span, text.clone())));
+        }
+    }
+    substitutions.sort_by_key(|(span, _)| span.lo);
+
+    let indent = selection.indentation(call.stmt.span.lo);
+    let (stmts_span, stmts_indent) = match (block.stmts.first(), block.stmts.last()) {
+        (Some(first), Some(last)) => (
+            Span {
+                lo: first.span.lo,
+                hi: last.span.hi,
+            },
+            selection.indentation(first.span.lo),
+        ),
+        _ => (
+            Span {
+                lo: block.span.lo,
+                hi: block.span.lo,
+            },
+            "",
+        ),
+    };
+    let stmts_text = substitute(selection, stmts_span, &substitutions);
+
+    // A call that is a statement of its own, and that has no value, can be replaced by the
+    // statements of the body if they don't declare any locals. Otherwise, the body is
+    // inlined as a block, unless it's a single expression.
+    let is_stmt = matches!(
+        &*call.stmt.kind,
+        ast::StmtKind::Expr(expr) | ast::StmtKind::Semi(expr) if expr.id == call.expr.id
+    ) && compilation.get_ty(call.expr.id) == Some(&Ty::UNIT);
+
+    let single_expr = match &*block.stmts {
+        [stmt] => match &*stmt.kind {
+            ast::StmtKind::Expr(expr) => Some(expr),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    let (span, new_text) =
+        if is_stmt && locals.is_empty() && body.bound.is_empty() && !block.stmts.is_empty() {
+            let mut text = reindent(&stmts_text, stmts_indent, indent);
+            if let Some(stmt) = block.stmts.last() {
+                if let ast::StmtKind::Expr(expr) = &*stmt.kind {
+                    if !is_stmt_final(expr) {
+                        text.push(';');
+                    }
+                }
+            }
+            (call.stmt.span, text)
+        } else if let (Some(expr), true) = (single_expr, locals.is_empty()) {
+            if is_atomic(expr) || is_direct_child(call.stmt, call.expr) {
+                (call.expr.span, stmts_text)
+            } else {
+                (call.expr.span, format!("({stmts_text})"))
+            }
+        } else if block.stmts.is_empty() && locals.is_empty() {
+            (call.expr.span, "()".to_string())
+        } else {
+            let inner_indent = format!("{indent}    ");
+            let mut text = "{\n".to_string();
+            for &i in &locals {
+                let name = params[i].map_or("_", |param| &*param.name);
+                text.push_str(&format!(
+                    "{inner_indent}let {name} = {};\n",
+                    selection.text(args[i].span)
+                ));
+            }
+            if !block.stmts.is_empty() {
+                text.push_str(&format!(
+                    "{inner_indent}{}\n",
+                    reindent(&stmts_text, stmts_indent, &inner_indent)
+                ));
+            }
+            text.push_str(&format!("{indent}}}"));
+            if is_stmt {
+                (call.stmt.span, text)
+            } else {
+                (call.expr.span, text)
+            }
+        };
+
+    Some(selection.code_action(
+        format!("Inline call to `{}`", call.path.name.name),
+        CodeActionKind::RefactorInline,
+        vec![(span, new_text)],
+    ))
+}
+
+/// Finds the declaration of the callable that the path refers to, if it's in the namespace.
+fn find_callable_decl<'a>(
+    compilation: &Compilation,
+    namespace: &'a ast::Namespace,
+    path: &ast::Path,
+) -> Option<&'a ast::CallableDecl> {
+    let Some(resolve::Res::Item(item_id, _)) = compilation.get_res(path.id) else {
+        return None;
+    };
+    namespace.items.iter().find_map(|item| match &*item.kind {
+        ast::ItemKind::Callable(decl) => match compilation.get_res(decl.name.id) {
+            Some(resolve::Res::Item(id, _)) if id == item_id => Some(&**decl),
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+/// Gets the parameters of a callable, which must not be nested tuples.
+/// Discarded parameters are `None`.
+fn params(input: &ast::Pat) -> Option<Vec<Option<&ast::Ident>>> {
+    let items = match &*input.kind {
+        ast::PatKind::Tuple(items) => items.iter().map(AsRef::as_ref).collect(),
+        _ => vec![input],
+    };
+    let mut params = Vec::with_capacity(items.len());
+    for mut pat in items {
+        while let ast::PatKind::Paren(inner) = &*pat.kind {
+            pat = inner;
+        }
+        match &*pat.kind {
+            ast::PatKind::Bind(name, _) => params.push(Some(&**name)),
+            ast::PatKind::Discard(_) => params.push(None),
+            ast::PatKind::Elided
+            | ast::PatKind::Err
+            | ast::PatKind::Paren(_)
+            | ast::PatKind::Tuple(_) => return None,
+        }
+    }
+    Some(params)
+}
+
+/// Gets the arguments of a call.
+fn args(args: &ast::Expr) -> Vec<&ast::Expr> {
+    match &*args.kind {
+        ast::ExprKind::Tuple(items) => items.iter().map(AsRef::as_ref).collect(),
+        ast::ExprKind::Paren(inner) => vec![&**inner],
+        _ => vec![args],
+    }
+}
+
+/// Returns true if evaluating the expression has no effects, so it can be repeated or skipped.
+fn is_pure(expr: &ast::Expr) -> bool {
+    matches!(&*expr.kind, ast::ExprKind::Path(_) | ast::ExprKind::Lit(_))
+}
+
+/// Returns true if the expression doesn't need parentheses when it's used as an operand.
+fn is_atomic(expr: &ast::Expr) -> bool {
+    matches!(
+        &*expr.kind,
+        ast::ExprKind::Array(_)
+            | ast::ExprKind::ArrayRepeat(..)
+            | ast::ExprKind::Call(..)
+            | ast::ExprKind::Field(..)
+            | ast::ExprKind::Index(..)
+            | ast::ExprKind::Interpolate(_)
+            | ast::ExprKind::Lit(_)
+            | ast::ExprKind::Paren(_)
+            | ast::ExprKind::Path(_)
+            | ast::ExprKind::Struct(..)
+            | ast::ExprKind::Tuple(_)
+    )
+}
+
+/// Returns true if the expression ends a statement without a semicolon.
+fn is_stmt_final(expr: &ast::Expr) -> bool {
+    matches!(
+        &*expr.kind,
+        ast::ExprKind::Block(_)
+            | ast::ExprKind::Conjugate(..)
+            | ast::ExprKind::For(..)
+            | ast::ExprKind::If(..)
+            | ast::ExprKind::Repeat(..)
+            | ast::ExprKind::While(..)
+    )
+}
+
+fn parenthesize(text: &str, expr: &ast::Expr) -> String {
+    if is_atomic(expr) {
+        text.to_string()
+    } else {
+        format!("({text})")
+    }
+}
+
+/// Returns true if the expression is the whole expression of the statement.
+fn is_direct_child(stmt: &ast::Stmt, expr: &ast::Expr) -> bool {
+    match &*stmt.kind {
+        ast::StmtKind::Expr(inner)
+        | ast::StmtKind::Semi(inner)
+        | ast::StmtKind::Local(_, _, inner) => inner.id == expr.id,
+        _ => false,
+    }
+}
+
+/// Gets the text of the span with the substitutions, which must be sorted and inside the span.
+fn substitute(selection: &Selection, span: Span, substitutions: &[(Span, String)]) -> String {
+    let mut text = String::new();
+    let mut offset = span.lo;
+    for (sub_span, new_text) in substitutions {
+        text.push_str(selection.text(Span {
+            lo: offset,
+            hi: sub_span.lo,
+        }));
+        text.push_str(new_text);
+        offset = sub_span.hi;
+    }
+    text.push_str(selection.text(Span {
+        lo: offset,
+        hi: span.hi,
+    }));
+    text
+}
+
+struct Call<'a> {
+    namespace: &'a ast::Namespace,
+    /// The innermost statement that contains the call.
+    stmt: &'a ast::Stmt,
+    expr: &'a ast::Expr,
+    path: &'a ast::Path,
+    args: &'a ast::Expr,
+}
+
+/// Finds the innermost call whose callee is a path that contains the selection.
+struct CallFinder<'a> {
+    compilation: &'a Compilation,
+    span: Span,
+    namespace: Option<&'a ast::Namespace>,
+    stmt: Option<&'a ast::Stmt>,
+    found: Option<Call<'a>>,
+}
+
+impl<'a> Visitor<'a> for CallFinder<'a> {
+    fn visit_namespace(&mut self, namespace: &'a ast::Namespace) {
+        if encloses(namespace.span, self.span) {
+            self.namespace = Some(namespace);
+            walk_namespace(self, namespace);
+        }
+    }
+
+    fn visit_stmt(&mut self, stmt: &'a ast::Stmt) {
+        if encloses(stmt.span, self.span) {
+            self.stmt = Some(stmt);
+            walk_stmt(self, stmt);
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &'a ast::Expr) {
+        if !encloses(expr.span, self.span) {
+            return;
+        }
+        if let ast::ExprKind::Call(callee, args) = &*expr.kind {
+            if let ast::ExprKind::Path(ast::PathKind::Ok(path)) = &*callee.kind {
+                let is_callable = matches!(
+                    self.compilation.get_res(path.id),
+                    Some(resolve::Res::Item(..))
+                );
+                if let (true, true, Some(namespace), Some(stmt)) = (
+                    is_callable,
+                    encloses(path.span, self.span),
+                    self.namespace,
+                    self.stmt,
+                ) {
+                    self.found = Some(Call {
+                        namespace,
+                        stmt,
+                        expr,
+                        path,
+                        args,
+                    });
+                }
+            }
+        }
+        walk_expr(self, expr);
+    }
+}
+
+/// Finds the uses of the parameters in the body of a callable, and the names that it binds.
+struct BodyAnalyzer<'a, 'b> {
+    compilation: &'a Compilation,
+    params: &'a [Option<&'b ast::Ident>],
+    /// The spans of the uses of each parameter.
+    uses: Vec<Vec<Span>>,
+    bound: FxHashSet<String>,
+    /// Whether the body can be inlined. Bodies that return early or declare items can't be.
+    is_supported: bool,
+}
+
+impl<'a> Visitor<'a> for BodyAnalyzer<'_, '_> {
+    fn visit_stmt(&mut self, stmt: &'a ast::Stmt) {
+        if matches!(&*stmt.kind, ast::StmtKind::Item(_)) {
+            self.is_supported = false;
+        }
+        walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &'a ast::Expr) {
+        if matches!(&*expr.kind, ast::ExprKind::Return(_)) {
+            self.is_supported = false;
+        }
+        walk_expr(self, expr);
+    }
+
+    fn visit_pat(&mut self, pat: &'a ast::Pat) {
+        if let ast::PatKind::Bind(name, _) = &*pat.kind {
+            self.bound.insert(name.name.to_string());
+        }
+        walk_pat(self, pat);
+    }
+
+    fn visit_path(&mut self, path: &'a ast::Path) {
+        if let Some((id, ident)) = local_ref(self.compilation, path) {
+            if let Some(i) = self
+                .params
+                .iter()
+                .position(|param| param.is_some_and(|param| param.id == id))
+            {
+                self.uses[i].push(ident.span);
+            }
+        }
+    }
+}
@@ -0,0 +1,152 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{encloses, unique_name, Selection};
+use crate::{
+    protocol::{CodeAction, CodeActionKind},
+    qsc_utils::is_known,
+};
+use qsc::{
+    ast::{
+        self,
+        visit::{walk_expr, walk_stmt, Visitor},
+    },
+    display::Lookup,
+    hir::ty::Ty,
+    Span,
+};
+
+/// Replaces the selected expression with a new local variable,
+/// which is declared just before the statement that contains the expression.
+pub(super) fn introduce_local(selection: &Selection) -> Option<CodeAction> {
+    if selection.is_empty() {
+        return None;
+    }
+    let mut finder = ExprFinder {
+        span: selection.span,
+        stmt: None,
+        hoistable: true,
+        found: None,
+    };
+    finder.visit_package(&selection.compilation.user_unit().ast.package);
+    let (stmt, expr) = finder.found?;
+
+    // Paths are already names, and a statement that is only the expression
+    // doesn't need a variable.
+    if matches!(
+        &*expr.kind,
+        ast::ExprKind::Path(_) | ast::ExprKind::Hole | ast::ExprKind::Err
+    ) {
+        return None;
+    }
+    if let ast::StmtKind::Expr(inner) | ast::StmtKind::Semi(inner) = &*stmt.kind {
+        if inner.id == expr.id {
+            return None;
+        }
+    }
+    let ty = selection.compilation.get_ty(expr.id)?;
+    if !is_known(ty) || *ty == Ty::UNIT {
+        return None;
+    }
+
+    let name = unique_name("newLocal", selection.contents);
+    let new_text = format!(
+        "let {name} = {};\n{}{}{name}",
+        selection.text(expr.span),
+        selection.indentation(stmt.span.lo),
+        selection.text(Span {
+            lo: stmt.span.lo,
+            hi: expr.span.lo,
+        }),
+    );
+    let edit = (
+        Span {
+            lo: stmt.span.lo,
+            hi: expr.span.hi,
+        },
+        new_text,
+    );
+    Some(selection.code_action(
+        "Introduce local variable".to_string(),
+        CodeActionKind::RefactorExtract,
+        vec![edit],
+    ))
+}
+
+/// Finds the outermost expression whose span is exactly the selection,
+/// along with the innermost statement that contains it.
+struct ExprFinder<'a> {
+    span: Span,
+    stmt: Option<&'a ast::Stmt>,
+    /// Whether the expressions being visited are always evaluated exactly once when the
+    /// statement runs, so that evaluating them before the statement keeps the same meaning.
+    hoistable: bool,
+    found: Option<(&'a ast::Stmt, &'a ast::Expr)>,
+}
+
+impl<'a> ExprFinder<'a> {
+    fn visit_not_hoistable(&mut self, expr: &'a ast::Expr) {
+        let outer = self.hoistable;
+        self.hoistable = false;
+        self.visit_expr(expr);
+        self.hoistable = outer;
+    }
+}
+
+impl<'a> Visitor<'a> for ExprFinder<'a> {
+    fn visit_stmt(&mut self, stmt: &'a ast::Stmt) {
+        if self.found.is_some() || !encloses(stmt.span, self.span) {
+            return;
+        }
+        let (outer_stmt, outer_hoistable) = (self.stmt, self.hoistable);
+        self.stmt = Some(stmt);
+        self.hoistable = true;
+        walk_stmt(self, stmt);
+        self.stmt = outer_stmt;
+        self.hoistable = outer_hoistable;
+    }
+
+    fn visit_expr(&mut self, expr: &'a ast::Expr) {
+        if self.found.is_some() || !encloses(expr.span, self.span) {
+            return;
+        }
+        if expr.span == self.span {
+            if let (Some(stmt), true) = (self.stmt, self.hoistable) {
+                self.found = Some((stmt, expr));
+            }
+            return;
+        }
+
+        match &*expr.kind {
+            ast::ExprKind::While(cond, block) => {
+                self.visit_not_hoistable(cond);
+                self.visit_block(block);
+            }
+            ast::ExprKind::Repeat(body, until, fixup) => {
+                self.visit_block(body);
+                self.visit_not_hoistable(until);
+                if let Some(fixup) = fixup {
+                    self.visit_block(fixup);
+                }
+            }
+            ast::ExprKind::If(cond, body, otherwise) => {
+                self.visit_expr(cond);
+                self.visit_block(body);
+                if let Some(otherwise) = otherwise {
+                    self.visit_not_hoistable(otherwise);
+                }
+            }
+            ast::ExprKind::BinOp(ast::BinOp::AndL | ast::BinOp::OrL, lhs, rhs) => {
+                self.visit_expr(lhs);
+                self.visit_not_hoistable(rhs);
+            }
+            ast::ExprKind::TernOp(ast::TernOp::Cond, cond, if_true, if_false) => {
+                self.visit_expr(cond);
+                self.visit_not_hoistable(if_true);
+                self.visit_not_hoistable(if_false);
+            }
+            ast::ExprKind::Lambda(_, _, body) => self.visit_not_hoistable(body),
+            _ => walk_expr(self, expr),
+        }
+    }
+}
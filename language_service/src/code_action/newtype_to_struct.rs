@@ -0,0 +1,182 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{encloses, Selection};
+use crate::{
+    compilation::Compilation,
+    protocol::{CodeAction, CodeActionKind},
+};
+use qsc::{
+    ast::{
+        self,
+        visit::{walk_expr, walk_item, Visitor},
+    },
+    display::Lookup,
+    hir::{self, ty::Ty},
+    resolve, Span,
+};
+
+/// Converts the selected newtype, if all of its items are named, into a struct.
+/// Calls to its constructor become struct expressions, and field accesses that use `::`
+/// use `.` instead.
+pub(super) fn newtype_to_struct(selection: &Selection) -> Option<CodeAction> {
+    let compilation = selection.compilation;
+    let mut finder = NewtypeFinder {
+        span: selection.span,
+        found: None,
+    };
+    finder.visit_package(&compilation.user_unit().ast.package);
+    let (item, name, def) = finder.found?;
+    let Some(resolve::Res::Item(item_id, _)) = compilation.get_res(name.id) else {
+        return None;
+    };
+    let fields = fields(def)?;
+
+    let keyword_lo = item.span.lo
+        + u32::try_from(
+            selection
+                .text(Span {
+                    lo: item.span.lo,
+                    hi: name.span.lo,
+                })
+                .rfind("newtype")?,
+        )
+        .expect("offset should fit into u32");
+    let fields_text = fields
+        .iter()
+        .map(|(field, ty)| format!("{} : {}", field.name, selection.text(ty.span)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut edits = vec![(
+        Span {
+            lo: keyword_lo,
+            hi: item.span.hi,
+        },
+        format!("struct {} {{ {fields_text} }}", name.name),
+    )];
+
+    let mut usages = UsageFinder {
+        compilation,
+        selection,
+        item_id: *item_id,
+        fields: &fields,
+        edits: Vec::new(),
+    };
+    usages.visit_package(&compilation.user_unit().ast.package);
+    edits.extend(usages.edits);
+
+    Some(selection.code_action(
+        format!("Convert newtype `{}` to struct", name.name),
+        CodeActionKind::RefactorRewrite,
+        edits,
+    ))
+}
+
+/// Gets the named items of a type definition, which must not be nested.
+fn fields(def: &ast::TyDef) -> Option<Vec<(&ast::Ident, &ast::Ty)>> {
+    fn field(def: &ast::TyDef) -> Option<(&ast::Ident, &ast::Ty)> {
+        match &*def.kind {
+            ast::TyDefKind::Field(Some(name), ty) => Some((name, ty)),
+            ast::TyDefKind::Paren(inner) => field(inner),
+            ast::TyDefKind::Field(None, _) | ast::TyDefKind::Tuple(_) | ast::TyDefKind::Err => None,
+        }
+    }
+
+    match &*def.kind {
+        ast::TyDefKind::Paren(inner) => fields(inner),
+        ast::TyDefKind::Tuple(items) if !items.is_empty() => {
+            items.iter().map(|item| field(item)).collect()
+        }
+        _ => field(def).map(|field| vec![field]),
+    }
+}
+
+/// Finds the newtype declaration that contains the selection.
+struct NewtypeFinder<'a> {
+    span: Span,
+    found: Option<(&'a ast::Item, &'a ast::Ident, &'a ast::TyDef)>,
+}
+
+impl<'a> Visitor<'a> for NewtypeFinder<'a> {
+    fn visit_item(&mut self, item: &'a ast::Item) {
+        if !encloses(item.span, self.span) {
+            return;
+        }
+        if let ast::ItemKind::Ty(name, def) = &*item.kind {
+            self.found = Some((item, name, def));
+        } else {
+            walk_item(self, item);
+        }
+    }
+}
+
+/// Finds the usages of the newtype that are written differently for a struct.
+struct UsageFinder<'a> {
+    compilation: &'a Compilation,
+    selection: &'a Selection<'a>,
+    item_id: hir::ItemId,
+    fields: &'a [(&'a ast::Ident, &'a ast::Ty)],
+    edits: Vec<(Span, String)>,
+}
+
+impl<'a> Visitor<'a> for UsageFinder<'_> {
+    fn visit_expr(&mut self, expr: &'a ast::Expr) {
+        match &*expr.kind {
+            ast::ExprKind::Call(callee, args) => {
+                if let ast::ExprKind::Path(ast::PathKind::Ok(path)) = &*callee.kind {
+                    if let Some(new_text) = self.struct_expr(path, args) {
+                        // The arguments are kept as they are, since they are part of the edit.
+                        self.edits.push((expr.span, new_text));
+                        return;
+                    }
+                }
+            }
+            ast::ExprKind::Field(record, field) => {
+                let separator = Span {
+                    lo: record.span.hi,
+                    hi: field.span.lo,
+                };
+                if self.is_newtype(record) && self.selection.text(separator).trim() == "::" {
+                    self.edits.push((separator, ".".to_string()));
+                }
+            }
+            _ => {}
+        }
+        walk_expr(self, expr);
+    }
+}
+
+impl UsageFinder<'_> {
+    fn is_newtype(&self, expr: &ast::Expr) -> bool {
+        matches!(
+            self.compilation.get_ty(expr.id),
+            Some(Ty::Udt(_, hir::Res::Item(id))) if *id == self.item_id
+        )
+    }
+
+    /// Gets the struct expression for a call to the constructor with an argument for each field.
+    fn struct_expr(&self, path: &ast::Path, args: &ast::Expr) -> Option<String> {
+        match self.compilation.get_res(path.id) {
+            Some(resolve::Res::Item(id, _)) if *id == self.item_id => {}
+            _ => return None,
+        }
+        let args = match (&*args.kind, self.fields.len()) {
+            (ast::ExprKind::Tuple(items), len) if items.len() == len && len > 1 => {
+                items.iter().map(AsRef::as_ref).collect()
+            }
+            (ast::ExprKind::Paren(inner), 1) => vec![&**inner],
+            _ => return None,
+        };
+        let assigns = self
+            .fields
+            .iter()
+            .zip(args)
+            .map(|((field, _), arg)| format!("{} = {}", field.name, self.selection.text(arg.span)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        Some(format!(
+            "new {} {{ {assigns} }}",
+            self.selection.text(path.span)
+        ))
+    }
+}
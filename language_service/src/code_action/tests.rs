@@ -0,0 +1,456 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::get_code_actions;
+use crate::{
+    compilation::Compilation,
+    protocol::CodeAction,
+    test_utils::{compile_with_fake_stdlib_and_markers_no_cursor, compile_with_markers},
+    Encoding,
+};
+use expect_test::{expect, Expect};
+use indoc::indoc;
+use qsc::line_column::Range;
+
+/// Gets the code actions for the range between the `◉` markers, or at the `↘` cursor,
+/// whose titles start with the given text.
+fn code_actions(source_with_markers: &str, title: &str) -> (Compilation, Vec<CodeAction>) {
+    let (compilation, range) = if source_with_markers.contains('↘') {
+        let (compilation, cursor_position, _) = compile_with_markers(source_with_markers, true);
+        (
+            compilation,
+            Range {
+                start: cursor_position,
+                end: cursor_position,
+            },
+        )
+    } else {
+        let (compilation, ranges) =
+            compile_with_fake_stdlib_and_markers_no_cursor(source_with_markers, true);
+        (compilation, ranges[0])
+    };
+    let actions = get_code_actions(&compilation, "<source>", range, Encoding::Utf8)
+        .into_iter()
+        .filter(|action| action.title.starts_with(title))
+        .collect();
+    (compilation, actions)
+}
+
+/// Applies the edits of the code action whose title starts with the given text,
+/// and checks the resulting source.
+fn check(source_with_markers: &str, title: &str, expect: &Expect) {
+    let (compilation, actions) = code_actions(source_with_markers, title);
+    assert_eq!(
+        actions.len(),
+        1,
+        "expected one code action, got {actions:?}"
+    );
+    let edit = actions[0]
+        .edit
+        .as_ref()
+        .expect("code action should have an edit");
+
+    let mut actual = compilation
+        .user_unit()
+        .sources
+        .find_by_name("<source>")
+        .expect("source should exist")
+        .contents
+        .to_string();
+    let mut edits = edit
+        .changes
+        .iter()
+        .flat_map(|(source, edits)| {
+            assert_eq!(source, "<source>");
+            edits
+        })
+        .map(|edit| {
+            let offset = |position: qsc::line_column::Position| -> usize {
+                position
+                    .to_utf8_byte_offset(Encoding::Utf8, &actual)
+                    .try_into()
+                    .expect("offset should fit into usize")
+            };
+            (
+                offset(edit.range.start),
+                offset(edit.range.end),
+                &edit.new_text,
+            )
+        })
+        .collect::<Vec<_>>();
+    edits.sort_by_key(|(start, _, _)| *start);
+    for (start, end, new_text) in edits.into_iter().rev() {
+        actual.replace_range(start..end, new_text);
+    }
+    expect.assert_eq(&actual);
+}
+
+fn check_none(source_with_markers: &str, title: &str) {
+    let (_, actions) = code_actions(source_with_markers, title);
+    assert!(
+        actions.is_empty(),
+        "expected no code actions, got {actions:?}"
+    );
+}
+
+#[test]
+fn introduce_local_for_selected_expression() {
+    check(
+        indoc! {r#"
+            namespace Test {
+                function Main() : Unit {
+                    let x = 1;
+                    let y = ◉x * 2◉ + 1;
+                }
+            }
+        "#},
+        "Introduce local variable",
+        &expect![[r#"
+            namespace Test {
+                function Main() : Unit {
+                    let x = 1;
+                    let newLocal = x * 2;
+                    let y = newLocal + 1;
+                }
+            }
+        "#]],
+    );
+}
+
+#[test]
+fn introduce_local_not_offered_for_loop_condition() {
+    check_none(
+        indoc! {r#"
+            namespace Test {
+                function Main() : Unit {
+                    mutable i = 0;
+                    while ◉i < 3◉ {
+                        set i += 1;
+                    }
+                }
+            }
+        "#},
+        "Introduce local variable",
+    );
+}
+
+#[test]
+fn extract_function_with_parameters_and_result() {
+    check(
+        indoc! {r#"
+            namespace Test {
+                function Main() : Unit {
+                    let a = 1;
+                    ◉let b = a + 1;
+                    let c = b * 2;◉
+                    let d = c + a;
+                }
+            }
+        "#},
+        "Extract into new",
+        &expect![[r#"
+            namespace Test {
+                function Main() : Unit {
+                    let a = 1;
+                    let c = NewFunction(a);
+                    let d = c + a;
+                }
+
+                function NewFunction(a : Int) : Int {
+                    let b = a + 1;
+                    let c = b * 2;
+                    c
+                }
+            }
+        "#]],
+    );
+}
+
+#[test]
+fn extract_operation_keeps_functors() {
+    check(
+        indoc! {r#"
+            namespace Test {
+                operation Main(q : Qubit) : Unit is Adj {
+                    ◉X(q);
+                    Y(q);◉
+                }
+                operation X(q : Qubit) : Unit is Adj {}
+                operation Y(q : Qubit) : Unit is Adj {}
+            }
+        "#},
+        "Extract into new",
+        &expect![[r#"
+            namespace Test {
+                operation Main(q : Qubit) : Unit is Adj {
+                    NewOperation(q);
+                }
+
+                operation NewOperation(q : Qubit) : Unit is Adj {
+                    X(q);
+                    Y(q);
+                }
+                operation X(q : Qubit) : Unit is Adj {}
+                operation Y(q : Qubit) : Unit is Adj {}
+            }
+        "#]],
+    );
+}
+
+#[test]
+fn extract_block_value() {
+    check(
+        indoc! {r#"
+            namespace Test {
+                function Main() : Int {
+                    let a = 2;
+                    ◉let b = a * a;
+                    b + 1◉
+                }
+            }
+        "#},
+        "Extract into new",
+        &expect![[r#"
+            namespace Test {
+                function Main() : Int {
+                    let a = 2;
+                    NewFunction(a)
+                }
+
+                function NewFunction(a : Int) : Int {
+                    let b = a * a;
+                    b + 1
+                }
+            }
+        "#]],
+    );
+}
+
+#[test]
+fn extract_not_offered_when_updating_outer_local() {
+    check_none(
+        indoc! {r#"
+            namespace Test {
+                function Main() : Unit {
+                    mutable total = 0;
+                    ◉set total += 1;◉
+                }
+            }
+        "#},
+        "Extract into new",
+    );
+}
+
+#[test]
+fn inline_expression_body() {
+    check(
+        indoc! {r#"
+            namespace Test {
+                function Add(a : Int, b : Int) : Int { a + b }
+                function Main() : Unit {
+                    let x = 3 * ↘Add(1, 2);
+                }
+            }
+        "#},
+        "Inline call to `Add`",
+        &expect![[r#"
+            namespace Test {
+                function Add(a : Int, b : Int) : Int { a + b }
+                function Main() : Unit {
+                    let x = 3 * (1 + 2);
+                }
+            }
+        "#]],
+    );
+}
+
+#[test]
+fn inline_statements_with_substituted_arguments() {
+    check(
+        indoc! {r#"
+            namespace Test {
+                operation Apply(q : Qubit, n : Int) : Unit {
+                    for _ in 1..n {
+                        X(q);
+                    }
+                }
+                operation X(q : Qubit) : Unit {}
+                function Count() : Int { 3 }
+                operation Main(q : Qubit) : Unit {
+                    ↘Apply(q, 3);
+                }
+            }
+        "#},
+        "Inline call to `Apply`",
+        &expect![[r#"
+            namespace Test {
+                operation Apply(q : Qubit, n : Int) : Unit {
+                    for _ in 1..n {
+                        X(q);
+                    }
+                }
+                operation X(q : Qubit) : Unit {}
+                function Count() : Int { 3 }
+                operation Main(q : Qubit) : Unit {
+                    for _ in 1..3 {
+                        X(q);
+                    }
+                }
+            }
+        "#]],
+    );
+}
+
+#[test]
+fn inline_statements_with_locals_for_arguments() {
+    check(
+        indoc! {r#"
+            namespace Test {
+                operation Apply(q : Qubit, n : Int) : Unit {
+                    for _ in 1..n {
+                        X(q);
+                    }
+                }
+                operation X(q : Qubit) : Unit {}
+                function Count() : Int { 3 }
+                operation Main(qs : Qubit[]) : Unit {
+                    ↘Apply(qs[0], Count());
+                }
+            }
+        "#},
+        "Inline call to `Apply`",
+        &expect![[r#"
+            namespace Test {
+                operation Apply(q : Qubit, n : Int) : Unit {
+                    for _ in 1..n {
+                        X(q);
+                    }
+                }
+                operation X(q : Qubit) : Unit {}
+                function Count() : Int { 3 }
+                operation Main(qs : Qubit[]) : Unit {
+                    {
+                        let q = qs[0];
+                        let n = Count();
+                        for _ in 1..n {
+                            X(q);
+                        }
+                    }
+                }
+            }
+        "#]],
+    );
+}
+
+#[test]
+fn inline_operation_argument_used_in_loop_is_bound_to_local() {
+    check(
+        indoc! {r#"
+            namespace Test {
+                operation Apply(q : Qubit, r : Result) : Unit {
+                    for _ in 1..3 {
+                        if r == One {
+                            X(q);
+                        }
+                    }
+                }
+                operation X(q : Qubit) : Unit {}
+                operation M(q : Qubit) : Result { Zero }
+                operation Main(q : Qubit) : Unit {
+                    ↘Apply(q, M(q));
+                }
+            }
+        "#},
+        "Inline call to `Apply`",
+        &expect![[r#"
+            namespace Test {
+                operation Apply(q : Qubit, r : Result) : Unit {
+                    for _ in 1..3 {
+                        if r == One {
+                            X(q);
+                        }
+                    }
+                }
+                operation X(q : Qubit) : Unit {}
+                operation M(q : Qubit) : Result { Zero }
+                operation Main(q : Qubit) : Unit {
+                    {
+                        let r = M(q);
+                        for _ in 1..3 {
+                            if r == One {
+                                X(q);
+                            }
+                        }
+                    }
+                }
+            }
+        "#]],
+    );
+}
+
+#[test]
+fn inline_single_use_call_argument_is_bound_to_local() {
+    check(
+        indoc! {r#"
+            namespace Test {
+                function Add(a : Int, b : Int) : Int { a + b }
+                function Count() : Int { 3 }
+                function Main() : Unit {
+                    let x = ↘Add(Count(), 2);
+                }
+            }
+        "#},
+        "Inline call to `Add`",
+        &expect![[r#"
+            namespace Test {
+                function Add(a : Int, b : Int) : Int { a + b }
+                function Count() : Int { 3 }
+                function Main() : Unit {
+                    let x = {
+                        let a = Count();
+                        a + 2
+                    };
+                }
+            }
+        "#]],
+    );
+}
+
+#[test]
+fn newtype_to_struct_updates_usages() {
+    check(
+        indoc! {r#"
+            namespace Test {
+                newtype ↘Pair = (First : Int, Second : Int);
+                function Main() : Unit {
+                    let p = Pair(1, 2);
+                    let f = p::First;
+                    let s = p.Second;
+                }
+            }
+        "#},
+        "Convert newtype",
+        &expect![[r#"
+            namespace Test {
+                struct Pair { First : Int, Second : Int }
+                function Main() : Unit {
+                    let p = new Pair { First = 1, Second = 2 };
+                    let f = p.First;
+                    let s = p.Second;
+                }
+            }
+        "#]],
+    );
+}
+
+#[test]
+fn newtype_with_unnamed_items_not_converted() {
+    check_none(
+        indoc! {r#"
+            namespace Test {
+                newtype ↘Pair = (Int, Int);
+            }
+        "#},
+        "Convert newtype",
+    );
+}
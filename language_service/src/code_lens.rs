@@ -11,7 +11,7 @@ use crate::{
 };
 use qsc::{
     circuit::qubit_param_info,
-    hir::{Expr, ExprKind, ItemId, ItemKind, LocalItemId, Package, Res},
+    hir::{Attr, Expr, ExprKind, ItemId, ItemKind, LocalItemId, Package, Res},
     line_column::Encoding,
 };
 
@@ -53,7 +53,7 @@ pub(crate) fn get_code_lenses(
 
     callables
         .flat_map(|(item, range, namespace, name, is_entry_point)| {
-            if is_entry_point {
+            let mut code_lenses = if is_entry_point {
                 vec![
                     CodeLens {
                         range,
@@ -76,18 +76,24 @@ pub(crate) fn get_code_lenses(
                         command: CodeLensCommand::Circuit(None),
                     },
                 ]
+            } else if let Some((_, total_num_qubits)) = qubit_param_info(item) {
+                vec![CodeLens {
+                    range,
+                    command: CodeLensCommand::Circuit(Some(OperationInfo {
+                        operation: format!("{namespace}.{name}"),
+                        total_num_qubits,
+                    })),
+                }]
             } else {
-                if let Some((_, total_num_qubits)) = qubit_param_info(item) {
-                    return vec![CodeLens {
-                        range,
-                        command: CodeLensCommand::Circuit(Some(OperationInfo {
-                            operation: format!("{namespace}.{name}"),
-                            total_num_qubits,
-                        })),
-                    }];
-                }
                 vec![]
+            };
+            if item.attrs.contains(&Attr::Test) {
+                code_lenses.push(CodeLens {
+                    range,
+                    command: CodeLensCommand::RunTest(format!("{namespace}.{name}")),
+                });
             }
+            code_lenses
         })
         .collect()
 }
@@ -194,3 +194,30 @@ fn qubit_arrays_operation_circuit() {
         "#]],
     );
 }
+
+#[test]
+fn test_callable_run_test() {
+    check(
+        r#"
+        namespace Test {
+            @Test()
+            ◉operation Foo() : Unit {
+            }◉
+
+            operation Bar() : Unit {
+            }
+        }"#,
+        &expect![[r#"
+            [
+                (
+                    0,
+                    [
+                        RunTest(
+                            "Test.Foo",
+                        ),
+                    ],
+                ),
+            ]
+        "#]],
+    );
+}
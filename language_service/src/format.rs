@@ -1,14 +1,21 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
+#[cfg(test)]
+mod tests;
+
 use crate::{compilation::Compilation, protocol::TextEdit};
 
-use qsc::formatter::calculate_format_edits;
-use qsc::line_column::{Encoding, Range};
+use qsc::ast::visit::{walk_block, walk_item, walk_namespace, walk_stmt, Visitor};
+use qsc::ast::{Block, Item, Namespace, Stmt};
+use qsc::formatter::{calculate_format_edits_with_config, FormatterConfig};
+use qsc::line_column::{Encoding, Position, Range};
+use qsc::Span;
 
 pub(crate) fn get_format_changes(
     compilation: &Compilation,
     source_name: &str,
+    config: &FormatterConfig,
     encoding: Encoding,
 ) -> Vec<TextEdit> {
     let contents = &compilation
@@ -18,11 +25,142 @@ pub(crate) fn get_format_changes(
         .expect("can't find source by name")
         .contents;
 
-    calculate_format_edits(contents)
+    calculate_format_edits_with_config(contents, config)
+        .into_iter()
+        .map(|edit| TextEdit {
+            new_text: edit.new_text,
+            range: Range::from_span(encoding, contents, &edit.span),
+        })
+        .collect()
+}
+
+/// Formats the document, keeping only the edits that overlap the given range.
+pub(crate) fn get_range_format_changes(
+    compilation: &Compilation,
+    source_name: &str,
+    range: Range,
+    config: &FormatterConfig,
+    encoding: Encoding,
+) -> Vec<TextEdit> {
+    let source = compilation
+        .user_unit()
+        .sources
+        .find_by_name(source_name)
+        .expect("can't find source by name");
+    let span =
+        compilation.source_range_to_package_span(source_name, range, encoding) - source.offset;
+
+    format_span(&source.contents, span, config, encoding)
+}
+
+/// Formats the code that was just completed by typing `ch` at `position`.
+/// A `;` formats the statement it ends, and a `}` formats the widest
+/// statement, block or item it closes. If no such node can be found, for example
+/// because the compilation is out of date, the current line is formatted instead.
+pub(crate) fn get_on_type_format_changes(
+    compilation: &Compilation,
+    source_name: &str,
+    position: Position,
+    ch: &str,
+    config: &FormatterConfig,
+    encoding: Encoding,
+) -> Vec<TextEdit> {
+    if !matches!(ch, ";" | "}") {
+        return Vec::new();
+    }
+
+    let source = compilation
+        .user_unit()
+        .sources
+        .find_by_name(source_name)
+        .expect("can't find source by name");
+    let package_offset =
+        compilation.source_position_to_package_offset(source_name, position, encoding);
+    let offset = package_offset - source.offset;
+    let Some(preceding) = source.contents.get(..offset as usize) else {
+        return Vec::new();
+    };
+    if !preceding.ends_with(ch) {
+        return Vec::new();
+    }
+
+    let mut finder = CompletedNodeFinder {
+        offset: package_offset,
+        span: None,
+    };
+    finder.visit_package(&compilation.user_unit().ast.package);
+    let span = finder.span.map_or_else(
+        || {
+            let line_start = preceding.rfind('\n').map_or(0, |i| i + 1);
+            Span {
+                lo: u32::try_from(line_start).expect("offset should fit into u32"),
+                hi: offset,
+            }
+        },
+        |span| span - source.offset,
+    );
+
+    format_span(&source.contents, span, config, encoding)
+}
+
+fn format_span(
+    contents: &str,
+    span: Span,
+    config: &FormatterConfig,
+    encoding: Encoding,
+) -> Vec<TextEdit> {
+    calculate_format_edits_with_config(contents, config)
         .into_iter()
+        // Edits that start where the span ends only touch the whitespace after it,
+        // such as the indentation of the next line, so they are left out.
+        .filter(|edit| edit.span.hi >= span.lo && edit.span.lo < span.hi)
         .map(|edit| TextEdit {
             new_text: edit.new_text,
             range: Range::from_span(encoding, contents, &edit.span),
         })
         .collect()
 }
+
+/// Finds the widest namespace, item, block or statement that ends at the given offset.
+struct CompletedNodeFinder {
+    offset: u32,
+    span: Option<Span>,
+}
+
+impl CompletedNodeFinder {
+    fn consider(&mut self, span: Span) {
+        if span.hi == self.offset && self.span.map_or(true, |found| span.lo < found.lo) {
+            self.span = Some(span);
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for CompletedNodeFinder {
+    fn visit_namespace(&mut self, namespace: &'a Namespace) {
+        if namespace.span.touches(self.offset) {
+            self.consider(namespace.span);
+            walk_namespace(self, namespace);
+        }
+    }
+
+    fn visit_item(&mut self, item: &'a Item) {
+        if item.span.touches(self.offset) {
+            self.consider(item.span);
+            walk_item(self, item);
+        }
+    }
+
+    fn visit_block(&mut self, block: &'a Block) {
+        if block.span.touches(self.offset) {
+            self.consider(block.span);
+            walk_block(self, block);
+        }
+    }
+
+    fn visit_stmt(&mut self, stmt: &'a Stmt) {
+        if stmt.span.touches(self.offset) {
+            self.consider(stmt.span);
+            walk_stmt(self, stmt);
+        }
+    }
+}
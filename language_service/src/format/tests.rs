@@ -0,0 +1,223 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{get_on_type_format_changes, get_range_format_changes};
+use crate::{
+    compilation::Compilation,
+    protocol::TextEdit,
+    test_utils::{compile_with_fake_stdlib_and_markers_no_cursor, compile_with_markers},
+};
+use expect_test::{expect, Expect};
+use indoc::indoc;
+use qsc::formatter::FormatterConfig;
+use qsc::line_column::Encoding;
+
+/// Applies the edits to the `<source>` document and returns the resulting text.
+fn apply_edits(compilation: &Compilation, mut edits: Vec<TextEdit>) -> String {
+    let mut contents = compilation
+        .user_unit()
+        .sources
+        .find_by_name("<source>")
+        .expect("source should exist")
+        .contents
+        .to_string();
+    // Apply the edits back to front so earlier positions stay valid.
+    edits.sort_by_key(|edit| (edit.range.start.line, edit.range.start.column));
+    for edit in edits.into_iter().rev() {
+        let lo = edit
+            .range
+            .start
+            .to_utf8_byte_offset(Encoding::Utf8, &contents) as usize;
+        let hi = edit
+            .range
+            .end
+            .to_utf8_byte_offset(Encoding::Utf8, &contents) as usize;
+        contents.replace_range(lo..hi, &edit.new_text);
+    }
+    contents
+}
+
+/// Formats the range between the two `◉` markers and checks the resulting text.
+fn check_range(source_with_markers: &str, expect: &Expect) {
+    let (compilation, target_ranges) =
+        compile_with_fake_stdlib_and_markers_no_cursor(source_with_markers, true);
+    let edits = get_range_format_changes(
+        &compilation,
+        "<source>",
+        target_ranges[0],
+        &FormatterConfig::default(),
+        Encoding::Utf8,
+    );
+    expect.assert_eq(&apply_edits(&compilation, edits));
+}
+
+/// Formats after typing `ch` just before the `↘` marker and checks the resulting text.
+fn check_on_type(source_with_markers: &str, ch: &str, expect: &Expect) {
+    let (compilation, cursor_position, _) = compile_with_markers(source_with_markers, true);
+    let edits = get_on_type_format_changes(
+        &compilation,
+        "<source>",
+        cursor_position,
+        ch,
+        &FormatterConfig::default(),
+        Encoding::Utf8,
+    );
+    expect.assert_eq(&apply_edits(&compilation, edits));
+}
+
+#[test]
+fn range_formats_only_selected_statement() {
+    check_range(
+        indoc! {r#"
+        namespace Test {
+            operation Main() : Unit {
+                ◉let x   =  1;◉
+                let y   =  2;
+            }
+        }
+        "#},
+        &expect![[r#"
+            namespace Test {
+                operation Main() : Unit {
+                    let x = 1;
+                    let y   =  2;
+                }
+            }
+        "#]],
+    );
+}
+
+#[test]
+fn range_fixes_indentation_at_start_of_selection() {
+    check_range(
+        indoc! {r#"
+        namespace Test {
+            operation Main() : Unit {
+        ◉let x   =  1;◉
+          let y   =  2;
+            }
+        }
+        "#},
+        &expect![[r#"
+            namespace Test {
+                operation Main() : Unit {
+                    let x = 1;
+              let y   =  2;
+                }
+            }
+        "#]],
+    );
+}
+
+#[test]
+fn on_type_semicolon_formats_statement() {
+    check_on_type(
+        indoc! {r#"
+        namespace Test {
+            operation Main() : Unit {
+                let x   =  1;
+                let y   =  2;↘
+            }
+        }
+        "#},
+        ";",
+        &expect![[r#"
+            namespace Test {
+                operation Main() : Unit {
+                    let x   =  1;
+                    let y = 2;
+                }
+            }
+        "#]],
+    );
+}
+
+#[test]
+fn on_type_closing_brace_formats_enclosing_statement() {
+    check_on_type(
+        indoc! {r#"
+        namespace Test {
+            operation Main() : Unit {
+                if true   {
+                let x   =  1;
+                }↘
+                let y   =  2;
+            }
+        }
+        "#},
+        "}",
+        &expect![[r#"
+            namespace Test {
+                operation Main() : Unit {
+                    if true {
+                        let x = 1;
+                    }
+                    let y   =  2;
+                }
+            }
+        "#]],
+    );
+}
+
+#[test]
+fn on_type_closing_brace_formats_callable() {
+    check_on_type(
+        indoc! {r#"
+        namespace Test {
+            operation Main()   : Unit {
+                let x   =  1;
+            }↘
+        }
+        "#},
+        "}",
+        &expect![[r#"
+            namespace Test {
+                operation Main() : Unit {
+                    let x = 1;
+                }
+            }
+        "#]],
+    );
+}
+
+#[test]
+fn on_type_other_character_has_no_edits() {
+    check_on_type(
+        indoc! {r#"
+        namespace Test {
+            operation Main() : Unit {
+                let x   =  1;↘
+            }
+        }
+        "#},
+        "a",
+        &expect![[r#"
+            namespace Test {
+                operation Main() : Unit {
+                    let x   =  1;
+                }
+            }
+        "#]],
+    );
+}
+
+#[test]
+fn on_type_character_not_before_cursor_has_no_edits() {
+    check_on_type(
+        indoc! {r#"
+        namespace Test {
+            operation Main() : Unit {
+                let x   =  1↘;
+            }
+        }
+        "#},
+        ";",
+        &expect![[r#"
+            namespace Test {
+                operation Main() : Unit {
+                    let x   =  1;
+                }
+            }
+        "#]],
+    );
+}
@@ -0,0 +1,194 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    compilation::Compilation,
+    protocol::{InlayHint, InlayHintKind, InlayHintsConfig},
+    qsc_utils::{into_range, is_known},
+};
+use qsc::{
+    ast::{
+        self,
+        visit::{walk_expr, walk_item, walk_stmt, Visitor},
+        PathKind,
+    },
+    display::Lookup,
+    hir,
+    line_column::{Encoding, Range},
+    resolve, Span,
+};
+
+pub(crate) fn get_inlay_hints(
+    compilation: &Compilation,
+    source_name: &str,
+    (range, config): (Range, InlayHintsConfig),
+    position_encoding: Encoding,
+) -> Vec<InlayHint> {
+    let mut collector = HintCollector {
+        compilation,
+        position_encoding,
+        config,
+        span: compilation.source_range_to_package_span(source_name, range, position_encoding),
+        hints: Vec::new(),
+    };
+    collector.visit_package(&compilation.user_unit().ast.package);
+    // The parameter hints for a call are added before the hints inside its
+    // arguments, so the hints are sorted to return them in document order.
+    collector
+        .hints
+        .sort_by_key(|hint| (hint.position.line, hint.position.column));
+    collector.hints
+}
+
+struct HintCollector<'a> {
+    compilation: &'a Compilation,
+    position_encoding: Encoding,
+    config: InlayHintsConfig,
+    /// Only the nodes that overlap this span are visited.
+    span: Span,
+    hints: Vec<InlayHint>,
+}
+
+impl<'c> HintCollector<'c> {
+    fn overlaps(&self, span: Span) -> bool {
+        span.lo <= self.span.hi && self.span.lo <= span.hi
+    }
+
+    fn push(&mut self, offset: u32, label: String, kind: InlayHintKind) {
+        let position = into_range(
+            self.position_encoding,
+            Span {
+                lo: offset,
+                hi: offset,
+            },
+            &self.compilation.user_unit().sources,
+        )
+        .start;
+        self.hints.push(InlayHint {
+            position,
+            label,
+            kind,
+            padding_left: kind == InlayHintKind::Type,
+            padding_right: kind == InlayHintKind::Parameter,
+        });
+    }
+
+    /// Adds a type hint after each binding in the pattern that has no type annotation.
+    fn type_hints(&mut self, pat: &ast::Pat) {
+        if !self.config.types {
+            return;
+        }
+        match &*pat.kind {
+            ast::PatKind::Bind(name, None) => {
+                if let Some(ty) = self.compilation.get_ty(pat.id) {
+                    if is_known(ty) && self.overlaps(name.span) {
+                        self.push(
+                            name.span.hi,
+                            format!(": {}", ty.display()),
+                            InlayHintKind::Type,
+                        );
+                    }
+                }
+            }
+            ast::PatKind::Paren(item) => self.type_hints(item),
+            ast::PatKind::Tuple(items) => items.iter().for_each(|item| self.type_hints(item)),
+            ast::PatKind::Bind(_, Some(_))
+            | ast::PatKind::Discard(_)
+            | ast::PatKind::Elided
+            | ast::PatKind::Err => {}
+        }
+    }
+
+    /// Adds a parameter name hint before each literal argument.
+    fn parameter_hints(&mut self, params: &hir::Pat, args: &ast::Expr) {
+        match (&params.kind, &*args.kind) {
+            (_, ast::ExprKind::Paren(inner)) => self.parameter_hints(params, inner),
+            (hir::PatKind::Tuple(params), ast::ExprKind::Tuple(args))
+                if params.len() == args.len() =>
+            {
+                for (param, arg) in params.iter().zip(args.iter()) {
+                    self.parameter_hints(param, arg);
+                }
+            }
+            (hir::PatKind::Bind(name), _) if is_literal(args) && self.overlaps(args.span) => {
+                self.push(
+                    args.span.lo,
+                    format!("{}:", name.name),
+                    InlayHintKind::Parameter,
+                );
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the declaration of the callable that the callee expression refers to,
+    /// if it's a direct reference to a global callable, possibly with the `Adjoint` functor.
+    fn callee_decl(&self, callee: &ast::Expr) -> Option<&'c hir::CallableDecl> {
+        match &*callee.kind {
+            ast::ExprKind::Paren(inner)
+            | ast::ExprKind::UnOp(ast::UnOp::Functor(ast::Functor::Adj), inner) => {
+                self.callee_decl(inner)
+            }
+            ast::ExprKind::Path(PathKind::Ok(path)) => {
+                let Some(resolve::Res::Item(item_id, _)) = self.compilation.get_res(path.id) else {
+                    return None;
+                };
+                let (item, _, _) = self
+                    .compilation
+                    .resolve_item_relative_to_user_package(item_id);
+                match &item.kind {
+                    hir::ItemKind::Callable(decl) => Some(decl),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for HintCollector<'_> {
+    fn visit_item(&mut self, item: &'a ast::Item) {
+        if self.overlaps(item.span) {
+            walk_item(self, item);
+        }
+    }
+
+    fn visit_stmt(&mut self, stmt: &'a ast::Stmt) {
+        if !self.overlaps(stmt.span) {
+            return;
+        }
+        if let ast::StmtKind::Local(_, pat, _) = &*stmt.kind {
+            self.type_hints(pat);
+        }
+        walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &'a ast::Expr) {
+        if !self.overlaps(expr.span) {
+            return;
+        }
+        match &*expr.kind {
+            ast::ExprKind::For(pat, _, _) | ast::ExprKind::Lambda(_, pat, _) => {
+                self.type_hints(pat);
+            }
+            ast::ExprKind::Call(callee, args) if self.config.parameter_names => {
+                if let Some(decl) = self.callee_decl(callee) {
+                    self.parameter_hints(&decl.input, args);
+                }
+            }
+            _ => {}
+        }
+        walk_expr(self, expr);
+    }
+}
+
+fn is_literal(expr: &ast::Expr) -> bool {
+    match &*expr.kind {
+        ast::ExprKind::Lit(_) => true,
+        ast::ExprKind::UnOp(ast::UnOp::Neg | ast::UnOp::Pos, inner) => is_literal(inner),
+        _ => false,
+    }
+}
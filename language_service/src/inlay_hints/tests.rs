@@ -0,0 +1,200 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::get_inlay_hints;
+use crate::{
+    protocol::{InlayHintKind, InlayHintsConfig},
+    test_utils::compile_with_fake_stdlib_and_markers_no_cursor,
+    Encoding,
+};
+use expect_test::{expect, Expect};
+use qsc::line_column::{Position, Range};
+use std::fmt::Write;
+
+/// Inserts the hints into the source, between `«»`, and returns the lines that have hints.
+/// Hints are requested for the range between the markers if there are any,
+/// otherwise for the whole source.
+fn check_with_config(source_with_markers: &str, config: InlayHintsConfig, expect: &Expect) {
+    let (compilation, ranges) =
+        compile_with_fake_stdlib_and_markers_no_cursor(source_with_markers, true);
+    let range = ranges.first().copied().unwrap_or(Range {
+        start: Position { line: 0, column: 0 },
+        end: Position {
+            line: u32::MAX,
+            column: 0,
+        },
+    });
+    let hints = get_inlay_hints(&compilation, "<source>", (range, config), Encoding::Utf8);
+
+    let mut actual = compilation
+        .user_unit()
+        .sources
+        .find_by_name("<source>")
+        .expect("source should exist")
+        .contents
+        .to_string();
+    for hint in hints.iter().rev() {
+        let offset = hint
+            .position
+            .to_utf8_byte_offset(Encoding::Utf8, &actual)
+            .try_into()
+            .expect("offset should fit into usize");
+        let padding = |enabled| if enabled { " " } else { "" };
+        let label = format!(
+            "{}«{}»{}",
+            padding(hint.padding_left),
+            hint.label,
+            padding(hint.padding_right)
+        );
+        actual.insert_str(offset, &label);
+    }
+    let lines = actual
+        .lines()
+        .filter(|line| line.contains('«'))
+        .fold(String::new(), |mut output, line| {
+            let _ = writeln!(output, "{}", line.trim());
+            output
+        });
+    expect.assert_eq(&lines);
+}
+
+fn check(source_with_markers: &str, expect: &Expect) {
+    check_with_config(source_with_markers, InlayHintsConfig::default(), expect);
+}
+
+#[test]
+fn local_bindings() {
+    check(
+        r#"
+        namespace Test {
+            function Main() : Unit {
+                let x = 1;
+                mutable y = 2.0;
+                let z : Int = 3;
+                let (a, (b, _)) = (x, (true, "s"));
+            }
+        }"#,
+        &expect![[r#"
+            let x «: Int» = 1;
+            mutable y «: Double» = 2.0;
+            let (a «: Int», (b «: Bool», _)) = (x, (true, "s"));
+        "#]],
+    );
+}
+
+#[test]
+fn for_loops_and_lambda_parameters() {
+    check(
+        r#"
+        namespace Test {
+            function Apply(f : (Int, Int) -> Int) : Unit {}
+            operation Main(qs : Qubit[]) : Unit {
+                for q in qs {}
+                for i in 0..3 {}
+                Apply((a, b) -> a + b + 1);
+            }
+        }"#,
+        &expect![[r#"
+            for q «: Qubit» in qs {}
+            for i «: Int» in 0..3 {}
+            Apply((a «: Int», b «: Int») -> a + b + 1);
+        "#]],
+    );
+}
+
+#[test]
+fn unknown_types_are_not_shown() {
+    check(
+        r#"
+        namespace Test {
+            function Main() : Unit {
+                let x = Missing();
+                let empty = [];
+            }
+        }"#,
+        &expect![[""]],
+    );
+}
+
+#[test]
+fn parameter_names_for_literal_arguments() {
+    check(
+        r#"
+        namespace Test {
+            open FakeStdLib;
+            function Add(a : Int, b : Int) : Int { a + b }
+            operation Main() : Unit {
+                FakeWithParam(4);
+                let x = 1;
+                let sum = Add(x, -2);
+                Adjoint FakeCtlAdj();
+                let nested = Add(Add(1, 2), 3);
+            }
+        }"#,
+        &expect![[r#"
+            FakeWithParam(«x:» 4);
+            let x «: Int» = 1;
+            let sum «: Int» = Add(x, «b:» -2);
+            let nested «: Int» = Add(Add(«a:» 1, «b:» 2), «b:» 3);
+        "#]],
+    );
+}
+
+#[test]
+fn hints_can_be_disabled() {
+    check_with_config(
+        r#"
+        namespace Test {
+            open FakeStdLib;
+            operation Main() : Unit {
+                let x = 1;
+                FakeWithParam(4);
+            }
+        }"#,
+        InlayHintsConfig {
+            types: false,
+            parameter_names: true,
+        },
+        &expect![[r#"
+            FakeWithParam(«x:» 4);
+        "#]],
+    );
+}
+
+#[test]
+fn only_hints_in_range() {
+    check(
+        r#"
+        namespace Test {
+            function Main() : Unit {
+                let x = 1;
+                ◉let y = 2;◉
+                let z = 3;
+            }
+        }"#,
+        &expect![[r#"
+            let y «: Int» = 2;
+        "#]],
+    );
+}
+
+#[test]
+fn hint_kinds_and_padding() {
+    let (compilation, _) = compile_with_fake_stdlib_and_markers_no_cursor(
+        "namespace Test { operation Main() : Unit { let x = 1; FakeStdLib.FakeWithParam(x); } }",
+        true,
+    );
+    let range = Range {
+        start: Position { line: 0, column: 0 },
+        end: Position { line: 1, column: 0 },
+    };
+    let hints = get_inlay_hints(
+        &compilation,
+        "<source>",
+        (range, InlayHintsConfig::default()),
+        Encoding::Utf8,
+    );
+    assert_eq!(hints.len(), 1);
+    assert_eq!(hints[0].kind, InlayHintKind::Type);
+    assert!(hints[0].padding_left && !hints[0].padding_right);
+}
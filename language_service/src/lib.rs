@@ -1,6 +1,7 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
+pub mod call_hierarchy;
 pub mod code_action;
 pub mod code_lens;
 mod compilation;
@@ -8,13 +9,16 @@ pub mod completion;
 pub mod definition;
 pub mod format;
 pub mod hover;
+pub mod inlay_hints;
 mod name_locator;
 pub mod protocol;
 mod qsc_utils;
 pub mod references;
 pub mod rename;
+pub mod semantic_tokens;
 pub mod signature_help;
 mod state;
+pub mod symbols;
 #[cfg(test)]
 mod test_utils;
 #[cfg(test)]
@@ -28,8 +32,10 @@ use futures::channel::{
 use futures_util::StreamExt;
 use log::{trace, warn};
 use protocol::{
-    CodeAction, CodeLens, CompletionList, DiagnosticUpdate, Hover, NotebookMetadata, SignatureHelp,
-    TextEdit, WorkspaceConfigurationUpdate,
+    CallHierarchyIncomingCall, CallHierarchyItem, CallHierarchyOutgoingCall, CodeAction, CodeLens,
+    CompletionList, DiagnosticUpdate, DocumentSymbol, Hover, InlayHint, InlayHintsConfig,
+    NotebookMetadata, SemanticTokens, SemanticTokensDelta, SignatureHelp, TextEdit,
+    WorkspaceConfigurationUpdate, WorkspaceSymbol,
 };
 use qsc::{
     line_column::{Encoding, Position, Range},
@@ -50,6 +56,11 @@ pub struct LanguageService {
     state: Rc<RefCell<CompilationState>>,
     /// Channel for compilation state update messages coming from the client.
     state_updater: Option<UnboundedSender<Update>>,
+    /// The last semantic tokens returned for each document, used to compute deltas.
+    semantic_tokens: RefCell<semantic_tokens::ResultCache>,
+    /// Which kinds of inlay hints to show. These settings don't affect
+    /// compilation, so they're kept here rather than in the compilation state.
+    inlay_hints_config: InlayHintsConfig,
 }
 
 impl LanguageService {
@@ -59,6 +70,8 @@ impl LanguageService {
             position_encoding,
             state: Rc::default(),
             state_updater: Option::default(),
+            semantic_tokens: RefCell::default(),
+            inlay_hints_config: InlayHintsConfig::default(),
         }
     }
 
@@ -122,6 +135,9 @@ impl LanguageService {
     /// LSP: workspace/didChangeConfiguration
     pub fn update_configuration(&mut self, configuration: WorkspaceConfigurationUpdate) {
         trace!("update_configuration: {configuration:?}");
+        if let Some(inlay_hints_config) = configuration.inlay_hints {
+            self.inlay_hints_config = inlay_hints_config;
+        }
         self.send_update(Update::Configuration {
             changed: configuration,
         });
@@ -150,6 +166,7 @@ impl LanguageService {
     /// LSP: textDocument/didClose
     pub fn close_document(&mut self, uri: &str) {
         trace!("close_document: {uri}");
+        self.semantic_tokens.get_mut().remove(uri);
         self.send_update(Update::CloseDocument { uri: uri.into() });
     }
 
@@ -231,7 +248,7 @@ impl LanguageService {
         position: Position,
         include_declaration: bool,
     ) -> Vec<Location> {
-        self.document_op(
+        let mut locations = self.document_op(
             |compilation, uri, position, position_encoding| {
                 references::get_references(
                     compilation,
@@ -244,19 +261,88 @@ impl LanguageService {
             "get_references",
             uri,
             position,
-        )
+        );
+
+        // The other members of a workspace each have their own compilation, so
+        // references in those members are found using the definition's location.
+        let compilation_state = self.state.borrow();
+        let workspace_compilations = compilation_state.get_workspace_compilations(uri);
+        if !workspace_compilations.is_empty() {
+            if let Some(definition) = self.get_definition(uri, position) {
+                for compilation in workspace_compilations {
+                    for location in references::get_references_to_definition(
+                        compilation,
+                        &definition,
+                        self.position_encoding,
+                    ) {
+                        if !locations.contains(&location) {
+                            locations.push(location);
+                        }
+                    }
+                }
+            }
+        }
+
+        locations
     }
 
     /// LSP: textDocument/format
     #[must_use]
     pub fn get_format_changes(&self, uri: &str) -> Vec<TextEdit> {
+        let config = self.state.borrow().get_formatter_config(uri);
         self.document_op(
-            |compilation, uri, (), position_encoding| {
-                format::get_format_changes(compilation, uri, position_encoding)
+            |compilation, uri, config, position_encoding| {
+                format::get_format_changes(compilation, uri, &config, position_encoding)
             },
             "get_format_changes",
             uri,
-            (),
+            config,
+        )
+    }
+
+    /// LSP: textDocument/rangeFormatting
+    #[must_use]
+    pub fn get_range_format_changes(&self, uri: &str, range: Range) -> Vec<TextEdit> {
+        let config = self.state.borrow().get_formatter_config(uri);
+        self.document_op(
+            |compilation, uri, range, position_encoding| {
+                format::get_range_format_changes(
+                    compilation,
+                    uri,
+                    range,
+                    &config,
+                    position_encoding,
+                )
+            },
+            "get_range_format_changes",
+            uri,
+            range,
+        )
+    }
+
+    /// LSP: textDocument/onTypeFormatting
+    #[must_use]
+    pub fn get_on_type_format_changes(
+        &self,
+        uri: &str,
+        position: Position,
+        ch: &str,
+    ) -> Vec<TextEdit> {
+        let config = self.state.borrow().get_formatter_config(uri);
+        self.document_op(
+            |compilation, uri, position, position_encoding| {
+                format::get_on_type_format_changes(
+                    compilation,
+                    uri,
+                    position,
+                    ch,
+                    &config,
+                    position_encoding,
+                )
+            },
+            "get_on_type_format_changes",
+            uri,
+            position,
         )
     }
 
@@ -302,6 +388,121 @@ impl LanguageService {
         )
     }
 
+    /// LSP: textDocument/documentSymbol
+    #[must_use]
+    pub fn get_document_symbols(&self, uri: &str) -> Vec<DocumentSymbol> {
+        self.document_op(
+            |compilation, uri, (), position_encoding| {
+                symbols::get_document_symbols(compilation, uri, position_encoding)
+            },
+            "get_document_symbols",
+            uri,
+            (),
+        )
+    }
+
+    /// LSP: workspace/symbol
+    ///
+    /// Searches all the open projects and notebooks, and their dependencies.
+    #[must_use]
+    pub fn get_workspace_symbols(&self, query: &str) -> Vec<WorkspaceSymbol> {
+        trace!("get_workspace_symbols: query: {query}");
+        let compilation_state = self.state.borrow();
+        let res = symbols::get_workspace_symbols(
+            compilation_state.get_all_compilations(),
+            query,
+            self.position_encoding,
+        );
+        trace!("get_workspace_symbols result: {} symbols", res.len());
+        res
+    }
+
+    /// LSP: textDocument/semanticTokens/full
+    #[must_use]
+    pub fn get_semantic_tokens(&self, uri: &str) -> SemanticTokens {
+        let data = self.document_op(
+            |compilation, uri, (), position_encoding| {
+                semantic_tokens::get_semantic_tokens(compilation, uri, position_encoding)
+            },
+            "get_semantic_tokens",
+            uri,
+            (),
+        );
+        self.semantic_tokens.borrow_mut().insert(uri, data)
+    }
+
+    /// LSP: textDocument/semanticTokens/full/delta
+    #[must_use]
+    pub fn get_semantic_tokens_delta(
+        &self,
+        uri: &str,
+        previous_result_id: &str,
+    ) -> SemanticTokensDelta {
+        let data = self.document_op(
+            |compilation, uri, (), position_encoding| {
+                semantic_tokens::get_semantic_tokens(compilation, uri, position_encoding)
+            },
+            "get_semantic_tokens_delta",
+            uri,
+            (),
+        );
+        self.semantic_tokens
+            .borrow_mut()
+            .delta(uri, previous_result_id, data)
+    }
+
+    /// LSP: textDocument/inlayHint
+    #[must_use]
+    pub fn get_inlay_hints(&self, uri: &str, range: Range) -> Vec<InlayHint> {
+        self.document_op(
+            inlay_hints::get_inlay_hints,
+            "get_inlay_hints",
+            uri,
+            (range, self.inlay_hints_config),
+        )
+    }
+
+    /// LSP: textDocument/prepareCallHierarchy
+    #[must_use]
+    pub fn prepare_call_hierarchy(&self, uri: &str, position: Position) -> Vec<CallHierarchyItem> {
+        self.document_op(
+            call_hierarchy::prepare_call_hierarchy,
+            "prepare_call_hierarchy",
+            uri,
+            position,
+        )
+    }
+
+    /// LSP: callHierarchy/incomingCalls
+    ///
+    /// Searches all the open projects and notebooks, and their dependencies.
+    #[must_use]
+    pub fn get_incoming_calls(&self, item: &CallHierarchyItem) -> Vec<CallHierarchyIncomingCall> {
+        trace!("get_incoming_calls: item: {item:?}");
+        let compilation_state = self.state.borrow();
+        let res = call_hierarchy::get_incoming_calls(
+            compilation_state.get_all_compilations(),
+            item,
+            self.position_encoding,
+        );
+        trace!("get_incoming_calls result: {res:?}");
+        res
+    }
+
+    /// LSP: callHierarchy/outgoingCalls
+    #[must_use]
+    pub fn get_outgoing_calls(&self, item: &CallHierarchyItem) -> Vec<CallHierarchyOutgoingCall> {
+        trace!("get_outgoing_calls: item: {item:?}");
+        let compilation_state = self.state.borrow();
+        let res = call_hierarchy::get_outgoing_calls(
+            compilation_state.get_all_compilations(),
+            item,
+            self.position_encoding,
+        );
+        trace!("get_outgoing_calls result: {res:?}");
+        res
+    }
+
     /// Executes an operation that takes a document uri, using the current compilation for that document.
     /// All "read" operations should go through this method. This method will borrow the current
     /// compilation state to perform the request.
@@ -2,7 +2,8 @@
 // Licensed under the MIT License.
 
 use miette::Diagnostic;
-use qsc::line_column::Range;
+use qsc::line_column::{Position, Range};
+use qsc::location::Location;
 use qsc::{compile, project};
 use qsc::{linter::LintConfig, project::Manifest, target::Profile, LanguageFeatures, PackageType};
 use thiserror::Error;
@@ -14,6 +15,7 @@ pub struct WorkspaceConfigurationUpdate {
     pub package_type: Option<PackageType>,
     pub language_features: Option<LanguageFeatures>,
     pub lints_config: Option<Vec<LintConfig>>,
+    pub inlay_hints: Option<InlayHintsConfig>,
 }
 
 #[derive(Clone, Debug, Diagnostic, Error)]
@@ -182,6 +184,8 @@ pub enum CodeLensCommand {
     Run,
     Estimate,
     Circuit(Option<OperationInfo>),
+    /// Runs the test callable with the given fully qualified name.
+    RunTest(String),
 }
 
 #[derive(Debug)]
@@ -189,3 +193,162 @@ pub struct OperationInfo {
     pub operation: String,
     pub total_num_qubits: u32,
 }
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SymbolKind {
+    Namespace,
+    Function,
+    Operation,
+    Struct,
+    Newtype,
+    Specialization,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct DocumentSymbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// The range enclosing the whole declaration, including its body.
+    pub range: Range,
+    /// The range that should be selected when navigating to the symbol,
+    /// e.g. the name of a callable. Always contained in `range`.
+    pub selection_range: Range,
+    pub children: Vec<DocumentSymbol>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct WorkspaceSymbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// The name of the namespace that declares the symbol.
+    pub container_name: Option<String>,
+    pub location: Location,
+}
+
+/// The kinds of semantic tokens. The order of the variants defines
+/// the token types legend, i.e. the value of each token type in
+/// [`SemanticTokens::data`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SemanticTokenType {
+    Namespace,
+    Struct,
+    TypeParameter,
+    Parameter,
+    Variable,
+    Property,
+    Function,
+    Operation,
+}
+
+/// The modifiers of semantic tokens. The order of the variants defines
+/// the token modifiers legend, i.e. the bit that represents each modifier in
+/// [`SemanticTokens::data`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SemanticTokenModifier {
+    Declaration,
+    Mutable,
+    /// Deprecated syntax: a `newtype` declaration, a struct called as a function,
+    /// or a field accessed with `::`.
+    Deprecated,
+    Adjointable,
+    Controllable,
+    /// A variable or parameter of type `Qubit` or `Qubit[]`.
+    Qubit,
+    /// An item declared outside of the user's package,
+    /// i.e. in the standard library or in a dependency.
+    Library,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct SemanticTokens {
+    /// Identifies these tokens, so that the next request can ask
+    /// for the changes since this result.
+    pub result_id: String,
+    /// Five integers for each token, as defined by LSP: the line and the start
+    /// column, both relative to the previous token, the length, the token type
+    /// and the bit set of token modifiers.
+    pub data: Vec<u32>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SemanticTokensDelta {
+    /// All the tokens, when the previous result is not known.
+    Full(SemanticTokens),
+    /// The edits that transform the data of the previous result into the current one.
+    Edits {
+        result_id: String,
+        edits: Vec<SemanticTokensEdit>,
+    },
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SemanticTokensEdit {
+    /// The index in the previous result's `data` where the edit starts.
+    pub start: u32,
+    pub delete_count: u32,
+    pub data: Vec<u32>,
+}
+
+/// Which kinds of inlay hints are shown.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct InlayHintsConfig {
+    /// Show the inferred types of `let`, `mutable` and `for` bindings,
+    /// and of lambda parameters, when they have no type annotation.
+    pub types: bool,
+    /// Show parameter names before literal arguments at call sites.
+    pub parameter_names: bool,
+}
+
+impl Default for InlayHintsConfig {
+    fn default() -> Self {
+        Self {
+            types: true,
+            parameter_names: true,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum InlayHintKind {
+    Type,
+    Parameter,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct InlayHint {
+    pub position: Position,
+    pub label: String,
+    pub kind: InlayHintKind,
+    pub padding_left: bool,
+    pub padding_right: bool,
+}
+
+/// A callable in a call hierarchy.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CallHierarchyItem {
+    pub name: String,
+    /// Either [`SymbolKind::Function`] or [`SymbolKind::Operation`].
+    pub kind: SymbolKind,
+    /// The namespace that the callable is declared in.
+    pub detail: String,
+    /// The whole declaration of the callable.
+    pub location: Location,
+    /// The name of the callable, in the same source as `location`.
+    pub selection_range: Range,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct CallHierarchyIncomingCall {
+    /// The callable that makes the calls.
+    pub from: CallHierarchyItem,
+    /// The calls, in the source of `from`.
+    pub from_ranges: Vec<Range>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct CallHierarchyOutgoingCall {
+    /// The callable that is called.
+    pub to: CallHierarchyItem,
+    /// The calls, in the source of the callable that makes them.
+    pub from_ranges: Vec<Range>,
+}
@@ -4,7 +4,10 @@
 use crate::compilation::Compilation;
 use qsc::line_column::{Encoding, Range};
 use qsc::location::Location;
-use qsc::{hir::PackageId, SourceMap, Span};
+use qsc::{
+    hir::{ty::Ty, PackageId},
+    SourceMap, Span,
+};
 
 pub(crate) fn into_range(encoding: Encoding, span: Span, source_map: &SourceMap) -> Range {
     let lo_source = source_map
@@ -37,3 +40,14 @@ pub(crate) fn into_location(
         position_encoding,
     )
 }
+
+/// Returns true if the type was fully inferred, without any errors.
+pub(crate) fn is_known(ty: &Ty) -> bool {
+    match ty {
+        Ty::Array(item) => is_known(item),
+        Ty::Arrow(arrow) => is_known(&arrow.input) && is_known(&arrow.output),
+        Ty::Tuple(items) => items.iter().all(is_known),
+        Ty::Param(..) | Ty::Prim(_) | Ty::Udt(..) => true,
+        Ty::Infer(_) | Ty::Err => false,
+    }
+}
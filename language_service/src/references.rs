@@ -40,6 +40,64 @@ pub(crate) fn get_references(
     name_handler.references
 }
 
+/// Finds the references in the user package of `compilation` to the item declared
+/// at `definition`. The declaration can be in the user package or in one of its
+/// dependencies. This is used to find references in the other members of a workspace,
+/// where `definition` was found using a different compilation.
+pub(crate) fn get_references_to_definition(
+    compilation: &Compilation,
+    definition: &Location,
+    position_encoding: Encoding,
+) -> Vec<Location> {
+    if compilation
+        .user_unit()
+        .sources
+        .find_by_name(&definition.source)
+        .is_some()
+    {
+        return get_references(
+            compilation,
+            &definition.source,
+            definition.range.start,
+            position_encoding,
+            false,
+        );
+    }
+
+    for (package_id, unit) in &compilation.package_store {
+        if package_id == compilation.user_package_id {
+            continue;
+        }
+        let Some(source) = unit.sources.find_by_name(&definition.source) else {
+            continue;
+        };
+        let offset = source.offset
+            + definition
+                .range
+                .start
+                .to_utf8_byte_offset(position_encoding, &source.contents);
+        let item = unit
+            .package
+            .items
+            .iter()
+            .find(|(_, item)| match &item.kind {
+                hir::ItemKind::Callable(decl) => decl.name.span.touches(offset),
+                hir::ItemKind::Ty(name, _) => name.span.touches(offset),
+                hir::ItemKind::Namespace(..) | hir::ItemKind::Export(..) => false,
+            });
+        if let Some((item, _)) = item {
+            return ReferenceFinder::new(position_encoding, compilation, false).for_item(
+                &hir::ItemId {
+                    package: Some(package_id),
+                    item,
+                },
+            );
+        }
+    }
+
+    Vec::new()
+}
+
 pub(crate) struct ReferenceFinder<'a> {
     position_encoding: Encoding,
     compilation: &'a Compilation,
@@ -0,0 +1,538 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    compilation::Compilation,
+    protocol::{
+        SemanticTokenModifier, SemanticTokenType, SemanticTokens, SemanticTokensDelta,
+        SemanticTokensEdit,
+    },
+};
+use qsc::{
+    ast::{
+        self,
+        visit::{walk_expr, walk_item, walk_pat, walk_stmt, walk_ty, walk_ty_def, Visitor},
+        Idents, Mutability, NodeId, PathKind,
+    },
+    display::Lookup,
+    hir::{
+        self,
+        ty::{FunctorSetValue, Prim},
+    },
+    line_column::Encoding,
+    resolve, Span,
+};
+use rustc_hash::FxHashMap;
+
+/// A classified identifier, before encoding.
+#[derive(Clone, Copy, Debug)]
+#[allow(clippy::struct_field_names)]
+struct Token {
+    span: Span,
+    token_type: SemanticTokenType,
+    modifiers: u32,
+}
+
+/// Returns the semantic tokens of the source, encoded as described in [`SemanticTokens::data`].
+pub(crate) fn get_semantic_tokens(
+    compilation: &Compilation,
+    source_name: &str,
+    position_encoding: Encoding,
+) -> Vec<u32> {
+    let source_span = compilation.package_span_of_source(source_name);
+    let mut collector = TokenCollector {
+        compilation,
+        source_span,
+        tokens: Vec::new(),
+        locals: FxHashMap::default(),
+        in_params: false,
+        mutability: Mutability::Immutable,
+    };
+    collector.visit_package(&compilation.user_unit().ast.package);
+
+    let source = compilation
+        .user_unit()
+        .sources
+        .find_by_name(source_name)
+        .expect("source should exist in the user source map");
+    let mut tokens = collector.tokens;
+    tokens.retain(|token| source_span.contains(token.span.lo));
+    tokens.sort_by_key(|token| token.span.lo);
+    // Tokens can't overlap, which could otherwise happen for nodes that are
+    // visited twice, e.g. generated nodes that reuse the span of the source.
+    tokens.dedup_by_key(|token| token.span.lo);
+
+    encode(&tokens, &source.contents, source.offset, position_encoding)
+}
+
+/// Encodes the tokens, which must be sorted, as five integers each, with positions relative
+/// to the previous token. Tokens that span multiple lines are dropped.
+fn encode(tokens: &[Token], contents: &str, offset: u32, position_encoding: Encoding) -> Vec<u32> {
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(contents.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    let code_units = |s: &str| {
+        match position_encoding {
+            Encoding::Utf8 => u32::try_from(s.len()),
+            Encoding::Utf16 => u32::try_from(s.encode_utf16().count()),
+        }
+        .expect("token length should fit into u32")
+    };
+
+    let mut data = Vec::with_capacity(tokens.len() * 5);
+    let (mut prev_line, mut prev_column) = (0, 0);
+    for token in tokens {
+        let lo = (token.span.lo - offset) as usize;
+        let hi = ((token.span.hi - offset) as usize).min(contents.len());
+        let (Some(text), Some(before)) = (contents.get(lo..hi), contents.get(..lo)) else {
+            continue;
+        };
+        if text.is_empty() || text.contains('\n') {
+            continue;
+        }
+
+        let line = line_starts.partition_point(|&start| start <= lo) - 1;
+        let column = code_units(&before[line_starts[line]..]);
+        let line = u32::try_from(line).expect("line number should fit into u32");
+        let delta_column = if line == prev_line {
+            column - prev_column
+        } else {
+            column
+        };
+
+        data.extend([
+            line - prev_line,
+            delta_column,
+            code_units(text),
+            token.token_type as u32,
+            token.modifiers,
+        ]);
+        (prev_line, prev_column) = (line, column);
+    }
+    data
+}
+
+fn modifier_bit(modifier: SemanticTokenModifier) -> u32 {
+    1 << modifier as u32
+}
+
+struct TokenCollector<'a> {
+    compilation: &'a Compilation,
+    source_span: Span,
+    tokens: Vec<Token>,
+    /// The token type and modifiers of each local declaration seen so far,
+    /// so that references to the local can be classified the same way.
+    locals: FxHashMap<NodeId, (SemanticTokenType, u32)>,
+    in_params: bool,
+    mutability: Mutability,
+}
+
+impl TokenCollector<'_> {
+    fn push(&mut self, span: Span, token_type: SemanticTokenType, modifiers: u32) {
+        self.tokens.push(Token {
+            span,
+            token_type,
+            modifiers,
+        });
+    }
+
+    /// Classifies a reference to, or the declaration of, a global item.
+    fn item_token(&self, res: &resolve::Res) -> Option<(SemanticTokenType, u32)> {
+        let (resolve::Res::Item(item_id, _) | resolve::Res::ExportedItem(item_id, _)) = res else {
+            return None;
+        };
+        let (item, _, resolved_item_id) = self
+            .compilation
+            .resolve_item_relative_to_user_package(item_id);
+
+        let mut modifiers = 0;
+        if resolved_item_id.package != Some(self.compilation.user_package_id) {
+            modifiers |= modifier_bit(SemanticTokenModifier::Library);
+        }
+
+        let token_type = match &item.kind {
+            hir::ItemKind::Callable(decl) => {
+                if matches!(
+                    decl.functors,
+                    FunctorSetValue::Adj | FunctorSetValue::CtlAdj
+                ) {
+                    modifiers |= modifier_bit(SemanticTokenModifier::Adjointable);
+                }
+                if matches!(
+                    decl.functors,
+                    FunctorSetValue::Ctl | FunctorSetValue::CtlAdj
+                ) {
+                    modifiers |= modifier_bit(SemanticTokenModifier::Controllable);
+                }
+                match decl.kind {
+                    hir::CallableKind::Function => SemanticTokenType::Function,
+                    hir::CallableKind::Operation => SemanticTokenType::Operation,
+                }
+            }
+            hir::ItemKind::Ty(..) => SemanticTokenType::Struct,
+            hir::ItemKind::Namespace(..) => SemanticTokenType::Namespace,
+            hir::ItemKind::Export(..) => return None,
+        };
+        Some((token_type, modifiers))
+    }
+
+    fn item_decl(&mut self, name: &ast::Ident, extra_modifiers: u32) {
+        if let Some((token_type, modifiers)) = self
+            .compilation
+            .get_res(name.id)
+            .and_then(|res| self.item_token(res))
+        {
+            self.push(
+                name.span,
+                token_type,
+                modifiers | extra_modifiers | modifier_bit(SemanticTokenModifier::Declaration),
+            );
+        }
+    }
+
+    /// Returns true if the resolution is a struct, which makes a call to it
+    /// a deprecated function constructor.
+    fn is_struct(&self, res: &resolve::Res) -> bool {
+        let (resolve::Res::Item(item_id, _) | resolve::Res::ExportedItem(item_id, _)) = res else {
+            return false;
+        };
+        let (item, ..) = self
+            .compilation
+            .resolve_item_relative_to_user_package(item_id);
+        matches!(&item.kind, hir::ItemKind::Ty(_, udt) if udt.is_struct())
+    }
+
+    /// Returns true if the field is accessed with the deprecated `::` operator.
+    fn is_double_colon_access(&self, record: &ast::Expr, field: &ast::Ident) -> bool {
+        let Some(source) = self
+            .compilation
+            .user_unit()
+            .sources
+            .find_by_offset(record.span.hi)
+        else {
+            return false;
+        };
+        let lo = (record.span.hi - source.offset) as usize;
+        let hi = (field.span.lo - source.offset) as usize;
+        source
+            .contents
+            .get(lo..hi)
+            .is_some_and(|op| op.contains("::"))
+    }
+
+    /// Classifies the parts of a path. Global items get the extra modifiers.
+    fn path(&mut self, path: &ast::Path, extra_modifiers: u32) {
+        let names = &self.compilation.user_unit().ast.names;
+        if let Some((node_id, parts)) = resolve::path_as_field_accessor(names, path) {
+            // A local followed by field accesses.
+            let (first, fields) = parts
+                .split_first()
+                .expect("paths should have at least one part");
+            if let Some(&(token_type, modifiers)) = self.locals.get(&node_id) {
+                self.push(first.span, token_type, modifiers);
+            }
+            for field in fields {
+                self.push(field.span, SemanticTokenType::Property, 0);
+            }
+            return;
+        }
+
+        match self.compilation.get_res(path.id) {
+            Some(resolve::Res::Local(node_id)) => {
+                if let Some(&(token_type, modifiers)) = self.locals.get(node_id) {
+                    self.push(path.name.span, token_type, modifiers);
+                }
+            }
+            Some(res) => {
+                if let Some((token_type, modifiers)) = self.item_token(res) {
+                    self.push(path.name.span, token_type, modifiers | extra_modifiers);
+                    for segment in path.segments.iter().flatten() {
+                        self.push(segment.span, SemanticTokenType::Namespace, 0);
+                    }
+                }
+            }
+            None => {}
+        }
+    }
+
+    fn params(&mut self, pat: &ast::Pat) {
+        self.in_params = true;
+        self.visit_pat(pat);
+        self.in_params = false;
+    }
+}
+
+impl<'a> Visitor<'a> for TokenCollector<'_> {
+    fn visit_package(&mut self, package: &'a ast::Package) {
+        // The entry expression is generated, so it's not visited.
+        package.nodes.iter().for_each(|node| match node {
+            ast::TopLevelNode::Namespace(namespace) => self.visit_namespace(namespace),
+            ast::TopLevelNode::Stmt(stmt) => self.visit_stmt(stmt),
+        });
+    }
+
+    fn visit_namespace(&mut self, namespace: &'a ast::Namespace) {
+        // Only the current source is of interest.
+        if self.source_span.contains(namespace.span.lo) {
+            // The name of an implicit namespace comes from the file name,
+            // and its span covers the whole file rather than the name.
+            for ident in namespace.name.iter().filter(|ident| {
+                ident.span.hi - ident.span.lo == u32::try_from(ident.name.len()).unwrap_or(0)
+            }) {
+                self.push(
+                    ident.span,
+                    SemanticTokenType::Namespace,
+                    modifier_bit(SemanticTokenModifier::Declaration),
+                );
+            }
+            namespace
+                .items
+                .iter()
+                .for_each(|item| self.visit_item(item));
+        }
+    }
+
+    fn visit_item(&mut self, item: &'a ast::Item) {
+        match &*item.kind {
+            ast::ItemKind::Callable(decl) => self.item_decl(&decl.name, 0),
+            ast::ItemKind::Ty(name, _) => {
+                // `newtype` declarations are deprecated in favor of `struct`.
+                self.item_decl(name, modifier_bit(SemanticTokenModifier::Deprecated));
+            }
+            ast::ItemKind::Struct(decl) => self.item_decl(&decl.name, 0),
+            ast::ItemKind::Open(PathKind::Ok(path), _) => {
+                for ident in path.iter() {
+                    self.push(ident.span, SemanticTokenType::Namespace, 0);
+                }
+                return;
+            }
+            _ => {}
+        }
+        walk_item(self, item);
+    }
+
+    fn visit_callable_decl(&mut self, decl: &'a ast::CallableDecl) {
+        for param in &decl.generics {
+            self.push(
+                param.span,
+                SemanticTokenType::TypeParameter,
+                modifier_bit(SemanticTokenModifier::Declaration),
+            );
+        }
+        self.params(&decl.input);
+        self.visit_ty(&decl.output);
+        match &*decl.body {
+            ast::CallableBody::Block(block) => self.visit_block(block),
+            ast::CallableBody::Specs(specs) => specs.iter().for_each(|s| self.visit_spec_decl(s)),
+        }
+    }
+
+    fn visit_spec_decl(&mut self, decl: &'a ast::SpecDecl) {
+        if let ast::SpecBody::Impl(pat, block) = &decl.body {
+            self.params(pat);
+            self.visit_block(block);
+        }
+    }
+
+    fn visit_ty_def(&mut self, def: &'a ast::TyDef) {
+        match &*def.kind {
+            ast::TyDefKind::Field(name, ty) => {
+                if let Some(name) = name {
+                    self.push(
+                        name.span,
+                        SemanticTokenType::Property,
+                        modifier_bit(SemanticTokenModifier::Declaration),
+                    );
+                }
+                self.visit_ty(ty);
+            }
+            _ => walk_ty_def(self, def),
+        }
+    }
+
+    fn visit_field_def(&mut self, def: &'a ast::FieldDef) {
+        self.push(
+            def.name.span,
+            SemanticTokenType::Property,
+            modifier_bit(SemanticTokenModifier::Declaration),
+        );
+        self.visit_ty(&def.ty);
+    }
+
+    fn visit_stmt(&mut self, stmt: &'a ast::Stmt) {
+        match &*stmt.kind {
+            ast::StmtKind::Local(mutability, pat, value) => {
+                self.visit_expr(value);
+                self.mutability = *mutability;
+                self.visit_pat(pat);
+                self.mutability = Mutability::Immutable;
+            }
+            _ => walk_stmt(self, stmt),
+        }
+    }
+
+    fn visit_pat(&mut self, pat: &'a ast::Pat) {
+        match &*pat.kind {
+            ast::PatKind::Bind(name, ty) => {
+                let token_type = if self.in_params {
+                    SemanticTokenType::Parameter
+                } else {
+                    SemanticTokenType::Variable
+                };
+                let mut modifiers = 0;
+                if self.mutability == Mutability::Mutable {
+                    modifiers |= modifier_bit(SemanticTokenModifier::Mutable);
+                }
+                if self.compilation.get_ty(pat.id).is_some_and(is_qubit) {
+                    modifiers |= modifier_bit(SemanticTokenModifier::Qubit);
+                }
+                self.locals.insert(name.id, (token_type, modifiers));
+                self.push(
+                    name.span,
+                    token_type,
+                    modifiers | modifier_bit(SemanticTokenModifier::Declaration),
+                );
+                if let Some(ty) = ty {
+                    self.visit_ty(ty);
+                }
+            }
+            _ => walk_pat(self, pat),
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &'a ast::Expr) {
+        match &*expr.kind {
+            ast::ExprKind::Field(record, field) => {
+                self.visit_expr(record);
+                let modifiers = if self.is_double_colon_access(record, field) {
+                    modifier_bit(SemanticTokenModifier::Deprecated)
+                } else {
+                    0
+                };
+                self.push(field.span, SemanticTokenType::Property, modifiers);
+            }
+            ast::ExprKind::Path(PathKind::Ok(path)) => {
+                // Calling a struct like a function is deprecated in favor of `new`.
+                let modifiers = match self.compilation.get_res(path.id) {
+                    Some(res) if self.is_struct(res) => {
+                        modifier_bit(SemanticTokenModifier::Deprecated)
+                    }
+                    _ => 0,
+                };
+                self.path(path, modifiers);
+            }
+            ast::ExprKind::Lambda(_, pat, body) => {
+                self.params(pat);
+                self.visit_expr(body);
+            }
+            _ => walk_expr(self, expr),
+        }
+    }
+
+    fn visit_field_assign(&mut self, assign: &'a ast::FieldAssign) {
+        self.push(assign.field.span, SemanticTokenType::Property, 0);
+        self.visit_expr(&assign.value);
+    }
+
+    fn visit_ty(&mut self, ty: &'a ast::Ty) {
+        match &*ty.kind {
+            ast::TyKind::Param(param) => self.push(param.span, SemanticTokenType::TypeParameter, 0),
+            _ => walk_ty(self, ty),
+        }
+    }
+
+    fn visit_path_kind(&mut self, path: &'a PathKind) {
+        if let PathKind::Ok(path) = path {
+            self.visit_path(path);
+        }
+    }
+
+    fn visit_path(&mut self, path: &'a ast::Path) {
+        self.path(path, 0);
+    }
+}
+
+fn is_qubit(ty: &hir::ty::Ty) -> bool {
+    match ty {
+        hir::ty::Ty::Prim(Prim::Qubit) => true,
+        hir::ty::Ty::Array(item) => is_qubit(item),
+        _ => false,
+    }
+}
+
+/// Remembers the last result returned for each document,
+/// so that later requests can be answered with the changes since.
+#[derive(Debug, Default)]
+pub(crate) struct ResultCache {
+    next_result_id: u64,
+    results: FxHashMap<String, SemanticTokens>,
+}
+
+impl ResultCache {
+    /// Stores the tokens as the latest result for the document.
+    pub(crate) fn insert(&mut self, uri: &str, data: Vec<u32>) -> SemanticTokens {
+        self.next_result_id += 1;
+        let result = SemanticTokens {
+            result_id: self.next_result_id.to_string(),
+            data,
+        };
+        self.results.insert(
+            uri.to_string(),
+            SemanticTokens {
+                result_id: result.result_id.clone(),
+                data: result.data.clone(),
+            },
+        );
+        result
+    }
+
+    /// Stores the tokens as the latest result for the document, and returns the edits
+    /// from the previous result, if it's the one the client has.
+    pub(crate) fn delta(
+        &mut self,
+        uri: &str,
+        previous_result_id: &str,
+        data: Vec<u32>,
+    ) -> SemanticTokensDelta {
+        let previous = self
+            .results
+            .remove(uri)
+            .filter(|previous| previous.result_id == previous_result_id);
+        let result = self.insert(uri, data);
+        match previous {
+            Some(previous) => SemanticTokensDelta::Edits {
+                edits: diff(&previous.data, &result.data).into_iter().collect(),
+                result_id: result.result_id,
+            },
+            None => SemanticTokensDelta::Full(result),
+        }
+    }
+
+    pub(crate) fn remove(&mut self, uri: &str) {
+        self.results.remove(uri);
+    }
+}
+
+/// Returns the single edit that replaces the range where the data differs, if any.
+fn diff(old: &[u32], new: &[u32]) -> Option<SemanticTokensEdit> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    if prefix == old.len() && prefix == new.len() {
+        return None;
+    }
+
+    let as_u32 = |n: usize| u32::try_from(n).expect("token data length should fit into u32");
+    Some(SemanticTokensEdit {
+        start: as_u32(prefix),
+        delete_count: as_u32(old.len() - prefix - suffix),
+        data: new[prefix..new.len() - suffix].to_vec(),
+    })
+}
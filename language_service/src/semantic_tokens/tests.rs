@@ -0,0 +1,346 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{diff, get_semantic_tokens, ResultCache};
+use crate::{
+    protocol::{SemanticTokenModifier, SemanticTokenType, SemanticTokensDelta},
+    test_utils::compile_with_fake_stdlib_and_markers_no_cursor,
+    Encoding,
+};
+use expect_test::{expect, Expect};
+use std::fmt::Write;
+
+const TOKEN_TYPES: [SemanticTokenType; 8] = [
+    SemanticTokenType::Namespace,
+    SemanticTokenType::Struct,
+    SemanticTokenType::TypeParameter,
+    SemanticTokenType::Parameter,
+    SemanticTokenType::Variable,
+    SemanticTokenType::Property,
+    SemanticTokenType::Function,
+    SemanticTokenType::Operation,
+];
+
+const TOKEN_MODIFIERS: [SemanticTokenModifier; 7] = [
+    SemanticTokenModifier::Declaration,
+    SemanticTokenModifier::Mutable,
+    SemanticTokenModifier::Deprecated,
+    SemanticTokenModifier::Adjointable,
+    SemanticTokenModifier::Controllable,
+    SemanticTokenModifier::Qubit,
+    SemanticTokenModifier::Library,
+];
+
+/// Decodes the tokens of the source into one line per token,
+/// with the token text, type and modifiers.
+fn check(source: &str, expect: &Expect) {
+    let (compilation, _) = compile_with_fake_stdlib_and_markers_no_cursor(source, true);
+    let data = get_semantic_tokens(&compilation, "<source>", Encoding::Utf8);
+    assert_eq!(data.len() % 5, 0, "expected five integers per token");
+
+    let lines = source.lines().collect::<Vec<_>>();
+    let (mut line, mut column) = (0, 0);
+    let mut actual = String::new();
+    for token in data.chunks(5) {
+        if token[0] > 0 {
+            line += token[0] as usize;
+            column = 0;
+        }
+        column += token[1] as usize;
+        let text = &lines[line][column..column + token[2] as usize];
+        let modifiers = TOKEN_MODIFIERS
+            .iter()
+            .enumerate()
+            .filter(|(bit, _)| token[4] & (1 << bit) != 0)
+            .map(|(_, modifier)| format!("{modifier:?}"))
+            .collect::<Vec<_>>();
+        let _ = writeln!(
+            actual,
+            "{text} {:?} [{}]",
+            TOKEN_TYPES[token[3] as usize],
+            modifiers.join(", ")
+        );
+    }
+    expect.assert_eq(&actual);
+}
+
+#[test]
+fn legend_matches_enum_order() {
+    for (i, token_type) in TOKEN_TYPES.iter().enumerate() {
+        assert_eq!(*token_type as usize, i);
+    }
+    for (i, modifier) in TOKEN_MODIFIERS.iter().enumerate() {
+        assert_eq!(*modifier as usize, i);
+    }
+}
+
+#[test]
+fn callables_parameters_and_locals() {
+    check(
+        r#"
+        namespace Test {
+            function Add(a : Int, b : Int) : Int { a + b }
+            operation Main() : Unit {
+                let x = Add(1, 2);
+            }
+        }"#,
+        &expect![[r#"
+            Test Namespace [Declaration]
+            Add Function [Declaration]
+            a Parameter [Declaration]
+            b Parameter [Declaration]
+            a Parameter []
+            b Parameter []
+            Main Operation [Declaration]
+            x Variable [Declaration]
+            Add Function []
+        "#]],
+    );
+}
+
+#[test]
+fn qubits_mutables_and_library_items() {
+    check(
+        r#"
+        namespace Test {
+            open FakeStdLib;
+            operation Main() : Unit {
+                use q = Qubit();
+                mutable count = 0;
+                FakeCtlAdj();
+                set count += 1;
+            }
+        }"#,
+        &expect![[r#"
+            Test Namespace [Declaration]
+            FakeStdLib Namespace []
+            Main Operation [Declaration]
+            q Variable [Declaration, Qubit]
+            count Variable [Declaration, Mutable]
+            FakeCtlAdj Operation [Adjointable, Controllable, Library]
+            count Variable [Mutable]
+        "#]],
+    );
+}
+
+#[test]
+fn qubit_array_parameter() {
+    check(
+        r#"
+        namespace Test {
+            operation ApplyAll(qs : Qubit[]) : Unit {
+                for q in qs {}
+            }
+        }"#,
+        &expect![[r#"
+            Test Namespace [Declaration]
+            ApplyAll Operation [Declaration]
+            qs Parameter [Declaration, Qubit]
+            q Variable [Declaration, Qubit]
+            qs Parameter [Qubit]
+        "#]],
+    );
+}
+
+#[test]
+fn deprecated_syntax() {
+    check(
+        r#"
+        namespace Test {
+            struct Pair { First : Int, Second : Int }
+            function Main() : Unit {
+                let p = Pair(1, 2);
+                let q = new Pair { First = 1, Second = 2 };
+                let f = p::First;
+                let s = q.Second;
+            }
+        }"#,
+        &expect![[r#"
+            Test Namespace [Declaration]
+            Pair Struct [Declaration]
+            First Property [Declaration]
+            Second Property [Declaration]
+            Main Function [Declaration]
+            p Variable [Declaration]
+            Pair Struct [Deprecated]
+            q Variable [Declaration]
+            Pair Struct []
+            First Property []
+            Second Property []
+            f Variable [Declaration]
+            p Variable []
+            First Property [Deprecated]
+            s Variable [Declaration]
+            q Variable []
+            Second Property []
+        "#]],
+    );
+}
+
+#[test]
+fn structs_fields_and_type_parameters() {
+    check(
+        r#"
+        namespace Test {
+            struct Pair { First : Int, Second : Int }
+            newtype Wrapper = (Inner : Int);
+            function Identity<'T>(value : 'T) : 'T { value }
+            function Main() : Unit {
+                let p = new Pair { First = 1, Second = 2 };
+                let f = p.First;
+            }
+        }"#,
+        &expect![[r#"
+            Test Namespace [Declaration]
+            Pair Struct [Declaration]
+            First Property [Declaration]
+            Second Property [Declaration]
+            Wrapper Struct [Declaration, Deprecated]
+            Inner Property [Declaration]
+            Identity Function [Declaration]
+            'T TypeParameter [Declaration]
+            value Parameter [Declaration]
+            'T TypeParameter []
+            'T TypeParameter []
+            value Parameter []
+            Main Function [Declaration]
+            p Variable [Declaration]
+            Pair Struct []
+            First Property []
+            Second Property []
+            f Variable [Declaration]
+            p Variable []
+            First Property []
+        "#]],
+    );
+}
+
+#[test]
+fn qualified_path() {
+    check(
+        r#"
+        namespace Test {
+            operation Main() : Unit {
+                FakeStdLib.Fake();
+            }
+        }"#,
+        &expect![[r#"
+            Test Namespace [Declaration]
+            Main Operation [Declaration]
+            FakeStdLib Namespace []
+            Fake Operation [Library]
+        "#]],
+    );
+}
+
+#[test]
+fn positions_are_relative_and_use_encoding() {
+    let source = "namespace A {\n    function F() : Unit { let s = \"😀\"; let x = s; }\n}";
+    let (compilation, _) = compile_with_fake_stdlib_and_markers_no_cursor(source, true);
+
+    let utf8 = get_semantic_tokens(&compilation, "<source>", Encoding::Utf8);
+    expect![[r#"
+        [
+            [0, 10, 1, 0, 1],
+            [1, 13, 1, 6, 1],
+            [0, 17, 1, 4, 1],
+            [0, 16, 1, 4, 1],
+            [0, 4, 1, 4, 0],
+        ]
+    "#]]
+    .assert_eq(&format_chunks(&utf8));
+
+    let utf16 = get_semantic_tokens(&compilation, "<source>", Encoding::Utf16);
+    expect![[r#"
+        [
+            [0, 10, 1, 0, 1],
+            [1, 13, 1, 6, 1],
+            [0, 17, 1, 4, 1],
+            [0, 14, 1, 4, 1],
+            [0, 4, 1, 4, 0],
+        ]
+    "#]]
+    .assert_eq(&format_chunks(&utf16));
+}
+
+fn format_chunks(data: &[u32]) -> String {
+    let mut output = "[\n".to_string();
+    for chunk in data.chunks(5) {
+        let _ = writeln!(output, "    {chunk:?},");
+    }
+    output.push_str("]\n");
+    output
+}
+
+#[test]
+fn diff_replaces_changed_range() {
+    expect![[r#"
+        Some(
+            SemanticTokensEdit {
+                start: 2,
+                delete_count: 1,
+                data: [
+                    9,
+                ],
+            },
+        )
+    "#]]
+    .assert_debug_eq(&diff(&[1, 2, 3, 4, 5], &[1, 2, 9, 4, 5]));
+}
+
+#[test]
+fn diff_appends() {
+    expect![[r#"
+        Some(
+            SemanticTokensEdit {
+                start: 3,
+                delete_count: 0,
+                data: [
+                    4,
+                    5,
+                ],
+            },
+        )
+    "#]]
+    .assert_debug_eq(&diff(&[1, 2, 3], &[1, 2, 3, 4, 5]));
+}
+
+#[test]
+fn diff_of_equal_data_is_empty() {
+    assert_eq!(diff(&[1, 2, 3], &[1, 2, 3]), None);
+}
+
+#[test]
+fn delta_from_previous_result() {
+    let mut cache = ResultCache::default();
+    let first = cache.insert("a.qs", vec![0, 1, 2, 3, 4]);
+
+    let delta = cache.delta("a.qs", &first.result_id, vec![0, 1, 2, 3, 5]);
+    expect![[r#"
+        Edits {
+            result_id: "2",
+            edits: [
+                SemanticTokensEdit {
+                    start: 4,
+                    delete_count: 1,
+                    data: [
+                        5,
+                    ],
+                },
+            ],
+        }
+    "#]]
+    .assert_debug_eq(&delta);
+}
+
+#[test]
+fn delta_from_unknown_result_returns_all_tokens() {
+    let mut cache = ResultCache::default();
+    let _ = cache.insert("a.qs", vec![0, 1, 2, 3, 4]);
+
+    let delta = cache.delta("a.qs", "unknown", vec![0, 1, 2, 3, 5]);
+    assert!(
+        matches!(&delta, SemanticTokensDelta::Full(tokens) if tokens.data == [0, 1, 2, 3, 5]),
+        "expected all the tokens, got {delta:?}"
+    );
+}
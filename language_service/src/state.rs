@@ -9,12 +9,12 @@ use super::protocol::{DiagnosticUpdate, NotebookMetadata};
 use crate::protocol::{ErrorKind, WorkspaceConfigurationUpdate};
 use log::{debug, trace};
 use miette::Diagnostic;
-use qsc::{compile, project};
+use qsc::{compile, formatter::FormatterConfig, project};
 use qsc::{target::Profile, LanguageFeatures, PackageType};
 use qsc_linter::LintConfig;
-use qsc_project::{FileSystemAsync, JSProjectHost, PackageCache, Project};
+use qsc_project::{FileSystemAsync, JSProjectHost, PackageCache, Project, Workspace};
 use rustc_hash::{FxHashMap, FxHashSet};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::{cell::RefCell, fmt::Debug, mem::take, rc::Rc, sync::Arc};
 
 #[derive(Default, Debug)]
@@ -40,6 +40,11 @@ pub(super) struct CompilationState {
     /// This map doesn't necessarily contain ALL the documents that
     /// make up a compilation - only the ones that are currently open.
     open_documents: FxHashMap<DocumentUri, OpenDocument>,
+    /// Compilations of projects that are members of a workspace, mapped to the
+    /// path of the workspace manifest. All the members of a workspace are compiled
+    /// while any of them has an open document, so that references can be found
+    /// across members.
+    workspace_members: FxHashMap<CompilationUri, Arc<str>>,
 }
 
 type CompilationUri = Arc<str>;
@@ -82,6 +87,7 @@ pub struct PartialConfiguration {
     pub package_type: Option<PackageType>,
     pub language_features: Option<LanguageFeatures>,
     pub lints_config: Vec<LintConfig>,
+    pub formatter_config: FormatterConfig,
 }
 
 pub(super) struct CompilationStateUpdater<'a> {
@@ -139,13 +145,19 @@ impl<'a> CompilationStateUpdater<'a> {
         let doc_uri: Arc<str> = Arc::from(uri);
         let text: Arc<str> = Arc::from(text);
 
-        let project = match self.load_manifest(&doc_uri).await {
-            Ok(Some(p)) => p,
-            Ok(None) => Project::from_single_file(doc_uri.clone(), text.clone()),
-            Err(errors) => Project {
-                errors,
-                ..Project::from_single_file(doc_uri.clone(), text.clone())
-            },
+        let (project, workspace) = match self.load_manifest(&doc_uri).await {
+            Ok(Some(loaded)) => loaded,
+            Ok(None) => (
+                Project::from_single_file(doc_uri.clone(), text.clone()),
+                None,
+            ),
+            Err(errors) => (
+                Project {
+                    errors,
+                    ..Project::from_single_file(doc_uri.clone(), text.clone())
+                },
+                None,
+            ),
         };
 
         let compilation_uri = project.path.clone();
@@ -174,16 +186,21 @@ impl<'a> CompilationStateUpdater<'a> {
 
         self.insert_buffer_aware_compilation(project);
 
+        if let Some(workspace) = workspace {
+            self.insert_workspace_members(&compilation_uri, workspace);
+        }
+
         self.publish_diagnostics();
     }
 
     /// Attempts to resolve a manifest for the given document uri.
     /// If a manifest is found, returns the manifest uri along
-    /// with the sources for the project
+    /// with the sources for the project, and the rest of the workspace
+    /// if the project is a workspace member.
     async fn load_manifest(
         &self,
         doc_uri: &Arc<str>,
-    ) -> Result<Option<Project>, Vec<project::Error>> {
+    ) -> Result<Option<(Project, Option<Workspace>)>, Vec<project::Error>> {
         let dir = self.project_host.find_manifest_directory(doc_uri).await;
 
         self.load_manifest_from_dir(dir).await
@@ -192,15 +209,18 @@ impl<'a> CompilationStateUpdater<'a> {
     async fn load_manifest_from_dir(
         &self,
         dir: Option<Arc<str>>,
-    ) -> Result<Option<Project>, Vec<project::Error>> {
+    ) -> Result<Option<(Project, Option<Workspace>)>, Vec<project::Error>> {
         if let Some(dir) = dir {
             let dir = PathBuf::from(dir.to_string());
+            if let Some((project, workspace)) = self.load_workspace_for_project(&dir).await {
+                return Ok(Some((project, Some(workspace))));
+            }
             let res = self
                 .project_host
                 .load_project(&dir, Some(&self.cache))
                 .await;
             match res {
-                Ok(proj) => Ok(Some(proj)),
+                Ok(proj) => Ok(Some((proj, None))),
                 Err(e) => {
                     debug!("failed to load manifest: {e:?}, defaulting to single-file mode");
                     Err(e)
@@ -212,6 +232,56 @@ impl<'a> CompilationStateUpdater<'a> {
         }
     }
 
+    /// If the project in the given directory is a member of a workspace, loads the
+    /// whole workspace, and returns the project separately from the other members.
+    async fn load_workspace_for_project(&self, dir: &Path) -> Option<(Project, Workspace)> {
+        let workspace_dir = self.project_host.find_workspace(dir).await?;
+        let mut workspace = match self
+            .project_host
+            .load_workspace(&workspace_dir, Some(&self.cache))
+            .await
+        {
+            Ok(workspace) => workspace,
+            Err(e) => {
+                debug!("failed to load workspace: {e:?}, loading the project on its own");
+                return None;
+            }
+        };
+        let index = workspace
+            .members
+            .iter()
+            .position(|member| Path::new(&*member.path).parent() == Some(dir))?;
+        let mut project = workspace.members.remove(index);
+        // Errors about the workspace itself, such as members that failed to load,
+        // are reported along with the project.
+        project.errors.extend(take(&mut workspace.errors));
+        Some((project, workspace))
+    }
+
+    /// Records the workspace that a compilation belongs to, and compiles the other
+    /// members of the workspace that don't have a compilation yet.
+    fn insert_workspace_members(&mut self, compilation_uri: &CompilationUri, workspace: Workspace) {
+        let new_members = self.with_state_mut(|state| {
+            state
+                .workspace_members
+                .insert(compilation_uri.clone(), workspace.path.clone());
+            workspace
+                .members
+                .into_iter()
+                .filter(|member| {
+                    state
+                        .workspace_members
+                        .insert(member.path.clone(), workspace.path.clone());
+                    !state.compilations.contains_key(&member.path)
+                })
+                .collect::<Vec<_>>()
+        });
+
+        for member in new_members {
+            self.insert_buffer_aware_compilation(member);
+        }
+    }
+
     /// This function takes a `LoadedProject` and creates a compilation out of them.
     /// It checks currently open documents and uses those buffers instead of any
     /// sources provided in the vector, effectively prioritizing open document contents
@@ -235,6 +305,7 @@ impl<'a> CompilationStateUpdater<'a> {
                     loaded_project.package_graph_sources.root.language_features,
                 ),
                 lints_config: loaded_project.lints,
+                formatter_config: loaded_project.formatter,
                 package_type: loaded_project.package_graph_sources.root.package_type.map(
                     |x| match x {
                         qsc_project::PackageType::Exe => qsc::PackageType::Exe,
@@ -275,7 +346,7 @@ impl<'a> CompilationStateUpdater<'a> {
             // If the project is still open, update it so that it
             // uses the disk contents instead of the open buffer contents
             // for this document
-            if let Ok(Some(project)) = project {
+            if let Ok(Some((project, _))) = project {
                 self.insert_buffer_aware_compilation(project);
             }
         }
@@ -301,14 +372,29 @@ impl<'a> CompilationStateUpdater<'a> {
 
     fn maybe_close_project(&mut self, compilation_uri: &Arc<str>) -> bool {
         self.with_state_mut(|state| {
+            // Workspace members stay open as long as any member has an open document.
+            let compilation_uris: Vec<CompilationUri> =
+                match state.workspace_members.get(compilation_uri) {
+                    Some(workspace) => state
+                        .workspace_members
+                        .iter()
+                        .filter(|(_, w)| *w == workspace)
+                        .map(|(uri, _)| uri.clone())
+                        .collect(),
+                    None => vec![compilation_uri.clone()],
+                };
+
             // if there are no remaining open documents with the project's compilation URI
             if state
                 .open_documents
                 .iter()
-                .all(|(_uri, doc)| doc.compilation != *compilation_uri)
+                .all(|(_uri, doc)| !compilation_uris.contains(&doc.compilation))
             {
                 trace!("closing project {:?}", compilation_uri);
-                state.compilations.remove(compilation_uri);
+                for uri in &compilation_uris {
+                    state.compilations.remove(uri);
+                    state.workspace_members.remove(uri);
+                }
                 return true;
             }
             false
@@ -332,7 +418,8 @@ impl<'a> CompilationStateUpdater<'a> {
             .load_manifest_from_dir(notebook_metadata.project_root.clone().map(Arc::from))
             .await
             .ok()
-            .flatten();
+            .flatten()
+            .map(|(project, _)| project);
 
         self.with_state_mut(|state| {
             let compilation_uri: Arc<str> = notebook_uri.into();
@@ -347,7 +434,12 @@ impl<'a> CompilationStateUpdater<'a> {
                 language_features: Some(notebook_metadata.language_features),
                 lints_config: notebook_metadata
                     .manifest
-                    .map(|manifest| manifest.lints)
+                    .as_ref()
+                    .map(|manifest| manifest.lints.clone())
+                    .unwrap_or_default(),
+                formatter_config: notebook_metadata
+                    .manifest
+                    .map(|manifest| manifest.formatter)
                     .unwrap_or_default(),
             };
             let configuration = merge_configurations(&notebook_configuration, &configuration);
@@ -558,6 +650,39 @@ impl CompilationState {
             panic!("document associated with compilation that hasn't been initialized ({compilation_uri})" ,)
         }).0)
     }
+
+    /// Returns every compilation, i.e. all the open projects, notebooks and standalone documents.
+    pub(crate) fn get_all_compilations(&self) -> impl Iterator<Item = &Compilation> {
+        self.compilations
+            .values()
+            .map(|(compilation, _)| compilation)
+    }
+
+    /// Returns the compilations of the other members of the workspace that the
+    /// document's project belongs to. Empty if the project isn't a workspace member.
+    pub(crate) fn get_workspace_compilations(&self, uri: &str) -> Vec<&Compilation> {
+        let Some(doc) = self.open_documents.get(uri) else {
+            return Vec::new();
+        };
+        let Some(workspace) = self.workspace_members.get(&doc.compilation) else {
+            return Vec::new();
+        };
+        self.workspace_members
+            .iter()
+            .filter(|(compilation_uri, w)| *w == workspace && **compilation_uri != doc.compilation)
+            .filter_map(|(compilation_uri, _)| self.compilations.get(compilation_uri))
+            .map(|(compilation, _)| compilation)
+            .collect()
+    }
+
+    /// Returns the formatter configuration of the compilation that the document belongs to.
+    pub(crate) fn get_formatter_config(&self, uri: &str) -> FormatterConfig {
+        self.open_documents
+            .get(uri)
+            .and_then(|doc| self.compilations.get(&doc.compilation))
+            .map(|(_, configuration)| configuration.formatter_config.clone())
+            .unwrap_or_default()
+    }
 }
 
 fn map_errors_to_docs(
@@ -2143,6 +2143,74 @@ async fn error_from_dependency_reported() {
     .assert_debug_eq(&received_errors.borrow());
 }
 
+#[tokio::test]
+async fn workspace_members_compiled_while_any_member_is_open() {
+    let fs = FsNode::Dir(
+        [dir(
+            "workspace",
+            [
+                file(
+                    "qsharp.workspace.json",
+                    r#"{ "members": ["lib", "app"], "lints": [{ "lint": "divisionByZero", "level": "allow" }] }"#,
+                ),
+                dir(
+                    "lib",
+                    [
+                        file("qsharp.json", "{}"),
+                        dir("src", [file("lib.qs", "function Helper() : Int { 1 / 0 }")]),
+                    ],
+                ),
+                dir(
+                    "app",
+                    [
+                        file(
+                            "qsharp.json",
+                            r#"{ "dependencies": { "MyLib": { "path": "../lib" } } }"#,
+                        ),
+                        dir("src", [file("app.qs", "function Main() : Unit {}")]),
+                    ],
+                ),
+            ],
+        )]
+        .into_iter()
+        .collect(),
+    );
+
+    let fs = Rc::new(RefCell::new(fs));
+    let received_errors = RefCell::new(Vec::new());
+    let mut updater = new_updater_with_file_system(&received_errors, &fs);
+
+    updater
+        .update_document("workspace/app/src/app.qs", 1, "function Main() : Unit {}")
+        .await;
+
+    // The library is compiled too, and the lint is allowed by the workspace.
+    assert_compilation_uris(
+        &updater,
+        &expect![[r#"
+            [
+                "workspace/app/qsharp.json",
+                "workspace/lib/qsharp.json",
+            ]
+        "#]],
+    );
+    expect_errors(
+        &received_errors,
+        &expect![[r#"
+            []
+        "#]],
+    );
+
+    updater.close_document("workspace/app/src/app.qs").await;
+
+    assert_compilation_uris(
+        &updater,
+        &expect![[r#"
+            []
+        "#]],
+    );
+}
+
 type ErrorInfo = (
     String,
     Option<u32>,
@@ -2231,6 +2299,13 @@ fn assert_compilation_sources(updater: &CompilationStateUpdater<'_>, expected: &
     expected.assert_eq(&compilation_sources);
 }
 
+fn assert_compilation_uris(updater: &CompilationStateUpdater<'_>, expected: &Expect) {
+    let state = updater.state.try_borrow().expect("borrow should succeed");
+    let mut compilation_uris = state.compilations.keys().collect::<Vec<_>>();
+    compilation_uris.sort();
+    expected.assert_debug_eq(&compilation_uris);
+}
+
 fn assert_open_documents(updater: &CompilationStateUpdater<'_>, expected: &Expect) {
     let state = updater.state.try_borrow().expect("borrow should succeed");
     expected.assert_debug_eq(&state.open_documents);
@@ -2259,7 +2334,7 @@ fn check_state(
 
 /// Checks that the lints config is being loaded from the qsharp.json manifest
 async fn check_lints_config(updater: &CompilationStateUpdater<'_>, expected_config: &Expect) {
-    let manifest = updater
+    let (manifest, _) = updater
         .load_manifest(&"project/src/this_file.qs".into())
         .await
         .expect("manifest should load successfully")
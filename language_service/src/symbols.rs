@@ -0,0 +1,239 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    compilation::Compilation,
+    protocol::{DocumentSymbol, SymbolKind, WorkspaceSymbol},
+    qsc_utils::{into_location, into_range},
+};
+use qsc::{
+    ast::{self, CallableBody, Idents, StmtKind, TopLevelNode},
+    line_column::{Encoding, Position, Range},
+    SourceMap, Span,
+};
+use rustc_hash::FxHashSet;
+use std::{rc::Rc, sync::Arc};
+
+pub(crate) fn get_document_symbols(
+    compilation: &Compilation,
+    source_name: &str,
+    position_encoding: Encoding,
+) -> Vec<DocumentSymbol> {
+    let unit = compilation.user_unit();
+    let source_span = compilation.package_span_of_source(source_name);
+    let builder = DocumentSymbolBuilder {
+        position_encoding,
+        sources: &unit.sources,
+    };
+
+    unit.ast
+        .package
+        .nodes
+        .iter()
+        .filter_map(|node| match node {
+            TopLevelNode::Namespace(namespace) if source_span.contains(namespace.span.lo) => {
+                Some(builder.namespace(namespace))
+            }
+            // Items declared at the top level of a notebook cell.
+            TopLevelNode::Stmt(stmt) => match &*stmt.kind {
+                StmtKind::Item(item) if source_span.contains(item.span.lo) => builder.item(item),
+                _ => None,
+            },
+            TopLevelNode::Namespace(_) => None,
+        })
+        .collect()
+}
+
+/// The maximum number of symbols returned for a workspace symbol query.
+const MAX_WORKSPACE_SYMBOLS: usize = 100;
+
+/// The scheme of the source names of the core and standard libraries.
+const LIBRARY_SOURCE_SCHEME: &str = "qsharp-library-source:";
+
+/// Finds the callables, structs and newtypes whose names fuzzy match `query`,
+/// in all the given compilations and their dependencies, including the standard library.
+/// Namespaces are not included since a namespace can be declared across many files.
+/// Symbols are ordered by how well their names match, with library symbols after all others,
+/// and at most [`MAX_WORKSPACE_SYMBOLS`] are returned.
+pub(crate) fn get_workspace_symbols<'a>(
+    compilations: impl Iterator<Item = &'a Compilation>,
+    query: &str,
+    position_encoding: Encoding,
+) -> Vec<WorkspaceSymbol> {
+    let mut symbols = Vec::new();
+    // The same package can be part of several compilations, e.g. the standard library,
+    // so symbols are deduplicated by name and location.
+    let mut seen: FxHashSet<(Arc<str>, Position, Rc<str>)> = FxHashSet::default();
+
+    for compilation in compilations {
+        for (package_id, unit) in &compilation.package_store {
+            for node in &unit.ast.package.nodes {
+                let (container_name, items) = match node {
+                    TopLevelNode::Namespace(namespace) => {
+                        (Some(namespace.name.full_name()), &namespace.items[..])
+                    }
+                    TopLevelNode::Stmt(stmt) => match &*stmt.kind {
+                        StmtKind::Item(item) => (None, std::slice::from_ref(item)),
+                        _ => continue,
+                    },
+                };
+
+                for item in items {
+                    let Some((name, kind)) = item_name_and_kind(item) else {
+                        continue;
+                    };
+                    let Some(quality) = match_quality(query, &name.name) else {
+                        continue;
+                    };
+
+                    let location =
+                        into_location(position_encoding, compilation, name.span, package_id);
+                    if seen.insert((
+                        location.source.clone(),
+                        location.range.start,
+                        name.name.clone(),
+                    )) {
+                        let is_library = location.source.starts_with(LIBRARY_SOURCE_SCHEME);
+                        symbols.push((
+                            is_library,
+                            quality,
+                            WorkspaceSymbol {
+                                name: name.name.to_string(),
+                                kind,
+                                container_name: container_name.as_ref().map(ToString::to_string),
+                                location,
+                            },
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    symbols.sort_by(|(a_library, a_quality, a), (b_library, b_quality, b)| {
+        (a_library, a_quality, &a.name).cmp(&(b_library, b_quality, &b.name))
+    });
+    symbols
+        .into_iter()
+        .take(MAX_WORKSPACE_SYMBOLS)
+        .map(|(_, _, symbol)| symbol)
+        .collect()
+}
+
+struct DocumentSymbolBuilder<'a> {
+    position_encoding: Encoding,
+    sources: &'a SourceMap,
+}
+
+impl DocumentSymbolBuilder<'_> {
+    fn namespace(&self, namespace: &ast::Namespace) -> DocumentSymbol {
+        DocumentSymbol {
+            name: namespace.name.full_name().to_string(),
+            kind: SymbolKind::Namespace,
+            range: self.range(namespace.span),
+            selection_range: self.range(namespace.name.full_span()),
+            children: namespace
+                .items
+                .iter()
+                .filter_map(|item| self.item(item))
+                .collect(),
+        }
+    }
+
+    fn item(&self, item: &ast::Item) -> Option<DocumentSymbol> {
+        let (name, kind) = item_name_and_kind(item)?;
+        let children = match &*item.kind {
+            ast::ItemKind::Callable(decl) => match &*decl.body {
+                CallableBody::Block(_) => Vec::new(),
+                CallableBody::Specs(specs) => specs
+                    .iter()
+                    .map(|spec| DocumentSymbol {
+                        name: spec.spec.to_string(),
+                        kind: SymbolKind::Specialization,
+                        range: self.range(spec.span),
+                        selection_range: self.range(spec.span),
+                        children: Vec::new(),
+                    })
+                    .collect(),
+            },
+            _ => Vec::new(),
+        };
+
+        Some(DocumentSymbol {
+            name: name.name.to_string(),
+            kind,
+            range: self.range(item.span),
+            selection_range: self.range(name.span),
+            children,
+        })
+    }
+
+    fn range(&self, span: Span) -> Range {
+        into_range(self.position_encoding, span, self.sources)
+    }
+}
+
+/// Returns the name and symbol kind of an item that declares a symbol, or `None`
+/// for items such as `open` and `import` that don't.
+fn item_name_and_kind(item: &ast::Item) -> Option<(&ast::Ident, SymbolKind)> {
+    let (name, kind) = match &*item.kind {
+        ast::ItemKind::Callable(decl) => (
+            &*decl.name,
+            match decl.kind {
+                ast::CallableKind::Function => SymbolKind::Function,
+                ast::CallableKind::Operation => SymbolKind::Operation,
+            },
+        ),
+        ast::ItemKind::Struct(decl) => (&*decl.name, SymbolKind::Struct),
+        ast::ItemKind::Ty(name, _) => (&**name, SymbolKind::Newtype),
+        ast::ItemKind::Err | ast::ItemKind::Open(..) | ast::ItemKind::ImportOrExport(_) => {
+            return None
+        }
+    };
+
+    // Declarations with parse errors can have empty names.
+    if name.name.is_empty() {
+        None
+    } else {
+        Some((name, kind))
+    }
+}
+
+/// How closely a name matches a workspace symbol query, from best to worst.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum MatchQuality {
+    Exact,
+    Prefix,
+    Substring,
+    Fuzzy,
+}
+
+/// Returns how closely `name` matches `query`, ignoring case, or `None` if it doesn't match.
+fn match_quality(query: &str, name: &str) -> Option<MatchQuality> {
+    let query = query.to_lowercase();
+    let name = name.to_lowercase();
+    if name == query {
+        Some(MatchQuality::Exact)
+    } else if name.starts_with(&query) {
+        Some(MatchQuality::Prefix)
+    } else if name.contains(&query) {
+        Some(MatchQuality::Substring)
+    } else if fuzzy_match(&query, &name) {
+        Some(MatchQuality::Fuzzy)
+    } else {
+        None
+    }
+}
+
+/// Returns true if the characters of `query` appear in `name` in order, ignoring case.
+/// An empty query matches every name.
+fn fuzzy_match(query: &str, name: &str) -> bool {
+    let mut name_chars = name.chars().flat_map(char::to_lowercase);
+    query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|q| name_chars.any(|c| c == q))
+}
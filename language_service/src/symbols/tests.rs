@@ -0,0 +1,289 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{
+    fuzzy_match, get_document_symbols, get_workspace_symbols, match_quality, MatchQuality,
+    MAX_WORKSPACE_SYMBOLS,
+};
+use crate::{
+    protocol::{DocumentSymbol, WorkspaceSymbol},
+    test_utils::{
+        compile_notebook_with_fake_stdlib, compile_with_fake_stdlib_and_markers_no_cursor,
+    },
+    Encoding,
+};
+use expect_test::{expect, Expect};
+use qsc::line_column::Position;
+use std::fmt::Write;
+
+fn format_document_symbols(symbols: &[DocumentSymbol], depth: usize, out: &mut String) {
+    for symbol in symbols {
+        let key = |p: Position| (p.line, p.column);
+        assert!(
+            key(symbol.range.start) <= key(symbol.selection_range.start)
+                && key(symbol.selection_range.end) <= key(symbol.range.end),
+            "selection range should be contained in range: {symbol:?}"
+        );
+        writeln!(
+            out,
+            "{}{:?} {}",
+            "  ".repeat(depth),
+            symbol.kind,
+            symbol.name
+        )
+        .expect("writing to string should succeed");
+        format_document_symbols(&symbol.children, depth + 1, out);
+    }
+}
+
+fn flatten(symbols: &[DocumentSymbol]) -> Vec<&DocumentSymbol> {
+    symbols
+        .iter()
+        .flat_map(|symbol| std::iter::once(symbol).chain(flatten(&symbol.children)))
+        .collect()
+}
+
+/// Checks the outline of the document, and that each range between a pair of
+/// markers is the range of one of the symbols.
+fn check_document_symbols(source_with_markers: &str, expect: &Expect) {
+    let (compilation, expected_ranges) =
+        compile_with_fake_stdlib_and_markers_no_cursor(source_with_markers, true);
+    let symbols = get_document_symbols(&compilation, "<source>", Encoding::Utf8);
+
+    let all_symbols = flatten(&symbols);
+    for expected_range in &expected_ranges {
+        assert!(
+            all_symbols.iter().any(|s| s.range == *expected_range),
+            "expected range not found in document symbols: {expected_range:?}"
+        );
+    }
+
+    let mut actual = String::new();
+    format_document_symbols(&symbols, 0, &mut actual);
+    expect.assert_eq(&actual);
+}
+
+fn format_workspace_symbols(symbols: &[WorkspaceSymbol]) -> String {
+    symbols.iter().fold(String::new(), |mut output, symbol| {
+        let _ = writeln!(
+            output,
+            "{:?} {} in {} ({})",
+            symbol.kind,
+            symbol.name,
+            symbol.container_name.as_deref().unwrap_or("<none>"),
+            symbol.location.source
+        );
+        output
+    })
+}
+
+#[test]
+fn callables_and_types() {
+    check_document_symbols(
+        r#"
+        namespace Test {
+            open FakeStdLib;
+            ◉operation Foo() : Unit {}◉
+            ◉function Bar() : Int { 0 }◉
+            ◉struct Pair { a : Int, b : Int }◉
+            ◉newtype Wrapper = Int;◉
+        }"#,
+        &expect![[r#"
+            Namespace Test
+              Operation Foo
+              Function Bar
+              Struct Pair
+              Newtype Wrapper
+        "#]],
+    );
+}
+
+#[test]
+fn item_range_includes_attributes() {
+    check_document_symbols(
+        r#"
+        namespace Test {
+            ◉@EntryPoint()
+            operation Main() : Unit {}◉
+        }"#,
+        &expect![[r#"
+            Namespace Test
+              Operation Main
+        "#]],
+    );
+}
+
+#[test]
+fn specializations() {
+    check_document_symbols(
+        r#"
+        namespace Test {
+            operation Foo(q : Qubit) : Unit is Adj + Ctl {
+                ◉body ... {}◉
+                ◉adjoint self;◉
+                ◉controlled (cs, ...) {}◉
+                ◉controlled adjoint invert;◉
+            }
+        }"#,
+        &expect![[r#"
+            Namespace Test
+              Operation Foo
+                Specialization body
+                Specialization adjoint
+                Specialization controlled
+                Specialization controlled adjoint
+        "#]],
+    );
+}
+
+#[test]
+fn multiple_namespaces() {
+    check_document_symbols(
+        r#"
+        namespace A {
+            function F() : Unit {}
+        }
+        namespace B.C {
+            function G() : Unit {}
+        }"#,
+        &expect![[r#"
+            Namespace A
+              Function F
+            Namespace B.C
+              Function G
+        "#]],
+    );
+}
+
+#[test]
+fn notebook_cells() {
+    let compilation = compile_notebook_with_fake_stdlib(
+        [
+            ("cell1", "operation Foo() : Unit {}"),
+            ("cell2", "function Bar() : Unit {}\nstruct Pair { a : Int }"),
+        ]
+        .into_iter(),
+    );
+
+    let mut actual = String::new();
+    format_document_symbols(
+        &get_document_symbols(&compilation, "cell2", Encoding::Utf8),
+        0,
+        &mut actual,
+    );
+    expect![[r#"
+        Function Bar
+        Struct Pair
+    "#]]
+    .assert_eq(&actual);
+}
+
+#[test]
+fn workspace_symbols_include_dependencies() {
+    let (compilation, _) = compile_with_fake_stdlib_and_markers_no_cursor(
+        r#"
+        namespace Test {
+            operation FakeWorkspaceOp() : Unit {}
+            function NotAMatch() : Unit {}
+        }"#,
+        true,
+    );
+
+    let symbols = get_workspace_symbols(std::iter::once(&compilation), "fakew", Encoding::Utf8);
+    expect![[r#"
+        Operation FakeWorkspaceOp in Test (<source>)
+        Operation FakeWithParam in FakeStdLib (qsharp-library-source:<std>)
+        Operation FakeWithTypeParam in FakeStdLib (qsharp-library-source:<std>)
+    "#]]
+    .assert_eq(&format_workspace_symbols(&symbols));
+}
+
+#[test]
+fn workspace_symbols_are_deduplicated() {
+    let (compilation, _) = compile_with_fake_stdlib_and_markers_no_cursor(
+        r#"
+        namespace Test {
+            struct FakeStructTwo { x : Int }
+        }"#,
+        true,
+    );
+
+    let symbols = get_workspace_symbols(
+        [&compilation, &compilation].into_iter(),
+        "FakeStruct",
+        Encoding::Utf8,
+    );
+    expect![[r#"
+        Struct FakeStructTwo in Test (<source>)
+        Struct FakeStruct in FakeStdLib (qsharp-library-source:<std>)
+    "#]]
+    .assert_eq(&format_workspace_symbols(&symbols));
+}
+
+#[test]
+fn fuzzy_matching() {
+    assert!(fuzzy_match("", "Anything"));
+    assert!(fuzzy_match("ghz", "PrepareGHZState"));
+    assert!(fuzzy_match("PGS", "PrepareGHZState"));
+    assert!(!fuzzy_match("ZG", "PrepareGHZState"));
+    assert!(!fuzzy_match("PrepareGHZStates", "PrepareGHZState"));
+}
+
+#[test]
+fn workspace_symbols_are_ordered_by_match_quality() {
+    let (compilation, _) = compile_with_fake_stdlib_and_markers_no_cursor(
+        r#"
+        namespace Test {
+            function PrepareState() : Unit {}
+            function State() : Unit {}
+            function StateVector() : Unit {}
+            function SwapTargetEntry() : Unit {}
+        }"#,
+        true,
+    );
+
+    let symbols = get_workspace_symbols(std::iter::once(&compilation), "state", Encoding::Utf8);
+    expect![[r#"
+        Function State in Test (<source>)
+        Function StateVector in Test (<source>)
+        Function PrepareState in Test (<source>)
+        Function SwapTargetEntry in Test (<source>)
+    "#]]
+    .assert_eq(&format_workspace_symbols(&symbols));
+}
+
+#[test]
+fn workspace_symbols_are_capped() {
+    let source = format!(
+        "namespace Test {{ {} }}",
+        (0..=MAX_WORKSPACE_SYMBOLS).fold(String::new(), |mut output, i| {
+            let _ = write!(output, "function Symbol{i}() : Unit {{}} ");
+            output
+        })
+    );
+    let (compilation, _) = compile_with_fake_stdlib_and_markers_no_cursor(&source, true);
+
+    let symbols = get_workspace_symbols(std::iter::once(&compilation), "symbol", Encoding::Utf8);
+    assert_eq!(symbols.len(), MAX_WORKSPACE_SYMBOLS);
+    assert!(symbols
+        .iter()
+        .all(|symbol| symbol.name.starts_with("Symbol")));
+}
+
+#[test]
+fn match_qualities() {
+    assert_eq!(match_quality("state", "State"), Some(MatchQuality::Exact));
+    assert_eq!(
+        match_quality("state", "StateVector"),
+        Some(MatchQuality::Prefix)
+    );
+    assert_eq!(
+        match_quality("state", "PrepareState"),
+        Some(MatchQuality::Substring)
+    );
+    assert_eq!(
+        match_quality("state", "SwapTargetEntry"),
+        Some(MatchQuality::Fuzzy)
+    );
+    assert_eq!(match_quality("state", "Measure"), None);
+}
@@ -143,6 +143,8 @@ fn compile_project_with_markers_cursor_optional(
             language_features: LanguageFeatures::default(),
             dependencies: FxHashMap::default(),
             package_type: None,
+            version: None,
+            version_requirements: FxHashMap::default(),
         },
         packages: FxHashMap::default(),
     };
@@ -155,6 +157,8 @@ fn compile_project_with_markers_cursor_optional(
                 language_features: LanguageFeatures::default(),
                 dependencies: FxHashMap::default(),
                 package_type: None,
+                version: None,
+                version_requirements: FxHashMap::default(),
             },
         );
 
@@ -181,6 +185,8 @@ fn compile_project_with_markers_cursor_optional(
                 language_features: LanguageFeatures::default(),
                 dependencies: FxHashMap::default(),
                 package_type: Some(qsc_project::PackageType::Lib),
+                version: None,
+                version_requirements: FxHashMap::default(),
             },
         );
 
@@ -185,6 +185,81 @@ async fn document_in_project() {
     println!("document_in_project : end");
 }
 
+#[allow(clippy::await_holding_refcell_ref)]
+#[tokio::test]
+async fn references_across_workspace_members() {
+    run_async_ls_test(|ls, _| async move {
+        let mut ls = ls.borrow_mut();
+
+        // Only the library is open, but the references in the app that
+        // depends on it should still be found.
+        ls.update_document(
+            "workspace/lib/src/Lib.qs",
+            1,
+            "namespace Lib { function Helper() : Unit {} export Helper; }",
+        );
+
+        ls.pending_updates().wait().await;
+
+        let references = ls.get_references(
+            "workspace/lib/src/Lib.qs",
+            Position {
+                line: 0,
+                column: 26,
+            },
+            true,
+        );
+
+        expect![[r#"
+            [
+                Location {
+                    source: "workspace/lib/src/Lib.qs",
+                    range: Range {
+                        start: Position {
+                            line: 0,
+                            column: 25,
+                        },
+                        end: Position {
+                            line: 0,
+                            column: 31,
+                        },
+                    },
+                },
+                Location {
+                    source: "workspace/lib/src/Lib.qs",
+                    range: Range {
+                        start: Position {
+                            line: 0,
+                            column: 51,
+                        },
+                        end: Position {
+                            line: 0,
+                            column: 57,
+                        },
+                    },
+                },
+                Location {
+                    source: "workspace/app/src/App.qs",
+                    range: Range {
+                        start: Position {
+                            line: 0,
+                            column: 51,
+                        },
+                        end: Position {
+                            line: 0,
+                            column: 57,
+                        },
+                    },
+                },
+            ]
+        "#]]
+        .assert_debug_eq(&references);
+
+        ls.stop_updates();
+    })
+    .await;
+}
+
 // the below tests test the asynchronous behavior of the language service.
 // we use `get_completions` as a rough analog for all document operations, as
 // they all go through the same `document_op` infrastructure.
@@ -365,22 +440,59 @@ thread_local! { static TEST_FS: Rc<RefCell<FsNode>> = Rc::new(RefCell::new(test_
 
 fn test_fs() -> FsNode {
     FsNode::Dir(
-        [dir(
-            "project",
-            [
-                file("qsharp.json", "{}"),
-                dir(
-                    "src",
-                    [
-                        file(
-                            "other_file.qs",
-                            "namespace OtherFile { operation Other() : Unit {} }",
-                        ),
-                        file("this_file.qs", "namespace Foo { }"),
-                    ],
-                ),
-            ],
-        )]
+        [
+            dir(
+                "project",
+                [
+                    file("qsharp.json", "{}"),
+                    dir(
+                        "src",
+                        [
+                            file(
+                                "other_file.qs",
+                                "namespace OtherFile { operation Other() : Unit {} }",
+                            ),
+                            file("this_file.qs", "namespace Foo { }"),
+                        ],
+                    ),
+                ],
+            ),
+            dir(
+                "workspace",
+                [
+                    file("qsharp.workspace.json", r#"{ "members": ["lib", "app"] }"#),
+                    dir(
+                        "lib",
+                        [
+                            file("qsharp.json", "{}"),
+                            dir(
+                                "src",
+                                [file(
+                                    "Lib.qs",
+                                    "namespace Lib { function Helper() : Unit {} export Helper; }",
+                                )],
+                            ),
+                        ],
+                    ),
+                    dir(
+                        "app",
+                        [
+                            file(
+                                "qsharp.json",
+                                r#"{ "dependencies": { "MyLib": { "path": "../lib" } } }"#,
+                            ),
+                            dir(
+                                "src",
+                                [file(
+                                    "App.qs",
+                                    "namespace App { function Main() : Unit { MyLib.Lib.Helper(); } }",
+                                )],
+                            ),
+                        ],
+                    ),
+                ],
+            ),
+        ]
         .into_iter()
         .collect(),
     )
@@ -229,4 +229,13 @@ impl JSProjectHost for TestProjectHost {
     ) -> miette::Result<Arc<str>> {
         unimplemented!()
     }
+
+    async fn resolve_github_commit(
+        &self,
+        _owner: &str,
+        _repo: &str,
+        _ref: &str,
+    ) -> miette::Result<Arc<str>> {
+        unimplemented!()
+    }
 }
@@ -11,6 +11,7 @@ use crate::{operation::Operation, Error, SquareMatrix, TOLERANCE};
 use nalgebra::{DMatrix, DVector};
 
 /// An instrument is the means by which we make measurements on a quantum system.
+#[derive(Clone)]
 pub struct Instrument {
     operations: Vec<Operation>,
     summed_operation: SquareMatrix,
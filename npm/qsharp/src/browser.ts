@@ -154,19 +154,31 @@ export function getLanguageServiceWorker(
 export { StepResultId } from "../lib/web/qsc_wasm.js";
 export type {
   IBreakpointSpan,
+  ICallHierarchyIncomingCall,
+  ICallHierarchyItem,
+  ICallHierarchyOutgoingCall,
   ICodeAction,
   ICodeLens,
   IDocFile,
+  IDocumentSymbol,
+  IInlayHint,
   ILocation,
+  INoiseModel,
   IOperationInfo,
+  IOperationMatrix,
   IPosition,
   IProjectConfig,
   IProjectHost,
   IQSharpError,
   IRange,
+  ISemanticTokens,
+  ISemanticTokensDelta,
   IStackFrame,
   IStructStepResult,
   IWorkspaceEdit,
+  IWorkspaceSymbol,
+  NoiseConfig,
+  PauliProbabilities,
   ProjectLoader,
   VSDiagnostic,
 } from "../lib/web/qsc_wasm.js";
@@ -4,7 +4,10 @@
 import { type Circuit as CircuitData } from "@microsoft/quantum-viz.js/lib/circuit.js";
 import {
   IDocFile,
+  INoiseModel,
   IOperationInfo,
+  NoiseConfig,
+  IOperationMatrix,
   IPackageGraphSources,
   IProgramConfig as wasmIProgramConfig,
   TargetProfile,
@@ -50,10 +53,14 @@ export interface ICompiler {
     expr: string,
     shots: number,
     eventHandler: IQscEventTarget,
+    noise?: NoiseConfig,
+    noiseModel?: INoiseModel,
   ): Promise<void>;
 
   getQir(program: ProgramConfig): Promise<string>;
 
+  getQasm3(program: ProgramConfig): Promise<string>;
+
   getEstimates(program: ProgramConfig, params: string): Promise<string>;
 
   getCircuit(
@@ -62,6 +69,12 @@ export interface ICompiler {
     operation?: IOperationInfo,
   ): Promise<CircuitData>;
 
+  getOperationMatrix(
+    program: ProgramConfig,
+    operation: string,
+    numQubits: number,
+  ): Promise<IOperationMatrix>;
+
   getDocumentation(additionalProgram?: ProgramConfig): Promise<IDocFile[]>;
 
   checkExerciseSolution(
@@ -118,6 +131,9 @@ export class Compiler implements ICompiler {
         listDirectory: async () => [],
         resolvePath: async () => null,
         fetchGithub: async () => "",
+        resolveGithubCommit: async () => {
+          throw new Error("GitHub dependencies are not supported");
+        },
         findManifestDirectory: async () => null,
       },
     );
@@ -159,6 +175,8 @@ export class Compiler implements ICompiler {
     expr: string,
     shots: number,
     eventHandler: IQscEventTarget,
+    noise?: NoiseConfig,
+    noiseModel?: INoiseModel,
   ): Promise<void> {
     // All results are communicated as events, but if there is a compiler error (e.g. an invalid
     // entry expression or similar), it may throw on run. The caller should expect this promise
@@ -168,6 +186,8 @@ export class Compiler implements ICompiler {
       expr,
       (msg: string) => onCompilerEvent(msg, eventHandler!),
       shots!,
+      noise,
+      noiseModel,
     );
   }
 
@@ -175,6 +195,10 @@ export class Compiler implements ICompiler {
     return this.wasm.get_qir(toWasmProgramConfig(program, "base"));
   }
 
+  async getQasm3(program: ProgramConfig): Promise<string> {
+    return this.wasm.get_qasm3(toWasmProgramConfig(program, "base"));
+  }
+
   async getEstimates(program: ProgramConfig, params: string): Promise<string> {
     return this.wasm.get_estimates(
       toWasmProgramConfig(program, "unrestricted"),
@@ -194,6 +218,18 @@ export class Compiler implements ICompiler {
     );
   }
 
+  async getOperationMatrix(
+    program: ProgramConfig,
+    operation: string,
+    numQubits: number,
+  ): Promise<IOperationMatrix> {
+    return this.wasm.get_operation_matrix(
+      toWasmProgramConfig(program, "unrestricted"),
+      operation,
+      numQubits,
+    );
+  }
+
   // Returns all autogenerated documentation files for the standard library
   // and loaded project (if requested). This include file names and metadata,
   // including specially formatted table of content file.
@@ -295,8 +331,10 @@ export const compilerProtocol: ServiceProtocol<ICompiler, QscEventData> = {
     getAst: "request",
     getHir: "request",
     getQir: "request",
+    getQasm3: "request",
     getEstimates: "request",
     getCircuit: "request",
+    getOperationMatrix: "request",
     getDocumentation: "request",
     run: "requestWithProgress",
     checkExerciseSolution: "requestWithProgress",
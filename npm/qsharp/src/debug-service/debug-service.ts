@@ -52,6 +52,14 @@ export interface IDebugService {
     bps: number[],
     eventHandler: IQscEventTarget,
   ): Promise<IStructStepResult>;
+  evalStepBack(
+    bps: number[],
+    eventHandler: IQscEventTarget,
+  ): Promise<IStructStepResult>;
+  evalReverseContinue(
+    bps: number[],
+    eventHandler: IQscEventTarget,
+  ): Promise<IStructStepResult>;
   dispose(): Promise<void>;
 }
 
@@ -135,6 +143,24 @@ export class QSharpDebugService implements IDebugService {
     return this.debugService.eval_step_out(event_cb, ids);
   }
 
+  async evalStepBack(
+    bps: number[],
+    eventHandler: IQscEventTarget,
+  ): Promise<IStructStepResult> {
+    const event_cb = (msg: string) => onCompilerEvent(msg, eventHandler);
+    const ids = new Uint32Array(bps);
+    return this.debugService.eval_step_back(event_cb, ids);
+  }
+
+  async evalReverseContinue(
+    bps: number[],
+    eventHandler: IQscEventTarget,
+  ): Promise<IStructStepResult> {
+    const event_cb = (msg: string) => onCompilerEvent(msg, eventHandler);
+    const ids = new Uint32Array(bps);
+    return this.debugService.eval_reverse_continue(event_cb, ids);
+  }
+
   async dispose() {
     this.debugService.free();
   }
@@ -194,6 +220,8 @@ export const debugServiceProtocol: ServiceProtocol<
     evalNext: "requestWithProgress",
     evalStepIn: "requestWithProgress",
     evalStepOut: "requestWithProgress",
+    evalStepBack: "requestWithProgress",
+    evalReverseContinue: "requestWithProgress",
     dispose: "request",
   },
   eventNames: ["DumpMachine", "Message", "Matrix", "Result"],
@@ -2,18 +2,26 @@
 // Licensed under the MIT License.
 
 import type {
+  ICallHierarchyIncomingCall,
+  ICallHierarchyItem,
+  ICallHierarchyOutgoingCall,
   ICodeAction,
   ICodeLens,
   ICompletionList,
+  IDocumentSymbol,
   IHover,
+  IInlayHint,
   ILocation,
   INotebookMetadata,
   IPosition,
   IRange,
+  ISemanticTokens,
+  ISemanticTokensDelta,
   ISignatureHelp,
   ITextEdit,
   IWorkspaceConfiguration,
   IWorkspaceEdit,
+  IWorkspaceSymbol,
   LanguageService,
   VSDiagnostic,
 } from "../../lib/web/qsc_wasm.js";
@@ -59,6 +67,15 @@ export interface ILanguageService {
     position: IPosition,
   ): Promise<ICompletionList>;
   getFormatChanges(documentUri: string): Promise<ITextEdit[]>;
+  getRangeFormatChanges(
+    documentUri: string,
+    range: IRange,
+  ): Promise<ITextEdit[]>;
+  getOnTypeFormatChanges(
+    documentUri: string,
+    position: IPosition,
+    ch: string,
+  ): Promise<ITextEdit[]>;
   getHover(
     documentUri: string,
     position: IPosition,
@@ -86,6 +103,24 @@ export interface ILanguageService {
     position: IPosition,
   ): Promise<ITextEdit | undefined>;
   getCodeLenses(documentUri: string): Promise<ICodeLens[]>;
+  getDocumentSymbols(documentUri: string): Promise<IDocumentSymbol[]>;
+  getWorkspaceSymbols(query: string): Promise<IWorkspaceSymbol[]>;
+  getSemanticTokens(documentUri: string): Promise<ISemanticTokens>;
+  getSemanticTokensDelta(
+    documentUri: string,
+    previousResultId: string,
+  ): Promise<ISemanticTokensDelta>;
+  getInlayHints(documentUri: string, range: IRange): Promise<IInlayHint[]>;
+  prepareCallHierarchy(
+    documentUri: string,
+    position: IPosition,
+  ): Promise<ICallHierarchyItem[]>;
+  getIncomingCalls(
+    item: ICallHierarchyItem,
+  ): Promise<ICallHierarchyIncomingCall[]>;
+  getOutgoingCalls(
+    item: ICallHierarchyItem,
+  ): Promise<ICallHierarchyOutgoingCall[]>;
 
   dispose(): Promise<void>;
 
@@ -119,6 +154,9 @@ export class QSharpLanguageService implements ILanguageService {
       listDirectory: async () => [],
       resolvePath: async () => null,
       fetchGithub: async () => "",
+      resolveGithubCommit: async () => {
+        throw new Error("GitHub dependencies are not supported");
+      },
       findManifestDirectory: async () => null,
     },
   ) {
@@ -182,6 +220,27 @@ export class QSharpLanguageService implements ILanguageService {
     return this.languageService.get_format_changes(documentUri);
   }
 
+  async getRangeFormatChanges(
+    documentUri: string,
+    range: IRange,
+  ): Promise<ITextEdit[]> {
+    await this.languageService.apply_pending_updates();
+    return this.languageService.get_range_format_changes(documentUri, range);
+  }
+
+  async getOnTypeFormatChanges(
+    documentUri: string,
+    position: IPosition,
+    ch: string,
+  ): Promise<ITextEdit[]> {
+    await this.languageService.apply_pending_updates();
+    return this.languageService.get_on_type_format_changes(
+      documentUri,
+      position,
+      ch,
+    );
+  }
+
   async getHover(
     documentUri: string,
     position: IPosition,
@@ -234,6 +293,54 @@ export class QSharpLanguageService implements ILanguageService {
     return this.languageService.get_code_lenses(documentUri);
   }
 
+  async getDocumentSymbols(documentUri: string): Promise<IDocumentSymbol[]> {
+    return this.languageService.get_document_symbols(documentUri);
+  }
+
+  async getWorkspaceSymbols(query: string): Promise<IWorkspaceSymbol[]> {
+    return this.languageService.get_workspace_symbols(query);
+  }
+
+  async getSemanticTokens(documentUri: string): Promise<ISemanticTokens> {
+    return this.languageService.get_semantic_tokens(documentUri);
+  }
+
+  async getSemanticTokensDelta(
+    documentUri: string,
+    previousResultId: string,
+  ): Promise<ISemanticTokensDelta> {
+    return this.languageService.get_semantic_tokens_delta(
+      documentUri,
+      previousResultId,
+    );
+  }
+
+  async getInlayHints(
+    documentUri: string,
+    range: IRange,
+  ): Promise<IInlayHint[]> {
+    return this.languageService.get_inlay_hints(documentUri, range);
+  }
+
+  async prepareCallHierarchy(
+    documentUri: string,
+    position: IPosition,
+  ): Promise<ICallHierarchyItem[]> {
+    return this.languageService.prepare_call_hierarchy(documentUri, position);
+  }
+
+  async getIncomingCalls(
+    item: ICallHierarchyItem,
+  ): Promise<ICallHierarchyIncomingCall[]> {
+    return this.languageService.get_incoming_calls(item);
+  }
+
+  async getOutgoingCalls(
+    item: ICallHierarchyItem,
+  ): Promise<ICallHierarchyOutgoingCall[]> {
+    return this.languageService.get_outgoing_calls(item);
+  }
+
   async dispose() {
     this.languageService.stop_background_work();
     await this.backgroundWork;
@@ -292,6 +399,8 @@ export const languageServiceProtocol: ServiceProtocol<
     getCodeActions: "request",
     getCompletions: "request",
     getFormatChanges: "request",
+    getRangeFormatChanges: "request",
+    getOnTypeFormatChanges: "request",
     getHover: "request",
     getDefinition: "request",
     getReferences: "request",
@@ -299,6 +408,14 @@ export const languageServiceProtocol: ServiceProtocol<
     getRename: "request",
     prepareRename: "request",
     getCodeLenses: "request",
+    getDocumentSymbols: "request",
+    getWorkspaceSymbols: "request",
+    getSemanticTokens: "request",
+    getSemanticTokensDelta: "request",
+    getInlayHints: "request",
+    prepareCallHierarchy: "request",
+    getIncomingCalls: "request",
+    getOutgoingCalls: "request",
     dispose: "request",
     addEventListener: "addEventListener",
     removeEventListener: "removeEventListener",
@@ -6,13 +6,16 @@ from ._qsharp import (
     eval,
     run,
     compile,
+    compile_to_qasm3,
     circuit,
     estimate,
     set_quantum_seed,
     set_classical_seed,
     dump_machine,
     dump_circuit,
+    get_operation_matrix,
     StateDump,
+    OperationMatrix,
     ShotResult,
 )
 
@@ -37,7 +40,9 @@ __all__ = [
     "set_classical_seed",
     "dump_machine",
     "dump_circuit",
+    "get_operation_matrix",
     "compile",
+    "compile_to_qasm3",
     "circuit",
     "estimate",
     "Result",
@@ -45,5 +50,6 @@ __all__ = [
     "QSharpError",
     "TargetProfile",
     "StateDump",
+    "OperationMatrix",
     "ShotResult",
 ]
@@ -32,3 +32,35 @@ def fetch_github(owner: str, repo: str, ref: str, path: str) -> str:
     path_no_leading_slash = path[1:] if path.startswith("/") else path
     url = f"https://raw.githubusercontent.com/{owner}/{repo}/{ref}/{path_no_leading_slash}"
     return urllib.request.urlopen(url).read().decode("utf-8-sig")
+
+
+def resolve_github_commit(owner: str, repo: str, ref: str) -> str:
+    """
+    Resolves a reference in a GitHub repository to the commit it points to.
+
+    Args:
+        owner (str): The owner of the GitHub repository.
+        repo (str): The name of the GitHub repository.
+        ref (str): The reference (branch, tag, or commit) to resolve.
+
+    Returns:
+        str: The full SHA of the commit.
+
+    Raises:
+        urllib.error.HTTPError: If the reference can't be resolved.
+        urllib.error.URLError: If there is an error with the URL.
+        ValueError: If GitHub does not return a commit SHA.
+    """
+
+    import re
+    import urllib.parse
+    import urllib.request
+
+    url = f"https://api.github.com/repos/{owner}/{repo}/commits/{urllib.parse.quote(ref, safe='')}"
+    request = urllib.request.Request(
+        url, headers={"Accept": "application/vnd.github.sha"}
+    )
+    sha = urllib.request.urlopen(request).read().decode("utf-8").strip()
+    if not re.fullmatch(r"[0-9a-f]{40}", sha):
+        raise ValueError(f"{owner}/{repo}@{ref} did not resolve to a commit: {sha}")
+    return sha
@@ -2,7 +2,8 @@
 # Licensed under the MIT License.
 
 from enum import Enum
-from typing import Any, Callable, Optional, Dict, List, Tuple
+from typing import Any, Callable, Optional, Dict, List, Tuple, Union
+from .noisy_simulator import NoiseModel
 
 # pylint: disable=unused-argument
 # E302 is fighting with the formatter for number of blank lines
@@ -98,6 +99,16 @@ class TargetProfile(Enum):
     reset capabilities, as defined by the QIR specification.
     """
 
+    Adaptive_RIF: TargetProfile
+    """
+    Target supports the Adaptive profile with integer computation, floating-point
+    computation and qubit reset capabilities.
+
+    This profile includes all of the required Adaptive Profile
+    capabilities, as well as the optional integer computation, floating-point
+    computation and qubit reset capabilities, as defined by the QIR specification.
+    """
+
     Unrestricted: TargetProfile
     """
     Describes the unrestricted set of capabilities required to run any Q# program.
@@ -139,12 +150,29 @@ class Interpreter:
         """
         ...
 
-    def run(self, entry_expr: str, output_fn: Callable[[Output], None]) -> Any:
+    def run(
+        self,
+        entry_expr: str,
+        output_fn: Callable[[Output], None],
+        noise: Optional[
+            Union[
+                Tuple[float, float, float],
+                Dict[str, Union[Tuple[float, float, float], float]],
+            ]
+        ] = None,
+        noise_model: Optional[NoiseModel] = None,
+    ) -> Any:
         """
         Runs the given Q# expression with an independent instance of the simulator.
 
         :param entry_expr: The entry expression.
         :param output_fn: A callback function that will be called with each output.
+        :param noise: The probabilities of X, Y and Z errors applied after every gate, or a
+            dictionary from channel names, such as `"h"`, `"reset"` or `"idle"`, to such
+            probabilities, in which the `"measurement"` entry is the probability of a
+            measurement error.
+        :param noise_model: A per-gate noise model. If given, the expression runs on the noisy
+            density matrix simulator instead. It can't be combined with `noise`.
 
         :returns values: A result or runtime errors.
 
@@ -152,6 +180,38 @@ class Interpreter:
         """
         ...
 
+    def run_shots(
+        self,
+        entry_expr: str,
+        shots: int,
+        output_fn: Callable[[Output], None],
+        result_fn: Callable[[Any], None],
+        noise: Optional[
+            Union[
+                Tuple[float, float, float],
+                Dict[str, Union[Tuple[float, float, float], float]],
+            ]
+        ] = None,
+        parallelism: int = 1,
+    ) -> None:
+        """
+        Runs the given Q# expression for the given number of shots, each with an independent
+        instance of the simulator, spreading them across up to `parallelism` threads.
+
+        :param entry_expr: The entry expression.
+        :param shots: The number of shots to run.
+        :param output_fn: A callback function that will be called with each output, in shot order,
+            as soon as the shot that produced it has finished.
+        :param result_fn: A callback function that will be called with the result of each shot,
+            after its outputs.
+        :param noise: The noise to simulate, in the same form as for `run`.
+        :param parallelism: The maximum number of threads to run shots on.
+
+        :raises QSharpError: If there is an error interpreting the input, or once reaching a
+            shot that failed with a runtime error.
+        """
+        ...
+
     def qir(self, entry_expr: str) -> str:
         """
         Generates QIR from Q# source code.
@@ -162,6 +222,16 @@ class Interpreter:
         """
         ...
 
+    def qasm3(self, entry_expr: str) -> str:
+        """
+        Generates OpenQASM 3 from Q# source code.
+
+        :param entry_expr: The entry expression.
+
+        :returns qasm: The OpenQASM 3 string.
+        """
+        ...
+
     def circuit(
         self,
         entry_expr: Optional[str],
@@ -181,6 +251,19 @@ class Interpreter:
         """
         ...
 
+    def get_operation_matrix(self, operation: str, num_qubits: int) -> MatrixData:
+        """
+        Computes the unitary matrix of an operation.
+
+        :param operation: The operation to compute the matrix of. This can be a name of
+        an operation or a lambda expression. The operation must take a single array of qubits.
+
+        :param num_qubits: The number of qubits the operation acts on.
+
+        :raises QSharpError: If there is an error computing the matrix.
+        """
+        ...
+
     def estimate(self, entry_expr: str, params: str) -> str:
         """
         Estimates resources for Q# source code.
@@ -276,6 +359,20 @@ class StateDumpData:
     def _repr_markdown_(self) -> str: ...
     def _repr_latex_(self) -> Optional[str]: ...
 
+class MatrixData:
+    """
+    The unitary matrix of an operation returned from the Q# interpreter.
+    """
+
+    """
+    Get the rows of the matrix as lists of complex entries.
+    """
+    def get_rows(self) -> List[List[complex]]: ...
+    def __len__(self) -> int: ...
+    def __repr__(self) -> str: ...
+    def __str__(self) -> str: ...
+    def _repr_latex_(self) -> str: ...
+
 class Circuit:
     def json(self) -> str: ...
     def __repr__(self) -> str: ...
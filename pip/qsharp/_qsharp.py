@@ -5,6 +5,7 @@ from ._native import (
     Interpreter,
     TargetProfile,
     StateDumpData,
+    MatrixData,
     QSharpError,
     Output,
     Circuit,
@@ -14,12 +15,14 @@ from typing import (
     Callable,
     Dict,
     Optional,
+    Tuple,
     TypedDict,
     Union,
     List,
     overload,
 )
 from .estimator._estimator import EstimatorResult, EstimatorParams
+from .noisy_simulator import NoiseModel
 import json
 import os
 
@@ -65,6 +68,8 @@ class Config:
     ):
         if target_profile == TargetProfile.Adaptive_RI:
             self._config = {"targetProfile": "adaptive_ri"}
+        elif target_profile == TargetProfile.Adaptive_RIF:
+            self._config = {"targetProfile": "adaptive_rif"}
         elif target_profile == TargetProfile.Base:
             self._config = {"targetProfile": "base"}
         elif target_profile == TargetProfile.Unrestricted:
@@ -214,6 +219,14 @@ def run(
     *,
     on_result: Optional[Callable[[ShotResult], None]] = None,
     save_events: bool = False,
+    noise: Optional[
+        Union[
+            Tuple[float, float, float],
+            Dict[str, Union[Tuple[float, float, float], float]],
+        ]
+    ] = None,
+    noise_model: Optional[NoiseModel] = None,
+    parallelism: int = 1,
 ) -> List[Any]:
     """
     Runs the given Q# expression for the given number of shots.
@@ -223,6 +236,15 @@ def run(
     :param shots: The number of shots to run.
     :param on_result: A callback function that will be called with each result.
     :param save_events: If true, the output of each shot will be saved. If false, they will be printed.
+    :param noise: The probabilities of X, Y and Z errors applied after every gate, or a dictionary
+        from channel names to such probabilities. The channel names are the gate names, such as
+        `"h"` or `"cx"`, `"reset"` for qubit allocation and reset, and `"idle"` for each operation
+        a qubit sits idle through. The `"measurement"` entry is instead the probability that a
+        measurement reports the wrong result. If not given, the simulation is noiseless.
+    :param noise_model: A per-gate noise model. If given, the shots run on the noisy density
+        matrix simulator instead. It can't be combined with `noise`.
+    :param parallelism: The maximum number of threads to run shots on. Results and outputs are
+        still reported in shot order. Ignored when `noise_model` is given.
 
     :returns values: A list of results or runtime errors. If `save_events` is true,
     a List of ShotResults is returned.
@@ -250,10 +272,37 @@ def run(
         # Append the output to the last shot's output list
         results[-1]["events"].append(output)
 
+    if noise_model is None:
+        # The outputs of a shot are reported before its result, so the entry for the next shot
+        # is added as soon as the current one is complete.
+        def on_shot_result(result: Any) -> None:
+            results[-1]["result"] = result
+            if on_result:
+                on_result(results[-1])
+            results.append({"result": None, "events": []})
+
+        results.append({"result": None, "events": []})
+        get_interpreter().run_shots(
+            entry_expr,
+            shots,
+            on_save_events if save_events else print_output,
+            on_shot_result,
+            noise,
+            parallelism,
+        )
+        results.pop()
+        if save_events:
+            return results
+        else:
+            return [shot["result"] for shot in results]
+
     for shot in range(shots):
         results.append({"result": None, "events": []})
         run_results = get_interpreter().run(
-            entry_expr, on_save_events if save_events else print_output
+            entry_expr,
+            on_save_events if save_events else print_output,
+            noise,
+            noise_model,
         )
         results[-1]["result"] = run_results
         if on_result:
@@ -317,6 +366,27 @@ def compile(entry_expr: str) -> QirInputData:
     return QirInputData("main", ll_str)
 
 
+def compile_to_qasm3(entry_expr: str) -> str:
+    """
+    Compiles the Q# source code into an OpenQASM 3 program.
+
+    :param entry_expr: The Q# expression that will be used as the entrypoint
+        for the program.
+
+    :returns str: The OpenQASM 3 program.
+
+    Example:
+
+    .. code-block:: python
+        program = qsharp.compile_to_qasm3("...")
+        with open('myfile.qasm', 'w') as file:
+            file.write(program)
+    """
+    ipython_helper()
+
+    return get_interpreter().qasm3(entry_expr)
+
+
 def circuit(
     entry_expr: Optional[str] = None, *, operation: Optional[str] = None
 ) -> Circuit:
@@ -482,6 +552,63 @@ def dump_machine() -> StateDump:
     return StateDump(get_interpreter().dump_machine())
 
 
+class OperationMatrix:
+    """
+    The unitary matrix of an operation returned from the Q# interpreter.
+    Entry `[i][j]` is the amplitude of basis state `i` when the operation
+    is applied to basis state `j`.
+    """
+
+    """
+    The number of qubits the operation acts on.
+    """
+    qubit_count: int
+
+    __rows: List[List[complex]]
+    __data: MatrixData
+
+    def __init__(self, data: MatrixData, qubit_count: int):
+        self.__data = data
+        self.__rows = data.get_rows()
+        self.qubit_count = qubit_count
+
+    def __getitem__(self, index: int) -> List[complex]:
+        return self.__rows.__getitem__(index)
+
+    def __iter__(self):
+        return self.__rows.__iter__()
+
+    def __len__(self) -> int:
+        return len(self.__rows)
+
+    def __repr__(self) -> str:
+        return self.__data.__repr__()
+
+    def __str__(self) -> str:
+        return self.__data.__str__()
+
+    def _repr_latex_(self) -> str:
+        return self.__data._repr_latex_()
+
+
+def get_operation_matrix(operation: str, num_qubits: int) -> OperationMatrix:
+    """
+    Computes the unitary matrix of an operation by simulating it.
+
+    :param operation: The operation to compute the matrix of. This can be a name of
+        an operation or a lambda expression. The operation must take a single array of
+        qubits, e.g. `qs => CNOT(qs[0], qs[1])`.
+    :param num_qubits: The number of qubits the operation acts on.
+
+    :returns: The unitary matrix of the operation.
+    :raises QSharpError: If there is an error computing the matrix.
+    """
+    ipython_helper()
+    return OperationMatrix(
+        get_interpreter().get_operation_matrix(operation, num_qubits), num_qubits
+    )
+
+
 def dump_circuit() -> Circuit:
     """
     Dumps the current circuit state of the interpreter.
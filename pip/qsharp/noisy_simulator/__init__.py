@@ -5,6 +5,7 @@ from ._noisy_simulator import (
     NoisySimulatorError,
     Operation,
     Instrument,
+    NoiseModel,
     DensityMatrixSimulator,
     StateVectorSimulator,
 )
@@ -13,6 +14,7 @@ __all__ = [
     "NoisySimulatorError",
     "Operation",
     "Instrument",
+    "NoiseModel",
     "DensityMatrixSimulator",
     "StateVectorSimulator",
 ]
@@ -5,6 +5,7 @@ from .._native import (
     NoisySimulatorError,
     Operation,
     Instrument,
+    NoiseModel,
     DensityMatrixSimulator,
     StateVectorSimulator,
 )
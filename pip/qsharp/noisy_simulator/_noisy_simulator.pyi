@@ -1,7 +1,7 @@
 # Copyright (c) Microsoft Corporation.
 # Licensed under the MIT License.
 
-from typing import Optional, List, Any
+from typing import Optional, List, Any, Dict
 
 class NoisySimulatorError(BaseException):
     """
@@ -71,6 +71,33 @@ class Instrument:
         """
         ...
 
+class NoiseModel:
+    """
+    EXPERIMENTAL:
+
+    A per-gate noise model used to run Q# programs on the noisy density matrix simulator.
+    """
+
+    def __init__(
+        self,
+        qubits: int,
+        gates: Optional[Dict[str, Operation]] = None,
+        measurement: Optional[Instrument] = None,
+    ) -> None:
+        """
+        Constructs a noise model.
+        Raises a `NoisySimulatorError` if a gate name is unknown or if its
+        operation doesn't act on as many qubits as the gate.
+
+        Input:
+            qubits: The maximum number of simultaneously allocated qubits.
+            gates: The operation applied after each gate, keyed by the name of the gate,
+                such as "h", "cx" or "rz". Gates without an operation are noiseless.
+            measurement: The instrument used for measurements. Outcome 0 is reported as
+                `Zero` and outcome 1 as `One`. If not given, measurements are ideal.
+        """
+        ...
+
 class DensityMatrix:
     """
     EXPERIMENTAL:
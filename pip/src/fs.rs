@@ -101,6 +101,24 @@ impl FileSystem for Py<'_> {
         )
         .map_err(|e| diagnostic_from(self.py, &e))
     }
+
+    fn resolve_github_commit(
+        &self,
+        owner: &str,
+        repo: &str,
+        r#ref: &str,
+    ) -> miette::Result<Arc<str>> {
+        resolve_github_commit(self.py, owner, repo, r#ref).map_err(|e| diagnostic_from(self.py, &e))
+    }
+
+    fn write_file(&self, path: &Path, contents: &str) -> miette::Result<()> {
+        // The Python file system hooks operate on the local file system through `os`,
+        // so the lockfile and package cache can be written with `std::fs` directly.
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| miette!(e.to_string()))?;
+        }
+        std::fs::write(path, contents).map_err(|e| miette!(e.to_string()))
+    }
 }
 
 fn read_file(py: Python, read_file: &PyObject, path: &Path) -> PyResult<(Arc<str>, Arc<str>)> {
@@ -175,6 +193,17 @@ fn fetch_github(
         .into())
 }
 
+/// Like `write_file`, ref resolution isn't one of the file system hooks, since it
+/// always goes through the GitHub API regardless of how files are accessed.
+fn resolve_github_commit(py: Python, owner: &str, repo: &str, r#ref: &str) -> PyResult<Arc<str>> {
+    let commit = py
+        .import_bound("qsharp._http")?
+        .getattr("resolve_github_commit")?
+        .call1((owner, repo, r#ref))?;
+
+    Ok(commit.downcast::<PyString>()?.to_string().into())
+}
+
 fn get_tuple_string(tuple: &Bound<'_, PyTuple>, index: usize) -> PyResult<Arc<str>> {
     Ok(tuple
         .get_item(index)?
@@ -8,7 +8,7 @@ use crate::{
         compile_qasm3_to_qir, compile_qasm3_to_qsharp, compile_qasm_enriching_errors,
         map_entry_compilation_errors, resource_estimate_qasm3, run_ast, run_qasm3, ImportResolver,
     },
-    noisy_simulator::register_noisy_simulator_submodule,
+    noisy_simulator::{register_noisy_simulator_submodule, NoiseModel},
 };
 use miette::{Diagnostic, Report};
 use num_bigint::BigUint;
@@ -24,16 +24,19 @@ use qsc::{
     interpret::{
         self,
         output::{Error, Receiver},
-        CircuitEntryPoint, Value,
+        CircuitEntryPoint, ShotResult, ShotValue, Value,
     },
     packages::BuildableProgram,
     project::{FileSystem, PackageCache, PackageGraphSources},
     target::Profile,
-    LanguageFeatures, PackageType, SourceMap,
+    ChannelNoise, LanguageFeatures, NoiseConfig, PackageType, PauliNoise, SourceMap,
 };
 
 use resource_estimator::{self as re, estimate_expr};
-use std::{cell::RefCell, fmt::Write, path::PathBuf, rc::Rc, str::FromStr};
+use std::{
+    cell::RefCell, collections::HashMap, fmt::Write, ops::ControlFlow, path::PathBuf, rc::Rc,
+    str::FromStr,
+};
 
 /// If the classes are not Send, the Python interpreter
 /// will not be able to use them in a separate thread.
@@ -58,6 +61,7 @@ fn verify_classes_are_sendable() {
     is_send::<Pauli>();
     is_send::<Output>();
     is_send::<StateDumpData>();
+    is_send::<MatrixData>();
     is_send::<Circuit>();
 }
 
@@ -72,6 +76,7 @@ fn _native<'a>(py: Python<'a>, m: &Bound<'a, PyModule>) -> PyResult<()> {
     m.add_class::<Pauli>()?;
     m.add_class::<Output>()?;
     m.add_class::<StateDumpData>()?;
+    m.add_class::<MatrixData>()?;
     m.add_class::<Circuit>()?;
     m.add_function(wrap_pyfunction!(physical_estimates, m)?)?;
     m.add("QSharpError", py.get_type_bound::<QSharpError>())?;
@@ -104,6 +109,13 @@ pub(crate) enum TargetProfile {
     /// capabilities, as well as the optional integer computation and qubit
     /// reset capabilities, as defined by the QIR specification.
     Adaptive_RI,
+    /// Target supports the Adaptive profile with integer computation, floating-point
+    /// computation and qubit reset capabilities.
+    ///
+    /// This profile includes all of the required Adaptive Profile
+    /// capabilities, as well as the optional integer computation, floating-point
+    /// computation and qubit reset capabilities, as defined by the QIR specification.
+    Adaptive_RIF,
     /// Target supports the full set of capabilities required to run any Q# program.
     ///
     /// This option maps to the Full Profile as defined by the QIR specification.
@@ -134,6 +146,7 @@ impl From<Profile> for TargetProfile {
         match profile {
             Profile::Base => TargetProfile::Base,
             Profile::AdaptiveRI => TargetProfile::Adaptive_RI,
+            Profile::AdaptiveRIF => TargetProfile::Adaptive_RIF,
             Profile::Unrestricted => TargetProfile::Unrestricted,
         }
     }
@@ -144,6 +157,7 @@ impl From<TargetProfile> for Profile {
         match profile {
             TargetProfile::Base => Profile::Base,
             TargetProfile::Adaptive_RI => Profile::AdaptiveRI,
+            TargetProfile::Adaptive_RIF => Profile::AdaptiveRIF,
             TargetProfile::Unrestricted => Profile::Unrestricted,
         }
     }
@@ -250,7 +264,10 @@ impl Interpreter {
             {
                 let project =
                     file_system(py, read_file, list_directory, resolve_path, fetch_github)
-                        .load_project(&PathBuf::from(project_root), Some(&package_cache))
+                        .load_project_and_update_lockfile(
+                            &PathBuf::from(project_root),
+                            Some(&package_cache),
+                        )
                         .map_err(IntoPyErr::into_py_err)?;
 
                 if !project.errors.is_empty() {
@@ -333,20 +350,87 @@ impl Interpreter {
         Circuit(self.interpreter.get_circuit()).into_py(py)
     }
 
-    #[pyo3(signature=(entry_expr=None, callback=None))]
+    #[pyo3(signature=(entry_expr=None, callback=None, noise=None, noise_model=None))]
     fn run(
         &mut self,
         py: Python,
         entry_expr: Option<&str>,
         callback: Option<PyObject>,
+        noise: Option<NoiseArg>,
+        noise_model: Option<PyRef<NoiseModel>>,
     ) -> PyResult<PyObject> {
+        if let Some(noise_model) = noise_model {
+            if noise.is_some() {
+                return Err(PyValueError::new_err(
+                    "noise and noise_model can't be used together",
+                ));
+            }
+            let mut receiver = OptionalCallbackReceiver { callback, py };
+            return match self.interpreter.run_with_noise_model(
+                &mut receiver,
+                entry_expr,
+                noise_model.qubits,
+                noise_model.model.clone(),
+            ) {
+                Ok(value) => Ok(ValueWrapper(value).into_py(py)),
+                Err(errors) => Err(QSharpError::new_err(format_errors(errors))),
+            };
+        }
         let mut receiver = OptionalCallbackReceiver { callback, py };
-        match self.interpreter.run(&mut receiver, entry_expr) {
+        let result = match noise {
+            Some(noise) => {
+                let noise = noise_config(noise)?;
+                self.interpreter
+                    .run_with_noise(&mut receiver, entry_expr, noise)
+            }
+            None => self.interpreter.run(&mut receiver, entry_expr),
+        };
+        match result {
             Ok(value) => Ok(ValueWrapper(value).into_py(py)),
             Err(errors) => Err(QSharpError::new_err(format_errors(errors))),
         }
     }
 
+    /// Runs the given Q# expression for the given number of shots, spreading them across up to
+    /// `parallelism` threads. As each shot finishes, its outputs are passed to `callback` and
+    /// then the value it returned to `result_callback`, in shot order.
+    ///
+    /// :raises QSharpError: If the expression fails to compile, or once reaching a shot that
+    ///     failed with a runtime error.
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature=(entry_expr, shots, callback=None, result_callback=None, noise=None, parallelism=1))]
+    fn run_shots(
+        &mut self,
+        py: Python,
+        entry_expr: &str,
+        shots: usize,
+        callback: Option<PyObject>,
+        result_callback: Option<PyObject>,
+        noise: Option<NoiseArg>,
+        parallelism: usize,
+    ) -> PyResult<()> {
+        let noise = noise.map(noise_config).transpose()?;
+        let mut receiver = OptionalCallbackReceiver { callback, py };
+        let mut error = None;
+        self.interpreter
+            .run_shots_with(
+                Some(entry_expr),
+                shots,
+                None,
+                parallelism,
+                noise.as_ref(),
+                |shot| match report_shot(py, &mut receiver, result_callback.as_ref(), shot) {
+                    Ok(()) => ControlFlow::Continue(()),
+                    Err(err) => {
+                        error = Some(err);
+                        ControlFlow::Break(())
+                    }
+                },
+            )
+            .map_err(|errors| QSharpError::new_err(format_errors(errors)))?;
+        error.map_or(Ok(()), Err)
+    }
+
     fn qir(&mut self, _py: Python, entry_expr: &str) -> PyResult<String> {
         match self.interpreter.qirgen(entry_expr) {
             Ok(qir) => Ok(qir),
@@ -354,6 +438,13 @@ impl Interpreter {
         }
     }
 
+    fn qasm3(&mut self, _py: Python, entry_expr: &str) -> PyResult<String> {
+        match self.interpreter.qasm3gen(entry_expr) {
+            Ok(qasm) => Ok(qasm),
+            Err(errors) => Err(QSharpError::new_err(format_errors(errors))),
+        }
+    }
+
     /// Synthesizes a circuit for a Q# program. Either an entry
     /// expression or an operation must be provided.
     ///
@@ -387,6 +478,26 @@ impl Interpreter {
         }
     }
 
+    /// Computes the unitary matrix of an operation.
+    ///
+    /// :param operation: The operation to compute the matrix of. This can be a name of
+    /// an operation or a lambda expression. The operation must take a single array of qubits.
+    ///
+    /// :param num_qubits: The number of qubits the operation acts on.
+    ///
+    /// :raises QSharpError: If there is an error computing the matrix.
+    fn get_operation_matrix(
+        &mut self,
+        _py: Python,
+        operation: &str,
+        num_qubits: usize,
+    ) -> PyResult<MatrixData> {
+        match self.interpreter.get_operation_matrix(operation, num_qubits) {
+            Ok(matrix) => Ok(MatrixData(DisplayableMatrix(matrix))),
+            Err(errors) => Err(QSharpError::new_err(format_errors(errors))),
+        }
+    }
+
     fn estimate(&mut self, _py: Python, entry_expr: &str, job_params: &str) -> PyResult<String> {
         match estimate_expr(&mut self.interpreter, entry_expr, job_params) {
             Ok(estimate) => Ok(estimate),
@@ -506,6 +617,69 @@ create_exception!(
     "An error returned from the OpenQASM parser."
 );
 
+/// Passes the outputs and the value of a finished shot to the callbacks, first giving Python a
+/// chance to interrupt the run.
+fn report_shot(
+    py: Python,
+    receiver: &mut OptionalCallbackReceiver<'_>,
+    result_callback: Option<&PyObject>,
+    shot: &ShotResult,
+) -> PyResult<()> {
+    py.check_signals()?;
+    for output in &shot.output {
+        output
+            .send_to(receiver)
+            .map_err(|_| QSharpError::new_err("failed to handle the output of a shot"))?;
+    }
+    match &shot.result {
+        Ok(value) => {
+            if let Some(result_callback) = result_callback {
+                result_callback.call1(py, (ShotValueWrapper(value.clone()).into_py(py),))?;
+            }
+            Ok(())
+        }
+        Err(errors) => Err(QSharpError::new_err(format_errors(errors.clone()))),
+    }
+}
+
+/// The `noise` argument of `run`: either the probabilities of X, Y and Z errors applied after
+/// every gate, or a dictionary from channel names to such probabilities, in which the
+/// `measurement` entry is the probability of a measurement error instead.
+#[derive(FromPyObject)]
+enum NoiseArg {
+    Uniform((f64, f64, f64)),
+    PerChannel(HashMap<String, NoiseValue>),
+}
+
+#[derive(FromPyObject)]
+enum NoiseValue {
+    Pauli((f64, f64, f64)),
+    Probability(f64),
+}
+
+impl From<NoiseValue> for ChannelNoise {
+    fn from(value: NoiseValue) -> Self {
+        match value {
+            NoiseValue::Pauli((p_x, p_y, p_z)) => ChannelNoise::Pauli(p_x, p_y, p_z),
+            NoiseValue::Probability(p) => ChannelNoise::Probability(p),
+        }
+    }
+}
+
+fn noise_config(noise: NoiseArg) -> PyResult<NoiseConfig> {
+    match noise {
+        NoiseArg::Uniform((p_x, p_y, p_z)) => {
+            PauliNoise::from_probabilities(p_x, p_y, p_z).map(NoiseConfig::uniform)
+        }
+        NoiseArg::PerChannel(channels) => NoiseConfig::from_channels(
+            channels
+                .into_iter()
+                .map(|(name, value)| (name, value.into())),
+        ),
+    }
+    .map_err(|err| PyValueError::new_err(err.to_string()))
+}
+
 pub(crate) fn format_errors(errors: Vec<interpret::Error>) -> String {
     errors
         .into_iter()
@@ -635,6 +809,47 @@ impl StateDumpData {
     }
 }
 
+#[pyclass]
+/// Captured unitary matrix of an operation.
+pub(crate) struct MatrixData(pub(crate) DisplayableMatrix);
+
+#[pymethods]
+impl MatrixData {
+    fn get_rows<'a>(&self, py: Python<'a>) -> Bound<'a, PyList> {
+        PyList::new_bound(
+            py,
+            self.0
+                 .0
+                .iter()
+                .map(|row| {
+                    PyList::new_bound(
+                        py,
+                        row.iter()
+                            .map(|v| PyComplex::from_doubles_bound(py, v.re, v.im))
+                            .collect::<Vec<_>>(),
+                    )
+                })
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    fn __len__(&self) -> usize {
+        self.0 .0.len()
+    }
+
+    fn __repr__(&self) -> String {
+        self.0.to_plain()
+    }
+
+    fn __str__(&self) -> String {
+        self.__repr__()
+    }
+
+    fn _repr_latex_(&self) -> String {
+        self.0.to_latex()
+    }
+}
+
 #[derive(Clone, Copy, PartialEq)]
 #[pyclass(eq, eq_int)]
 /// A Q# measurement result.
@@ -718,6 +933,45 @@ impl IntoPy<PyObject> for ValueWrapper {
     }
 }
 
+// Mapping of the values returned by shots to Python value types, matching `ValueWrapper`.
+struct ShotValueWrapper(ShotValue);
+
+impl IntoPy<PyObject> for ShotValueWrapper {
+    fn into_py(self, py: Python) -> PyObject {
+        match self.0 {
+            ShotValue::BigInt(val) => val.into_py(py),
+            ShotValue::Int(val) => val.into_py(py),
+            ShotValue::Double(val) => val.into_py(py),
+            ShotValue::Bool(val) => val.into_py(py),
+            ShotValue::String(val) => val.into_py(py),
+            ShotValue::Result(val) => if val { Result::One } else { Result::Zero }.into_py(py),
+            ShotValue::Pauli(val) => match val {
+                fir::Pauli::I => Pauli::I.into_py(py),
+                fir::Pauli::X => Pauli::X.into_py(py),
+                fir::Pauli::Y => Pauli::Y.into_py(py),
+                fir::Pauli::Z => Pauli::Z.into_py(py),
+            },
+            ShotValue::Tuple(val) => {
+                if val.is_empty() {
+                    // Special case Value::unit as None
+                    py.None()
+                } else {
+                    PyTuple::new_bound(py, val.into_iter().map(|v| ShotValueWrapper(v).into_py(py)))
+                        .into_py(py)
+                }
+            }
+            ShotValue::Array(val) => {
+                PyList::new_bound(py, val.into_iter().map(|v| ShotValueWrapper(v).into_py(py)))
+                    .into_py(py)
+            }
+            ShotValue::Other {
+                type_name,
+                rendered,
+            } => format!("<{type_name}> {rendered}").into_py(py),
+        }
+    }
+}
+
 pub(crate) struct OptionalCallbackReceiver<'a> {
     pub(crate) callback: Option<PyObject>,
     pub(crate) py: Python<'a>,
@@ -6,6 +6,7 @@
 use noisy_simulator::{ComplexVector, NoisySimulator, SquareMatrix};
 use num_complex::Complex;
 use pyo3::{exceptions::PyException, prelude::*};
+use std::collections::HashMap;
 type PythonMatrix = Vec<Vec<Complex<f64>>>;
 
 pub(crate) fn register_noisy_simulator_submodule<'a>(
@@ -18,6 +19,7 @@ pub(crate) fn register_noisy_simulator_submodule<'a>(
     )?;
     m.add_class::<Operation>()?;
     m.add_class::<Instrument>()?;
+    m.add_class::<NoiseModel>()?;
     m.add_class::<DensityMatrixSimulator>()?;
     m.add_class::<StateVectorSimulator>()?;
     Ok(())
@@ -109,6 +111,7 @@ impl Operation {
 }
 
 #[pyclass]
+#[derive(Clone)]
 pub(crate) struct Instrument(noisy_simulator::Instrument);
 
 #[pymethods]
@@ -122,6 +125,34 @@ impl Instrument {
     }
 }
 
+/// A per-gate noise model used to run Q# programs on the noisy density matrix simulator.
+#[pyclass]
+pub(crate) struct NoiseModel {
+    /// The maximum number of simultaneously allocated qubits.
+    pub(crate) qubits: usize,
+    pub(crate) model: qsc::noisy::NoiseModel,
+}
+
+#[pymethods]
+impl NoiseModel {
+    #[new]
+    #[pyo3(signature=(qubits, gates=None, measurement=None))]
+    pub fn new(
+        qubits: usize,
+        gates: Option<HashMap<String, Operation>>,
+        measurement: Option<Instrument>,
+    ) -> PyResult<Self> {
+        let mut model = qsc::noisy::NoiseModel::default();
+        for (gate, noise) in gates.unwrap_or_default() {
+            model
+                .set_gate_noise(&gate, noise.0)
+                .map_err(NoisySimulatorError::new_err)?;
+        }
+        model.measurement = measurement.map(|measurement| measurement.0);
+        Ok(Self { qubits, model })
+    }
+}
+
 #[pyclass]
 #[derive(Clone)]
 pub(crate) struct DensityMatrix {
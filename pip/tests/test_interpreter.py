@@ -215,6 +215,24 @@ def test_qirgen() -> None:
     assert isinstance(qir, str)
 
 
+def test_qasm3gen() -> None:
+    e = Interpreter(TargetProfile.Base)
+    e.interpret(
+        "operation Program() : Result { use q = Qubit(); H(q); return M(q) }"
+    )
+    qasm = e.qasm3("Program()")
+    assert qasm == (
+        "OPENQASM 3.0;\n"
+        'include "stdgates.inc";\n'
+        "qubit[1] q;\n"
+        "bit[1] c;\n"
+        "output bit[1] results;\n"
+        "h q[0];\n"
+        "c[0] = measure q[0];\n"
+        "results[0] = c[0];\n"
+    )
+
+
 def test_run_with_shots() -> None:
     e = Interpreter(TargetProfile.Unrestricted)
 
@@ -4,6 +4,7 @@
 import pytest
 import qsharp
 import qsharp.utils
+from qsharp.noisy_simulator import NoiseModel, NoisySimulatorError, Operation
 from contextlib import redirect_stdout
 import io
 
@@ -137,6 +138,23 @@ def test_dump_machine() -> None:
     assert state_dump.check_eq([1.0], tolerance=1e-4)
 
 
+def test_get_operation_matrix() -> None:
+    qsharp.init(target_profile=qsharp.TargetProfile.Unrestricted)
+    matrix = qsharp.get_operation_matrix("qs => CNOT(qs[0], qs[1])", 2)
+    assert matrix.qubit_count == 2
+    assert len(matrix) == 4
+    assert [[round(abs(entry), 6) for entry in row] for row in matrix] == [
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+        [0.0, 0.0, 1.0, 0.0],
+    ]
+    qsharp.eval("operation ApplyS(qs : Qubit[]) : Unit { S(qs[0]); }")
+    matrix = qsharp.get_operation_matrix("ApplyS", 1)
+    assert abs(matrix[1][1] - complex(0.0, 1.0)) < 1e-6
+    assert matrix._repr_latex_().startswith("$")
+
+
 def test_dump_operation() -> None:
     qsharp.init(target_profile=qsharp.TargetProfile.Unrestricted)
     res = qsharp.utils.dump_operation("qs => ()", 1)
@@ -316,6 +334,69 @@ def test_run_with_result_callback(capsys) -> None:
     assert called
 
 
+def test_run_returns_results_and_events_in_shot_order() -> None:
+    qsharp.init()
+    qsharp.eval(
+        'operation Foo() : Result { Message("hi"); use q = Qubit(); X(q); MResetZ(q) }'
+    )
+    results = qsharp.run("Foo()", 20, save_events=True)
+    assert len(results) == 20
+    for shot in results:
+        assert shot["result"] == qsharp.Result.One
+        assert [str(event) for event in shot["events"]] == ["hi"]
+
+
+def test_run_with_noise_applies_pauli_errors() -> None:
+    qsharp.init()
+    qsharp.eval("operation Foo() : Result { use q = Qubit(); X(q); MResetZ(q) }")
+    results = qsharp.run("Foo()", 3, noise=(1.0, 0.0, 0.0))
+    assert results == [qsharp.Result.Zero, qsharp.Result.Zero, qsharp.Result.Zero]
+
+
+def test_run_with_invalid_noise_produces_error() -> None:
+    qsharp.init()
+    qsharp.eval("operation Foo() : Result { use q = Qubit(); X(q); MResetZ(q) }")
+    with pytest.raises(ValueError):
+        qsharp.run("Foo()", 1, noise=(0.5, 0.5, 0.5))
+
+
+def test_run_with_per_channel_noise_applies_only_configured_errors() -> None:
+    qsharp.init()
+    qsharp.eval("operation Foo() : Result { use q = Qubit(); X(q); H(q); H(q); MResetZ(q) }")
+    results = qsharp.run("Foo()", 3, noise={"x": (1.0, 0.0, 0.0)})
+    assert results == [qsharp.Result.Zero, qsharp.Result.Zero, qsharp.Result.Zero]
+    results = qsharp.run("Foo()", 3, noise={"measurement": 1.0})
+    assert results == [qsharp.Result.Zero, qsharp.Result.Zero, qsharp.Result.Zero]
+
+
+def test_run_with_unknown_noise_channel_produces_error() -> None:
+    qsharp.init()
+    qsharp.eval("operation Foo() : Result { use q = Qubit(); MResetZ(q) }")
+    with pytest.raises(ValueError):
+        qsharp.run("Foo()", 1, noise={"foo": (0.1, 0.0, 0.0)})
+
+
+def test_run_with_noise_model_uses_noisy_simulator() -> None:
+    qsharp.init()
+    qsharp.eval("operation Foo() : Result { use q = Qubit(); X(q); MResetZ(q) }")
+    bit_flip = Operation([[[0j, 1 + 0j], [1 + 0j, 0j]]])
+    noise_model = NoiseModel(1, gates={"x": bit_flip})
+    results = qsharp.run("Foo()", 3, noise_model=noise_model)
+    assert results == [qsharp.Result.Zero, qsharp.Result.Zero, qsharp.Result.Zero]
+
+
+def test_noise_model_with_unknown_gate_produces_error() -> None:
+    bit_flip = Operation([[[0j, 1 + 0j], [1 + 0j, 0j]]])
+    with pytest.raises(NoisySimulatorError):
+        NoiseModel(1, gates={"foo": bit_flip})
+
+
+def test_run_with_noise_model_beyond_capacity_produces_error() -> None:
+    qsharp.init()
+    with pytest.raises(qsharp.QSharpError):
+        qsharp.run("{ use qs = Qubit[2]; }", 1, noise_model=NoiseModel(1))
+
+
 def test_run_with_invalid_shots_produces_error() -> None:
     qsharp.init()
     qsharp.eval('operation Foo() : Result { Message("Hello, world!"); Zero }')
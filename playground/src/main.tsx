@@ -446,31 +446,25 @@ function registerMonacoLanguageServiceProviders(
     },
   });
 
-  async function getFormatChanges(
-    model: monaco.editor.ITextModel,
-    range?: monaco.Range,
+  function toMonacoEdits(
+    lsEdits: Awaited<ReturnType<ILanguageService["getFormatChanges"]>>,
   ) {
-    const lsEdits = await languageService.getFormatChanges(
-      model.uri.toString(),
-    );
     if (!lsEdits) {
       return [];
     }
-    let edits = lsEdits.map((edit) => {
+    return lsEdits.map((edit) => {
       return {
         range: lsRangeToMonacoRange(edit.range),
         text: edit.newText,
       } as monaco.languages.TextEdit;
     });
-    if (range) {
-      edits = edits.filter((e) => monaco.Range.areIntersecting(range, e.range));
-    }
-    return edits;
   }
 
   monaco.languages.registerDocumentFormattingEditProvider("qsharp", {
     provideDocumentFormattingEdits: async (model: monaco.editor.ITextModel) => {
-      return getFormatChanges(model);
+      return toMonacoEdits(
+        await languageService.getFormatChanges(model.uri.toString()),
+      );
     },
   });
 
@@ -479,7 +473,29 @@ function registerMonacoLanguageServiceProviders(
       model: monaco.editor.ITextModel,
       range: monaco.Range,
     ) => {
-      return getFormatChanges(model, range);
+      return toMonacoEdits(
+        await languageService.getRangeFormatChanges(
+          model.uri.toString(),
+          monacoRangetoLsRange(range),
+        ),
+      );
+    },
+  });
+
+  monaco.languages.registerOnTypeFormattingEditProvider("qsharp", {
+    autoFormatTriggerCharacters: [";", "}"],
+    provideOnTypeFormattingEdits: async (
+      model: monaco.editor.ITextModel,
+      position: monaco.Position,
+      ch: string,
+    ) => {
+      return toMonacoEdits(
+        await languageService.getOnTypeFormatChanges(
+          model.uri.toString(),
+          monacoPositionToLsPosition(position),
+          ch,
+        ),
+      );
     },
   });
 
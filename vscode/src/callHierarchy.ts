@@ -0,0 +1,64 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+import { ICallHierarchyItem, ILanguageService } from "qsharp-lang";
+import * as vscode from "vscode";
+import { toVscodeRange } from "./common";
+
+export function createCallHierarchyProvider(languageService: ILanguageService) {
+  return new QSharpCallHierarchyProvider(languageService);
+}
+
+// Keeps the language service item, so that it can be sent back
+// when the incoming or outgoing calls are requested.
+class QSharpCallHierarchyItem extends vscode.CallHierarchyItem {
+  constructor(public item: ICallHierarchyItem) {
+    super(
+      item.kind === "function"
+        ? vscode.SymbolKind.Function
+        : vscode.SymbolKind.Method,
+      item.name,
+      item.detail,
+      vscode.Uri.parse(item.location.source),
+      toVscodeRange(item.location.span),
+      toVscodeRange(item.selectionRange),
+    );
+  }
+}
+
+class QSharpCallHierarchyProvider implements vscode.CallHierarchyProvider {
+  constructor(public languageService: ILanguageService) {}
+
+  async prepareCallHierarchy(
+    document: vscode.TextDocument,
+    position: vscode.Position,
+  ) {
+    const items = await this.languageService.prepareCallHierarchy(
+      document.uri.toString(),
+      position,
+    );
+    return items.map((item) => new QSharpCallHierarchyItem(item));
+  }
+
+  async provideCallHierarchyIncomingCalls(item: QSharpCallHierarchyItem) {
+    const calls = await this.languageService.getIncomingCalls(item.item);
+    return calls.map(
+      (call) =>
+        new vscode.CallHierarchyIncomingCall(
+          new QSharpCallHierarchyItem(call.from),
+          call.fromRanges.map(toVscodeRange),
+        ),
+    );
+  }
+
+  async provideCallHierarchyOutgoingCalls(item: QSharpCallHierarchyItem) {
+    const calls = await this.languageService.getOutgoingCalls(item.item);
+    return calls.map(
+      (call) =>
+        new vscode.CallHierarchyOutgoingCall(
+          new QSharpCallHierarchyItem(call.to),
+          call.fromRanges.map(toVscodeRange),
+        ),
+    );
+  }
+}
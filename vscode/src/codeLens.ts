@@ -69,6 +69,12 @@ function mapCodeLens(cl: ICodeLens): vscode.CodeLens {
         args = [cl.args];
       }
       break;
+    case "runTest":
+      title = "Run test";
+      command = "qsharp-vscode.runTest";
+      tooltip = "Run test";
+      args = [cl.args];
+      break;
   }
 
   return new vscode.CodeLens(toVscodeRange(cl.range), {
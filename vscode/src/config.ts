@@ -28,6 +28,14 @@ export async function setTarget(target: TargetProfile) {
   );
 }
 
+export function getInlayHintsConfig() {
+  const config = vscode.workspace.getConfiguration("Q#.inlayHints");
+  return {
+    types: config.get<boolean>("types", true),
+    parameterNames: config.get<boolean>("parameterNames", true),
+  };
+}
+
 export function getTargetFriendlyName(targetProfile?: string) {
   switch (targetProfile) {
     case "base":
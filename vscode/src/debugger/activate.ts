@@ -57,6 +57,19 @@ function registerCommands(context: vscode.ExtensionContext) {
       (resource: vscode.Uri) =>
         startDebugging(resource, { name: "Debug Q# File", stopOnEntry: true }),
     ),
+    vscode.commands.registerCommand(
+      `${qsharpExtensionId}.runTest`,
+      (callableName: string, resource: vscode.Uri) =>
+        startDebugging(
+          resource,
+          {
+            name: `Run test ${callableName}`,
+            stopOnEntry: false,
+            entry: `${callableName}()`,
+          },
+          { noDebug: true },
+        ),
+    ),
     vscode.commands.registerCommand(
       `${qsharpExtensionId}.runEditorContentsWithCircuit`,
       (resource: vscode.Uri) =>
@@ -175,7 +175,7 @@ export class QscDebugSession extends LoggingDebugSession {
     response.body.supportsConfigurationDoneRequest = true;
 
     // make VS Code show a 'step back' button
-    response.body.supportsStepBack = false;
+    response.body.supportsStepBack = true;
 
     // make VS Code support data breakpoints
     response.body.supportsDataBreakpoints = false;
@@ -370,6 +370,21 @@ export class QscDebugSession extends LoggingDebugSession {
     );
   }
 
+  private async stepBack(): Promise<void> {
+    const bps = this.getBreakpointIds();
+    await this.eval_step(
+      async () => await this.debugService.evalStepBack(bps, this.eventTarget),
+    );
+  }
+
+  private async reverseContinue(): Promise<void> {
+    const bps = this.getBreakpointIds();
+    await this.eval_step(
+      async () =>
+        await this.debugService.evalReverseContinue(bps, this.eventTarget),
+    );
+  }
+
   private async endSession(message: string, exitCode: number): Promise<void> {
     log.trace(message);
     this.writeToDebugConsole("");
@@ -479,6 +494,26 @@ export class QscDebugSession extends LoggingDebugSession {
     await this.stepOut();
   }
 
+  protected async stepBackRequest(
+    response: DebugProtocol.StepBackResponse,
+    args: DebugProtocol.StepBackArguments,
+  ): Promise<void> {
+    log.trace(`stepBackRequest: %O`, args);
+    this.sendResponse(response);
+
+    await this.stepBack();
+  }
+
+  protected async reverseContinueRequest(
+    response: DebugProtocol.ReverseContinueResponse,
+    args: DebugProtocol.ReverseContinueArguments,
+  ): Promise<void> {
+    log.trace(`reverseContinueRequest: %O`, args);
+    this.sendResponse(response);
+
+    await this.reverseContinue();
+  }
+
   protected async breakpointLocationsRequest(
     response: DebugProtocol.BreakpointLocationsResponse,
     args: DebugProtocol.BreakpointLocationsArguments,
@@ -12,6 +12,7 @@ import {
 } from "qsharp-lang";
 import * as vscode from "vscode";
 import { initAzureWorkspaces } from "./azure/commands.js";
+import { createCallHierarchyProvider } from "./callHierarchy.js";
 import { createCodeActionsProvider } from "./codeActions.js";
 import { createCodeLensProvider } from "./codeLens.js";
 import {
@@ -20,7 +21,7 @@ import {
   qsharpLanguageId,
 } from "./common.js";
 import { createCompletionItemProvider } from "./completion";
-import { getTarget } from "./config";
+import { getInlayHintsConfig, getTarget } from "./config";
 import { initProjectCreator } from "./createProject.js";
 import { activateDebugger } from "./debugger/activate";
 import { createDefinitionProvider } from "./definition";
@@ -44,14 +45,24 @@ import {
   getGithubSourceContent,
   listDirectory,
   readFile,
+  resolveGithubCommit,
   resolvePath,
   setGithubEndpoint,
 } from "./projectSystem.js";
 import { initCodegen } from "./qirGeneration.js";
+import { createInlayHintsProvider } from "./inlayHints.js";
 import { createReferenceProvider } from "./references.js";
 import { createRenameProvider } from "./rename.js";
+import {
+  createSemanticTokensProvider,
+  semanticTokensLegend,
+} from "./semanticTokens.js";
 import { createSignatureHelpProvider } from "./signature.js";
 import { activateTargetProfileStatusBarItem } from "./statusbar.js";
+import {
+  createDocumentSymbolProvider,
+  createWorkspaceSymbolProvider,
+} from "./symbols.js";
 import {
   EventType,
   QsharpDocumentType,
@@ -262,6 +273,16 @@ async function activateLanguageService(extensionUri: vscode.Uri) {
     ),
   );
 
+  // format on type
+  subscriptions.push(
+    vscode.languages.registerOnTypeFormattingEditProvider(
+      qsharpLanguageId,
+      createFormattingProvider(languageService),
+      ";",
+      "}",
+    ),
+  );
+
   // completions
   subscriptions.push(
     vscode.languages.registerCompletionItemProvider(
@@ -330,6 +351,45 @@ async function activateLanguageService(extensionUri: vscode.Uri) {
     ),
   );
 
+  // outline and symbol search
+  subscriptions.push(
+    vscode.languages.registerDocumentSymbolProvider(
+      qsharpLanguageId,
+      createDocumentSymbolProvider(languageService),
+    ),
+  );
+
+  subscriptions.push(
+    vscode.languages.registerWorkspaceSymbolProvider(
+      createWorkspaceSymbolProvider(languageService),
+    ),
+  );
+
+  // semantic highlighting
+  subscriptions.push(
+    vscode.languages.registerDocumentSemanticTokensProvider(
+      qsharpLanguageId,
+      createSemanticTokensProvider(languageService),
+      semanticTokensLegend,
+    ),
+  );
+
+  // inlay hints
+  subscriptions.push(
+    vscode.languages.registerInlayHintsProvider(
+      qsharpLanguageId,
+      createInlayHintsProvider(languageService),
+    ),
+  );
+
+  // call hierarchy
+  subscriptions.push(
+    vscode.languages.registerCallHierarchyProvider(
+      qsharpLanguageId,
+      createCallHierarchyProvider(languageService),
+    ),
+  );
+
   // add the language service dispose handler as well
   subscriptions.push(languageService);
 
@@ -355,6 +415,12 @@ async function updateLanguageServiceProfile(languageService: ILanguageService) {
   });
 }
 
+function updateLanguageServiceInlayHints(languageService: ILanguageService) {
+  languageService.updateConfiguration({
+    inlayHints: getInlayHintsConfig(),
+  });
+}
+
 async function loadLanguageService(baseUri: vscode.Uri) {
   const start = performance.now();
   const wasmUri = vscode.Uri.joinPath(baseUri, "./wasm/qsc_wasm_bg.wasm");
@@ -366,8 +432,10 @@ async function loadLanguageService(baseUri: vscode.Uri) {
     listDirectory,
     resolvePath: async (a, b) => resolvePath(a, b),
     fetchGithub: fetchGithubRaw,
+    resolveGithubCommit,
   });
   await updateLanguageServiceProfile(languageService);
+  updateLanguageServiceInlayHints(languageService);
   const end = performance.now();
   sendTelemetryEvent(
     EventType.LoadLanguageService,
@@ -384,6 +452,9 @@ function registerConfigurationChangeHandlers(
     if (event.affectsConfiguration("Q#.qir.targetProfile")) {
       updateLanguageServiceProfile(languageService);
     }
+    if (event.affectsConfiguration("Q#.inlayHints")) {
+      updateLanguageServiceInlayHints(languageService);
+    }
   });
 }
 
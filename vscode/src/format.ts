@@ -14,14 +14,14 @@ export function createFormattingProvider(languageService: ILanguageService) {
 class QSharpFormattingProvider
   implements
     vscode.DocumentFormattingEditProvider,
-    vscode.DocumentRangeFormattingEditProvider
+    vscode.DocumentRangeFormattingEditProvider,
+    vscode.OnTypeFormattingEditProvider
 {
   constructor(public languageService: ILanguageService) {}
 
   private async getFormatChanges(
-    document: vscode.TextDocument,
     eventKind: FormatEvent,
-    range?: vscode.Range,
+    getLsEdits: () => ReturnType<ILanguageService["getFormatChanges"]>,
   ) {
     // telemetry start format
     const associationId = getRandomGuid();
@@ -32,9 +32,7 @@ class QSharpFormattingProvider
     );
     const start = performance.now();
 
-    const lsEdits = await this.languageService.getFormatChanges(
-      document.uri.toString(),
-    );
+    const lsEdits = await getLsEdits();
 
     if (!lsEdits) {
       // telemetry end format
@@ -49,17 +47,10 @@ class QSharpFormattingProvider
       return [];
     }
 
-    let edits = lsEdits.map(
+    const edits = lsEdits.map(
       (edit) => new vscode.TextEdit(toVscodeRange(edit.range), edit.newText),
     );
 
-    if (range) {
-      edits = edits.filter(
-        (e) =>
-          range.start.isBefore(e.range.end) && range.end.isAfter(e.range.start),
-      );
-    }
-
     // telemetry end format
     sendTelemetryEvent(
       EventType.FormatEnd,
@@ -74,13 +65,34 @@ class QSharpFormattingProvider
   }
 
   async provideDocumentFormattingEdits(document: vscode.TextDocument) {
-    return await this.getFormatChanges(document, FormatEvent.OnDocument);
+    return await this.getFormatChanges(FormatEvent.OnDocument, () =>
+      this.languageService.getFormatChanges(document.uri.toString()),
+    );
   }
 
   async provideDocumentRangeFormattingEdits(
     document: vscode.TextDocument,
     range: vscode.Range,
   ) {
-    return await this.getFormatChanges(document, FormatEvent.OnRange, range);
+    return await this.getFormatChanges(FormatEvent.OnRange, () =>
+      this.languageService.getRangeFormatChanges(
+        document.uri.toString(),
+        range,
+      ),
+    );
+  }
+
+  async provideOnTypeFormattingEdits(
+    document: vscode.TextDocument,
+    position: vscode.Position,
+    ch: string,
+  ) {
+    return await this.getFormatChanges(FormatEvent.OnType, () =>
+      this.languageService.getOnTypeFormatChanges(
+        document.uri.toString(),
+        position,
+        ch,
+      ),
+    );
   }
 }
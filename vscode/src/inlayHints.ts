@@ -0,0 +1,45 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+import { ILanguageService, IInlayHint } from "qsharp-lang";
+import * as vscode from "vscode";
+
+export function createInlayHintsProvider(languageService: ILanguageService) {
+  return new QSharpInlayHintsProvider(languageService);
+}
+
+class QSharpInlayHintsProvider implements vscode.InlayHintsProvider {
+  private onDidChangeEmitter = new vscode.EventEmitter<void>();
+  onDidChangeInlayHints = this.onDidChangeEmitter.event;
+
+  constructor(public languageService: ILanguageService) {
+    // The settings are sent to the language service in extension.ts,
+    // here the editor is only told to request the hints again.
+    vscode.workspace.onDidChangeConfiguration((event) => {
+      if (event.affectsConfiguration("Q#.inlayHints")) {
+        this.onDidChangeEmitter.fire();
+      }
+    });
+  }
+
+  async provideInlayHints(document: vscode.TextDocument, range: vscode.Range) {
+    const hints = await this.languageService.getInlayHints(
+      document.uri.toString(),
+      range,
+    );
+    return hints.map(toInlayHint);
+  }
+}
+
+function toInlayHint(hint: IInlayHint): vscode.InlayHint {
+  const inlayHint = new vscode.InlayHint(
+    new vscode.Position(hint.position.line, hint.position.character),
+    hint.label,
+    hint.kind === "type"
+      ? vscode.InlayHintKind.Type
+      : vscode.InlayHintKind.Parameter,
+  );
+  inlayHint.paddingLeft = hint.paddingLeft;
+  inlayHint.paddingRight = hint.paddingRight;
+  return inlayHint;
+}
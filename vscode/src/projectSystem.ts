@@ -175,6 +175,7 @@ export async function loadProject(
       readFile,
       listDirectory,
       fetchGithub: fetchGithubRaw,
+      resolveGithubCommit,
       resolvePath: async (a, b) => resolvePath(a, b),
     });
   }
@@ -290,3 +291,35 @@ export async function fetchGithubRaw(
 
   return text;
 }
+
+const githubApiEndpoint = "https://api.github.com";
+
+/**
+ * Makes a request to the GitHub API to resolve a branch, tag or commit to the full SHA
+ * of the commit it points to. Throws if the ref can't be resolved.
+ */
+export async function resolveGithubCommit(
+  owner: string,
+  repo: string,
+  ref: string,
+): Promise<string> {
+  const uri = `${githubApiEndpoint}/repos/${owner}/${repo}/commits/${encodeURIComponent(ref)}`;
+  log.info(`making request to ${uri}`);
+  const response = await fetch(uri, {
+    headers: { Accept: "application/vnd.github.sha" },
+  });
+  if (!response.ok) {
+    log.warn(
+      `resolveGithubCommit: ${owner}/${repo}@${ref} -> ${response.status} ${response.statusText}`,
+    );
+    throw new Error(
+      `Request to ${uri} failed with status ${response.status} ${response.statusText ? ": " + response.statusText : ""}`,
+    );
+  }
+
+  const sha = (await response.text()).trim();
+  if (!/^[0-9a-f]{40}$/.test(sha)) {
+    throw new Error(`${owner}/${repo}@${ref} did not resolve to a commit`);
+  }
+  return sha;
+}
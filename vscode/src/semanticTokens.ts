@@ -0,0 +1,78 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+import { ILanguageService } from "qsharp-lang";
+import * as vscode from "vscode";
+
+// The order of these must match the order of the `SemanticTokenType`
+// and `SemanticTokenModifier` variants in the language service.
+export const semanticTokensLegend = new vscode.SemanticTokensLegend(
+  [
+    "namespace",
+    "struct",
+    "typeParameter",
+    "parameter",
+    "variable",
+    "property",
+    "function",
+    "operation",
+  ],
+  [
+    "declaration",
+    "mutable",
+    "deprecated",
+    "adjointable",
+    "controllable",
+    "qubit",
+    "defaultLibrary",
+  ],
+);
+
+export function createSemanticTokensProvider(
+  languageService: ILanguageService,
+) {
+  return new QSharpSemanticTokensProvider(languageService);
+}
+
+class QSharpSemanticTokensProvider
+  implements vscode.DocumentSemanticTokensProvider
+{
+  constructor(public languageService: ILanguageService) {}
+
+  async provideDocumentSemanticTokens(document: vscode.TextDocument) {
+    const tokens = await this.languageService.getSemanticTokens(
+      document.uri.toString(),
+    );
+    return new vscode.SemanticTokens(
+      new Uint32Array(tokens.data),
+      tokens.resultId,
+    );
+  }
+
+  async provideDocumentSemanticTokensEdits(
+    document: vscode.TextDocument,
+    previousResultId: string,
+  ) {
+    const delta = await this.languageService.getSemanticTokensDelta(
+      document.uri.toString(),
+      previousResultId,
+    );
+    if ("data" in delta) {
+      return new vscode.SemanticTokens(
+        new Uint32Array(delta.data),
+        delta.resultId,
+      );
+    }
+    return new vscode.SemanticTokensEdits(
+      delta.edits.map(
+        (edit) =>
+          new vscode.SemanticTokensEdit(
+            edit.start,
+            edit.deleteCount,
+            new Uint32Array(edit.data),
+          ),
+      ),
+      delta.resultId,
+    );
+  }
+}
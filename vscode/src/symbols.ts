@@ -0,0 +1,81 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+import {
+  IDocumentSymbol,
+  ILanguageService,
+  IWorkspaceSymbol,
+} from "qsharp-lang";
+import * as vscode from "vscode";
+import { toVscodeLocation, toVscodeRange } from "./common";
+
+export function createDocumentSymbolProvider(
+  languageService: ILanguageService,
+) {
+  return new QSharpDocumentSymbolProvider(languageService);
+}
+
+export function createWorkspaceSymbolProvider(
+  languageService: ILanguageService,
+) {
+  return new QSharpWorkspaceSymbolProvider(languageService);
+}
+
+class QSharpDocumentSymbolProvider implements vscode.DocumentSymbolProvider {
+  constructor(public languageService: ILanguageService) {}
+  async provideDocumentSymbols(document: vscode.TextDocument) {
+    const symbols = await this.languageService.getDocumentSymbols(
+      document.uri.toString(),
+    );
+    return symbols.map(toVscodeDocumentSymbol);
+  }
+}
+
+class QSharpWorkspaceSymbolProvider implements vscode.WorkspaceSymbolProvider {
+  constructor(public languageService: ILanguageService) {}
+  async provideWorkspaceSymbols(query: string) {
+    const symbols = await this.languageService.getWorkspaceSymbols(query);
+    return symbols.map(
+      (symbol: IWorkspaceSymbol) =>
+        new vscode.SymbolInformation(
+          symbol.name,
+          toVscodeSymbolKind(symbol.kind),
+          symbol.containerName ?? "",
+          toVscodeLocation(symbol.location),
+        ),
+    );
+  }
+}
+
+function toVscodeDocumentSymbol(
+  symbol: IDocumentSymbol,
+): vscode.DocumentSymbol {
+  const documentSymbol = new vscode.DocumentSymbol(
+    symbol.name,
+    "",
+    toVscodeSymbolKind(symbol.kind),
+    toVscodeRange(symbol.range),
+    toVscodeRange(symbol.selectionRange),
+  );
+  documentSymbol.children = symbol.children.map(toVscodeDocumentSymbol);
+  return documentSymbol;
+}
+
+function toVscodeSymbolKind(
+  kind: IDocumentSymbol["kind"],
+): vscode.SymbolKind {
+  switch (kind) {
+    case "namespace":
+      return vscode.SymbolKind.Namespace;
+    case "function":
+      return vscode.SymbolKind.Function;
+    case "operation":
+      return vscode.SymbolKind.Method;
+    case "struct":
+      return vscode.SymbolKind.Struct;
+    case "newtype":
+      return vscode.SymbolKind.Class;
+    case "specialization":
+      return vscode.SymbolKind.Method;
+  }
+}
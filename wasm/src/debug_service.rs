@@ -106,11 +106,27 @@ impl DebugService {
         self.eval(event_cb, ids, StepAction::Out)
     }
 
+    pub fn eval_step_back(
+        &mut self,
+        event_cb: &js_sys::Function,
+        ids: &[u32],
+    ) -> Result<IStructStepResult, JsValue> {
+        self.eval(event_cb, ids, Step::Back)
+    }
+
+    pub fn eval_reverse_continue(
+        &mut self,
+        event_cb: &js_sys::Function,
+        ids: &[u32],
+    ) -> Result<IStructStepResult, JsValue> {
+        self.eval(event_cb, ids, Step::ReverseContinue)
+    }
+
     fn eval(
         &mut self,
         event_cb: &js_sys::Function,
         ids: &[u32],
-        step: StepAction,
+        step: impl Into<Step>,
     ) -> Result<IStructStepResult, JsValue> {
         if !event_cb.is_function() {
             return Err(JsError::new("Events callback function must be provided").into());
@@ -121,7 +137,7 @@ impl DebugService {
             // See example at https://rustwasm.github.io/wasm-bindgen/reference/receiving-js-closures-in-rust.html
             let _ = event_cb.call1(&JsValue::null(), &JsValue::from(msg));
         };
-        match self.run_internal(event_cb, &bps, step) {
+        match self.run_internal(event_cb, &bps, step.into()) {
             Ok(value) => Ok(StructStepResult::from(value).into()),
             Err(e) => Err(JsError::from(&e[0]).into()),
         }
@@ -131,13 +147,17 @@ impl DebugService {
         &mut self,
         event_cb: F,
         bps: &[StmtId],
-        step: StepAction,
+        step: Step,
     ) -> Result<StepResult, Vec<Error>>
     where
         F: Fn(&str),
     {
         let mut out = CallbackReceiver { event_cb };
-        let result = self.debugger_mut().eval_step(&mut out, bps, step);
+        let result = match step {
+            Step::Forward(step) => self.debugger_mut().eval_step(&mut out, bps, step),
+            Step::Back => self.debugger_mut().step_back(),
+            Step::ReverseContinue => self.debugger_mut().reverse_continue(bps),
+        };
         let mut success = true;
 
         let msg: Option<serde_json::Value> = match &result {
@@ -234,6 +254,20 @@ fn render_error(error: Error) -> String {
     format!("{:?}\n", miette::Report::new(error))
 }
 
+/// The direction and kind of a debugger step.
+#[derive(Clone, Copy)]
+enum Step {
+    Forward(StepAction),
+    Back,
+    ReverseContinue,
+}
+
+impl From<StepAction> for Step {
+    fn from(step: StepAction) -> Self {
+        Step::Forward(step)
+    }
+}
+
 impl From<StepResult> for StructStepResult {
     fn from(value: StepResult) -> Self {
         match value {
@@ -260,6 +260,9 @@ fn interpret_error_labels(err: &interpret::Error) -> Vec<Label> {
         interpret::Error::NoEntryPoint
         | interpret::Error::UnsupportedRuntimeCapabilities
         | interpret::Error::Circuit(_)
-        | interpret::Error::NotAnOperation => Vec::new(),
+        | interpret::Error::NotAnOperation
+        | interpret::Error::Qasm3Gen(_)
+        | interpret::Error::NoOperationMatrix
+        | interpret::Error::ReplayDiverged => Vec::new(),
     }
 }
@@ -95,6 +95,12 @@ impl LanguageService {
                     .languageFeatures
                     .map(|features| features.iter().collect::<LanguageFeatures>()),
                 lints_config: config.lints,
+                inlay_hints: config
+                    .inlayHints
+                    .map(|hints| qsls::protocol::InlayHintsConfig {
+                        types: hints.types,
+                        parameter_names: hints.parameterNames,
+                    }),
             });
     }
 
@@ -227,6 +233,41 @@ impl LanguageService {
             .collect()
     }
 
+    pub fn get_range_format_changes(&self, uri: &str, range: IRange) -> Vec<ITextEdit> {
+        let range: Range = range.into();
+        let edits = self.0.get_range_format_changes(uri, range.into());
+        edits
+            .into_iter()
+            .map(|edit| {
+                TextEdit {
+                    range: edit.range.into(),
+                    newText: edit.new_text,
+                }
+                .into()
+            })
+            .collect()
+    }
+
+    pub fn get_on_type_format_changes(
+        &self,
+        uri: &str,
+        position: IPosition,
+        ch: &str,
+    ) -> Vec<ITextEdit> {
+        let position: Position = position.into();
+        let edits = self.0.get_on_type_format_changes(uri, position.into(), ch);
+        edits
+            .into_iter()
+            .map(|edit| {
+                TextEdit {
+                    range: edit.range.into(),
+                    newText: edit.new_text,
+                }
+                .into()
+            })
+            .collect()
+    }
+
     pub fn get_hover(&self, uri: &str, position: IPosition) -> Option<IHover> {
         let position: Position = position.into();
         let hover = self.0.get_hover(uri, position.into());
@@ -314,11 +355,16 @@ impl LanguageService {
                     qsls::protocol::CodeLensCommand::Estimate => ("estimate", None),
                     qsls::protocol::CodeLensCommand::Circuit(args) => (
                         "circuit",
-                        args.map(|args| OperationInfo {
-                            operation: args.operation,
-                            total_num_qubits: args.total_num_qubits,
+                        args.map(|args| {
+                            CodeLensArgs::Operation(OperationInfo {
+                                operation: args.operation,
+                                total_num_qubits: args.total_num_qubits,
+                            })
                         }),
                     ),
+                    qsls::protocol::CodeLensCommand::RunTest(callable_name) => {
+                        ("runTest", Some(CodeLensArgs::CallableName(callable_name)))
+                    }
                 };
                 CodeLens {
                     range,
@@ -329,6 +375,135 @@ impl LanguageService {
             })
             .collect()
     }
+
+    pub fn get_semantic_tokens(&self, uri: &str) -> ISemanticTokens {
+        let tokens = self.0.get_semantic_tokens(uri);
+        SemanticTokens {
+            resultId: tokens.result_id,
+            data: tokens.data,
+        }
+        .into()
+    }
+
+    pub fn get_semantic_tokens_delta(
+        &self,
+        uri: &str,
+        previous_result_id: &str,
+    ) -> ISemanticTokensDelta {
+        let delta = match self.0.get_semantic_tokens_delta(uri, previous_result_id) {
+            qsls::protocol::SemanticTokensDelta::Full(tokens) => SemanticTokensDelta {
+                resultId: tokens.result_id,
+                data: Some(tokens.data),
+                edits: None,
+            },
+            qsls::protocol::SemanticTokensDelta::Edits { result_id, edits } => {
+                SemanticTokensDelta {
+                    resultId: result_id,
+                    data: None,
+                    edits: Some(
+                        edits
+                            .into_iter()
+                            .map(|edit| SemanticTokensEdit {
+                                start: edit.start,
+                                deleteCount: edit.delete_count,
+                                data: edit.data,
+                            })
+                            .collect(),
+                    ),
+                }
+            }
+        };
+        delta.into()
+    }
+
+    pub fn get_document_symbols(&self, uri: &str) -> Vec<IDocumentSymbol> {
+        let symbols = self.0.get_document_symbols(uri);
+        symbols
+            .into_iter()
+            .map(|symbol| DocumentSymbol::from(symbol).into())
+            .collect()
+    }
+
+    pub fn get_workspace_symbols(&self, query: &str) -> Vec<IWorkspaceSymbol> {
+        let symbols = self.0.get_workspace_symbols(query);
+        symbols
+            .into_iter()
+            .map(|symbol| {
+                WorkspaceSymbol {
+                    name: symbol.name,
+                    kind: symbol_kind_name(symbol.kind).to_string(),
+                    containerName: symbol.container_name,
+                    location: symbol.location.into(),
+                }
+                .into()
+            })
+            .collect()
+    }
+
+    pub fn prepare_call_hierarchy(
+        &self,
+        uri: &str,
+        position: IPosition,
+    ) -> Vec<ICallHierarchyItem> {
+        let position: Position = position.into();
+        let items = self.0.prepare_call_hierarchy(uri, position.into());
+        items
+            .into_iter()
+            .map(|item| CallHierarchyItem::from(item).into())
+            .collect()
+    }
+
+    pub fn get_incoming_calls(&self, item: ICallHierarchyItem) -> Vec<ICallHierarchyIncomingCall> {
+        let item: CallHierarchyItem = item.into();
+        let calls = self.0.get_incoming_calls(&item.into());
+        calls
+            .into_iter()
+            .map(|call| {
+                CallHierarchyIncomingCall {
+                    from: call.from.into(),
+                    fromRanges: call.from_ranges.into_iter().map(Into::into).collect(),
+                }
+                .into()
+            })
+            .collect()
+    }
+
+    pub fn get_outgoing_calls(&self, item: ICallHierarchyItem) -> Vec<ICallHierarchyOutgoingCall> {
+        let item: CallHierarchyItem = item.into();
+        let calls = self.0.get_outgoing_calls(&item.into());
+        calls
+            .into_iter()
+            .map(|call| {
+                CallHierarchyOutgoingCall {
+                    to: call.to.into(),
+                    fromRanges: call.from_ranges.into_iter().map(Into::into).collect(),
+                }
+                .into()
+            })
+            .collect()
+    }
+
+    pub fn get_inlay_hints(&self, uri: &str, range: IRange) -> Vec<IInlayHint> {
+        let range: Range = range.into();
+        let hints = self.0.get_inlay_hints(uri, range.into());
+        hints
+            .into_iter()
+            .map(|hint| {
+                InlayHint {
+                    position: hint.position.into(),
+                    label: hint.label,
+                    kind: match hint.kind {
+                        qsls::protocol::InlayHintKind::Type => "type",
+                        qsls::protocol::InlayHintKind::Parameter => "parameter",
+                    }
+                    .to_string(),
+                    paddingLeft: hint.padding_left,
+                    paddingRight: hint.padding_right,
+                }
+                .into()
+            })
+            .collect()
+    }
 }
 
 serializable_type! {
@@ -337,17 +512,31 @@ serializable_type! {
         pub targetProfile: Option<String>,
         pub packageType: Option<String>,
         pub languageFeatures: Option<Vec<String>>,
-        pub lints: Option<Vec<LintConfig>>
+        pub lints: Option<Vec<LintConfig>>,
+        pub inlayHints: Option<InlayHintsConfiguration>
     },
     r#"export interface IWorkspaceConfiguration {
         targetProfile?: TargetProfile;
         packageType?: "exe" | "lib";
         languageFeatures?: LanguageFeatures[];
         lints?: { lint: string; level: string }[];
+        inlayHints?: IInlayHintsConfiguration;
     }"#,
     IWorkspaceConfiguration
 }
 
+serializable_type! {
+    InlayHintsConfiguration,
+    {
+        pub types: bool,
+        pub parameterNames: bool,
+    },
+    r#"export interface IInlayHintsConfiguration {
+        types: boolean;
+        parameterNames: boolean;
+    }"#
+}
+
 serializable_type! {
     CodeAction,
     {
@@ -505,7 +694,7 @@ serializable_type! {
         range: Range,
         command: String,
         #[serde(skip_serializing_if = "Option::is_none")]
-        args: Option<OperationInfo>,
+        args: Option<CodeLensArgs>,
     },
     r#"export type ICodeLens = {
         range: IRange;
@@ -514,10 +703,23 @@ serializable_type! {
         range: IRange;
         command: "circuit";
         args?: IOperationInfo
+    } | {
+        range: IRange;
+        command: "runTest";
+        args: string
     }"#,
     ICodeLens
 }
 
+/// The arguments of a code lens command, which depend on the command.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum CodeLensArgs {
+    Operation(OperationInfo),
+    /// The fully qualified name of a callable.
+    CallableName(String),
+}
+
 serializable_type! {
     OperationInfo,
     {
@@ -532,6 +734,201 @@ serializable_type! {
     IOperationInfo
 }
 
+serializable_type! {
+    SemanticTokens,
+    {
+        pub resultId: String,
+        pub data: Vec<u32>,
+    },
+    r#"export interface ISemanticTokens {
+        resultId: string;
+        data: number[];
+    }"#,
+    ISemanticTokens
+}
+
+serializable_type! {
+    SemanticTokensDelta,
+    {
+        pub resultId: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub data: Option<Vec<u32>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub edits: Option<Vec<SemanticTokensEdit>>,
+    },
+    r#"export type ISemanticTokensDelta = ISemanticTokens | {
+        resultId: string;
+        edits: ISemanticTokensEdit[];
+    }"#,
+    ISemanticTokensDelta
+}
+
+serializable_type! {
+    SemanticTokensEdit,
+    {
+        pub start: u32,
+        pub deleteCount: u32,
+        pub data: Vec<u32>,
+    },
+    r#"export interface ISemanticTokensEdit {
+        start: number;
+        deleteCount: number;
+        data: number[];
+    }"#
+}
+
+serializable_type! {
+    InlayHint,
+    {
+        pub position: Position,
+        pub label: String,
+        pub kind: String,
+        pub paddingLeft: bool,
+        pub paddingRight: bool,
+    },
+    r#"export interface IInlayHint {
+        position: IPosition;
+        label: string;
+        kind: "type" | "parameter";
+        paddingLeft: boolean;
+        paddingRight: boolean;
+    }"#,
+    IInlayHint
+}
+
+serializable_type! {
+    DocumentSymbol,
+    {
+        pub name: String,
+        pub kind: String,
+        pub range: Range,
+        pub selectionRange: Range,
+        pub children: Vec<DocumentSymbol>,
+    },
+    r#"export interface IDocumentSymbol {
+        name: string;
+        kind: SymbolKind;
+        range: IRange;
+        selectionRange: IRange;
+        children: IDocumentSymbol[];
+    }"#,
+    IDocumentSymbol
+}
+
+impl From<qsls::protocol::DocumentSymbol> for DocumentSymbol {
+    fn from(symbol: qsls::protocol::DocumentSymbol) -> Self {
+        Self {
+            name: symbol.name,
+            kind: symbol_kind_name(symbol.kind).to_string(),
+            range: symbol.range.into(),
+            selectionRange: symbol.selection_range.into(),
+            children: symbol.children.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+serializable_type! {
+    WorkspaceSymbol,
+    {
+        pub name: String,
+        pub kind: String,
+        pub containerName: Option<String>,
+        pub location: Location,
+    },
+    r#"export type SymbolKind = "namespace" | "function" | "operation" | "struct" | "newtype" | "specialization";
+    export interface IWorkspaceSymbol {
+        name: string;
+        kind: SymbolKind;
+        containerName?: string;
+        location: ILocation;
+    }"#,
+    IWorkspaceSymbol
+}
+
+fn symbol_kind_name(kind: qsls::protocol::SymbolKind) -> &'static str {
+    use qsls::protocol::SymbolKind;
+    match kind {
+        SymbolKind::Namespace => "namespace",
+        SymbolKind::Function => "function",
+        SymbolKind::Operation => "operation",
+        SymbolKind::Struct => "struct",
+        SymbolKind::Newtype => "newtype",
+        SymbolKind::Specialization => "specialization",
+    }
+}
+
+serializable_type! {
+    CallHierarchyItem,
+    {
+        pub name: String,
+        pub kind: String,
+        pub detail: String,
+        pub location: Location,
+        pub selectionRange: Range,
+    },
+    r#"export interface ICallHierarchyItem {
+        name: string;
+        kind: "function" | "operation";
+        detail: string;
+        location: ILocation;
+        selectionRange: IRange;
+    }"#,
+    ICallHierarchyItem
+}
+
+impl From<qsls::protocol::CallHierarchyItem> for CallHierarchyItem {
+    fn from(item: qsls::protocol::CallHierarchyItem) -> Self {
+        Self {
+            name: item.name,
+            kind: symbol_kind_name(item.kind).to_string(),
+            detail: item.detail,
+            location: item.location.into(),
+            selectionRange: item.selection_range.into(),
+        }
+    }
+}
+
+impl From<CallHierarchyItem> for qsls::protocol::CallHierarchyItem {
+    fn from(item: CallHierarchyItem) -> Self {
+        Self {
+            name: item.name,
+            kind: match item.kind.as_str() {
+                "function" => qsls::protocol::SymbolKind::Function,
+                _ => qsls::protocol::SymbolKind::Operation,
+            },
+            detail: item.detail,
+            location: item.location.into(),
+            selection_range: item.selectionRange.into(),
+        }
+    }
+}
+
+serializable_type! {
+    CallHierarchyIncomingCall,
+    {
+        pub from: CallHierarchyItem,
+        pub fromRanges: Vec<Range>,
+    },
+    r#"export interface ICallHierarchyIncomingCall {
+        from: ICallHierarchyItem;
+        fromRanges: IRange[];
+    }"#,
+    ICallHierarchyIncomingCall
+}
+
+serializable_type! {
+    CallHierarchyOutgoingCall,
+    {
+        pub to: CallHierarchyItem,
+        pub fromRanges: Vec<Range>,
+    },
+    r#"export interface ICallHierarchyOutgoingCall {
+        to: ICallHierarchyItem;
+        fromRanges: IRange[];
+    }"#,
+    ICallHierarchyOutgoingCall
+}
+
 serializable_type! {
     WorkspaceEdit,
     {
@@ -579,7 +976,7 @@ serializable_type! {
         pub projectRoot: Option<String>,
     },
     r#"export interface INotebookMetadata {
-        targetProfile?: "base" | "adaptive_ri" | "unrestricted";
+        targetProfile?: "base" | "adaptive_ri" | "adaptive_rif" | "unrestricted";
         languageFeatures?: "v2-preview-syntax"[];
         manifest?: string;
         projectRoot?: string;
@@ -19,14 +19,16 @@ use qsc::{
         output::{self, Receiver},
         CircuitEntryPoint,
     },
+    noisy::{self, DensityMatrixSim, NoiseModel},
     target::Profile,
-    LanguageFeatures, PackageStore, PackageType, SourceContents, SourceMap, SourceName, SparseSim,
-    TargetCapabilityFlags,
+    ChannelNoise, LanguageFeatures, NoiseConfig, PackageStore, PackageType, PauliNoise,
+    SourceContents, SourceMap, SourceName, TargetCapabilityFlags,
 };
 use resource_estimator::{self as re, estimate_entry};
+use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::{fmt::Write, str::FromStr};
+use std::{fmt::Write, ops::ControlFlow, str::FromStr};
 use wasm_bindgen::prelude::*;
 
 mod debug_service;
@@ -80,6 +82,21 @@ pub(crate) fn _get_qir(
         .map_err(interpret_errors_into_qsharp_errors_json)
 }
 
+#[wasm_bindgen]
+pub fn get_qasm3(program: ProgramConfig) -> Result<String, String> {
+    let (source_map, capabilities, language_features, store, deps) =
+        into_qsc_args(program, None).map_err(compile_errors_into_qsharp_errors_json)?;
+
+    qsc::codegen::qasm3::get_qasm3(
+        source_map,
+        language_features,
+        capabilities,
+        store,
+        &deps[..],
+    )
+    .map_err(interpret_errors_into_qsharp_errors_json)
+}
+
 #[wasm_bindgen]
 pub fn get_estimates(program: ProgramConfig, params: &str) -> Result<String, String> {
     let (source_map, capabilities, language_features, store, deps) = into_qsc_args(program, None)
@@ -143,6 +160,54 @@ pub fn get_circuit(
     serde_wasm_bindgen::to_value(&circuit).map_err(|e| e.to_string())
 }
 
+serializable_type! {
+    OperationMatrix,
+    {
+        pub matrix: Vec<Vec<(f64, f64)>>,
+        pub matrix_latex: String,
+    },
+    r#"export interface IOperationMatrix {
+        matrix: Array<Array<[number, number]>>;
+        matrixLatex: string;
+    }"#,
+    IOperationMatrix
+}
+
+/// Computes the unitary matrix of `operation`, which can be an operation name
+/// or a lambda expression that takes an array of `n_qubits` qubits.
+#[wasm_bindgen]
+pub fn get_operation_matrix(
+    program: ProgramConfig,
+    operation: &str,
+    n_qubits: u32,
+) -> Result<IOperationMatrix, String> {
+    let (source_map, capabilities, language_features, store, deps) =
+        into_qsc_args(program, None).map_err(compile_errors_into_qsharp_errors_json)?;
+
+    let mut interpreter = interpret::Interpreter::new(
+        source_map,
+        PackageType::Lib,
+        capabilities,
+        LanguageFeatures::from_iter(language_features),
+        store,
+        &deps[..],
+    )
+    .map_err(interpret_errors_into_qsharp_errors_json)?;
+
+    let matrix = interpreter
+        .get_operation_matrix(operation, n_qubits as usize)
+        .map_err(interpret_errors_into_qsharp_errors_json)?;
+
+    Ok(OperationMatrix {
+        matrix_latex: get_matrix_latex(&matrix),
+        matrix: matrix
+            .iter()
+            .map(|row| row.iter().map(|elem| (elem.re, elem.im)).collect())
+            .collect(),
+    }
+    .into())
+}
+
 #[allow(clippy::needless_pass_by_value)]
 fn interpret_errors_into_qsharp_errors_json(errs: Vec<qsc::interpret::Error>) -> String {
     serde_json::to_string(&interpret_errors_into_qsharp_errors(&errs))
@@ -325,6 +390,8 @@ fn run_internal_with_features<F>(
     sources: SourceMap,
     event_cb: F,
     shots: u32,
+    noise: Option<&NoiseConfig>,
+    noise_model: Option<&(usize, NoiseModel)>,
     language_features: LanguageFeatures,
     capabilities: TargetCapabilityFlags,
     store: PackageStore,
@@ -354,30 +421,175 @@ where
         }
     };
 
-    for _ in 0..shots {
-        let result = interpreter.eval_entry_with_sim(&mut SparseSim::new(), &mut out);
-        let mut success = true;
-        let msg: serde_json::Value = match result {
-            Ok(value) => serde_json::Value::String(value.to_string()),
-            Err(errors) => {
-                success = false;
-                interpret_errors_into_qsharp_errors_json_value(&errors)
-            }
-        };
+    if let Some((qubits, noise_model)) = noise_model {
+        for _ in 0..shots {
+            let mut sim = DensityMatrixSim::new(*qubits, noise_model.clone());
+            let result = interpreter.eval_entry_with_sim(&mut sim, &mut out);
+            (out.event_cb)(&result_event(result.map(|value| value.to_string())));
+        }
+        return Ok(());
+    }
 
-        let msg_string = json!({"type": "Result", "success": success, "result": msg}).to_string();
-        (out.event_cb)(&msg_string);
+    // Threads aren't available in the browser, so all shots run on the current thread.
+    let result = interpreter.run_shots_with(None, shots as usize, None, 1, noise, |shot| {
+        for output in &shot.output {
+            // The callback receiver never fails to handle output.
+            let _ = output.send_to(&mut out);
+        }
+        (out.event_cb)(&result_event(
+            shot.result
+                .as_ref()
+                .map(ToString::to_string)
+                .map_err(Clone::clone),
+        ));
+        ControlFlow::Continue(())
+    });
+    if let Err(errors) = result {
+        for _ in 0..shots {
+            (out.event_cb)(&result_event(Err(errors.clone())));
+        }
     }
     Ok(())
 }
 
+/// Renders the `Result` event reported at the end of each shot.
+fn result_event(result: Result<String, Vec<interpret::Error>>) -> String {
+    let (success, msg) = match result {
+        Ok(value) => (true, serde_json::Value::String(value)),
+        Err(errors) => (
+            false,
+            interpret_errors_into_qsharp_errors_json_value(&errors),
+        ),
+    };
+    json!({"type": "Result", "success": success, "result": msg}).to_string()
+}
+
+serializable_type! {
+    NoiseModelConfig,
+    {
+        pub qubits: usize,
+        #[serde(default)]
+        pub gates: FxHashMap<String, KrausOperators>,
+        pub measurement: Option<Vec<KrausOperators>>,
+    },
+    r#"export interface INoiseModel {
+        qubits: number;
+        gates?: { [gate: string]: Array<Array<Array<[number, number]>>> };
+        measurement?: Array<Array<Array<Array<[number, number]>>>>;
+    }"#
+}
+
+/// The Kraus operators of an operation, each given as the rows of a matrix
+/// whose entries are pairs of real and imaginary parts.
+type KrausOperators = Vec<Vec<Vec<(f64, f64)>>>;
+
+impl NoiseModelConfig {
+    fn into_noise_model(self) -> Result<(usize, NoiseModel), String> {
+        fn operation(kraus_operators: KrausOperators) -> Result<noisy::Operation, String> {
+            let kraus_operators = kraus_operators
+                .into_iter()
+                .map(|rows| {
+                    rows.into_iter()
+                        .map(|row| {
+                            row.into_iter()
+                                .map(|(re, im)| Complex64::new(re, im))
+                                .collect()
+                        })
+                        .collect()
+                })
+                .collect::<Vec<_>>();
+            noisy::operation_from_rows(&kraus_operators).map_err(|e| e.to_string())
+        }
+
+        let mut noise_model = NoiseModel::default();
+        for (gate, kraus_operators) in self.gates {
+            noise_model.set_gate_noise(&gate, operation(kraus_operators)?)?;
+        }
+        if let Some(outcomes) = self.measurement {
+            let operations = outcomes
+                .into_iter()
+                .map(operation)
+                .collect::<Result<Vec<_>, _>>()?;
+            noise_model.measurement =
+                Some(noisy::Instrument::new(operations).map_err(|e| e.to_string())?);
+        }
+        Ok((self.qubits, noise_model))
+    }
+}
+
+#[wasm_bindgen(typescript_custom_section)]
+const NOISE_CONFIG: &'static str = r#"
+export type PauliProbabilities = [number, number, number];
+export type NoiseConfig = PauliProbabilities | { [channel: string]: PauliProbabilities | number };
+"#;
+
+/// The noise to simulate: either the probabilities of X, Y and Z errors applied after every
+/// gate, or a map from channel names to such probabilities, in which the `measurement` entry
+/// is the probability of a measurement error instead.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NoiseArg {
+    Uniform((f64, f64, f64)),
+    PerChannel(FxHashMap<String, NoiseValue>),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NoiseValue {
+    Pauli((f64, f64, f64)),
+    Probability(f64),
+}
+
+impl From<NoiseValue> for ChannelNoise {
+    fn from(value: NoiseValue) -> Self {
+        match value {
+            NoiseValue::Pauli((p_x, p_y, p_z)) => ChannelNoise::Pauli(p_x, p_y, p_z),
+            NoiseValue::Probability(p) => ChannelNoise::Probability(p),
+        }
+    }
+}
+
+impl NoiseArg {
+    fn into_noise_config(self) -> Result<NoiseConfig, String> {
+        match self {
+            NoiseArg::Uniform((p_x, p_y, p_z)) => {
+                PauliNoise::from_probabilities(p_x, p_y, p_z).map(NoiseConfig::uniform)
+            }
+            NoiseArg::PerChannel(channels) => NoiseConfig::from_channels(
+                channels
+                    .into_iter()
+                    .map(|(name, value)| (name, value.into())),
+            ),
+        }
+        .map_err(|e| e.to_string())
+    }
+}
+
 #[wasm_bindgen]
 pub fn run(
     program: ProgramConfig,
     expr: &str,
     event_cb: &js_sys::Function,
     shots: u32,
+    noise: &JsValue,
+    noise_model: &JsValue,
 ) -> Result<bool, JsValue> {
+    let noise = if noise.is_undefined() || noise.is_null() {
+        None
+    } else {
+        let noise: NoiseArg = serde_wasm_bindgen::from_value(noise.clone()).map_err(|_| {
+            JsError::new("noise must be an array of three probabilities or a map of channels")
+        })?;
+        Some(noise.into_noise_config().map_err(|e| JsError::new(&e))?)
+    };
+    let noise_model = if noise_model.is_undefined() || noise_model.is_null() {
+        None
+    } else {
+        let config: NoiseModelConfig = serde_wasm_bindgen::from_value(noise_model.clone())
+            .map_err(|_| JsError::new("invalid noise model"))?;
+        Some(config.into_noise_model().map_err(|e| JsError::new(&e))?)
+    };
+
     let (source_map, capabilities, language_features, store, deps) =
         into_qsc_args(program, Some(expr.into())).map_err(|mut e| {
             // Wrap in `interpret::Error` and `JsError` to match the error type
@@ -399,6 +611,8 @@ pub fn run(
         source_map,
         event_cb,
         shots,
+        noise.as_ref(),
+        noise_model.as_ref(),
         language_features,
         capabilities,
         store,
@@ -509,7 +723,7 @@ pub fn generate_docs(additional_program: Option<ProgramConfig>) -> Vec<IDocFile>
 
 #[wasm_bindgen(typescript_custom_section)]
 const TARGET_PROFILE: &'static str = r#"
-export type TargetProfile = "base" | "adaptive_ri" | "unrestricted";
+export type TargetProfile = "base" | "adaptive_ri" | "adaptive_rif" | "unrestricted";
 "#;
 
 #[wasm_bindgen(typescript_custom_section)]
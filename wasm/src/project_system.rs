@@ -18,6 +18,7 @@ export interface IProjectHost {
     listDirectory(uri: string): Promise<[string, number][]>;
     resolvePath(base: string, path: string): Promise<string | null>;
     fetchGithub(owner: string, repo: string, ref: string, path: string): Promise<string>;
+    resolveGithubCommit(owner: string, repo: string, ref: string): Promise<string>;
     findManifestDirectory(docUri: string): Promise<string | null>;
 }
 
@@ -55,6 +56,14 @@ extern "C" {
         path: &str,
     ) -> Result<JsValue, JsValue>;
 
+    #[wasm_bindgen(method, structural, catch)]
+    async fn resolveGithubCommit(
+        this: &ProjectHost,
+        owner: &str,
+        repo: &str,
+        r#ref: &str,
+    ) -> Result<JsValue, JsValue>;
+
     #[wasm_bindgen(method, structural)]
     async fn findManifestDirectory(this: &ProjectHost, docUri: &str) -> JsValue;
 
@@ -96,17 +105,7 @@ impl JSProjectHost for ProjectHost {
 
         match self.readFile(uri).await {
             Ok(val) => Ok((name, val.as_string().unwrap_or_default().into())),
-
-            Err(js_val) => {
-                let err: js_sys::Error = js_val
-                    .dyn_into()
-                    .expect("exception should be an error type");
-                let message = err
-                    .message()
-                    .as_string()
-                    .expect("error message should be a string");
-                Err(Report::msg(message))
-            }
+            Err(js_val) => Err(report_from_js_error(js_val)),
         }
     }
 
@@ -162,16 +161,22 @@ impl JSProjectHost for ProjectHost {
                 .as_string()
                 .expect("fetchGithub should return a string or throw")
                 .into()),
-            Err(js_val) => {
-                let err: js_sys::Error = js_val
-                    .dyn_into()
-                    .expect("exception should be an error type");
-                let message = err
-                    .message()
-                    .as_string()
-                    .expect("error message should be a string");
-                Err(Report::msg(message))
-            }
+            Err(js_val) => Err(report_from_js_error(js_val)),
+        }
+    }
+
+    async fn resolve_github_commit(
+        &self,
+        owner: &str,
+        repo: &str,
+        r#ref: &str,
+    ) -> miette::Result<Arc<str>> {
+        match self.resolveGithubCommit(owner, repo, r#ref).await {
+            Ok(js_val) => Ok(js_val
+                .as_string()
+                .expect("resolveGithubCommit should return a string or throw")
+                .into()),
+            Err(js_val) => Err(report_from_js_error(js_val)),
         }
     }
 
@@ -181,6 +186,17 @@ impl JSProjectHost for ProjectHost {
     }
 }
 
+fn report_from_js_error(js_val: JsValue) -> Report {
+    let err: js_sys::Error = js_val
+        .dyn_into()
+        .expect("exception should be an error type");
+    let message = err
+        .message()
+        .as_string()
+        .expect("error message should be a string");
+    Report::msg(message)
+}
+
 #[wasm_bindgen]
 impl ProjectLoader {
     #[wasm_bindgen(constructor)]
@@ -369,6 +385,9 @@ impl From<PackageInfo> for qsc_project::PackageInfo {
                     "expected one of 'exe' or 'lib' -- should be guaranteed by TS types"
                 ),
             }),
+            // Versions are resolved when the project is loaded, so they aren't passed to JS.
+            version: None,
+            version_requirements: FxHashMap::default(),
         }
     }
 }
@@ -18,6 +18,8 @@ where
         sources,
         event_cb,
         shots,
+        None,
+        None,
         LanguageFeatures::default(),
         TargetCapabilityFlags::all(),
         store,
@@ -85,6 +87,79 @@ fn test_run_two_shots() {
     assert_eq!(count.get(), 2);
 }
 
+#[test]
+fn test_run_with_noise_model() {
+    let code = "
+        namespace Test {
+            operation Flip() : Result {
+                use q = Qubit();
+                X(q);
+                MResetZ(q)
+            }
+        }
+    ";
+    let expr = "Test.Flip()";
+    let config: crate::NoiseModelConfig = serde_json::from_str(
+        r#"{ "qubits": 1, "gates": { "x": [[[[0, 0], [1, 0]], [[1, 0], [0, 0]]]] } }"#,
+    )
+    .expect("noise model should deserialize");
+    let noise_model = config
+        .into_noise_model()
+        .expect("noise model should be valid");
+    let (std_id, store) = crate::compile::package_store_with_stdlib(TargetCapabilityFlags::all());
+    let count = std::cell::Cell::new(0);
+
+    let result = run_internal_with_features(
+        SourceMap::new([("test.qs".into(), code.into())], Some(expr.into())),
+        |msg| {
+            expect![[r#"{"result":"Zero","success":true,"type":"Result"}"#]].assert_eq(msg);
+            count.set(count.get() + 1);
+        },
+        2,
+        None,
+        Some(&noise_model),
+        LanguageFeatures::default(),
+        TargetCapabilityFlags::all(),
+        store,
+        &[(std_id, None)],
+    );
+    assert!(result.is_ok());
+    assert_eq!(count.get(), 2);
+}
+
+#[test]
+fn test_noise_model_with_unknown_gate_is_rejected() {
+    let config: crate::NoiseModelConfig =
+        serde_json::from_str(r#"{ "qubits": 1, "gates": { "foo": [[[[1, 0]]]] } }"#)
+            .expect("noise model should deserialize");
+    assert!(config.into_noise_model().is_err());
+}
+
+#[test]
+fn test_noise_is_configured_per_channel() {
+    let noise: crate::NoiseArg =
+        serde_json::from_str(r#"{ "x": [1, 0, 0], "idle": [0, 0, 0.5], "measurement": 0.25 }"#)
+            .expect("noise should deserialize");
+    let config = noise.into_noise_config().expect("noise should be valid");
+    assert_eq!(config.x.probabilities(), (1.0, 0.0, 0.0));
+    assert_eq!(config.idle.probabilities(), (0.0, 0.0, 0.5));
+    assert!(config.h.is_noiseless());
+    assert!((config.measurement() - 0.25).abs() < f64::EPSILON);
+}
+
+#[test]
+fn test_noise_with_unknown_channel_is_rejected() {
+    let noise: crate::NoiseArg =
+        serde_json::from_str(r#"{ "foo": [0.1, 0, 0] }"#).expect("noise should deserialize");
+    assert!(noise.into_noise_config().is_err());
+    let noise: crate::NoiseArg =
+        serde_json::from_str(r#"[0.1, 0, 0]"#).expect("noise should deserialize");
+    assert!(!noise
+        .into_noise_config()
+        .expect("noise should be valid")
+        .is_noiseless());
+}
+
 #[test]
 fn fail_ry() {
     let code = "namespace Sample {